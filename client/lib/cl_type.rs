@@ -134,6 +134,9 @@ pub mod help {
 "uref_name:uref='{}'"
 "public_key_name:public_key='{}'"
 
+The single quotes around a value are optional, e.g. "name_09:u512=8" is equivalent to the
+example above.
+
 Optional values of all of these types can also be specified.
 Prefix the type with "opt_" and use the term "null" without quotes to specify a None value:
 "name_01:opt_bool='true'"       # Some(true)
@@ -188,6 +191,19 @@ where
     })
 }
 
+/// Strips the single quotes surrounding the value of a simple arg.  The quotes are optional, but
+/// must be balanced if present.
+fn unquote(value: &str) -> Result<&str> {
+    match (value.strip_prefix('\''), value.ends_with('\'')) {
+        (Some(inner), true) if !inner.is_empty() => Ok(&inner[..inner.len() - 1]),
+        (None, false) => Ok(value),
+        _ => Err(Error::InvalidCLValue(format!(
+            "value in simple arg has unbalanced single quotes (value passed: {})",
+            value
+        ))),
+    }
+}
+
 /// Returns a value built from a single arg which has been split into its constituent parts.
 pub fn parts_to_cl_value(cl_type: CLType, value: &str) -> Result<CLValue> {
     let (cl_type_to_parse, optional_status, trimmed_value) = match cl_type {
@@ -195,24 +211,12 @@ pub fn parts_to_cl_value(cl_type: CLType, value: &str) -> Result<CLValue> {
             if value == "null" {
                 (*inner_type, OptionalStatus::None, "")
             } else {
-                (*inner_type, OptionalStatus::Some, value.trim_matches('\''))
+                (*inner_type, OptionalStatus::Some, unquote(value)?)
             }
         }
-        _ => (
-            cl_type,
-            OptionalStatus::NotOptional,
-            value.trim_matches('\''),
-        ),
+        _ => (cl_type, OptionalStatus::NotOptional, unquote(value)?),
     };
 
-    if value == trimmed_value {
-        return Err(Error::InvalidCLValue(format!(
-            "value in simple arg should be surrounded by single quotes unless it's a null \
-                   optional value (value passed: {})",
-            value
-        )));
-    }
-
     match cl_type_to_parse {
         CLType::Bool => {
             let parse = || match trimmed_value.to_lowercase().as_str() {
@@ -8,10 +8,11 @@ use std::{
 
 use casper_execution_engine::core::engine_state::ExecutableDeployItem;
 use casper_node::{
+    crypto,
     rpcs::{account::PutDeploy, chain::GetBlockResult, info::GetDeploy, RpcWithParams},
     types::{Deploy, DeployHash, TimeDiff, Timestamp},
 };
-use casper_types::{ProtocolVersion, RuntimeArgs, SecretKey, URef, U512};
+use casper_types::{ProtocolVersion, PublicKey, RuntimeArgs, SecretKey, Signature, URef, U512};
 
 use crate::{
     error::{Error, Result},
@@ -73,6 +74,73 @@ impl From<GetBlockResult> for ListDeploysResult {
     }
 }
 
+/// Human-readable view of a `Deploy`, as printed by the "print-deploy" subcommand.
+#[derive(Serialize, Debug)]
+pub struct DeployInfo<'a> {
+    /// The deploy hash.
+    pub hash: &'a DeployHash,
+    /// Whether the deploy hash and body hash match the contents of the deploy.
+    pub hash_is_valid: bool,
+    /// The account on whose behalf the deploy is executed.
+    pub account: &'a PublicKey,
+    /// The creation time of the deploy.
+    pub timestamp: Timestamp,
+    /// The time to live of the deploy.
+    pub ttl: TimeDiff,
+    /// The gas price of the deploy.
+    pub gas_price: u64,
+    /// The deploys this deploy depends on.
+    pub dependencies: &'a [DeployHash],
+    /// The name of the chain the deploy is meant for.
+    pub chain_name: &'a str,
+    /// The payment code, including its runtime args.
+    pub payment: &'a ExecutableDeployItem,
+    /// The session code, including its runtime args.
+    pub session: &'a ExecutableDeployItem,
+    /// The approvals of the deploy.
+    pub approvals: Vec<ApprovalInfo<'a>>,
+}
+
+/// Human-readable view of an approval of a `Deploy`.
+#[derive(Serialize, Debug)]
+pub struct ApprovalInfo<'a> {
+    /// The public key of the signer.
+    pub signer: &'a PublicKey,
+    /// The signature over the deploy hash.
+    pub signature: &'a Signature,
+    /// Whether the signature verifies against the deploy hash and the signer.
+    pub is_valid: bool,
+}
+
+impl<'a> From<&'a Deploy> for DeployInfo<'a> {
+    fn from(deploy: &'a Deploy) -> Self {
+        let header = deploy.header();
+        let approvals = deploy
+            .approvals()
+            .iter()
+            .map(|approval| ApprovalInfo {
+                signer: approval.signer(),
+                signature: approval.signature(),
+                is_valid: crypto::verify(deploy.id(), approval.signature(), approval.signer())
+                    .is_ok(),
+            })
+            .collect();
+        DeployInfo {
+            hash: deploy.id(),
+            hash_is_valid: deploy.verify_hash().is_ok(),
+            account: header.account(),
+            timestamp: header.timestamp(),
+            ttl: header.ttl(),
+            gas_price: header.gas_price(),
+            dependencies: header.dependencies(),
+            chain_name: header.chain_name(),
+            payment: deploy.payment(),
+            session: deploy.session(),
+            approvals,
+        }
+    }
+}
+
 /// An output abstraction for associating a Write with some metadata.
 pub(super) enum OutputKind<'a> {
     File {
@@ -261,6 +329,9 @@ impl DeployExt for Deploy {
                 let target_account_hash = target_account.to_account_hash().value();
                 transfer_args.insert(TRANSFER_ARG_TARGET, target_account_hash)?;
             }
+            TransferTarget::AccountHash(target_account_hash) => {
+                transfer_args.insert(TRANSFER_ARG_TARGET, target_account_hash.value())?;
+            }
         }
         let maybe_transfer_id = Some(transfer_id);
         transfer_args.insert(TRANSFER_ARG_ID, maybe_transfer_id)?;
@@ -299,6 +370,8 @@ impl DeployExt for Deploy {
         W: Write,
     {
         let mut deploy = Deploy::read_deploy(input)?;
+        // Don't add an approval to a deploy whose hash doesn't cover its contents.
+        deploy.verify_hash()?;
         deploy.sign(&secret_key);
         deploy.is_valid_size(MAX_SERIALIZED_SIZE)?;
         deploy.write_deploy(output)?;
@@ -310,7 +383,11 @@ impl DeployExt for Deploy {
 mod tests {
     use std::convert::TryInto;
 
-    use casper_node::{crypto::AsymmetricKeyExt, types::ExcessiveSizeDeployError};
+    use casper_node::{
+        crypto::AsymmetricKeyExt,
+        types::{DeployValidationFailure, ExcessiveSizeDeployError},
+    };
+    use casper_types::{account::AccountHash, runtime_args, Key};
 
     use super::*;
     use crate::{DeployStrParams, PaymentStrParams, SessionStrParams};
@@ -457,6 +534,62 @@ mod tests {
         assert_eq!(expected.session(), actual.session());
     }
 
+    #[test]
+    fn should_create_transfer_deploy() {
+        let amount = U512::from(2_500_000_000u64);
+        let target = AccountHash::new([7; 32]);
+        let transfer_id = 42;
+
+        let deploy = Deploy::new_transfer(
+            amount,
+            None,
+            TransferTarget::AccountHash(target),
+            transfer_id,
+            deploy_params().try_into().unwrap(),
+            PaymentStrParams::with_amount("100000").try_into().unwrap(),
+        )
+        .unwrap();
+
+        let expected_args = runtime_args! {
+            "amount" => amount,
+            "target" => target.value(),
+            "id" => Some(transfer_id),
+        };
+        match deploy.session() {
+            ExecutableDeployItem::Transfer { args } => assert_eq!(*args, expected_args),
+            session => panic!("expected a transfer, got {:?}", session),
+        }
+    }
+
+    #[test]
+    fn should_create_deploy_with_mixed_session_args() {
+        let session_args = vec![
+            "amount:u512=1000",
+            "name:string='a value'",
+            "target:account_hash=account-hash-0707070707070707070707070707070707070707070707070707070707070707",
+            "key:key=hash-0808080808080808080808080808080808080808080808080808080808080808",
+            "flag:bool=true",
+        ];
+        let session_params =
+            SessionStrParams::with_package_hash(PKG_HASH, VERSION, ENTRYPOINT, session_args, "");
+
+        let deploy = Deploy::with_payment_and_session(
+            deploy_params().try_into().unwrap(),
+            PaymentStrParams::with_amount("100000").try_into().unwrap(),
+            session_params.try_into().unwrap(),
+        )
+        .unwrap();
+
+        let expected_args = runtime_args! {
+            "amount" => U512::from(1000),
+            "name" => String::from("a value"),
+            "target" => AccountHash::new([7; 32]),
+            "key" => Key::Hash([8; 32]),
+            "flag" => true,
+        };
+        assert_eq!(*deploy.session().args(), expected_args);
+    }
+
     #[test]
     fn should_fail_to_create_large_deploy() {
         let deploy_params = deploy_params();
@@ -525,4 +658,76 @@ mod tests {
             signed_deploy
         );
     }
+
+    #[test]
+    fn should_flag_invalid_approvals_in_deploy_info() {
+        // Swap in the signature of the second approval for the first one.
+        let tampered = SAMPLE_DEPLOY.replace(
+            "010f538ef188770cdbf608bc2d7aa9460108b419b2b629f5e0714204a7f29149809a1d52776b0c514e3320494fdf6f9e9747f06f2c14ddf6f924ce218148e2840a",
+            "01c4f58d7f6145c1e4397efce766149cde5450cbe74991269161e5e1f30a397e6bc4c484f3c72a645cefd42c55cfde0294bfd91de55ca977798c3c8d2a7e43a40c",
+        );
+        let deploy = Deploy::read_deploy(tampered.as_bytes()).unwrap();
+
+        let info = DeployInfo::from(&deploy);
+        let printed = serde_json::to_value(&info).unwrap();
+        assert_eq!(
+            printed["hash"],
+            "4858bbd79ab7b825244c4e6959cbcd588a05608168ef36518bc6590937191d55"
+        );
+        assert_eq!(printed["hash_is_valid"], true);
+        assert_eq!(printed["approvals"][0]["is_valid"], false);
+        assert_eq!(printed["approvals"][1]["is_valid"], true);
+    }
+
+    #[test]
+    fn should_sign_deploy_with_multiple_keys() {
+        let bytes = SAMPLE_DEPLOY.as_bytes();
+        let deploy = Deploy::read_deploy(bytes).unwrap();
+
+        let first_key = SecretKey::generate_ed25519().unwrap();
+        let second_key = SecretKey::generate_ed25519().unwrap();
+        let first_signer = PublicKey::from(&first_key);
+        let second_signer = PublicKey::from(&second_key);
+
+        let mut signed_once = Vec::new();
+        Deploy::sign_and_write_deploy(bytes, first_key, &mut signed_once).unwrap();
+        let mut signed_twice = Vec::new();
+        Deploy::sign_and_write_deploy(&signed_once[..], second_key, &mut signed_twice).unwrap();
+        let mut signed_deploy = Deploy::read_deploy(&signed_twice[..]).unwrap();
+
+        assert_eq!(signed_deploy.id(), deploy.id());
+        assert_eq!(
+            signed_deploy.approvals().len(),
+            deploy.approvals().len() + 2
+        );
+        assert!(signed_deploy
+            .approvals()
+            .iter()
+            .any(|approval| *approval.signer() == first_signer));
+        assert!(signed_deploy
+            .approvals()
+            .iter()
+            .any(|approval| *approval.signer() == second_signer));
+        signed_deploy
+            .is_valid()
+            .unwrap_or_else(|error| panic!("{} - {:#?}", error, signed_deploy));
+    }
+
+    #[test]
+    fn should_not_sign_deploy_with_invalid_hash() {
+        let tampered = SAMPLE_DEPLOY.replace(
+            "4858bbd79ab7b825244c4e6959cbcd588a05608168ef36518bc6590937191d55",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+        );
+
+        let mut result = Vec::new();
+        let secret_key = SecretKey::generate_ed25519().unwrap();
+        assert!(matches!(
+            Deploy::sign_and_write_deploy(tampered.as_bytes(), secret_key, &mut result),
+            Err(Error::InvalidDeploy(
+                DeployValidationFailure::InvalidDeployHash
+            ))
+        ));
+        assert!(result.is_empty());
+    }
 }
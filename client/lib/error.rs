@@ -4,7 +4,13 @@ use humantime::{DurationError, TimestampError};
 use jsonrpc_lite::JsonRpc;
 use thiserror::Error;
 
-use casper_node::{crypto::Error as CryptoError, types::ExcessiveSizeDeployError};
+use casper_node::{
+    crypto::Error as CryptoError,
+    types::{
+        chainspec::{Error as ChainspecError, ValidationError as ChainspecValidationError},
+        DeployValidationFailure, ExcessiveSizeDeployError,
+    },
+};
 use casper_types::{
     bytesrepr::Error as ToBytesError, CLValueError, UIntParseError, URefFromStrError,
 };
@@ -47,6 +53,18 @@ pub enum Error {
     #[error("Deploy size too large: {0}")]
     DeploySizeTooLarge(#[from] ExcessiveSizeDeployError),
 
+    /// Deploy failed validation, e.g. its hash doesn't match its contents.
+    #[error("Invalid deploy: {0}")]
+    InvalidDeploy(#[from] DeployValidationFailure),
+
+    /// Failed to load a chainspec, e.g. due to a missing or malformed entry.
+    #[error("Failed to load chainspec: {0}")]
+    FailedToLoadChainspec(#[from] ChainspecError),
+
+    /// The values set in a loaded chainspec don't make sense.
+    #[error("Invalid chainspec: {0}")]
+    InvalidChainspec(#[from] ChainspecValidationError),
+
     /// Failed to get a response from the node.
     #[error("Failed to get RPC response: {0}")]
     FailedToGetResponse(reqwest::Error),
@@ -59,6 +77,10 @@ pub enum Error {
     #[error("File at {} already exists", .0.display())]
     FileAlreadyExists(PathBuf),
 
+    /// Key file doesn't match the secret key it should be derived from.
+    #[error("Key file at {} does not match the secret key", .0.display())]
+    KeyFileMismatch(PathBuf),
+
     /// Unsupported keygen algorithm.
     #[error("Unsupported keygen algorithm: {0}")]
     UnsupportedAlgorithm(String),
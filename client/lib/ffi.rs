@@ -11,7 +11,10 @@ use std::{
 use once_cell::sync::Lazy;
 use tokio::runtime;
 
-use super::error::{Error, Result};
+use super::{
+    error::{Error, Result},
+    RetryPolicy,
+};
 
 static LAST_ERROR: Lazy<Mutex<Option<Error>>> = Lazy::new(|| Mutex::new(None));
 static RUNTIME: Lazy<Mutex<Option<runtime::Runtime>>> = Lazy::new(|| Mutex::new(None));
@@ -54,6 +57,10 @@ pub enum casper_error_t {
     CASPER_CONFLICTING_ARGUMENTS = -23,
     CASPER_DEPLOY_SIZE_TOO_LARGE = -24,
     CASPER_FAILED_TO_CREATE_DICTIONARY_IDENTIFIER = -25,
+    CASPER_KEY_FILE_MISMATCH = -26,
+    CASPER_INVALID_DEPLOY = -27,
+    CASPER_FAILED_TO_LOAD_CHAINSPEC = -28,
+    CASPER_INVALID_CHAINSPEC = -29,
 }
 
 trait AsFFIError {
@@ -90,6 +97,10 @@ impl AsFFIError for Error {
             Error::FailedToParseDictionaryIdentifier => {
                 casper_error_t::CASPER_FAILED_TO_CREATE_DICTIONARY_IDENTIFIER
             }
+            Error::KeyFileMismatch(_) => casper_error_t::CASPER_KEY_FILE_MISMATCH,
+            Error::InvalidDeploy(_) => casper_error_t::CASPER_INVALID_DEPLOY,
+            Error::FailedToLoadChainspec(_) => casper_error_t::CASPER_FAILED_TO_LOAD_CHAINSPEC,
+            Error::InvalidChainspec(_) => casper_error_t::CASPER_INVALID_CHAINSPEC,
         }
     }
 }
@@ -280,6 +291,7 @@ pub extern "C" fn casper_put_deploy(
             maybe_rpc_id,
             node_address,
             verbosity_level,
+            RetryPolicy::default(),
             deploy_params,
             session_params,
             payment_params,
@@ -353,8 +365,13 @@ pub extern "C" fn casper_send_deploy_file(
     let node_address = try_unsafe_arg!(node_address);
     let input_path = try_unsafe_arg!(input_path);
     runtime.block_on(async move {
-        let result =
-            super::send_deploy_file(maybe_rpc_id, node_address, verbosity_level, input_path);
+        let result = super::send_deploy_file(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            RetryPolicy::default(),
+            input_path,
+        );
         let response = try_unwrap_rpc!(result);
         copy_str_to_buf(&response, response_buf, response_buf_len);
         casper_error_t::CASPER_SUCCESS
@@ -391,6 +408,7 @@ pub extern "C" fn casper_transfer(
             maybe_rpc_id,
             node_address,
             verbosity_level,
+            RetryPolicy::default(),
             amount,
             maybe_target_account,
             transfer_id,
@@ -453,7 +471,13 @@ pub extern "C" fn casper_get_deploy(
     let node_address = try_unsafe_arg!(node_address);
     let deploy_hash = try_unsafe_arg!(deploy_hash);
     runtime.block_on(async move {
-        let result = super::get_deploy(maybe_rpc_id, node_address, verbosity_level, deploy_hash);
+        let result = super::get_deploy(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            RetryPolicy::default(),
+            deploy_hash,
+        );
         let response = try_unwrap_rpc!(result);
         copy_str_to_buf(&response, response_buf, response_buf_len);
         casper_error_t::CASPER_SUCCESS
@@ -478,7 +502,13 @@ pub extern "C" fn casper_get_block(
     let node_address = try_unsafe_arg!(node_address);
     let maybe_block_id = try_unsafe_arg!(maybe_block_id);
     runtime.block_on(async move {
-        let result = super::get_block(maybe_rpc_id, node_address, verbosity_level, maybe_block_id);
+        let result = super::get_block(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            RetryPolicy::default(),
+            maybe_block_id,
+        );
         let response = try_unwrap_rpc!(result);
         copy_str_to_buf(&response, response_buf, response_buf_len);
         casper_error_t::CASPER_SUCCESS
@@ -503,8 +533,13 @@ pub extern "C" fn casper_get_block_transfers(
     let node_address = try_unsafe_arg!(node_address);
     let maybe_block_id = try_unsafe_arg!(maybe_block_id);
     runtime.block_on(async move {
-        let result =
-            super::get_block_transfers(maybe_rpc_id, node_address, verbosity_level, maybe_block_id);
+        let result = super::get_block_transfers(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            RetryPolicy::default(),
+            maybe_block_id,
+        );
         let response = try_unwrap_rpc!(result);
         copy_str_to_buf(&response, response_buf, response_buf_len);
         casper_error_t::CASPER_SUCCESS
@@ -529,8 +564,13 @@ pub extern "C" fn casper_get_state_root_hash(
     let node_address = try_unsafe_arg!(node_address);
     let maybe_block_id = try_unsafe_arg!(maybe_block_id);
     runtime.block_on(async move {
-        let result =
-            super::get_state_root_hash(maybe_rpc_id, node_address, verbosity_level, maybe_block_id);
+        let result = super::get_state_root_hash(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            RetryPolicy::default(),
+            maybe_block_id,
+        );
         let response = try_unwrap_rpc!(result);
         copy_str_to_buf(&response, response_buf, response_buf_len);
         casper_error_t::CASPER_SUCCESS
@@ -563,6 +603,7 @@ pub extern "C" fn casper_get_item(
             maybe_rpc_id,
             node_address,
             verbosity_level,
+            RetryPolicy::default(),
             state_root_hash,
             key,
             path,
@@ -597,6 +638,7 @@ pub extern "C" fn casper_get_balance(
             maybe_rpc_id,
             node_address,
             verbosity_level,
+            RetryPolicy::default(),
             state_root_hash,
             purse,
         );
@@ -628,6 +670,7 @@ pub extern "C" fn casper_get_era_info_by_switch_block(
             maybe_rpc_id,
             node_address,
             verbosity_level,
+            RetryPolicy::default(),
             maybe_block_id,
         );
         let response = try_unwrap_rpc!(result);
@@ -654,8 +697,13 @@ pub extern "C" fn casper_get_auction_info(
     let node_address = try_unsafe_arg!(node_address);
     let maybe_block_id = try_unsafe_arg!(maybe_block_id);
     runtime.block_on(async move {
-        let result =
-            super::get_auction_info(maybe_rpc_id, node_address, verbosity_level, maybe_block_id);
+        let result = super::get_auction_info(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            RetryPolicy::default(),
+            maybe_block_id,
+        );
         let response = try_unwrap_rpc!(result);
         copy_str_to_buf(&response, response_buf, response_buf_len);
         casper_error_t::CASPER_SUCCESS
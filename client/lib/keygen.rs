@@ -1,9 +1,20 @@
 //! Cryptographic key generation.
 
-use std::{fs, path::Path};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::{
+    convert::TryInto,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
 
-use casper_node::crypto::AsymmetricKeyExt;
-use casper_types::{AsymmetricType, PublicKey, SecretKey};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+
+use casper_node::crypto::{AsymmetricKeyExt, SecretKeyExt};
+use casper_types::{bytesrepr, crypto::Error as CryptoError, AsymmetricType, PublicKey, SecretKey};
 
 use crate::error::{Error, Result};
 
@@ -16,26 +27,517 @@ pub const PUBLIC_KEY_PEM: &str = "public_key.pem";
 
 /// List of keygen related filenames: "secret_key.pem", "public_key.pem" and "public_key_hex".
 pub const FILES: [&str; 3] = [SECRET_KEY_PEM, PUBLIC_KEY_PEM, PUBLIC_KEY_HEX];
+/// Label of the hex-encoded account hash in the output of [`account_summary`].
+pub const ACCOUNT_ID_HEX: &str = "account_id_hex";
+/// Label of the base64-encoded account hash in the output of [`account_summary`].
+pub const ACCOUNT_ID_BASE64: &str = "account_id_base64";
+/// Default filename for the JSON file containing all the keys.
+pub const KEYS_JSON: &str = "keys.json";
+
+/// Name of the output format which writes each key to its own file.
+pub const FORMAT_FILES: &str = "files";
+/// Name of the output format which writes all keys to a single JSON file.
+pub const FORMAT_JSON: &str = "json";
 
 /// Name of Ed25519 algorithm.
 pub const ED25519: &str = "Ed25519";
 /// Name of secp256k1 algorithm.
 pub const SECP256K1: &str = "secp256k1";
+/// Name of secp256r1 (NIST P-256) algorithm.
+pub const SECP256R1: &str = "secp256r1";
 
 /// Generates a new asymmetric key pair using the specified algorithm, and writes them to files in
 /// the specified directory.
 ///
 /// The secret key is written to "secret_key.pem", and the public key is written to "public_key.pem"
 /// and also in hex format to "public_key_hex". For the hex format, the algorithm's tag is
-/// prepended, e.g. `01` for Ed25519, `02` for secp256k1.
+/// prepended, e.g. `01` for Ed25519, `02` for secp256k1, `03` for secp256r1.
 ///
 /// If `force` is true, existing files will be overwritten. If `force` is false and any of the
 /// files exist, [`Error::FileAlreadyExists`](../enum.Error.html#variant.FileAlreadyExists) is
 /// returned and no files are written.
 pub fn generate_files(output_dir: &str, algorithm: &str, force: bool) -> Result<()> {
+    let output_dir = prepare_output_dir("generate_files", output_dir, &FILES, force)?;
+    let secret_key = generate_secret_key(algorithm)?;
+    write_key_files(&output_dir, &secret_key, None)
+}
+
+/// Generates a new asymmetric key pair using the specified algorithm, and writes them as a single
+/// JSON file named "keys.json" in the specified directory.
+///
+/// The JSON object contains the formatted account hash, the public key in hex and PEM formats and
+/// the PEM-encoded secret key.  See [`KeysJson`] for the field names.
+///
+/// If `force` is true, an existing file will be overwritten. If `force` is false and the file
+/// exists, [`Error::FileAlreadyExists`](../enum.Error.html#variant.FileAlreadyExists) is returned.
+pub fn generate_json_file(output_dir: &str, algorithm: &str, force: bool) -> Result<()> {
+    let output_dir = prepare_output_dir("generate_json_file", output_dir, &[KEYS_JSON], force)?;
+    let secret_key = generate_secret_key(algorithm)?;
+    write_keys_json_file(&output_dir, &secret_key, None)
+}
+
+/// Writes the given secret key and its derived public key to files in the specified directory.
+///
+/// The files written and the behavior of `force` are as per [`generate_files`].
+#[deprecated(note = "use `write_files_with_passphrase` instead")]
+pub fn write_files(output_dir: &str, secret_key: &SecretKey, force: bool) -> Result<()> {
+    write_files_with_passphrase(output_dir, secret_key, None, force)
+}
+
+/// Writes the given secret key and its derived public key to files in the specified directory.
+///
+/// The files written and the behavior of `force` are as per [`generate_files`].
+///
+/// If `maybe_passphrase` is `Some`, the secret key is written as a PKCS#8 container encrypted with
+/// AES-256 using the given passphrase.  It can be read back using
+/// `SecretKeyExt::from_encrypted_file`.
+pub fn write_files_with_passphrase(
+    output_dir: &str,
+    secret_key: &SecretKey,
+    maybe_passphrase: Option<&[u8]>,
+    force: bool,
+) -> Result<()> {
+    let output_dir = prepare_output_dir("write_files", output_dir, &FILES, force)?;
+    write_key_files(&output_dir, secret_key, maybe_passphrase)
+}
+
+/// Writes the given secret key and its derived public key as a single JSON file named "keys.json"
+/// in the specified directory.
+///
+/// The file contents and the behavior of `force` are as per [`generate_json_file`].
+#[deprecated(note = "use `write_json_file_with_passphrase` instead")]
+pub fn write_json_file(output_dir: &str, secret_key: &SecretKey, force: bool) -> Result<()> {
+    write_json_file_with_passphrase(output_dir, secret_key, None, force)
+}
+
+/// Writes the given secret key and its derived public key as a single JSON file named "keys.json"
+/// in the specified directory.
+///
+/// The file contents and the behavior of `force` are as per [`generate_json_file`].
+///
+/// If `maybe_passphrase` is `Some`, the PEM-encoded secret key is encrypted as per
+/// [`write_files_with_passphrase`].
+pub fn write_json_file_with_passphrase(
+    output_dir: &str,
+    secret_key: &SecretKey,
+    maybe_passphrase: Option<&[u8]>,
+    force: bool,
+) -> Result<()> {
+    let output_dir = prepare_output_dir("write_json_file", output_dir, &[KEYS_JSON], force)?;
+    write_keys_json_file(&output_dir, secret_key, maybe_passphrase)
+}
+
+/// Reads the passphrase used to encrypt a secret key from the file at `path`.
+///
+/// A single trailing newline (`\n` or `\r\n`) is removed, so that passphrase files created with
+/// e.g. `echo` work as expected.  An empty passphrase is rejected.
+pub fn read_passphrase_file(path: &str) -> Result<Vec<u8>> {
+    let mut passphrase = fs::read(path).map_err(|error| Error::IoError {
+        context: format!("unable to read passphrase file at '{}'", path),
+        error,
+    })?;
+    if passphrase.ends_with(b"\n") {
+        passphrase.pop();
+        if passphrase.ends_with(b"\r") {
+            passphrase.pop();
+        }
+    }
+    if passphrase.is_empty() {
+        return Err(Error::InvalidArgument(
+            "read_passphrase_file",
+            format!("passphrase file at '{}' is empty", path),
+        ));
+    }
+    Ok(passphrase)
+}
+
+/// Generates a new secret key using the specified algorithm and the operating system's
+/// cryptographically secure random number generator.
+pub fn generate_secret_key(algorithm: &str) -> Result<SecretKey> {
+    let secret_key = if algorithm.eq_ignore_ascii_case(ED25519) {
+        SecretKey::generate_ed25519()
+    } else if algorithm.eq_ignore_ascii_case(SECP256K1) {
+        SecretKey::generate_secp256k1()
+    } else if algorithm.eq_ignore_ascii_case(SECP256R1) {
+        SecretKey::generate_p256()
+    } else {
+        return Err(Error::UnsupportedAlgorithm(algorithm.to_string()));
+    };
+    secret_key.map_err(|error| Error::CryptoError {
+        context: "generate_secret_key",
+        error,
+    })
+}
+
+/// Deterministically derives a secret key using the specified algorithm from the given
+/// hex-encoded seed.
+///
+/// The seed must decode to exactly [`SecretKey::SEED_LENGTH`] bytes.  The same seed and algorithm
+/// always yield the same secret key, and hence the same "secret_key.pem" file.
+pub fn secret_key_from_seed(algorithm: &str, seed: &str) -> Result<SecretKey> {
+    let seed = decode_seed(seed)?;
+    derive_secret_key(algorithm, &seed, "secret_key_from_seed")
+}
+
+/// Generates `count` secret keys using the specified algorithm.
+///
+/// If `maybe_seed` is `None`, each key is generated as per [`generate_secret_key`].  Otherwise the
+/// hex-encoded seed is used to seed a single ChaCha20 random number generator, which is then
+/// advanced to derive the raw bytes of each key in turn.  Hence the same seed, algorithm and count
+/// always yield the same keys in the same order.
+///
+/// Note that the keys derived from a seed here differ from the single key returned by
+/// [`secret_key_from_seed`] for the same seed.
+pub fn generate_secret_keys(
+    algorithm: &str,
+    maybe_seed: Option<&str>,
+    count: usize,
+) -> Result<Vec<SecretKey>> {
+    let seed = match maybe_seed {
+        Some(seed) => decode_seed(seed)?,
+        None => {
+            return (0..count).map(|_| generate_secret_key(algorithm)).collect();
+        }
+    };
+
+    let mut rng = ChaCha20Rng::from_seed(seed);
+    (0..count)
+        .map(|_| {
+            let mut account_seed = [0; SecretKey::SEED_LENGTH];
+            rng.fill_bytes(&mut account_seed);
+            derive_secret_key(algorithm, &account_seed, "generate_secret_keys")
+        })
+        .collect()
+}
+
+/// Constructs a secret key using the specified algorithm from its hex-encoded raw bytes, e.g. as
+/// exported by another tool.
+///
+/// The hex string must not be prefixed with the algorithm tag, and must decode to exactly the
+/// secret key length of the given algorithm.
+pub fn secret_key_from_hex(algorithm: &str, secret_key_hex: &str) -> Result<SecretKey> {
+    let secret_key = if algorithm.eq_ignore_ascii_case(ED25519) {
+        SecretKey::from_hex_ed25519(secret_key_hex)
+    } else if algorithm.eq_ignore_ascii_case(SECP256K1) {
+        SecretKey::from_hex_secp256k1(secret_key_hex)
+    } else if algorithm.eq_ignore_ascii_case(SECP256R1) {
+        SecretKey::from_hex_secp256r1(secret_key_hex)
+    } else {
+        return Err(Error::UnsupportedAlgorithm(algorithm.to_string()));
+    };
+    secret_key.map_err(|error| {
+        let reason = match error {
+            CryptoError::FromHex(error) => format!("failed to decode as hex: {}", error),
+            CryptoError::AsymmetricKey(reason) => reason,
+            error => error.to_string(),
+        };
+        Error::InvalidArgument("secret_key_hex", reason)
+    })
+}
+
+/// Writes each of the given secret keys and its derived public key to files in a numbered
+/// subdirectory of the specified directory, i.e. "0", "1", ... "N-1".
+///
+/// The files written to each subdirectory are as per [`write_files_with_passphrase`].
+///
+/// If `force` is false and any of the files exist in any of the subdirectories,
+/// [`Error::FileAlreadyExists`](../enum.Error.html#variant.FileAlreadyExists) is returned and no
+/// files are written.
+pub fn write_numbered_files(
+    output_dir: &str,
+    secret_keys: &[SecretKey],
+    maybe_passphrase: Option<&[u8]>,
+    force: bool,
+) -> Result<()> {
+    write_numbered(
+        "write_numbered_files",
+        output_dir,
+        secret_keys,
+        &FILES,
+        force,
+        |subdir, secret_key| write_key_files(subdir, secret_key, maybe_passphrase),
+    )
+}
+
+/// Writes each of the given secret keys and its derived public key as a single JSON file named
+/// "keys.json" in a numbered subdirectory of the specified directory, i.e. "0", "1", ... "N-1".
+///
+/// The file contents are as per [`write_json_file_with_passphrase`], and the behavior of `force` is
+/// as per [`write_numbered_files`].
+pub fn write_numbered_json_files(
+    output_dir: &str,
+    secret_keys: &[SecretKey],
+    maybe_passphrase: Option<&[u8]>,
+    force: bool,
+) -> Result<()> {
+    write_numbered(
+        "write_numbered_json_files",
+        output_dir,
+        secret_keys,
+        &[KEYS_JSON],
+        force,
+        |subdir, secret_key| write_keys_json_file(subdir, secret_key, maybe_passphrase),
+    )
+}
+
+/// Verifies that the key files in the specified directory are consistent with each other.
+///
+/// The secret key is read from "secret_key.pem" (decrypting it using `maybe_passphrase` if
+/// provided), and the public key derived from it is checked against the contents of
+/// "public_key.pem" and "public_key_hex".
+///
+/// If any file cannot be read or doesn't match, an error naming the first such file is returned.
+/// The files are checked in the order listed above.
+pub fn verify_files(key_dir: &str, maybe_passphrase: Option<&[u8]>) -> Result<()> {
+    if key_dir.is_empty() {
+        return Err(Error::InvalidArgument(
+            "verify_files",
+            "empty key_dir provided, must be a valid path".to_string(),
+        ));
+    }
+    let key_dir = Path::new(key_dir);
+
+    let secret_key_path = key_dir.join(SECRET_KEY_PEM);
+    let secret_key = match maybe_passphrase {
+        Some(passphrase) => SecretKey::from_encrypted_file(&secret_key_path, passphrase),
+        None => SecretKey::from_file(&secret_key_path),
+    }
+    .map_err(|error| Error::CryptoError {
+        context: "secret_key",
+        error,
+    })?;
+    let public_key = PublicKey::from(&secret_key);
+
+    let public_key_path = key_dir.join(PUBLIC_KEY_PEM);
+    let public_key_from_pem =
+        PublicKey::from_file(&public_key_path).map_err(|error| Error::CryptoError {
+            context: "public_key",
+            error,
+        })?;
+    if public_key_from_pem != public_key {
+        return Err(Error::KeyFileMismatch(public_key_path));
+    }
+
+    let public_key_hex_path = key_dir.join(PUBLIC_KEY_HEX);
+    let public_key_hex =
+        fs::read_to_string(&public_key_hex_path).map_err(|error| Error::IoError {
+            context: format!(
+                "unable to read public key hex file at {:?}",
+                public_key_hex_path
+            ),
+            error,
+        })?;
+    if public_key_hex.trim() != public_key.to_hex() {
+        return Err(Error::KeyFileMismatch(public_key_hex_path));
+    }
+
+    Ok(())
+}
+
+/// Returns a summary of the account derived from the given secret key, suitable for printing to
+/// stdout instead of writing any files.
+///
+/// The summary contains three lines, each with a labeled prefix: the hex-encoded account hash, the
+/// base64-encoded account hash and the hex-encoded public key.
+pub fn account_summary(secret_key: &SecretKey) -> String {
+    let public_key = PublicKey::from(secret_key);
+    format!(
+        "{}\n{}: {}",
+        account_address(&public_key),
+        PUBLIC_KEY_HEX,
+        public_key.to_hex()
+    )
+}
+
+/// Returns the account hash of the given public key on two lines, labeled as in the output of
+/// [`account_summary`]: hex-encoded, then base64-encoded.
+pub fn account_address(public_key: &PublicKey) -> String {
+    let account_hash = public_key.to_account_hash();
+    format!(
+        "{}: {}\n{}: {}",
+        ACCOUNT_ID_HEX,
+        hex::encode(account_hash.as_bytes()),
+        ACCOUNT_ID_BASE64,
+        base64::encode(account_hash.as_bytes())
+    )
+}
+
+/// Parses an account public key, either hex-encoded or base64-encoded, prefixed with the tag of
+/// its algorithm as in the "public_key_hex" file.
+///
+/// The system public key is rejected, as there is no account belonging to it.
+pub fn parse_public_key(value: &str) -> Result<PublicKey> {
+    let value = value.trim();
+    let public_key = match PublicKey::from_hex(value) {
+        Ok(public_key) => public_key,
+        Err(hex_error) => {
+            let bytes = base64::decode(value).map_err(|_| {
+                Error::InvalidArgument(
+                    "public_key",
+                    format!(
+                        "failed to parse as a hex or base64-encoded public key: {}",
+                        hex_error
+                    ),
+                )
+            })?;
+            bytesrepr::deserialize(bytes).map_err(|error| {
+                Error::InvalidArgument(
+                    "public_key",
+                    format!("failed to parse base64-encoded public key: {}", error),
+                )
+            })?
+        }
+    };
+    if public_key == PublicKey::System {
+        return Err(Error::InvalidArgument(
+            "public_key",
+            "the system public key does not belong to an account".to_string(),
+        ));
+    }
+    Ok(public_key)
+}
+
+/// The contents of the "keys.json" file written by [`generate_json_file`].
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct KeysJson {
+    /// The formatted account hash, e.g. "account-hash-0102...".
+    pub account_hash: String,
+    /// The hex-encoded public key, prefixed with the algorithm tag.
+    pub public_key_hex: String,
+    /// The PEM-encoded public key.
+    pub public_key_pem: String,
+    /// The PEM-encoded secret key.
+    pub secret_key_pem: String,
+}
+
+impl KeysJson {
+    fn new(secret_key: &SecretKey, maybe_passphrase: Option<&[u8]>) -> Result<Self> {
+        let public_key = PublicKey::from(secret_key);
+        Ok(KeysJson {
+            account_hash: public_key.to_account_hash().to_formatted_string(),
+            public_key_hex: public_key.to_hex(),
+            public_key_pem: public_key.to_pem().map_err(|error| Error::CryptoError {
+                context: "public_key",
+                error,
+            })?,
+            secret_key_pem: secret_key_to_pem(secret_key, maybe_passphrase)?,
+        })
+    }
+}
+
+/// PEM encodes the secret key, encrypting it if a passphrase is provided.
+fn secret_key_to_pem(secret_key: &SecretKey, maybe_passphrase: Option<&[u8]>) -> Result<String> {
+    match maybe_passphrase {
+        Some(passphrase) => secret_key.to_encrypted_pem(passphrase),
+        None => secret_key.to_pem(),
+    }
+    .map_err(|error| Error::CryptoError {
+        context: "secret_key",
+        error,
+    })
+}
+
+/// Decodes the given hex-encoded seed, checking it has the expected length.
+fn decode_seed(seed: &str) -> Result<[u8; SecretKey::SEED_LENGTH]> {
+    let seed_bytes = hex::decode(seed).map_err(|error| {
+        Error::InvalidArgument("seed", format!("failed to decode as hex: {}", error))
+    })?;
+    seed_bytes.as_slice().try_into().map_err(|_| {
+        Error::InvalidArgument(
+            "seed",
+            format!(
+                "expected {} bytes ({} hex characters), got {} bytes",
+                SecretKey::SEED_LENGTH,
+                SecretKey::SEED_LENGTH * 2,
+                seed_bytes.len()
+            ),
+        )
+    })
+}
+
+/// Deterministically derives a secret key using the specified algorithm from the given seed.
+fn derive_secret_key(
+    algorithm: &str,
+    seed: &[u8; SecretKey::SEED_LENGTH],
+    context: &'static str,
+) -> Result<SecretKey> {
+    let secret_key = if algorithm.eq_ignore_ascii_case(ED25519) {
+        SecretKey::from_seed_ed25519(seed)
+    } else if algorithm.eq_ignore_ascii_case(SECP256K1) {
+        SecretKey::from_seed_secp256k1(seed)
+    } else if algorithm.eq_ignore_ascii_case(SECP256R1) {
+        SecretKey::from_seed_secp256r1(seed)
+    } else {
+        return Err(Error::UnsupportedAlgorithm(algorithm.to_string()));
+    };
+    secret_key.map_err(|error| Error::CryptoError {
+        context,
+        error: error.into(),
+    })
+}
+
+/// Writes each secret key to a numbered subdirectory of `output_dir` using `write`.
+///
+/// All subdirectories are checked for existing `files` before any are written.
+fn write_numbered<F>(
+    context: &'static str,
+    output_dir: &str,
+    secret_keys: &[SecretKey],
+    files: &[&str],
+    force: bool,
+    write: F,
+) -> Result<()>
+where
+    F: Fn(&Path, &SecretKey) -> Result<()>,
+{
+    if output_dir.is_empty() {
+        return Err(Error::InvalidArgument(
+            context,
+            "empty output_dir provided, must be a valid path".to_string(),
+        ));
+    }
+    let subdirs = (0..secret_keys.len())
+        .map(|index| {
+            Path::new(output_dir)
+                .join(index.to_string())
+                .display()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+
+    if !force {
+        for subdir in &subdirs {
+            for file in files
+                .iter()
+                .map(|filename| Path::new(subdir).join(filename))
+            {
+                if file.exists() {
+                    return Err(Error::FileAlreadyExists(file));
+                }
+            }
+        }
+    }
+
+    for (subdir, secret_key) in subdirs.iter().zip(secret_keys) {
+        let subdir = prepare_output_dir(context, subdir, files, force)?;
+        write(&subdir, secret_key)?;
+    }
+    Ok(())
+}
+
+/// Creates `output_dir` if required and returns its canonical path.
+///
+/// If `force` is false and any of `files` already exist in the directory,
+/// [`Error::FileAlreadyExists`](../enum.Error.html#variant.FileAlreadyExists) is returned.
+fn prepare_output_dir(
+    context: &'static str,
+    output_dir: &str,
+    files: &[&str],
+    force: bool,
+) -> Result<PathBuf> {
     if output_dir.is_empty() {
         return Err(Error::InvalidArgument(
-            "generate_files",
+            context,
             "empty output_dir provided, must be a valid path".to_string(),
         ));
     }
@@ -51,22 +553,23 @@ pub fn generate_files(output_dir: &str, algorithm: &str, force: bool) -> Result<
         })?;
 
     if !force {
-        for file in FILES.iter().map(|filename| output_dir.join(filename)) {
+        for file in files.iter().map(|filename| output_dir.join(filename)) {
             if file.exists() {
                 return Err(Error::FileAlreadyExists(file));
             }
         }
     }
 
-    let secret_key = if algorithm.eq_ignore_ascii_case(ED25519) {
-        SecretKey::generate_ed25519().unwrap()
-    } else if algorithm.eq_ignore_ascii_case(SECP256K1) {
-        SecretKey::generate_secp256k1().unwrap()
-    } else {
-        return Err(Error::UnsupportedAlgorithm(algorithm.to_string()));
-    };
+    Ok(output_dir)
+}
 
-    let public_key = PublicKey::from(&secret_key);
+/// Writes the secret key and its derived public key to the standard set of files.
+fn write_key_files(
+    output_dir: &Path,
+    secret_key: &SecretKey,
+    maybe_passphrase: Option<&[u8]>,
+) -> Result<()> {
+    let public_key = PublicKey::from(secret_key);
 
     let public_key_hex_path = output_dir.join(PUBLIC_KEY_HEX);
     fs::write(public_key_hex_path, public_key.to_hex()).map_err(|error| Error::IoError {
@@ -78,12 +581,14 @@ pub fn generate_files(output_dir: &str, algorithm: &str, force: bool) -> Result<
     })?;
 
     let secret_key_path = output_dir.join(SECRET_KEY_PEM);
-    secret_key
-        .to_file(&secret_key_path)
-        .map_err(|error| Error::CryptoError {
-            context: "secret_key",
-            error,
-        })?;
+    match maybe_passphrase {
+        Some(passphrase) => secret_key.to_encrypted_file(&secret_key_path, passphrase),
+        None => secret_key.to_file(&secret_key_path),
+    }
+    .map_err(|error| Error::CryptoError {
+        context: "secret_key",
+        error,
+    })?;
 
     let public_key_path = output_dir.join(PUBLIC_KEY_PEM);
     public_key
@@ -95,3 +600,28 @@ pub fn generate_files(output_dir: &str, algorithm: &str, force: bool) -> Result<
 
     Ok(())
 }
+
+/// Writes the secret key and its derived public key to "keys.json".
+///
+/// As the file contains the secret key, on Unix it is only readable and writable by its owner.
+fn write_keys_json_file(
+    output_dir: &Path,
+    secret_key: &SecretKey,
+    maybe_passphrase: Option<&[u8]>,
+) -> Result<()> {
+    let keys_json = KeysJson::new(secret_key, maybe_passphrase)?;
+    let contents = serde_json::to_string_pretty(&keys_json)?;
+
+    let keys_json_path = output_dir.join(KEYS_JSON);
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    options
+        .open(&keys_json_path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|error| Error::IoError {
+            context: format!("unable to write keys json file at {:?}", keys_json_path),
+            error,
+        })
+}
@@ -22,20 +22,26 @@ mod parsing;
 mod rpc;
 mod validation;
 
-use std::{convert::TryInto, fs, io::Cursor};
+use std::{convert::TryInto, fs, io::Cursor, path::Path};
 
 use jsonrpc_lite::JsonRpc;
 use serde::Serialize;
 
 use casper_execution_engine::core::engine_state::ExecutableDeployItem;
-use casper_node::{rpcs::state::DictionaryIdentifier, types::Deploy};
-use casper_types::{Key, UIntParseError, U512};
+use casper_node::{
+    crypto::hash::Digest,
+    rpcs::state::DictionaryIdentifier,
+    types::{Chainspec, Deploy},
+    utils::Loadable,
+};
+use casper_types::Key;
 
 pub use cl_type::help;
-pub use deploy::ListDeploysResult;
+pub use deploy::{ApprovalInfo, DeployInfo, ListDeploysResult};
 use deploy::{DeployExt, DeployParams, OutputKind};
 pub use error::Error;
 use error::Result;
+pub use rpc::RetryPolicy;
 use rpc::{RpcCall, TransferTarget};
 pub use validation::ValidateResponseError;
 
@@ -51,6 +57,8 @@ pub use validation::ValidateResponseError;
 ///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
 ///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
 ///   will not be printed to `stdout`.
+/// * `retry_policy` is the policy for retrying the call if it fails due to a connection error or an
+///   HTTP server error status. See [`RetryPolicy`](struct.RetryPolicy.html) for more details.
 /// * `deploy_params` contains deploy-related options for this `Deploy`. See
 ///   [`DeployStrParams`](struct.DeployStrParams.html) for more details.
 /// * `session_params` contains session-related options for this `Deploy`. See
@@ -61,6 +69,7 @@ pub fn put_deploy(
     maybe_rpc_id: &str,
     node_address: &str,
     verbosity_level: u64,
+    retry_policy: RetryPolicy,
     deploy_params: DeployStrParams<'_>,
     session_params: SessionStrParams<'_>,
     payment_params: PaymentStrParams<'_>,
@@ -70,7 +79,7 @@ pub fn put_deploy(
         payment_params.try_into()?,
         session_params.try_into()?,
     )?;
-    RpcCall::new(maybe_rpc_id, node_address, verbosity_level).put_deploy(deploy)
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level, retry_policy).put_deploy(deploy)
 }
 
 /// Creates a `Deploy` and outputs it to a file or stdout.
@@ -123,6 +132,9 @@ pub fn make_deploy(
 ///   `force` is false and a file exists at `maybe_output_path`,
 ///   [`Error::FileAlreadyExists`](enum.Error.html#variant.FileAlreadyExists) is returned and a file
 ///   will not be written.
+/// * If the deploy hash doesn't match the contents of the `Deploy`,
+///   [`Error::InvalidDeploy`](enum.Error.html#variant.InvalidDeploy) is returned and the `Deploy`
+///   is not signed.
 pub fn sign_deploy_file(
     input_path: &str,
     secret_key: &str,
@@ -147,6 +159,21 @@ pub fn sign_deploy_file(
     output.commit()
 }
 
+/// Reads a previously-saved `Deploy` from a file and returns a human-readable view of it.
+///
+/// * `input_path` specifies the path to the previously-saved `Deploy` file.
+///
+/// The returned JSON includes the validity of the deploy hash and of the signature of every
+/// approval, so invalid ones can be spotted without sending the `Deploy` to a node.
+pub fn print_deploy_file(input_path: &str) -> Result<String> {
+    let input = fs::read(input_path).map_err(|error| Error::IoError {
+        context: format!("unable to read deploy file at '{}'", input_path),
+        error,
+    })?;
+    let deploy = Deploy::read_deploy(Cursor::new(input))?;
+    Ok(serde_json::to_string_pretty(&DeployInfo::from(&deploy))?)
+}
+
 /// Reads a previously-saved `Deploy` from a file and sends it to the network for execution.
 ///
 /// * `maybe_rpc_id` is the JSON-RPC identifier, applied to the request and returned in the
@@ -159,14 +186,18 @@ pub fn sign_deploy_file(
 ///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
 ///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
 ///   will not be printed to `stdout`.
+/// * `retry_policy` is the policy for retrying the call if it fails due to a connection error or an
+///   HTTP server error status. See [`RetryPolicy`](struct.RetryPolicy.html) for more details.
 /// * `input_path` specifies the path to the previously-saved `Deploy` file.
 pub fn send_deploy_file(
     maybe_rpc_id: &str,
     node_address: &str,
     verbosity_level: u64,
+    retry_policy: RetryPolicy,
     input_path: &str,
 ) -> Result<JsonRpc> {
-    RpcCall::new(maybe_rpc_id, node_address, verbosity_level).send_deploy_file(input_path)
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level, retry_policy)
+        .send_deploy_file(input_path)
 }
 
 /// Transfers funds between purses.
@@ -181,9 +212,14 @@ pub fn send_deploy_file(
 ///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
 ///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
 ///   will not be printed to `stdout`.
-/// * `amount` is a string to be parsed as a `U512` specifying the amount to be transferred.
+/// * `retry_policy` is the policy for retrying the call if it fails due to a connection error or an
+///   HTTP server error status. See [`RetryPolicy`](struct.RetryPolicy.html) for more details.
+/// * `amount` is a string to be parsed as a `U512` specifying the number of motes to be
+///   transferred, or as an amount of CSPR if suffixed with "cspr", e.g. "2.5cspr". It must not be
+///   zero.
 /// * `target_account` is the account `PublicKey` into which the funds will be transferred,
-///   formatted as a hex-encoded string. The account's main purse will receive the funds.
+///   formatted as a hex-encoded string, or the formatted `AccountHash` of that account, e.g.
+///   "account-hash-0102...". The account's main purse will receive the funds.
 /// * `transfer_id` is a string to be parsed as a `u64` representing a user-defined identifier which
 ///   will be permanently associated with the transfer.
 /// * `deploy_params` contains deploy-related options for this `Deploy`. See
@@ -195,19 +231,19 @@ pub fn transfer(
     maybe_rpc_id: &str,
     node_address: &str,
     verbosity_level: u64,
+    retry_policy: RetryPolicy,
     amount: &str,
     target_account: &str,
     transfer_id: &str,
     deploy_params: DeployStrParams<'_>,
     payment_params: PaymentStrParams<'_>,
 ) -> Result<JsonRpc> {
-    let amount = U512::from_dec_str(amount)
-        .map_err(|err| Error::FailedToParseUint("amount", UIntParseError::FromDecStr(err)))?;
+    let amount = parsing::transfer_amount(amount)?;
     let source_purse = None;
     let target = parsing::get_transfer_target(target_account)?;
     let transfer_id = parsing::transfer_id(transfer_id)?;
 
-    RpcCall::new(maybe_rpc_id, node_address, verbosity_level).transfer(
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level, retry_policy).transfer(
         amount,
         source_purse,
         target,
@@ -224,9 +260,12 @@ pub fn transfer(
 /// using [`send_deploy_file()`](fn.send_deploy_file.html).
 ///
 /// * `maybe_output_path` specifies the output file, or if empty, will print it to `stdout`.
-/// * `amount` is a string to be parsed as a `U512` specifying the amount to be transferred.
+/// * `amount` is a string to be parsed as a `U512` specifying the number of motes to be
+///   transferred, or as an amount of CSPR if suffixed with "cspr", e.g. "2.5cspr". It must not be
+///   zero.
 /// * `target_account` is the account `PublicKey` into which the funds will be transferred,
-///   formatted as a hex-encoded string. The account's main purse will receive the funds.
+///   formatted as a hex-encoded string, or the formatted `AccountHash` of that account, e.g.
+///   "account-hash-0102...". The account's main purse will receive the funds.
 /// * `transfer_id` is a string to be parsed as a `u64` representing a user-defined identifier which
 ///   will be permanently associated with the transfer.
 /// * `deploy_params` contains deploy-related options for this `Deploy`. See
@@ -246,8 +285,7 @@ pub fn make_transfer(
     payment_params: PaymentStrParams<'_>,
     force: bool,
 ) -> Result<()> {
-    let amount = U512::from_dec_str(amount)
-        .map_err(|err| Error::FailedToParseUint("amount", UIntParseError::FromDecStr(err)))?;
+    let amount = parsing::transfer_amount(amount)?;
     let source_purse = None;
     let target = parsing::get_transfer_target(target_account)?;
     let transfer_id = parsing::transfer_id(transfer_id)?;
@@ -283,14 +321,17 @@ pub fn make_transfer(
 ///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
 ///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
 ///   will not be printed to `stdout`.
+/// * `retry_policy` is the policy for retrying the call if it fails due to a connection error or an
+///   HTTP server error status. See [`RetryPolicy`](struct.RetryPolicy.html) for more details.
 /// * `deploy_hash` must be a hex-encoded, 32-byte hash digest.
 pub fn get_deploy(
     maybe_rpc_id: &str,
     node_address: &str,
     verbosity_level: u64,
+    retry_policy: RetryPolicy,
     deploy_hash: &str,
 ) -> Result<JsonRpc> {
-    RpcCall::new(maybe_rpc_id, node_address, verbosity_level).get_deploy(deploy_hash)
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level, retry_policy).get_deploy(deploy_hash)
 }
 
 /// Retrieves a `Block` from the network.
@@ -305,15 +346,19 @@ pub fn get_deploy(
 ///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
 ///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
 ///   will not be printed to `stdout`.
+/// * `retry_policy` is the policy for retrying the call if it fails due to a connection error or an
+///   HTTP server error status. See [`RetryPolicy`](struct.RetryPolicy.html) for more details.
 /// * `maybe_block_id` must be a hex-encoded, 32-byte hash digest or a `u64` representing the
 ///   `Block` height or empty. If empty, the latest `Block` will be retrieved.
 pub fn get_block(
     maybe_rpc_id: &str,
     node_address: &str,
     verbosity_level: u64,
+    retry_policy: RetryPolicy,
     maybe_block_id: &str,
 ) -> Result<JsonRpc> {
-    RpcCall::new(maybe_rpc_id, node_address, verbosity_level).get_block(maybe_block_id)
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level, retry_policy)
+        .get_block(maybe_block_id)
 }
 
 /// Retrieves all `Transfer` items for a `Block` from the network.
@@ -328,15 +373,19 @@ pub fn get_block(
 ///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
 ///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
 ///   will not be printed to `stdout`.
+/// * `retry_policy` is the policy for retrying the call if it fails due to a connection error or an
+///   HTTP server error status. See [`RetryPolicy`](struct.RetryPolicy.html) for more details.
 /// * `maybe_block_id` must be a hex-encoded, 32-byte hash digest or a `u64` representing the
 ///   `Block` height or empty. If empty, the latest `Block` transfers will be retrieved.
 pub fn get_block_transfers(
     maybe_rpc_id: &str,
     node_address: &str,
     verbosity_level: u64,
+    retry_policy: RetryPolicy,
     maybe_block_id: &str,
 ) -> Result<JsonRpc> {
-    RpcCall::new(maybe_rpc_id, node_address, verbosity_level).get_block_transfers(maybe_block_id)
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level, retry_policy)
+        .get_block_transfers(maybe_block_id)
 }
 
 /// Retrieves a state root hash at a given `Block`.
@@ -351,15 +400,19 @@ pub fn get_block_transfers(
 ///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
 ///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
 ///   will not be printed to `stdout`.
+/// * `retry_policy` is the policy for retrying the call if it fails due to a connection error or an
+///   HTTP server error status. See [`RetryPolicy`](struct.RetryPolicy.html) for more details.
 /// * `maybe_block_id` must be a hex-encoded, 32-byte hash digest or a `u64` representing the
 ///   `Block` height or empty. If empty, the latest `Block` will be used.
 pub fn get_state_root_hash(
     maybe_rpc_id: &str,
     node_address: &str,
     verbosity_level: u64,
+    retry_policy: RetryPolicy,
     maybe_block_id: &str,
 ) -> Result<JsonRpc> {
-    RpcCall::new(maybe_rpc_id, node_address, verbosity_level).get_state_root_hash(maybe_block_id)
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level, retry_policy)
+        .get_state_root_hash(maybe_block_id)
 }
 
 /// Retrieves a stored value from the network.
@@ -374,6 +427,8 @@ pub fn get_state_root_hash(
 ///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
 ///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
 ///   will not be printed to `stdout`.
+/// * `retry_policy` is the policy for retrying the call if it fails due to a connection error or an
+///   HTTP server error status. See [`RetryPolicy`](struct.RetryPolicy.html) for more details.
 /// * `state_root_hash` must be a hex-encoded, 32-byte hash digest.
 /// * `key` must be a formatted [`PublicKey`](https://docs.rs/casper-node/latest/casper-node/crypto/asymmetric_key/enum.PublicKey.html)
 ///   or [`Key`](https://docs.rs/casper-types/latest/casper-types/enum.PublicKey.html). This will
@@ -391,11 +446,16 @@ pub fn get_item(
     maybe_rpc_id: &str,
     node_address: &str,
     verbosity_level: u64,
+    retry_policy: RetryPolicy,
     state_root_hash: &str,
     key: &str,
     path: &str,
 ) -> Result<JsonRpc> {
-    RpcCall::new(maybe_rpc_id, node_address, verbosity_level).get_item(state_root_hash, key, path)
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level, retry_policy).get_item(
+        state_root_hash,
+        key,
+        path,
+    )
 }
 
 /// Retrieves a purse's balance from the network.
@@ -410,6 +470,8 @@ pub fn get_item(
 ///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
 ///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
 ///   will not be printed to `stdout`.
+/// * `retry_policy` is the policy for retrying the call if it fails due to a connection error or an
+///   HTTP server error status. See [`RetryPolicy`](struct.RetryPolicy.html) for more details.
 /// * `state_root_hash` must be a hex-encoded, 32-byte hash digest.
 /// * `purse` is a URef, formatted as e.g.
 /// ```text
@@ -419,10 +481,12 @@ pub fn get_balance(
     maybe_rpc_id: &str,
     node_address: &str,
     verbosity_level: u64,
+    retry_policy: RetryPolicy,
     state_root_hash: &str,
     purse: &str,
 ) -> Result<JsonRpc> {
-    RpcCall::new(maybe_rpc_id, node_address, verbosity_level).get_balance(state_root_hash, purse)
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level, retry_policy)
+        .get_balance(state_root_hash, purse)
 }
 
 /// Retrieves era information from the network.
@@ -437,6 +501,8 @@ pub fn get_balance(
 ///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
 ///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
 ///   will not be printed to `stdout`.
+/// * `retry_policy` is the policy for retrying the call if it fails due to a connection error or an
+///   HTTP server error status. See [`RetryPolicy`](struct.RetryPolicy.html) for more details.
 /// * `maybe_block_id` must be a hex-encoded, 32-byte hash digest or a `u64` representing the
 ///   `Block` height or empty. If empty, era information from the latest block will be returned if
 ///   available.
@@ -444,9 +510,10 @@ pub fn get_era_info_by_switch_block(
     maybe_rpc_id: &str,
     node_address: &str,
     verbosity_level: u64,
+    retry_policy: RetryPolicy,
     maybe_block_id: &str,
 ) -> Result<JsonRpc> {
-    RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level, retry_policy)
         .get_era_info_by_switch_block(maybe_block_id)
 }
 
@@ -462,6 +529,8 @@ pub fn get_era_info_by_switch_block(
 ///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
 ///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
 ///   will not be printed to `stdout`.
+/// * `retry_policy` is the policy for retrying the call if it fails due to a connection error or an
+///   HTTP server error status. See [`RetryPolicy`](struct.RetryPolicy.html) for more details.
 /// * `maybe_block_id` must be a hex-encoded, 32-byte hash digest or a `u64` representing the
 ///   `Block` height or empty. If empty, era information from the latest block will be returned if
 ///   available.
@@ -469,9 +538,11 @@ pub fn get_auction_info(
     maybe_rpc_id: &str,
     node_address: &str,
     verbosity_level: u64,
+    retry_policy: RetryPolicy,
     maybe_block_id: &str,
 ) -> Result<JsonRpc> {
-    RpcCall::new(maybe_rpc_id, node_address, verbosity_level).get_auction_info(maybe_block_id)
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level, retry_policy)
+        .get_auction_info(maybe_block_id)
 }
 
 /// Retrieves an Account from the network.
@@ -486,6 +557,8 @@ pub fn get_auction_info(
 ///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
 ///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
 ///   will not be printed to `stdout`.
+/// * `retry_policy` is the policy for retrying the call if it fails due to a connection error or an
+///   HTTP server error status. See [`RetryPolicy`](struct.RetryPolicy.html) for more details.
 /// * `public_key` the public key associated with the `Account`
 /// * `maybe_block_id` must be a hex-encoded, 32-byte hash digest or a `u64` representing the
 ///   `Block` height or empty. If empty, the latest `Block` will be retrieved.
@@ -493,10 +566,11 @@ pub fn get_account_info(
     maybe_rpc_id: &str,
     node_address: &str,
     verbosity_level: u64,
+    retry_policy: RetryPolicy,
     public_key: &str,
     maybe_block_id: &str,
 ) -> Result<JsonRpc> {
-    RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level, retry_policy)
         .get_account_info(public_key, maybe_block_id)
 }
 
@@ -512,8 +586,15 @@ pub fn get_account_info(
 ///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
 ///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
 ///   will not be printed to `stdout`.
-pub fn list_rpcs(maybe_rpc_id: &str, node_address: &str, verbosity_level: u64) -> Result<JsonRpc> {
-    RpcCall::new(maybe_rpc_id, node_address, verbosity_level).list_rpcs()
+/// * `retry_policy` is the policy for retrying the call if it fails due to a connection error or an
+///   HTTP server error status. See [`RetryPolicy`](struct.RetryPolicy.html) for more details.
+pub fn list_rpcs(
+    maybe_rpc_id: &str,
+    node_address: &str,
+    verbosity_level: u64,
+    retry_policy: RetryPolicy,
+) -> Result<JsonRpc> {
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level, retry_policy).list_rpcs()
 }
 
 /// Retrieves a stored value from the network.
@@ -528,19 +609,42 @@ pub fn list_rpcs(maybe_rpc_id: &str, node_address: &str, verbosity_level: u64) -
 ///   count of the field.  When `verbosity_level` is greater than `1`, the request will be printed
 ///   to `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
 ///   will not be printed to `stdout`.
+/// * `retry_policy` is the policy for retrying the call if it fails due to a connection error or an
+///   HTTP server error status. See [`RetryPolicy`](struct.RetryPolicy.html) for more details.
 /// * `state_root_hash` must be a hex-encoded, 32-byte hash digest.
 /// * `dictionary_str_params` contains options to query a dictionary item.
 pub fn get_dictionary(
     maybe_rpc_id: &str,
     node_address: &str,
     verbosity_level: u64,
+    retry_policy: RetryPolicy,
     state_root_hash: &str,
     dictionary_str_params: DictionaryItemStrParams<'_>,
 ) -> Result<JsonRpc> {
-    RpcCall::new(maybe_rpc_id, node_address, verbosity_level)
+    RpcCall::new(maybe_rpc_id, node_address, verbosity_level, retry_policy)
         .get_dictionary_item(state_root_hash, dictionary_str_params)
 }
 
+/// Loads a chainspec the same way the node does on startup, checks that the values set in it make
+/// sense and returns its hash.
+///
+/// * `path` is the path to the directory containing the chainspec file, or to the chainspec file
+///   itself. Any accounts or global state update files are expected alongside it.
+///
+/// The returned hash is the one the node computes for the chainspec, so can be compared against
+/// the hash expected by the network.
+pub fn validate_chainspec(path: &str) -> Result<Digest> {
+    let path = Path::new(path);
+    let dir = if path.is_file() {
+        path.parent().unwrap_or_else(|| Path::new(""))
+    } else {
+        path
+    };
+    let chainspec = Chainspec::from_path(dir)?;
+    chainspec.validate()?;
+    Ok(chainspec.hash())
+}
+
 /// Container for `Deploy` construction options.
 #[derive(Default, Debug)]
 pub struct DeployStrParams<'a> {
@@ -601,7 +705,8 @@ impl<'a> TryInto<DeployParams> for DeployStrParams<'a> {
 /// ## `payment_args_simple`
 ///
 /// For methods taking `payment_args_simple`, this parameter is the payment contract arguments, in
-/// the form `<NAME:TYPE='VALUE'>` or `<NAME:TYPE=null>`.
+/// the form `<NAME:TYPE='VALUE'>` or `<NAME:TYPE=null>`.  The single quotes around `VALUE` are
+/// optional, so e.g. `amount:u512=1000` is equivalent to `amount:u512='1000'`.
 ///
 /// It can only be used with the following simple `CLType`s: bool, i32, i64, u8, u32, u64, u128,
 /// u256, u512, unit, string, key, account_hash, uref, public_key and `Option` of each of these.
@@ -1139,6 +1244,8 @@ pub fn pretty_print_at_level<T: ?Sized + Serialize>(value: &T, verbosity_level:
 
 #[cfg(test)]
 mod param_tests {
+    use casper_types::U512;
+
     use super::*;
 
     #[derive(Debug)]
@@ -5,14 +5,16 @@ use std::{convert::TryInto, fs, io, path::PathBuf, str::FromStr};
 
 use serde::{self, Deserialize};
 
-use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+use casper_execution_engine::{
+    core::engine_state::executable_deploy_item::ExecutableDeployItem, shared::motes::Motes,
+};
 use casper_node::{
     crypto::{hash::Digest, AsymmetricKeyExt},
     types::{DeployHash, TimeDiff, Timestamp},
 };
 use casper_types::{
-    bytesrepr, AsymmetricType, CLType, CLValue, HashAddr, Key, NamedArg, PublicKey, RuntimeArgs,
-    SecretKey, UIntParseError, U512,
+    account::AccountHash, bytesrepr, AsymmetricType, CLType, CLValue, HashAddr, Key, NamedArg,
+    PublicKey, RuntimeArgs, SecretKey, UIntParseError, U512,
 };
 
 use crate::{
@@ -97,7 +99,8 @@ mod arg_simple {
         Ok(runtime_args)
     }
 
-    /// Splits a single arg of the form `NAME:TYPE='VALUE'` into its constituent parts.
+    /// Splits a single arg of the form `NAME:TYPE='VALUE'` or `NAME:TYPE=VALUE` into its
+    /// constituent parts.
     fn split_arg(arg: &str) -> Result<(&str, CLType, &str)> {
         let parts: Vec<_> = arg.splitn(3, &[':', '='][..]).collect();
         if parts.len() != 3 {
@@ -549,11 +552,42 @@ pub(super) fn parse_payment_info(
     })
 }
 
+/// Suffix marking a transfer amount as denominated in CSPR rather than motes.
+const CSPR_SUFFIX: &str = "cspr";
+
+/// Parses a transfer amount, either a number of motes or an amount of CSPR with the suffix "cspr",
+/// e.g. "2.5cspr".
+pub(crate) fn transfer_amount(value: &str) -> Result<U512> {
+    let amount = match value.strip_suffix(CSPR_SUFFIX) {
+        Some(cspr) => Motes::from_cspr_str(cspr.trim_end())
+            .map_err(|error| Error::InvalidArgument("amount", error.to_string()))?
+            .value(),
+        None => U512::from_dec_str(value)
+            .map_err(|err| Error::FailedToParseUint("amount", UIntParseError::FromDecStr(err)))?,
+    };
+    if amount.is_zero() {
+        return Err(Error::InvalidArgument(
+            "amount",
+            "must be greater than zero".to_string(),
+        ));
+    }
+    Ok(amount)
+}
+
+/// Parses the target of a transfer, either the hex-encoded public key of the target account or its
+/// formatted account hash, e.g. "account-hash-0102...".
 pub(crate) fn get_transfer_target(target_account: &str) -> Result<TransferTarget> {
+    if let Ok(account_hash) = AccountHash::from_formatted_str(target_account) {
+        return Ok(TransferTarget::AccountHash(account_hash));
+    }
+
     let account = PublicKey::from_hex(target_account).map_err(|error| {
         Error::InvalidArgument(
             "target_account",
-            format!("failed to parse as a public key: {}", error),
+            format!(
+                "failed to parse as a public key or formatted account hash: {}",
+                error
+            ),
         )
     })?;
 
@@ -621,9 +655,58 @@ mod tests {
         }
     }
 
+    #[test]
+    fn should_parse_transfer_amount_in_motes_or_cspr() {
+        assert_eq!(
+            transfer_amount("2500000000").map_err(ErrWrapper),
+            Ok(U512::from(2_500_000_000u64))
+        );
+        assert_eq!(
+            transfer_amount("2.5cspr").map_err(ErrWrapper),
+            Ok(U512::from(2_500_000_000u64))
+        );
+        assert!(matches!(
+            transfer_amount("2.5"),
+            Err(Error::FailedToParseUint("amount", _))
+        ));
+        assert!(matches!(
+            transfer_amount("1.0000000001cspr"),
+            Err(Error::InvalidArgument("amount", _))
+        ));
+        assert!(matches!(
+            transfer_amount("0cspr"),
+            Err(Error::InvalidArgument("amount", _))
+        ));
+        assert!(matches!(
+            transfer_amount("0"),
+            Err(Error::InvalidArgument("amount", _))
+        ));
+    }
+
+    #[test]
+    fn should_parse_transfer_target() {
+        let account_hash = AccountHash::new([7; 32]);
+        assert!(matches!(
+            get_transfer_target(&account_hash.to_formatted_string()),
+            Ok(TransferTarget::AccountHash(target)) if target == account_hash
+        ));
+
+        let public_key_hex = "01f60bce2bb1059c41910eac1e7ee6c3ef4c8fcc63a901eb9603c1524cadfb0c18";
+        assert!(matches!(
+            get_transfer_target(public_key_hex),
+            Ok(TransferTarget::Account(target)) if target.to_hex() == public_key_hex
+        ));
+
+        assert!(matches!(
+            get_transfer_target("account-hash-0102"),
+            Err(Error::InvalidArgument("target_account", _))
+        ));
+    }
+
     mod bad {
         pub const EMPTY: &str = "";
-        pub const ARG_UNQUOTED: &str = "name:u32=0"; // value needs single quotes to be valid
+        pub const ARG_UNBALANCED_QUOTES: &str = "name:u32='0";
+        pub const ARG_BAD_VALUE: &str = "name:u512=1000motes";
         pub const ARG_BAD_TYPE: &str = "name:wat='false'";
         pub const ARG_GIBBERISH: &str = "asdf|1234(..)";
         pub const LARGE_2K_INPUT: &str = r#"
@@ -786,6 +869,15 @@ mod tests {
         valid_simple_args_test::<Option<U512>>("x:opt_u512=null", None);
     }
 
+    #[test]
+    fn should_parse_unquoted_values_via_args_simple() {
+        valid_simple_args_test("x:u512=1000", U512::from(1000));
+        valid_simple_args_test("x:opt_u512=1000", Some(U512::from(1000)));
+        valid_simple_args_test("x:bool=true", true);
+        valid_simple_args_test("x:string=value", String::from("value"));
+        valid_simple_args_test("x:unit=", ());
+    }
+
     #[test]
     fn should_parse_unit_via_args_simple() {
         valid_simple_args_test("x:unit=''", ());
@@ -862,7 +954,8 @@ mod tests {
     fn should_fail_to_parse_bad_args() {
         invalid_simple_args_test(bad::ARG_BAD_TYPE);
         invalid_simple_args_test(bad::ARG_GIBBERISH);
-        invalid_simple_args_test(bad::ARG_UNQUOTED);
+        invalid_simple_args_test(bad::ARG_UNBALANCED_QUOTES);
+        invalid_simple_args_test(bad::ARG_BAD_VALUE);
         invalid_simple_args_test(bad::EMPTY);
         invalid_simple_args_test(bad::LARGE_2K_INPUT);
     }
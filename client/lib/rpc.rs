@@ -1,4 +1,4 @@
-use std::{convert::TryInto, fs::File};
+use std::{convert::TryInto, fs::File, time::Duration};
 
 use futures::executor;
 use jsonrpc_lite::{Id, JsonRpc, Params};
@@ -11,7 +11,7 @@ use casper_execution_engine::core::engine_state::ExecutableDeployItem;
 use casper_node::{
     crypto::hash::Digest,
     rpcs::{
-        account::{PutDeploy, PutDeployParams},
+        account::{PutDeploy, PutDeployParams, PutDeployResult},
         chain::{
             BlockIdentifier, GetBlock, GetBlockParams, GetBlockTransfers, GetBlockTransfersParams,
             GetEraInfoBySwitchBlock, GetEraInfoParams, GetStateRootHash, GetStateRootHashParams,
@@ -26,7 +26,9 @@ use casper_node::{
     },
     types::{BlockHash, Deploy, DeployHash},
 };
-use casper_types::{AsymmetricType, Key, PublicKey, URef, U512};
+use casper_types::{
+    account::AccountHash, AsymmetricType, Key, ProtocolVersion, PublicKey, URef, U512,
+};
 
 use crate::{
     deploy::{DeployExt, DeployParams, SendDeploy, Transfer},
@@ -38,6 +40,54 @@ use crate::{
 pub(crate) enum TransferTarget {
     /// Transfer to another account.
     Account(PublicKey),
+    /// Transfer to another account, given by its account hash.
+    AccountHash(AccountHash),
+}
+
+/// Upper bound of the delay between retries, unless the configured initial delay is even longer.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Policy for retrying calls to the node which failed due to a connection error or an HTTP server
+/// error status.
+///
+/// Queries are idempotent and retried by default. Deploy submissions are only retried if
+/// `max_deploy_retries` is set, and before every retry the node is asked for the deploy by its
+/// hash, so that a deploy which did reach the node is not submitted twice.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RetryPolicy {
+    /// Maximum number of retries of a query.
+    pub max_query_retries: u32,
+    /// Maximum number of retries of a deploy submission.
+    pub max_deploy_retries: u32,
+    /// Delay before the first retry, doubled for every subsequent one.
+    pub retry_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_query_retries: 3,
+            max_deploy_retries: 0,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// A failed attempt to send a request to the node.
+struct FailedAttempt {
+    error: Error,
+    /// Whether the attempt failed due to a connection error or an HTTP server error status, so
+    /// that retrying the request might succeed.
+    is_transient: bool,
+}
+
+impl From<Error> for FailedAttempt {
+    fn from(error: Error) -> Self {
+        FailedAttempt {
+            error,
+            is_transient: false,
+        }
+    }
 }
 
 /// Struct representing a single JSON-RPC call to the casper node.
@@ -46,6 +96,9 @@ pub(crate) struct RpcCall {
     rpc_id: Id,
     node_address: String,
     verbosity_level: u64,
+    retry_policy: RetryPolicy,
+    /// The hash of the deploy submitted by this call, if any.
+    submitted_deploy: Option<DeployHash>,
 }
 
 /// `RpcCall` encapsulates calls made to the casper node service via JSON-RPC.
@@ -63,7 +116,15 @@ impl RpcCall {
     /// of the field.  When `verbosity_level` is greater than `1`, the request will be printed to
     /// `stdout` with no abbreviation of long fields.  When `verbosity_level` is `0`, the request
     /// will not be printed to `stdout`.
-    pub(crate) fn new(maybe_rpc_id: &str, node_address: &str, verbosity_level: u64) -> Self {
+    ///
+    /// `retry_policy` specifies how often and after which delay the call is retried if it fails due
+    /// to a connection error or an HTTP server error status.
+    pub(crate) fn new(
+        maybe_rpc_id: &str,
+        node_address: &str,
+        verbosity_level: u64,
+        retry_policy: RetryPolicy,
+    ) -> Self {
         let rpc_id = if maybe_rpc_id.is_empty() {
             Id::from(rand::thread_rng().gen::<i64>())
         } else if let Ok(i64_id) = maybe_rpc_id.parse::<i64>() {
@@ -76,6 +137,8 @@ impl RpcCall {
             rpc_id,
             node_address: node_address.trim_end_matches('/').to_string(),
             verbosity_level,
+            retry_policy,
+            submitted_deploy: None,
         }
     }
 
@@ -221,8 +284,9 @@ impl RpcCall {
             deploy_params,
             payment,
         )?;
+        let rpc_call = self.submitting(&deploy);
         let params = PutDeployParams { deploy };
-        Transfer::request_with_map_params(self, params)
+        Transfer::request_with_map_params(rpc_call, params)
     }
 
     pub(crate) fn send_deploy_file(self, input_path: &str) -> Result<JsonRpc> {
@@ -231,13 +295,23 @@ impl RpcCall {
             error,
         })?;
         let deploy = Deploy::read_deploy(input)?;
+        let rpc_call = self.submitting(&deploy);
         let params = PutDeployParams { deploy };
-        SendDeploy::request_with_map_params(self, params)
+        SendDeploy::request_with_map_params(rpc_call, params)
     }
 
     pub(crate) fn put_deploy(self, deploy: Deploy) -> Result<JsonRpc> {
+        let rpc_call = self.submitting(&deploy);
         let params = PutDeployParams { deploy };
-        PutDeploy::request_with_map_params(self, params)
+        PutDeploy::request_with_map_params(rpc_call, params)
+    }
+
+    /// Marks this call as submitting the given deploy, so that retries don't submit it twice.
+    fn submitting(self, deploy: &Deploy) -> Self {
+        RpcCall {
+            submitted_deploy: Some(*deploy.id()),
+            ..self
+        }
     }
 
     pub(crate) fn get_block(self, maybe_block_identifier: &str) -> Result<JsonRpc> {
@@ -305,23 +379,102 @@ impl RpcCall {
 
     async fn request(self, method: &str, params: Params) -> Result<JsonRpc> {
         let url = format!("{}/{}", self.node_address, RPC_API_PATH);
-        let rpc_req = JsonRpc::request_with_params(self.rpc_id, method, params);
+        let rpc_req = JsonRpc::request_with_params(self.rpc_id.clone(), method, params);
 
         crate::pretty_print_at_level(&rpc_req, self.verbosity_level);
 
+        let retry_policy = self.retry_policy;
+        let max_retries = match self.submitted_deploy {
+            Some(_) => retry_policy.max_deploy_retries,
+            None => retry_policy.max_query_retries,
+        };
+
         let client = Client::new();
+        let mut retries = 0;
+        loop {
+            let error = match self.send(&client, &url, &rpc_req).await {
+                Ok(response) => return Ok(response),
+                Err(FailedAttempt {
+                    error,
+                    is_transient: true,
+                }) if retries < max_retries => error,
+                Err(FailedAttempt { error, .. }) => return Err(error),
+            };
+
+            let delay = retry_policy
+                .retry_delay
+                .checked_mul(2u32.saturating_pow(retries))
+                .map_or(MAX_RETRY_DELAY, |delay| delay.min(MAX_RETRY_DELAY))
+                .max(retry_policy.retry_delay);
+            if self.verbosity_level > 0 {
+                eprintln!("Retrying in {:?} after error: {}", delay, error);
+            }
+            tokio::time::sleep(delay).await;
+            retries += 1;
+
+            // The failed submission might have reached the node regardless, so check before
+            // submitting the deploy again.
+            if let Some(deploy_hash) = self.submitted_deploy {
+                if let Some(response) = self.find_deploy(&client, &url, deploy_hash).await {
+                    return Ok(response);
+                }
+            }
+        }
+    }
+
+    /// Asks the node for the deploy with the given hash, returning the response its submission
+    /// would have received if the node has it.
+    async fn find_deploy(
+        &self,
+        client: &Client,
+        url: &str,
+        deploy_hash: DeployHash,
+    ) -> Option<JsonRpc> {
+        let params = GetDeployParams { deploy_hash };
+        let rpc_req = JsonRpc::request_with_params(
+            self.rpc_id.clone(),
+            GetDeploy::METHOD,
+            Params::from(params.into_json_map()),
+        );
+        let response = self.send(client, url, &rpc_req).await.ok()?;
+        let api_version: ProtocolVersion =
+            serde_json::from_value(response.get_result()?.get("api_version")?.clone()).ok()?;
+
+        if self.verbosity_level > 0 {
+            println!("Deploy {} was already received by the node", deploy_hash);
+        }
+        let result = PutDeployResult {
+            api_version,
+            deploy_hash,
+        };
+        Some(JsonRpc::success(self.rpc_id.clone(), &json!(result)))
+    }
+
+    /// Sends the request to the node once.
+    async fn send(
+        &self,
+        client: &Client,
+        url: &str,
+        rpc_req: &JsonRpc,
+    ) -> std::result::Result<JsonRpc, FailedAttempt> {
         let response = client
-            .post(&url)
-            .json(&rpc_req)
+            .post(url)
+            .json(rpc_req)
             .send()
             .await
-            .map_err(Error::FailedToGetResponse)?;
+            .map_err(|error| FailedAttempt {
+                is_transient: error.is_connect(),
+                error: Error::FailedToGetResponse(error),
+            })?;
 
         if let Err(error) = response.error_for_status_ref() {
             if self.verbosity_level > 0 {
                 println!("Failed Sending {}", error);
             }
-            return Err(Error::FailedSending(rpc_req));
+            return Err(FailedAttempt {
+                error: Error::FailedSending(rpc_req.clone()),
+                is_transient: response.status().is_server_error(),
+            });
         }
 
         let rpc_response = response.json().await.map_err(Error::FailedToParseResponse);
@@ -330,7 +483,7 @@ impl RpcCall {
             if self.verbosity_level > 0 {
                 println!("Failed parsing as a JSON-RPC response: {}", error);
             }
-            return Err(error);
+            return Err(error.into());
         }
 
         let rpc_response: JsonRpc = rpc_response?;
@@ -346,13 +499,13 @@ impl RpcCall {
             if self.verbosity_level > 0 {
                 println!("Response returned an error");
             }
-            return Err(Error::ResponseIsError(error.clone()));
+            return Err(Error::ResponseIsError(error.clone()).into());
         }
 
         if self.verbosity_level > 0 {
             println!("Invalid response returned");
         }
-        Err(Error::InvalidRpcResponse(rpc_response))
+        Err(Error::InvalidRpcResponse(rpc_response).into())
     }
 }
 
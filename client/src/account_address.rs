@@ -2,8 +2,7 @@ use std::str;
 
 use clap::{App, ArgMatches, SubCommand};
 
-use casper_client::Error;
-use casper_types::{AsymmetricType, PublicKey};
+use casper_client::{keygen, Error};
 
 use crate::{command::ClientCommand, common, Success};
 
@@ -17,7 +16,9 @@ pub struct GenerateAccountHash {}
 
 impl<'a, 'b> ClientCommand<'a, 'b> for GenerateAccountHash {
     const NAME: &'static str = "account-address";
-    const ABOUT: &'static str = "Generates an account hash from a given public key";
+    const ABOUT: &'static str =
+        "Generates an account hash from a given public key, printed both hex-encoded and \
+        base64-encoded";
 
     fn build(display_order: usize) -> App<'a, 'b> {
         SubCommand::with_name(Self::NAME)
@@ -28,12 +29,8 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GenerateAccountHash {
     }
 
     fn run(matches: &ArgMatches<'_>) -> Result<Success, Error> {
-        let hex_public_key = common::public_key::get(matches)?;
-        let public_key = PublicKey::from_hex(&hex_public_key).map_err(|error| {
-            eprintln!("Can't parse {} as a public key: {}", hex_public_key, error);
-            Error::FailedToParseKey
-        })?;
-        let account_hash = public_key.to_account_hash();
-        Ok(Success::Output(account_hash.to_formatted_string()))
+        let formatted_public_key = common::public_key::get(matches)?;
+        let public_key = keygen::parse_public_key(&formatted_public_key)?;
+        Ok(Success::Output(keygen::account_address(&public_key)))
     }
 }
@@ -27,6 +27,8 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetBlock {
             .arg(common::node_address::arg(
                 DisplayOrder::NodeAddress as usize,
             ))
+            .arg(common::max_retries::arg())
+            .arg(common::retry_delay::arg())
             .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
             .arg(common::block_identifier::arg(
                 DisplayOrder::BlockIdentifier as usize,
@@ -37,9 +39,16 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetBlock {
         let maybe_rpc_id = common::rpc_id::get(matches);
         let node_address = common::node_address::get(matches);
         let verbosity_level = common::verbose::get(matches);
+        let retry_policy = common::retry_policy(matches)?;
         let maybe_block_id = common::block_identifier::get(matches);
 
-        casper_client::get_block(maybe_rpc_id, node_address, verbosity_level, maybe_block_id)
-            .map(Success::from)
+        casper_client::get_block(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            retry_policy,
+            maybe_block_id,
+        )
+        .map(Success::from)
     }
 }
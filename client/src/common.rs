@@ -1,8 +1,8 @@
-use std::fs;
+use std::{fs, time::Duration};
 
 use clap::{Arg, ArgMatches};
 
-use casper_client::Error;
+use casper_client::{Error, RetryPolicy};
 use casper_types::PublicKey;
 
 pub const ARG_PATH: &str = "PATH";
@@ -62,6 +62,83 @@ pub mod node_address {
     }
 }
 
+/// Handles providing the arg for and retrieval of the maximum number of retries of a failed call to
+/// the node.
+pub mod max_retries {
+    use super::*;
+
+    const ARG_NAME: &str = "max-retries";
+    const ARG_VALUE_NAME: &str = ARG_INTEGER;
+    const ARG_HELP: &str =
+        "Maximum number of times a call to the node failing due to a network error or HTTP error \
+        status is retried. If not set, only queries are retried, up to 3 times. If set, deploy \
+        submissions are retried too, after checking the node hasn't already received the deploy";
+
+    pub fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+    }
+
+    pub fn get(matches: &ArgMatches) -> Result<Option<u32>, Error> {
+        matches
+            .value_of(ARG_NAME)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|error| Error::FailedToParseInt("max-retries", error))
+            })
+            .transpose()
+    }
+}
+
+/// Handles providing the arg for and retrieval of the delay before retrying a failed call to the
+/// node.
+pub mod retry_delay {
+    use super::*;
+
+    const ARG_NAME: &str = "retry-delay";
+    const ARG_VALUE_NAME: &str = "DURATION";
+    const ARG_DEFAULT: &str = "1s";
+    const ARG_HELP: &str =
+        "Delay before the first retry of a failed call to the node, doubled for every subsequent \
+        retry, e.g. '500ms' or '2s'";
+
+    pub fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .default_value(ARG_DEFAULT)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+    }
+
+    pub fn get(matches: &ArgMatches) -> Result<Option<Duration>, Error> {
+        matches
+            .value_of(ARG_NAME)
+            .map(|value| {
+                humantime::parse_duration(value)
+                    .map_err(|error| Error::FailedToParseTimeDiff("retry-delay", error))
+            })
+            .transpose()
+    }
+}
+
+/// Returns the policy for retrying failed calls to the node, as specified by the retry args.
+pub fn retry_policy(matches: &ArgMatches) -> Result<RetryPolicy, Error> {
+    let mut retry_policy = RetryPolicy::default();
+    if let Some(max_retries) = max_retries::get(matches)? {
+        retry_policy.max_query_retries = max_retries;
+        retry_policy.max_deploy_retries = max_retries;
+    }
+    if let Some(retry_delay) = retry_delay::get(matches)? {
+        retry_policy.retry_delay = retry_delay;
+    }
+    Ok(retry_policy)
+}
+
 /// Handles providing the arg for the RPC ID.
 pub mod rpc_id {
     use super::*;
@@ -3,6 +3,7 @@ mod get;
 mod list;
 mod make;
 mod make_transfer;
+mod print;
 mod put;
 mod send;
 mod sign;
@@ -11,6 +12,7 @@ mod transfer;
 pub use list::ListDeploys;
 pub use make::MakeDeploy;
 pub use make_transfer::MakeTransfer;
+pub use print::PrintDeploy;
 pub use send::SendDeploy;
 pub use sign::SignDeploy;
 pub use transfer::Transfer;
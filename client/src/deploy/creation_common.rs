@@ -521,10 +521,13 @@ pub(super) fn apply_common_creation_options<'a, 'b>(
         .arg(show_arg_examples::arg());
 
     if include_node_address {
-        subcommand = subcommand.arg(
-            common::node_address::arg(DisplayOrder::NodeAddress as usize)
-                .required_unless(show_arg_examples::ARG_NAME),
-        );
+        subcommand = subcommand
+            .arg(
+                common::node_address::arg(DisplayOrder::NodeAddress as usize)
+                    .required_unless(show_arg_examples::ARG_NAME),
+            )
+            .arg(common::max_retries::arg())
+            .arg(common::retry_delay::arg());
     }
 
     subcommand = subcommand
@@ -50,6 +50,8 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetDeploy {
             .arg(common::node_address::arg(
                 DisplayOrder::NodeAddress as usize,
             ))
+            .arg(common::max_retries::arg())
+            .arg(common::retry_delay::arg())
             .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
             .arg(deploy_hash::arg())
     }
@@ -58,9 +60,16 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetDeploy {
         let maybe_rpc_id = common::rpc_id::get(matches);
         let node_address = common::node_address::get(matches);
         let verbosity_level = common::verbose::get(matches);
+        let retry_policy = common::retry_policy(matches)?;
         let deploy_hash = deploy_hash::get(matches);
 
-        casper_client::get_deploy(maybe_rpc_id, node_address, verbosity_level, deploy_hash)
-            .map(Success::from)
+        casper_client::get_deploy(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            retry_policy,
+            deploy_hash,
+        )
+        .map(Success::from)
     }
 }
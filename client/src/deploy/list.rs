@@ -29,6 +29,8 @@ impl<'a, 'b> ClientCommand<'a, 'b> for ListDeploys {
             .arg(common::node_address::arg(
                 DisplayOrder::NodeAddress as usize,
             ))
+            .arg(common::max_retries::arg())
+            .arg(common::retry_delay::arg())
             .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
             .arg(common::block_identifier::arg(
                 DisplayOrder::BlockHash as usize,
@@ -39,10 +41,16 @@ impl<'a, 'b> ClientCommand<'a, 'b> for ListDeploys {
         let maybe_rpc_id = common::rpc_id::get(matches);
         let node_address = common::node_address::get(matches);
         let verbosity_level = common::verbose::get(matches);
+        let retry_policy = common::retry_policy(matches)?;
         let maybe_block_id = common::block_identifier::get(matches);
 
-        let result =
-            casper_client::get_block(maybe_rpc_id, node_address, verbosity_level, maybe_block_id);
+        let result = casper_client::get_block(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            retry_policy,
+            maybe_block_id,
+        );
 
         result.map(|response| {
             let response_value = response.get_result().cloned().unwrap();
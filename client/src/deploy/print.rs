@@ -0,0 +1,27 @@
+use clap::{App, ArgMatches, SubCommand};
+
+use casper_client::Error;
+
+use super::creation_common;
+use crate::{command::ClientCommand, Success};
+
+pub struct PrintDeploy;
+
+impl<'a, 'b> ClientCommand<'a, 'b> for PrintDeploy {
+    const NAME: &'static str = "print-deploy";
+    const ABOUT: &'static str =
+        "Reads a previously-saved deploy from a file and prints it as JSON, flagging any invalid \
+        hash or approval";
+
+    fn build(display_order: usize) -> App<'a, 'b> {
+        SubCommand::with_name(Self::NAME)
+            .about(Self::ABOUT)
+            .display_order(display_order)
+            .arg(creation_common::input::arg())
+    }
+
+    fn run(matches: &ArgMatches<'_>) -> Result<Success, Error> {
+        let input_path = creation_common::input::get(matches);
+        casper_client::print_deploy_file(input_path).map(Success::Output)
+    }
+}
@@ -27,6 +27,7 @@ impl<'a, 'b> ClientCommand<'a, 'b> for PutDeploy {
         let maybe_rpc_id = common::rpc_id::get(matches);
         let node_address = common::node_address::get(matches);
         let verbosity_level = common::verbose::get(matches);
+        let retry_policy = common::retry_policy(matches)?;
 
         let secret_key = common::secret_key::get(matches);
         let timestamp = creation_common::timestamp::get(matches);
@@ -42,6 +43,7 @@ impl<'a, 'b> ClientCommand<'a, 'b> for PutDeploy {
             maybe_rpc_id,
             node_address,
             verbosity_level,
+            retry_policy,
             DeployStrParams {
                 secret_key,
                 timestamp,
@@ -20,6 +20,8 @@ impl<'a, 'b> ClientCommand<'a, 'b> for SendDeploy {
             .arg(common::node_address::arg(
                 DisplayOrder::NodeAddress as usize,
             ))
+            .arg(common::max_retries::arg())
+            .arg(common::retry_delay::arg())
             .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
             .arg(creation_common::input::arg())
     }
@@ -28,9 +30,16 @@ impl<'a, 'b> ClientCommand<'a, 'b> for SendDeploy {
         let maybe_rpc_id = common::rpc_id::get(matches);
         let node_address = common::node_address::get(matches);
         let verbosity_level = common::verbose::get(matches);
+        let retry_policy = common::retry_policy(matches)?;
         let input_path = creation_common::input::get(matches);
 
-        casper_client::send_deploy_file(maybe_rpc_id, node_address, verbosity_level, input_path)
-            .map(Success::from)
+        casper_client::send_deploy_file(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            retry_policy,
+            input_path,
+        )
+        .map(Success::from)
     }
 }
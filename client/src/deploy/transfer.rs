@@ -11,8 +11,10 @@ pub(super) mod amount {
 
     const ARG_NAME: &str = "amount";
     const ARG_SHORT: &str = "a";
-    const ARG_VALUE_NAME: &str = "512-BIT INTEGER";
-    const ARG_HELP: &str = "The number of motes to transfer";
+    const ARG_VALUE_NAME: &str = "512-BIT INTEGER or CSPR AMOUNT";
+    const ARG_HELP: &str =
+        "The number of motes to transfer, or an amount of CSPR suffixed with \"cspr\", e.g. \
+        \"2.5cspr\". Must not be zero";
 
     pub(in crate::deploy) fn arg() -> Arg<'static, 'static> {
         Arg::with_name(ARG_NAME)
@@ -37,10 +39,10 @@ pub(super) mod target_account {
 
     pub(in crate::deploy) const ARG_NAME: &str = "target-account";
     const ARG_SHORT: &str = "t";
-    const ARG_VALUE_NAME: &str = "HEX STRING";
+    const ARG_VALUE_NAME: &str = "HEX STRING or FORMATTED STRING";
     const ARG_HELP: &str =
-        "Hex-encoded public key of the account from which the main purse will be used as the \
-        target";
+        "Hex-encoded public key or formatted account hash (e.g. \"account-hash-0102...\") of the \
+        account from which the main purse will be used as the target";
 
     // Conflicts with --target-purse, but that's handled via an `ArgGroup` in the subcommand. Don't
     // add a `conflicts_with()` to the arg or the `ArgGroup` fails to work correctly.
@@ -112,6 +114,7 @@ impl<'a, 'b> ClientCommand<'a, 'b> for Transfer {
         let maybe_rpc_id = common::rpc_id::get(matches);
         let node_address = common::node_address::get(matches);
         let verbosity_level = common::verbose::get(matches);
+        let retry_policy = common::retry_policy(matches)?;
 
         let secret_key = common::secret_key::get(matches);
         let timestamp = creation_common::timestamp::get(matches);
@@ -126,6 +129,7 @@ impl<'a, 'b> ClientCommand<'a, 'b> for Transfer {
             maybe_rpc_id,
             node_address,
             verbosity_level,
+            retry_policy,
             amount,
             target_account,
             transfer_id,
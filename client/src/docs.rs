@@ -26,6 +26,8 @@ impl<'a, 'b> ClientCommand<'a, 'b> for ListRpcs {
             .arg(common::node_address::arg(
                 DisplayOrder::NodeAddress as usize,
             ))
+            .arg(common::max_retries::arg())
+            .arg(common::retry_delay::arg())
             .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
     }
 
@@ -33,7 +35,9 @@ impl<'a, 'b> ClientCommand<'a, 'b> for ListRpcs {
         let maybe_rpc_id = common::rpc_id::get(matches);
         let node_address = common::node_address::get(matches);
         let verbosity_level = common::verbose::get(matches);
+        let retry_policy = common::retry_policy(matches)?;
 
-        casper_client::list_rpcs(maybe_rpc_id, node_address, verbosity_level).map(Success::from)
+        casper_client::list_rpcs(maybe_rpc_id, node_address, verbosity_level, retry_policy)
+            .map(Success::from)
     }
 }
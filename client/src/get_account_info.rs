@@ -28,6 +28,8 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetAccountInfo {
             .arg(common::node_address::arg(
                 DisplayOrder::NodeAddress as usize,
             ))
+            .arg(common::max_retries::arg())
+            .arg(common::retry_delay::arg())
             .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
             .arg(common::public_key::arg(DisplayOrder::PublicKey as usize))
             .arg(common::block_identifier::arg(
@@ -39,6 +41,7 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetAccountInfo {
         let maybe_rpc_id = common::rpc_id::get(matches);
         let node_address = common::node_address::get(matches);
         let verbosity_level = common::verbose::get(matches);
+        let retry_policy = common::retry_policy(matches)?;
         let public_key = common::public_key::get(matches)?;
         let block_identifier = common::block_identifier::get(matches);
 
@@ -46,6 +49,7 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetAccountInfo {
             maybe_rpc_id,
             node_address,
             verbosity_level,
+            retry_policy,
             &public_key,
             block_identifier,
         )
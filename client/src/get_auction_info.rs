@@ -28,6 +28,8 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetAuctionInfo {
             .arg(common::node_address::arg(
                 DisplayOrder::NodeAddress as usize,
             ))
+            .arg(common::max_retries::arg())
+            .arg(common::retry_delay::arg())
             .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
             .arg(common::block_identifier::arg(
                 DisplayOrder::BlockIdentifier as usize,
@@ -38,9 +40,16 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetAuctionInfo {
         let maybe_rpc_id = common::rpc_id::get(matches);
         let node_address = common::node_address::get(matches);
         let verbosity_level = common::verbose::get(matches);
+        let retry_policy = common::retry_policy(matches)?;
         let maybe_block_id = common::block_identifier::get(matches);
 
-        casper_client::get_auction_info(maybe_rpc_id, node_address, verbosity_level, maybe_block_id)
-            .map(Success::from)
+        casper_client::get_auction_info(
+            maybe_rpc_id,
+            node_address,
+            verbosity_level,
+            retry_policy,
+            maybe_block_id,
+        )
+        .map(Success::from)
     }
 }
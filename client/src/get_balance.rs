@@ -56,6 +56,8 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetBalance {
             .arg(common::node_address::arg(
                 DisplayOrder::NodeAddress as usize,
             ))
+            .arg(common::max_retries::arg())
+            .arg(common::retry_delay::arg())
             .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
             .arg(common::state_root_hash::arg(
                 DisplayOrder::StateRootHash as usize,
@@ -67,6 +69,7 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetBalance {
         let maybe_rpc_id = common::rpc_id::get(matches);
         let node_address = common::node_address::get(matches);
         let verbosity_level = common::verbose::get(matches);
+        let retry_policy = common::retry_policy(matches)?;
         let state_root_hash = common::state_root_hash::get(matches);
         let purse_uref = purse_uref::get(matches);
 
@@ -74,6 +77,7 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetBalance {
             maybe_rpc_id,
             node_address,
             verbosity_level,
+            retry_policy,
             state_root_hash,
             purse_uref,
         )
@@ -23,13 +23,19 @@ enum DisplayOrder {
     OutputDir,
     Force,
     Algorithm,
+    Format,
+    Seed,
+    ImportSecretHex,
+    Count,
+    StdoutOnly,
+    PassphraseFile,
 }
 
 /// Handles providing the arg for and retrieval of the output directory.
 mod output_dir {
     use super::*;
 
-    const ARG_NAME: &str = "output-dir";
+    pub(super) const ARG_NAME: &str = "output-dir";
     const ARG_VALUE_NAME: &str = common::ARG_PATH;
     const ARG_HELP: &str =
         "Path to output directory where key files will be created. If the path doesn't exist, it \
@@ -65,6 +71,7 @@ mod algorithm {
             .default_value(keygen::ED25519)
             .possible_value(keygen::ED25519)
             .possible_value(keygen::SECP256K1)
+            .possible_value(keygen::SECP256R1)
             .value_name(ARG_VALUE_NAME)
             .help(ARG_HELP)
             .display_order(DisplayOrder::Algorithm as usize)
@@ -77,6 +84,178 @@ mod algorithm {
     }
 }
 
+/// Handles providing the arg for and retrieval of the output format.
+mod format {
+    use super::*;
+
+    const ARG_NAME: &str = "format";
+    const ARG_VALUE_NAME: &str = common::ARG_STRING;
+    const ARG_HELP: &str =
+        "The output format. \"files\" writes each key to its own file, while \"json\" writes a \
+        single \"keys.json\" file containing the account hash, the public key in hex and PEM \
+        formats and the PEM-encoded secret key";
+
+    pub fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .default_value(keygen::FORMAT_FILES)
+            .possible_value(keygen::FORMAT_FILES)
+            .possible_value(keygen::FORMAT_JSON)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::Format as usize)
+    }
+
+    pub fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches
+            .value_of(ARG_NAME)
+            .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
+    }
+}
+
+/// Handles providing the arg for and retrieval of the optional seed.
+mod seed {
+    use super::*;
+
+    pub(super) const ARG_NAME: &str = "seed";
+    const ARG_VALUE_NAME: &str = common::ARG_HEX_STRING;
+    const ARG_HELP: &str =
+        "Hex-encoded 32-byte seed from which the secret key is deterministically derived instead of \
+        using the operating system's random number generator. The same seed and algorithm always \
+        yield the same secret_key.pem. Only use this for test fixtures, as anyone knowing the seed \
+        can recreate the secret key";
+
+    pub fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::Seed as usize)
+    }
+
+    pub fn get<'a>(matches: &'a ArgMatches) -> Option<&'a str> {
+        matches.value_of(ARG_NAME)
+    }
+}
+
+/// Handles providing the arg for and retrieval of the optional hex-encoded secret key to import.
+mod import_secret_hex {
+    use super::*;
+
+    pub(super) const ARG_NAME: &str = "import-secret-hex";
+    const ARG_VALUE_NAME: &str = common::ARG_HEX_STRING;
+    const ARG_HELP: &str =
+        "Hex-encoded raw bytes of an existing secret key to import instead of generating a new one. \
+        The hex string must not be prefixed with the algorithm tag, and its algorithm must be given \
+        via --algorithm. All public key files are derived from the imported secret key";
+
+    pub fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .conflicts_with(seed::ARG_NAME)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::ImportSecretHex as usize)
+    }
+
+    pub fn get<'a>(matches: &'a ArgMatches) -> Option<&'a str> {
+        matches.value_of(ARG_NAME)
+    }
+}
+
+/// Handles providing the arg for and retrieval of the number of account key sets to create.
+mod count {
+    use super::*;
+
+    const ARG_NAME: &str = "count";
+    const ARG_VALUE_NAME: &str = common::ARG_INTEGER;
+    const ARG_DEFAULT: &str = "1";
+    const ARG_HELP: &str =
+        "The number of account key sets to create. If greater than 1, each set is written to a \
+        numbered subdirectory \"0\", \"1\", ... of the output directory. If --seed is also given, \
+        it seeds a single random number generator from which all the keys are derived, so the \
+        same seed and count always yield the same key sets";
+
+    pub fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .default_value(ARG_DEFAULT)
+            .conflicts_with(import_secret_hex::ARG_NAME)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::Count as usize)
+    }
+
+    pub fn get(matches: &ArgMatches) -> Result<usize, Error> {
+        let value = matches
+            .value_of(ARG_NAME)
+            .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME));
+        let count = value
+            .parse::<usize>()
+            .map_err(|error| Error::FailedToParseInt("count", error))?;
+        if count == 0 {
+            return Err(Error::InvalidArgument(
+                "count",
+                "must be at least 1".to_string(),
+            ));
+        }
+        Ok(count)
+    }
+}
+
+/// Handles providing the arg for and retrieval of the stdout-only flag.
+mod stdout_only {
+    use super::*;
+
+    const ARG_NAME: &str = "stdout-only";
+    const ARG_HELP: &str =
+        "If this flag is passed, no files are written. Instead the hex-encoded account hash, the \
+        base64-encoded account hash and the hex-encoded public key are printed to stdout. Cannot \
+        be used along with an output directory";
+
+    pub fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .conflicts_with(output_dir::ARG_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::StdoutOnly as usize)
+    }
+
+    pub fn get(matches: &ArgMatches) -> bool {
+        matches.is_present(ARG_NAME)
+    }
+}
+
+/// Handles providing the arg for and retrieval of the optional passphrase file.
+mod passphrase_file {
+    use super::*;
+
+    const ARG_NAME: &str = "passphrase-file";
+    const ARG_VALUE_NAME: &str = common::ARG_PATH;
+    const ARG_HELP: &str =
+        "Path to a file containing a passphrase. If provided, the secret key is written as a \
+        PKCS#8 container encrypted with AES-256 using the passphrase. A single trailing newline in \
+        the file is ignored";
+
+    pub fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::PassphraseFile as usize)
+    }
+
+    pub fn get<'a>(matches: &'a ArgMatches) -> Option<&'a str> {
+        matches.value_of(ARG_NAME)
+    }
+}
+
 pub struct Keygen {}
 
 impl<'a, 'b> ClientCommand<'a, 'b> for Keygen {
@@ -91,14 +270,84 @@ impl<'a, 'b> ClientCommand<'a, 'b> for Keygen {
             .arg(output_dir::arg())
             .arg(common::force::arg(DisplayOrder::Force as usize, false))
             .arg(algorithm::arg())
+            .arg(format::arg())
+            .arg(seed::arg())
+            .arg(import_secret_hex::arg())
+            .arg(count::arg())
+            .arg(stdout_only::arg())
+            .arg(passphrase_file::arg())
     }
 
     fn run(matches: &ArgMatches<'_>) -> Result<Success, Error> {
         let output_dir = output_dir::get(matches);
         let algorithm = algorithm::get(matches);
         let force = common::force::get(matches);
+        let count = count::get(matches)?;
+
+        if count > 1 {
+            return run_batch(matches, &output_dir, algorithm, count, force);
+        }
+
+        let secret_key = match (seed::get(matches), import_secret_hex::get(matches)) {
+            (Some(seed), _) => keygen::secret_key_from_seed(algorithm, seed)?,
+            (None, Some(secret_key_hex)) => keygen::secret_key_from_hex(algorithm, secret_key_hex)?,
+            (None, None) => keygen::generate_secret_key(algorithm)?,
+        };
+
+        if stdout_only::get(matches) {
+            return Ok(Success::Output(keygen::account_summary(&secret_key)));
+        }
 
-        keygen::generate_files(&output_dir, algorithm, force)
+        let maybe_passphrase = passphrase_file::get(matches)
+            .map(keygen::read_passphrase_file)
+            .transpose()?;
+        let maybe_passphrase = maybe_passphrase.as_deref();
+
+        if format::get(matches) == keygen::FORMAT_JSON {
+            return keygen::write_json_file_with_passphrase(
+                &output_dir,
+                &secret_key,
+                maybe_passphrase,
+                force,
+            )
+            .map(|_| Success::Output(format!("Wrote {} to {}", keygen::KEYS_JSON, output_dir)));
+        }
+
+        keygen::write_files_with_passphrase(&output_dir, &secret_key, maybe_passphrase, force)
             .map(|_| Success::Output(format!("Wrote files to {}", output_dir)))
     }
 }
+
+/// Creates `count` account key sets in numbered subdirectories of `output_dir`.
+fn run_batch(
+    matches: &ArgMatches<'_>,
+    output_dir: &str,
+    algorithm: &str,
+    count: usize,
+    force: bool,
+) -> Result<Success, Error> {
+    let secret_keys = keygen::generate_secret_keys(algorithm, seed::get(matches), count)?;
+
+    if stdout_only::get(matches) {
+        let summaries = secret_keys
+            .iter()
+            .map(keygen::account_summary)
+            .collect::<Vec<_>>();
+        return Ok(Success::Output(summaries.join("\n\n")));
+    }
+
+    let maybe_passphrase = passphrase_file::get(matches)
+        .map(keygen::read_passphrase_file)
+        .transpose()?;
+    let maybe_passphrase = maybe_passphrase.as_deref();
+
+    if format::get(matches) == keygen::FORMAT_JSON {
+        keygen::write_numbered_json_files(output_dir, &secret_keys, maybe_passphrase, force)?;
+    } else {
+        keygen::write_numbered_files(output_dir, &secret_keys, maybe_passphrase, force)?;
+    }
+    Ok(Success::Output(format!(
+        "Wrote {} account key sets to {}",
+        count, output_dir
+    )))
+}
@@ -13,6 +13,8 @@ mod get_state_hash;
 mod keygen;
 mod query_dictionary;
 mod query_state;
+mod validate_chainspec;
+mod verify_keys;
 
 use std::process;
 
@@ -29,9 +31,13 @@ use casper_node::rpcs::{
 
 use account_address::GenerateAccountHash as AccountAddress;
 use command::{ClientCommand, Success};
-use deploy::{ListDeploys, MakeDeploy, MakeTransfer, SendDeploy, SignDeploy, Transfer};
+use deploy::{
+    ListDeploys, MakeDeploy, MakeTransfer, PrintDeploy, SendDeploy, SignDeploy, Transfer,
+};
 use generate_completion::GenerateCompletion;
 use keygen::Keygen;
+use validate_chainspec::ValidateChainspec;
+use verify_keys::VerifyKeys;
 
 const APP_NAME: &str = "Casper client";
 
@@ -40,6 +46,7 @@ enum DisplayOrder {
     PutDeploy,
     MakeDeploy,
     SignDeploy,
+    PrintDeploy,
     SendDeploy,
     Transfer,
     MakeTransfer,
@@ -54,10 +61,12 @@ enum DisplayOrder {
     GetEraInfo,
     GetAuctionInfo,
     Keygen,
+    VerifyKeys,
     GenerateCompletion,
     GetRpcs,
     AccountAddress,
     GetDictionaryItem,
+    ValidateChainspec,
 }
 
 fn cli<'a, 'b>() -> App<'a, 'b> {
@@ -67,6 +76,7 @@ fn cli<'a, 'b>() -> App<'a, 'b> {
         .subcommand(PutDeploy::build(DisplayOrder::PutDeploy as usize))
         .subcommand(MakeDeploy::build(DisplayOrder::MakeDeploy as usize))
         .subcommand(SignDeploy::build(DisplayOrder::SignDeploy as usize))
+        .subcommand(PrintDeploy::build(DisplayOrder::PrintDeploy as usize))
         .subcommand(SendDeploy::build(DisplayOrder::SendDeploy as usize))
         .subcommand(Transfer::build(DisplayOrder::Transfer as usize))
         .subcommand(MakeTransfer::build(DisplayOrder::MakeTransfer as usize))
@@ -87,6 +97,7 @@ fn cli<'a, 'b>() -> App<'a, 'b> {
         ))
         .subcommand(GetAuctionInfo::build(DisplayOrder::GetAuctionInfo as usize))
         .subcommand(Keygen::build(DisplayOrder::Keygen as usize))
+        .subcommand(VerifyKeys::build(DisplayOrder::VerifyKeys as usize))
         .subcommand(GenerateCompletion::build(
             DisplayOrder::GenerateCompletion as usize,
         ))
@@ -95,6 +106,9 @@ fn cli<'a, 'b>() -> App<'a, 'b> {
         .subcommand(GetDictionaryItem::build(
             DisplayOrder::GetDictionaryItem as usize,
         ))
+        .subcommand(ValidateChainspec::build(
+            DisplayOrder::ValidateChainspec as usize,
+        ))
 }
 
 #[tokio::main]
@@ -104,6 +118,7 @@ async fn main() {
         (PutDeploy::NAME, Some(matches)) => (PutDeploy::run(matches), matches),
         (MakeDeploy::NAME, Some(matches)) => (MakeDeploy::run(matches), matches),
         (SignDeploy::NAME, Some(matches)) => (SignDeploy::run(matches), matches),
+        (PrintDeploy::NAME, Some(matches)) => (PrintDeploy::run(matches), matches),
         (SendDeploy::NAME, Some(matches)) => (SendDeploy::run(matches), matches),
         (Transfer::NAME, Some(matches)) => (Transfer::run(matches), matches),
         (MakeTransfer::NAME, Some(matches)) => (MakeTransfer::run(matches), matches),
@@ -120,10 +135,12 @@ async fn main() {
         }
         (GetAuctionInfo::NAME, Some(matches)) => (GetAuctionInfo::run(matches), matches),
         (Keygen::NAME, Some(matches)) => (Keygen::run(matches), matches),
+        (VerifyKeys::NAME, Some(matches)) => (VerifyKeys::run(matches), matches),
         (GenerateCompletion::NAME, Some(matches)) => (GenerateCompletion::run(matches), matches),
         (ListRpcs::NAME, Some(matches)) => (ListRpcs::run(matches), matches),
         (AccountAddress::NAME, Some(matches)) => (AccountAddress::run(matches), matches),
         (GetDictionaryItem::NAME, Some(matches)) => (GetDictionaryItem::run(matches), matches),
+        (ValidateChainspec::NAME, Some(matches)) => (ValidateChainspec::run(matches), matches),
         _ => {
             let _ = cli().print_long_help();
             println!();
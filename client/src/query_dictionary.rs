@@ -204,6 +204,8 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetDictionaryItem {
             .arg(common::node_address::arg(
                 DisplayOrder::NodeAddress as usize,
             ))
+            .arg(common::max_retries::arg())
+            .arg(common::retry_delay::arg())
             .arg(common::rpc_id::arg(DisplayOrder::RpcId as usize))
             .arg(common::state_root_hash::arg(
                 DisplayOrder::StateRootHash as usize,
@@ -232,6 +234,7 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetDictionaryItem {
         let maybe_rpc_id = common::rpc_id::get(matches);
         let node_address = common::node_address::get(matches);
         let verbosity_level = common::verbose::get(matches);
+        let retry_policy = common::retry_policy(matches)?;
         let state_root_hash = common::state_root_hash::get(matches);
 
         let account_hash = account_hash::get(matches)?;
@@ -269,6 +272,7 @@ impl<'a, 'b> ClientCommand<'a, 'b> for GetDictionaryItem {
             maybe_rpc_id,
             node_address,
             verbosity_level,
+            retry_policy,
             state_root_hash,
             dictionary_query_str_params,
         )
@@ -0,0 +1,57 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+use casper_client::Error;
+
+use crate::{command::ClientCommand, common, Success};
+
+/// This struct defines the order in which the args are shown for this subcommand's help message.
+enum DisplayOrder {
+    Path,
+}
+
+/// Handles providing the arg for and retrieval of the chainspec path.
+mod path {
+    use super::*;
+
+    const ARG_NAME: &str = "path";
+    const ARG_VALUE_NAME: &str = common::ARG_PATH;
+    const ARG_HELP: &str =
+        "Path to the chainspec file, or to the directory containing it. Any accounts or global \
+        state update files are expected alongside the chainspec file";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .required(true)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::Path as usize)
+    }
+
+    pub(super) fn get<'a>(matches: &'a ArgMatches) -> &'a str {
+        matches
+            .value_of(ARG_NAME)
+            .unwrap_or_else(|| panic!("should have {} arg", ARG_NAME))
+    }
+}
+
+pub struct ValidateChainspec;
+
+impl<'a, 'b> ClientCommand<'a, 'b> for ValidateChainspec {
+    const NAME: &'static str = "validate-chainspec";
+    const ABOUT: &'static str =
+        "Loads a chainspec the same way the node does, checks that the values set in it make \
+        sense and prints its hash";
+
+    fn build(display_order: usize) -> App<'a, 'b> {
+        SubCommand::with_name(Self::NAME)
+            .about(Self::ABOUT)
+            .display_order(display_order)
+            .arg(path::arg())
+    }
+
+    fn run(matches: &ArgMatches<'_>) -> Result<Success, Error> {
+        let path = path::get(matches);
+        casper_client::validate_chainspec(path)
+            .map(|hash| Success::Output(format!("Chainspec is valid, hash: {:x}", hash)))
+    }
+}
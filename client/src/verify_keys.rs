@@ -0,0 +1,99 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+use once_cell::sync::Lazy;
+
+use casper_client::{
+    keygen::{self, PUBLIC_KEY_HEX, PUBLIC_KEY_PEM, SECRET_KEY_PEM},
+    Error,
+};
+
+use crate::{command::ClientCommand, common, Success};
+
+static MORE_ABOUT: Lazy<String> = Lazy::new(|| {
+    format!(
+        "{}. Reads \"{}\" and checks that the public key derived from it matches the contents of \
+        \"{}\" and \"{}\". Fails naming the first mismatched file",
+        VerifyKeys::ABOUT,
+        SECRET_KEY_PEM,
+        PUBLIC_KEY_PEM,
+        PUBLIC_KEY_HEX
+    )
+});
+
+/// This struct defines the order in which the args are shown for this subcommand's help message.
+enum DisplayOrder {
+    KeyDir,
+    PassphraseFile,
+}
+
+/// Handles providing the arg for and retrieval of the key directory.
+mod key_dir {
+    use super::*;
+
+    const ARG_NAME: &str = "key-dir";
+    const ARG_VALUE_NAME: &str = common::ARG_PATH;
+    const ARG_HELP: &str =
+        "Path to the directory containing the key files. If not set, the current working \
+        directory will be used";
+
+    pub(super) fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .required(false)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::KeyDir as usize)
+    }
+
+    pub(super) fn get(matches: &ArgMatches) -> String {
+        matches.value_of(ARG_NAME).unwrap_or(".").to_string()
+    }
+}
+
+/// Handles providing the arg for and retrieval of the optional passphrase file.
+mod passphrase_file {
+    use super::*;
+
+    const ARG_NAME: &str = "passphrase-file";
+    const ARG_VALUE_NAME: &str = common::ARG_PATH;
+    const ARG_HELP: &str =
+        "Path to a file containing the passphrase used to encrypt the secret key. Required if the \
+        secret key was generated with a passphrase";
+
+    pub fn arg() -> Arg<'static, 'static> {
+        Arg::with_name(ARG_NAME)
+            .long(ARG_NAME)
+            .required(false)
+            .value_name(ARG_VALUE_NAME)
+            .help(ARG_HELP)
+            .display_order(DisplayOrder::PassphraseFile as usize)
+    }
+
+    pub fn get<'a>(matches: &'a ArgMatches) -> Option<&'a str> {
+        matches.value_of(ARG_NAME)
+    }
+}
+
+pub struct VerifyKeys {}
+
+impl<'a, 'b> ClientCommand<'a, 'b> for VerifyKeys {
+    const NAME: &'static str = "verify-keys";
+    const ABOUT: &'static str = "Verifies that the account key files in the given directory match";
+
+    fn build(display_order: usize) -> App<'a, 'b> {
+        SubCommand::with_name(Self::NAME)
+            .about(Self::ABOUT)
+            .long_about(MORE_ABOUT.as_str())
+            .display_order(display_order)
+            .arg(key_dir::arg())
+            .arg(passphrase_file::arg())
+    }
+
+    fn run(matches: &ArgMatches<'_>) -> Result<Success, Error> {
+        let key_dir = key_dir::get(matches);
+        let maybe_passphrase = passphrase_file::get(matches)
+            .map(keygen::read_passphrase_file)
+            .transpose()?;
+
+        keygen::verify_files(&key_dir, maybe_passphrase.as_deref())
+            .map(|_| Success::Output(format!("Key files in {} are consistent", key_dir)))
+    }
+}
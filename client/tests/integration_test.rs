@@ -1,4 +1,4 @@
-use std::{convert::Infallible, fs, net::SocketAddr, sync::Arc, time::Duration};
+use std::{convert::Infallible, fs, net::SocketAddr, path::Path, sync::Arc, time::Duration};
 
 use futures::{channel::oneshot, future};
 use hyper::{Body, Response, Server};
@@ -9,10 +9,11 @@ use tower::builder::ServiceBuilder;
 use warp::{Filter, Rejection};
 use warp_json_rpc::Builder;
 
-use casper_node::crypto::Error as CryptoError;
+use casper_node::crypto::{AsymmetricKeyExt, Error as CryptoError, SecretKeyExt};
+use casper_types::{AsymmetricType, PublicKey, SecretKey};
 use hex::FromHexError;
 
-use casper_client::{DeployStrParams, Error, PaymentStrParams, SessionStrParams};
+use casper_client::{DeployStrParams, Error, PaymentStrParams, RetryPolicy, SessionStrParams};
 use casper_node::rpcs::{
     account::{PutDeploy, PutDeployParams},
     chain::{GetStateRootHash, GetStateRootHashParams},
@@ -120,33 +121,54 @@ impl MockServerHandle {
     }
 
     fn get_balance(&self, state_root_hash: &str, purse_uref: &str) -> Result<(), ErrWrapper> {
-        casper_client::get_balance("1", &self.url(), 0, state_root_hash, purse_uref)
-            .map(|_| ())
-            .map_err(ErrWrapper)
+        casper_client::get_balance(
+            "1",
+            &self.url(),
+            0,
+            RetryPolicy::default(),
+            state_root_hash,
+            purse_uref,
+        )
+        .map(|_| ())
+        .map_err(ErrWrapper)
     }
 
     fn get_deploy(&self, deploy_hash: &str) -> Result<(), ErrWrapper> {
-        casper_client::get_deploy("1", &self.url(), 0, deploy_hash)
+        casper_client::get_deploy("1", &self.url(), 0, RetryPolicy::default(), deploy_hash)
             .map(|_| ())
             .map_err(ErrWrapper)
     }
 
     fn get_state_root_hash(&self, maybe_block_id: &str) -> Result<(), ErrWrapper> {
-        casper_client::get_state_root_hash("1", &self.url(), 0, maybe_block_id)
-            .map(|_| ())
-            .map_err(ErrWrapper)
+        casper_client::get_state_root_hash(
+            "1",
+            &self.url(),
+            0,
+            RetryPolicy::default(),
+            maybe_block_id,
+        )
+        .map(|_| ())
+        .map_err(ErrWrapper)
     }
 
     fn get_block(&self, maybe_block_id: &str) -> Result<(), ErrWrapper> {
-        casper_client::get_block("1", &self.url(), 0, maybe_block_id)
+        casper_client::get_block("1", &self.url(), 0, RetryPolicy::default(), maybe_block_id)
             .map(|_| ())
             .map_err(ErrWrapper)
     }
 
     fn get_item(&self, state_root_hash: &str, key: &str, path: &str) -> Result<(), ErrWrapper> {
-        casper_client::get_item("1", &self.url(), 0, state_root_hash, key, path)
-            .map(|_| ())
-            .map_err(ErrWrapper)
+        casper_client::get_item(
+            "1",
+            &self.url(),
+            0,
+            RetryPolicy::default(),
+            state_root_hash,
+            key,
+            path,
+        )
+        .map(|_| ())
+        .map_err(ErrWrapper)
     }
 
     fn transfer(
@@ -160,6 +182,7 @@ impl MockServerHandle {
             "1",
             &self.url(),
             0,
+            RetryPolicy::default(),
             amount,
             maybe_target_account,
             "2",
@@ -180,6 +203,7 @@ impl MockServerHandle {
             "1",
             &self.url(),
             0,
+            RetryPolicy::default(),
             deploy_params,
             session_params,
             payment_params,
@@ -189,13 +213,13 @@ impl MockServerHandle {
     }
 
     fn send_deploy_file(&self, input_path: &str) -> Result<(), ErrWrapper> {
-        casper_client::send_deploy_file("1", &self.url(), 0, input_path)
+        casper_client::send_deploy_file("1", &self.url(), 0, RetryPolicy::default(), input_path)
             .map(|_| ())
             .map_err(ErrWrapper)
     }
 
     fn get_auction_info(&self, maybe_block_id: &str) -> Result<(), ErrWrapper> {
-        casper_client::get_auction_info("1", &self.url(), 0, maybe_block_id)
+        casper_client::get_auction_info("1", &self.url(), 0, RetryPolicy::default(), maybe_block_id)
             .map(|_| ())
             .map_err(ErrWrapper)
     }
@@ -956,6 +980,326 @@ mod keygen_generate_files {
         assert_eq!(result, Ok(()));
     }
 
+    #[test]
+    fn should_succeed_for_valid_args_secp256r1() {
+        let temp_dir = TempDir::new()
+            .unwrap_or_else(|err| panic!("Failed to create a temp dir with error: {}", err));
+        let path = temp_dir.path().join("test-keygen-secp256r1");
+        let result = casper_client::keygen::generate_files(
+            path.to_str().unwrap(),
+            casper_client::keygen::SECP256R1,
+            true,
+        )
+        .map_err(ErrWrapper);
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn should_roundtrip_generated_keys() {
+        for algorithm in &[
+            casper_client::keygen::ED25519,
+            casper_client::keygen::SECP256K1,
+            casper_client::keygen::SECP256R1,
+        ] {
+            let temp_dir = TempDir::new()
+                .unwrap_or_else(|err| panic!("Failed to create a temp dir with error: {}", err));
+            let path = temp_dir
+                .path()
+                .join(format!("test-keygen-roundtrip-{}", algorithm));
+            casper_client::keygen::generate_files(path.to_str().unwrap(), algorithm, true)
+                .unwrap_or_else(|err| panic!("Failed to generate {} keys: {}", algorithm, err));
+
+            let secret_key =
+                SecretKey::from_file(path.join(casper_client::keygen::SECRET_KEY_PEM)).unwrap();
+            let public_key =
+                PublicKey::from_file(path.join(casper_client::keygen::PUBLIC_KEY_PEM)).unwrap();
+            assert_eq!(PublicKey::from(&secret_key), public_key);
+
+            let public_key_hex =
+                fs::read_to_string(path.join(casper_client::keygen::PUBLIC_KEY_HEX)).unwrap();
+            assert_eq!(public_key.to_hex(), public_key_hex);
+        }
+    }
+
+    #[test]
+    fn should_write_json_bundle() {
+        let temp_dir = TempDir::new()
+            .unwrap_or_else(|err| panic!("Failed to create a temp dir with error: {}", err));
+        let path = temp_dir.path().join("test-keygen-json");
+        let result = casper_client::keygen::generate_json_file(
+            path.to_str().unwrap(),
+            casper_client::keygen::ED25519,
+            false,
+        )
+        .map_err(ErrWrapper);
+        assert_eq!(result, Ok(()));
+
+        for file in casper_client::keygen::FILES.iter() {
+            assert!(!path.join(file).exists());
+        }
+
+        let contents = fs::read_to_string(path.join(casper_client::keygen::KEYS_JSON)).unwrap();
+        let keys: casper_client::keygen::KeysJson = serde_json::from_str(&contents).unwrap();
+        let secret_key = SecretKey::from_pem(&keys.secret_key_pem).unwrap();
+        let public_key = PublicKey::from(&secret_key);
+        assert_eq!(keys.public_key_hex, public_key.to_hex());
+        assert_eq!(
+            PublicKey::from_pem(&keys.public_key_pem).unwrap(),
+            public_key
+        );
+        assert_eq!(
+            keys.account_hash,
+            public_key.to_account_hash().to_formatted_string()
+        );
+
+        let result = casper_client::keygen::generate_json_file(
+            path.to_str().unwrap(),
+            casper_client::keygen::ED25519,
+            false,
+        )
+        .map_err(ErrWrapper);
+        assert_eq!(
+            result,
+            Err(Error::FileAlreadyExists(path.join(casper_client::keygen::KEYS_JSON)).into())
+        );
+    }
+
+    #[test]
+    fn should_derive_same_secret_key_from_same_seed() {
+        const SEED: &str = "0707070707070707070707070707070707070707070707070707070707070707";
+
+        for algorithm in &[
+            casper_client::keygen::ED25519,
+            casper_client::keygen::SECP256K1,
+            casper_client::keygen::SECP256R1,
+        ] {
+            let temp_dir = TempDir::new()
+                .unwrap_or_else(|err| panic!("Failed to create a temp dir with error: {}", err));
+            let first_path = temp_dir.path().join("first");
+            let second_path = temp_dir.path().join("second");
+
+            for path in &[&first_path, &second_path] {
+                let secret_key =
+                    casper_client::keygen::secret_key_from_seed(algorithm, SEED).unwrap();
+                casper_client::keygen::write_files_with_passphrase(
+                    path.to_str().unwrap(),
+                    &secret_key,
+                    None,
+                    false,
+                )
+                .unwrap();
+            }
+
+            let first_pem =
+                fs::read_to_string(first_path.join(casper_client::keygen::SECRET_KEY_PEM)).unwrap();
+            let second_pem =
+                fs::read_to_string(second_path.join(casper_client::keygen::SECRET_KEY_PEM))
+                    .unwrap();
+            assert_eq!(first_pem, second_pem);
+        }
+    }
+
+    #[test]
+    fn should_fail_for_invalid_seed() {
+        let result =
+            casper_client::keygen::secret_key_from_seed(casper_client::keygen::ED25519, "07070707")
+                .map(|_| ())
+                .map_err(ErrWrapper);
+        assert_eq!(
+            result,
+            Err(Error::InvalidArgument(
+                "seed",
+                "expected 32 bytes (64 hex characters), got 4 bytes".to_string()
+            )
+            .into())
+        );
+
+        let result =
+            casper_client::keygen::secret_key_from_seed(casper_client::keygen::ED25519, "not hex")
+                .map(|_| ());
+        assert!(matches!(result, Err(Error::InvalidArgument("seed", _))));
+    }
+
+    #[test]
+    fn should_import_secret_key_from_hex() {
+        const SECRET_KEY_HEX: &str =
+            "0101010101010101010101010101010101010101010101010101010101010101";
+
+        for algorithm in &[
+            casper_client::keygen::ED25519,
+            casper_client::keygen::SECP256K1,
+            casper_client::keygen::SECP256R1,
+        ] {
+            let temp_dir = TempDir::new()
+                .unwrap_or_else(|err| panic!("Failed to create a temp dir with error: {}", err));
+            let path = temp_dir.path();
+
+            let secret_key =
+                casper_client::keygen::secret_key_from_hex(algorithm, SECRET_KEY_HEX).unwrap();
+            casper_client::keygen::write_files_with_passphrase(
+                path.to_str().unwrap(),
+                &secret_key,
+                None,
+                false,
+            )
+            .unwrap();
+
+            let secret_key_from_file =
+                SecretKey::from_file(path.join(casper_client::keygen::SECRET_KEY_PEM)).unwrap();
+            let public_key_hex =
+                fs::read_to_string(path.join(casper_client::keygen::PUBLIC_KEY_HEX)).unwrap();
+            assert_eq!(
+                PublicKey::from(&secret_key_from_file).to_hex(),
+                public_key_hex
+            );
+            assert_eq!(PublicKey::from(&secret_key).to_hex(), public_key_hex);
+        }
+    }
+
+    #[test]
+    fn should_fail_for_invalid_secret_key_hex() {
+        let result =
+            casper_client::keygen::secret_key_from_hex(casper_client::keygen::ED25519, "0101")
+                .map(|_| ())
+                .map_err(ErrWrapper);
+        assert_eq!(
+            result,
+            Err(Error::InvalidArgument(
+                "secret_key_hex",
+                "invalid Ed25519 secret key length.  Expected 32 bytes (64 hex characters), got 2 \
+                bytes"
+                    .to_string()
+            )
+            .into())
+        );
+
+        let result =
+            casper_client::keygen::secret_key_from_hex(casper_client::keygen::SECP256K1, "not hex")
+                .map(|_| ());
+        assert!(matches!(
+            result,
+            Err(Error::InvalidArgument("secret_key_hex", _))
+        ));
+    }
+
+    #[test]
+    fn should_write_numbered_key_sets() {
+        const SEED: &str = "0707070707070707070707070707070707070707070707070707070707070707";
+        const COUNT: usize = 3;
+
+        let temp_dir = TempDir::new()
+            .unwrap_or_else(|err| panic!("Failed to create a temp dir with error: {}", err));
+        let path = temp_dir.path();
+
+        let secret_keys = casper_client::keygen::generate_secret_keys(
+            casper_client::keygen::ED25519,
+            Some(SEED),
+            COUNT,
+        )
+        .unwrap();
+        assert_eq!(secret_keys.len(), COUNT);
+        casper_client::keygen::write_numbered_files(
+            path.to_str().unwrap(),
+            &secret_keys,
+            None,
+            false,
+        )
+        .unwrap();
+
+        let mut public_keys = vec![];
+        for index in 0..COUNT {
+            let subdir = path.join(index.to_string());
+            for file in &casper_client::keygen::FILES {
+                assert!(subdir.join(file).exists());
+            }
+            casper_client::keygen::verify_files(subdir.to_str().unwrap(), None).unwrap();
+            let secret_key =
+                SecretKey::from_file(subdir.join(casper_client::keygen::SECRET_KEY_PEM)).unwrap();
+            public_keys.push(PublicKey::from(&secret_key));
+        }
+        assert!(!path.join(COUNT.to_string()).exists());
+        assert_ne!(public_keys[0], public_keys[1]);
+        assert_ne!(public_keys[1], public_keys[2]);
+
+        let regenerated_public_keys = casper_client::keygen::generate_secret_keys(
+            casper_client::keygen::ED25519,
+            Some(SEED),
+            COUNT,
+        )
+        .unwrap()
+        .iter()
+        .map(PublicKey::from)
+        .collect::<Vec<_>>();
+        assert_eq!(regenerated_public_keys, public_keys);
+
+        let result = casper_client::keygen::write_numbered_files(
+            path.to_str().unwrap(),
+            &secret_keys,
+            None,
+            false,
+        )
+        .map_err(ErrWrapper);
+        assert_eq!(
+            result,
+            Err(Error::FileAlreadyExists(
+                path.join("0").join(casper_client::keygen::SECRET_KEY_PEM)
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn should_summarize_account_without_writing_files() {
+        let secret_key = casper_client::keygen::generate_secret_key(casper_client::keygen::ED25519)
+            .unwrap_or_else(|err| panic!("Failed to generate secret key with error: {}", err));
+        let public_key = PublicKey::from(&secret_key);
+        let account_hash = public_key.to_account_hash();
+
+        let summary = casper_client::keygen::account_summary(&secret_key);
+        let lines = summary.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                format!("account_id_hex: {}", hex::encode(account_hash.as_bytes())),
+                format!(
+                    "account_id_base64: {}",
+                    base64::encode(account_hash.as_bytes())
+                ),
+                format!("public_key_hex: {}", public_key.to_hex()),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_write_encrypted_secret_key_with_passphrase() {
+        let temp_dir = TempDir::new()
+            .unwrap_or_else(|err| panic!("Failed to create a temp dir with error: {}", err));
+        let passphrase_path = temp_dir.path().join("passphrase");
+        fs::write(&passphrase_path, "correct horse battery staple\n").unwrap();
+        let passphrase =
+            casper_client::keygen::read_passphrase_file(passphrase_path.to_str().unwrap()).unwrap();
+        assert_eq!(passphrase, b"correct horse battery staple".to_vec());
+
+        let path = temp_dir.path().join("test-keygen-encrypted");
+        let secret_key =
+            casper_client::keygen::generate_secret_key(casper_client::keygen::SECP256K1).unwrap();
+        casper_client::keygen::write_files_with_passphrase(
+            path.to_str().unwrap(),
+            &secret_key,
+            Some(passphrase.as_slice()),
+            false,
+        )
+        .unwrap();
+
+        let secret_key_path = path.join(casper_client::keygen::SECRET_KEY_PEM);
+        assert!(SecretKey::from_file(&secret_key_path).is_err());
+        let decrypted = SecretKey::from_encrypted_file(&secret_key_path, &passphrase).unwrap();
+        let public_key =
+            PublicKey::from_file(path.join(casper_client::keygen::PUBLIC_KEY_PEM)).unwrap();
+        assert_eq!(PublicKey::from(&decrypted), public_key);
+        assert_eq!(PublicKey::from(&secret_key), public_key);
+    }
+
     #[test]
     fn should_force_overwrite_when_set() {
         let temp_dir = TempDir::new()
@@ -1021,6 +1365,143 @@ mod keygen_generate_files {
     }
 }
 
+mod keygen_verify_files {
+    use super::*;
+
+    fn generate_key_dir(temp_dir: &TempDir) -> String {
+        let path = temp_dir.path().join("test-verify-keys");
+        let path = path.to_str().unwrap().to_string();
+        casper_client::keygen::generate_files(&path, casper_client::keygen::ED25519, false)
+            .unwrap_or_else(|err| panic!("Failed to generate keys with error: {}", err));
+        path
+    }
+
+    #[test]
+    fn should_succeed_for_consistent_files() {
+        let temp_dir = TempDir::new()
+            .unwrap_or_else(|err| panic!("Failed to create a temp dir with error: {}", err));
+        let path = generate_key_dir(&temp_dir);
+        let result = casper_client::keygen::verify_files(&path, None).map_err(ErrWrapper);
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn should_fail_naming_mismatched_public_key_pem() {
+        let temp_dir = TempDir::new()
+            .unwrap_or_else(|err| panic!("Failed to create a temp dir with error: {}", err));
+        let path = generate_key_dir(&temp_dir);
+        let other_path = temp_dir.path().join("other");
+        casper_client::keygen::generate_files(
+            other_path.to_str().unwrap(),
+            casper_client::keygen::ED25519,
+            false,
+        )
+        .unwrap();
+        fs::copy(
+            other_path.join(casper_client::keygen::PUBLIC_KEY_PEM),
+            Path::new(&path).join(casper_client::keygen::PUBLIC_KEY_PEM),
+        )
+        .unwrap();
+
+        let result = casper_client::keygen::verify_files(&path, None).map_err(ErrWrapper);
+        assert_eq!(
+            result,
+            Err(Error::KeyFileMismatch(
+                Path::new(&path).join(casper_client::keygen::PUBLIC_KEY_PEM)
+            )
+            .into())
+        );
+    }
+
+    #[test]
+    fn should_fail_naming_mismatched_public_key_hex() {
+        let temp_dir = TempDir::new()
+            .unwrap_or_else(|err| panic!("Failed to create a temp dir with error: {}", err));
+        let path = generate_key_dir(&temp_dir);
+        let public_key_hex_path = Path::new(&path).join(casper_client::keygen::PUBLIC_KEY_HEX);
+        fs::write(&public_key_hex_path, "01").unwrap();
+
+        let result = casper_client::keygen::verify_files(&path, None).map_err(ErrWrapper);
+        assert_eq!(
+            result,
+            Err(Error::KeyFileMismatch(public_key_hex_path).into())
+        );
+    }
+}
+
+mod account_address {
+    use super::*;
+
+    const ED25519_PUBLIC_KEY_HEX: &str =
+        "01f60bce2bb1059c41910eac1e7ee6c3ef4c8fcc63a901eb9603c1524cadfb0c18";
+    const ED25519_PUBLIC_KEY_BASE64: &str = "AfYLziuxBZxBkQ6sHn7mw+9Mj8xjqQHrlgPBUkyt+wwY";
+    const ED25519_ACCOUNT_ADDRESS: &str =
+        "account_id_hex: 1ace33e66142d5a0679ba5507ef75b9c09888d1567e86100d1db535fa819a962\n\
+        account_id_base64: Gs4z5mFC1aBnm6VQfvdbnAmIjRVn6GEA0dtTX6gZqWI=";
+
+    const SECP256K1_PUBLIC_KEY_HEX: &str =
+        "020279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    const SECP256K1_PUBLIC_KEY_BASE64: &str = "AgJ5vmZ++dy7rFWgYpXOhwsHApv82y3OKNlZ8oFbFvgXmA==";
+    const SECP256K1_ACCOUNT_ADDRESS: &str =
+        "account_id_hex: 86937931937ee0281e50806b94f8d4993e8869b0689dfa0a21d2946ab677183c\n\
+        account_id_base64: hpN5MZN+4CgeUIBrlPjUmT6IabBonfoKIdKUarZ3GDw=";
+
+    fn account_address(formatted_public_key: &str) -> String {
+        let public_key = casper_client::keygen::parse_public_key(formatted_public_key)
+            .unwrap_or_else(|err| panic!("Failed to parse public key with error: {}", err));
+        casper_client::keygen::account_address(&public_key)
+    }
+
+    #[test]
+    fn should_generate_account_address_for_ed25519_key() {
+        assert_eq!(
+            account_address(ED25519_PUBLIC_KEY_HEX),
+            ED25519_ACCOUNT_ADDRESS
+        );
+        assert_eq!(
+            account_address(ED25519_PUBLIC_KEY_BASE64),
+            ED25519_ACCOUNT_ADDRESS
+        );
+    }
+
+    #[test]
+    fn should_generate_account_address_for_secp256k1_key() {
+        assert_eq!(
+            account_address(SECP256K1_PUBLIC_KEY_HEX),
+            SECP256K1_ACCOUNT_ADDRESS
+        );
+        assert_eq!(
+            account_address(SECP256K1_PUBLIC_KEY_BASE64),
+            SECP256K1_ACCOUNT_ADDRESS
+        );
+    }
+
+    #[test]
+    fn should_fail_for_malformed_public_key() {
+        // Truncated key.
+        let result = casper_client::keygen::parse_public_key(&ED25519_PUBLIC_KEY_HEX[..64]);
+        assert!(matches!(
+            result,
+            Err(Error::InvalidArgument("public_key", _))
+        ));
+
+        // Unknown algorithm tag.
+        let result =
+            casper_client::keygen::parse_public_key(&format!("05{}", &ED25519_PUBLIC_KEY_HEX[2..]));
+        assert!(matches!(
+            result,
+            Err(Error::InvalidArgument("public_key", _))
+        ));
+
+        // The system key, which can only be given base64-encoded, has no account.
+        let result = casper_client::keygen::parse_public_key("AA==");
+        assert!(matches!(
+            result,
+            Err(Error::InvalidArgument("public_key", _))
+        ));
+    }
+}
+
 mod put_deploy {
     use super::*;
 
@@ -1107,3 +1588,212 @@ mod transfer {
         );
     }
 }
+
+mod retry {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Instant,
+    };
+
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        StatusCode,
+    };
+    use jsonrpc_lite::JsonRpc;
+
+    use super::*;
+
+    const SUCCESS_RESPONSE: &str = r#"{"jsonrpc":"2.0","id":1,"result":{"api_version":"1.0.0"}}"#;
+
+    /// A retry policy retrying every call up to `max_retries` times with a short delay.
+    fn fast_retry_policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_query_retries: max_retries,
+            max_deploy_retries: max_retries,
+            retry_delay: Duration::from_millis(10),
+        }
+    }
+
+    /// Spawns a server responding with the HTTP error `status` to the first `failures` requests
+    /// and with a successful JSON-RPC response to all subsequent ones.
+    ///
+    /// Returns the URL of the server, the number of requests received and the sender shutting it
+    /// down.
+    fn spawn_flaky_server(
+        failures: usize,
+        status: StatusCode,
+    ) -> (String, Arc<AtomicUsize>, oneshot::Sender<()>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let make_svc = make_service_fn(move |_| {
+            let counter = Arc::clone(&counter);
+            future::ok::<_, Infallible>(service_fn(move |_request| {
+                let response = if counter.fetch_add(1, Ordering::SeqCst) < failures {
+                    Response::builder().status(status).body(Body::empty())
+                } else {
+                    Response::builder()
+                        .header("content-type", "application/json")
+                        .body(Body::from(SUCCESS_RESPONSE))
+                };
+                future::ready(response)
+            }))
+        });
+
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_svc);
+        let url = format!("http://{}", server.local_addr());
+        let (shutdown, shutdown_receiver) = oneshot::channel::<()>();
+        tokio::spawn(server.with_graceful_shutdown(async {
+            shutdown_receiver.await.ok();
+        }));
+        (url, requests, shutdown)
+    }
+
+    fn put_deploy(url: String, retry_policy: RetryPolicy) -> JoinHandle<Result<JsonRpc, Error>> {
+        task::spawn_blocking(move || {
+            casper_client::put_deploy(
+                "1",
+                &url,
+                0,
+                retry_policy,
+                deploy_params::test_data_valid(),
+                session_params::test_data_with_package_hash(),
+                payment_params::test_data_with_name(),
+            )
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_retry_query_until_it_succeeds() {
+        let (url, requests, _shutdown) = spawn_flaky_server(2, StatusCode::SERVICE_UNAVAILABLE);
+
+        let result = task::spawn_blocking(move || {
+            casper_client::get_state_root_hash("1", &url, 0, fast_retry_policy(3), "")
+        })
+        .await
+        .unwrap();
+
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_not_retry_query_rejected_by_client_error_status() {
+        let (url, requests, _shutdown) = spawn_flaky_server(1, StatusCode::BAD_REQUEST);
+
+        let result = task::spawn_blocking(move || {
+            casper_client::get_state_root_hash("1", &url, 0, fast_retry_policy(3), "")
+        })
+        .await
+        .unwrap();
+
+        assert!(matches!(result, Err(Error::FailedSending(_))));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_retry_query_after_connection_error() {
+        // Nothing listens on the port of a server which has been shut down.
+        let (url, requests, shutdown) = spawn_flaky_server(0, StatusCode::SERVICE_UNAVAILABLE);
+        shutdown.send(()).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let start = Instant::now();
+        let result = task::spawn_blocking(move || {
+            casper_client::get_state_root_hash("1", &url, 0, fast_retry_policy(2), "")
+        })
+        .await
+        .unwrap();
+
+        // Both retries should have been made, after delays of 10 and 20 ms.
+        assert!(matches!(result, Err(Error::FailedToGetResponse(_))));
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn should_only_retry_deploy_submission_if_configured() {
+        // Deploy submissions aren't retried by default.
+        let (url, requests, _shutdown) = spawn_flaky_server(1, StatusCode::SERVICE_UNAVAILABLE);
+        let result = put_deploy(url, RetryPolicy::default()).await.unwrap();
+        assert!(matches!(result, Err(Error::FailedSending(_))));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // If configured, the failed submission is followed by a query for the deploy, which the
+        // server knows, so it isn't submitted again.
+        let (url, requests, _shutdown) = spawn_flaky_server(1, StatusCode::SERVICE_UNAVAILABLE);
+        let result = put_deploy(url, fast_retry_policy(3)).await.unwrap();
+        let response = result.unwrap();
+        assert!(response.get_result().unwrap().get("deploy_hash").is_some());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+}
+
+mod validate_chainspec {
+    use casper_node::{
+        types::{chainspec::ValidationError, Chainspec},
+        utils::Loadable,
+    };
+
+    use super::*;
+
+    const VALID_CHAINSPEC_DIR: &str = "../resources/test/valid/0_9_0";
+
+    /// Copies the valid chainspec and its accounts to a temp dir, replacing `from` with `to` in the
+    /// chainspec file.
+    fn write_modified_chainspec(from: &str, to: &str) -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let chainspec =
+            fs::read_to_string(Path::new(VALID_CHAINSPEC_DIR).join("chainspec.toml")).unwrap();
+        assert!(chainspec.contains(from));
+        fs::write(
+            temp_dir.path().join("chainspec.toml"),
+            chainspec.replace(from, to),
+        )
+        .unwrap();
+        fs::copy(
+            Path::new(VALID_CHAINSPEC_DIR).join("accounts.toml"),
+            temp_dir.path().join("accounts.toml"),
+        )
+        .unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn should_validate_chainspec_with_stable_hash() {
+        let hash = casper_client::validate_chainspec(VALID_CHAINSPEC_DIR).unwrap();
+        let expected_hash = Chainspec::from_path(VALID_CHAINSPEC_DIR).unwrap().hash();
+        assert_eq!(hash, expected_hash);
+
+        // Passing the chainspec file itself yields the same hash.
+        let chainspec_path = Path::new(VALID_CHAINSPEC_DIR).join("chainspec.toml");
+        assert_eq!(
+            casper_client::validate_chainspec(chainspec_path.to_str().unwrap()).unwrap(),
+            hash
+        );
+    }
+
+    #[test]
+    fn should_fail_to_validate_chainspec_with_invalid_finality_threshold() {
+        let temp_dir = write_modified_chainspec(
+            "finality_threshold_fraction = [2, 25]",
+            "finality_threshold_fraction = [1, 1]",
+        );
+        match casper_client::validate_chainspec(temp_dir.path().to_str().unwrap()) {
+            Err(Error::InvalidChainspec(ValidationError::FinalityThresholdFractionOutOfRange(
+                _,
+            ))) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn should_fail_to_validate_chainspec_with_missing_cost() {
+        let temp_dir = write_modified_chainspec("\nmint = 10_000\n", "\n");
+        match casper_client::validate_chainspec(temp_dir.path().to_str().unwrap()) {
+            Err(Error::FailedToLoadChainspec(error)) => {
+                assert!(error.to_string().contains("missing field `mint`"))
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
@@ -1,22 +1,35 @@
-const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
+use super::WASMLESS_TRANSFER_FIXED_GAS_PRICE;
+
+/// The default maximum depth of a query.
+pub const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
 
 /// The runtime configuration of the execution engine
 #[derive(Debug, Copy, Clone)]
 pub struct EngineConfig {
     pub(crate) max_query_depth: u64,
+    wasmless_transfer_gas_price: u64,
 }
 
 impl Default for EngineConfig {
     fn default() -> Self {
         EngineConfig {
             max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
+            wasmless_transfer_gas_price: WASMLESS_TRANSFER_FIXED_GAS_PRICE,
         }
     }
 }
 
 impl EngineConfig {
     /// Creates a new engine configuration with provided parameters.
-    pub fn new(max_query_depth: u64) -> EngineConfig {
-        EngineConfig { max_query_depth }
+    pub fn new(max_query_depth: u64, wasmless_transfer_gas_price: u64) -> EngineConfig {
+        EngineConfig {
+            max_query_depth,
+            wasmless_transfer_gas_price,
+        }
+    }
+
+    /// Returns the gas/motes conversion rate applied to the cost of wasmless transfers.
+    pub fn wasmless_transfer_gas_price(&self) -> u64 {
+        self.wasmless_transfer_gas_price
     }
 }
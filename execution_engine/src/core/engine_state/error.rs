@@ -1,14 +1,14 @@
 use datasize::DataSize;
 use thiserror::Error;
 
-use casper_types::{bytesrepr, system::mint, ProtocolVersion};
+use casper_types::{bytesrepr, system::mint, Phase, ProtocolVersion};
 
 use crate::{
     core::{
         engine_state::{genesis::GenesisError, upgrade::ProtocolUpgradeError},
         execution,
     },
-    shared::{newtypes::Blake2bHash, wasm_prep},
+    shared::{gas::Gas, newtypes::Blake2bHash, wasm_prep},
     storage,
 };
 
@@ -36,6 +36,10 @@ pub enum Error {
     Authorization,
     #[error("Insufficient payment")]
     InsufficientPayment,
+    /// Displays as the `Exec(GasLimit)` error it replaces, so the reported error message is
+    /// unchanged.
+    #[error("Out of gas error")]
+    GasLimitExceeded { gas_limit: Gas, phase: Phase },
     #[error("Gas conversion overflow")]
     GasConversionOverflow,
     #[error("Deploy error")]
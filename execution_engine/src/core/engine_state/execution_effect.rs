@@ -13,27 +13,88 @@ impl ExecutionEffect {
     pub fn new(ops: AdditiveMap<Key, Op>, transforms: AdditiveMap<Key, Transform>) -> Self {
         ExecutionEffect { ops, transforms }
     }
+
+    /// Returns the transforms as serializable `(Key, Transform)` pairs, ordered by key.
+    pub fn ordered_transforms(&self) -> Vec<(Key, casper_types::Transform)> {
+        ordered_transforms(&self.transforms)
+    }
+}
+
+/// Converts the given transforms to serializable `(Key, Transform)` pairs, ordered by key.
+///
+/// The transforms are held in a hash map, so the order of iterating them is arbitrary.  Ordering
+/// the pairs ensures the same effects are always reported in the same way.
+pub fn ordered_transforms(
+    transforms: &AdditiveMap<Key, Transform>,
+) -> Vec<(Key, casper_types::Transform)> {
+    let mut ordered_transforms: Vec<(Key, casper_types::Transform)> = transforms
+        .iter()
+        .map(|(key, transform)| (*key, transform.into()))
+        .collect();
+    ordered_transforms.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+    ordered_transforms
 }
 
 impl From<&ExecutionEffect> for casper_types::ExecutionEffect {
     fn from(effect: &ExecutionEffect) -> Self {
+        let mut ops: Vec<(&Key, &Op)> = effect.ops.iter().collect();
+        ops.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
         casper_types::ExecutionEffect {
-            operations: effect
-                .ops
-                .iter()
+            operations: ops
+                .into_iter()
                 .map(|(key, op)| casper_types::Operation {
                     key: key.to_formatted_string(),
                     kind: op.into(),
                 })
                 .collect(),
             transforms: effect
-                .transforms
-                .iter()
+                .ordered_transforms()
+                .into_iter()
                 .map(|(key, transform)| casper_types::TransformEntry {
                     key: key.to_formatted_string(),
-                    transform: transform.into(),
+                    transform,
                 })
                 .collect(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{AccessRights, CLValue, URef};
+
+    use super::*;
+    use crate::shared::stored_value::StoredValue;
+
+    #[test]
+    fn should_order_transforms_by_key() {
+        let keys: Vec<Key> = (0..10u8)
+            .rev()
+            .map(|byte| Key::URef(URef::new([byte; 32], AccessRights::READ_ADD_WRITE)))
+            .collect();
+        let mut transforms = AdditiveMap::new();
+        for (index, key) in keys.iter().enumerate() {
+            let value = StoredValue::CLValue(CLValue::from_t(index as u64).unwrap());
+            transforms.insert(*key, Transform::Write(value));
+        }
+        let effect = ExecutionEffect::new(AdditiveMap::new(), transforms);
+
+        let ordered_keys: Vec<Key> = effect
+            .ordered_transforms()
+            .into_iter()
+            .map(|(key, _transform)| key)
+            .collect();
+        let mut expected_keys = keys;
+        expected_keys.sort();
+        assert_eq!(ordered_keys, expected_keys);
+
+        let formatted_keys: Vec<String> = casper_types::ExecutionEffect::from(&effect)
+            .transforms
+            .into_iter()
+            .map(|transform_entry| transform_entry.key)
+            .collect();
+        let expected_formatted_keys: Vec<String> =
+            expected_keys.iter().map(Key::to_formatted_string).collect();
+        assert_eq!(formatted_keys, expected_formatted_keys);
+    }
+}
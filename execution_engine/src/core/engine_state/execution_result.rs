@@ -1,9 +1,12 @@
 use std::collections::VecDeque;
 
-use casper_types::{bytesrepr::FromBytes, CLTyped, CLValue, CLValueError, Key, TransferAddr};
+use casper_types::{
+    bytesrepr::FromBytes, CLTyped, CLValue, CLValueError, Key, Phase, TransferAddr,
+};
 
 use super::{error, execution_effect::ExecutionEffect, op::Op};
 use crate::{
+    core::execution,
     shared::{
         additive_map::AdditiveMap, gas::Gas, motes::Motes, newtypes::CorrelationId,
         stored_value::StoredValue, transform::Transform,
@@ -139,6 +142,25 @@ impl ExecutionResult {
         }
     }
 
+    /// Replaces an out of gas error with [`error::Error::GasLimitExceeded`], identifying the gas
+    /// limit of the execution and the phase in which it was exceeded.
+    pub fn report_gas_limit_exceeded(self, gas_limit: Gas, phase: Phase) -> Self {
+        match self {
+            ExecutionResult::Failure {
+                error: error::Error::Exec(execution::Error::GasLimit),
+                effect,
+                transfers,
+                cost,
+            } => ExecutionResult::Failure {
+                error: error::Error::GasLimitExceeded { gas_limit, phase },
+                effect,
+                transfers,
+                cost,
+            },
+            result => result,
+        }
+    }
+
     pub fn with_cost(self, cost: Gas) -> Self {
         match self {
             ExecutionResult::Failure {
@@ -185,6 +207,24 @@ impl ExecutionResult {
         }
     }
 
+    /// Records crediting the purse with the given balance key as an add, as is done for payment
+    /// errors.
+    ///
+    /// The finalize payment phase reads the proposer's purse only to check that it exists before
+    /// crediting it.  Recording this as a read would make every deploy of a block depend on the fee
+    /// paid by the previous one.
+    pub fn with_credit_recorded_as_add(self, purse_balance_key: Key) -> Self {
+        let purse_balance_key = purse_balance_key.normalize();
+        let mut effect = self.effect().clone();
+        if let (Some(Op::Write), Some(Transform::AddUInt512(_))) = (
+            effect.ops.get(&purse_balance_key),
+            effect.transforms.get(&purse_balance_key),
+        ) {
+            effect.ops.insert(purse_balance_key, Op::Add);
+        }
+        self.with_effect(effect)
+    }
+
     pub fn with_transfers(self, transfers: Vec<TransferAddr>) -> Self {
         match self {
             ExecutionResult::Failure {
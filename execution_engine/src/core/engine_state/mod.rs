@@ -92,8 +92,8 @@ use crate::{
 pub const MAX_PAYMENT_AMOUNT: u64 = 2_500_000_000;
 pub static MAX_PAYMENT: Lazy<U512> = Lazy::new(|| U512::from(MAX_PAYMENT_AMOUNT));
 
-/// Gas/motes conversion rate of wasmless transfer cost is always 1 regardless of what user wants to
-/// pay.
+/// Default gas/motes conversion rate of wasmless transfer cost, applied regardless of what user
+/// wants to pay.  Can be overridden by [`EngineConfig::wasmless_transfer_gas_price`].
 pub const WASMLESS_TRANSFER_FIXED_GAS_PRICE: u64 = 1;
 
 #[derive(Debug)]
@@ -631,7 +631,7 @@ where
 
         let wasmless_transfer_motes = match Motes::from_gas(
             wasmless_transfer_gas_cost,
-            WASMLESS_TRANSFER_FIXED_GAS_PRICE,
+            self.config.wasmless_transfer_gas_price(),
         ) {
             Some(motes) => motes,
             None => {
@@ -934,13 +934,13 @@ where
             // (b) after executing payment code it's balance has to be equal to the wasmless gas
             // cost price
 
-            let payment_gas =
-                match Gas::from_motes(payment_purse_balance, WASMLESS_TRANSFER_FIXED_GAS_PRICE) {
-                    Some(gas) => gas,
-                    None => {
-                        return Ok(make_charged_execution_failure(Error::GasConversionOverflow))
-                    }
-                };
+            let payment_gas = match Gas::from_motes(
+                payment_purse_balance,
+                self.config.wasmless_transfer_gas_price(),
+            ) {
+                Some(gas) => gas,
+                None => return Ok(make_charged_execution_failure(Error::GasConversionOverflow)),
+            };
 
             debug_assert_eq!(payment_gas, wasmless_transfer_gas_cost);
 
@@ -1062,7 +1062,7 @@ where
                     finalize_payment_call_stack,
                 );
 
-            finalize_result
+            finalize_result.with_credit_recorded_as_add(proposer_main_purse_balance_key)
         };
 
         // Create + persist deploy info.
@@ -1291,7 +1291,7 @@ where
             let payment_args = payment.args().clone();
             let system_contract_cache = SystemContractCache::clone(&self.system_contract_cache);
 
-            if is_standard_payment {
+            let payment_result = if is_standard_payment {
                 executor.exec_standard_payment(
                     payment_module,
                     payment_args,
@@ -1331,7 +1331,8 @@ where
                     &payment_package,
                     payment_call_stack,
                 )
-            }
+            };
+            payment_result.report_gas_limit_exceeded(payment_gas_limit, phase)
         };
 
         debug!("Payment result: {:?}", payment_result);
@@ -1487,26 +1488,28 @@ where
                 };
             let system_contract_cache = SystemContractCache::clone(&self.system_contract_cache);
 
-            executor.exec(
-                session_module,
-                session_entry_point,
-                session_args,
-                session_base_key,
-                &account,
-                &mut session_named_keys,
-                authorization_keys.clone(),
-                blocktime,
-                deploy_hash,
-                session_gas_limit,
-                protocol_version,
-                correlation_id,
-                Rc::clone(&session_tracking_copy),
-                Phase::Session,
-                protocol_data,
-                system_contract_cache,
-                &session_package,
-                session_call_stack,
-            )
+            executor
+                .exec(
+                    session_module,
+                    session_entry_point,
+                    session_args,
+                    session_base_key,
+                    &account,
+                    &mut session_named_keys,
+                    authorization_keys.clone(),
+                    blocktime,
+                    deploy_hash,
+                    session_gas_limit,
+                    protocol_version,
+                    correlation_id,
+                    Rc::clone(&session_tracking_copy),
+                    Phase::Session,
+                    protocol_data,
+                    system_contract_cache,
+                    &session_package,
+                    session_call_stack,
+                )
+                .report_gas_limit_exceeded(session_gas_limit, Phase::Session)
         };
         debug!("Session result: {:?}", session_result);
 
@@ -1619,7 +1622,7 @@ where
                     handle_payment_call_stack,
                 );
 
-            finalize_result
+            finalize_result.with_credit_recorded_as_add(Key::Balance(proposer_purse.addr()))
         };
 
         execution_result_builder.set_finalize_execution_result(finalize_result);
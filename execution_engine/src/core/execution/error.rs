@@ -1,3 +1,5 @@
+use std::fmt::{self, Display, Formatter};
+
 use parity_wasm::elements;
 use thiserror::Error;
 
@@ -68,8 +70,8 @@ pub enum Error {
     ExpectedReturnValue,
     #[error("Unexpected return value")]
     UnexpectedReturnValue,
-    #[error("Invalid context")]
-    InvalidContext,
+    #[error("InvalidContext{}", describe_invalid_context(.0))]
+    InvalidContext(Option<InvalidContextAccess>),
     #[error("Incompatible protocol major version. Expected version {expected} but actual version is {actual}")]
     IncompatibleProtocolMajorVersion { expected: u32, actual: u32 },
     #[error("{0}")]
@@ -177,6 +179,33 @@ impl From<RemoveKeyFailure> for Error {
     }
 }
 
+/// An access to a key which isn't allowed in the current context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidContextAccess {
+    /// The key which was accessed.
+    pub key: Key,
+    /// The access rights required for the access.
+    pub required: AccessRights,
+    /// The access rights to the key held in the current context.
+    pub held: AccessRights,
+}
+
+impl Display for InvalidContextAccess {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "access to {} requires {} but {} held",
+            self.key, self.required, self.held
+        )
+    }
+}
+
+fn describe_invalid_context(access: &Option<InvalidContextAccess>) -> String {
+    access
+        .map(|access| format!(": {}", access))
+        .unwrap_or_default()
+}
+
 impl From<UpdateKeyFailure> for Error {
     fn from(err: UpdateKeyFailure) -> Self {
         Error::UpdateKeyFailure(err)
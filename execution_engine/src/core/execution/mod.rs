@@ -7,6 +7,6 @@ mod tests;
 
 pub use self::{
     address_generator::{AddressGenerator, AddressGeneratorBuilder},
-    error::Error,
+    error::{Error, InvalidContextAccess},
     executor::{DirectSystemContractCall, Executor},
 };
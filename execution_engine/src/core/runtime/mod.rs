@@ -2015,7 +2015,7 @@ where
         match (current, next) {
             (EntryPointType::Contract, EntryPointType::Session) => {
                 // Session code can't be called from Contract code for security reasons.
-                Err(Error::InvalidContext)
+                Err(Error::InvalidContext(None))
             }
             (EntryPointType::Session, EntryPointType::Session) => {
                 // Session code called from session reuses current base key
@@ -2649,7 +2649,7 @@ where
         id: Option<u64>,
     ) -> Result<(), Error> {
         if self.context.base_key() != Key::from(self.protocol_data().mint()) {
-            return Err(Error::InvalidContext);
+            return Err(Error::InvalidContext(None));
         }
 
         if self.context.phase() != Phase::Session {
@@ -2675,7 +2675,7 @@ where
     /// Records given auction info at a given era id
     fn record_era_info(&mut self, era_id: EraId, era_info: EraInfo) -> Result<(), Error> {
         if self.context.base_key() != Key::from(self.protocol_data().auction()) {
-            return Err(Error::InvalidContext);
+            return Err(Error::InvalidContext(None));
         }
 
         if self.context.phase() != Phase::Session {
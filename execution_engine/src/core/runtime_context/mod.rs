@@ -24,7 +24,7 @@ use casper_types::{
 use crate::{
     core::{
         engine_state::execution_effect::ExecutionEffect,
-        execution::{AddressGenerator, Error},
+        execution::{AddressGenerator, Error, InvalidContextAccess},
         runtime_context::dictionary::DictionaryValue,
         tracking_copy::{AddResult, TrackingCopy},
         Address,
@@ -63,7 +63,7 @@ pub fn validate_entry_point_access_with(
         if groups.is_empty() {
             // Exits early in a special case of empty list of groups regardless of the group
             // checking logic below it.
-            return Err(Error::InvalidContext);
+            return Err(Error::InvalidContext(None));
         }
 
         let find_result = groups.iter().find(|g| {
@@ -75,7 +75,7 @@ pub fn validate_entry_point_access_with(
         });
 
         if find_result.is_none() {
-            return Err(Error::InvalidContext);
+            return Err(Error::InvalidContext(None));
         }
     }
     Ok(())
@@ -1032,10 +1032,26 @@ where
 
     /// Gets main purse id
     pub fn get_main_purse(&self) -> Result<URef, Error> {
+        let main_purse = self.account().main_purse();
         if !self.is_valid_context() {
-            return Err(Error::InvalidContext);
+            return Err(Error::InvalidContext(Some(InvalidContextAccess {
+                key: Key::URef(main_purse),
+                required: main_purse.access_rights(),
+                held: self.held_access_rights(&main_purse),
+            })));
         }
-        Ok(self.account().main_purse())
+        Ok(main_purse)
+    }
+
+    /// Returns the access rights to the given uref held in the current context.
+    fn held_access_rights(&self, uref: &URef) -> AccessRights {
+        self.access_rights
+            .get(&uref.addr())
+            .map_or(AccessRights::NONE, |rights| {
+                rights
+                    .iter()
+                    .fold(AccessRights::NONE, |held, rights| held | *rights)
+            })
     }
 
     /// Gets entry point type.
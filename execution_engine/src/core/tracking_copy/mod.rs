@@ -320,6 +320,8 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
             self.fns.insert_add(normalized_key, Transform::Identity);
             Ok(Some(value))
         } else {
+            // The absence of the value is observed too, but there is nothing to transform.
+            self.ops.insert_add(normalized_key, Op::Read);
             Ok(None)
         }
     }
@@ -137,6 +137,22 @@ fn tracking_copy_read() {
     assert_eq!(tc.ops.get(&k), Some(&Op::Read));
 }
 
+#[test]
+fn tracking_copy_read_absent() {
+    let correlation_id = CorrelationId::new();
+    let (gs, root_hash) = InMemoryGlobalState::from_pairs(correlation_id, &[]).unwrap();
+    let view = gs.checkout(root_hash).unwrap().unwrap();
+    let mut tc = TrackingCopy::new(view);
+    let k = Key::Hash([0u8; 32]);
+
+    assert_eq!(tc.read(correlation_id, &k).unwrap(), None);
+    // reading a missing value produces no transform
+    assert!(tc.fns.is_empty());
+    // but still produces an op
+    assert_eq!(tc.ops.len(), 1);
+    assert_eq!(tc.ops.get(&k), Some(&Op::Read));
+}
+
 #[test]
 fn tracking_copy_write() {
     let counter = Rc::new(Cell::new(0));
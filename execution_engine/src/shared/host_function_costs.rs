@@ -735,6 +735,45 @@ mod tests {
 
         assert_eq!(lhs, Gas::new(rhs));
     }
+
+    #[test]
+    fn should_require_every_host_function_cost() {
+        let host_function_costs = HostFunctionCosts::default();
+        let json = serde_json::to_value(&host_function_costs).unwrap();
+
+        let mut missing_entry = json.clone();
+        missing_entry
+            .as_object_mut()
+            .unwrap()
+            .remove("transfer_from_purse_to_purse");
+        assert!(serde_json::from_value::<HostFunctionCosts>(missing_entry).is_err());
+
+        // Entries no longer used, like `delete` in older chainspecs, are ignored.
+        let mut unknown_entry = json.clone();
+        unknown_entry.as_object_mut().unwrap().insert(
+            "delete".to_string(),
+            serde_json::to_value(HostFunction::<[Cost; 2]>::fixed(COST)).unwrap(),
+        );
+        assert_eq!(
+            serde_json::from_value::<HostFunctionCosts>(unknown_entry).unwrap(),
+            host_function_costs
+        );
+
+        let mut aliased_entry = json;
+        let write_cost = aliased_entry
+            .as_object_mut()
+            .unwrap()
+            .remove("dictionary_put")
+            .unwrap();
+        aliased_entry
+            .as_object_mut()
+            .unwrap()
+            .insert("write_local".to_string(), write_cost);
+        assert_eq!(
+            serde_json::from_value::<HostFunctionCosts>(aliased_entry).unwrap(),
+            host_function_costs
+        );
+    }
 }
 
 #[cfg(test)]
@@ -3,6 +3,7 @@ use std::{fmt, iter::Sum};
 use datasize::DataSize;
 use num::Zero;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
@@ -11,6 +12,27 @@ use casper_types::{
 
 use crate::shared::gas::Gas;
 
+/// The number of decimal places used when expressing an amount of motes in CSPR.
+pub const CSPR_DECIMAL_PLACES: usize = 9;
+
+/// The number of motes in one CSPR.
+pub const MOTES_PER_CSPR: u64 = 1_000_000_000;
+
+/// Error returned when parsing an amount of CSPR from a decimal string.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseCsprError {
+    #[error("CSPR amount is empty")]
+    Empty,
+    #[error("CSPR amount must not be negative: {0}")]
+    Negative(String),
+    #[error("CSPR amount is not a valid decimal number: {0}")]
+    InvalidFormat(String),
+    #[error("CSPR amount has more than 9 decimal places: {0}")]
+    ExcessivePrecision(String),
+    #[error("CSPR amount is too large: {0}")]
+    Overflow(String),
+}
+
 #[derive(
     DataSize, Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize,
 )]
@@ -25,6 +47,19 @@ impl Motes {
         self.0.checked_add(rhs.value()).map(Self::new)
     }
 
+    pub fn checked_sub(&self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.value()).map(Self::new)
+    }
+
+    /// Multiplies by a scalar, e.g. a number of units each costing `self`.
+    pub fn checked_mul(&self, multiplier: U512) -> Option<Self> {
+        self.0.checked_mul(multiplier).map(Self::new)
+    }
+
+    pub fn saturating_sub(&self, rhs: Self) -> Self {
+        Self::new(self.0.saturating_sub(rhs.value()))
+    }
+
     pub fn value(&self) -> U512 {
         self.0
     }
@@ -34,6 +69,63 @@ impl Motes {
             .checked_mul(U512::from(conv_rate))
             .map(Self::new)
     }
+
+    /// Parses an amount of CSPR given as a decimal string, e.g. `"1.5"`, into motes.
+    pub fn from_cspr_str(input: &str) -> Result<Self, ParseCsprError> {
+        if input.is_empty() {
+            return Err(ParseCsprError::Empty);
+        }
+        if input.starts_with('-') {
+            return Err(ParseCsprError::Negative(input.to_string()));
+        }
+
+        let (whole, fraction) = match input.find('.') {
+            Some(index) => (&input[..index], &input[index + 1..]),
+            None => (input, ""),
+        };
+        let is_decimal = |digits: &str| digits.bytes().all(|byte| byte.is_ascii_digit());
+        if whole.is_empty()
+            || !is_decimal(whole)
+            || !is_decimal(fraction)
+            || (input.contains('.') && fraction.is_empty())
+        {
+            return Err(ParseCsprError::InvalidFormat(input.to_string()));
+        }
+        if fraction.len() > CSPR_DECIMAL_PLACES {
+            return Err(ParseCsprError::ExcessivePrecision(input.to_string()));
+        }
+
+        let overflow = || ParseCsprError::Overflow(input.to_string());
+        let whole_motes = U512::from_dec_str(whole)
+            .map_err(|_| overflow())?
+            .checked_mul(U512::from(MOTES_PER_CSPR))
+            .ok_or_else(overflow)?;
+        let fraction_motes = if fraction.is_empty() {
+            U512::zero()
+        } else {
+            let padded = format!("{:0<width$}", fraction, width = CSPR_DECIMAL_PLACES);
+            U512::from_dec_str(&padded).map_err(|_| overflow())?
+        };
+        whole_motes
+            .checked_add(fraction_motes)
+            .map(Self::new)
+            .ok_or_else(overflow)
+    }
+
+    /// Returns this amount of motes as a decimal string of CSPR, e.g. `"1.5"`.
+    ///
+    /// Trailing zeros of the fractional part are omitted, as is the decimal point when the amount
+    /// is a whole number of CSPR.
+    pub fn to_cspr_string(&self) -> String {
+        let motes_per_cspr = U512::from(MOTES_PER_CSPR);
+        let whole = self.0 / motes_per_cspr;
+        let fraction = (self.0 % motes_per_cspr).as_u64();
+        if fraction == 0 {
+            return whole.to_string();
+        }
+        let fraction = format!("{:0>width$}", fraction, width = CSPR_DECIMAL_PLACES);
+        format!("{}.{}", whole, fraction.trim_end_matches('0'))
+    }
 }
 
 impl fmt::Display for Motes {
@@ -115,7 +207,10 @@ impl FromBytes for Motes {
 mod tests {
     use casper_types::U512;
 
-    use crate::shared::{gas::Gas, motes::Motes};
+    use crate::shared::{
+        gas::Gas,
+        motes::{Motes, ParseCsprError, MOTES_PER_CSPR},
+    };
 
     #[test]
     fn should_be_able_to_get_instance_of_motes() {
@@ -228,4 +323,132 @@ mod tests {
         let maybe = Motes::from_gas(gas, conv_rate);
         assert!(maybe.is_none(), "should be none due to overflow");
     }
+
+    #[test]
+    fn should_support_checked_arithmetic() {
+        let left_motes = Motes::new(U512::from(100));
+        let right_motes = Motes::new(U512::from(10));
+        assert_eq!(
+            left_motes.checked_add(right_motes),
+            Some(Motes::new(U512::from(110)))
+        );
+        assert_eq!(
+            left_motes.checked_sub(right_motes),
+            Some(Motes::new(U512::from(90)))
+        );
+        assert_eq!(
+            left_motes.checked_mul(U512::from(10)),
+            Some(Motes::new(U512::from(1000)))
+        );
+    }
+
+    #[test]
+    fn should_return_none_on_checked_arithmetic_overflow() {
+        let small_motes = Motes::new(U512::from(10));
+        let large_motes = Motes::new(U512::MAX);
+        assert!(
+            small_motes.checked_sub(large_motes).is_none(),
+            "should be none due to underflow"
+        );
+        assert!(
+            large_motes.checked_add(small_motes).is_none(),
+            "should be none due to overflow"
+        );
+        assert!(
+            large_motes.checked_mul(U512::from(10)).is_none(),
+            "should be none due to overflow"
+        );
+    }
+
+    #[test]
+    fn should_support_saturating_sub() {
+        let left_motes = Motes::new(U512::from(100));
+        let right_motes = Motes::new(U512::from(10));
+        assert_eq!(
+            left_motes.saturating_sub(right_motes),
+            Motes::new(U512::from(90))
+        );
+        assert_eq!(right_motes.saturating_sub(left_motes), Motes::default());
+    }
+
+    #[test]
+    fn should_parse_cspr_str() {
+        assert_eq!(
+            Motes::from_cspr_str("1.5"),
+            Ok(Motes::new(U512::from(MOTES_PER_CSPR * 3 / 2)))
+        );
+        assert_eq!(
+            Motes::from_cspr_str("2"),
+            Ok(Motes::new(U512::from(MOTES_PER_CSPR * 2)))
+        );
+        assert_eq!(
+            Motes::from_cspr_str("0.000000001"),
+            Ok(Motes::new(U512::one()))
+        );
+        assert_eq!(Motes::from_cspr_str("0"), Ok(Motes::default()));
+    }
+
+    #[test]
+    fn should_fail_to_parse_invalid_cspr_str() {
+        assert_eq!(Motes::from_cspr_str(""), Err(ParseCsprError::Empty));
+        assert!(matches!(
+            Motes::from_cspr_str("-1.5"),
+            Err(ParseCsprError::Negative(_))
+        ));
+        for input in &["1.", ".5", "1.2.3", "1,5", "abc", " 1", "+1"] {
+            assert!(
+                matches!(
+                    Motes::from_cspr_str(input),
+                    Err(ParseCsprError::InvalidFormat(_))
+                ),
+                "{} should be invalid",
+                input
+            );
+        }
+        assert!(matches!(
+            Motes::from_cspr_str("1.0000000001"),
+            Err(ParseCsprError::ExcessivePrecision(_))
+        ));
+        let too_large = format!("{}", U512::MAX);
+        assert!(matches!(
+            Motes::from_cspr_str(&too_large),
+            Err(ParseCsprError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn should_format_cspr_string() {
+        assert_eq!(
+            Motes::new(U512::from(MOTES_PER_CSPR * 3 / 2)).to_cspr_string(),
+            "1.5"
+        );
+        assert_eq!(
+            Motes::new(U512::from(MOTES_PER_CSPR * 2)).to_cspr_string(),
+            "2"
+        );
+        assert_eq!(Motes::new(U512::one()).to_cspr_string(), "0.000000001");
+        assert_eq!(Motes::default().to_cspr_string(), "0");
+    }
+
+    #[test]
+    fn should_roundtrip_cspr_string() {
+        for value in &[
+            0,
+            1,
+            10,
+            999_999_999,
+            1_000_000_000,
+            1_500_000_000,
+            123_456_789_012,
+        ] {
+            let motes = Motes::new(U512::from(*value));
+            let cspr = motes.to_cspr_string();
+            assert_eq!(Motes::from_cspr_str(&cspr), Ok(motes), "{}", cspr);
+        }
+        let max_motes = Motes::new(U512::MAX);
+        assert_eq!(
+            Motes::from_cspr_str(&max_motes.to_cspr_string()),
+            Ok(max_motes)
+        );
+    }
 }
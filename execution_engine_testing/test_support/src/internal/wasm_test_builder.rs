@@ -19,6 +19,7 @@ use casper_execution_engine::{
         engine_state::{
             era_validators::GetEraValidatorsRequest,
             execute_request::ExecuteRequest,
+            execution_effect,
             execution_result::ExecutionResult,
             run_genesis_request::RunGenesisRequest,
             step::{StepRequest, StepResult},
@@ -32,6 +33,7 @@ use casper_execution_engine::{
         additive_map::AdditiveMap,
         gas::Gas,
         logging::{self, Settings, Style},
+        motes::Motes,
         newtypes::{Blake2bHash, CorrelationId},
         stored_value::StoredValue,
         transform::Transform,
@@ -60,8 +62,8 @@ use casper_types::{
         mint::TOTAL_SUPPLY_KEY,
     },
     CLTyped, CLValue, Contract, ContractHash, ContractPackage, ContractPackageHash, ContractWasm,
-    DeployHash, DeployInfo, EraId, Key, KeyTag, PublicKey, RuntimeArgs, Transfer, TransferAddr,
-    URef, U512,
+    DeployHash, DeployInfo, EraId, Key, KeyTag, Phase, PublicKey, RuntimeArgs, Transfer,
+    TransferAddr, URef, U512,
 };
 
 use crate::internal::{
@@ -90,6 +92,9 @@ pub struct WasmTestBuilder<S> {
     engine_state: Rc<EngineState<S>>,
     /// [`ExecutionResult`] is wrapped in [`Rc`] to work around a missing [`Clone`] implementation
     exec_results: Vec<Vec<Rc<ExecutionResult>>>,
+    /// The gas prices of the deploys executed by each exec call, i.e. `exec_gas_prices[0][1]` is
+    /// the gas price of the second deploy of the first exec call.
+    exec_gas_prices: Vec<Vec<u64>>,
     upgrade_results: Vec<Result<UpgradeResult, engine_state::Error>>,
     genesis_hash: Option<Blake2bHash>,
     post_state_hash: Option<Blake2bHash>,
@@ -119,26 +124,7 @@ impl<S> WasmTestBuilder<S> {
 
 impl Default for InMemoryWasmTestBuilder {
     fn default() -> Self {
-        Self::initialize_logging();
-        let engine_config = EngineConfig::default();
-
-        let global_state = InMemoryGlobalState::empty().expect("should create global state");
-        let engine_state = EngineState::new(global_state, engine_config);
-
-        WasmTestBuilder {
-            engine_state: Rc::new(engine_state),
-            exec_results: Vec::new(),
-            upgrade_results: Vec::new(),
-            genesis_hash: None,
-            post_state_hash: None,
-            transforms: Vec::new(),
-            genesis_account: None,
-            genesis_transforms: None,
-            mint_contract_hash: None,
-            handle_payment_contract_hash: None,
-            standard_payment_hash: None,
-            auction_contract_hash: None,
-        }
+        Self::new_with_config(EngineConfig::default())
     }
 }
 
@@ -149,6 +135,7 @@ impl<S> Clone for WasmTestBuilder<S> {
         WasmTestBuilder {
             engine_state: Rc::clone(&self.engine_state),
             exec_results: self.exec_results.clone(),
+            exec_gas_prices: self.exec_gas_prices.clone(),
             upgrade_results: self.upgrade_results.clone(),
             genesis_hash: self.genesis_hash,
             post_state_hash: self.post_state_hash,
@@ -174,7 +161,46 @@ impl<S> WasmTestResult<S> {
     }
 }
 
+/// A captured state of a [`WasmTestBuilder`] which can be restored any number of times.
+///
+/// Only the state root hash is captured rather than a copy of global state.  Since global state is
+/// content-addressed, commits made by a restored builder produce new state roots and never alter
+/// the captured one.
+#[derive(Clone)]
+pub struct WasmTestSnapshot<S>(WasmTestResult<S>);
+
+impl<S> WasmTestSnapshot<S> {
+    /// Returns the state root hash captured by this snapshot.
+    pub fn post_state_hash(&self) -> Option<Blake2bHash> {
+        self.0.builder().post_state_hash
+    }
+}
+
 impl InMemoryWasmTestBuilder {
+    /// Creates a builder with empty global state, running the engine with `engine_config`.
+    pub fn new_with_config(engine_config: EngineConfig) -> Self {
+        Self::initialize_logging();
+
+        let global_state = InMemoryGlobalState::empty().expect("should create global state");
+        let engine_state = EngineState::new(global_state, engine_config);
+
+        WasmTestBuilder {
+            engine_state: Rc::new(engine_state),
+            exec_results: Vec::new(),
+            exec_gas_prices: Vec::new(),
+            upgrade_results: Vec::new(),
+            genesis_hash: None,
+            post_state_hash: None,
+            transforms: Vec::new(),
+            genesis_account: None,
+            genesis_transforms: None,
+            mint_contract_hash: None,
+            handle_payment_contract_hash: None,
+            standard_payment_hash: None,
+            auction_contract_hash: None,
+        }
+    }
+
     pub fn new(
         global_state: InMemoryGlobalState,
         engine_config: EngineConfig,
@@ -222,6 +248,7 @@ impl LmdbWasmTestBuilder {
         WasmTestBuilder {
             engine_state: Rc::new(engine_state),
             exec_results: Vec::new(),
+            exec_gas_prices: Vec::new(),
             upgrade_results: Vec::new(),
             genesis_hash: None,
             post_state_hash: None,
@@ -298,6 +325,7 @@ impl LmdbWasmTestBuilder {
         WasmTestBuilder {
             engine_state: Rc::new(engine_state),
             exec_results: Vec::new(),
+            exec_gas_prices: Vec::new(),
             upgrade_results: Vec::new(),
             genesis_hash: None,
             post_state_hash: Some(post_state_hash),
@@ -338,6 +366,7 @@ where
         WasmTestBuilder {
             engine_state: result.0.engine_state,
             exec_results: Vec::new(),
+            exec_gas_prices: Vec::new(),
             upgrade_results: Vec::new(),
             genesis_hash: result.0.genesis_hash,
             post_state_hash: result.0.post_state_hash,
@@ -420,6 +449,13 @@ where
         Err(format!("{:?}", query_result))
     }
 
+    /// Queries the value stored under `path` relative to `base_key` in the current state, walking
+    /// the named keys of the accounts and contracts along the way.
+    pub fn query_path(&self, base_key: Key, path: &[&str]) -> Result<StoredValue, String> {
+        let path: Vec<String> = path.iter().map(|name| name.to_string()).collect();
+        self.query(None, base_key, &path)
+    }
+
     pub fn query_dictionary_item(
         &self,
         maybe_post_state: Option<Blake2bHash>,
@@ -481,6 +517,13 @@ where
             exec_request.parent_state_hash = hash;
             exec_request
         };
+        self.exec_gas_prices.push(
+            exec_request
+                .deploys()
+                .iter()
+                .map(|deploy_item| deploy_item.gas_price)
+                .collect(),
+        );
         let maybe_exec_results = self
             .engine_state
             .run_execute(CorrelationId::new(), exec_request);
@@ -672,6 +715,17 @@ where
         self.transforms.clone()
     }
 
+    /// Gets the transforms of the deploy at `index` in the cache of transforms, as serializable
+    /// `(Key, Transform)` pairs ordered by key.
+    pub fn get_ordered_transforms(
+        &self,
+        index: usize,
+    ) -> Option<Vec<(Key, casper_types::Transform)>> {
+        self.transforms
+            .get(index)
+            .map(execution_effect::ordered_transforms)
+    }
+
     /// Gets genesis account (if present)
     pub fn get_genesis_account(&self) -> &Account {
         self.genesis_account
@@ -754,6 +808,36 @@ where
         WasmTestResult(self.clone())
     }
 
+    /// Captures the current state of this builder so that it can later be restored with
+    /// [`WasmTestBuilder::restore`].
+    ///
+    /// Results of previous executions and upgrades are not captured.
+    pub fn snapshot(&self) -> WasmTestSnapshot<S> {
+        let builder = WasmTestBuilder {
+            engine_state: Rc::clone(&self.engine_state),
+            exec_results: Vec::new(),
+            exec_gas_prices: Vec::new(),
+            upgrade_results: Vec::new(),
+            genesis_hash: self.genesis_hash,
+            post_state_hash: self.post_state_hash,
+            transforms: Vec::new(),
+            genesis_account: self.genesis_account.clone(),
+            genesis_transforms: self.genesis_transforms.clone(),
+            mint_contract_hash: self.mint_contract_hash,
+            handle_payment_contract_hash: self.handle_payment_contract_hash,
+            standard_payment_hash: self.standard_payment_hash,
+            auction_contract_hash: self.auction_contract_hash,
+        };
+        WasmTestSnapshot(WasmTestResult(builder))
+    }
+
+    /// Resets this builder to the state captured in `snapshot`, discarding any results cached
+    /// since.
+    pub fn restore(&mut self, snapshot: &WasmTestSnapshot<S>) -> &mut Self {
+        *self = Self::from_result(snapshot.0.clone());
+        self
+    }
+
     pub fn get_handle_payment_contract(&self) -> Contract {
         let handle_payment_contract: Key = self
             .handle_payment_contract_hash
@@ -765,8 +849,13 @@ where
     }
 
     pub fn get_purse_balance(&self, purse: URef) -> U512 {
+        self.get_purse_balance_at(self.get_post_state_hash(), purse)
+    }
+
+    /// Returns the balance of `purse` as of the given historical `state_root_hash`.
+    pub fn get_purse_balance_at(&self, state_root_hash: Blake2bHash, purse: URef) -> U512 {
         let base_key = Key::Balance(purse.addr());
-        self.query(None, base_key, &[])
+        self.query(Some(state_root_hash), base_key, &[])
             .and_then(|v| CLValue::try_from(v).map_err(|error| format!("{:?}", error)))
             .and_then(|cl_value| cl_value.into_t().map_err(|error| format!("{:?}", error)))
             .expect("should parse balance into a U512")
@@ -890,11 +979,60 @@ where
         exec_result.cost()
     }
 
+    /// Returns the summed cost of every deploy executed by this builder, each converted to motes
+    /// at the gas price given in its deploy.
+    pub fn total_gas_cost(&self) -> Motes {
+        self.exec_results
+            .iter()
+            .flatten()
+            .zip(self.exec_gas_prices.iter().flatten())
+            .map(|(exec_result, gas_price)| {
+                Motes::from_gas(exec_result.cost(), *gas_price)
+                    .expect("gas cost should convert to motes")
+            })
+            .sum()
+    }
+
     pub fn exec_error_message(&self, index: usize) -> Option<String> {
         let response = self.get_exec_result(index)?;
         Some(utils::get_error_message(response))
     }
 
+    /// Returns the error of the first failed deploy in the execution at `index`, if any.
+    pub fn exec_error(&self, index: usize) -> Option<engine_state::Error> {
+        self.get_exec_result(index)?
+            .iter()
+            .find_map(|exec_result| exec_result.as_error().cloned())
+    }
+
+    /// Asserts that a deploy in the execution at `index` failed with an error for which
+    /// `predicate` returns `true`.
+    pub fn expect_exec_error<F>(&self, index: usize, predicate: F) -> &Self
+    where
+        F: FnOnce(&engine_state::Error) -> bool,
+    {
+        let error = self
+            .exec_error(index)
+            .unwrap_or_else(|| panic!("Expected execution {} to fail", index));
+        if !predicate(&error) {
+            panic!("Unexpected error in execution {}: {:?}", index, error);
+        }
+        self
+    }
+
+    /// Returns the gas limit and the phase of the deploy in the execution at `index` which
+    /// exceeded its gas limit.
+    ///
+    /// Panics if no deploy in that execution failed with
+    /// [`engine_state::Error::GasLimitExceeded`].
+    pub fn expect_gas_limit_exceeded(&self, index: usize) -> (Gas, Phase) {
+        match self.exec_error(index) {
+            Some(engine_state::Error::GasLimitExceeded { gas_limit, phase }) => (gas_limit, phase),
+            Some(error) => panic!("Unexpected error in execution {}: {:?}", index, error),
+            None => panic!("Expected execution {} to fail", index),
+        }
+    }
+
     pub fn exec_commit_finish(&mut self, execute_request: ExecuteRequest) -> WasmTestResult<S> {
         self.exec(execute_request)
             .expect_success()
@@ -211,7 +211,7 @@ fn assert_invalid_context(builder: &mut InMemoryWasmTestBuilder, depth: usize) {
         assert!(matches!(
             error,
             casper_execution_engine::core::engine_state::Error::Exec(
-                casper_execution_engine::core::execution::Error::InvalidContext
+                casper_execution_engine::core::execution::Error::InvalidContext(_)
             )
         ));
     }
@@ -0,0 +1,67 @@
+use casper_engine_test_support::{
+    internal::{ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST},
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_types::{runtime_args, Key, RuntimeArgs, Transform};
+
+const CONTRACT_WRITE_NAMED_KEYS: &str = "write_named_keys.wasm";
+const FIRST_KEY_NAME: &str = "first";
+const FIRST_VALUE: u64 = 1;
+const SECOND_KEY_NAME: &str = "second";
+const SECOND_VALUE: u64 = 2;
+
+#[ignore]
+#[test]
+fn should_report_transforms_of_written_named_keys_in_order() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_WRITE_NAMED_KEYS,
+        runtime_args! {},
+    )
+    .build();
+    builder.exec(exec_request).expect_success().commit();
+
+    let account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have account");
+    let first_key = *account
+        .named_keys()
+        .get(FIRST_KEY_NAME)
+        .expect("should have first named key");
+    let second_key = *account
+        .named_keys()
+        .get(SECOND_KEY_NAME)
+        .expect("should have second named key");
+
+    let transforms = builder
+        .get_ordered_transforms(0)
+        .expect("should have transforms");
+    assert!(
+        transforms.windows(2).all(|pair| pair[0].0 < pair[1].0),
+        "transforms should be ordered by key"
+    );
+
+    // Both writes appear, in the order of their keys.
+    let written_values: Vec<(Key, u64)> = transforms
+        .iter()
+        .filter(|(key, _transform)| *key == first_key || *key == second_key)
+        .map(|(key, transform)| match transform {
+            Transform::WriteCLValue(cl_value) => {
+                (*key, cl_value.clone().into_t().expect("should be u64"))
+            }
+            _ => panic!("expected {} to be written, got {:?}", key, transform),
+        })
+        .collect();
+    let mut expected_values = vec![(first_key, FIRST_VALUE), (second_key, SECOND_VALUE)];
+    expected_values.sort();
+    assert_eq!(written_values, expected_values);
+
+    // The transforms are serializable for reporting to clients.
+    let json = serde_json::to_string(&transforms).expect("should serialize transforms");
+    let deserialized: Vec<(Key, Transform)> =
+        serde_json::from_str(&json).expect("should deserialize transforms");
+    assert_eq!(deserialized, transforms);
+}
@@ -373,5 +373,5 @@ fn should_not_call_session_from_contract() {
     assert_eq!(response.len(), 1);
     let exec_response = response.last().expect("should have response");
     let error = exec_response.as_error().expect("should have error");
-    assert_matches!(error, Error::Exec(execution::Error::InvalidContext));
+    assert_matches!(error, Error::Exec(execution::Error::InvalidContext(_)));
 }
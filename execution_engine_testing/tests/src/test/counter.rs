@@ -211,3 +211,43 @@ fn should_call_counter_contract_directly() {
 
     assert_eq!(value, 1);
 }
+
+#[ignore]
+#[test]
+fn should_query_counter_value_by_path() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    let exec_request_1 = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_COUNTER_DEFINE,
+        RuntimeArgs::new(),
+    )
+    .build();
+
+    builder
+        .run_genesis(&DEFAULT_RUN_GENESIS_REQUEST)
+        .exec(exec_request_1)
+        .expect_success()
+        .commit();
+
+    let account_key = Key::Account(*DEFAULT_ACCOUNT_ADDR);
+
+    let value: i32 = builder
+        .query_path(
+            account_key,
+            &[COUNTER_CONTRACT_HASH_KEY_NAME, COUNTER_VALUE_UREF],
+        )
+        .expect("should have counter value")
+        .as_cl_value()
+        .expect("should be CLValue")
+        .clone()
+        .into_t()
+        .expect("should cast CLValue to integer");
+
+    assert_eq!(value, 0);
+
+    let error = builder
+        .query_path(account_key, &[COUNTER_CONTRACT_HASH_KEY_NAME, "missing"])
+        .expect_err("should not find missing named key");
+    assert!(error.contains("missing"), "unexpected error: {}", error);
+}
@@ -7,15 +7,15 @@ use parity_wasm::{
 use casper_engine_test_support::{
     internal::{
         DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, ARG_AMOUNT,
-        DEFAULT_PAYMENT, DEFAULT_RUN_GENESIS_REQUEST, DEFAULT_WASM_CONFIG,
+        DEFAULT_GAS_PRICE, DEFAULT_PAYMENT, DEFAULT_RUN_GENESIS_REQUEST, DEFAULT_WASM_CONFIG,
     },
     DEFAULT_ACCOUNT_ADDR,
 };
 use casper_execution_engine::{
     core::engine_state::Error,
-    shared::{gas::Gas, wasm_prep::PreprocessingError},
+    shared::{gas::Gas, motes::Motes, wasm_prep::PreprocessingError},
 };
-use casper_types::{contracts::DEFAULT_ENTRY_POINT_NAME, runtime_args, RuntimeArgs};
+use casper_types::{contracts::DEFAULT_ENTRY_POINT_NAME, runtime_args, Phase, RuntimeArgs, U512};
 
 /// Creates minimal session code that does nothing
 fn make_minimal_do_nothing() -> Vec<u8> {
@@ -259,3 +259,76 @@ fn should_correctly_measure_gas_for_opcodes() {
         accounted_opcodes
     );
 }
+
+#[ignore]
+#[test]
+fn should_sum_gas_cost_of_all_executed_deploys() {
+    const DO_NOTHING_WASM: &str = "do_nothing.wasm";
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let exec_request_1 =
+        ExecuteRequestBuilder::standard(*DEFAULT_ACCOUNT_ADDR, DO_NOTHING_WASM, RuntimeArgs::new())
+            .build();
+    builder.exec(exec_request_1).expect_success().commit();
+
+    // The second deploy pays a higher gas price, which should apply to its cost only.
+    let gas_price_2 = DEFAULT_GAS_PRICE * 2;
+    let exec_request_2 = {
+        let deploy_item = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_session_code(DO_NOTHING_WASM, RuntimeArgs::new())
+            .with_empty_payment_bytes(runtime_args! {
+                ARG_AMOUNT => *DEFAULT_PAYMENT
+            })
+            .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+            .with_deploy_hash([43; 32])
+            .with_gas_price(gas_price_2)
+            .build();
+        ExecuteRequestBuilder::from_deploy_item(deploy_item).build()
+    };
+    builder.exec(exec_request_2).expect_success().commit();
+
+    let cost_1 = Motes::from_gas(builder.exec_costs(0)[0], DEFAULT_GAS_PRICE)
+        .expect("should convert gas to motes");
+    let cost_2 = Motes::from_gas(builder.exec_costs(1)[0], gas_price_2)
+        .expect("should convert gas to motes");
+    let expected_total = cost_1
+        .checked_add(cost_2)
+        .expect("should add costs in motes");
+
+    assert!(!expected_total.value().is_zero());
+    assert_eq!(builder.total_gas_cost(), expected_total);
+}
+
+#[ignore]
+#[test]
+fn should_report_gas_limit_exceeded_by_session_code() {
+    const EXPENSIVE_CALCULATION_WASM: &str = "expensive_calculation.wasm";
+    let payment_amount = U512::from(10_001);
+
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let exec_request = {
+        let deploy_item = DeployItemBuilder::new()
+            .with_address(*DEFAULT_ACCOUNT_ADDR)
+            .with_session_code(EXPENSIVE_CALCULATION_WASM, RuntimeArgs::default())
+            .with_empty_payment_bytes(runtime_args! {
+                ARG_AMOUNT => payment_amount
+            })
+            .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+            .with_deploy_hash([42; 32])
+            .build();
+        ExecuteRequestBuilder::from_deploy_item(deploy_item).build()
+    };
+    builder.exec(exec_request).commit();
+
+    let (gas_limit, phase) = builder.expect_gas_limit_exceeded(0);
+    let expected_gas_limit = Gas::from_motes(Motes::new(payment_amount), DEFAULT_GAS_PRICE)
+        .expect("should convert to gas");
+    assert_eq!(gas_limit, expected_gas_limit);
+    assert_eq!(phase, Phase::Session);
+    assert_eq!(builder.exec_costs(0)[0], gas_limit);
+}
@@ -191,3 +191,44 @@ fn get_balance_using_public_key_should_work() {
         Err(ValidationError::UnexpectedValue)
     );
 }
+
+#[ignore]
+#[test]
+fn get_purse_balance_at_should_return_historical_balance() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let transfer_args = runtime_args! {
+        TRANSFER_ARG_TARGET => *ALICE_ADDR,
+        TRANSFER_ARG_AMOUNT => *TRANSFER_AMOUNT_1,
+        TRANSFER_ARG_ID => <Option<u64>>::None,
+    };
+
+    let transfer_request_1 =
+        ExecuteRequestBuilder::transfer(*DEFAULT_ACCOUNT_ADDR, transfer_args.clone()).build();
+    builder.exec(transfer_request_1).commit().expect_success();
+    let state_root_hash_1 = builder.get_post_state_hash();
+
+    let transfer_request_2 =
+        ExecuteRequestBuilder::transfer(*DEFAULT_ACCOUNT_ADDR, transfer_args).build();
+    builder.exec(transfer_request_2).commit().expect_success();
+    let state_root_hash_2 = builder.get_post_state_hash();
+
+    let alice_main_purse = builder
+        .get_account(*ALICE_ADDR)
+        .expect("should have Alice's account")
+        .main_purse();
+
+    assert_eq!(
+        builder.get_purse_balance_at(state_root_hash_1, alice_main_purse),
+        *TRANSFER_AMOUNT_1
+    );
+    assert_eq!(
+        builder.get_purse_balance_at(state_root_hash_2, alice_main_purse),
+        *TRANSFER_AMOUNT_1 * 2
+    );
+    assert_eq!(
+        builder.get_purse_balance(alice_main_purse),
+        builder.get_purse_balance_at(state_root_hash_2, alice_main_purse)
+    );
+}
@@ -238,7 +238,7 @@ fn should_not_call_restricted_session_from_wrong_account() {
     assert_eq!(response.len(), 1);
     let exec_response = response.last().expect("should have response");
     let error = exec_response.as_error().expect("should have error");
-    assert_matches!(error, Error::Exec(execution::Error::InvalidContext));
+    assert_matches!(error, Error::Exec(execution::Error::InvalidContext(_)));
 }
 
 #[test]
@@ -320,7 +320,7 @@ fn should_not_call_restricted_session_caller_from_wrong_account() {
     assert_eq!(response.len(), 1);
     let exec_response = response.last().expect("should have response");
     let error = exec_response.as_error().expect("should have error");
-    assert_matches!(error, Error::Exec(execution::Error::InvalidContext));
+    assert_matches!(error, Error::Exec(execution::Error::InvalidContext(_)));
 }
 
 #[ignore]
@@ -463,7 +463,7 @@ fn should_not_call_group_restricted_contract_from_wrong_account() {
     assert_eq!(response.len(), 1);
     let exec_response = response.last().expect("should have response");
     let error = exec_response.as_error().expect("should have error");
-    assert_matches!(error, Error::Exec(execution::Error::InvalidContext));
+    assert_matches!(error, Error::Exec(execution::Error::InvalidContext(_)));
 }
 
 #[ignore]
@@ -734,7 +734,7 @@ fn should_call_group_restricted_contract_as_session_from_wrong_account() {
     assert_eq!(response.len(), 1);
     let exec_response = response.last().expect("should have response");
     let error = exec_response.as_error().expect("should have error");
-    assert_matches!(error, Error::Exec(execution::Error::InvalidContext));
+    assert_matches!(error, Error::Exec(execution::Error::InvalidContext(_)));
 }
 
 #[ignore]
@@ -802,7 +802,7 @@ fn should_not_call_uncallable_contract_from_deploy() {
     assert_eq!(response.len(), 1);
     let exec_response = response.last().expect("should have response");
     let error = exec_response.as_error().expect("should have error");
-    assert_matches!(error, Error::Exec(execution::Error::InvalidContext));
+    assert_matches!(error, Error::Exec(execution::Error::InvalidContext(_)));
 
     let exec_request_3 = {
         let args = runtime_args! {
@@ -892,7 +892,7 @@ fn should_not_call_uncallable_session_from_deploy() {
     assert_eq!(response.len(), 1);
     let exec_response = response.last().expect("should have response");
     let error = exec_response.as_error().expect("should have error");
-    assert_matches!(error, Error::Exec(execution::Error::InvalidContext));
+    assert_matches!(error, Error::Exec(execution::Error::InvalidContext(_)));
 
     let exec_request_3 = {
         let args = runtime_args! {
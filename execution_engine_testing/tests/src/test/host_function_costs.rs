@@ -1,8 +1,22 @@
+use once_cell::sync::Lazy;
+
 use casper_engine_test_support::{
-    internal::{ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST},
+    internal::{
+        ExecuteRequestBuilder, InMemoryWasmTestBuilder, UpgradeRequestBuilder,
+        DEFAULT_PROTOCOL_VERSION, DEFAULT_RUN_GENESIS_REQUEST,
+    },
     DEFAULT_ACCOUNT_ADDR,
 };
-use casper_types::{bytesrepr::Bytes, runtime_args, ContractHash, RuntimeArgs};
+use casper_execution_engine::shared::{
+    gas::Gas,
+    host_function_costs::{Cost, HostFunction, HostFunctionCosts},
+    opcode_costs::OpcodeCosts,
+    storage_costs::StorageCosts,
+    wasm_config::{WasmConfig, DEFAULT_MAX_STACK_HEIGHT, DEFAULT_WASM_MAX_MEMORY},
+};
+use casper_types::{
+    bytesrepr::Bytes, runtime_args, ContractHash, EraId, ProtocolVersion, RuntimeArgs, U512,
+};
 
 const HOST_FUNCTION_COSTS_NAME: &str = "host_function_costs.wasm";
 const CONTRACT_KEY_NAME: &str = "contract";
@@ -15,6 +29,22 @@ const ARG_BYTES: &str = "bytes";
 const ARG_SIZE_FUNCTION_CALL_1_NAME: &str = "arg_size_function_call_1";
 const ARG_SIZE_FUNCTION_CALL_100_NAME: &str = "arg_size_function_call_100";
 
+const CONTRACT_TRANSFER_PURSE_TO_PURSE: &str = "transfer_purse_to_purse.wasm";
+const ARG_SOURCE: &str = "source";
+const ARG_TARGET: &str = "target";
+const ARG_AMOUNT: &str = "amount";
+const PURSE_TO_PURSE_AMOUNT: u64 = 42;
+const TRANSFER_FROM_PURSE_TO_PURSE_COST: Cost = 1_000_000;
+const DEFAULT_ACTIVATION_POINT: EraId = EraId::new(1);
+
+static NEW_PROTOCOL_VERSION: Lazy<ProtocolVersion> = Lazy::new(|| {
+    ProtocolVersion::from_parts(
+        DEFAULT_PROTOCOL_VERSION.value().major,
+        DEFAULT_PROTOCOL_VERSION.value().minor,
+        DEFAULT_PROTOCOL_VERSION.value().patch + 1,
+    )
+});
+
 #[ignore]
 #[test]
 fn should_measure_gas_cost() {
@@ -235,3 +265,72 @@ fn should_measure_argument_size_in_host_function_call() {
         call_2_cost
     );
 }
+
+/// Runs a purse to purse transfer after upgrading to a wasm config which charges
+/// `transfer_cost` for the `transfer_from_purse_to_purse` host function, and returns the gas
+/// charged for the deploy.
+fn purse_to_purse_transfer_gas_cost(transfer_cost: Cost) -> Gas {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+
+    let new_host_function_costs = HostFunctionCosts {
+        transfer_from_purse_to_purse: HostFunction::fixed(transfer_cost),
+        ..Default::default()
+    };
+
+    let new_wasm_config = WasmConfig::new(
+        DEFAULT_WASM_MAX_MEMORY,
+        DEFAULT_MAX_STACK_HEIGHT,
+        OpcodeCosts::default(),
+        StorageCosts::default(),
+        new_host_function_costs,
+    );
+
+    let mut upgrade_request = UpgradeRequestBuilder::new()
+        .with_current_protocol_version(*DEFAULT_PROTOCOL_VERSION)
+        .with_new_protocol_version(*NEW_PROTOCOL_VERSION)
+        .with_activation_point(DEFAULT_ACTIVATION_POINT)
+        .with_new_wasm_config(new_wasm_config)
+        .build();
+
+    builder
+        .upgrade_with_upgrade_request(&mut upgrade_request)
+        .expect_upgrade_success();
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_TRANSFER_PURSE_TO_PURSE,
+        runtime_args! {
+            ARG_SOURCE => "purse:main".to_string(),
+            ARG_TARGET => "purse:secondary".to_string(),
+            ARG_AMOUNT => U512::from(PURSE_TO_PURSE_AMOUNT),
+        },
+    )
+    .with_protocol_version(*NEW_PROTOCOL_VERSION)
+    .build();
+
+    builder.exec(exec_request).expect_success().commit();
+
+    builder.last_exec_gas_cost()
+}
+
+#[ignore]
+#[test]
+fn should_charge_configured_host_function_cost() {
+    let base_gas_cost = purse_to_purse_transfer_gas_cost(0);
+    let single_gas_cost = purse_to_purse_transfer_gas_cost(TRANSFER_FROM_PURSE_TO_PURSE_COST);
+    let double_gas_cost = purse_to_purse_transfer_gas_cost(TRANSFER_FROM_PURSE_TO_PURSE_COST * 2);
+
+    let single_transfer_charge = single_gas_cost - base_gas_cost;
+    let double_transfer_charge = double_gas_cost - base_gas_cost;
+
+    assert_eq!(
+        single_transfer_charge,
+        Gas::new(U512::from(TRANSFER_FROM_PURSE_TO_PURSE_COST))
+    );
+    assert_eq!(
+        double_transfer_charge,
+        Gas::new(U512::from(TRANSFER_FROM_PURSE_TO_PURSE_COST * 2))
+    );
+}
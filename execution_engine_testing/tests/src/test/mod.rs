@@ -10,6 +10,7 @@ mod groups;
 mod host_function_costs;
 mod manage_groups;
 mod regression;
+mod snapshot;
 mod step;
 mod storage_costs;
 mod system_contracts;
@@ -10,12 +10,9 @@ use casper_engine_test_support::{
     DEFAULT_ACCOUNT_ADDR, DEFAULT_ACCOUNT_INITIAL_BALANCE,
 };
 use casper_execution_engine::{
-    core::{
-        engine_state::{
-            genesis::{GenesisAccount, GenesisValidator},
-            Error,
-        },
-        execution,
+    core::engine_state::{
+        genesis::{GenesisAccount, GenesisValidator},
+        Error,
     },
     shared::{motes::Motes, wasm::do_nothing_bytes, wasm_prep::PreprocessingError},
 };
@@ -24,7 +21,7 @@ use casper_types::{
     contracts::DEFAULT_ENTRY_POINT_NAME,
     runtime_args,
     system::auction::{self, DelegationRate},
-    PublicKey, RuntimeArgs, SecretKey, U512,
+    Phase, PublicKey, RuntimeArgs, SecretKey, U512,
 };
 
 const ENTRY_POINT_NAME: &str = "create_purse";
@@ -102,7 +99,13 @@ fn should_run_ee_1129_underfunded_delegate_call() {
         .expect("should have error");
 
     assert!(
-        matches!(error, Error::Exec(execution::Error::GasLimit)),
+        matches!(
+            error,
+            Error::GasLimitExceeded {
+                phase: Phase::Session,
+                ..
+            }
+        ),
         "Unexpected error {:?}",
         error
     );
@@ -166,7 +169,13 @@ fn should_run_ee_1129_underfunded_add_bid_call() {
         .expect("should have error");
 
     assert!(
-        matches!(error, Error::Exec(execution::Error::GasLimit)),
+        matches!(
+            error,
+            Error::GasLimitExceeded {
+                phase: Phase::Session,
+                ..
+            }
+        ),
         "Unexpected error {:?}",
         error
     );
@@ -214,7 +223,13 @@ fn should_run_ee_1129_underfunded_mint_contract_call() {
         .expect("should have error");
 
     assert!(
-        matches!(error, Error::Exec(execution::Error::GasLimit)),
+        matches!(
+            error,
+            Error::GasLimitExceeded {
+                phase: Phase::Session,
+                ..
+            }
+        ),
         "Unexpected error {:?}",
         error
     );
@@ -7,17 +7,28 @@ use casper_engine_test_support::{
     },
     DEFAULT_ACCOUNT_ADDR,
 };
-use casper_types::{account::AccountHash, runtime_args, RuntimeArgs, U512};
+use casper_execution_engine::{
+    core::{engine_state::Error, execution},
+    shared::motes::Motes,
+};
+use casper_types::{account::AccountHash, runtime_args, AccessRights, Key, RuntimeArgs, U512};
 
 const CONTRACT_EE_599_REGRESSION: &str = "ee_599_regression.wasm";
 const CONTRACT_TRANSFER_TO_ACCOUNT: &str = "transfer_to_account_u512.wasm";
+const BATCH_PURSE_PREFIX: &str = "batch_purse";
 const DONATION_PURSE_COPY_KEY: &str = "donation_purse_copy";
-const EXPECTED_ERROR: &str = "InvalidContext";
 const TRANSFER_FUNDS_KEY: &str = "transfer_funds";
 const VICTIM_ADDR: AccountHash = AccountHash::new([42; 32]);
 
 static VICTIM_INITIAL_FUNDS: Lazy<U512> = Lazy::new(|| *DEFAULT_PAYMENT * 10);
 
+fn victim_funds_after_fee(transaction_fee: U512) -> U512 {
+    Motes::new(*VICTIM_INITIAL_FUNDS)
+        .checked_sub(Motes::new(transaction_fee))
+        .expect("transaction fee should not exceed victim's funds")
+        .value()
+}
+
 fn setup() -> InMemoryWasmTestBuilder {
     // Creates victim account
     let exec_request_1 = {
@@ -91,22 +102,16 @@ fn should_not_be_able_to_transfer_funds_with_transfer_purse_to_purse() {
 
     let transaction_fee = builder.get_proposer_purse_balance() - proposer_reward_starting_balance;
 
-    let error_msg = result_2
-        .builder()
-        .exec_error_message(0)
-        .expect("should have error");
-    assert!(
-        error_msg.contains(EXPECTED_ERROR),
-        "Got error: {}",
-        error_msg
-    );
+    result_2.builder().expect_exec_error(0, |error| {
+        matches!(error, Error::Exec(execution::Error::InvalidContext(_)))
+    });
 
     let victim_balance_after = result_2
         .builder()
         .get_purse_balance(victim_account.main_purse());
 
     assert_eq!(
-        *VICTIM_INITIAL_FUNDS - transaction_fee,
+        victim_funds_after_fee(transaction_fee),
         victim_balance_after
     );
 
@@ -116,6 +121,48 @@ fn should_not_be_able_to_transfer_funds_with_transfer_purse_to_purse() {
     );
 }
 
+#[ignore]
+#[test]
+fn invalid_context_error_should_identify_illegally_accessed_purse() {
+    let mut builder = setup();
+
+    let victim_account = builder
+        .get_account(VICTIM_ADDR)
+        .expect("should have victim account");
+
+    let default_account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have default account");
+    let transfer_funds = default_account
+        .named_keys()
+        .get(TRANSFER_FUNDS_KEY)
+        .cloned()
+        .unwrap_or_else(|| panic!("should have {}", TRANSFER_FUNDS_KEY));
+
+    let exec_request_3 = {
+        let args = runtime_args! {
+            "method" => "call",
+            "contract_key" => transfer_funds.into_hash().expect("should be hash"),
+            "sub_contract_method_fwd" => "transfer_from_purse_to_purse_ext",
+        };
+        ExecuteRequestBuilder::standard(VICTIM_ADDR, CONTRACT_EE_599_REGRESSION, args).build()
+    };
+
+    builder.exec(exec_request_3).commit();
+
+    // The contract tried to get hold of the victim's main purse, to which it has no access
+    let error = builder.get_error().expect("should have error");
+    match &error {
+        Error::Exec(execution::Error::InvalidContext(Some(access))) => {
+            assert_eq!(access.key, Key::URef(victim_account.main_purse()));
+            assert_eq!(access.required, AccessRights::READ_ADD_WRITE);
+            assert_eq!(access.held, AccessRights::NONE);
+        }
+        _ => panic!("unexpected error: {:?}", error),
+    }
+    assert!(error.to_string().contains("InvalidContext"));
+}
+
 #[ignore]
 #[test]
 fn should_not_be_able_to_transfer_funds_with_transfer_from_purse_to_account() {
@@ -159,17 +206,14 @@ fn should_not_be_able_to_transfer_funds_with_transfer_from_purse_to_account() {
 
     let transaction_fee = builder.get_proposer_purse_balance() - proposer_reward_starting_balance;
 
-    let error_msg = builder.exec_error_message(0).expect("should have error");
-    assert!(
-        error_msg.contains(EXPECTED_ERROR),
-        "Got error: {}",
-        error_msg
-    );
+    builder.expect_exec_error(0, |error| {
+        matches!(error, Error::Exec(execution::Error::InvalidContext(_)))
+    });
 
     let victim_balance_after = builder.get_purse_balance(victim_account.main_purse());
 
     assert_eq!(
-        *VICTIM_INITIAL_FUNDS - transaction_fee,
+        victim_funds_after_fee(transaction_fee),
         victim_balance_after
     );
     // In this variant of test `donation_purse` is left unchanged i.e. zero balance
@@ -227,22 +271,16 @@ fn should_not_be_able_to_transfer_funds_with_transfer_to_account() {
 
     let transaction_fee = builder.get_proposer_purse_balance() - proposer_reward_starting_balance;
 
-    let error_msg = result_2
-        .builder()
-        .exec_error_message(0)
-        .expect("should have error");
-    assert!(
-        error_msg.contains(EXPECTED_ERROR),
-        "Got error: {}",
-        error_msg
-    );
+    result_2.builder().expect_exec_error(0, |error| {
+        matches!(error, Error::Exec(execution::Error::InvalidContext(_)))
+    });
 
     let victim_balance_after = result_2
         .builder()
         .get_purse_balance(victim_account.main_purse());
 
     assert_eq!(
-        *VICTIM_INITIAL_FUNDS - transaction_fee,
+        victim_funds_after_fee(transaction_fee),
         victim_balance_after
     );
 
@@ -299,15 +337,9 @@ fn should_not_be_able_to_get_main_purse_in_invalid_context() {
 
     let transaction_fee = builder.get_proposer_purse_balance() - proposer_reward_starting_balance;
 
-    let error_msg = result_2
-        .builder()
-        .exec_error_message(0)
-        .expect("should have error");
-    assert!(
-        error_msg.contains(EXPECTED_ERROR),
-        "Got error: {}",
-        error_msg
-    );
+    result_2.builder().expect_exec_error(0, |error| {
+        matches!(error, Error::Exec(execution::Error::InvalidContext(_)))
+    });
 
     let victim_balance_after = result_2
         .builder()
@@ -318,3 +350,142 @@ fn should_not_be_able_to_get_main_purse_in_invalid_context() {
         victim_balance_after
     );
 }
+
+#[ignore]
+#[test]
+fn should_not_be_able_to_transfer_funds_with_transfer_from_purse_to_purses() {
+    let mut builder = setup();
+
+    let victim_account = builder
+        .get_account(VICTIM_ADDR)
+        .expect("should have victim account");
+
+    let default_account = builder
+        .get_account(*DEFAULT_ACCOUNT_ADDR)
+        .expect("should have default account");
+    let transfer_funds = default_account
+        .named_keys()
+        .get(TRANSFER_FUNDS_KEY)
+        .cloned()
+        .unwrap_or_else(|| panic!("should have {}", TRANSFER_FUNDS_KEY));
+    let donation_purse_copy_key = default_account
+        .named_keys()
+        .get(DONATION_PURSE_COPY_KEY)
+        .cloned()
+        .unwrap_or_else(|| panic!("should have {}", DONATION_PURSE_COPY_KEY));
+
+    let donation_purse_copy = donation_purse_copy_key.into_uref().expect("should be uref");
+
+    let exec_request_3 = {
+        let args = runtime_args! {
+            "method" => "call",
+            "contract_key" => transfer_funds.into_hash().expect("should be hash"),
+            "sub_contract_method_fwd" => "transfer_from_purse_to_purses_ext",
+        };
+        ExecuteRequestBuilder::standard(VICTIM_ADDR, CONTRACT_EE_599_REGRESSION, args).build()
+    };
+
+    let proposer_reward_starting_balance = builder.get_proposer_purse_balance();
+
+    builder.exec(exec_request_3).commit();
+
+    let transaction_fee = builder.get_proposer_purse_balance() - proposer_reward_starting_balance;
+
+    builder.expect_exec_error(0, |error| {
+        matches!(error, Error::Exec(execution::Error::InvalidContext(_)))
+    });
+
+    let victim_balance_after = builder.get_purse_balance(victim_account.main_purse());
+
+    assert_eq!(
+        victim_funds_after_fee(transaction_fee),
+        victim_balance_after
+    );
+
+    // None of the transfers of the batch took effect
+    assert_eq!(builder.get_purse_balance(donation_purse_copy), U512::zero());
+}
+
+#[ignore]
+#[test]
+fn should_transfer_funds_with_transfer_from_purse_to_purses_in_valid_context() {
+    let mut builder = setup();
+
+    let amounts = vec![U512::from(1), U512::from(2), U512::from(3)];
+
+    let exec_request_3 = {
+        let args = runtime_args! {
+            "method" => "batch_transfer",
+            "amounts" => amounts.clone(),
+        };
+        ExecuteRequestBuilder::standard(VICTIM_ADDR, CONTRACT_EE_599_REGRESSION, args).build()
+    };
+
+    let proposer_reward_starting_balance = builder.get_proposer_purse_balance();
+
+    builder.exec(exec_request_3).expect_success().commit();
+
+    let transaction_fee = builder.get_proposer_purse_balance() - proposer_reward_starting_balance;
+
+    let victim_account = builder
+        .get_account(VICTIM_ADDR)
+        .expect("should have victim account");
+
+    for (index, amount) in amounts.iter().enumerate() {
+        let batch_purse = victim_account
+            .named_keys()
+            .get(&format!("{}_{}", BATCH_PURSE_PREFIX, index))
+            .and_then(|key| key.into_uref())
+            .expect("should have batch purse");
+        assert_eq!(builder.get_purse_balance(batch_purse), *amount);
+    }
+
+    let total_amount: U512 = amounts
+        .into_iter()
+        .fold(U512::zero(), |sum, amount| sum + amount);
+    assert_eq!(
+        builder.get_purse_balance(victim_account.main_purse()),
+        victim_funds_after_fee(transaction_fee) - total_amount
+    );
+}
+
+#[ignore]
+#[test]
+fn should_not_make_partial_transfers_with_transfer_from_purse_to_purses() {
+    let mut builder = setup();
+
+    // The last transfer exceeds the victim's funds, so the whole batch fails
+    let amounts = vec![U512::from(1), *VICTIM_INITIAL_FUNDS + U512::one()];
+
+    let exec_request_3 = {
+        let args = runtime_args! {
+            "method" => "batch_transfer",
+            "amounts" => amounts,
+        };
+        ExecuteRequestBuilder::standard(VICTIM_ADDR, CONTRACT_EE_599_REGRESSION, args).build()
+    };
+
+    let proposer_reward_starting_balance = builder.get_proposer_purse_balance();
+
+    builder.exec(exec_request_3).commit();
+
+    let transaction_fee = builder.get_proposer_purse_balance() - proposer_reward_starting_balance;
+
+    builder.expect_exec_error(0, |error| {
+        matches!(error, Error::Exec(execution::Error::Revert(_)))
+    });
+
+    let victim_account = builder
+        .get_account(VICTIM_ADDR)
+        .expect("should have victim account");
+
+    // Only the fee was taken from the victim and no purses were stored
+    assert_eq!(
+        builder.get_purse_balance(victim_account.main_purse()),
+        victim_funds_after_fee(transaction_fee)
+    );
+    assert!(!victim_account
+        .named_keys()
+        .keys()
+        .any(|name| name.starts_with(BATCH_PURSE_PREFIX)));
+}
@@ -132,19 +132,19 @@ fn gh_1470_call_contract_should_verify_group_access() {
 
     match (&call_contract_error, &call_versioned_contract_error) {
         (
-            Error::Exec(execution::Error::InvalidContext),
-            Error::Exec(execution::Error::InvalidContext),
+            Error::Exec(execution::Error::InvalidContext(_)),
+            Error::Exec(execution::Error::InvalidContext(_)),
         ) => (),
         _ => panic!("Both variants should raise same error."),
     }
 
     assert!(matches!(
         call_versioned_contract_error,
-        Error::Exec(execution::Error::InvalidContext)
+        Error::Exec(execution::Error::InvalidContext(_))
     ));
     assert!(matches!(
         call_contract_error,
-        Error::Exec(execution::Error::InvalidContext)
+        Error::Exec(execution::Error::InvalidContext(_))
     ));
 }
 
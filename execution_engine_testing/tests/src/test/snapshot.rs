@@ -0,0 +1,65 @@
+use once_cell::sync::Lazy;
+
+use casper_engine_test_support::{
+    internal::{ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST},
+    DEFAULT_ACCOUNT_ADDR,
+};
+use casper_types::{account::AccountHash, runtime_args, RuntimeArgs, U512};
+
+const ARG_TARGET: &str = "target";
+const ARG_AMOUNT: &str = "amount";
+const ARG_ID: &str = "id";
+const ALICE_ADDR: AccountHash = AccountHash::new([42; 32]);
+
+static TRANSFER_AMOUNT: Lazy<U512> = Lazy::new(|| U512::from(100_000_000));
+
+fn transfer_to_alice(builder: &mut InMemoryWasmTestBuilder) {
+    let transfer_request = ExecuteRequestBuilder::transfer(
+        *DEFAULT_ACCOUNT_ADDR,
+        runtime_args! {
+            ARG_TARGET => ALICE_ADDR,
+            ARG_AMOUNT => *TRANSFER_AMOUNT,
+            ARG_ID => <Option<u64>>::None,
+        },
+    )
+    .build();
+
+    builder.exec(transfer_request).expect_success().commit();
+}
+
+fn alice_balance(builder: &InMemoryWasmTestBuilder) -> U512 {
+    let alice_account = builder
+        .get_account(ALICE_ADDR)
+        .expect("should have Alice's account");
+    builder.get_purse_balance(alice_account.main_purse())
+}
+
+#[ignore]
+#[test]
+fn should_restore_identical_state_from_snapshot() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST);
+    transfer_to_alice(&mut builder);
+
+    let snapshot = builder.snapshot();
+    let snapshot_state_hash = builder.get_post_state_hash();
+    assert_eq!(snapshot.post_state_hash(), Some(snapshot_state_hash));
+
+    // Changes made after restoring must not leak into later restores.
+    builder.restore(&snapshot);
+    assert_eq!(builder.get_post_state_hash(), snapshot_state_hash);
+    assert_eq!(alice_balance(&builder), *TRANSFER_AMOUNT);
+    transfer_to_alice(&mut builder);
+    assert_ne!(builder.get_post_state_hash(), snapshot_state_hash);
+    assert_eq!(alice_balance(&builder), *TRANSFER_AMOUNT * 2);
+
+    builder.restore(&snapshot);
+    assert_eq!(builder.get_post_state_hash(), snapshot_state_hash);
+    assert_eq!(builder.get_exec_results_count(), 0);
+    assert_eq!(alice_balance(&builder), *TRANSFER_AMOUNT);
+
+    let mut other_builder = InMemoryWasmTestBuilder::default();
+    other_builder.restore(&snapshot);
+    assert_eq!(other_builder.get_post_state_hash(), snapshot_state_hash);
+    assert_eq!(alice_balance(&other_builder), *TRANSFER_AMOUNT);
+}
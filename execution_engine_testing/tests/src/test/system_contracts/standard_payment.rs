@@ -14,7 +14,7 @@ use casper_execution_engine::{
     },
     shared::{gas::Gas, motes::Motes, transform::Transform},
 };
-use casper_types::{account::AccountHash, runtime_args, ApiError, RuntimeArgs, U512};
+use casper_types::{account::AccountHash, runtime_args, ApiError, Phase, RuntimeArgs, U512};
 
 const ACCOUNT_1_ADDR: AccountHash = AccountHash::new([42u8; 32]);
 const DO_NOTHING_WASM: &str = "do_nothing.wasm";
@@ -208,7 +208,13 @@ fn should_forward_payment_execution_gas_limit_error() {
 
     let execution_result = utils::get_success_result(response);
     let error = execution_result.as_error().expect("should have error");
-    assert_matches!(error, Error::Exec(execution::Error::GasLimit));
+    assert_matches!(
+        error,
+        Error::GasLimitExceeded {
+            phase: Phase::Payment,
+            ..
+        }
+    );
     let payment_gas_limit = Gas::from_motes(Motes::new(*MAX_PAYMENT), DEFAULT_GAS_PRICE)
         .expect("should convert to gas");
     assert_eq!(
@@ -255,7 +261,13 @@ fn should_run_out_of_gas_when_session_code_exceeds_gas_limit() {
 
     let execution_result = utils::get_success_result(response);
     let error = execution_result.as_error().expect("should have error");
-    assert_matches!(error, Error::Exec(execution::Error::GasLimit));
+    assert_matches!(
+        error,
+        Error::GasLimitExceeded {
+            phase: Phase::Session,
+            ..
+        }
+    );
     let session_gas_limit = Gas::from_motes(Motes::new(payment_purse_amount), DEFAULT_GAS_PRICE)
         .expect("should convert to gas");
     assert_eq!(
@@ -318,7 +330,13 @@ fn should_correctly_charge_when_session_code_runs_out_of_gas() {
 
     let execution_result = utils::get_success_result(response);
     let error = execution_result.as_error().expect("should have error");
-    assert_matches!(error, Error::Exec(execution::Error::GasLimit));
+    assert_matches!(
+        error,
+        Error::GasLimitExceeded {
+            phase: Phase::Session,
+            ..
+        }
+    );
     let session_gas_limit = Gas::from_motes(Motes::new(payment_purse_amount), DEFAULT_GAS_PRICE)
         .expect("should convert to gas");
     assert_eq!(
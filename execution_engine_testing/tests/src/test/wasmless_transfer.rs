@@ -7,7 +7,10 @@ use casper_engine_test_support::{
 };
 use casper_execution_engine::{
     core::{
-        engine_state::{Error as CoreError, WASMLESS_TRANSFER_FIXED_GAS_PRICE},
+        engine_state::{
+            engine_config::DEFAULT_MAX_QUERY_DEPTH, EngineConfig, Error as CoreError,
+            WASMLESS_TRANSFER_FIXED_GAS_PRICE,
+        },
         execution::Error as ExecError,
     },
     shared::{
@@ -596,6 +599,60 @@ fn transfer_wasmless_should_create_target_if_it_doesnt_exist() {
     );
 }
 
+#[ignore]
+#[test]
+fn transfer_wasmless_should_charge_at_configured_gas_price() {
+    const WASMLESS_TRANSFER_GAS_PRICE: u64 = 3;
+
+    let engine_config = EngineConfig::new(DEFAULT_MAX_QUERY_DEPTH, WASMLESS_TRANSFER_GAS_PRICE);
+    let mut builder = init_wasmless_transform_builder_with_config(false, engine_config);
+    let transfer_amount: U512 = U512::from(1000);
+
+    let account_1_purse = builder
+        .get_account(ACCOUNT_1_ADDR)
+        .expect("should get account 1")
+        .main_purse();
+    let account_1_starting_balance = builder.get_purse_balance(account_1_purse);
+
+    let runtime_args = runtime_args! {
+       mint::ARG_TARGET => ACCOUNT_2_ADDR,
+       mint::ARG_AMOUNT => transfer_amount,
+       mint::ARG_ID => <Option<u64>>::None
+    };
+
+    let no_wasm_transfer_request = {
+        let deploy_item = DeployItemBuilder::new()
+            .with_address(ACCOUNT_1_ADDR)
+            .with_empty_payment_bytes(runtime_args! {})
+            .with_transfer_args(runtime_args)
+            .with_authorization_keys(&[ACCOUNT_1_ADDR])
+            .build();
+        ExecuteRequestBuilder::from_deploy_item(deploy_item).build()
+    };
+
+    builder
+        .exec(no_wasm_transfer_request)
+        .expect_success()
+        .commit();
+
+    let wasmless_transfer_gas_cost = Gas::from(DEFAULT_WASMLESS_TRANSFER_COST);
+    let wasmless_transfer_cost =
+        Motes::from_gas(wasmless_transfer_gas_cost, WASMLESS_TRANSFER_GAS_PRICE)
+            .expect("gas overflow");
+    let default_wasmless_transfer_cost = Motes::from_gas(
+        wasmless_transfer_gas_cost,
+        WASMLESS_TRANSFER_FIXED_GAS_PRICE,
+    )
+    .expect("gas overflow");
+    assert_ne!(wasmless_transfer_cost, default_wasmless_transfer_cost);
+
+    assert_eq!(
+        account_1_starting_balance - transfer_amount - wasmless_transfer_cost.value(),
+        builder.get_purse_balance(account_1_purse),
+        "account 1 should be charged at the configured gas price"
+    );
+}
+
 fn get_default_account_named_uref(builder: &mut InMemoryWasmTestBuilder, name: &str) -> URef {
     let default_account = builder
         .get_account(*DEFAULT_ACCOUNT_ADDR)
@@ -610,7 +667,14 @@ fn get_default_account_named_uref(builder: &mut InMemoryWasmTestBuilder, name: &
 }
 
 fn init_wasmless_transform_builder(create_account_2: bool) -> InMemoryWasmTestBuilder {
-    let mut builder = InMemoryWasmTestBuilder::default();
+    init_wasmless_transform_builder_with_config(create_account_2, EngineConfig::default())
+}
+
+fn init_wasmless_transform_builder_with_config(
+    create_account_2: bool,
+    engine_config: EngineConfig,
+) -> InMemoryWasmTestBuilder {
+    let mut builder = InMemoryWasmTestBuilder::new_with_config(engine_config);
 
     let id: Option<u64> = None;
 
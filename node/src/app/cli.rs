@@ -11,13 +11,15 @@ use std::{
 };
 
 use anyhow::{self, Context};
+use once_cell::sync::Lazy;
 use regex::Regex;
 use structopt::StructOpt;
 use toml::{value::Table, Value};
 use tracing::{error, info, warn};
 
 use casper_node::{
-    logging,
+    crypto::hash::Digest,
+    logging::{self, LoggingFormat},
     reactor::{initializer, joiner, participating, ReactorExit, Runner},
     setup_signal_hooks,
     types::ExitCode,
@@ -56,6 +58,17 @@ pub enum Cli {
         /// Overrides and extensions for configuration file entries in the form
         /// <SECTION>.<KEY>=<VALUE>.  For example, '-C=node.chainspec_config_path=chainspec.toml'
         config_ext: Vec<ConfigExt>,
+
+        #[structopt(long, parse(try_from_str = Digest::from_hex))]
+        /// Hex-encoded hash which the loaded chainspec must have.  The node refuses to start if
+        /// the chainspec's hash differs, overriding any 'node.expected_chainspec_hash'
+        /// config entry.
+        expected_chainspec_hash: Option<Digest>,
+
+        #[structopt(long, possible_values = &["text", "json"])]
+        /// Output format of the logs, either 'text' or 'json'.  Overrides any 'logging.format'
+        /// config entry.
+        log_format: Option<LoggingFormat>,
     },
     /// Migrate modified values from the old config as required after an upgrade.
     MigrateConfig {
@@ -142,12 +155,19 @@ impl Cli {
     /// Executes selected CLI command.
     pub async fn run(self) -> anyhow::Result<i32> {
         match self {
-            Cli::Validator { config, config_ext } => {
+            Cli::Validator {
+                config,
+                config_ext,
+                expected_chainspec_hash,
+                log_format,
+            } => {
                 // Setup UNIX signal hooks.
                 setup_signal_hooks();
 
-                let validator_config = Self::init(&config, config_ext)?;
+                let validator_config =
+                    Self::init(&config, config_ext, expected_chainspec_hash, log_format)?;
                 info!(version = %casper_node::VERSION_STRING.as_str(), "node starting up");
+                Lazy::force(&casper_node::NODE_START_TIME);
 
                 let pidfile_outcome = {
                     // Determine storage directory to store pidfile in.
@@ -247,7 +267,7 @@ impl Cli {
                 old_config,
                 new_config,
             } => {
-                let new_config = Self::init(&new_config, vec![])?;
+                let new_config = Self::init(&new_config, vec![], None, None)?;
 
                 let old_root = old_config
                     .parent()
@@ -266,7 +286,7 @@ impl Cli {
                 old_config,
                 new_config,
             } => {
-                let new_config = Self::init(&new_config, vec![])?;
+                let new_config = Self::init(&new_config, vec![], None, None)?;
 
                 let old_root = old_config
                     .parent()
@@ -285,9 +305,14 @@ impl Cli {
     }
 
     /// Parses the config file for the current version of casper-node, and initializes logging.
+    ///
+    /// If `expected_chainspec_hash` or `log_format` are given, they replace any value set in the
+    /// config file.
     fn init(
         config: &Path,
         config_ext: Vec<ConfigExt>,
+        expected_chainspec_hash: Option<Digest>,
+        log_format: Option<LoggingFormat>,
     ) -> anyhow::Result<WithDir<participating::Config>> {
         // Determine the parent directory of the configuration file, if any.
         // Otherwise, we default to `/`.
@@ -311,7 +336,13 @@ impl Cli {
         }
 
         // Create participating config, including any overridden values.
-        let participating_config: participating::Config = config_table.try_into()?;
+        let mut participating_config: participating::Config = config_table.try_into()?;
+        if expected_chainspec_hash.is_some() {
+            participating_config.node.expected_chainspec_hash = expected_chainspec_hash;
+        }
+        if let Some(log_format) = log_format {
+            participating_config.logging.set_format(log_format);
+        }
         logging::init_with_config(&participating_config.logging)?;
 
         Ok(WithDir::new(root, participating_config))
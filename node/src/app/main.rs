@@ -16,13 +16,15 @@ use structopt::StructOpt;
 use tokio::runtime::Builder;
 use tracing::info;
 
-use casper_node::MAX_THREAD_COUNT;
+use casper_node::{reactor, MAX_THREAD_COUNT};
 
 use cli::Cli;
 
 /// Aborting panic hook.
 ///
-/// Will exit the application using `abort` when an error occurs. Always shows a backtrace.
+/// Will exit the application using `abort` when an error occurs, unless the panic is caught by the
+/// reactor's panic boundary around event handling, in which case the reactor shuts down on its
+/// own. Always shows a backtrace.
 fn panic_hook(info: &PanicInfo) {
     let backtrace = Backtrace::new();
 
@@ -38,6 +40,10 @@ fn panic_hook(info: &PanicInfo) {
         eprintln!("{}", info);
     }
 
+    if reactor::in_panic_boundary() {
+        return;
+    }
+
     // Abort after a panic, even if only a worker thread panicked.
     process::abort()
 }
@@ -71,4 +71,29 @@ pub trait Component<REv> {
         rng: &mut NodeRng,
         event: Self::Event,
     ) -> Effects<Self::Event>;
+
+    /// Initializes the component, outputting zero or more effects.
+    ///
+    /// This is called once on every component by the reactor while wiring it, before any event is
+    /// dispatched to it, allowing it to start its work without a dedicated bootstrap event.  The
+    /// default implementation does nothing.
+    fn init(&mut self, _effect_builder: EffectBuilder<REv>) -> Effects<Self::Event> {
+        Effects::new()
+    }
+
+    /// Returns the name of the component, used to identify it in logs and metrics.
+    ///
+    /// This should be a short snake-case identifier, e.g. `storage`, which is unique among the
+    /// components of a reactor.
+    fn name(&self) -> &str;
+
+    /// Notifies the component that the reactor is shutting down, outputting zero or more effects.
+    ///
+    /// This is called once on every component when the reactor receives a fatal error or a
+    /// termination signal, allowing it to flush state or notify peers.  The reactor waits only a
+    /// bounded time for the returned effects to complete before the process exits, so they should
+    /// not depend on further events being handled.  The default implementation does nothing.
+    fn on_shutdown(&mut self, _effect_builder: EffectBuilder<REv>) -> Effects<Self::Event> {
+        Effects::new()
+    }
 }
@@ -11,13 +11,14 @@ mod metrics;
 mod tests;
 
 use std::{
+    cmp::Reverse,
     collections::{HashMap, HashSet},
     convert::Infallible,
     sync::Arc,
     time::Duration,
 };
 
-pub use config::Config;
+pub use config::{Config, DeployOrdering};
 use datasize::DataSize;
 use itertools::Itertools;
 use prometheus::{self, Registry};
@@ -133,6 +134,10 @@ where
     type Event = Event;
     type ConstructionError = Infallible;
 
+    fn name(&self) -> &str {
+        "block_proposer"
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -165,6 +170,7 @@ where
                     deploy_config: *deploy_config,
                     request_queue: Default::default(),
                     local_config: local_config.clone(),
+                    latest_block_timestamp: None,
                 };
 
                 // Replay postponed events onto new state.
@@ -192,13 +198,18 @@ where
             }
 
             (BlockProposerState::Ready(ref mut ready_state), event) => {
+                let is_prune = matches!(event, Event::Prune);
+                let pending_before = ready_state.sets.pending_count();
                 effects.extend(ready_state.handle_event(effect_builder, event));
 
                 // Update metrics after the effects have been applied.
-                self.metrics.pending_deploys.set(
-                    ready_state.sets.pending_deploys.len() as i64
-                        + ready_state.sets.pending_transfers.len() as i64,
-                );
+                let pending_after = ready_state.sets.pending_count();
+                if is_prune {
+                    self.metrics
+                        .expired_deploys
+                        .inc_by(pending_before.saturating_sub(pending_after) as u64);
+                }
+                self.metrics.pending_deploys.set(pending_after as i64);
             }
         };
 
@@ -222,6 +233,8 @@ struct BlockProposerReady {
     request_queue: RequestQueue,
     /// The block proposer configuration, containing local settings for selecting deploys.
     local_config: Config,
+    /// The timestamp of the latest finalized block, if any.
+    latest_block_timestamp: Option<Timestamp>,
 }
 
 impl BlockProposerReady {
@@ -258,12 +271,15 @@ impl BlockProposerReady {
                         .ignore()
                 }
             }
+            Event::Request(BlockProposerRequest::IsBufferFull { responder }) => {
+                responder.respond(self.is_buffer_full()).ignore()
+            }
             Event::BufferDeploy { hash, deploy_info } => {
                 self.add_deploy(Timestamp::now(), hash, *deploy_info);
                 Effects::new()
             }
             Event::Prune => {
-                let pruned = self.prune(Timestamp::now());
+                let pruned = self.prune(self.expiry_reference_time(Timestamp::now()));
                 debug!(%pruned, "pruned deploys from buffer");
 
                 // Re-trigger timer after `PRUNE_INTERVAL`.
@@ -277,6 +293,8 @@ impl BlockProposerReady {
                 Effects::new()
             }
             Event::FinalizedBlock(block) => {
+                self.latest_block_timestamp =
+                    self.latest_block_timestamp.max(Some(block.timestamp()));
                 let deploys = block.deploys_and_transfers_iter().collect_vec();
                 let mut height = block.height();
 
@@ -344,20 +362,35 @@ impl BlockProposerReady {
         info!(%hash, "added deploy to the buffer");
     }
 
+    /// Returns whether the buffer holds the maximum number of pending deploys and transfers.
+    fn is_buffer_full(&self) -> bool {
+        let max_pending_deploys = self.local_config.max_pending_deploys as usize;
+        max_pending_deploys != 0 && self.sets.pending_count() >= max_pending_deploys
+    }
+
     /// Notifies the block proposer that a block has been finalized.
     fn finalized_deploys<I>(&mut self, deploys: I)
     where
         I: IntoIterator<Item = DeployOrTransferHash>,
     {
-        for deploy_hash in deploys.into_iter() {
-            let (hash, remove_result) = match deploy_hash {
-                DeployOrTransferHash::Deploy(hash) => {
-                    (hash, self.sets.pending_deploys.remove(&hash))
-                }
-                DeployOrTransferHash::Transfer(hash) => {
-                    (hash, self.sets.pending_transfers.remove(&hash))
-                }
-            };
+        let deploy_hashes = deploys
+            .into_iter()
+            .map(DeployOrTransferHash::into)
+            .collect_vec();
+        self.remove_finalized(&deploy_hashes);
+    }
+
+    /// Permanently removes the given finalized deploys and transfers from the pending and proposed
+    /// sets, so that they are never proposed again.
+    fn remove_finalized(&mut self, deploy_hashes: &[DeployHash]) {
+        for hash in deploy_hashes {
+            self.sets.proposed.remove(hash);
+            let remove_result = self
+                .sets
+                .pending_deploys
+                .remove(hash)
+                .or_else(|| self.sets.pending_transfers.remove(hash));
+            let hash = *hash;
             match remove_result {
                 Some((deploy_info, _)) => {
                     self.sets.finalized_deploys.insert(hash, deploy_info.header);
@@ -370,6 +403,36 @@ impl BlockProposerReady {
         }
     }
 
+    /// Returns the given deploys and transfers from a proposed block which will not be finalized to
+    /// the pending sets, so that they can be proposed again.
+    fn return_proposed(&mut self, deploy_hashes: &[DeployHash]) {
+        for hash in deploy_hashes {
+            if self.sets.proposed.remove(hash).is_some() {
+                debug!(%hash, "returned deploy from orphaned proposal to the buffer");
+            }
+        }
+    }
+
+    /// Returns the deploys of any proposed blocks which have been orphaned.
+    ///
+    /// Finalized blocks have strictly increasing timestamps, so once a block with a timestamp no
+    /// earlier than a proposal's has been finalized without containing its deploys, the proposal
+    /// can no longer be finalized.
+    fn return_orphaned_proposals(&mut self) {
+        let latest_block_timestamp = match self.latest_block_timestamp {
+            Some(timestamp) => timestamp,
+            None => return,
+        };
+        let orphaned = self
+            .sets
+            .proposed
+            .iter()
+            .filter(|(_, proposal_timestamp)| **proposal_timestamp <= latest_block_timestamp)
+            .map(|(hash, _)| *hash)
+            .collect_vec();
+        self.return_proposed(&orphaned);
+    }
+
     /// Handles finalization of a block.
     fn handle_finalized_block<I, REv>(
         &mut self,
@@ -381,6 +444,7 @@ impl BlockProposerReady {
         I: IntoIterator<Item = DeployOrTransferHash>,
     {
         self.finalized_deploys(deploys);
+        self.return_orphaned_proposals();
         self.sets.next_finalized = self.sets.next_finalized.max(height + 1);
 
         if let Some(requests) = self.request_queue.remove(&self.sets.next_finalized) {
@@ -404,12 +468,15 @@ impl BlockProposerReady {
         }
     }
 
-    /// Checks if a deploy's dependencies are satisfied, so the deploy is eligible for inclusion.
-    fn deps_resolved(&self, header: &DeployHeader, past_deploys: &HashSet<DeployHash>) -> bool {
+    /// Checks if a deploy's dependencies are finalized, so the deploy is eligible for inclusion.
+    ///
+    /// Dependencies which are only included in ancestors of the proposed block are not enough, as
+    /// these could still be orphaned.
+    fn deps_resolved(&self, header: &DeployHeader) -> bool {
         header
             .dependencies()
             .iter()
-            .all(|dep| past_deploys.contains(dep) || self.contains_finalized(dep))
+            .all(|dep| self.contains_finalized(dep))
     }
 
     /// Returns a list of candidates for inclusion into a block.
@@ -420,7 +487,7 @@ impl BlockProposerReady {
         accusations: Vec<PublicKey>,
         random_bit: bool,
     ) -> Arc<BlockPayload> {
-        let past_deploys = context
+        let past_deploys: HashSet<DeployHash> = context
             .ancestor_values()
             .iter()
             .flat_map(|block_payload| block_payload.deploys_and_transfers_iter())
@@ -431,9 +498,11 @@ impl BlockProposerReady {
         let mut appendable_block = AppendableBlock::new(deploy_config, block_timestamp);
 
         // We prioritize transfers over deploys, so we try to include them first.
-        for (hash, (deploy_info, received_time)) in &self.sets.pending_transfers {
-            if !self.deps_resolved(&deploy_info.header, &past_deploys)
+        let pending_transfers = self.in_proposal_order(&self.sets.pending_transfers);
+        for (hash, (deploy_info, received_time)) in pending_transfers {
+            if !self.deps_resolved(&deploy_info.header)
                 || past_deploys.contains(hash)
+                || self.sets.proposed.contains_key(hash)
                 || self.contains_finalized(hash)
                 || block_timestamp.saturating_diff(*received_time) < self.local_config.deploy_delay
             {
@@ -455,9 +524,11 @@ impl BlockProposerReady {
         }
 
         // Now we try to add other deploys to the block.
-        for (hash, (deploy_info, received_time)) in &self.sets.pending_deploys {
-            if !self.deps_resolved(&deploy_info.header, &past_deploys)
+        let pending_deploys = self.in_proposal_order(&self.sets.pending_deploys);
+        for (hash, (deploy_info, received_time)) in pending_deploys {
+            if !self.deps_resolved(&deploy_info.header)
                 || past_deploys.contains(hash)
+                || self.sets.proposed.contains_key(hash)
                 || self.contains_finalized(hash)
                 || block_timestamp.saturating_diff(*received_time) < self.local_config.deploy_delay
             {
@@ -489,7 +560,44 @@ impl BlockProposerReady {
             }
         }
 
-        Arc::new(appendable_block.into_block_payload(accusations, random_bit))
+        let block_payload = appendable_block.into_block_payload(accusations, random_bit);
+        for hash in block_payload.deploys_and_transfers_iter() {
+            self.sets.proposed.insert(hash.into(), block_timestamp);
+        }
+        Arc::new(block_payload)
+    }
+
+    /// Returns the given pending deploys in the order in which they should be considered for
+    /// inclusion in a block, according to the configured `DeployOrdering`.
+    fn in_proposal_order<'a>(
+        &self,
+        pending: &'a HashMap<DeployHash, (DeployInfo, Timestamp)>,
+    ) -> Vec<(&'a DeployHash, &'a (DeployInfo, Timestamp))> {
+        let mut ordered = pending.iter().collect_vec();
+        match self.local_config.deploy_ordering {
+            DeployOrdering::ArrivalTime => {
+                ordered.sort_by_key(|(hash, (_, received_time))| (*received_time, **hash))
+            }
+            DeployOrdering::GasPrice => {
+                ordered.sort_by_key(|(hash, (deploy_info, received_time))| {
+                    (
+                        Reverse(deploy_info.header.gas_price()),
+                        *received_time,
+                        **hash,
+                    )
+                })
+            }
+        }
+        ordered
+    }
+
+    /// Returns the time relative to which deploys are considered expired: the later of `now` and
+    /// the timestamp of the latest finalized block.
+    ///
+    /// Deploys which are expired at this time can't be included in any future block.
+    fn expiry_reference_time(&self, now: Timestamp) -> Timestamp {
+        self.latest_block_timestamp
+            .map_or(now, |block_timestamp| block_timestamp.max(now))
     }
 
     /// Prunes expired deploy information from the BlockProposer, returns the total deploys pruned.
@@ -3,6 +3,22 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::TimeDiff;
 
+/// The order in which pending deploys are considered for inclusion in a proposed block.
+#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeployOrdering {
+    /// Deploys received earlier are proposed first.
+    ArrivalTime,
+    /// Deploys with a higher gas price are proposed first, ties being broken by arrival time.
+    GasPrice,
+}
+
+impl Default for DeployOrdering {
+    fn default() -> Self {
+        DeployOrdering::ArrivalTime
+    }
+}
+
 /// Block proposer configuration.
 #[derive(DataSize, Debug, Deserialize, Serialize, Clone)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
@@ -13,12 +29,23 @@ pub struct Config {
     /// other nodes, and don't have to be requested from the proposer afterwards.
     #[serde(default = "default_deploy_delay")]
     pub deploy_delay: TimeDiff,
+    /// The order in which pending deploys and transfers are added to a proposed block, until its
+    /// limits are reached.
+    #[serde(default)]
+    pub deploy_ordering: DeployOrdering,
+    /// The maximum number of pending deploys and transfers held in the buffer. Further deploys
+    /// from clients are refused by the deploy acceptor until some of the pending ones have
+    /// been proposed or have expired. Deploys from peers are still buffered. Unlimited if 0.
+    #[serde(default)]
+    pub max_pending_deploys: u32,
 }
 
 impl Default for Config {
     fn default() -> Config {
         Config {
             deploy_delay: default_deploy_delay(),
+            deploy_ordering: DeployOrdering::default(),
+            max_pending_deploys: 0,
         }
     }
 }
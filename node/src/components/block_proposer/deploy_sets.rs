@@ -17,6 +17,10 @@ pub(super) struct BlockProposerDeploySets {
     /// The collection of transfers pending for inclusion in a block, with a timestamp of when we
     /// received them.
     pub(super) pending_transfers: HashMap<DeployHash, (DeployInfo, Timestamp)>,
+    /// The pending deploys and transfers which have been included in a proposed block that has not
+    /// yet been finalized or orphaned, with the timestamp of that block.  These are not proposed
+    /// again in a concurrent block.
+    pub(super) proposed: HashMap<DeployHash, Timestamp>,
     /// The deploys that have already been included in a finalized block.
     pub(super) finalized_deploys: HashMap<DeployHash, DeployHeader>,
     /// The next block height we expect to be finalized.
@@ -48,19 +52,29 @@ impl Display for BlockProposerDeploySets {
         write!(
             f,
             "(pending:{}, finalized:{})",
-            self.pending_deploys.len() + self.pending_transfers.len(),
+            self.pending_count(),
             self.finalized_deploys.len()
         )
     }
 }
 
 impl BlockProposerDeploySets {
+    /// Returns the number of pending deploys and transfers.
+    pub(super) fn pending_count(&self) -> usize {
+        self.pending_deploys.len() + self.pending_transfers.len()
+    }
+
     /// Prunes expired deploy information from the BlockProposerState, returns the total deploys
     /// pruned
     pub(crate) fn prune(&mut self, current_instant: Timestamp) -> usize {
         let pending_deploys = prune_pending_deploys(&mut self.pending_deploys, current_instant);
         let pending_transfers = prune_pending_deploys(&mut self.pending_transfers, current_instant);
         let finalized = prune_deploys(&mut self.finalized_deploys, current_instant);
+        let pending_deploys_map = &self.pending_deploys;
+        let pending_transfers_map = &self.pending_transfers;
+        self.proposed.retain(|hash, _| {
+            pending_deploys_map.contains_key(hash) || pending_transfers_map.contains_key(hash)
+        });
         pending_deploys + pending_transfers + finalized
     }
 }
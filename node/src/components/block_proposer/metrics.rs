@@ -1,5 +1,5 @@
 use datasize::DataSize;
-use prometheus::{self, IntGauge, Registry};
+use prometheus::{self, IntCounter, IntGauge, Registry};
 
 use crate::unregister_metric;
 
@@ -9,6 +9,9 @@ pub(super) struct BlockProposerMetrics {
     /// Amount of pending deploys
     #[data_size(skip)]
     pub(super) pending_deploys: IntGauge,
+    /// Number of pending deploys evicted after their time-to-live elapsed.
+    #[data_size(skip)]
+    pub(super) expired_deploys: IntCounter,
     /// Registry stored to allow deregistration later.
     #[data_size(skip)]
     registry: Registry,
@@ -18,9 +21,15 @@ impl BlockProposerMetrics {
     /// Creates a new instance of the block proposer metrics.
     pub fn new(registry: Registry) -> Result<Self, prometheus::Error> {
         let pending_deploys = IntGauge::new("pending_deploy", "amount of pending deploys")?;
+        let expired_deploys = IntCounter::new(
+            "block_proposer_expired_deploys",
+            "number of pending deploys evicted after their time-to-live elapsed",
+        )?;
         registry.register(Box::new(pending_deploys.clone()))?;
+        registry.register(Box::new(expired_deploys.clone()))?;
         Ok(BlockProposerMetrics {
             pending_deploys,
+            expired_deploys,
             registry,
         })
     }
@@ -29,5 +38,6 @@ impl BlockProposerMetrics {
 impl Drop for BlockProposerMetrics {
     fn drop(&mut self) {
         unregister_metric!(self.registry, self.pending_deploys);
+        unregister_metric!(self.registry, self.expired_deploys);
     }
 }
@@ -90,7 +90,10 @@ fn generate_deploy(
 
 fn create_test_proposer(deploy_delay: TimeDiff) -> BlockProposerReady {
     BlockProposerReady {
-        local_config: Config { deploy_delay },
+        local_config: Config {
+            deploy_delay,
+            ..Default::default()
+        },
         ..Default::default()
     }
 }
@@ -207,17 +210,17 @@ fn should_add_and_take_deploys() {
     assert!(block.deploy_hashes().contains(deploy1.id()));
     assert!(block.deploy_hashes().contains(deploy2.id()));
 
-    // take the deploys out
-    let block = proposer.propose_block_payload(
+    // they shouldn't be returned again in a concurrent proposal
+    let concurrent_block = proposer.propose_block_payload(
         DeployConfig::default(),
         BlockContext::new(block_time2, vec![]),
         vec![],
         true,
     );
-    assert!(block.transfer_hashes().is_empty());
-    assert_eq!(block.deploy_hashes().len(), 2);
+    assert!(concurrent_block.deploy_hashes().is_empty());
+    assert!(concurrent_block.transfer_hashes().is_empty());
 
-    // but they shouldn't be returned if we include it in the past deploys
+    // nor if we include it in the past deploys
     let deploy_hashes = block.deploys_and_transfers_iter().collect_vec();
     let block = proposer.propose_block_payload(
         DeployConfig::default(),
@@ -702,6 +705,15 @@ fn should_return_deploy_dependencies() {
     assert_eq!(deploys.len(), 1);
     assert!(deploys.contains(&deploy1.deploy_or_transfer_hash()));
 
+    // deploy1 is in an ancestor block, but not finalized yet, so deploy2 must wait for it
+    let child = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(block_time, vec![block]),
+        vec![],
+        true,
+    );
+    assert!(child.deploy_hashes().is_empty());
+
     // the deploy will be included in block 1
     proposer.finalized_deploys(deploys.iter().copied());
 
@@ -754,3 +766,227 @@ fn should_respect_deploy_delay() {
     );
     assert_eq!(&vec![*deploy.id()], block.deploy_hashes());
 }
+
+#[test]
+fn should_report_full_buffer_but_keep_deploys_received_while_full() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(0);
+    let ttl = TimeDiff::from(10000);
+    let mut proposer = create_test_proposer(0.into());
+    proposer.local_config.max_pending_deploys = 2;
+
+    let deploys: Vec<_> = (0..3)
+        .map(|_| {
+            generate_deploy(
+                &mut rng,
+                creation_time,
+                ttl,
+                vec![],
+                default_gas_payment(),
+                DEFAULT_TEST_GAS_PRICE,
+            )
+        })
+        .collect();
+    for deploy in &deploys[..2] {
+        assert!(!proposer.is_buffer_full());
+        proposer.add_deploy(
+            100.into(),
+            deploy.deploy_or_transfer_hash(),
+            deploy.deploy_info().unwrap(),
+        );
+    }
+    assert!(proposer.is_buffer_full());
+
+    // Deploys are only refused by the deploy acceptor: one which was accepted regardless, e.g.
+    // because it was received from a peer, is still buffered.
+    proposer.add_deploy(
+        100.into(),
+        deploys[2].deploy_or_transfer_hash(),
+        deploys[2].deploy_info().unwrap(),
+    );
+    assert_eq!(proposer.sets.pending_count(), 3);
+    assert!(proposer.sets.pending_deploys.contains_key(deploys[2].id()));
+    assert!(proposer.is_buffer_full());
+
+    // Once enough deploys were finalized, there is room for another one.
+    proposer.finalized_deploys(vec![
+        deploys[0].deploy_or_transfer_hash(),
+        deploys[1].deploy_or_transfer_hash(),
+    ]);
+    assert!(!proposer.is_buffer_full());
+}
+
+#[test]
+fn should_propose_highest_gas_price_deploys_within_gas_limit() {
+    const GAS_PER_DEPLOY: u64 = 10;
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(0);
+    let ttl = TimeDiff::from(10000);
+    let block_time = Timestamp::from(1000);
+    let mut deploy_config = DeployConfig::default();
+    deploy_config.block_gas_limit = 3 * GAS_PER_DEPLOY;
+
+    // Each deploy pays for the same amount of gas at a different price.
+    let deploys = [5, 1, 9, 3, 7, 2]
+        .iter()
+        .map(|gas_price| {
+            generate_deploy(
+                &mut rng,
+                creation_time,
+                ttl,
+                vec![],
+                Gas::from(GAS_PER_DEPLOY * gas_price),
+                *gas_price,
+            )
+        })
+        .collect_vec();
+    let hashes_with_gas_prices = |gas_prices: &[u64]| -> HashSet<DeployHash> {
+        deploys
+            .iter()
+            .filter(|deploy| gas_prices.contains(&deploy.header().gas_price()))
+            .map(|deploy| *deploy.id())
+            .collect()
+    };
+
+    let mut arrival_time_proposer = create_test_proposer(0.into());
+    let mut gas_price_proposer = create_test_proposer(0.into());
+    gas_price_proposer.local_config.deploy_ordering = DeployOrdering::GasPrice;
+    for (index, deploy) in deploys.iter().enumerate() {
+        let received_time = Timestamp::from(index as u64);
+        for proposer in &mut [&mut arrival_time_proposer, &mut gas_price_proposer] {
+            proposer.add_deploy(
+                received_time,
+                deploy.deploy_or_transfer_hash(),
+                deploy.deploy_info().unwrap(),
+            );
+        }
+    }
+
+    // By default, the earliest deploys fitting within the block gas limit are proposed.
+    let block = arrival_time_proposer.propose_block_payload(
+        deploy_config,
+        BlockContext::new(block_time, vec![]),
+        vec![],
+        true,
+    );
+    let proposed: HashSet<DeployHash> = block.deploy_hashes().iter().copied().collect();
+    assert_eq!(proposed, hashes_with_gas_prices(&[5, 1, 9]));
+
+    // When ordering by gas price, the highest-paying deploys fitting within the limit are proposed.
+    let block = gas_price_proposer.propose_block_payload(
+        deploy_config,
+        BlockContext::new(block_time, vec![]),
+        vec![],
+        true,
+    );
+    let proposed: HashSet<DeployHash> = block.deploy_hashes().iter().copied().collect();
+    assert_eq!(proposed, hashes_with_gas_prices(&[9, 7, 5]));
+}
+
+#[test]
+fn should_drop_deploys_expired_at_latest_block_timestamp() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(100);
+    let local_time = Timestamp::from(150);
+    let latest_block_timestamp = Timestamp::from(201);
+    let deploy = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE,
+    );
+    let mut proposer = create_test_proposer(0.into());
+    proposer.add_deploy(
+        creation_time,
+        deploy.deploy_or_transfer_hash(),
+        deploy.deploy_info().unwrap(),
+    );
+
+    // Before it is swept, the deploy must not be proposed in a block past its time-to-live.
+    let block = proposer.propose_block_payload(
+        DeployConfig::default(),
+        BlockContext::new(latest_block_timestamp, vec![]),
+        vec![],
+        true,
+    );
+    assert!(block.deploy_hashes().is_empty());
+    assert_eq!(proposer.sets.pending_deploys.len(), 1);
+
+    // The deploy is still live relative to the local clock, but not to the latest block.
+    assert_eq!(proposer.expiry_reference_time(local_time), local_time);
+    proposer.latest_block_timestamp = Some(latest_block_timestamp);
+    let reference_time = proposer.expiry_reference_time(local_time);
+    assert_eq!(reference_time, latest_block_timestamp);
+
+    assert_eq!(proposer.prune(reference_time), 1);
+    assert!(proposer.sets.pending_deploys.is_empty());
+}
+
+#[test]
+fn should_return_deploys_of_orphaned_proposals_only() {
+    let mut rng = crate::new_rng();
+    let creation_time = Timestamp::from(100);
+    let ttl = TimeDiff::from(Duration::from_millis(1000));
+    let first_proposal_time = Timestamp::from(200);
+    let second_proposal_time = Timestamp::from(300);
+    let mut proposer = create_test_proposer(0.into());
+
+    let propose = |proposer: &mut BlockProposerReady, block_time: Timestamp| {
+        proposer
+            .propose_block_payload(
+                DeployConfig::default(),
+                BlockContext::new(block_time, vec![]),
+                vec![],
+                true,
+            )
+            .deploy_hashes()
+            .clone()
+    };
+
+    let deploy1 = generate_deploy(
+        &mut rng,
+        creation_time,
+        ttl,
+        vec![],
+        default_gas_payment(),
+        DEFAULT_TEST_GAS_PRICE,
+    );
+    proposer.add_deploy(
+        creation_time,
+        deploy1.deploy_or_transfer_hash(),
+        deploy1.deploy_info().unwrap(),
+    );
+
+    // deploy1 is proposed, and not proposed again while that proposal is outstanding.
+    assert_eq!(
+        propose(&mut proposer, first_proposal_time),
+        vec![*deploy1.id()]
+    );
+    assert!(propose(&mut proposer, first_proposal_time).is_empty());
+
+    // A block not containing deploy1 is finalized at the proposal's timestamp, so the proposal
+    // has been orphaned and deploy1 should be proposed again.
+    proposer.latest_block_timestamp = Some(first_proposal_time);
+    proposer.return_orphaned_proposals();
+    assert_eq!(proposer.sets.pending_deploys.len(), 1);
+    assert_eq!(
+        propose(&mut proposer, second_proposal_time),
+        vec![*deploy1.id()]
+    );
+
+    // The second proposal is still outstanding after the earlier block, so isn't returned.
+    proposer.return_orphaned_proposals();
+    assert!(proposer.sets.proposed.contains_key(deploy1.id()));
+
+    // Once the second proposal is finalized, deploy1 is removed permanently.
+    proposer.remove_finalized(&[*deploy1.id()]);
+    proposer.latest_block_timestamp = Some(second_proposal_time);
+    proposer.return_orphaned_proposals();
+    assert!(proposer.sets.pending_deploys.is_empty());
+    assert!(proposer.sets.proposed.is_empty());
+    assert!(proposer.contains_finalized(deploy1.id()));
+    assert!(propose(&mut proposer, second_proposal_time).is_empty());
+}
@@ -6,6 +6,11 @@
 //! When multiple requests are made to validate the same block payload, they will eagerly return
 //! true if valid, but only fail if all sources have been exhausted. This is only relevant when
 //! calling for validation of the same protoblock multiple times at the same time.
+//!
+//! The approvals of each retrieved deploy are verified concurrently on blocking threads, with the
+//! number of concurrent verifications bounded by the number of CPUs.  A block is rejected as soon
+//! as any of its deploys is found to be invalid, and only accepted once all have been verified, so
+//! the outcome does not depend on the order in which the verifications complete.
 
 mod keyed_counter;
 #[cfg(test)]
@@ -23,7 +28,9 @@ use datasize::DataSize;
 use derive_more::{Display, From};
 use itertools::Itertools;
 use smallvec::{smallvec, SmallVec};
-use tracing::info;
+use thiserror::Error;
+use tokio::sync::Semaphore;
+use tracing::{info, warn};
 
 use crate::{
     components::{
@@ -36,8 +43,9 @@ use crate::{
         EffectBuilder, EffectExt, EffectOptionExt, Effects, Responder,
     },
     types::{
-        appendable_block::AppendableBlock, Block, Chainspec, Deploy, DeployHash,
-        DeployOrTransferHash, Timestamp,
+        appendable_block::{AddError, AppendableBlock},
+        chainspec::DeployConfig,
+        Block, Chainspec, Deploy, DeployHash, DeployOrTransferHash, Timestamp,
     },
     NodeRng,
 };
@@ -66,6 +74,14 @@ impl From<ProposedBlock<ClContext>> for ValidatingBlock {
 }
 
 impl ValidatingBlock {
+    /// Returns the block's height, or `None` for a proposed block, whose height is not known.
+    fn height(&self) -> Option<u64> {
+        match self {
+            ValidatingBlock::Block(block) => Some(block.height()),
+            ValidatingBlock::ProposedBlock(_) => None,
+        }
+    }
+
     fn timestamp(&self) -> Timestamp {
         match self {
             ValidatingBlock::Block(block) => block.timestamp(),
@@ -100,6 +116,105 @@ impl ValidatingBlock {
     }
 }
 
+/// Error describing why a deploy is invalid for inclusion in the block being validated.
+#[derive(Debug, Error, PartialEq)]
+pub(crate) enum BlockValidationError {
+    /// The deploy's timestamp is later than the block's.
+    #[error(
+        "deploy {deploy_hash} has timestamp {deploy_timestamp} later than block timestamp \
+        {block_timestamp}"
+    )]
+    DeployFromFuture {
+        /// The hash of the offending deploy.
+        deploy_hash: DeployHash,
+        /// The timestamp of the deploy.
+        deploy_timestamp: Timestamp,
+        /// The timestamp of the block.
+        block_timestamp: Timestamp,
+    },
+    /// The deploy's time-to-live has elapsed at the block's timestamp.
+    #[error("deploy {deploy_hash} expired at {expiry}, before block timestamp {block_timestamp}")]
+    DeployExpired {
+        /// The hash of the offending deploy.
+        deploy_hash: DeployHash,
+        /// The time at which the deploy expired.
+        expiry: Timestamp,
+        /// The timestamp of the block.
+        block_timestamp: Timestamp,
+    },
+    /// The block contains more deploys than permitted by the chainspec.
+    #[error("block contains {got} deploys, exceeding the limit of {max_deploy_count}")]
+    ExcessiveDeployCount {
+        /// The maximum number of deploys per block.
+        max_deploy_count: u32,
+        /// The number of deploys in the block.
+        got: usize,
+    },
+    /// The block contains more transfers than permitted by the chainspec.
+    #[error("block contains {got} transfers, exceeding the limit of {max_transfer_count}")]
+    ExcessiveTransferCount {
+        /// The maximum number of transfers per block.
+        max_transfer_count: u32,
+        /// The number of transfers in the block.
+        got: usize,
+    },
+    /// The cumulative gas of the block's deploys exceeds the limit permitted by the chainspec.
+    #[error("deploy {deploy_hash} takes the block's gas over the limit of {block_gas_limit}")]
+    ExcessiveGas {
+        /// The hash of the deploy which takes the block over its gas limit.
+        deploy_hash: DeployHash,
+        /// The maximum cumulative gas of deploys in a block.
+        block_gas_limit: u64,
+    },
+}
+
+/// Checks that the block contains no more deploys and transfers than permitted.
+fn check_deploy_counts(
+    block: &ValidatingBlock,
+    deploy_config: &DeployConfig,
+) -> Result<(), BlockValidationError> {
+    let deploy_count = block.deploy_hashes().len();
+    if deploy_count > deploy_config.block_max_deploy_count as usize {
+        return Err(BlockValidationError::ExcessiveDeployCount {
+            max_deploy_count: deploy_config.block_max_deploy_count,
+            got: deploy_count,
+        });
+    }
+    let transfer_count = block.transfer_hashes().len();
+    if transfer_count > deploy_config.block_max_transfer_count as usize {
+        return Err(BlockValidationError::ExcessiveTransferCount {
+            max_transfer_count: deploy_config.block_max_transfer_count,
+            got: transfer_count,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that a deploy has been created no later than the block's timestamp, and has not expired
+/// by then.
+fn check_deploy_timing(
+    deploy_hash: DeployHash,
+    deploy_info: &DeployInfo,
+    block_timestamp: Timestamp,
+) -> Result<(), BlockValidationError> {
+    let deploy_timestamp = deploy_info.header.timestamp();
+    if deploy_timestamp > block_timestamp {
+        return Err(BlockValidationError::DeployFromFuture {
+            deploy_hash,
+            deploy_timestamp,
+            block_timestamp,
+        });
+    }
+    if deploy_info.header.expired(block_timestamp) {
+        return Err(BlockValidationError::DeployExpired {
+            deploy_hash,
+            expiry: deploy_timestamp + deploy_info.header.ttl(),
+            block_timestamp,
+        });
+    }
+    Ok(())
+}
+
 /// Block validator component event.
 #[derive(Debug, From, Display)]
 pub enum Event<I> {
@@ -107,18 +222,37 @@ pub enum Event<I> {
     #[from]
     Request(BlockValidationRequest<I>),
 
-    /// A deploy has been successfully found.
+    /// A block has been added to the linear chain.
+    #[display(fmt = "block {} added", height)]
+    BlockAdded { height: u64 },
+
+    /// A deploy has been retrieved from storage or a peer, and its approvals need to be verified.
+    #[display(fmt = "{} received", dt_hash)]
+    DeployReceived {
+        dt_hash: DeployOrTransferHash,
+        deploy: Box<Deploy>,
+    },
+
+    /// A deploy has been successfully found and verified.
     #[display(fmt = "{} found", dt_hash)]
     DeployFound {
         dt_hash: DeployOrTransferHash,
         deploy_info: Box<DeployInfo>,
     },
 
+    /// A deploy is not held in local storage, and needs to be fetched from `sender`.
+    #[display(fmt = "{} not in storage", dt_hash)]
+    DeployNotStored {
+        dt_hash: DeployOrTransferHash,
+        sender: I,
+    },
+
     /// A request to find a specific deploy, potentially from a peer, failed.
     #[display(fmt = "{} missing", _0)]
     DeployMissing(DeployOrTransferHash),
 
-    /// Deploy was invalid. Unable to convert to a deploy type.
+    /// Deploy was invalid. Either its approvals failed verification, or it was unable to be
+    /// converted to a deploy type.
     #[display(fmt = "{} invalid", _0)]
     CannotConvertDeploy(DeployOrTransferHash),
 }
@@ -175,6 +309,12 @@ pub(crate) struct BlockValidator<I> {
     validation_states: HashMap<ValidatingBlock, BlockValidationState<I>>,
     /// Number of requests for a specific deploy hash still in flight.
     in_flight: KeyedCounter<DeployHash>,
+    /// Permits bounding the number of deploys being verified concurrently.
+    #[data_size(skip)]
+    verification_permits: Arc<Semaphore>,
+    /// The height assumed for proposed blocks when looking up the chainspec's deploy parameters:
+    /// one above the highest block added to the linear chain.
+    next_block_height: u64,
 }
 
 impl<I> BlockValidator<I>
@@ -182,14 +322,22 @@ where
     I: Clone + Debug + Send + 'static + Send,
 {
     /// Creates a new block validator instance.
-    pub(crate) fn new(chainspec: Arc<Chainspec>) -> Self {
+    pub(crate) fn new(chainspec: Arc<Chainspec>, next_block_height: u64) -> Self {
         BlockValidator {
             chainspec,
             validation_states: HashMap::new(),
             in_flight: KeyedCounter::default(),
+            verification_permits: Arc::new(Semaphore::new(num_cpus::get().max(1))),
+            next_block_height,
         }
     }
 
+    /// Returns the deploy parameters which apply to the given block.
+    fn deploy_config_for(&self, block: &ValidatingBlock) -> DeployConfig {
+        let height = block.height().unwrap_or(self.next_block_height);
+        *self.chainspec.deploy_config_at(height)
+    }
+
     /// Prints a log message about an invalid block with duplicated deploys.
     fn log_block_with_replay(&self, sender: I, block: &ValidatingBlock) {
         let mut deploy_counts = BTreeMap::new();
@@ -221,6 +369,10 @@ where
     type Event = Event<I>;
     type ConstructionError = Infallible;
 
+    fn name(&self) -> &str {
+        "block_validator"
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -245,6 +397,11 @@ where
                     self.log_block_with_replay(sender, &block);
                     return responder.respond(false).ignore();
                 }
+                let deploy_config = self.deploy_config_for(&block);
+                if let Err(error) = check_deploy_counts(&block, &deploy_config) {
+                    info!(?sender, %error, "received invalid block");
+                    return responder.respond(false).ignore();
+                }
 
                 match self.validation_states.entry(block) {
                     Entry::Occupied(mut entry) => {
@@ -263,17 +420,15 @@ where
                     }
                     Entry::Vacant(entry) => {
                         // Our entry is vacant - create an entry to track the state.
-                        let in_flight = &mut self.in_flight;
-                        effects.extend(entry.key().deploys_and_transfers_iter().flat_map(
-                            |dt_hash| {
-                                // For every request, increase the number of in-flight...
-                                in_flight.inc(&dt_hash.into());
-                                // ...then request it.
-                                fetch_deploy(effect_builder, dt_hash, sender.clone())
-                            },
-                        ));
+                        let dt_hashes = entry.key().deploys_and_transfers_iter().collect_vec();
+                        for dt_hash in &dt_hashes {
+                            // For every request, increase the number of in-flight...
+                            self.in_flight.inc(&(*dt_hash).into());
+                        }
+                        // ...then look all of them up in storage at once, fetching only the ones
+                        // we don't have.
+                        effects.extend(fetch_deploys(effect_builder, dt_hashes, sender));
                         let block_timestamp = entry.key().timestamp();
-                        let deploy_config = self.chainspec.deploy_config;
                         entry.insert(BlockValidationState {
                             appendable_block: AppendableBlock::new(deploy_config, block_timestamp),
                             missing_deploys: block_deploys,
@@ -284,6 +439,15 @@ where
                     }
                 }
             }
+            Event::BlockAdded { height } => {
+                self.next_block_height = self.next_block_height.max(height + 1);
+            }
+            Event::DeployReceived { dt_hash, deploy } => {
+                let permits = Arc::clone(&self.verification_permits);
+                effects.extend(
+                    verify_deploy(effect_builder, dt_hash, deploy, permits).event(|event| event),
+                );
+            }
             Event::DeployFound {
                 dt_hash,
                 deploy_info,
@@ -294,10 +458,19 @@ where
                 // If a deploy is received for a given block that makes that block invalid somehow,
                 // mark it for removal.
                 let mut invalid = Vec::new();
+                let chainspec = &self.chainspec;
+                let next_block_height = self.next_block_height;
 
                 // Our first pass updates all validation states, crossing off the found deploy.
                 for (key, state) in self.validation_states.iter_mut() {
                     if state.missing_deploys.remove(&dt_hash) {
+                        if let Err(error) =
+                            check_deploy_timing(dt_hash.into(), &deploy_info, key.timestamp())
+                        {
+                            info!(block = ?key, %error, "block invalid");
+                            invalid.push(key.clone());
+                            continue;
+                        }
                         // If the deploy is of the wrong type or would be invalid for this block,
                         // notify everyone still waiting on it that all is lost.
                         let add_result = match dt_hash {
@@ -308,9 +481,23 @@ where
                                 state.appendable_block.add_transfer(hash, &*deploy_info)
                             }
                         };
-                        if let Err(err) = add_result {
-                            info!(block = ?key, %dt_hash, ?deploy_info, ?err, "block invalid");
-                            invalid.push(key.clone());
+                        match add_result {
+                            Ok(()) => (),
+                            Err(AddError::GasLimit) => {
+                                let height = key.height().unwrap_or(next_block_height);
+                                let error = BlockValidationError::ExcessiveGas {
+                                    deploy_hash: dt_hash.into(),
+                                    block_gas_limit: chainspec
+                                        .deploy_config_at(height)
+                                        .block_gas_limit,
+                                };
+                                info!(block = ?key, %error, "block invalid");
+                                invalid.push(key.clone());
+                            }
+                            Err(err) => {
+                                info!(block = ?key, %dt_hash, ?deploy_info, ?err, "block invalid");
+                                invalid.push(key.clone());
+                            }
                         }
                     }
                 }
@@ -329,6 +516,22 @@ where
                     true
                 });
             }
+            Event::DeployNotStored { dt_hash, sender } => {
+                // Ask the peer which sent us the block first, then the other peers known to have
+                // sent a block containing the deploy.
+                let mut peers = vec![sender];
+                for state in self.validation_states.values() {
+                    if !state.missing_deploys.contains(&dt_hash) {
+                        continue;
+                    }
+                    for peer in &state.sources {
+                        if !peers.contains(peer) {
+                            peers.push(peer.clone());
+                        }
+                    }
+                }
+                effects.extend(fetch_deploy_from_peers(effect_builder, dt_hash, peers));
+            }
             Event::DeployMissing(dt_hash) => {
                 info!(%dt_hash, "request to download deploy timed out");
                 // A deploy failed to fetch. If there is still hope (i.e. other outstanding
@@ -401,7 +604,111 @@ where
     }
 }
 
-/// Returns effects that fetch the deploy and validate it.
+/// Returns the event for a deploy retrieved for `dt_hash`, either `DeployFound` or
+/// `CannotConvertDeploy`.
+fn validate_deploy<I>(dt_hash: DeployOrTransferHash, deploy: &Deploy) -> Event<I> {
+    (deploy.deploy_or_transfer_hash() == dt_hash)
+        .then(|| deploy.deploy_info().ok())
+        .flatten()
+        .map_or(Event::CannotConvertDeploy(dt_hash), |deploy_info| {
+            Event::DeployFound {
+                dt_hash,
+                deploy_info: Box::new(deploy_info),
+            }
+        })
+}
+
+/// Verifies the deploy's hashes and approvals on a blocking thread once a permit is available, then
+/// returns the event for the deploy, either `DeployFound` or `CannotConvertDeploy`.
+async fn verify_deploy<REv, I>(
+    effect_builder: EffectBuilder<REv>,
+    dt_hash: DeployOrTransferHash,
+    mut deploy: Box<Deploy>,
+    permits: Arc<Semaphore>,
+) -> Event<I> {
+    let _permit = match permits.acquire().await {
+        Ok(permit) => permit,
+        Err(error) => {
+            warn!(%error, "failed to acquire deploy verification permit");
+            return Event::CannotConvertDeploy(dt_hash);
+        }
+    };
+    let (deploy, verification) = effect_builder
+        .run_blocking(move || {
+            let result = deploy.is_valid();
+            (deploy, result)
+        })
+        .await;
+    match verification {
+        Ok(()) => validate_deploy(dt_hash, &deploy),
+        Err(error) => {
+            info!(%dt_hash, %error, "deploy failed verification");
+            Event::CannotConvertDeploy(dt_hash)
+        }
+    }
+}
+
+/// Returns effects that look up all the given deploys in storage in a single request, yielding a
+/// `DeployReceived` event for each of the ones found and a `DeployNotStored` event for each of the
+/// others.
+fn fetch_deploys<REv, I>(
+    effect_builder: EffectBuilder<REv>,
+    dt_hashes: Vec<DeployOrTransferHash>,
+    sender: I,
+) -> Effects<Event<I>>
+where
+    REv: From<StorageRequest> + Send,
+    I: Clone + Send + 'static,
+{
+    let deploy_hashes = dt_hashes
+        .iter()
+        .map(|dt_hash| DeployHash::from(*dt_hash))
+        .collect();
+    effect_builder
+        .get_deploys_from_storage(deploy_hashes)
+        .events(move |results| {
+            dt_hashes.into_iter().zip(results).map(
+                move |(dt_hash, maybe_deploy)| match maybe_deploy {
+                    Some(deploy) => Event::DeployReceived {
+                        dt_hash,
+                        deploy: Box::new(deploy),
+                    },
+                    None => Event::DeployNotStored {
+                        dt_hash,
+                        sender: sender.clone(),
+                    },
+                },
+            )
+        })
+}
+
+/// Returns effects that fetch the deploy, asking each of `peers` in turn until one provides it, and
+/// yielding a `DeployReceived` event if successful.
+fn fetch_deploy_from_peers<REv, I>(
+    effect_builder: EffectBuilder<REv>,
+    dt_hash: DeployOrTransferHash,
+    peers: Vec<I>,
+) -> Effects<Event<I>>
+where
+    REv: From<Event<I>>
+        + From<BlockValidationRequest<I>>
+        + From<StorageRequest>
+        + From<FetcherRequest<I, Deploy>>
+        + Send,
+    I: Clone + Send + PartialEq + Eq + 'static,
+{
+    let validate_fetch_result = move |result: FetchResult<Deploy, I>| match result {
+        FetchResult::FromStorage(deploy) | FetchResult::FromPeer(deploy, _) => {
+            Event::DeployReceived { dt_hash, deploy }
+        }
+    };
+
+    effect_builder
+        .fetch_deploy_from_peers(dt_hash.into(), peers)
+        .map_or_else(validate_fetch_result, move || Event::DeployMissing(dt_hash))
+}
+
+/// Returns effects that fetch the deploy, yielding a `DeployReceived` event if successful.
 fn fetch_deploy<REv, I>(
     effect_builder: EffectBuilder<REv>,
     dt_hash: DeployOrTransferHash,
@@ -415,21 +722,13 @@ where
         + Send,
     I: Clone + Send + PartialEq + Eq + 'static,
 {
-    let validate_deploy = move |result: FetchResult<Deploy, I>| match result {
+    let validate_fetch_result = move |result: FetchResult<Deploy, I>| match result {
         FetchResult::FromStorage(deploy) | FetchResult::FromPeer(deploy, _) => {
-            (deploy.deploy_or_transfer_hash() == dt_hash)
-                .then(|| deploy)
-                .and_then(|deploy| deploy.deploy_info().ok())
-                .map_or(Event::CannotConvertDeploy(dt_hash), |deploy_info| {
-                    Event::DeployFound {
-                        dt_hash,
-                        deploy_info: Box::new(deploy_info),
-                    }
-                })
+            Event::DeployReceived { dt_hash, deploy }
         }
     };
 
     effect_builder
         .fetch_deploy(dt_hash.into(), sender)
-        .map_or_else(validate_deploy, move || Event::DeployMissing(dt_hash))
+        .map_or_else(validate_fetch_result, move || Event::DeployMissing(dt_hash))
 }
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
 use casper_types::{
@@ -7,13 +7,14 @@ use casper_types::{
 };
 use derive_more::From;
 use itertools::Itertools;
+use rand::seq::SliceRandom;
 
 use crate::{
     components::{consensus::BlockContext, fetcher::FetchResult},
     crypto::AsymmetricKeyExt,
     reactor::{EventQueueHandle, QueueKind, Scheduler},
     testing::TestRng,
-    types::{BlockPayload, TimeDiff},
+    types::{chainspec::HeightActivationPoint, BlockPayload, TimeDiff},
     utils::{self, Loadable},
 };
 
@@ -49,7 +50,7 @@ impl MockReactor {
     }
 
     async fn expect_block_validator_event(&self) -> Event<NodeId> {
-        let (reactor_event, _) = self.scheduler.pop().await;
+        let ((reactor_event, _), _) = self.scheduler.pop().await;
         if let ReactorEvent::BlockValidator(event) = reactor_event {
             event
         } else {
@@ -57,29 +58,55 @@ impl MockReactor {
         }
     }
 
-    async fn expect_fetch_deploy<T>(&self, deploy: T)
-    where
-        T: Into<Option<Deploy>>,
-    {
-        let (reactor_event, _) = self.scheduler.pop().await;
-        if let ReactorEvent::Fetcher(FetcherRequest::Fetch {
-            id,
-            peer,
+    async fn expect_get_deploys_from_storage(&self, stored_deploys: &[Deploy]) {
+        let ((reactor_event, _), _) = self.scheduler.pop().await;
+        if let ReactorEvent::Storage(StorageRequest::GetDeploys {
+            deploy_hashes,
             responder,
         }) = reactor_event
         {
-            match deploy.into() {
-                None => responder.respond(None).await,
-                Some(deploy) => {
-                    assert_eq!(id, *deploy.id());
-                    let response = FetchResult::FromPeer(Box::new(deploy), peer);
-                    responder.respond(Some(response)).await;
-                }
-            }
+            let results = deploy_hashes
+                .iter()
+                .map(|deploy_hash| {
+                    stored_deploys
+                        .iter()
+                        .find(|deploy| deploy.id() == deploy_hash)
+                        .cloned()
+                })
+                .collect();
+            responder.respond(results).await;
         } else {
             panic!("unexpected event: {:?}", reactor_event);
         }
     }
+
+    async fn expect_fetch_deploy<T>(&self, deploy: T)
+    where
+        T: Into<Option<Deploy>>,
+    {
+        let ((reactor_event, _), _) = self.scheduler.pop().await;
+        let (id, peer, responder) = match reactor_event {
+            ReactorEvent::Fetcher(FetcherRequest::Fetch {
+                id,
+                peer,
+                responder,
+            }) => (id, peer, responder),
+            ReactorEvent::Fetcher(FetcherRequest::FetchFromPeers {
+                id,
+                peers,
+                responder,
+            }) => (id, peers[0], responder),
+            other => panic!("unexpected event: {:?}", other),
+        };
+        match deploy.into() {
+            None => responder.respond(None).await,
+            Some(deploy) => {
+                assert_eq!(id, *deploy.id());
+                let response = FetchResult::FromPeer(Box::new(deploy), peer);
+                responder.respond(Some(response)).await;
+            }
+        }
+    }
 }
 
 fn new_proposed_block(
@@ -151,6 +178,40 @@ async fn validate_block(
     timestamp: Timestamp,
     deploys: Vec<Deploy>,
     transfers: Vec<Deploy>,
+) -> bool {
+    validate_block_with_stored_deploys(rng, timestamp, deploys, transfers, &[]).await
+}
+
+/// Validates a block using a `BlockValidator` component, with `stored_deploys` already held in
+/// local storage, and returns the result.
+async fn validate_block_with_stored_deploys(
+    rng: &mut TestRng,
+    timestamp: Timestamp,
+    deploys: Vec<Deploy>,
+    transfers: Vec<Deploy>,
+    stored_deploys: &[Deploy],
+) -> bool {
+    let deploy_config = Chainspec::from_resources("local").deploy_config;
+    validate_block_with_deploy_config(
+        rng,
+        deploy_config,
+        timestamp,
+        deploys,
+        transfers,
+        stored_deploys,
+    )
+    .await
+}
+
+/// Validates a block using a `BlockValidator` component with the given deploy config in its
+/// chainspec, with `stored_deploys` already held in local storage, and returns the result.
+async fn validate_block_with_deploy_config(
+    rng: &mut TestRng,
+    deploy_config: DeployConfig,
+    timestamp: Timestamp,
+    deploys: Vec<Deploy>,
+    transfers: Vec<Deploy>,
+    stored_deploys: &[Deploy],
 ) -> bool {
     // Assemble the block to be validated.
     let deploy_hashes = deploys.iter().map(|deploy| *deploy.id()).collect_vec();
@@ -160,8 +221,9 @@ async fn validate_block(
     // Create the reactor and component.
     let reactor = MockReactor::new();
     let effect_builder = EffectBuilder::new(EventQueueHandle::new(reactor.scheduler));
-    let chainspec = Arc::new(Chainspec::from_resources("local"));
-    let mut block_validator = BlockValidator::<NodeId>::new(chainspec);
+    let mut chainspec = Chainspec::from_resources("local");
+    chainspec.deploy_config = deploy_config;
+    let mut block_validator = BlockValidator::<NodeId>::new(Arc::new(chainspec), 0);
 
     // Pass the block to the component. This future will eventually resolve to the result, i.e.
     // whether the block is valid or not.
@@ -179,30 +241,57 @@ async fn validate_block(
         return validation_result.await.unwrap();
     }
 
-    // Otherwise the effects must be requests to fetch the block's deploys.
-    let fetch_results: Vec<_> = effects.into_iter().map(tokio::spawn).collect();
+    // Otherwise the effect must be a single request to look up all the block's deploys in storage.
+    assert_eq!(1, effects.len());
+    let storage_results: Vec<_> = effects.into_iter().map(tokio::spawn).collect();
+    reactor
+        .expect_get_deploys_from_storage(stored_deploys)
+        .await;
 
-    // We make our mock reactor answer with the expected deploys and transfers:
-    for deploy in deploys.into_iter().chain(transfers) {
-        reactor.expect_fetch_deploy(deploy).await;
+    // The deploys found in storage are verified straight away, the others need to be fetched.
+    let mut effects = Effects::new();
+    for storage_result in storage_results {
+        for event in storage_result.await.unwrap() {
+            effects.extend(block_validator.handle_event(effect_builder, rng, event));
+        }
     }
+    let mut pending: Vec<_> = effects.into_iter().map(tokio::spawn).collect();
 
-    // The resulting `FetchResult`s are passed back into the component. When any deploy turns out
-    // to be invalid, or once all of them have been validated, the component will respond.
-    let mut effects = Effects::new();
-    for fetch_result in fetch_results {
-        let events = fetch_result.await.unwrap();
-        assert_eq!(1, events.len());
-        effects.extend(events.into_iter().flat_map(|found_deploy| {
-            block_validator.handle_event(effect_builder, rng, found_deploy)
-        }));
+    // We make our mock reactor answer with the expected deploys and transfers not in storage:
+    for deploy in deploys
+        .into_iter()
+        .chain(transfers)
+        .filter(|deploy| !stored_deploys.contains(deploy))
+    {
+        reactor.expect_fetch_deploy(deploy).await;
     }
 
-    // We expect exactly one effect: the validation response. This will resolve the result.
-    assert_eq!(1, effects.len());
-    for effect in effects {
-        tokio::spawn(effect).await.unwrap(); // Response.
+    // The resulting events are passed back into the component until no further effects remain:
+    // fetched deploys are verified, and when any deploy turns out to be invalid, or once all of
+    // them have been verified, the component will respond.  Every fetch and verification yields
+    // exactly one event, and we expect exactly one effect yielding none: the validation response.
+    // Since verifications run concurrently, their events are passed back in random order.  This
+    // will resolve the result.
+    let mut responses = 0;
+    while !pending.is_empty() {
+        let mut events = Vec::new();
+        for handle in pending {
+            let handle_events = handle.await.unwrap();
+            if handle_events.is_empty() {
+                responses += 1;
+                continue;
+            }
+            assert_eq!(1, handle_events.len());
+            events.extend(handle_events);
+        }
+        events.shuffle(rng);
+        let mut effects = Effects::new();
+        for event in events {
+            effects.extend(block_validator.handle_event(effect_builder, rng, event));
+        }
+        pending = effects.into_iter().map(tokio::spawn).collect();
     }
+    assert_eq!(1, responses);
     validation_result.await.unwrap()
 }
 
@@ -280,3 +369,333 @@ async fn transfer_deploy_mixup_and_replay() {
     let transfers = vec![transfer1.clone(), transfer2.clone(), transfer2.clone()];
     assert!(!validate_block(&mut rng, timestamp, deploys, transfers).await);
 }
+
+/// Verifies that deploys already held in local storage are not fetched from peers.
+#[tokio::test]
+async fn stored_deploys_are_not_fetched() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from(200);
+    let timestamp = Timestamp::from(1000);
+    let deploys = vec![
+        new_deploy(&mut rng, timestamp, ttl),
+        new_deploy(&mut rng, timestamp, ttl),
+    ];
+    let transfers = vec![new_transfer(&mut rng, timestamp, ttl)];
+    let all_deploys = deploys.iter().chain(&transfers).cloned().collect_vec();
+
+    // All deploys are in storage, so none of them are fetched.
+    assert!(
+        validate_block_with_stored_deploys(
+            &mut rng,
+            timestamp,
+            deploys.clone(),
+            transfers.clone(),
+            &all_deploys,
+        )
+        .await
+    );
+
+    // Only the first deploy is in storage, so the rest are fetched.
+    assert!(
+        validate_block_with_stored_deploys(
+            &mut rng,
+            timestamp,
+            deploys,
+            transfers,
+            &all_deploys[..1],
+        )
+        .await
+    );
+
+    // A transfer in storage hidden amongst the deploys still invalidates the block.
+    let deploys = vec![all_deploys[0].clone(), all_deploys[2].clone()];
+    assert!(
+        !validate_block_with_stored_deploys(&mut rng, timestamp, deploys, vec![], &all_deploys)
+            .await
+    );
+}
+
+/// Verifies that deploys created after the block, or expired by then, are reported by hash.
+#[test]
+fn deploy_timing_errors_name_offending_deploy() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from(200);
+    let block_timestamp = Timestamp::from(1000);
+
+    // A deploy created at the block's timestamp is well-formed.
+    let deploy = new_deploy(&mut rng, block_timestamp, ttl);
+    let deploy_info = deploy.deploy_info().unwrap();
+    assert_eq!(
+        check_deploy_timing(*deploy.id(), &deploy_info, block_timestamp),
+        Ok(())
+    );
+
+    // A future-dated deploy is rejected.
+    let future_deploy = new_deploy(&mut rng, 1001.into(), ttl);
+    let deploy_info = future_deploy.deploy_info().unwrap();
+    assert_eq!(
+        check_deploy_timing(*future_deploy.id(), &deploy_info, block_timestamp),
+        Err(BlockValidationError::DeployFromFuture {
+            deploy_hash: *future_deploy.id(),
+            deploy_timestamp: 1001.into(),
+            block_timestamp,
+        })
+    );
+
+    // So is one whose time-to-live elapsed before the block's timestamp.
+    let expired_deploy = new_deploy(&mut rng, 799.into(), ttl);
+    let deploy_info = expired_deploy.deploy_info().unwrap();
+    let error = check_deploy_timing(*expired_deploy.id(), &deploy_info, block_timestamp)
+        .expect_err("should be expired");
+    assert_eq!(
+        error,
+        BlockValidationError::DeployExpired {
+            deploy_hash: *expired_deploy.id(),
+            expiry: 999.into(),
+            block_timestamp,
+        }
+    );
+    assert!(error.to_string().contains(&expired_deploy.id().to_string()));
+}
+
+/// Verifies that blocks with more deploys or transfers than the chainspec permits are invalid.
+#[tokio::test]
+async fn deploy_and_transfer_count_limits() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from(200);
+    let timestamp = Timestamp::from(1000);
+    let mut deploy_config = Chainspec::from_resources("local").deploy_config;
+    deploy_config.block_max_deploy_count = 2;
+    deploy_config.block_max_transfer_count = 2;
+    let deploys = (0..3)
+        .map(|_| new_deploy(&mut rng, timestamp, ttl))
+        .collect_vec();
+    let transfers = (0..3)
+        .map(|_| new_transfer(&mut rng, timestamp, ttl))
+        .collect_vec();
+
+    // Exactly at the limits is valid.
+    assert!(
+        validate_block_with_deploy_config(
+            &mut rng,
+            deploy_config,
+            timestamp,
+            deploys[..2].to_vec(),
+            transfers[..2].to_vec(),
+            &[],
+        )
+        .await
+    );
+
+    // One over either limit is invalid.
+    assert!(
+        !validate_block_with_deploy_config(
+            &mut rng,
+            deploy_config,
+            timestamp,
+            deploys.clone(),
+            transfers[..2].to_vec(),
+            &[],
+        )
+        .await
+    );
+    assert!(
+        !validate_block_with_deploy_config(
+            &mut rng,
+            deploy_config,
+            timestamp,
+            deploys[..2].to_vec(),
+            transfers,
+            &[],
+        )
+        .await
+    );
+}
+
+/// Verifies that blocks whose deploys' cumulative gas exceeds the chainspec limit are invalid.
+#[tokio::test]
+async fn block_gas_limit() {
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from(200);
+    let timestamp = Timestamp::from(1000);
+    // Each deploy pays one mote at a gas price of one, so uses one unit of gas.
+    let mut deploy_config = Chainspec::from_resources("local").deploy_config;
+    deploy_config.block_gas_limit = 2;
+    let deploys = (0..3)
+        .map(|_| new_deploy(&mut rng, timestamp, ttl))
+        .collect_vec();
+
+    assert!(
+        validate_block_with_deploy_config(
+            &mut rng,
+            deploy_config,
+            timestamp,
+            deploys[..2].to_vec(),
+            vec![],
+            &[],
+        )
+        .await
+    );
+    assert!(
+        !validate_block_with_deploy_config(
+            &mut rng,
+            deploy_config,
+            timestamp,
+            deploys,
+            vec![],
+            &[]
+        )
+        .await
+    );
+}
+
+/// Verifies that excessive deploy counts are reported with the limit.
+#[test]
+fn deploy_count_errors_name_limit() {
+    let mut rng = TestRng::new();
+    let mut deploy_config = DeployConfig::default();
+    deploy_config.block_max_deploy_count = 1;
+    let deploy_hashes = vec![DeployHash::random(&mut rng), DeployHash::random(&mut rng)];
+    let block = ValidatingBlock::from(new_proposed_block(1000.into(), deploy_hashes, vec![]));
+    assert_eq!(
+        check_deploy_counts(&block, &deploy_config),
+        Err(BlockValidationError::ExcessiveDeployCount {
+            max_deploy_count: 1,
+            got: 2,
+        })
+    );
+}
+
+/// Verifies that a large block is validated, and that a single deploy with an invalid approval
+/// makes it invalid regardless of its position.
+#[tokio::test]
+async fn large_block_with_invalid_approval() {
+    const DEPLOY_COUNT: usize = 100;
+
+    let mut rng = TestRng::new();
+    let ttl = TimeDiff::from(200);
+    let timestamp = Timestamp::from(1000);
+    let deploys = (0..DEPLOY_COUNT)
+        .map(|_| new_deploy(&mut rng, timestamp, ttl))
+        .collect_vec();
+    let stored_deploys = &deploys[..DEPLOY_COUNT / 2];
+    assert!(
+        validate_block_with_stored_deploys(
+            &mut rng,
+            timestamp,
+            deploys.clone(),
+            vec![],
+            stored_deploys
+        )
+        .await
+    );
+
+    // Replace one deploy's approval with one signed over a different deploy's hash, in turn at the
+    // start, middle and end of the block.
+    let other_deploy = new_deploy(&mut rng, timestamp, ttl);
+    for index in [0, DEPLOY_COUNT / 2, DEPLOY_COUNT - 1].iter().copied() {
+        let mut invalid_deploys = deploys.clone();
+        let mut json_deploy = serde_json::to_value(&invalid_deploys[index]).unwrap();
+        json_deploy["approvals"] = serde_json::to_value(other_deploy.approvals()).unwrap();
+        invalid_deploys[index] = serde_json::from_value(json_deploy).unwrap();
+        let stored_deploys = invalid_deploys[..DEPLOY_COUNT / 2].to_vec();
+        assert!(
+            !validate_block_with_stored_deploys(
+                &mut rng,
+                timestamp,
+                invalid_deploys,
+                vec![],
+                &stored_deploys
+            )
+            .await
+        );
+    }
+}
+
+/// Verifies that the deploy parameters of the chainspec's height activation points apply to blocks
+/// at or above their heights, with proposed blocks assumed to follow the highest added block.
+#[test]
+fn deploy_config_follows_height_activation_points() {
+    const ACTIVATION_HEIGHT: u64 = 10;
+
+    let mut rng = TestRng::new();
+    let mut chainspec = Chainspec::from_resources("local");
+    let genesis_deploy_config = chainspec.deploy_config;
+    let mut upgraded_deploy_config = genesis_deploy_config;
+    upgraded_deploy_config.block_max_deploy_count =
+        genesis_deploy_config.block_max_deploy_count + 1;
+    chainspec.height_activation_points = vec![HeightActivationPoint {
+        height: ACTIVATION_HEIGHT,
+        deploys: upgraded_deploy_config,
+    }];
+
+    let reactor = MockReactor::new();
+    let effect_builder = EffectBuilder::new(EventQueueHandle::new(reactor.scheduler));
+    let mut block_validator =
+        BlockValidator::<NodeId>::new(Arc::new(chainspec), ACTIVATION_HEIGHT - 1);
+    let proposed_block = ValidatingBlock::from(new_proposed_block(1000.into(), vec![], vec![]));
+    assert_eq!(
+        block_validator.deploy_config_for(&proposed_block),
+        genesis_deploy_config
+    );
+
+    let effects = block_validator.handle_event(
+        effect_builder,
+        &mut rng,
+        Event::BlockAdded {
+            height: ACTIVATION_HEIGHT - 1,
+        },
+    );
+    assert!(effects.is_empty());
+    assert_eq!(
+        block_validator.deploy_config_for(&proposed_block),
+        upgraded_deploy_config
+    );
+
+    // Blocks with a known height use the parameters for that height, regardless of the highest
+    // added block.
+    let block = ValidatingBlock::from(Block::random(&mut rng));
+    let expected = if block.height().unwrap() < ACTIVATION_HEIGHT {
+        genesis_deploy_config
+    } else {
+        upgraded_deploy_config
+    };
+    assert_eq!(block_validator.deploy_config_for(&block), expected);
+}
+
+/// Verifies that a deploy is only verified once a permit is available, bounding the number of
+/// concurrent verifications.
+#[tokio::test]
+async fn deploy_verification_waits_for_permit() {
+    let mut rng = TestRng::new();
+    let reactor = MockReactor::new();
+    let effect_builder = EffectBuilder::new(EventQueueHandle::new(reactor.scheduler));
+    let deploy = new_deploy(&mut rng, 1000.into(), TimeDiff::from(200));
+    let dt_hash = deploy.deploy_or_transfer_hash();
+
+    let permits = Arc::new(Semaphore::new(1));
+    let held_permit = permits.try_acquire().unwrap();
+    let mut verification = tokio::spawn(verify_deploy::<_, NodeId>(
+        effect_builder,
+        dt_hash,
+        Box::new(deploy),
+        Arc::clone(&permits),
+    ));
+
+    // While the only permit is held, the verification cannot complete.
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), &mut verification)
+            .await
+            .is_err()
+    );
+
+    // Once the permit is released, the deploy is verified.
+    drop(held_permit);
+    match verification.await.unwrap() {
+        Event::DeployFound {
+            dt_hash: found_hash,
+            ..
+        } => assert_eq!(found_hash, dt_hash),
+        other => panic!("unexpected event: {}", other),
+    }
+}
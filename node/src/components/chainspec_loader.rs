@@ -50,7 +50,7 @@ use crate::{
     },
     reactor::ReactorExit,
     types::{
-        chainspec::{Error, ProtocolConfig, CHAINSPEC_NAME},
+        chainspec::{chainspec_file_path, ChainspecFormat, Error, ProtocolConfig},
         ActivationPoint, Block, BlockHeader, Chainspec, ChainspecInfo, ExitCode,
     },
     utils::{self, Loadable},
@@ -180,16 +180,23 @@ pub struct ChainspecLoader {
 }
 
 impl ChainspecLoader {
+    /// Loads the chainspec from `chainspec_dir`.
+    ///
+    /// If `expected_chainspec_hash` is given, returns an error unless it matches the hash of the
+    /// loaded chainspec.
     pub(crate) fn new<P, REv>(
         chainspec_dir: P,
+        expected_chainspec_hash: Option<Digest>,
         effect_builder: EffectBuilder<REv>,
     ) -> Result<(Self, Effects<Event>), Error>
     where
         P: AsRef<Path>,
         REv: From<Event> + From<StorageRequest> + From<StateStoreRequest> + Send,
     {
+        let chainspec = Chainspec::from_path(&chainspec_dir.as_ref())?;
+        check_chainspec_hash(&chainspec, expected_chainspec_hash)?;
         Ok(Self::new_with_chainspec_and_path(
-            Arc::new(Chainspec::from_path(&chainspec_dir.as_ref())?),
+            Arc::new(chainspec),
             chainspec_dir,
             effect_builder,
         ))
@@ -650,6 +657,19 @@ impl ChainspecLoader {
     }
 }
 
+/// Returns an error if `expected_hash` is given and differs from the hash of `chainspec`.
+fn check_chainspec_hash(chainspec: &Chainspec, expected_hash: Option<Digest>) -> Result<(), Error> {
+    let actual_hash = chainspec.hash();
+    info!(chainspec_hash = ?actual_hash, "loaded chainspec");
+    match expected_hash {
+        Some(expected_hash) if expected_hash != actual_hash => Err(Error::HashMismatch {
+            expected_hash,
+            actual_hash,
+        }),
+        _ => Ok(()),
+    }
+}
+
 impl<REv> Component<REv> for ChainspecLoader
 where
     REv: From<Event>
@@ -662,6 +682,10 @@ where
     type Event = Event;
     type ConstructionError = Error;
 
+    fn name(&self) -> &str {
+        "chainspec_loader"
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -693,7 +717,7 @@ where
     }
 }
 
-/// This struct can be parsed from a TOML-encoded chainspec file.  It means that as the
+/// This struct can be parsed from a TOML- or JSON-encoded chainspec file.  It means that as the
 /// chainspec format changes over versions, as long as we maintain the protocol config in this form
 /// in the chainspec file, it can continue to be parsed as an `UpgradePoint`.
 #[derive(Deserialize)]
@@ -703,11 +727,12 @@ struct UpgradePoint {
 }
 
 impl UpgradePoint {
-    /// Parses a chainspec file at the given path as an `UpgradePoint`.
+    /// Parses the chainspec file in the given directory as an `UpgradePoint`.
     fn from_chainspec_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
-        let bytes = utils::read_file(path.as_ref().join(&CHAINSPEC_NAME))
-            .map_err(Error::LoadUpgradePoint)?;
-        Ok(toml::from_slice(&bytes)?)
+        let chainspec_path = chainspec_file_path(path.as_ref());
+        let format = ChainspecFormat::from_path(&chainspec_path)?;
+        let bytes = utils::read_file(chainspec_path).map_err(Error::LoadUpgradePoint)?;
+        format.decode(&bytes)
     }
 }
 
@@ -823,6 +848,32 @@ mod tests {
         types::chainspec::CHAINSPEC_NAME,
     };
 
+    #[test]
+    fn should_check_chainspec_hash() {
+        let mut rng = crate::new_rng();
+        let mut chainspec = Chainspec::random(&mut rng);
+        let hash = chainspec.hash();
+        assert!(check_chainspec_hash(&chainspec, None).is_ok());
+        assert!(check_chainspec_hash(&chainspec, Some(hash)).is_ok());
+
+        // Modifying any field should change the hash, and hence fail the check.
+        chainspec.deploy_config.max_dependencies =
+            chainspec.deploy_config.max_dependencies.wrapping_add(1);
+        let modified_hash = chainspec.hash();
+        assert_ne!(modified_hash, hash);
+        assert!(check_chainspec_hash(&chainspec, None).is_ok());
+        match check_chainspec_hash(&chainspec, Some(hash)) {
+            Err(Error::HashMismatch {
+                expected_hash,
+                actual_hash,
+            }) => {
+                assert_eq!(expected_hash, hash);
+                assert_eq!(actual_hash, modified_hash);
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
     #[test]
     fn should_get_next_installed_version() {
         let tempdir = tempfile::tempdir().expect("should create temp dir");
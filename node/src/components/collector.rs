@@ -57,6 +57,10 @@ where
     type Event = Event<P>;
     type ConstructionError = Infallible;
 
+    fn name(&self) -> &str {
+        "collector"
+    }
+
     fn handle_event(
         &mut self,
         _effect_builder: EffectBuilder<REv>,
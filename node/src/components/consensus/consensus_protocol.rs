@@ -139,11 +139,25 @@ pub(crate) struct FinalizedBlock<C: Context> {
 
 pub(crate) type ProtocolOutcomes<I, C> = Vec<ProtocolOutcome<I, C>>;
 
+/// How much our own validator took part in a consensus instance so far.
+#[derive(Clone, Copy, DataSize, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Participation {
+    /// The number of blocks proposed by us.
+    pub(crate) proposals: u64,
+    /// The number of units other than proposals, i.e. confirmations and witness units, we created.
+    pub(crate) votes: u64,
+    /// The number of rounds in which we failed to create a witness unit.
+    pub(crate) missed_rounds: u64,
+}
+
 // TODO: get rid of anyhow::Error; use variant and derive Clone and PartialEq. This is for testing.
 #[derive(Debug)]
 pub(crate) enum ProtocolOutcome<I, C: Context> {
     CreatedGossipMessage(Vec<u8>),
     CreatedTargetedMessage(Vec<u8>, I),
+    /// A request for a peer's recent messages, to be sent to a single random peer that supports
+    /// catch-up requests.
+    CreatedCatchUpRequest(Vec<u8>),
     InvalidIncomingMessage(Vec<u8>, I, Error),
     ScheduleTimer(Timestamp, TimerId),
     QueueAction(ActionId),
@@ -162,7 +176,14 @@ pub(crate) enum ProtocolOutcome<I, C: Context> {
         proposed_block: ProposedBlock<C>,
     },
     /// New direct evidence was added against the given validator.
-    NewEvidence(C::ValidatorId),
+    ///
+    /// If the validator created and signed two conflicting messages, these are included,
+    /// serialized. Both signatures have been verified, and the validator has been marked as
+    /// faulty.
+    NewEvidence {
+        validator_id: C::ValidatorId,
+        conflicting_messages: Option<(Vec<u8>, Vec<u8>)>,
+    },
     /// Send evidence about the validator from an earlier era to the peer.
     SendEvidence(I, C::ValidatorId),
     /// We've detected an equivocation our own node has made.
@@ -255,4 +276,8 @@ pub(crate) trait ConsensusProtocol<I, C: Context>: Send {
 
     // TODO: Make this less Highway-specific.
     fn next_round_length(&self) -> Option<TimeDiff>;
+
+    /// Returns how much our own validator took part in this instance, or `None` if we are not an
+    /// active validator.
+    fn participation(&self) -> Option<Participation>;
 }
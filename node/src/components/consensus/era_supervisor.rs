@@ -29,7 +29,7 @@ use prometheus::Registry;
 use rand::Rng;
 use tracing::{debug, error, info, trace, warn};
 
-use casper_types::{AsymmetricType, EraId, PublicKey, SecretKey, U512};
+use casper_types::{AsymmetricType, EraId, ProtocolVersion, PublicKey, SecretKey, U512};
 
 use crate::{
     components::consensus::{
@@ -64,6 +64,9 @@ pub use self::era::Era;
 /// fault tolerance threshold.
 const FTT_EXCEEDED_SHUTDOWN_DELAY_MILLIS: u64 = 60 * 1000;
 
+/// The first protocol version whose nodes understand catch-up requests.
+const CATCH_UP_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::from_parts(1, 4, 0);
+
 type ConsensusConstructor<I> = dyn Fn(
         Digest,                    // the era's unique instance ID
         BTreeMap<PublicKey, U512>, // validator weights
@@ -333,6 +336,25 @@ where
         let prev_era = era_id
             .checked_sub(1)
             .and_then(|last_era_id| self.active_eras.get(&last_era_id));
+        if let Some(prev_era) = prev_era {
+            let prev_validators = prev_era.validators();
+            let removed: Vec<&PublicKey> = prev_validators
+                .keys()
+                .filter(|pub_key| !validators.contains_key(pub_key))
+                .collect();
+            let added: Vec<&PublicKey> = validators
+                .keys()
+                .filter(|pub_key| !prev_validators.contains_key(pub_key))
+                .collect();
+            if !removed.is_empty() || !added.is_empty() {
+                info!(
+                    era = era_id.value(),
+                    ?added,
+                    ?removed,
+                    "validator set changed"
+                );
+            }
+        }
 
         let (mut consensus, mut outcomes) = (self.new_consensus)(
             instance_id,
@@ -382,6 +404,10 @@ where
             }
         }
 
+        if era_id == self.current_era {
+            self.metrics
+                .set_participation(era.consensus.participation());
+        }
         let _ = self.active_eras.insert(era_id, era);
         let oldest_bonded_era_id = oldest_bonded_era(&self.protocol_config, era_id);
         // Clear the obsolete data from the era whose validators are unbonded now. We only retain
@@ -705,6 +731,11 @@ where
             }
             Some(era) => {
                 let outcomes = f(&mut *era.consensus);
+                if era_id == self.era_supervisor.current_era {
+                    self.era_supervisor
+                        .metrics
+                        .set_participation(era.consensus.participation());
+                }
                 self.handle_consensus_outcomes(era_id, outcomes)
             }
         }
@@ -1023,6 +1054,31 @@ where
                     .send_message(to, message.into())
                     .ignore()
             }
+            ProtocolOutcome::CreatedCatchUpRequest(payload) => {
+                let message = ConsensusMessage::Protocol { era_id, payload };
+                let effect_builder = self.effect_builder;
+                let choice: usize = self.rng.gen();
+                async move {
+                    // Peers running an older protocol version would fail to parse the request.
+                    let mut candidates = Vec::new();
+                    for (peer, _) in effect_builder.network_peers::<I>().await {
+                        let supports_catch_up = effect_builder
+                            .get_peer_protocol_version(peer.clone())
+                            .await
+                            .map_or(false, |version| version >= CATCH_UP_PROTOCOL_VERSION);
+                        if supports_catch_up {
+                            candidates.push(peer);
+                        }
+                    }
+                    if candidates.is_empty() {
+                        debug!(%era_id, "no peer to request to catch up from");
+                        return;
+                    }
+                    let peer = candidates.swap_remove(choice % candidates.len());
+                    effect_builder.send_message(peer, message.into()).await;
+                }
+                .ignore()
+            }
             ProtocolOutcome::ScheduleTimer(timestamp, timer_id) => {
                 let timediff = timestamp.saturating_diff(Timestamp::now());
                 self.effect_builder
@@ -1102,11 +1158,26 @@ where
                 self.era_supervisor
                     .metrics
                     .finalized_block(&finalized_block);
-                // Announce the finalized block.
-                let mut effects = self
-                    .effect_builder
-                    .announce_finalized_block(finalized_block.clone())
-                    .ignore();
+                // Announce the finalized block, and if it was the era's last one, the era's end.
+                // Both are announced by the same effect, so that the era end is announced after
+                // the block.
+                let effect_builder = self.effect_builder;
+                let announced_block = finalized_block.clone();
+                let era_end = finalized_block
+                    .era_report()
+                    .cloned()
+                    .map(|era_report| (finalized_block.height(), era_report));
+                let mut effects = async move {
+                    effect_builder
+                        .announce_finalized_block(announced_block)
+                        .await;
+                    if let Some((switch_block_height, era_report)) = era_end {
+                        effect_builder
+                            .announce_era_ended(era_id, switch_block_height, era_report)
+                            .await;
+                    }
+                }
+                .ignore();
                 self.era_supervisor.next_block_height = self
                     .era_supervisor
                     .next_block_height
@@ -1176,11 +1247,19 @@ where
                 );
                 effects
             }
-            ProtocolOutcome::NewEvidence(pub_key) => {
+            ProtocolOutcome::NewEvidence {
+                validator_id: pub_key,
+                conflicting_messages,
+            } => {
                 info!(%pub_key, era = era_id.value(), "validator equivocated");
                 let mut effects = self
                     .effect_builder
-                    .announce_fault_event(era_id, pub_key.clone(), Timestamp::now())
+                    .announce_fault_event(
+                        era_id,
+                        pub_key.clone(),
+                        Timestamp::now(),
+                        conflicting_messages,
+                    )
                     .ignore();
                 for e_id in self
                     .era_supervisor
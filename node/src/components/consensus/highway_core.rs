@@ -33,6 +33,7 @@
 mod test_macros;
 
 pub(crate) mod active_validator;
+pub(crate) mod evidence;
 pub(crate) mod finality_detector;
 pub(crate) mod highway;
 pub(crate) mod state;
@@ -40,7 +41,6 @@ pub(super) mod synchronizer;
 pub(crate) mod validators;
 
 mod endorsement;
-mod evidence;
 #[cfg(test)]
 pub(crate) mod highway_testing;
 
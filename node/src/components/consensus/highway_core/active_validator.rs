@@ -19,7 +19,7 @@ use super::{
 
 use crate::{
     components::consensus::{
-        consensus_protocol::BlockContext,
+        consensus_protocol::{BlockContext, Participation},
         highway_core::{highway::SignedWireUnit, state::Fault},
         traits::{Context, ValidatorSecret},
     },
@@ -80,6 +80,8 @@ where
     target_ftt: Weight,
     /// If this flag is set we don't create new units and just send pings instead.
     paused: bool,
+    /// The units we created so far, and the rounds we missed.
+    participation: Participation,
 }
 
 impl<C: Context> Debug for ActiveValidator<C> {
@@ -89,6 +91,7 @@ impl<C: Context> Debug for ActiveValidator<C> {
             .field("next_round_exp", &self.next_round_exp)
             .field("next_timer", &self.next_timer)
             .field("paused", &self.paused)
+            .field("participation", &self.participation)
             .finish()
     }
 }
@@ -126,6 +129,7 @@ impl<C: Context> ActiveValidator<C> {
             own_last_unit,
             target_ftt,
             paused: false,
+            participation: Participation::default(),
         };
         let mut effects = av.schedule_timer(start_time, state);
         effects.push(av.send_ping(current_time, instance_id));
@@ -190,12 +194,13 @@ impl<C: Context> ActiveValidator<C> {
         let r_exp = self.round_exp(state, timestamp);
         let r_id = state::round_id(timestamp, r_exp);
         let r_len = state::round_len(r_exp);
+        let is_witness_time = timestamp == r_id + self.witness_offset(r_len);
         // Only create new units if enough validators are online.
         if !self.paused && self.enough_validators_online(state, timestamp) {
             if timestamp == r_id && state.leader(r_id) == self.vidx {
                 effects.extend(self.request_new_block(state, instance_id, timestamp));
                 return effects;
-            } else if timestamp == r_id + self.witness_offset(r_len) {
+            } else if is_witness_time {
                 let panorama = self.panorama_at(state, timestamp);
                 if let Some(witness_unit) =
                     self.new_unit(panorama, timestamp, None, state, instance_id)
@@ -206,11 +211,15 @@ impl<C: Context> ActiveValidator<C> {
                     {
                         info!(round_id = %r_id, "sending witness in round with no proposal");
                     }
+                    self.participation.votes = self.participation.votes.saturating_add(1);
                     effects.push(Effect::NewVertex(ValidVertex(Vertex::Unit(witness_unit))));
                     return effects;
                 }
             }
         }
+        if is_witness_time {
+            self.participation.missed_rounds = self.participation.missed_rounds.saturating_add(1);
+        }
         // We are not creating a new unit. Send a ping if necessary, to show that we're online.
         if !state.has_ping(self.vidx, timestamp) {
             warn!(%timestamp, "too many validators offline, sending ping");
@@ -259,6 +268,7 @@ impl<C: Context> ActiveValidator<C> {
                 if let Some(confirmation_unit) =
                     self.new_unit(panorama, now, None, state, instance_id)
                 {
+                    self.participation.votes = self.participation.votes.saturating_add(1);
                     let vv = ValidVertex(Vertex::Unit(confirmation_unit));
                     effects.push(Effect::NewVertex(vv));
                 }
@@ -309,9 +319,9 @@ impl<C: Context> ActiveValidator<C> {
         let maybe_parent_hash = state.fork_choice(&panorama);
         // If the parent is a terminal block, just create a unit without a new block.
         if maybe_parent_hash.map_or(false, |hash| state.is_terminal_block(hash)) {
-            return self
-                .new_unit(panorama, timestamp, None, state, instance_id)
-                .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))));
+            let proposal_unit = self.new_unit(panorama, timestamp, None, state, instance_id)?;
+            self.participation.proposals = self.participation.proposals.saturating_add(1);
+            return Some(Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))));
         }
         // Otherwise we need to request a new consensus value to propose.
         let ancestor_values = match maybe_parent_hash {
@@ -353,10 +363,13 @@ impl<C: Context> ActiveValidator<C> {
             warn!("Creator knows it's faulty. Won't create a message.");
             return vec![];
         }
-        self.new_unit(panorama, timestamp, Some(value), state, instance_id)
-            .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))))
-            .into_iter()
-            .collect()
+        match self.new_unit(panorama, timestamp, Some(value), state, instance_id) {
+            Some(proposal_unit) => {
+                self.participation.proposals = self.participation.proposals.saturating_add(1);
+                vec![Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit)))]
+            }
+            None => vec![],
+        }
     }
 
     /// Returns whether the incoming message is a proposal that we need to send a confirmation for.
@@ -603,6 +616,11 @@ impl<C: Context> ActiveValidator<C> {
     pub(crate) fn next_round_length(&self) -> TimeDiff {
         state::round_len(self.next_round_exp)
     }
+
+    /// Returns the units we created so far, and the rounds we missed.
+    pub(crate) fn participation(&self) -> Participation {
+        self.participation
+    }
 }
 
 pub(crate) fn read_last_unit<C, P>(path: P) -> io::Result<SignedWireUnit<C>>
@@ -939,6 +957,59 @@ mod tests {
         assert_eq!(Some(&new_unit.hash()), test.next_finalized());
     }
 
+    #[test]
+    #[allow(clippy::unreadable_literal)] // 0xC0FFEE is more readable than 0x00C0_FFEE.
+    fn participation_is_counted() {
+        let mut test = TestState::new(
+            State::new_test(&[Weight(3), Weight(4)], 0),
+            410.into(),
+            1u64,
+            FinalityDetector::new(Weight(2)),
+            vec![ALICE, BOB],
+        );
+
+        // Alice leads the first round, but doesn't propose anything. With no proposal to cite,
+        // neither of them can create a witness unit, so both miss the round.
+        let _ = test.handle_timer(ALICE, 416.into());
+        let _ = test.handle_timer(ALICE, 426.into());
+        let _ = test.handle_timer(BOB, 426.into());
+        for vidx in &[ALICE, BOB] {
+            let participation = test.active_validators[*vidx].participation();
+            assert_eq!(participation.proposals, 0);
+            assert_eq!(participation.votes, 0);
+            assert_eq!(participation.missed_rounds, 1);
+        }
+
+        // Bob leads the second round and proposes a block, which Alice confirms.
+        let bctx = match &*test.handle_timer(BOB, 432.into()) {
+            [Eff::ScheduleTimer(_), Eff::RequestNewBlock(bctx)] => bctx.clone(),
+            effects => panic!("unexpected effects {:?}", effects),
+        };
+        let (_, proposal) = test.propose(BOB, 0xC0FFEE, bctx);
+        let _ = unwrap_single(&test.handle_new_unit(ALICE, &proposal.hash())).unwrap_unit();
+
+        // Both of them create their witness units.
+        let _ = test.handle_timer(BOB, 442.into());
+        let _ = test.handle_timer(ALICE, 442.into());
+
+        assert_eq!(
+            test.active_validators[ALICE].participation(),
+            Participation {
+                proposals: 0,
+                votes: 2,
+                missed_rounds: 1,
+            }
+        );
+        assert_eq!(
+            test.active_validators[BOB].participation(),
+            Participation {
+                proposals: 1,
+                votes: 1,
+                missed_rounds: 1,
+            }
+        );
+    }
+
     #[test]
     fn ping_on_startup() {
         let state = State::new_test(&[Weight(3)], 0);
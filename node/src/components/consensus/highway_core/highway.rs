@@ -13,7 +13,7 @@ use tracing::{debug, error, info, trace};
 
 use crate::{
     components::consensus::{
-        consensus_protocol::BlockContext,
+        consensus_protocol::{BlockContext, Participation},
         highway_core::{
             active_validator::{ActiveValidator, Effect},
             evidence::EvidenceError,
@@ -665,6 +665,12 @@ impl<C: Context> Highway<C> {
             .map(|av| av.next_round_length())
     }
 
+    /// Returns how much our own validator took part in this instance, if we are an active
+    /// validator.
+    pub(crate) fn participation(&self) -> Option<Participation> {
+        self.active_validator.as_ref().map(|av| av.participation())
+    }
+
     /// Logs a message if this is a block and any previous blocks were skipped.
     fn log_if_missing_proposal(&self, unit_hash: &C::Hash) {
         let state = &self.state;
@@ -1,5 +1,6 @@
 use prometheus::{Gauge, IntGauge, Registry};
 
+use super::consensus_protocol::Participation;
 use crate::{
     types::{FinalizedBlock, Timestamp},
     unregister_metric,
@@ -18,6 +19,12 @@ pub(super) struct ConsensusMetrics {
     time_of_last_finalized_block: IntGauge,
     /// The Current era.
     pub(super) current_era: IntGauge,
+    /// The number of blocks proposed by our validator in the current era.
+    proposals: IntGauge,
+    /// The number of confirmation and witness units created by our validator in the current era.
+    votes: IntGauge,
+    /// The number of rounds our validator missed in the current era.
+    missed_rounds: IntGauge,
     /// registry component.
     registry: Registry,
 }
@@ -39,17 +46,37 @@ impl ConsensusMetrics {
             "timestamp of the most recently finalized block",
         )?;
         let current_era = IntGauge::new("current_era", "The current era")?;
+        let proposals = IntGauge::new(
+            "consensus_proposals",
+            "the number of blocks proposed by this node's validator in the current era",
+        )?;
+        let votes = IntGauge::new(
+            "consensus_votes",
+            "the number of confirmation and witness units created by this node's validator in the \
+            current era",
+        )?;
+        let missed_rounds = IntGauge::new(
+            "consensus_missed_rounds",
+            "the number of rounds in which this node's validator failed to create a witness unit \
+            in the current era",
+        )?;
         registry.register(Box::new(finalization_time.clone()))?;
         registry.register(Box::new(finalized_block_count.clone()))?;
         registry.register(Box::new(current_era.clone()))?;
         registry.register(Box::new(time_of_last_proposed_block.clone()))?;
         registry.register(Box::new(time_of_last_finalized_block.clone()))?;
+        registry.register(Box::new(proposals.clone()))?;
+        registry.register(Box::new(votes.clone()))?;
+        registry.register(Box::new(missed_rounds.clone()))?;
         Ok(ConsensusMetrics {
             finalization_time,
             finalized_block_count,
             time_of_last_proposed_block,
             time_of_last_finalized_block,
             current_era,
+            proposals,
+            votes,
+            missed_rounds,
             registry: registry.clone(),
         })
     }
@@ -69,6 +96,15 @@ impl ConsensusMetrics {
         self.time_of_last_proposed_block
             .set(Timestamp::now().millis() as i64);
     }
+
+    /// Updates the participation of our validator in the current era, which is zero if we are not
+    /// a validator in it.
+    pub(super) fn set_participation(&mut self, participation: Option<Participation>) {
+        let participation = participation.unwrap_or_default();
+        self.proposals.set(participation.proposals as i64);
+        self.votes.set(participation.votes as i64);
+        self.missed_rounds.set(participation.missed_rounds as i64);
+    }
 }
 
 impl Drop for ConsensusMetrics {
@@ -78,5 +114,8 @@ impl Drop for ConsensusMetrics {
         unregister_metric!(self.registry, self.current_era);
         unregister_metric!(self.registry, self.time_of_last_finalized_block);
         unregister_metric!(self.registry, self.time_of_last_proposed_block);
+        unregister_metric!(self.registry, self.proposals);
+        unregister_metric!(self.registry, self.votes);
+        unregister_metric!(self.registry, self.missed_rounds);
     }
 }
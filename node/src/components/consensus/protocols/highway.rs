@@ -1,5 +1,7 @@
+mod adaptive_timeout;
 pub(crate) mod config;
 mod participation;
+mod replay_buffer;
 mod round_success_meter;
 #[cfg(test)]
 mod tests;
@@ -24,14 +26,16 @@ use crate::{
     components::consensus::{
         config::{Config, ProtocolConfig},
         consensus_protocol::{
-            BlockContext, ConsensusProtocol, ProposedBlock, ProtocolOutcome, ProtocolOutcomes,
+            BlockContext, ConsensusProtocol, Participation, ProposedBlock, ProtocolOutcome,
+            ProtocolOutcomes,
         },
         highway_core::{
             active_validator::Effect as AvEffect,
+            evidence::Evidence,
             finality_detector::{FinalityDetector, FttExceeded},
             highway::{
-                Dependency, GetDepOutcome, Highway, Params, PreValidatedVertex, ValidVertex,
-                Vertex, VertexError,
+                Dependency, GetDepOutcome, Highway, Params, PreValidatedVertex, SignedWireUnit,
+                ValidVertex, Vertex, VertexError,
             },
             state,
             state::{Observation, Panorama},
@@ -45,12 +49,20 @@ use crate::{
 };
 
 pub use self::config::Config as HighwayConfig;
-use self::round_success_meter::RoundSuccessMeter;
+use self::{
+    adaptive_timeout::AdaptiveTimeout, replay_buffer::ReplayBuffer,
+    round_success_meter::RoundSuccessMeter,
+};
 
 /// Never allow more than this many units in a piece of evidence for conflicting endorsements,
 /// even if eras are longer than this.
 const MAX_ENDORSEMENT_EVIDENCE_LIMIT: u64 = 10_000;
 
+/// The minimum time between two catch-up requests from the same peer that are answered.
+const MIN_CATCH_UP_REQUEST_INTERVAL: TimeDiff = TimeDiff::from_seconds(10);
+/// The maximum total size of the messages replayed in response to a single catch-up request.
+const MAX_CATCH_UP_RESPONSE_SIZE: usize = 16 * 1024 * 1024;
+
 /// The timer for creating new units, as a validator actively participating in consensus.
 const TIMER_ID_ACTIVE_VALIDATOR: TimerId = TimerId(0);
 /// The timer for adding a vertex with a future timestamp.
@@ -88,8 +100,8 @@ where
     /// long, an alert is raised.
     last_panorama: Panorama<C>,
     /// If the current era's protocol state has not progressed for this long, request the latest
-    /// state from peers.
-    standstill_timeout: TimeDiff,
+    /// state from peers. Adapts to the latencies of the blocks finalized in this era.
+    standstill_timeout: AdaptiveTimeout,
     /// If after another `standstill_timeout` there is no progress, raise
     /// `ProtocolOutcome::StandstillAlert` and shut down.
     shutdown_on_standstill: bool,
@@ -97,6 +109,10 @@ where
     log_participation_interval: TimeDiff,
     /// Whether to log the size of every incoming and outgoing serialized unit.
     log_unit_sizes: bool,
+    /// The most recent vertices added to the state, to be replayed to peers catching up.
+    replay_buffer: ReplayBuffer,
+    /// The time each peer's most recent catch-up request was answered.
+    catch_up_requests: HashMap<I, Timestamp>,
 }
 
 impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
@@ -211,10 +227,12 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
             pvv_cache: Default::default(),
             evidence_only: false,
             last_panorama,
-            standstill_timeout: config.highway.standstill_timeout,
+            standstill_timeout: AdaptiveTimeout::new(&config.highway),
             shutdown_on_standstill: config.highway.shutdown_on_standstill,
             log_participation_interval: config.highway.log_participation_interval,
             log_unit_sizes: config.highway.log_unit_sizes,
+            replay_buffer: ReplayBuffer::new(config.highway.replay_buffer_capacity),
+            catch_up_requests: HashMap::new(),
         });
 
         (hw_proto, outcomes)
@@ -257,7 +275,7 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
             AvEffect::NewVertex(vv) => {
                 self.log_unit_size(vv.inner(), "sending new unit");
                 self.calculate_round_exponent(&vv, now);
-                self.process_new_vertex(vv)
+                self.process_new_vertex(vv, now)
             }
             AvEffect::ScheduleTimer(timestamp) => {
                 vec![ProtocolOutcome::ScheduleTimer(
@@ -275,7 +293,7 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
         }
     }
 
-    fn process_new_vertex(&mut self, vv: ValidVertex<C>) -> ProtocolOutcomes<I, C> {
+    fn process_new_vertex(&mut self, vv: ValidVertex<C>, now: Timestamp) -> ProtocolOutcomes<I, C> {
         let mut outcomes = Vec::new();
         if let Vertex::Evidence(ev) = vv.inner() {
             let v_id = self
@@ -284,17 +302,48 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
                 .id(ev.perpetrator())
                 .expect("validator not found") // We already validated this vertex.
                 .clone();
-            outcomes.push(ProtocolOutcome::NewEvidence(v_id));
+            // Evidence is only added to the state after validation, which includes checking the
+            // signatures of both units against the perpetrator's public key.
+            let conflicting_messages = match ev {
+                Evidence::Equivocation(unit1, unit2) => {
+                    let serialize_unit = |swunit: &SignedWireUnit<C>| {
+                        HighwayMessage::NewVertex(Vertex::Unit(swunit.clone())).serialize()
+                    };
+                    Some((serialize_unit(unit1), serialize_unit(unit2)))
+                }
+                Evidence::Endorsements { .. } => None,
+            };
+            outcomes.push(ProtocolOutcome::NewEvidence {
+                validator_id: v_id,
+                conflicting_messages,
+            });
+        }
+        let msg = HighwayMessage::NewVertex(vv.into()).serialize();
+        if self.replay_buffer.is_enabled() {
+            self.replay_buffer.push(msg.clone());
         }
-        let msg = HighwayMessage::NewVertex(vv.into());
-        outcomes.push(ProtocolOutcome::CreatedGossipMessage(msg.serialize()));
-        outcomes.extend(self.detect_finality());
+        outcomes.push(ProtocolOutcome::CreatedGossipMessage(msg));
+        outcomes.extend(self.detect_finality(now));
         outcomes
     }
 
-    fn detect_finality(&mut self) -> ProtocolOutcomes<I, C> {
+    fn detect_finality(&mut self, now: Timestamp) -> ProtocolOutcomes<I, C> {
         let faulty_weight = match self.finality_detector.run(&self.highway) {
-            Ok(iter) => return iter.map(ProtocolOutcome::FinalizedBlock).collect(),
+            Ok(iter) => {
+                let finalized_blocks = iter.collect_vec();
+                for finalized_block in &finalized_blocks {
+                    let latency = now.saturating_diff(finalized_block.timestamp);
+                    self.standstill_timeout.record_latency(latency);
+                    if finalized_block.terminal_block_data.is_some() {
+                        // The era has ended, so no peer needs to catch up on it anymore.
+                        self.replay_buffer.close();
+                    }
+                }
+                return finalized_blocks
+                    .into_iter()
+                    .map(ProtocolOutcome::FinalizedBlock)
+                    .collect();
+            }
             Err(FttExceeded(weight)) => weight.0,
         };
         error!(
@@ -379,7 +428,7 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
         // waiting for are now satisfied, and try adding the pending vertices as well.
         outcomes.extend(self.synchronizer.remove_satisfied_deps(&self.highway));
         // Check whether any new blocks were finalized.
-        outcomes.extend(self.detect_finality());
+        outcomes.extend(self.detect_finality(now));
         outcomes
     }
 
@@ -419,6 +468,10 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
         // round has finished, we now have all the vertices from that round in the state, and no
         // newer ones.
         self.calculate_round_exponent(&vv, now);
+        if self.replay_buffer.is_enabled() {
+            let msg = HighwayMessage::NewVertex(vv.inner().clone()).serialize();
+            self.replay_buffer.push(msg);
+        }
         let av_effects = self.highway.add_valid_vertex(vv, now);
         // Once vertex is added to the state, we can remove it from the cache.
         self.pvv_cache.remove(&vertex_id);
@@ -481,7 +534,7 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
             info!(
                 instance_id = ?self.highway.instance_id(),
                 "no progress in the last {}, creating latest state request",
-                self.standstill_timeout,
+                self.standstill_timeout.current(),
             );
             // We haven't made any progress. Request latest panorama from peers and schedule
             // standstill alert. If we still won't progress by the time
@@ -489,7 +542,7 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
             let mut outcomes = self.latest_panorama_request();
             if self.shutdown_on_standstill {
                 outcomes.push(ProtocolOutcome::ScheduleTimer(
-                    now + self.standstill_timeout,
+                    now + self.standstill_timeout.current(),
                     TIMER_ID_STANDSTILL_ALERT,
                 ));
             }
@@ -506,12 +559,12 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
         debug!(
             instance_id = ?self.highway.instance_id(),
             "progress detected; scheduling next standstill check in {}",
-            self.standstill_timeout,
+            self.standstill_timeout.current(),
         );
         // Record the current panorama and schedule the next standstill check.
         self.last_panorama = self.highway.state().panorama().clone();
         vec![ProtocolOutcome::ScheduleTimer(
-            now + self.standstill_timeout,
+            now + self.standstill_timeout.current(),
             TIMER_ID_STANDSTILL_ALERT,
         )]
     }
@@ -527,19 +580,19 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
             info!(
                 instance_id = ?self.highway.instance_id(),
                 "no progress in the last {}, raising standstill alert",
-                self.standstill_timeout,
+                self.standstill_timeout.current(),
             );
             return vec![ProtocolOutcome::StandstillAlert]; // No progress within the timeout.
         }
         debug!(
             instance_id = ?self.highway.instance_id(),
             "progress detected; scheduling next standstill check in {}",
-            self.standstill_timeout,
+            self.standstill_timeout.current(),
         );
         // Record the current panorama and schedule the next standstill check.
         self.last_panorama = self.highway.state().panorama().clone();
         vec![ProtocolOutcome::ScheduleTimer(
-            now + self.standstill_timeout,
+            now + self.standstill_timeout.current(),
             TIMER_ID_STANDSTILL_ALERT,
         )]
     }
@@ -582,6 +635,15 @@ impl<I: NodeIdT, C: Context + 'static> HighwayProtocol<I, C> {
         Ok(pvv)
     }
 
+    /// Creates a request for a peer's buffered recent messages.
+    fn catch_up_request(&self) -> ProtocolOutcomes<I, C> {
+        if !self.replay_buffer.is_enabled() {
+            return vec![];
+        }
+        let request: HighwayMessage<C> = HighwayMessage::CatchUpRequest;
+        vec![ProtocolOutcome::CreatedCatchUpRequest(request.serialize())]
+    }
+
     /// Creates a message to be gossiped that sends the validator's panorama.
     fn latest_panorama_request(&self) -> ProtocolOutcomes<I, C> {
         let request = HighwayMessage::LatestStateRequest(self.highway.state().panorama().clone());
@@ -600,6 +662,8 @@ pub(crate) enum HighwayMessage<C: Context> {
     NewVertex(Vertex<C>),
     RequestDependency(Dependency<C>),
     LatestStateRequest(Panorama<C>),
+    /// A request for all recent messages the recipient buffered, to catch up on the era.
+    CatchUpRequest,
 }
 
 impl<C: Context> HighwayMessage<C> {
@@ -767,6 +831,20 @@ where
                     })
                     .collect()
             }
+            Ok(HighwayMessage::CatchUpRequest) => {
+                if let Some(last_request) = self.catch_up_requests.get(&sender) {
+                    if now < *last_request + MIN_CATCH_UP_REQUEST_INTERVAL {
+                        debug!(%sender, "ignoring repeated catch-up request");
+                        return vec![];
+                    }
+                }
+                trace!(%sender, "received a catch-up request");
+                self.catch_up_requests.insert(sender.clone(), now);
+                self.replay_buffer
+                    .recent_messages(MAX_CATCH_UP_RESPONSE_SIZE)
+                    .map(|msg| ProtocolOutcome::CreatedTargetedMessage(msg.clone(), sender.clone()))
+                    .collect()
+            }
         }
     }
 
@@ -810,8 +888,10 @@ where
     }
 
     fn handle_is_current(&self) -> ProtocolOutcomes<I, C> {
-        // Request latest protocol state of the current era.
-        self.latest_panorama_request()
+        // Request latest protocol state of the current era, and the recent messages leading to it.
+        let mut outcomes = self.latest_panorama_request();
+        outcomes.extend(self.catch_up_request());
+        outcomes
     }
 
     fn handle_action(&mut self, action_id: ActionId, now: Timestamp) -> ProtocolOutcomes<I, C> {
@@ -846,7 +926,7 @@ where
                 .flat_map(|(vv, _)| self.add_valid_vertex(vv, now))
                 .collect_vec();
             outcomes.extend(self.synchronizer.remove_satisfied_deps(&self.highway));
-            outcomes.extend(self.detect_finality());
+            outcomes.extend(self.detect_finality(now));
             outcomes
         } else {
             // TODO: Report proposer as faulty?
@@ -892,6 +972,7 @@ where
         // TODO: We could also drop the finality detector and round success meter here. Maybe make
         // HighwayProtocol an enum with an EvidenceOnly variant?
         self.pending_values.clear();
+        self.replay_buffer.close();
         self.synchronizer.retain_evidence_only();
         self.highway.retain_evidence_only();
         self.evidence_only = true;
@@ -955,4 +1036,8 @@ where
     fn next_round_length(&self) -> Option<TimeDiff> {
         self.highway.next_round_length()
     }
+
+    fn participation(&self) -> Option<Participation> {
+        self.highway.participation()
+    }
 }
@@ -0,0 +1,150 @@
+//! Adaptive standstill timeout
+//!
+//! The time without progress after which an era is considered to be at a standstill can adapt to
+//! how fast blocks are actually being finalized: Once blocks were finalized, the timeout is a
+//! multiple of the average latency between the proposal and the finalization of the most recent
+//! ones, bounded by the configured minimum and maximum. That way we neither wait needlessly long on
+//! fast networks nor raise premature alerts on slow ones.
+//!
+//! Every era's protocol instance has its own adaptive timeout, so the observed latencies are reset
+//! at era boundaries.
+//!
+//! This is the only timeout in Highway that can be adapted: Validators never wait for a round to
+//! time out, since units are created at fixed points in time within each round, and the round
+//! lengths themselves already adapt to the network's performance via the round exponent chosen by
+//! the `RoundSuccessMeter`. The standstill timeout, however, is fixed, so it either leaves a
+//! stalled era undetected for a long time on fast networks, or raises premature alerts on slow
+//! ones.
+
+use std::collections::VecDeque;
+
+use datasize::DataSize;
+
+use crate::types::TimeDiff;
+
+use super::config::Config;
+
+/// The number of most recent finalization latencies the average is taken over.
+pub(crate) const NUM_LATENCIES_TO_CONSIDER: usize = 10;
+
+/// A timeout scaled to the recently observed finalization latencies.
+#[derive(DataSize, Debug, Clone)]
+pub(crate) struct AdaptiveTimeout {
+    /// The most recent finalization latencies, oldest first.
+    latencies: VecDeque<TimeDiff>,
+    /// The timeout used until the first block is finalized, or if adaptivity is disabled.
+    initial: TimeDiff,
+    /// The timeout is this many times the average latency. Adaptivity is disabled if zero.
+    latency_factor: u64,
+    /// The lower bound of the adapted timeout.
+    min: TimeDiff,
+    /// The upper bound of the adapted timeout.
+    max: TimeDiff,
+}
+
+impl AdaptiveTimeout {
+    /// Creates a new adaptive standstill timeout, without any latencies observed yet.
+    pub(crate) fn new(config: &Config) -> Self {
+        AdaptiveTimeout {
+            latencies: VecDeque::with_capacity(NUM_LATENCIES_TO_CONSIDER),
+            initial: config.standstill_timeout,
+            latency_factor: config.standstill_timeout_latency_factor,
+            min: config.min_standstill_timeout,
+            max: config.max_standstill_timeout,
+        }
+    }
+
+    /// Records the latency between a block's proposal and its finalization.
+    pub(crate) fn record_latency(&mut self, latency: TimeDiff) {
+        if self.latencies.len() >= NUM_LATENCIES_TO_CONSIDER {
+            self.latencies.pop_front();
+        }
+        self.latencies.push_back(latency);
+    }
+
+    /// Returns the average of the most recent finalization latencies, if any were recorded.
+    pub(crate) fn average_latency(&self) -> Option<TimeDiff> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        let sum: u64 = self.latencies.iter().map(TimeDiff::millis).sum();
+        Some(TimeDiff::from(sum / self.latencies.len() as u64))
+    }
+
+    /// Returns the current timeout.
+    pub(crate) fn current(&self) -> TimeDiff {
+        if self.latency_factor == 0 {
+            return self.initial;
+        }
+        match self.average_latency() {
+            Some(average) => average
+                .saturating_mul(self.latency_factor)
+                .max(self.min)
+                .min(self.max),
+            None => self.initial,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adaptive_timeout(latency_factor: u64) -> AdaptiveTimeout {
+        let config = Config {
+            standstill_timeout: "1min".parse().unwrap(),
+            standstill_timeout_latency_factor: latency_factor,
+            min_standstill_timeout: "30sec".parse().unwrap(),
+            max_standstill_timeout: "5min".parse().unwrap(),
+            ..Config::default()
+        };
+        AdaptiveTimeout::new(&config)
+    }
+
+    #[test]
+    fn timeout_should_track_average_latency_within_bounds() {
+        let mut timeout = adaptive_timeout(10);
+        let secs = TimeDiff::from_seconds;
+
+        // Without any finalized blocks, the configured standstill timeout is used.
+        assert_eq!(timeout.current(), secs(60));
+
+        timeout.record_latency(secs(4));
+        timeout.record_latency(secs(8));
+        assert_eq!(timeout.average_latency(), Some(secs(6)));
+        assert_eq!(timeout.current(), secs(60));
+
+        timeout.record_latency(secs(15));
+        assert_eq!(timeout.average_latency(), Some(secs(9)));
+        assert_eq!(timeout.current(), secs(90));
+
+        // Only the most recent latencies are considered: Fast blocks bring the timeout down to the
+        // lower bound...
+        for _ in 0..NUM_LATENCIES_TO_CONSIDER {
+            timeout.record_latency(secs(1));
+        }
+        assert_eq!(timeout.average_latency(), Some(secs(1)));
+        assert_eq!(timeout.current(), secs(30));
+
+        // ...and slow blocks raise it to the upper bound.
+        for _ in 0..NUM_LATENCIES_TO_CONSIDER {
+            timeout.record_latency(secs(60));
+        }
+        assert_eq!(timeout.average_latency(), Some(secs(60)));
+        assert_eq!(timeout.current(), secs(300));
+
+        // In between, the timeout follows the average.
+        for latency in [20, 21, 22, 23, 24, 16, 17, 18, 19, 20].iter() {
+            timeout.record_latency(secs(*latency));
+        }
+        assert_eq!(timeout.average_latency(), Some(secs(20)));
+        assert_eq!(timeout.current(), secs(200));
+    }
+
+    #[test]
+    fn timeout_should_not_adapt_if_disabled() {
+        let mut timeout = adaptive_timeout(0);
+        timeout.record_latency(TimeDiff::from_seconds(1));
+        assert_eq!(timeout.current(), TimeDiff::from_seconds(60));
+    }
+}
@@ -22,6 +22,16 @@ pub struct Config {
     /// If another `standstill_timeout` passes assume we failed to join the network and restart.
     #[serde(default = "default_shutdown_on_standstill")]
     pub shutdown_on_standstill: bool,
+    /// If non-zero, once blocks were finalized in the current era the standstill timeout is this
+    /// many times their recent average finalization latency instead of `standstill_timeout`.
+    #[serde(default = "default_standstill_timeout_latency_factor")]
+    pub standstill_timeout_latency_factor: u64,
+    /// The lower bound of the standstill timeout adapted to the finalization latency.
+    #[serde(default = "default_min_standstill_timeout")]
+    pub min_standstill_timeout: TimeDiff,
+    /// The upper bound of the standstill timeout adapted to the finalization latency.
+    #[serde(default = "default_max_standstill_timeout")]
+    pub max_standstill_timeout: TimeDiff,
     /// Log inactive or faulty validators periodically, with this interval.
     pub log_participation_interval: TimeDiff,
     /// Log the size of every incoming and outgoing serialized unit.
@@ -31,6 +41,10 @@ pub struct Config {
     pub max_execution_delay: u64,
     /// The maximum number of peers we request the same vertex from in parallel.
     pub max_requests_for_vertex: usize,
+    /// The maximum number of recent messages per era kept for replaying to peers catching up.
+    /// If zero, no messages are kept and no catch-up is requested.
+    #[serde(default = "default_replay_buffer_capacity")]
+    pub replay_buffer_capacity: usize,
     pub round_success_meter: RSMConfig,
 }
 
@@ -38,6 +52,22 @@ fn default_shutdown_on_standstill() -> bool {
     false
 }
 
+fn default_replay_buffer_capacity() -> usize {
+    10_000
+}
+
+fn default_standstill_timeout_latency_factor() -> u64 {
+    20
+}
+
+fn default_min_standstill_timeout() -> TimeDiff {
+    "1min".parse().unwrap()
+}
+
+fn default_max_standstill_timeout() -> TimeDiff {
+    "30min".parse().unwrap()
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config {
@@ -45,10 +75,14 @@ impl Default for Config {
             pending_vertex_timeout: "10sec".parse().unwrap(),
             standstill_timeout: "1min".parse().unwrap(),
             shutdown_on_standstill: false,
+            standstill_timeout_latency_factor: default_standstill_timeout_latency_factor(),
+            min_standstill_timeout: default_min_standstill_timeout(),
+            max_standstill_timeout: default_max_standstill_timeout(),
             log_participation_interval: "10sec".parse().unwrap(),
             log_unit_sizes: false,
             max_execution_delay: 3,
             max_requests_for_vertex: 5,
+            replay_buffer_capacity: default_replay_buffer_capacity(),
             round_success_meter: RSMConfig::default(),
         }
     }
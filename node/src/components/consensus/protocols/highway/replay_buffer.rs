@@ -0,0 +1,121 @@
+//! Replay buffer
+//!
+//! Every era's protocol instance keeps the most recent vertices added to its state, serialized as
+//! the signed messages they were sent in, in the order they were added. A peer that joined the era
+//! late can request them all at once and catch up on the protocol state, instead of requesting
+//! every missing dependency individually.
+//!
+//! The buffer holds at most the configured number of messages, evicting the oldest ones first. Once
+//! the era ends, the buffer is cleared and no further messages are stored.
+//!
+//! The messages replayed in response to a single request are limited in total size, favoring the
+//! most recent ones: the requester can still request any missing dependencies individually.
+
+use std::collections::VecDeque;
+
+use datasize::DataSize;
+
+/// A bounded buffer of recent consensus messages of a single era.
+#[derive(DataSize, Debug)]
+pub(crate) struct ReplayBuffer {
+    /// The buffered serialized messages, oldest first.
+    messages: VecDeque<Vec<u8>>,
+    /// The maximum number of messages kept. The buffer is disabled if zero.
+    capacity: usize,
+    /// Whether the era ended, so no more messages are buffered.
+    closed: bool,
+}
+
+impl ReplayBuffer {
+    /// Creates a new, empty replay buffer holding at most `capacity` messages.
+    pub(crate) fn new(capacity: usize) -> Self {
+        ReplayBuffer {
+            messages: VecDeque::new(),
+            capacity,
+            closed: false,
+        }
+    }
+
+    /// Returns whether the buffer is able to store messages at all.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Adds a message to the buffer, evicting the oldest one if it is full.
+    pub(crate) fn push(&mut self, message: Vec<u8>) {
+        if self.closed || !self.is_enabled() {
+            return;
+        }
+        if self.messages.len() >= self.capacity {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(message);
+    }
+
+    /// Clears the buffer and stops buffering further messages, once the era has ended.
+    pub(crate) fn close(&mut self) {
+        self.messages = VecDeque::new();
+        self.closed = true;
+    }
+
+    /// Returns the buffered messages, oldest first.
+    pub(crate) fn messages(&self) -> impl Iterator<Item = &Vec<u8>> {
+        self.messages.iter()
+    }
+
+    /// Returns the most recent buffered messages whose total size does not exceed `max_size`
+    /// bytes, oldest first.
+    pub(crate) fn recent_messages(&self, max_size: usize) -> impl Iterator<Item = &Vec<u8>> {
+        let mut total_size = 0;
+        let count = self
+            .messages
+            .iter()
+            .rev()
+            .take_while(|message| {
+                total_size += message.len();
+                total_size <= max_size
+            })
+            .count();
+        self.messages.iter().skip(self.messages.len() - count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_evict_oldest_messages_and_stop_when_closed() {
+        let mut buffer = ReplayBuffer::new(3);
+        for i in 0..5u8 {
+            buffer.push(vec![i]);
+        }
+        let buffered: Vec<_> = buffer.messages().cloned().collect();
+        assert_eq!(buffered, vec![vec![2], vec![3], vec![4]]);
+
+        buffer.close();
+        buffer.push(vec![5]);
+        assert_eq!(buffer.messages().count(), 0);
+
+        let mut disabled = ReplayBuffer::new(0);
+        disabled.push(vec![0]);
+        assert!(!disabled.is_enabled());
+        assert_eq!(disabled.messages().count(), 0);
+    }
+
+    #[test]
+    fn should_limit_total_size_of_recent_messages() {
+        let mut buffer = ReplayBuffer::new(10);
+        for i in 1..=4u8 {
+            buffer.push(vec![i; i as usize]);
+        }
+
+        // The most recent messages are returned, oldest first, as long as they fit.
+        let recent = |max_size| -> Vec<_> { buffer.recent_messages(max_size).cloned().collect() };
+        assert_eq!(recent(10), buffer.messages().cloned().collect::<Vec<_>>());
+        assert_eq!(recent(9), vec![vec![2; 2], vec![3; 3], vec![4; 4]]);
+        assert_eq!(recent(8), vec![vec![3; 3], vec![4; 4]]);
+        assert_eq!(recent(4), vec![vec![4; 4]]);
+        assert!(recent(3).is_empty());
+    }
+}
@@ -19,7 +19,7 @@ use crate::{
         },
         protocols::highway::{
             config::Config as HighwayConfig, HighwayMessage, ACTION_ID_VERTEX,
-            TIMER_ID_STANDSTILL_ALERT,
+            MIN_CATCH_UP_REQUEST_INTERVAL, TIMER_ID_STANDSTILL_ALERT,
         },
         tests::utils::{new_test_chainspec, ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PUBLIC_KEY},
         traits::Context,
@@ -60,6 +60,21 @@ pub(crate) fn new_test_highway_protocol<I1, I2, T>(
     weights: I1,
     init_faulty: I2,
 ) -> Box<dyn ConsensusProtocol<NodeId, ClContext>>
+where
+    I1: IntoIterator<Item = (PublicKey, T)>,
+    I2: IntoIterator<Item = PublicKey>,
+    T: Into<U512>,
+{
+    new_test_highway_protocol_for_era(weights, init_faulty, INSTANCE_ID_DATA, None)
+}
+
+/// Creates a new `HighwayProtocol` with the given validators, instance ID and previous era.
+fn new_test_highway_protocol_for_era<I1, I2, T>(
+    weights: I1,
+    init_faulty: I2,
+    instance_id_data: &[u8],
+    prev_cp: Option<&dyn ConsensusProtocol<NodeId, ClContext>>,
+) -> Box<dyn ConsensusProtocol<NodeId, ClContext>>
 where
     I1: IntoIterator<Item = (PublicKey, T)>,
     I2: IntoIterator<Item = PublicKey>,
@@ -84,13 +99,13 @@ where
     // Timestamp of the genesis era start and test start.
     let start_timestamp: Timestamp = 0.into();
     let (hw_proto, outcomes) = HighwayProtocol::<NodeId, ClContext>::new_boxed(
-        ClContext::hash(INSTANCE_ID_DATA),
+        ClContext::hash(instance_id_data),
         weights.into_iter().collect(),
         &init_faulty.into_iter().collect(),
         &None.into_iter().collect(),
         &(&chainspec).into(),
         &config,
-        None,
+        prev_cp,
         start_timestamp,
         0,
         start_timestamp,
@@ -247,6 +262,262 @@ fn send_a_valid_wire_unit() {
     );
 }
 
+#[test]
+fn ignore_units_from_dropped_validator() {
+    const NEXT_INSTANCE_ID_DATA: &[u8; 1] = &[124u8; 1];
+    let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
+    let now = Timestamp::zero();
+    let sender = NodeId(123);
+    let alice_unit_msg = |instance_id_data: &[u8], creator, panorama| {
+        let wunit: WireUnit<ClContext> = WireUnit {
+            panorama,
+            creator,
+            instance_id: ClContext::hash(instance_id_data),
+            value: Some(Arc::new(BlockPayload::new(vec![], vec![], vec![], false))),
+            seq_number: 0,
+            timestamp: now,
+            round_exp: 14,
+            endorsed: BTreeSet::new(),
+        };
+        let swunit = SignedWireUnit::new(wunit.into_hashed(), &alice_keypair);
+        HighwayMessage::NewVertex(Vertex::Unit(swunit)).serialize()
+    };
+    let is_rejected = |outcomes: &[ProtocolOutcome<NodeId, ClContext>]| {
+        outcomes
+            .iter()
+            .any(|outcome| matches!(outcome, ProtocolOutcome::InvalidIncomingMessage(..)))
+    };
+
+    // In the current era, ALICE is a validator and her units are accepted.
+    let validators = vec![
+        (ALICE_PUBLIC_KEY.clone(), 100),
+        (BOB_PUBLIC_KEY.clone(), 100),
+    ];
+    let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
+    let msg = alice_unit_msg(INSTANCE_ID_DATA, ALICE, Panorama::from(vec![N, N]));
+    let outcomes = highway_protocol.handle_message(sender, msg, now);
+    assert!(
+        !is_rejected(&outcomes),
+        "unexpected outcomes: {:?}",
+        outcomes
+    );
+
+    // The next era's weights drop ALICE and change BOB's weight. Her units are rejected, no matter
+    // which validator index they claim.
+    let next_validators = vec![(BOB_PUBLIC_KEY.clone(), 50)];
+    let mut next_highway_protocol = new_test_highway_protocol_for_era(
+        next_validators,
+        vec![],
+        NEXT_INSTANCE_ID_DATA,
+        Some(&*highway_protocol),
+    );
+    assert!(next_highway_protocol.validators_with_evidence().is_empty());
+    for creator in [ValidatorIndex(0), ValidatorIndex(1)].iter() {
+        let msg = alice_unit_msg(NEXT_INSTANCE_ID_DATA, *creator, Panorama::from(vec![N]));
+        let outcomes = next_highway_protocol.handle_message(sender, msg, now);
+        assert!(
+            is_rejected(&outcomes),
+            "unexpected outcomes: {:?}",
+            outcomes
+        );
+    }
+}
+
+#[test]
+fn report_equivocation() {
+    let creator: ValidatorIndex = ALICE;
+    let validators = vec![
+        (ALICE_PUBLIC_KEY.clone(), 100),
+        (BOB_PUBLIC_KEY.clone(), 100),
+    ];
+    let state: State<ClContext> = new_test_state(validators.iter().map(|(_pk, w)| *w), 0);
+    let panorama: Panorama<ClContext> = Panorama::from(vec![N, N]);
+    let seq_number = panorama.next_seq_num(&state, creator);
+    let instance_id = ClContext::hash(INSTANCE_ID_DATA);
+    let now = Timestamp::zero();
+    let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
+    // Two proposals by ALICE with the same sequence number, differing only in their values.
+    let create_proposal = |random_bit| {
+        let wunit: WireUnit<ClContext> = WireUnit {
+            panorama: panorama.clone(),
+            creator,
+            instance_id,
+            value: Some(Arc::new(BlockPayload::new(
+                vec![],
+                vec![],
+                vec![],
+                random_bit,
+            ))),
+            seq_number,
+            timestamp: now,
+            round_exp: 14,
+            endorsed: BTreeSet::new(),
+        };
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair)
+    };
+    let proposals = vec![create_proposal(false), create_proposal(true)];
+    let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
+    let sender = NodeId(123);
+
+    let mut equivocations = Vec::new();
+    for proposal in &proposals {
+        let msg = HighwayMessage::NewVertex(Vertex::Unit(proposal.clone())).serialize();
+        let mut outcomes = highway_protocol.handle_message(sender, msg, now);
+        while let Some(outcome) = outcomes.pop() {
+            match outcome {
+                ProtocolOutcome::QueueAction(ACTION_ID_VERTEX) => {
+                    outcomes.extend(highway_protocol.handle_action(ACTION_ID_VERTEX, now))
+                }
+                ProtocolOutcome::NewEvidence {
+                    validator_id,
+                    conflicting_messages: Some((message1, message2)),
+                } => equivocations.push((validator_id, message1, message2)),
+                _ => (),
+            }
+        }
+    }
+
+    // The equivocation is reported once, along with both conflicting proposals.
+    let unit_hash = |msg: &[u8]| match bincode::deserialize::<HighwayMessage<ClContext>>(msg) {
+        Ok(HighwayMessage::NewVertex(Vertex::Unit(swunit))) => swunit.hash(),
+        other => panic!("unexpected message {:?}", other),
+    };
+    match &*equivocations {
+        [(validator_id, message1, message2)] => {
+            assert_eq!(*validator_id, *ALICE_PUBLIC_KEY);
+            let mut reported_hashes = vec![unit_hash(message1), unit_hash(message2)];
+            let mut proposal_hashes: Vec<_> =
+                proposals.iter().map(|swunit| swunit.hash()).collect();
+            reported_hashes.sort();
+            proposal_hashes.sort();
+            assert_eq!(reported_hashes, proposal_hashes);
+        }
+        _ => panic!("unexpected equivocations: {:?}", equivocations),
+    }
+    assert!(highway_protocol
+        .validators_with_evidence()
+        .contains(&&*ALICE_PUBLIC_KEY));
+}
+
+/// Passes the message to the protocol, handling all resulting actions, and returns all other
+/// outcomes.
+fn handle_message_and_actions(
+    protocol: &mut dyn ConsensusProtocol<NodeId, ClContext>,
+    sender: NodeId,
+    msg: Vec<u8>,
+    now: Timestamp,
+) -> Vec<ProtocolOutcome<NodeId, ClContext>> {
+    let mut outcomes = protocol.handle_message(sender, msg, now);
+    let mut other_outcomes = Vec::new();
+    while let Some(outcome) = outcomes.pop() {
+        match outcome {
+            ProtocolOutcome::QueueAction(ACTION_ID_VERTEX) => {
+                outcomes.extend(protocol.handle_action(ACTION_ID_VERTEX, now))
+            }
+            outcome => other_outcomes.push(outcome),
+        }
+    }
+    other_outcomes
+}
+
+/// Returns the panorama of the given Highway protocol instance.
+fn panorama_of(protocol: &dyn ConsensusProtocol<NodeId, ClContext>) -> Panorama<ClContext> {
+    protocol
+        .as_any()
+        .downcast_ref::<HighwayProtocol<NodeId, ClContext>>()
+        .expect("should be a Highway instance")
+        .highway
+        .state()
+        .panorama()
+        .clone()
+}
+
+#[test]
+fn fresh_instance_catches_up_by_replaying_buffer() {
+    let validators = vec![(ALICE_PUBLIC_KEY.clone(), 100)];
+    let now = Timestamp::zero();
+    let wunit: WireUnit<ClContext> = WireUnit {
+        panorama: Panorama::from(vec![N]),
+        creator: ALICE,
+        instance_id: ClContext::hash(INSTANCE_ID_DATA),
+        value: Some(Arc::new(BlockPayload::new(vec![], vec![], vec![], false))),
+        seq_number: 0,
+        timestamp: now,
+        round_exp: 14,
+        endorsed: BTreeSet::new(),
+    };
+    let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
+    let msg = HighwayMessage::NewVertex(Vertex::Unit(SignedWireUnit::new(
+        wunit.into_hashed(),
+        &alice_keypair,
+    )))
+    .serialize();
+
+    // An instance that has been running for a while has added ALICE's unit to its state.
+    let up_to_date_peer = NodeId(1);
+    let mut up_to_date = new_test_highway_protocol(validators.clone(), vec![]);
+    let _ = handle_message_and_actions(&mut *up_to_date, NodeId(123), msg, now);
+
+    // A fresh instance requests to catch up once it becomes current.
+    let fresh_peer = NodeId(2);
+    let mut fresh = new_test_highway_protocol(validators, vec![]);
+    assert_ne!(panorama_of(&*fresh), panorama_of(&*up_to_date));
+    let catch_up_request = fresh
+        .handle_is_current()
+        .into_iter()
+        .find_map(|outcome| match outcome {
+            ProtocolOutcome::CreatedCatchUpRequest(msg)
+                if matches!(
+                    bincode::deserialize::<HighwayMessage<ClContext>>(&msg),
+                    Ok(HighwayMessage::CatchUpRequest)
+                ) =>
+            {
+                Some(msg)
+            }
+            _ => None,
+        })
+        .expect("should request to catch up");
+
+    // The up-to-date instance replays its buffered messages to the fresh one...
+    let replayed: Vec<_> = up_to_date
+        .handle_message(fresh_peer, catch_up_request.clone(), now)
+        .into_iter()
+        .map(|outcome| match outcome {
+            ProtocolOutcome::CreatedTargetedMessage(msg, recipient) => {
+                assert_eq!(recipient, fresh_peer);
+                msg
+            }
+            outcome => panic!("unexpected outcome: {:?}", outcome),
+        })
+        .collect();
+    assert_eq!(replayed.len(), 1);
+
+    // Repeated requests from the same peer are only answered after a while.
+    assert!(up_to_date
+        .handle_message(fresh_peer, catch_up_request.clone(), now)
+        .is_empty());
+    let later = now + MIN_CATCH_UP_REQUEST_INTERVAL;
+    assert_eq!(
+        up_to_date
+            .handle_message(fresh_peer, catch_up_request, later)
+            .len(),
+        1
+    );
+
+    // ...which then reaches the same protocol state.
+    for msg in replayed {
+        let outcomes = handle_message_and_actions(&mut *fresh, up_to_date_peer, msg, now);
+        assert!(
+            !outcomes
+                .iter()
+                .any(|outcome| matches!(outcome, ProtocolOutcome::InvalidIncomingMessage(..))),
+            "unexpected outcomes: {:?}",
+            outcomes
+        );
+    }
+    assert_eq!(panorama_of(&*fresh), panorama_of(&*up_to_date));
+}
+
 #[test]
 fn detect_doppelganger() {
     let creator: ValidatorIndex = ALICE;
@@ -1,4 +1,5 @@
 //! Contract Runtime component.
+mod checkpoint;
 mod config;
 mod operations;
 mod types;
@@ -6,10 +7,12 @@ mod types;
 use std::{
     collections::{BTreeMap, HashMap, VecDeque},
     fmt::{self, Debug, Formatter},
+    future::Future,
     sync::Arc,
-    time::Instant,
+    time::{Duration, Instant},
 };
 
+use checkpoint::CheckpointStore;
 pub use config::Config;
 use smallvec::SmallVec;
 
@@ -18,7 +21,7 @@ pub use types::{EraValidatorsRequest, ValidatorWeightsByEraIdRequest};
 use datasize::DataSize;
 use derive_more::From;
 use lmdb::DatabaseFlags;
-use prometheus::{self, Histogram, HistogramOpts, IntGauge, Registry};
+use prometheus::{self, Histogram, HistogramOpts, HistogramVec, IntCounter, IntGauge, Registry};
 use thiserror::Error;
 use tracing::{debug, error, trace};
 
@@ -39,6 +42,11 @@ use casper_types::{
     system::auction::ValidatorWeights, ExecutionResult, ProtocolVersion, PublicKey, U512,
 };
 
+#[cfg(test)]
+use casper_execution_engine::core::engine_state::{
+    BalanceRequest, BalanceResult, QueryRequest, QueryResult,
+};
+
 use crate::{
     components::Component,
     crypto::hash::Digest,
@@ -143,6 +151,13 @@ pub struct ContractRuntime {
 
     protocol_version: ProtocolVersion,
 
+    /// Whether the deploys of a block are executed speculatively in parallel.
+    parallel_deploy_execution: bool,
+
+    /// Persists the progress of executing a block, to resume from after a restart.
+    #[data_size(skip)]
+    checkpoint_store: Arc<CheckpointStore>,
+
     /// A mapping from block height to executed block's ID and post-state hash, to allow
     /// identification of a parent block's details once a finalized block has been executed.
     ///
@@ -176,6 +191,14 @@ pub struct ContractRuntimeMetrics {
     missing_trie_keys: Histogram,
     put_trie: Histogram,
     read_trie: Histogram,
+    /// Wall-clock time to execute a single deploy of a block, by outcome.
+    deploy_execution_time: HistogramVec,
+    /// Wall-clock time to execute a single deploy of a block per unit of gas, by outcome.
+    deploy_execution_time_per_gas: HistogramVec,
+    /// Number of deploys executed in parallel whose speculative result was committed.
+    speculative_deploys: IntCounter,
+    /// Number of deploys executed in parallel which had to be re-executed due to a conflict.
+    reexecuted_deploys: IntCounter,
     /// The current chain height.
     pub chain_height: IntGauge,
 }
@@ -221,6 +244,15 @@ const PUT_TRIE_NAME: &str = "contract_runtime_put_trie";
 const PUT_TRIE_HELP: &str = "tracking run of engine_state.put_trie in seconds.";
 const MISSING_TRIE_KEYS_NAME: &str = "contract_runtime_missing_trie_keys";
 const MISSING_TRIE_KEYS_HELP: &str = "tracking run of engine_state.missing_trie_keys in seconds.";
+const DEPLOY_EXECUTION_TIME_NAME: &str = "contract_runtime_deploy_execution_time";
+const DEPLOY_EXECUTION_TIME_HELP: &str =
+    "tracking execution of a single deploy of a block in seconds, by outcome.";
+const DEPLOY_EXECUTION_TIME_PER_GAS_NAME: &str = "contract_runtime_deploy_execution_time_per_gas";
+const DEPLOY_EXECUTION_TIME_PER_GAS_HELP: &str =
+    "tracking execution of a single deploy of a block in nanoseconds per unit of gas, by outcome.";
+
+/// Label of the deploy execution metrics telling whether the deploy succeeded or failed.
+const OUTCOME_LABEL: &str = "outcome";
 
 /// Create prometheus Histogram and register.
 fn register_histogram_metric(
@@ -244,8 +276,42 @@ impl ContractRuntimeMetrics {
     fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
         let chain_height = IntGauge::new("chain_height", "current chain height")?;
         registry.register(Box::new(chain_height.clone()))?;
+        let speculative_deploys = IntCounter::new(
+            "contract_runtime_speculative_deploys",
+            "number of deploys executed in parallel whose speculative result was committed",
+        )?;
+        registry.register(Box::new(speculative_deploys.clone()))?;
+        let reexecuted_deploys = IntCounter::new(
+            "contract_runtime_reexecuted_deploys",
+            "number of deploys executed in parallel which were re-executed due to a conflict",
+        )?;
+        registry.register(Box::new(reexecuted_deploys.clone()))?;
+        let deploy_execution_time = HistogramVec::new(
+            HistogramOpts::new(DEPLOY_EXECUTION_TIME_NAME, DEPLOY_EXECUTION_TIME_HELP).buckets(
+                prometheus::exponential_buckets(
+                    EXPONENTIAL_BUCKET_START,
+                    EXPONENTIAL_BUCKET_FACTOR,
+                    EXPONENTIAL_BUCKET_COUNT,
+                )?,
+            ),
+            &[OUTCOME_LABEL],
+        )?;
+        registry.register(Box::new(deploy_execution_time.clone()))?;
+        let deploy_execution_time_per_gas = HistogramVec::new(
+            HistogramOpts::new(
+                DEPLOY_EXECUTION_TIME_PER_GAS_NAME,
+                DEPLOY_EXECUTION_TIME_PER_GAS_HELP,
+            )
+            .buckets(prometheus::exponential_buckets(0.001, 4.0, 12)?),
+            &[OUTCOME_LABEL],
+        )?;
+        registry.register(Box::new(deploy_execution_time_per_gas.clone()))?;
         Ok(ContractRuntimeMetrics {
             chain_height,
+            deploy_execution_time,
+            deploy_execution_time_per_gas,
+            speculative_deploys,
+            reexecuted_deploys,
             run_execute: register_histogram_metric(registry, RUN_EXECUTE_NAME, RUN_EXECUTE_HELP)?,
             apply_effect: register_histogram_metric(
                 registry,
@@ -287,6 +353,31 @@ impl ContractRuntimeMetrics {
     }
 }
 
+impl ContractRuntimeMetrics {
+    /// Records the time it took to execute a single deploy of a block.
+    fn observe_deploy_execution(
+        &self,
+        execution_result: &engine_state::ExecutionResult,
+        execution_time: Duration,
+    ) {
+        let outcome = if execution_result.is_success() {
+            "success"
+        } else {
+            "failure"
+        };
+        self.deploy_execution_time
+            .with_label_values(&[outcome])
+            .observe(execution_time.as_secs_f64());
+        let gas = execution_result.cost().value();
+        if !gas.is_zero() {
+            let gas = gas.min(U512::from(u64::MAX)).low_u64();
+            self.deploy_execution_time_per_gas
+                .with_label_values(&[outcome])
+                .observe(execution_time.as_nanos() as f64 / gas as f64);
+        }
+    }
+}
+
 impl<REv: ReactorEventT> Component<REv> for ContractRuntime
 where
     REv: From<Event> + Send,
@@ -294,6 +385,10 @@ where
     type Event = Event;
     type ConstructionError = ConfigError;
 
+    fn name(&self) -> &str {
+        "contract_runtime"
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -461,6 +556,19 @@ where
                         }
                         .ignore()
                     }
+                    ContractRuntimeRequest::ExecuteDryRun {
+                        execute_request,
+                        responder,
+                    } => {
+                        trace!(?execute_request, "execute dry run request");
+                        let result = self.execute_dry_run(*execute_request);
+                        async move {
+                            let result = result.await;
+                            trace!(?result, "execute dry run response");
+                            responder.respond(result).await
+                        }
+                        .ignore()
+                    }
                     ContractRuntimeRequest::Step {
                         step_request,
                         responder,
@@ -656,12 +764,14 @@ impl ContractRuntime {
         initial_state_root_hash: Digest,
         initial_block_header: Option<&BlockHeader>,
         protocol_version: ProtocolVersion,
+        native_transfer_gas_price: u64,
         storage_config: WithDir<StorageConfig>,
         contract_runtime_config: &Config,
         registry: &Registry,
     ) -> Result<Self, ConfigError> {
         let initial_state = InitialState::new(initial_state_root_hash, initial_block_header);
         let path = storage_config.with_dir(storage_config.value().path.clone());
+        let checkpoint_store = Arc::new(CheckpointStore::new(&path));
         let environment = Arc::new(LmdbEnvironment::new(
             path.as_path(),
             contract_runtime_config.max_global_state_size(),
@@ -681,7 +791,10 @@ impl ContractRuntime {
         )?);
 
         let global_state = LmdbGlobalState::empty(environment, trie_store, protocol_data_store)?;
-        let engine_config = EngineConfig::new(contract_runtime_config.max_query_depth());
+        let engine_config = EngineConfig::new(
+            contract_runtime_config.max_query_depth(),
+            native_transfer_gas_price,
+        );
 
         let engine_state = Arc::new(EngineState::new(global_state, engine_config));
 
@@ -689,6 +802,8 @@ impl ContractRuntime {
         Ok(ContractRuntime {
             initial_state,
             protocol_version,
+            parallel_deploy_execution: contract_runtime_config.parallel_deploy_execution(),
+            checkpoint_store,
             parent_map: HashMap::new(),
             exec_queue: HashMap::new(),
             engine_state,
@@ -697,7 +812,7 @@ impl ContractRuntime {
     }
 
     /// Commits a genesis using a chainspec
-    fn commit_genesis(
+    pub(crate) fn commit_genesis(
         &self,
         chainspec: Arc<Chainspec>,
     ) -> Result<GenesisResult, engine_state::Error> {
@@ -714,6 +829,44 @@ impl ContractRuntime {
         )
     }
 
+    /// Runs a query against global state.
+    #[cfg(test)]
+    pub(crate) fn run_query(
+        &self,
+        query_request: QueryRequest,
+    ) -> Result<QueryResult, engine_state::Error> {
+        self.engine_state
+            .run_query(CorrelationId::new(), query_request)
+    }
+
+    /// Gets the balance of a purse.
+    #[cfg(test)]
+    pub(crate) fn get_balance(
+        &self,
+        balance_request: BalanceRequest,
+    ) -> Result<BalanceResult, engine_state::Error> {
+        self.engine_state.get_purse_balance(
+            CorrelationId::new(),
+            balance_request.state_hash(),
+            balance_request.purse_uref(),
+        )
+    }
+
+    /// Executes the given request against global state, discarding the resulting effects rather
+    /// than committing them.
+    ///
+    /// The returned execution result reports the gas the execution would have cost.
+    pub(crate) fn execute_dry_run(
+        &self,
+        execute_request: ExecuteRequest,
+    ) -> impl Future<Output = Result<ExecutionResult, engine_state::Error>> + Send + 'static {
+        operations::execute_dry_run(
+            Arc::clone(&self.engine_state),
+            Arc::clone(&self.metrics),
+            execute_request,
+        )
+    }
+
     /// Retrieve trie keys for the integrity check.
     pub fn trie_store_check(&self, trie_keys: Vec<Blake2bHash>) -> Vec<Blake2bHash> {
         let correlation_id = CorrelationId::new();
@@ -782,25 +935,28 @@ impl ContractRuntime {
         let era_id = finalized_block.era_id();
         let height = finalized_block.height();
 
-        // Get all deploys in order they appear in the finalized block.
+        // Get all deploys in order they appear in the finalized block, then move dependents after
+        // their dependencies.
         effect_builder
             .get_deploys_from_storage(deploy_hashes)
             .event(move |result| {
                 Event::Result(Box::new(ContractRuntimeResult::GetDeploysResult {
                     finalized_block,
-                    deploys: result
-                        .into_iter()
-                        // Assumes all deploys are present
-                        .map(|maybe_deploy| {
-                            maybe_deploy.unwrap_or_else(|| {
-                                panic!(
-                                "deploy for block in era={} and height={} is expected to exist \
-                                in the storage",
-                                era_id, height
-                            )
+                    deploys: operations::order_by_dependencies(
+                        result
+                            .into_iter()
+                            // Assumes all deploys are present
+                            .map(|maybe_deploy| {
+                                maybe_deploy.unwrap_or_else(|| {
+                                    panic!(
+                                        "deploy for block in era={} and height={} is expected \
+                                         to exist in the storage",
+                                        era_id, height
+                                    )
+                                })
                             })
-                        })
-                        .collect(),
+                            .collect(),
+                    ),
                 }))
             })
     }
@@ -815,6 +971,9 @@ impl ContractRuntime {
         // The state hash of the last execute-commit cycle is used as the block's post state
         // hash.
         let next_height = state.finalized_block.height() + 1;
+        let clear_checkpoint = Arc::clone(&self.checkpoint_store)
+            .clear(effect_builder, &state.finalized_block)
+            .ignore();
         // Update the metric.
         self.metrics
             .chain_height
@@ -828,6 +987,7 @@ impl ContractRuntime {
         let mut effects = effect_builder
             .announce_linear_chain_block(block, state.execution_results)
             .ignore();
+        effects.extend(clear_checkpoint);
         // If the child is already finalized, start execution.
         if let Some((finalized_block, deploys)) = self.exec_queue.remove(&next_height) {
             effects.extend(self.handle_get_deploys_result(
@@ -847,7 +1007,7 @@ impl ContractRuntime {
         if state.remaining_deploys.is_empty() {
             self.finalize_block_or_step(effect_builder, state)
         } else {
-            self.execute_all_deploys_in_block(state)
+            self.execute_all_deploys_in_block(effect_builder, state)
         }
     }
 
@@ -896,14 +1056,41 @@ impl ContractRuntime {
         })
     }
 
-    fn execute_all_deploys_in_block(&mut self, mut state: Box<RequestState>) -> Effects<Event> {
+    fn execute_all_deploys_in_block<REv: Send>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        state: Box<RequestState>,
+    ) -> Effects<Event> {
         let engine_state = Arc::clone(&self.engine_state);
         let metrics = Arc::clone(&self.metrics);
+        let checkpoint_store = Arc::clone(&self.checkpoint_store);
         let protocol_version = self.protocol_version;
         let block_time = state.finalized_block.timestamp().millis();
         let proposer = state.finalized_block.proposer();
+        let parallel_deploy_execution = self.parallel_deploy_execution;
         async move {
-            for deploy in state.remaining_deploys.drain(..) {
+            // If execution of this block was interrupted, continue after the last committed
+            // deploy.
+            let mut state = Arc::clone(&checkpoint_store)
+                .resume(effect_builder, state)
+                .await;
+            if parallel_deploy_execution && state.remaining_deploys.len() > 1 {
+                let stats = operations::execute_deploys_in_parallel(
+                    effect_builder,
+                    engine_state,
+                    Arc::clone(&metrics),
+                    &checkpoint_store,
+                    &mut state,
+                    block_time,
+                    protocol_version,
+                    proposer,
+                )
+                .await;
+                metrics.speculative_deploys.inc_by(stats.speculative as u64);
+                metrics.reexecuted_deploys.inc_by(stats.reexecuted as u64);
+                return state;
+            }
+            while let Some(deploy) = state.remaining_deploys.pop_front() {
                 let deploy_hash = *deploy.id();
                 let deploy_header = deploy.header().clone();
                 let deploy_item = DeployItem::from(deploy);
@@ -921,13 +1108,22 @@ impl ContractRuntime {
                 // mapping between deploy_hash and execution result, and this outer logic is
                 // enriching it with the deploy hash. If we were passing multiple deploys per exec
                 // the relation between the deploy and the execution results would be lost.
-                let result =
-                    operations::execute(engine_state.clone(), metrics.clone(), execute_request)
-                        .await;
+                let start = Instant::now();
+                let result = effect_builder
+                    .run_blocking({
+                        let engine_state = Arc::clone(&engine_state);
+                        let metrics = Arc::clone(&metrics);
+                        move || operations::run_execute(&engine_state, &metrics, execute_request)
+                    })
+                    .await;
+                let execution_time = start.elapsed();
 
                 trace!(%deploy_hash, ?result, "deploy execution result");
                 // As for now a given state is expected to exist.
                 let execution_results = result.unwrap();
+                for execution_result in &execution_results {
+                    metrics.observe_deploy_execution(execution_result, execution_time);
+                }
                 match operations::commit_execution_effects(
                     engine_state.clone(),
                     metrics.clone(),
@@ -942,6 +1138,9 @@ impl ContractRuntime {
                             .execution_results
                             .insert(deploy_hash, (deploy_header, execution_result));
                         state.state_root_hash = state_hash;
+                        Arc::clone(&checkpoint_store)
+                            .save(effect_builder, &state, deploy_hash)
+                            .await;
                     }
                     // When commit fails we panic as we'll not be able to execute the next
                     // block.
@@ -1134,3 +1333,577 @@ impl InitialState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use casper_execution_engine::core::engine_state::{
+        executable_deploy_item::ExecutableDeployItem, genesis::GenesisResult,
+    };
+    use casper_types::{
+        account::AccountHash,
+        bytesrepr::Bytes,
+        runtime_args,
+        system::{mint, standard_payment},
+        EraId, RuntimeArgs, SecretKey,
+    };
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{
+        crypto::AsymmetricKeyExt,
+        reactor::{EventQueueHandle, QueueKind, Scheduler},
+        types::{BlockPayload, Timestamp},
+        utils::{self, Loadable, RESOURCES_PATH},
+    };
+
+    fn gas_of(execution_result: &ExecutionResult) -> U512 {
+        match execution_result {
+            ExecutionResult::Success { cost, .. } | ExecutionResult::Failure { cost, .. } => *cost,
+        }
+    }
+
+    /// Creates a contract runtime with the local chainspec's genesis committed, returning it along
+    /// with the genesis state root hash.
+    fn new_contract_runtime_at_genesis(
+        chainspec: &Arc<Chainspec>,
+        storage_dir: &TempDir,
+        storage_config: StorageConfig,
+    ) -> (ContractRuntime, Digest) {
+        let contract_runtime = ContractRuntime::new(
+            Digest::default(),
+            None,
+            chainspec.protocol_config.version,
+            chainspec.deploy_config.native_transfer_gas_price,
+            WithDir::new(storage_dir.path(), storage_config),
+            &Config::default(),
+            &Registry::new(),
+        )
+        .unwrap();
+        let genesis_state_root_hash = match contract_runtime.commit_genesis(Arc::clone(chainspec)) {
+            Ok(GenesisResult::Success {
+                post_state_hash, ..
+            }) => Digest::from(post_state_hash),
+            result => panic!("failed to commit genesis: {:?}", result),
+        };
+        (contract_runtime, genesis_state_root_hash)
+    }
+
+    /// Creates a deploy transferring `amount` to `target`.
+    fn new_transfer_deploy(
+        chainspec: &Chainspec,
+        secret_key: &SecretKey,
+        target: AccountHash,
+        amount: U512,
+        id: u64,
+    ) -> Deploy {
+        let payment = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: runtime_args! { standard_payment::ARG_AMOUNT => U512::from(10_000_000_000u64) },
+        };
+        let session = ExecutableDeployItem::Transfer {
+            args: runtime_args! {
+                mint::ARG_TARGET => target,
+                mint::ARG_AMOUNT => amount,
+                mint::ARG_ID => Some(id),
+            },
+        };
+        Deploy::new(
+            Timestamp::now(),
+            chainspec.deploy_config.max_ttl,
+            1,
+            vec![],
+            chainspec.network_config.name.clone(),
+            payment,
+            session,
+            secret_key,
+        )
+    }
+
+    /// Creates a finalized block at height 1 containing the given transfers.
+    fn new_finalized_block(transfers: &[Deploy], proposer: PublicKey) -> FinalizedBlock {
+        FinalizedBlock::new(
+            BlockPayload::new(
+                vec![],
+                transfers.iter().map(|deploy| *deploy.id()).collect(),
+                vec![],
+                false,
+            ),
+            None,
+            Timestamp::now(),
+            EraId::from(0),
+            1,
+            proposer,
+        )
+    }
+
+    #[tokio::test]
+    async fn dry_run_should_report_gas_charged_by_real_execution() {
+        let (storage_config, temp_dir) = StorageConfig::default_for_tests();
+        let chainspec = Arc::new(Chainspec::from_resources("local"));
+        let (contract_runtime, genesis_state_root_hash) =
+            new_contract_runtime_at_genesis(&chainspec, &temp_dir, storage_config);
+
+        // The faucet is the first genesis account; the others are used as proposer and recipient.
+        let secret_key = SecretKey::from_file(RESOURCES_PATH.join("local/secret_keys/faucet.pem"))
+            .expect("should load faucet secret key");
+        let accounts = chainspec.network_config.accounts_config.accounts();
+        let proposer = accounts[1].public_key();
+        let target = accounts[2].public_key().to_account_hash();
+        let amount = U512::from(chainspec.deploy_config.native_transfer_minimum_motes);
+        let deploy = new_transfer_deploy(&chainspec, &secret_key, target, amount, 1);
+        let new_execute_request = || {
+            ExecuteRequest::new(
+                genesis_state_root_hash.into(),
+                deploy.header().timestamp().millis(),
+                vec![DeployItem::from(deploy.clone())],
+                chainspec.protocol_config.version,
+                proposer.clone(),
+            )
+        };
+
+        let dry_run_result = contract_runtime
+            .execute_dry_run(new_execute_request())
+            .await
+            .expect("dry run should execute");
+        assert!(
+            matches!(dry_run_result, ExecutionResult::Success { .. }),
+            "unexpected dry run result: {:?}",
+            dry_run_result
+        );
+        assert!(gas_of(&dry_run_result) > U512::zero());
+
+        // Executing the same deploy for real against the same state, and committing the effects,
+        // charges the same gas.
+        let execution_results = operations::execute(
+            Arc::clone(&contract_runtime.engine_state),
+            Arc::clone(&contract_runtime.metrics),
+            new_execute_request(),
+        )
+        .await
+        .expect("should execute");
+        let (_post_state_hash, execution_result) = operations::commit_execution_effects(
+            Arc::clone(&contract_runtime.engine_state),
+            Arc::clone(&contract_runtime.metrics),
+            genesis_state_root_hash,
+            *deploy.id(),
+            execution_results,
+        )
+        .await
+        .expect("should commit");
+        assert_eq!(gas_of(&dry_run_result), gas_of(&execution_result));
+        assert_eq!(dry_run_result, execution_result);
+    }
+
+    /// Executes and commits the given deploys one after the other, returning the resulting state
+    /// root hash and execution results.
+    async fn execute_serially(
+        contract_runtime: &ContractRuntime,
+        finalized_block: &FinalizedBlock,
+        deploys: &[Deploy],
+        mut state_root_hash: Digest,
+    ) -> (Digest, HashMap<DeployHash, ExecutionResult>) {
+        let mut execution_results = HashMap::new();
+        for deploy in deploys {
+            let execute_request = ExecuteRequest::new(
+                state_root_hash.into(),
+                finalized_block.timestamp().millis(),
+                vec![DeployItem::from(deploy.clone())],
+                contract_runtime.protocol_version,
+                finalized_block.proposer(),
+            );
+            let engine_execution_results = operations::execute(
+                Arc::clone(&contract_runtime.engine_state),
+                Arc::clone(&contract_runtime.metrics),
+                execute_request,
+            )
+            .await
+            .expect("should execute");
+            let (post_state_hash, execution_result) = operations::commit_execution_effects(
+                Arc::clone(&contract_runtime.engine_state),
+                Arc::clone(&contract_runtime.metrics),
+                state_root_hash,
+                *deploy.id(),
+                engine_execution_results,
+            )
+            .await
+            .expect("should commit");
+            assert!(
+                matches!(execution_result, ExecutionResult::Success { .. }),
+                "unexpected execution result: {:?}",
+                execution_result
+            );
+            state_root_hash = post_state_hash;
+            execution_results.insert(*deploy.id(), execution_result);
+        }
+        (state_root_hash, execution_results)
+    }
+
+    /// Executes the given deploys in parallel, checking that the resulting state root hash and
+    /// execution results are the same as those of serial execution.
+    async fn assert_parallel_execution_matches_serial_execution(
+        contract_runtime: &ContractRuntime,
+        finalized_block: FinalizedBlock,
+        deploys: &[Deploy],
+        pre_state_root_hash: Digest,
+    ) -> operations::ParallelExecutionStats {
+        let (serial_state_root_hash, serial_execution_results) = execute_serially(
+            contract_runtime,
+            &finalized_block,
+            deploys,
+            pre_state_root_hash,
+        )
+        .await;
+
+        let scheduler = utils::leak(Scheduler::<()>::new(QueueKind::weights()));
+        let effect_builder = EffectBuilder::new(EventQueueHandle::new(scheduler));
+        let block_time = finalized_block.timestamp().millis();
+        let proposer = finalized_block.proposer();
+        let mut state = RequestState {
+            finalized_block,
+            remaining_deploys: deploys.iter().cloned().collect(),
+            execution_results: HashMap::new(),
+            state_root_hash: pre_state_root_hash,
+        };
+        let stats = operations::execute_deploys_in_parallel(
+            effect_builder,
+            Arc::clone(&contract_runtime.engine_state),
+            Arc::clone(&contract_runtime.metrics),
+            &contract_runtime.checkpoint_store,
+            &mut state,
+            block_time,
+            contract_runtime.protocol_version,
+            proposer,
+        )
+        .await;
+
+        assert!(state.remaining_deploys.is_empty());
+        assert_eq!(state.state_root_hash, serial_state_root_hash);
+        let parallel_execution_results: HashMap<_, _> = state
+            .execution_results
+            .into_iter()
+            .map(|(deploy_hash, (_, execution_result))| (deploy_hash, execution_result))
+            .collect();
+        assert_eq!(parallel_execution_results, serial_execution_results);
+        assert_eq!(stats.speculative + stats.reexecuted, deploys.len());
+        stats
+    }
+
+    #[tokio::test]
+    async fn parallel_execution_should_match_serial_execution() {
+        let (storage_config, temp_dir) = StorageConfig::default_for_tests();
+        let chainspec = Arc::new(Chainspec::from_resources("local"));
+        let (contract_runtime, genesis_state_root_hash) =
+            new_contract_runtime_at_genesis(&chainspec, &temp_dir, storage_config);
+
+        // Each node account sends a transfer to a distinct new account; the faucet is the proposer.
+        let accounts = chainspec.network_config.accounts_config.accounts();
+        let proposer = accounts[0].public_key();
+        let amount = U512::from(chainspec.deploy_config.native_transfer_minimum_motes);
+        let deploys: Vec<Deploy> = (1..=4)
+            .map(|index| {
+                let secret_key = SecretKey::from_file(
+                    RESOURCES_PATH.join(format!("local/secret_keys/node-{}.pem", index)),
+                )
+                .expect("should load node secret key");
+                let target = AccountHash::new([index as u8; 32]);
+                new_transfer_deploy(&chainspec, &secret_key, target, amount, index as u64)
+            })
+            .collect();
+        let finalized_block = new_finalized_block(&deploys, proposer.clone());
+
+        let stats = assert_parallel_execution_matches_serial_execution(
+            &contract_runtime,
+            finalized_block,
+            &deploys,
+            genesis_state_root_hash,
+        )
+        .await;
+
+        // The deploys only share the payment purse, which every one of them empties again, and the
+        // proposer's purse, which they only add to, so none of them has to be re-executed.
+        assert_eq!(stats.speculative, deploys.len());
+    }
+
+    #[tokio::test]
+    async fn parallel_execution_should_reexecute_deploys_reading_keys_created_earlier() {
+        let (storage_config, temp_dir) = StorageConfig::default_for_tests();
+        let chainspec = Arc::new(Chainspec::from_resources("local"));
+        let (contract_runtime, genesis_state_root_hash) =
+            new_contract_runtime_at_genesis(&chainspec, &temp_dir, storage_config);
+
+        // Two node accounts send a transfer to the same new account.  Executed against the block's
+        // pre-state, both would create the account, while the second one has to find it created
+        // by the first one.
+        let accounts = chainspec.network_config.accounts_config.accounts();
+        let proposer = accounts[0].public_key();
+        let amount = U512::from(chainspec.deploy_config.native_transfer_minimum_motes);
+        let target = AccountHash::new([42; 32]);
+        let deploys: Vec<Deploy> = (1..=2)
+            .map(|index| {
+                let secret_key = SecretKey::from_file(
+                    RESOURCES_PATH.join(format!("local/secret_keys/node-{}.pem", index)),
+                )
+                .expect("should load node secret key");
+                new_transfer_deploy(&chainspec, &secret_key, target, amount, index as u64)
+            })
+            .collect();
+        let finalized_block = new_finalized_block(&deploys, proposer.clone());
+
+        let stats = assert_parallel_execution_matches_serial_execution(
+            &contract_runtime,
+            finalized_block,
+            &deploys,
+            genesis_state_root_hash,
+        )
+        .await;
+
+        assert_eq!(
+            stats,
+            operations::ParallelExecutionStats {
+                speculative: 1,
+                reexecuted: 1
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn should_record_execution_time_of_each_deploy() {
+        let (storage_config, temp_dir) = StorageConfig::default_for_tests();
+        let chainspec = Arc::new(Chainspec::from_resources("local"));
+        let (mut contract_runtime, genesis_state_root_hash) =
+            new_contract_runtime_at_genesis(&chainspec, &temp_dir, storage_config);
+
+        let accounts = chainspec.network_config.accounts_config.accounts();
+        let proposer = accounts[0].public_key();
+        let target = accounts[2].public_key().to_account_hash();
+        let amount = U512::from(chainspec.deploy_config.native_transfer_minimum_motes);
+        let faucet_secret_key =
+            SecretKey::from_file(RESOURCES_PATH.join("local/secret_keys/faucet.pem"))
+                .expect("should load faucet secret key");
+        let node_secret_key =
+            SecretKey::from_file(RESOURCES_PATH.join("local/secret_keys/node-1.pem"))
+                .expect("should load node secret key");
+        // The last transfer fails, as it exceeds the balance of the node account.
+        let overdraft = accounts[1].balance().value() + 1;
+        let deploys = vec![
+            new_transfer_deploy(&chainspec, &faucet_secret_key, target, amount, 1),
+            new_transfer_deploy(&chainspec, &faucet_secret_key, target, amount, 2),
+            new_transfer_deploy(&chainspec, &node_secret_key, target, overdraft, 3),
+        ];
+
+        let state = Box::new(RequestState {
+            finalized_block: new_finalized_block(&deploys, proposer),
+            remaining_deploys: deploys.into_iter().collect(),
+            execution_results: HashMap::new(),
+            state_root_hash: genesis_state_root_hash,
+        });
+        for effect in contract_runtime.execute_all_deploys_in_block(new_effect_builder(), state) {
+            effect.await;
+        }
+
+        let metrics = &contract_runtime.metrics;
+        for &(outcome, expected_count) in [("success", 2), ("failure", 1)].iter() {
+            assert_eq!(
+                metrics
+                    .deploy_execution_time
+                    .with_label_values(&[outcome])
+                    .get_sample_count(),
+                expected_count
+            );
+            assert_eq!(
+                metrics
+                    .deploy_execution_time_per_gas
+                    .with_label_values(&[outcome])
+                    .get_sample_count(),
+                expected_count
+            );
+        }
+    }
+
+    /// Creates an effect builder for effects which never schedule any event.
+    fn new_effect_builder() -> EffectBuilder<Event> {
+        let scheduler = utils::leak(Scheduler::new(QueueKind::weights()));
+        EffectBuilder::new(EventQueueHandle::new(scheduler))
+    }
+
+    /// Executes the remaining deploys of the block in `state`, returning the resulting state.
+    async fn execute_block(
+        contract_runtime: &mut ContractRuntime,
+        state: Box<RequestState>,
+    ) -> Box<RequestState> {
+        let mut executed_state = None;
+        for effect in contract_runtime.execute_all_deploys_in_block(new_effect_builder(), state) {
+            for event in effect.await {
+                match event {
+                    Event::Result(result) => match *result {
+                        ContractRuntimeResult::ExecutedAndCommitted(state) => {
+                            executed_state = Some(state)
+                        }
+                        other => panic!("unexpected result: {:?}", other),
+                    },
+                    other => panic!("unexpected event: {:?}", other),
+                }
+            }
+        }
+        executed_state.expect("should execute block")
+    }
+
+    #[tokio::test]
+    async fn interrupted_block_execution_should_resume_from_checkpoint() {
+        let chainspec = Arc::new(Chainspec::from_resources("local"));
+        let secret_key = SecretKey::from_file(RESOURCES_PATH.join("local/secret_keys/faucet.pem"))
+            .expect("should load faucet secret key");
+        let accounts = chainspec.network_config.accounts_config.accounts();
+        let proposer = accounts[1].public_key();
+        let target = accounts[2].public_key().to_account_hash();
+        let amount = U512::from(chainspec.deploy_config.native_transfer_minimum_motes);
+        let deploys: Vec<Deploy> = (1..=3)
+            .map(|id| new_transfer_deploy(&chainspec, &secret_key, target, amount, id))
+            .collect();
+        let finalized_block = new_finalized_block(&deploys, proposer.clone());
+        let new_request_state = |deploys: &[Deploy], state_root_hash| {
+            Box::new(RequestState {
+                finalized_block: finalized_block.clone(),
+                remaining_deploys: deploys.iter().cloned().collect(),
+                execution_results: HashMap::new(),
+                state_root_hash,
+            })
+        };
+
+        // Execute the whole block without interruption first.
+        let (storage_config, temp_dir) = StorageConfig::default_for_tests();
+        let (mut contract_runtime, genesis_state_root_hash) =
+            new_contract_runtime_at_genesis(&chainspec, &temp_dir, storage_config);
+        let uninterrupted_state = execute_block(
+            &mut contract_runtime,
+            new_request_state(&deploys, genesis_state_root_hash),
+        )
+        .await;
+
+        // Then, on another node, only execute the first deploy before "crashing".
+        let (storage_config, temp_dir) = StorageConfig::default_for_tests();
+        let (mut contract_runtime, genesis_state_root_hash) =
+            new_contract_runtime_at_genesis(&chainspec, &temp_dir, storage_config.clone());
+        execute_block(
+            &mut contract_runtime,
+            new_request_state(&deploys[..1], genesis_state_root_hash),
+        )
+        .await;
+        drop(contract_runtime);
+
+        // After restarting, only the remaining deploys are executed.
+        let mut contract_runtime = ContractRuntime::new(
+            genesis_state_root_hash,
+            None,
+            chainspec.protocol_config.version,
+            chainspec.deploy_config.native_transfer_gas_price,
+            WithDir::new(temp_dir.path(), storage_config),
+            &Config::default(),
+            &Registry::new(),
+        )
+        .unwrap();
+        let resumed_state = execute_block(
+            &mut contract_runtime,
+            new_request_state(&deploys, genesis_state_root_hash),
+        )
+        .await;
+        assert_eq!(
+            contract_runtime
+                .metrics
+                .deploy_execution_time
+                .with_label_values(&["success"])
+                .get_sample_count(),
+            2
+        );
+        assert_eq!(
+            resumed_state.state_root_hash,
+            uninterrupted_state.state_root_hash
+        );
+        assert_eq!(
+            resumed_state.execution_results,
+            uninterrupted_state.execution_results
+        );
+
+        // A checkpoint doesn't apply to other blocks, nor once it has been cleared.
+        let checkpoint_store = &contract_runtime.checkpoint_store;
+        let other_state = Box::new(RequestState {
+            finalized_block: new_finalized_block(&deploys[1..], proposer),
+            remaining_deploys: deploys[1..].iter().cloned().collect(),
+            execution_results: HashMap::new(),
+            state_root_hash: genesis_state_root_hash,
+        });
+        let other_state = Arc::clone(checkpoint_store)
+            .resume(new_effect_builder(), other_state)
+            .await;
+        assert_eq!(other_state.remaining_deploys.len(), deploys.len() - 1);
+        Arc::clone(checkpoint_store)
+            .clear(new_effect_builder(), &finalized_block)
+            .await;
+        let state = Arc::clone(checkpoint_store)
+            .resume(
+                new_effect_builder(),
+                new_request_state(&deploys, genesis_state_root_hash),
+            )
+            .await;
+        assert_eq!(state.remaining_deploys.len(), deploys.len());
+    }
+
+    #[test]
+    fn should_order_deploys_after_their_dependencies() {
+        let mut rng = crate::new_rng();
+        let chainspec = Chainspec::from_resources("local");
+        let secret_key = SecretKey::random(&mut rng);
+        let new_deploy = |dependencies: Vec<DeployHash>| {
+            Deploy::new(
+                Timestamp::now(),
+                chainspec.deploy_config.max_ttl,
+                1,
+                dependencies,
+                chainspec.network_config.name.clone(),
+                ExecutableDeployItem::ModuleBytes {
+                    module_bytes: Bytes::new(),
+                    args: RuntimeArgs::new(),
+                },
+                ExecutableDeployItem::ModuleBytes {
+                    module_bytes: Bytes::new(),
+                    args: RuntimeArgs::new(),
+                },
+                &secret_key,
+            )
+        };
+
+        let approve = new_deploy(vec![]);
+        let transfer = new_deploy(vec![*approve.id()]);
+        let unrelated = new_deploy(vec![]);
+        // Dependencies outside the block don't affect the order.
+        let outside = new_deploy(vec![DeployHash::random(&mut rng)]);
+
+        let ids = |deploys: &VecDeque<Deploy>| {
+            deploys
+                .iter()
+                .map(|deploy| *deploy.id())
+                .collect::<Vec<_>>()
+        };
+        let block_order: VecDeque<_> = vec![
+            transfer.clone(),
+            unrelated.clone(),
+            approve.clone(),
+            outside.clone(),
+        ]
+        .into();
+        let ordered = operations::order_by_dependencies(block_order);
+        assert_eq!(
+            ids(&ordered),
+            vec![
+                *unrelated.id(),
+                *approve.id(),
+                *outside.id(),
+                *transfer.id()
+            ]
+        );
+
+        // Deploys already in dependency order are left as they are.
+        let ordered_again = operations::order_by_dependencies(ordered.clone());
+        assert_eq!(ids(&ordered_again), ids(&ordered));
+    }
+}
@@ -0,0 +1,280 @@
+//! Checkpoints of partially executed blocks.
+//!
+//! After each deploy of a block has been executed and its effects committed, the resulting state
+//! root hash is appended to a log along with the deploy's execution result. A node restarting in
+//! the middle of executing a block can then resume after the last committed deploy instead of
+//! re-executing the whole block.
+//!
+//! The log starts with the hash of the finalized block it belongs to, followed by one
+//! length-prefixed entry per committed deploy. Every write is synced to disk before execution
+//! continues, and a log for a new block is only moved into place once its header has been synced.
+//! An entry only partially written due to a crash is discarded when resuming.
+
+use std::{
+    convert::TryInto,
+    fs::{self, File, OpenOptions},
+    future::Future,
+    io::{self, Write},
+    mem,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use casper_types::ExecutionResult;
+
+use super::RequestState;
+use crate::{
+    crypto::hash::{self, Digest},
+    effect::EffectBuilder,
+    types::{DeployHash, DeployHeader, FinalizedBlock},
+};
+
+/// Name of the file holding the checkpoint log, inside the global state directory.
+const CHECKPOINT_FILE_NAME: &str = "block_execution_checkpoint.log";
+
+/// Size of the length prefix of each entry of the log.
+const LENGTH_PREFIX_SIZE: usize = mem::size_of::<u32>();
+
+/// The outcome of executing and committing one deploy of a block.
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    deploy_hash: DeployHash,
+    deploy_header: DeployHeader,
+    execution_result: ExecutionResult,
+    /// The state root hash after committing the deploy's effects.
+    state_root_hash: Digest,
+}
+
+/// Persists the progress of executing a block.
+#[derive(Debug)]
+pub(super) struct CheckpointStore {
+    /// Path of the checkpoint log.
+    path: PathBuf,
+    /// Hash of the finalized block the log currently belongs to, if known.
+    block_hash: Mutex<Option<Digest>>,
+}
+
+/// Returns the hash identifying the given finalized block.
+///
+/// The hash of the block itself depends on the outcome of executing it, so the finalized block it
+/// is created from is hashed instead.
+fn finalized_block_hash(finalized_block: &FinalizedBlock) -> Digest {
+    let serialized = bincode::serialize(finalized_block).expect("should serialize finalized block");
+    hash::hash(&serialized)
+}
+
+impl CheckpointStore {
+    /// Creates a checkpoint store keeping its log in `dir`.
+    pub(super) fn new(dir: &Path) -> Self {
+        CheckpointStore {
+            path: dir.join(CHECKPOINT_FILE_NAME),
+            block_hash: Mutex::new(None),
+        }
+    }
+
+    /// Appends the outcome of executing the given deploy of the block in `state` to the log.
+    ///
+    /// Failing to persist a checkpoint is not fatal, it only means more deploys need to be executed
+    /// again after a restart.
+    pub(super) async fn save<REv: Send>(
+        self: Arc<Self>,
+        effect_builder: EffectBuilder<REv>,
+        state: &RequestState,
+        deploy_hash: DeployHash,
+    ) {
+        let (deploy_header, execution_result) = match state.execution_results.get(&deploy_hash) {
+            Some((deploy_header, execution_result)) => {
+                (deploy_header.clone(), execution_result.clone())
+            }
+            None => return,
+        };
+        let entry = Entry {
+            deploy_hash,
+            deploy_header,
+            execution_result,
+            state_root_hash: state.state_root_hash,
+        };
+        let block_hash = finalized_block_hash(&state.finalized_block);
+        effect_builder
+            .run_blocking(move || {
+                if let Err(error) = self.append(block_hash, &entry) {
+                    warn!(
+                        %error,
+                        path = %self.path.display(),
+                        "failed to save block execution checkpoint"
+                    );
+                }
+            })
+            .await
+    }
+
+    /// Skips the deploys of the block in `state` which were already executed according to the
+    /// log, if it belongs to that block.
+    pub(super) async fn resume<REv: Send>(
+        self: Arc<Self>,
+        effect_builder: EffectBuilder<REv>,
+        mut state: Box<RequestState>,
+    ) -> Box<RequestState> {
+        effect_builder
+            .run_blocking(move || {
+                self.resume_blocking(&mut state);
+                state
+            })
+            .await
+    }
+
+    /// Removes the log, once the given block it belongs to has been executed completely.
+    pub(super) fn clear<REv: Send>(
+        self: Arc<Self>,
+        effect_builder: EffectBuilder<REv>,
+        finalized_block: &FinalizedBlock,
+    ) -> impl Future<Output = ()> {
+        let block_hash = finalized_block_hash(finalized_block);
+        effect_builder.run_blocking(move || self.clear_blocking(block_hash))
+    }
+
+    /// Appends `entry` to the log, starting a new log if it belongs to another block.
+    fn append(&self, block_hash: Digest, entry: &Entry) -> io::Result<()> {
+        let serialized = bincode::serialize(entry)
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+        let length: u32 = serialized
+            .len()
+            .try_into()
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+
+        let mut logged_block_hash = self.block_hash.lock().expect("lock poisoned");
+        if *logged_block_hash != Some(block_hash) {
+            // Any previous log is only replaced once the new one is safely on disk.
+            *logged_block_hash = None;
+            let temp_path = self.path.with_extension("tmp");
+            let mut file = File::create(&temp_path)?;
+            file.write_all(block_hash.as_ref())?;
+            file.sync_all()?;
+            fs::rename(&temp_path, &self.path)?;
+            sync_parent_dir(&self.path)?;
+            *logged_block_hash = Some(block_hash);
+        }
+
+        let mut file = OpenOptions::new().append(true).open(&self.path)?;
+        file.write_all(&length.to_le_bytes())?;
+        file.write_all(&serialized)?;
+        file.sync_data()
+    }
+
+    fn resume_blocking(&self, state: &mut RequestState) {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                if error.kind() != io::ErrorKind::NotFound {
+                    warn!(
+                        %error,
+                        path = %self.path.display(),
+                        "failed to read block execution checkpoint"
+                    );
+                }
+                return;
+            }
+        };
+        let block_hash = finalized_block_hash(&state.finalized_block);
+        if bytes.len() < Digest::LENGTH
+            || bytes[..Digest::LENGTH] != *block_hash.as_ref()
+            || !state.execution_results.is_empty()
+        {
+            return;
+        }
+
+        // The entries are only valid as long as they are those of the next deploys of this block.
+        let mut executed = 0;
+        let mut valid_length = Digest::LENGTH;
+        let mut remaining = &bytes[Digest::LENGTH..];
+        while let Some(deploy) = state.remaining_deploys.get(executed) {
+            let entry = match parse_entry(remaining) {
+                Some((entry, rest)) if entry.deploy_hash == *deploy.id() => {
+                    remaining = rest;
+                    entry
+                }
+                _ => break,
+            };
+            valid_length = bytes.len() - remaining.len();
+            state.state_root_hash = entry.state_root_hash;
+            state.execution_results.insert(
+                entry.deploy_hash,
+                (entry.deploy_header, entry.execution_result),
+            );
+            executed += 1;
+        }
+
+        // Drop anything after the valid entries, such as an entry only partially written before a
+        // crash, so further entries can be appended.
+        if valid_length < bytes.len() {
+            warn!(
+                discarded = bytes.len() - valid_length,
+                "discarding invalid end of block execution checkpoint"
+            );
+            if let Err(error) = OpenOptions::new()
+                .write(true)
+                .open(&self.path)
+                .and_then(|file| {
+                    file.set_len(valid_length as u64)?;
+                    file.sync_all()
+                })
+            {
+                warn!(%error, "failed to truncate block execution checkpoint");
+                return;
+            }
+        }
+        *self.block_hash.lock().expect("lock poisoned") = Some(block_hash);
+
+        if executed > 0 {
+            info!(
+                block_height = state.finalized_block.height(),
+                executed, "resuming block execution from checkpoint"
+            );
+            state.remaining_deploys.drain(..executed);
+        }
+    }
+
+    fn clear_blocking(&self, block_hash: Digest) {
+        let mut logged_block_hash = self.block_hash.lock().expect("lock poisoned");
+        // The log might already belong to the next block.
+        if logged_block_hash.map_or(false, |logged| logged != block_hash) {
+            return;
+        }
+        *logged_block_hash = None;
+        if let Err(error) = fs::remove_file(&self.path) {
+            if error.kind() != io::ErrorKind::NotFound {
+                warn!(
+                    %error,
+                    path = %self.path.display(),
+                    "failed to remove block execution checkpoint"
+                );
+            }
+        }
+    }
+}
+
+/// Parses the first entry of `bytes`, returning it along with the remaining bytes.
+fn parse_entry(bytes: &[u8]) -> Option<(Entry, &[u8])> {
+    if bytes.len() < LENGTH_PREFIX_SIZE {
+        return None;
+    }
+    let (length_prefix, rest) = bytes.split_at(LENGTH_PREFIX_SIZE);
+    let length = u32::from_le_bytes(length_prefix.try_into().ok()?) as usize;
+    if rest.len() < length {
+        return None;
+    }
+    let (serialized, rest) = rest.split_at(length);
+    let entry = bincode::deserialize(serialized).ok()?;
+    Some((entry, rest))
+}
+
+/// Syncs the directory containing `path`, so a file renamed to it persists.
+fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(dir) => File::open(dir)?.sync_all(),
+        None => Ok(()),
+    }
+}
@@ -6,6 +6,7 @@ use casper_execution_engine::shared::utils;
 const DEFAULT_MAX_GLOBAL_STATE_SIZE: usize = 805_306_368_000; // 750 GiB
 const DEFAULT_MAX_READERS: u32 = 512;
 const DEFAULT_MAX_QUERY_DEPTH: u64 = 5;
+const DEFAULT_PARALLEL_DEPLOY_EXECUTION: bool = false;
 
 /// Contract runtime configuration.
 #[derive(Clone, Copy, DataSize, Debug, Deserialize, Serialize)]
@@ -26,6 +27,11 @@ pub struct Config {
     ///
     /// Defaults to 5.
     max_query_depth: Option<u64>,
+    /// Whether the deploys of a block are executed speculatively in parallel, with conflicting
+    /// deploys being re-executed in order.
+    ///
+    /// Defaults to false.
+    parallel_deploy_execution: Option<bool>,
 }
 
 impl Config {
@@ -44,6 +50,11 @@ impl Config {
     pub(crate) fn max_query_depth(&self) -> u64 {
         self.max_query_depth.unwrap_or(DEFAULT_MAX_QUERY_DEPTH)
     }
+
+    pub(crate) fn parallel_deploy_execution(&self) -> bool {
+        self.parallel_deploy_execution
+            .unwrap_or(DEFAULT_PARALLEL_DEPLOY_EXECUTION)
+    }
 }
 
 impl Default for Config {
@@ -52,6 +63,7 @@ impl Default for Config {
             max_global_state_size: Some(DEFAULT_MAX_GLOBAL_STATE_SIZE),
             max_readers: Some(DEFAULT_MAX_READERS),
             max_query_depth: Some(DEFAULT_MAX_QUERY_DEPTH),
+            parallel_deploy_execution: Some(DEFAULT_PARALLEL_DEPLOY_EXECUTION),
         }
     }
 }
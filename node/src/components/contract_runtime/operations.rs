@@ -1,19 +1,72 @@
-use std::{collections::VecDeque, sync::Arc, time::Instant};
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+    time::Instant,
+};
 
-use super::ContractRuntimeMetrics;
-use crate::{crypto::hash::Digest, types::DeployHash};
+use super::{checkpoint::CheckpointStore, ContractRuntimeMetrics, RequestState};
+use crate::{
+    crypto::hash::Digest,
+    effect::EffectBuilder,
+    types::{Deploy, DeployHash},
+};
 use casper_execution_engine::{
     core::engine_state::{
-        self, EngineState, ExecutionResult as EngineExecutionResult, ExecutionResults,
+        self, op::Op, DeployItem, EngineState, ExecutionResult as EngineExecutionResult,
+        ExecutionResults,
     },
     shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
     storage::global_state::{lmdb::LmdbGlobalState, CommitResult},
 };
-use casper_types::{ExecutionResult, Key};
+use casper_types::{ExecutionResult, Key, ProtocolVersion, PublicKey};
 use engine_state::ExecuteRequest;
+use futures::future;
 use itertools::Itertools;
 use tracing::{debug, error, trace};
 
+/// Numbers of deploys of a block executed in parallel, by how their results were obtained.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) struct ParallelExecutionStats {
+    /// Deploys whose speculative result, executed against the block's pre-state, was committed.
+    pub(super) speculative: usize,
+    /// Deploys re-executed after the committed effects of earlier deploys in the block.
+    pub(super) reexecuted: usize,
+}
+
+/// Orders the deploys of a block so that each one comes after those of its dependencies which are
+/// in the same block, keeping the order of the block otherwise.
+///
+/// Deploys whose dependencies can't be ordered before them, as they form a cycle, are placed last,
+/// in the order of the block.
+pub(super) fn order_by_dependencies(deploys: VecDeque<Deploy>) -> VecDeque<Deploy> {
+    let in_block: HashSet<DeployHash> = deploys.iter().map(|deploy| *deploy.id()).collect();
+    let mut ordered = VecDeque::with_capacity(deploys.len());
+    let mut ordered_hashes = HashSet::new();
+    let mut waiting = deploys;
+    loop {
+        let waiting_count = waiting.len();
+        let mut blocked = VecDeque::new();
+        for deploy in waiting {
+            let ready = deploy
+                .header()
+                .dependencies()
+                .iter()
+                .all(|dep| !in_block.contains(dep) || ordered_hashes.contains(dep));
+            if ready {
+                ordered_hashes.insert(*deploy.id());
+                ordered.push_back(deploy);
+            } else {
+                blocked.push_back(deploy);
+            }
+        }
+        if blocked.is_empty() || blocked.len() == waiting_count {
+            ordered.extend(blocked);
+            return ordered;
+        }
+        waiting = blocked;
+    }
+}
+
 /// Commits the execution effects.
 pub(super) async fn commit_execution_effects(
     engine_state: Arc<EngineState<LmdbGlobalState>>,
@@ -86,10 +139,34 @@ pub(super) async fn commit(
     result
 }
 
+/// Executes the given request without committing the resulting effects, so global state is left
+/// unchanged.
+pub(super) async fn execute_dry_run(
+    engine_state: Arc<EngineState<LmdbGlobalState>>,
+    metrics: Arc<ContractRuntimeMetrics>,
+    execute_request: ExecuteRequest,
+) -> Result<ExecutionResult, engine_state::Error> {
+    let execution_results = execute(engine_state, metrics, execute_request).await?;
+    let ee_execution_result = execution_results
+        .into_iter()
+        .exactly_one()
+        .expect("should only be one exec result");
+    debug!(cost = %ee_execution_result.cost(), "dry run execution finished");
+    Ok(ExecutionResult::from(&ee_execution_result))
+}
+
 pub(super) async fn execute(
     engine_state: Arc<EngineState<LmdbGlobalState>>,
     metrics: Arc<ContractRuntimeMetrics>,
     execute_request: ExecuteRequest,
+) -> Result<VecDeque<EngineExecutionResult>, engine_state::Error> {
+    run_execute(&engine_state, &metrics, execute_request)
+}
+
+pub(super) fn run_execute(
+    engine_state: &EngineState<LmdbGlobalState>,
+    metrics: &ContractRuntimeMetrics,
+    execute_request: ExecuteRequest,
 ) -> Result<VecDeque<EngineExecutionResult>, engine_state::Error> {
     trace!(?execute_request, "execute");
     let correlation_id = CorrelationId::new();
@@ -99,3 +176,125 @@ pub(super) async fn execute(
     trace!(?result, "execute result");
     result
 }
+
+/// Executes the remaining deploys of the block in `state` and commits their effects in order.
+///
+/// All deploys are first executed speculatively in parallel against the block's pre-state. Then,
+/// in the order of the block, a deploy's speculative result is committed only if it succeeded and
+/// none of the keys it accessed were written by an earlier deploy of the block. Otherwise the
+/// deploy is re-executed against the current state, exactly as in serial execution. The progress
+/// is saved to `checkpoint_store` after each committed deploy.
+pub(super) async fn execute_deploys_in_parallel<REv: Send>(
+    effect_builder: EffectBuilder<REv>,
+    engine_state: Arc<EngineState<LmdbGlobalState>>,
+    metrics: Arc<ContractRuntimeMetrics>,
+    checkpoint_store: &Arc<CheckpointStore>,
+    state: &mut RequestState,
+    block_time: u64,
+    protocol_version: ProtocolVersion,
+    proposer: PublicKey,
+) -> ParallelExecutionStats {
+    let new_execute_request = |state_root_hash: Digest, deploy: &Deploy| {
+        ExecuteRequest::new(
+            state_root_hash.into(),
+            block_time,
+            vec![DeployItem::from(deploy.clone())],
+            protocol_version,
+            proposer.clone(),
+        )
+    };
+
+    let deploys: Vec<Deploy> = state.remaining_deploys.drain(..).collect();
+    let pre_state_root_hash = state.state_root_hash;
+    let timed_execute = |execute_request| {
+        let engine_state = Arc::clone(&engine_state);
+        let metrics = Arc::clone(&metrics);
+        effect_builder.run_blocking(move || {
+            let start = Instant::now();
+            let result = run_execute(&engine_state, &metrics, execute_request);
+            (result, start.elapsed())
+        })
+    };
+    let speculative_results = future::join_all(
+        deploys
+            .iter()
+            .map(|deploy| timed_execute(new_execute_request(pre_state_root_hash, deploy))),
+    )
+    .await;
+
+    let mut stats = ParallelExecutionStats::default();
+    let mut written_keys = HashSet::new();
+    for (deploy, speculative_result) in deploys.into_iter().zip(speculative_results) {
+        let deploy_hash = *deploy.id();
+        let (speculative_result, speculative_execution_time) = speculative_result;
+        let (execution_results, execution_time) = match speculative_result {
+            Ok(execution_results) if !conflicts(&execution_results, &written_keys) => {
+                stats.speculative += 1;
+                (execution_results, speculative_execution_time)
+            }
+            _ => {
+                stats.reexecuted += 1;
+                let (result, execution_time) =
+                    timed_execute(new_execute_request(state.state_root_hash, &deploy)).await;
+                trace!(%deploy_hash, ?result, "deploy re-execution result");
+                // As for now a given state is expected to exist.
+                (result.unwrap(), execution_time)
+            }
+        };
+        for execution_result in &execution_results {
+            metrics.observe_deploy_execution(execution_result, execution_time);
+            // Identity transforms, such as those of the payment purse which is emptied again by
+            // every deploy, leave the value unchanged.
+            written_keys.extend(
+                execution_result
+                    .effect()
+                    .transforms
+                    .iter()
+                    .filter(|(_, transform)| **transform != Transform::Identity)
+                    .map(|(key, _)| *key),
+            );
+        }
+
+        match commit_execution_effects(
+            engine_state.clone(),
+            metrics.clone(),
+            state.state_root_hash,
+            deploy_hash,
+            execution_results,
+        )
+        .await
+        {
+            Ok((state_hash, execution_result)) => {
+                state
+                    .execution_results
+                    .insert(deploy_hash, (deploy.header().clone(), execution_result));
+                state.state_root_hash = state_hash;
+                Arc::clone(checkpoint_store)
+                    .save(effect_builder, state, deploy_hash)
+                    .await;
+            }
+            // When commit fails we panic as we'll not be able to execute the next block.
+            Err(_err) => panic!("unable to commit"),
+        }
+    }
+    debug!(?stats, "executed deploys in parallel");
+    stats
+}
+
+/// Returns whether any of the speculative execution results read one of the `written_keys`.
+///
+/// This includes reads of keys which were absent, as an earlier deploy may have created them.  Keys
+/// which were only added to don't conflict, as the additions are applied to the current value when
+/// committing.  Failed executions always conflict, as the keys accessed by the failed session code
+/// aren't part of their effects.
+fn conflicts(execution_results: &ExecutionResults, written_keys: &HashSet<Key>) -> bool {
+    execution_results
+        .iter()
+        .any(|execution_result| match execution_result {
+            EngineExecutionResult::Failure { .. } => true,
+            EngineExecutionResult::Success { effect, .. } => effect
+                .ops
+                .iter()
+                .any(|(key, op)| matches!(op, Op::Read | Op::Write) && written_keys.contains(key)),
+        })
+}
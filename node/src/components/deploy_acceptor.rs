@@ -1,29 +1,38 @@
 mod config;
 mod event;
+#[cfg(test)]
+mod tests;
 
-use std::{convert::Infallible, fmt::Debug};
+use std::{collections::HashSet, convert::Infallible, fmt::Debug, sync::Arc};
 
+use serde::Serialize;
 use thiserror::Error;
 use tracing::{debug, error, info};
 
+use casper_execution_engine::shared::{gas::Gas, motes::Motes};
+
 use crate::{
     components::Component,
     effect::{
-        announcements::DeployAcceptorAnnouncement,
-        requests::{ContractRuntimeRequest, StorageRequest},
+        announcements::{BlocklistAnnouncement, DeployAcceptorAnnouncement},
+        requests::{BlockProposerRequest, ContractRuntimeRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects,
     },
-    types::{chainspec::DeployConfig, Chainspec, Deploy, DeployValidationFailure, NodeId},
+    types::{Chainspec, Deploy, DeployValidationFailure, NodeId},
     utils::Source,
     NodeRng,
 };
-use casper_types::Key;
+use casper_types::{Key, PublicKey};
 
 use crate::effect::Responder;
 pub use config::Config;
 pub use event::Event;
 
-#[derive(Debug, Error)]
+/// The reason a deploy was rejected by the deploy acceptor.
+///
+/// It is serialized as part of the error returned to clients submitting a deploy via the RPC
+/// server.
+#[derive(Debug, Error, Serialize)]
 pub enum Error {
     /// An invalid deploy was received from the client.
     #[error("invalid deploy: {0}")]
@@ -34,14 +43,68 @@ pub enum Error {
     /// A deploy was sent from account with insufficient balance.
     #[error("insufficient balance")]
     InsufficientBalance,
+    /// A deploy was sent with a gas price below the configured minimum.
+    #[error("gas price of {got} is below the minimum of {min_gas_price}")]
+    InsufficientGasPrice {
+        /// The configured minimum gas price.
+        min_gas_price: u64,
+        /// The deploy's gas price.
+        got: u64,
+    },
+    /// A deploy was sent with a payment amount which does not buy any gas at its gas price.
+    #[error(
+        "payment amount of {payment_amount} cannot cover any gas at a gas price of {gas_price}"
+    )]
+    InsufficientPayment {
+        /// The deploy's payment amount.
+        payment_amount: Motes,
+        /// The deploy's gas price.
+        gas_price: u64,
+    },
+    /// A deploy was sent with more approvals than the configured maximum.
+    #[error("{got} approvals exceeds limit of {max_approvals}")]
+    ExcessiveApprovals {
+        /// The configured maximum number of approvals.
+        max_approvals: u32,
+        /// The deploy's number of approvals.
+        got: usize,
+    },
+    /// A deploy was sent with more than one approval from the same signer.
+    #[error("duplicate approval from {signer}")]
+    DuplicateApproval {
+        /// The signer of the duplicate approvals.
+        signer: PublicKey,
+    },
+    /// A deploy was sent by a client while the buffer of pending deploys was full.  The same
+    /// deploy can be sent again later.
+    #[error("deploy buffer is full, retry later")]
+    DeployBufferFull,
+}
+
+impl Error {
+    /// Returns `true` if the error proves that the deploy was tampered with or forged.
+    ///
+    /// Peers only pass on deploys whose hashes and approvals they verified themselves, so receiving
+    /// such a deploy from a peer is a protocol violation.  All other errors depend on the
+    /// receiving node's chainspec version, configuration or clock, and are not proof of malice.
+    fn is_provably_malicious(&self) -> bool {
+        matches!(
+            self,
+            Error::InvalidDeploy(DeployValidationFailure::InvalidDeployHash)
+                | Error::InvalidDeploy(DeployValidationFailure::InvalidBodyHash)
+                | Error::InvalidDeploy(DeployValidationFailure::InvalidApproval { .. })
+        )
+    }
 }
 
 /// A helper trait constraining `DeployAcceptor` compatible reactor events.
 pub trait ReactorEventT:
     From<Event>
     + From<DeployAcceptorAnnouncement<NodeId>>
+    + From<BlocklistAnnouncement<NodeId>>
     + From<StorageRequest>
     + From<ContractRuntimeRequest>
+    + From<BlockProposerRequest>
     + Send
 {
 }
@@ -49,8 +112,10 @@ pub trait ReactorEventT:
 impl<REv> ReactorEventT for REv where
     REv: From<Event>
         + From<DeployAcceptorAnnouncement<NodeId>>
+        + From<BlocklistAnnouncement<NodeId>>
         + From<StorageRequest>
         + From<ContractRuntimeRequest>
+        + From<BlockProposerRequest>
         + Send
 {
 }
@@ -62,17 +127,97 @@ impl<REv> ReactorEventT for REv where
 /// accepted `Deploy`.
 #[derive(Debug)]
 pub struct DeployAcceptor {
-    chain_name: String,
-    deploy_config: DeployConfig,
+    chainspec: Arc<Chainspec>,
+    /// The height of the block for which accepted deploys are destined, used to look up the
+    /// chainspec's deploy parameters: one above the highest block added to the linear chain.
+    next_block_height: u64,
     verify_accounts: bool,
+    min_gas_price: u64,
+    max_approvals: u32,
 }
 
 impl DeployAcceptor {
-    pub(crate) fn new(config: Config, chainspec: &Chainspec) -> Self {
+    pub(crate) fn new(config: Config, chainspec: Arc<Chainspec>, next_block_height: u64) -> Self {
         DeployAcceptor {
-            chain_name: chainspec.network_config.name.clone(),
-            deploy_config: chainspec.deploy_config,
+            chainspec,
+            next_block_height,
             verify_accounts: config.verify_accounts(),
+            min_gas_price: config.min_gas_price(),
+            max_approvals: config.max_approvals(),
+        }
+    }
+
+    /// Checks that the deploy is acceptable to this node.
+    ///
+    /// The approval and pricing limits are local to this node, so they are only applied to deploys
+    /// received from clients: deploys from peers may already be included in a proposed block.  The
+    /// approvals are checked before calling `Deploy::is_acceptable`, so that an excessive number of
+    /// signatures is not verified.
+    fn check_acceptable(&self, deploy: &mut Deploy, from_client: bool) -> Result<(), Error> {
+        if from_client {
+            self.check_approvals(deploy)?;
+        }
+        let deploy_config = self.chainspec.deploy_config_at(self.next_block_height);
+        deploy
+            .is_acceptable(&self.chainspec.network_config.name, deploy_config)
+            .map_err(Error::InvalidDeploy)?;
+        if from_client {
+            self.check_pricing(deploy)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that the deploy has no more than the configured maximum number of approvals, and at
+    /// most one approval from each signer.
+    ///
+    /// The validity of each approval's signature is checked by `Deploy::is_acceptable`.
+    fn check_approvals(&self, deploy: &Deploy) -> Result<(), Error> {
+        let approvals = deploy.approvals();
+        if approvals.len() > self.max_approvals as usize {
+            return Err(Error::ExcessiveApprovals {
+                max_approvals: self.max_approvals,
+                got: approvals.len(),
+            });
+        }
+
+        let mut signers = HashSet::new();
+        for approval in approvals {
+            if !signers.insert(approval.signer()) {
+                return Err(Error::DuplicateApproval {
+                    signer: approval.signer().clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the deploy's gas price is at least the configured minimum, and that its payment
+    /// amount, converted to gas at that price, buys a non-zero gas limit.
+    ///
+    /// Deploys whose payment code does not take a standard "amount" argument are not checked for
+    /// underpayment here.
+    fn check_pricing(&self, deploy: &Deploy) -> Result<(), Error> {
+        let gas_price = deploy.header().gas_price();
+        if gas_price < self.min_gas_price {
+            return Err(Error::InsufficientGasPrice {
+                min_gas_price: self.min_gas_price,
+                got: gas_price,
+            });
+        }
+
+        if deploy.session().is_transfer() {
+            return Ok(());
+        }
+        let payment_amount = match deploy.deploy_info() {
+            Ok(deploy_info) => deploy_info.payment_amount,
+            Err(_) => return Ok(()),
+        };
+        match Gas::from_motes(payment_amount, gas_price) {
+            Some(gas_limit) if !gas_limit.value().is_zero() => Ok(()),
+            _ => Err(Error::InsufficientPayment {
+                payment_amount,
+                gas_price,
+            }),
         }
     }
 
@@ -89,12 +234,21 @@ impl DeployAcceptor {
     ) -> Effects<Event> {
         let mut cloned_deploy = deploy.clone();
         let mut effects = Effects::new();
-        let is_acceptable = cloned_deploy.is_acceptable(&self.chain_name, &self.deploy_config);
-        if let Err(error) = is_acceptable {
+        if let Err(error) = self.check_acceptable(&mut cloned_deploy, source.from_client()) {
+            debug!(%error, deploy_hash = %deploy.id(), "received unacceptable deploy");
+            if let Source::Peer(peer) = &source {
+                if error.is_provably_malicious() {
+                    effects.extend(
+                        effect_builder
+                            .announce_protocol_violation(peer.clone())
+                            .ignore(),
+                    );
+                }
+            }
             // The client has submitted an invalid deploy. Return an error to the RPC component via
             // the responder.
             if let Some(responder) = maybe_responder {
-                effects.extend(responder.respond(Err(Error::InvalidDeploy(error))).ignore());
+                effects.extend(responder.respond(Err(error)).ignore());
             }
             effects.extend(
                 effect_builder
@@ -143,16 +297,19 @@ impl DeployAcceptor {
 
         match verified {
             Some(true) => {
-                effects.extend(effect_builder.put_deploy_to_storage(deploy.clone()).event(
-                    move |is_new| Event::PutToStorageResult {
-                        deploy,
-                        source,
-                        is_new,
-                        maybe_responder,
-                    },
-                ));
-
-                return effects;
+                // Deploys from peers are accepted regardless, as they may already be included in a
+                // proposed block.
+                if source.from_client() {
+                    return effect_builder
+                        .is_deploy_buffer_full()
+                        .event(move |buffer_full| Event::BufferCheckResult {
+                            deploy,
+                            source,
+                            buffer_full,
+                            maybe_responder,
+                        });
+                }
+                return self.put_to_storage(effect_builder, deploy, source, maybe_responder);
             }
 
             Some(false) => {
@@ -186,6 +343,42 @@ impl DeployAcceptor {
         effects
     }
 
+    fn buffer_check<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        deploy: Box<Deploy>,
+        source: Source<NodeId>,
+        buffer_full: bool,
+        maybe_responder: Option<Responder<Result<(), Error>>>,
+    ) -> Effects<Event> {
+        if !buffer_full {
+            return self.put_to_storage(effect_builder, deploy, source, maybe_responder);
+        }
+        info!(deploy_hash = %deploy.id(), "deploy buffer is full, client should retry later");
+        // The deploy is not invalid, so it is not announced as such.
+        match maybe_responder {
+            Some(responder) => responder.respond(Err(Error::DeployBufferFull)).ignore(),
+            None => Effects::new(),
+        }
+    }
+
+    fn put_to_storage<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        deploy: Box<Deploy>,
+        source: Source<NodeId>,
+        maybe_responder: Option<Responder<Result<(), Error>>>,
+    ) -> Effects<Event> {
+        effect_builder
+            .put_deploy_to_storage(deploy.clone())
+            .event(move |is_new| Event::PutToStorageResult {
+                deploy,
+                source,
+                is_new,
+                maybe_responder,
+            })
+    }
+
     fn handle_put_to_storage<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -214,6 +407,10 @@ impl<REv: ReactorEventT> Component<REv> for DeployAcceptor {
     type Event = Event;
     type ConstructionError = Infallible;
 
+    fn name(&self) -> &str {
+        "deploy_acceptor"
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -249,6 +446,16 @@ impl<REv: ReactorEventT> Component<REv> for DeployAcceptor {
                 verified,
                 maybe_responder,
             ),
+            Event::BufferCheckResult {
+                deploy,
+                source,
+                buffer_full,
+                maybe_responder,
+            } => self.buffer_check(effect_builder, deploy, source, buffer_full, maybe_responder),
+            Event::BlockAdded { height } => {
+                self.next_block_height = self.next_block_height.max(height + 1);
+                Effects::new()
+            }
         }
     }
 }
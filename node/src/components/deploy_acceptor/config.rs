@@ -1,28 +1,63 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+/// Default minimum gas price of deploys received from clients.
+const DEFAULT_MIN_GAS_PRICE: u64 = 1;
+/// Default maximum number of approvals of deploys received from clients.
+const DEFAULT_MAX_APPROVALS: u32 = 100;
+
 /// Configuration options for fetching.
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
 pub struct Config {
     verify_accounts: bool,
+    /// The minimum gas price of deploys received from clients.
+    #[serde(default = "default_min_gas_price")]
+    min_gas_price: u64,
+    /// The maximum number of approvals of deploys received from clients.
+    #[serde(default = "default_max_approvals")]
+    max_approvals: u32,
 }
 
 impl Config {
     /// Constructor for deploy_acceptor config.
     pub fn new(verify_accounts: bool) -> Self {
-        Config { verify_accounts }
+        Config {
+            verify_accounts,
+            min_gas_price: DEFAULT_MIN_GAS_PRICE,
+            max_approvals: DEFAULT_MAX_APPROVALS,
+        }
     }
 
     /// Get verify_accounts setting.
     pub(crate) fn verify_accounts(&self) -> bool {
         self.verify_accounts
     }
+
+    /// Get min_gas_price setting.
+    pub(crate) fn min_gas_price(&self) -> u64 {
+        self.min_gas_price
+    }
+
+    /// Get max_approvals setting.
+    pub(crate) fn max_approvals(&self) -> u32 {
+        self.max_approvals
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             verify_accounts: true,
+            min_gas_price: DEFAULT_MIN_GAS_PRICE,
+            max_approvals: DEFAULT_MAX_APPROVALS,
         }
     }
 }
+
+fn default_min_gas_price() -> u64 {
+    DEFAULT_MIN_GAS_PRICE
+}
+
+fn default_max_approvals() -> u32 {
+    DEFAULT_MAX_APPROVALS
+}
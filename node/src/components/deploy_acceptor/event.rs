@@ -34,6 +34,16 @@ pub enum Event {
         verified: Option<bool>,
         maybe_responder: Option<Responder<Result<(), Error>>>,
     },
+    /// The result of checking whether the block proposer's buffer of pending deploys is full,
+    /// before storing a `Deploy` received from a client.
+    BufferCheckResult {
+        deploy: Box<Deploy>,
+        source: Source<NodeId>,
+        buffer_full: bool,
+        maybe_responder: Option<Responder<Result<(), Error>>>,
+    },
+    /// A block has been added to the linear chain.
+    BlockAdded { height: u64 },
 }
 
 impl From<RpcServerAnnouncement> for Event {
@@ -76,6 +86,20 @@ impl Display for Event {
                     account_key
                 )
             }
+            Event::BufferCheckResult {
+                deploy,
+                buffer_full,
+                ..
+            } => {
+                let prefix = if *buffer_full { "" } else { "not " };
+                write!(
+                    formatter,
+                    "buffer {}full for deploy {}",
+                    prefix,
+                    deploy.id()
+                )
+            }
+            Event::BlockAdded { height } => write!(formatter, "block {} added", height),
         }
     }
 }
@@ -0,0 +1,284 @@
+use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+use casper_types::{
+    bytesrepr::{Bytes, ToBytes},
+    runtime_args, RuntimeArgs, SecretKey, U512,
+};
+
+use super::*;
+use crate::{
+    testing::TestRng,
+    types::{
+        chainspec::{DeployConfig, HeightActivationPoint},
+        DeployHash, TimeDiff, Timestamp,
+    },
+};
+
+const CHAIN_NAME: &str = "casper-example";
+const MIN_GAS_PRICE: u64 = 10;
+
+/// Creates a random chainspec for the network `CHAIN_NAME`, with the default deploy config and no
+/// height activation points.
+fn new_chainspec(rng: &mut TestRng) -> Chainspec {
+    let mut chainspec = Chainspec::random(rng);
+    chainspec.network_config.name = CHAIN_NAME.to_string();
+    chainspec.deploy_config = DeployConfig::default();
+    chainspec.height_activation_points.clear();
+    chainspec
+}
+
+fn new_deploy_acceptor(rng: &mut TestRng) -> DeployAcceptor {
+    let mut config = Config::new(false);
+    config.min_gas_price = MIN_GAS_PRICE;
+    DeployAcceptor::new(config, Arc::new(new_chainspec(rng)), 0)
+}
+
+/// Creates a deploy using standard payment of `payment_amount` motes.
+fn new_deploy(rng: &mut TestRng, gas_price: u64, payment_amount: u64) -> Deploy {
+    let secret_key = SecretKey::random(rng);
+    Deploy::new(
+        Timestamp::now(),
+        TimeDiff::from(60_000),
+        gas_price,
+        vec![],
+        CHAIN_NAME.to_string(),
+        ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: runtime_args! { "amount" => U512::from(payment_amount) },
+        },
+        ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: RuntimeArgs::new(),
+        },
+        &secret_key,
+    )
+}
+
+#[test]
+fn should_accept_deploy_with_sufficient_gas_price_and_payment() {
+    let mut rng = crate::new_rng();
+    let deploy_acceptor = new_deploy_acceptor(&mut rng);
+
+    let deploy = new_deploy(&mut rng, MIN_GAS_PRICE, 1_000 * MIN_GAS_PRICE);
+    assert!(deploy_acceptor.check_pricing(&deploy).is_ok());
+
+    // A payment buying exactly one unit of gas is the least acceptable.
+    let deploy = new_deploy(&mut rng, MIN_GAS_PRICE, MIN_GAS_PRICE);
+    assert!(deploy_acceptor.check_pricing(&deploy).is_ok());
+}
+
+#[test]
+fn should_reject_deploy_with_gas_price_below_minimum() {
+    let mut rng = crate::new_rng();
+    let deploy_acceptor = new_deploy_acceptor(&mut rng);
+
+    let deploy = new_deploy(&mut rng, MIN_GAS_PRICE - 1, 1_000 * MIN_GAS_PRICE);
+    match deploy_acceptor.check_pricing(&deploy) {
+        Err(Error::InsufficientGasPrice { min_gas_price, got }) => {
+            assert_eq!(min_gas_price, MIN_GAS_PRICE);
+            assert_eq!(got, MIN_GAS_PRICE - 1);
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn should_reject_deploy_with_payment_not_covering_any_gas() {
+    let mut rng = crate::new_rng();
+    let deploy_acceptor = new_deploy_acceptor(&mut rng);
+
+    let deploy = new_deploy(&mut rng, MIN_GAS_PRICE, MIN_GAS_PRICE - 1);
+    match deploy_acceptor.check_pricing(&deploy) {
+        Err(Error::InsufficientPayment {
+            payment_amount,
+            gas_price,
+        }) => {
+            assert_eq!(payment_amount, Motes::new(U512::from(MIN_GAS_PRICE - 1)));
+            assert_eq!(gas_price, MIN_GAS_PRICE);
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn should_accept_deploy_at_size_limit() {
+    let mut rng = crate::new_rng();
+    let mut deploy = new_deploy(&mut rng, MIN_GAS_PRICE, 1_000 * MIN_GAS_PRICE);
+
+    let mut chainspec = new_chainspec(&mut rng);
+    chainspec.deploy_config.max_deploy_size = deploy.serialized_length() as u32;
+    let deploy_acceptor = DeployAcceptor::new(Config::new(false), Arc::new(chainspec), 0);
+
+    assert!(deploy_acceptor.check_acceptable(&mut deploy, false).is_ok());
+}
+
+#[test]
+fn should_reject_deploy_over_size_limit() {
+    let mut rng = crate::new_rng();
+    let mut deploy = new_deploy(&mut rng, MIN_GAS_PRICE, 1_000 * MIN_GAS_PRICE);
+    let deploy_size = deploy.serialized_length();
+
+    let mut chainspec = new_chainspec(&mut rng);
+    chainspec.deploy_config.max_deploy_size = deploy_size as u32 - 1;
+    let deploy_acceptor = DeployAcceptor::new(Config::new(false), Arc::new(chainspec), 0);
+
+    match deploy_acceptor.check_acceptable(&mut deploy, false) {
+        Err(Error::InvalidDeploy(DeployValidationFailure::ExcessiveSize(error))) => {
+            assert_eq!(error.max_deploy_size, deploy_size as u32 - 1);
+            assert_eq!(error.actual_deploy_size, deploy_size);
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn should_reject_deploy_with_duplicate_approvals() {
+    let mut rng = crate::new_rng();
+    let deploy_acceptor = new_deploy_acceptor(&mut rng);
+    let mut deploy = new_deploy(&mut rng, MIN_GAS_PRICE, 1_000 * MIN_GAS_PRICE);
+    let secret_key = SecretKey::random(&mut rng);
+    deploy.sign(&secret_key);
+    deploy.sign(&secret_key);
+
+    match deploy_acceptor.check_acceptable(&mut deploy, true) {
+        Err(Error::DuplicateApproval { signer }) => {
+            assert_eq!(signer, PublicKey::from(&secret_key));
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn should_reject_deploy_with_invalid_approval() {
+    let mut rng = crate::new_rng();
+    let deploy_acceptor = new_deploy_acceptor(&mut rng);
+    let deploy = new_deploy(&mut rng, MIN_GAS_PRICE, 1_000 * MIN_GAS_PRICE);
+
+    // Replace the deploy's approval with one signed over a different deploy's hash.
+    let other_deploy = new_deploy(&mut rng, MIN_GAS_PRICE, 1_000 * MIN_GAS_PRICE);
+    let mut json_deploy = serde_json::to_value(&deploy).unwrap();
+    json_deploy["approvals"] = serde_json::to_value(other_deploy.approvals()).unwrap();
+    let mut deploy: Deploy = serde_json::from_value(json_deploy).unwrap();
+
+    match deploy_acceptor.check_acceptable(&mut deploy, true) {
+        Err(Error::InvalidDeploy(DeployValidationFailure::InvalidApproval { index, .. })) => {
+            assert_eq!(index, 0);
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn should_reject_deploy_with_excessive_approvals() {
+    let mut rng = crate::new_rng();
+    let deploy_acceptor = new_deploy_acceptor(&mut rng);
+    let mut deploy = new_deploy(&mut rng, MIN_GAS_PRICE, 1_000 * MIN_GAS_PRICE);
+
+    // `new_deploy` already added one approval.
+    for _ in 1..deploy_acceptor.max_approvals {
+        deploy.sign(&SecretKey::random(&mut rng));
+    }
+    assert!(deploy_acceptor.check_acceptable(&mut deploy, true).is_ok());
+
+    deploy.sign(&SecretKey::random(&mut rng));
+    match deploy_acceptor.check_acceptable(&mut deploy, true) {
+        Err(Error::ExcessiveApprovals { max_approvals, got }) => {
+            assert_eq!(max_approvals, deploy_acceptor.max_approvals);
+            assert_eq!(got, max_approvals as usize + 1);
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    // Deploys received from peers are not subject to the limit.
+    assert!(deploy_acceptor.check_acceptable(&mut deploy, false).is_ok());
+}
+
+#[test]
+fn should_apply_deploy_config_of_height_activation_points() {
+    const ACTIVATION_HEIGHT: u64 = 10;
+
+    let mut rng = crate::new_rng();
+    let mut deploy = new_deploy(&mut rng, MIN_GAS_PRICE, 1_000 * MIN_GAS_PRICE);
+    let deploy_size = deploy.serialized_length();
+
+    // The deploy is within the size limit until the activation point lowers it.
+    let mut chainspec = new_chainspec(&mut rng);
+    chainspec.deploy_config.max_deploy_size = deploy_size as u32;
+    let mut upgraded_deploy_config = chainspec.deploy_config;
+    upgraded_deploy_config.max_deploy_size = deploy_size as u32 - 1;
+    chainspec.height_activation_points = vec![HeightActivationPoint {
+        height: ACTIVATION_HEIGHT,
+        deploys: upgraded_deploy_config,
+    }];
+    let chainspec = Arc::new(chainspec);
+
+    let deploy_acceptor = DeployAcceptor::new(
+        Config::new(false),
+        Arc::clone(&chainspec),
+        ACTIVATION_HEIGHT - 1,
+    );
+    assert!(deploy_acceptor.check_acceptable(&mut deploy, false).is_ok());
+
+    let deploy_acceptor = DeployAcceptor::new(Config::new(false), chainspec, ACTIVATION_HEIGHT);
+    match deploy_acceptor.check_acceptable(&mut deploy, false) {
+        Err(Error::InvalidDeploy(DeployValidationFailure::ExcessiveSize(error))) => {
+            assert_eq!(error.max_deploy_size, deploy_size as u32 - 1);
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn should_reject_tampered_deploy() {
+    let mut rng = crate::new_rng();
+    let deploy_acceptor = new_deploy_acceptor(&mut rng);
+
+    // Replace the hash of an otherwise valid deploy.
+    let deploy = new_deploy(&mut rng, MIN_GAS_PRICE, 1_000 * MIN_GAS_PRICE);
+    let mut json = serde_json::to_value(&deploy).unwrap();
+    json["hash"] = serde_json::to_value(DeployHash::random(&mut rng)).unwrap();
+    let mut tampered_deploy: Deploy = serde_json::from_value(json).unwrap();
+
+    match deploy_acceptor.check_acceptable(&mut tampered_deploy, false) {
+        Err(Error::InvalidDeploy(DeployValidationFailure::InvalidDeployHash)) => (),
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn should_only_treat_tampered_deploys_as_malicious() {
+    let mut rng = crate::new_rng();
+    let deploy_acceptor = new_deploy_acceptor(&mut rng);
+
+    // A forged approval proves the sender did not verify the deploy.
+    let deploy = new_deploy(&mut rng, MIN_GAS_PRICE, 1_000 * MIN_GAS_PRICE);
+    let other_deploy = new_deploy(&mut rng, MIN_GAS_PRICE, 1_000 * MIN_GAS_PRICE);
+    let mut json_deploy = serde_json::to_value(&deploy).unwrap();
+    json_deploy["approvals"] = serde_json::to_value(other_deploy.approvals()).unwrap();
+    let mut forged_deploy: Deploy = serde_json::from_value(json_deploy).unwrap();
+    let error = deploy_acceptor
+        .check_acceptable(&mut forged_deploy, false)
+        .unwrap_err();
+    assert!(error.is_provably_malicious());
+
+    // A deploy for another network is merely unacceptable to this node's chainspec.
+    let mut other_chain_deploy = Deploy::new(
+        Timestamp::now(),
+        TimeDiff::from(60_000),
+        MIN_GAS_PRICE,
+        vec![],
+        "other-chain".to_string(),
+        ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: RuntimeArgs::new(),
+        },
+        ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: RuntimeArgs::new(),
+        },
+        &SecretKey::random(&mut rng),
+    );
+    let error = deploy_acceptor
+        .check_acceptable(&mut other_chain_deploy, false)
+        .unwrap_err();
+    assert!(!error.is_provably_malicious());
+}
@@ -17,6 +17,10 @@
 //! unintended subscriber disconnects, if a disconnected subscriber re-subscribes before the buffer
 //! has advanced past their last received event.
 //!
+//! Blocks are only stored, and hence announced, once they have been finalized by consensus. The
+//! linear chain is therefore never reorganized: a block sent in a `BlockAdded` event is never
+//! replaced by another one at the same height, so subscribers can cache it indefinitely.
+//!
 //! For details about the SSE model and a list of supported SSEs, see:
 //! <https://github.com/CasperLabs/ceps/blob/master/text/0009-client-api.md#rpcs>
 
@@ -168,6 +172,10 @@ where
     type Event = Event;
     type ConstructionError = Infallible;
 
+    fn name(&self) -> &str {
+        "event_stream_server"
+    }
+
     fn handle_event(
         &mut self,
         _effect_builder: EffectBuilder<REv>,
@@ -204,6 +212,13 @@ where
                 timestamp,
             }),
             Event::FinalitySignature(fs) => self.broadcast(SseData::FinalitySignature(fs)),
+            Event::EraEnded {
+                era_id,
+                switch_block_height,
+            } => self.broadcast(SseData::EraEnded {
+                era_id,
+                switch_block_height,
+            }),
             Event::Step { era_id, effect } => self.broadcast(SseData::Step {
                 era_id,
                 execution_effect: effect,
@@ -20,6 +20,10 @@ pub enum Event {
         timestamp: Timestamp,
     },
     FinalitySignature(Box<FinalitySignature>),
+    EraEnded {
+        era_id: EraId,
+        switch_block_height: u64,
+    },
     Step {
         era_id: EraId,
         effect: ExecutionEffect,
@@ -46,6 +50,14 @@ impl Display for Event {
                 public_key, timestamp, era_id,
             ),
             Event::FinalitySignature(fs) => write!(formatter, "finality signature {}", fs),
+            Event::EraEnded {
+                era_id,
+                switch_block_height,
+            } => write!(
+                formatter,
+                "era {} ended with switch block at height {}",
+                era_id, switch_block_height
+            ),
             Event::Step { era_id, .. } => write!(formatter, "step committed for {}", era_id),
         }
     }
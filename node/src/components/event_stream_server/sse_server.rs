@@ -52,20 +52,25 @@ pub const SSE_API_MAIN_PATH: &str = "main";
 pub const SSE_API_DEPLOYS_PATH: &str = "deploys";
 /// The URL path part to subscribe to only `FinalitySignature` events.
 pub const SSE_API_SIGNATURES_PATH: &str = "sigs";
+/// The URL path part to subscribe to only summaries of `BlockAdded` events.
+pub const SSE_API_BLOCKS_PATH: &str = "blocks";
 /// The URL query string field name.
 pub const QUERY_FIELD: &str = "start_from";
 
 /// The filter associated with `/events/main` path.
-const MAIN_FILTER: [EventFilter; 4] = [
+const MAIN_FILTER: [EventFilter; 5] = [
     EventFilter::BlockAdded,
     EventFilter::DeployProcessed,
     EventFilter::Fault,
+    EventFilter::EraEnded,
     EventFilter::Step,
 ];
 /// The filter associated with `/events/deploys` path.
 const DEPLOYS_FILTER: [EventFilter; 1] = [EventFilter::DeployAccepted];
 /// The filter associated with `/events/sigs` path.
 const SIGNATURES_FILTER: [EventFilter; 1] = [EventFilter::FinalitySignature];
+/// The filter associated with `/events/blocks` path.
+const BLOCKS_FILTER: [EventFilter; 1] = [EventFilter::BlockSummary];
 
 /// The max time to wait for getting a deploy before trying a second and final time.
 const GET_DEPLOY_TIMEOUT: Duration = Duration::from_secs(1);
@@ -109,6 +114,12 @@ pub enum SseData {
     },
     /// New finality signature received.
     FinalitySignature(Box<FinalitySignature>),
+    /// The given era ended, i.e. its switch block was finalized.  The switch block's `BlockAdded`
+    /// event follows once it has been executed.
+    EraEnded {
+        era_id: EraId,
+        switch_block_height: u64,
+    },
     Step {
         era_id: EraId,
         #[data_size(skip)]
@@ -120,11 +131,15 @@ impl SseData {
     pub(super) fn should_include(&self, filter: &[EventFilter]) -> bool {
         match self {
             SseData::ApiVersion(_) => true,
-            SseData::BlockAdded { .. } => filter.contains(&EventFilter::BlockAdded),
+            SseData::BlockAdded { .. } => {
+                filter.contains(&EventFilter::BlockAdded)
+                    || filter.contains(&EventFilter::BlockSummary)
+            }
             SseData::DeployAccepted { .. } => filter.contains(&EventFilter::DeployAccepted),
             SseData::DeployProcessed { .. } => filter.contains(&EventFilter::DeployProcessed),
             SseData::Fault { .. } => filter.contains(&EventFilter::Fault),
             SseData::FinalitySignature(_) => filter.contains(&EventFilter::FinalitySignature),
+            SseData::EraEnded { .. } => filter.contains(&EventFilter::EraEnded),
             SseData::Step { .. } => filter.contains(&EventFilter::Step),
         }
     }
@@ -191,6 +206,14 @@ impl SseData {
         )))
     }
 
+    /// Returns a random `SseData::EraEnded`.
+    pub(super) fn random_era_ended(rng: &mut TestRng) -> Self {
+        SseData::EraEnded {
+            era_id: EraId::new(rng.gen()),
+            switch_block_height: rng.gen(),
+        }
+    }
+
     /// Returns a random `SseData::Step`.
     pub(super) fn random_step(rng: &mut TestRng) -> Self {
         let execution_effect = match rng.gen::<ExecutionResult>() {
@@ -211,6 +234,32 @@ pub(super) struct DeployAccepted {
     pub(super) deploy_accepted: Deploy,
 }
 
+/// The hash, height and timestamp of a block which has been added to the linear chain.
+#[derive(Serialize)]
+pub(super) struct BlockSummary {
+    pub(super) block_hash: BlockHash,
+    pub(super) height: u64,
+    pub(super) timestamp: Timestamp,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct BlockAddedSummary {
+    pub(super) block_added_summary: BlockSummary,
+}
+
+impl BlockAddedSummary {
+    pub(super) fn new(block_hash: BlockHash, block: &JsonBlock) -> Self {
+        BlockAddedSummary {
+            block_added_summary: BlockSummary {
+                block_hash,
+                height: block.height(),
+                timestamp: block.timestamp(),
+            },
+        }
+    }
+}
+
 /// The components of a single SSE.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub(super) struct ServerSentEvent {
@@ -255,10 +304,12 @@ pub(super) struct NewSubscriberInfo {
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
 pub(super) enum EventFilter {
     BlockAdded,
+    BlockSummary,
     DeployAccepted,
     DeployProcessed,
     Fault,
     FinalitySignature,
+    EraEnded,
     Step,
 }
 
@@ -297,9 +348,23 @@ async fn filter_map_server_sent_event(
                 WarpServerSentEvent::default()
             }))),
 
+        &SseData::BlockAdded {
+            block_hash,
+            ref block,
+        } if event_filter.contains(&EventFilter::BlockSummary) => {
+            Some(Ok(WarpServerSentEvent::default()
+                .json_data(&BlockAddedSummary::new(block_hash, block))
+                .unwrap_or_else(|error| {
+                    warn!(%error, "failed to jsonify sse event");
+                    WarpServerSentEvent::default()
+                })
+                .id(id)))
+        }
+
         &SseData::BlockAdded { .. }
         | &SseData::DeployProcessed { .. }
         | &SseData::Fault { .. }
+        | &SseData::EraEnded { .. }
         | &SseData::Step { .. }
         | &SseData::FinalitySignature(_) => Some(Ok(WarpServerSentEvent::default()
             .json_data(&event.data)
@@ -342,6 +407,7 @@ pub(super) fn get_filter(path_param: &str) -> Option<&'static [EventFilter]> {
         SSE_API_MAIN_PATH => Some(&MAIN_FILTER[..]),
         SSE_API_DEPLOYS_PATH => Some(&DEPLOYS_FILTER[..]),
         SSE_API_SIGNATURES_PATH => Some(&SIGNATURES_FILTER[..]),
+        SSE_API_BLOCKS_PATH => Some(&BLOCKS_FILTER[..]),
         _ => None,
     }
 }
@@ -371,11 +437,13 @@ fn parse_query(query: HashMap<String, String>) -> Result<Option<Id>, Response> {
 /// Creates a 404 response with a useful error message in the body.
 fn create_404() -> Response {
     let mut response = Response::new(Body::from(format!(
-        "invalid path: expected '/{root}/{main}', '/{root}/{deploys}' or '/{root}/{sigs}'\n",
+        "invalid path: expected '/{root}/{main}', '/{root}/{deploys}', '/{root}/{sigs}' or \
+        '/{root}/{blocks}'\n",
         root = SSE_API_ROOT_PATH,
         main = SSE_API_MAIN_PATH,
         deploys = SSE_API_DEPLOYS_PATH,
-        sigs = SSE_API_SIGNATURES_PATH
+        sigs = SSE_API_SIGNATURES_PATH,
+        blocks = SSE_API_BLOCKS_PATH
     )));
     *response.status_mut() = StatusCode::NOT_FOUND;
     response
@@ -637,6 +705,10 @@ mod tests {
             id: Some(rng.gen()),
             data: SseData::random_finality_signature(&mut rng),
         };
+        let era_ended = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: SseData::random_era_ended(&mut rng),
+        };
         let step = ServerSentEvent {
             id: Some(rng.gen()),
             data: SseData::random_step(&mut rng),
@@ -647,6 +719,7 @@ mod tests {
         should_not_filter_out(&block_added, &MAIN_FILTER[..], getter.clone()).await;
         should_not_filter_out(&deploy_processed, &MAIN_FILTER[..], getter.clone()).await;
         should_not_filter_out(&fault, &MAIN_FILTER[..], getter.clone()).await;
+        should_not_filter_out(&era_ended, &MAIN_FILTER[..], getter.clone()).await;
         should_not_filter_out(&step, &MAIN_FILTER[..], getter.clone()).await;
 
         should_filter_out(&deploy_accepted, &MAIN_FILTER[..], getter.clone()).await;
@@ -661,6 +734,7 @@ mod tests {
         should_filter_out(&deploy_processed, &DEPLOYS_FILTER[..], getter.clone()).await;
         should_filter_out(&fault, &DEPLOYS_FILTER[..], getter.clone()).await;
         should_filter_out(&finality_signature, &DEPLOYS_FILTER[..], getter.clone()).await;
+        should_filter_out(&era_ended, &DEPLOYS_FILTER[..], getter.clone()).await;
         should_filter_out(&step, &DEPLOYS_FILTER[..], getter.clone()).await;
 
         // `EventFilter::Signatures` should filter out everything except `ApiVersion`s and
@@ -672,6 +746,7 @@ mod tests {
         should_filter_out(&deploy_accepted, &SIGNATURES_FILTER[..], getter.clone()).await;
         should_filter_out(&deploy_processed, &SIGNATURES_FILTER[..], getter.clone()).await;
         should_filter_out(&fault, &SIGNATURES_FILTER[..], getter.clone()).await;
+        should_filter_out(&era_ended, &SIGNATURES_FILTER[..], getter.clone()).await;
         should_filter_out(&step, &SIGNATURES_FILTER[..], getter).await;
     }
 
@@ -710,6 +785,10 @@ mod tests {
             id: None,
             data: SseData::random_finality_signature(&mut rng),
         };
+        let malformed_era_ended = ServerSentEvent {
+            id: None,
+            data: SseData::random_era_ended(&mut rng),
+        };
         let malformed_step = ServerSentEvent {
             id: None,
             data: SseData::random_step(&mut rng),
@@ -726,6 +805,7 @@ mod tests {
             should_filter_out(&malformed_deploy_processed, filter, getter.clone()).await;
             should_filter_out(&malformed_fault, filter, getter.clone()).await;
             should_filter_out(&malformed_finality_signature, filter, getter.clone()).await;
+            should_filter_out(&malformed_era_ended, filter, getter.clone()).await;
             should_filter_out(&malformed_step, filter, getter.clone()).await;
         }
     }
@@ -25,7 +25,8 @@ use tracing::debug;
 use super::*;
 use crate::{logging, testing::TestRng};
 use sse_server::{
-    DeployAccepted, Id, QUERY_FIELD, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH,
+    BlockAddedSummary, DeployAccepted, EventFilter, Id, QUERY_FIELD,
+    SSE_API_BLOCKS_PATH as BLOCKS_PATH, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH,
     SSE_API_MAIN_PATH as MAIN_PATH, SSE_API_ROOT_PATH as ROOT_PATH,
     SSE_API_SIGNATURES_PATH as SIGS_PATH,
 };
@@ -205,7 +206,7 @@ impl TestFixture {
 
         let mut deploys = HashMap::new();
         let events = (0..EVENT_COUNT)
-            .map(|i| match i % 6 {
+            .map(|i| match i % 7 {
                 0 => SseData::random_block_added(rng),
                 1 => {
                     let (event, deploy) = SseData::random_deploy_accepted(rng);
@@ -216,6 +217,7 @@ impl TestFixture {
                 3 => SseData::random_fault(rng),
                 4 => SseData::random_step(rng),
                 5 => SseData::random_finality_signature(rng),
+                6 => SseData::random_era_ended(rng),
                 _ => unreachable!(),
             })
             .collect();
@@ -342,6 +344,7 @@ impl TestFixture {
             from as u128
         };
 
+        let filter = sse_server::get_filter(final_path_element).unwrap();
         let id_filter = |id: u128, event: &SseData| -> Option<ReceivedEvent> {
             if id < from {
                 return None;
@@ -354,6 +357,11 @@ impl TestFixture {
                     let deploy_accepted = self.deploy_getter.get_test_deploy(*deploy_hash).unwrap();
                     serde_json::to_string(&DeployAccepted { deploy_accepted }).unwrap()
                 }
+                SseData::BlockAdded { block_hash, block }
+                    if filter.contains(&EventFilter::BlockSummary) =>
+                {
+                    serde_json::to_string(&BlockAddedSummary::new(*block_hash, block)).unwrap()
+                }
                 _ => serde_json::to_string(event).unwrap(),
             };
 
@@ -368,7 +376,6 @@ impl TestFixture {
             data: serde_json::to_string(&SseData::ApiVersion(self.protocol_version)).unwrap(),
         };
 
-        let filter = sse_server::get_filter(final_path_element).unwrap();
         let events: Vec<_> = iter::once(api_version_event)
             .chain(self.events.iter().enumerate().filter_map(|(id, event)| {
                 let id = id as u128 + self.first_event_id as u128;
@@ -578,7 +585,8 @@ fn parse_response(response_text: String, client_id: &str) -> Vec<ReceivedEvent>
 ///   * no `?start_from=` query
 ///   * connected before first event
 ///
-/// Expected to receive all main, deploy-accepted or signature events depending on `filter`.
+/// Expected to receive all main, deploy-accepted, signature or block summary events depending on
+/// `filter`.
 async fn should_serve_events_with_no_query(path: &str) {
     let mut rng = crate::new_rng();
     let mut fixture = TestFixture::new(&mut rng);
@@ -610,12 +618,17 @@ async fn should_serve_signature_events_with_no_query() {
     should_serve_events_with_no_query(SIGS_PATH).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_serve_block_summary_events_with_no_query() {
+    should_serve_events_with_no_query(BLOCKS_PATH).await;
+}
+
 /// Client setup:
 ///   * `<IP:port>/events/<path>?start_from=25`
 ///   * connected just before event ID 50
 ///
-/// Expected to receive main, deploy-accepted or signature events (depending on `path`) from ID 25
-/// onwards, as events 25 to 49 should still be in the server buffer.
+/// Expected to receive main, deploy-accepted, signature or block summary events (depending on
+/// `path`) from ID 25 onwards, as events 25 to 49 should still be in the server buffer.
 async fn should_serve_events_with_query(path: &str) {
     let mut rng = crate::new_rng();
     let mut fixture = TestFixture::new(&mut rng);
@@ -650,12 +663,17 @@ async fn should_serve_signature_events_with_query() {
     should_serve_events_with_query(SIGS_PATH).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_serve_block_summary_events_with_query() {
+    should_serve_events_with_query(BLOCKS_PATH).await;
+}
+
 /// Client setup:
 ///   * `<IP:port>/events/<path>?start_from=0`
 ///   * connected just before event ID 75
 ///
-/// Expected to receive main, deploy-accepted or signature events (depending on `path`) from ID 25
-/// onwards, as events 0 to 24 should have been purged from the server buffer.
+/// Expected to receive main, deploy-accepted, signature or block summary events (depending on
+/// `path`) from ID 25 onwards, as events 0 to 24 should have been purged from the server buffer.
 async fn should_serve_remaining_events_with_query(path: &str) {
     let mut rng = crate::new_rng();
     let mut fixture = TestFixture::new(&mut rng);
@@ -691,12 +709,17 @@ async fn should_serve_remaining_signature_events_with_query() {
     should_serve_remaining_events_with_query(SIGS_PATH).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_serve_remaining_block_summary_events_with_query() {
+    should_serve_remaining_events_with_query(BLOCKS_PATH).await;
+}
+
 /// Client setup:
 ///   * `<IP:port>/events/<path>?start_from=25`
 ///   * connected before first event
 ///
-/// Expected to receive all main, deploy-accepted or signature events (depending on `path`), as
-/// event 25 hasn't been added to the server buffer yet.
+/// Expected to receive all main, deploy-accepted, signature or block summary events (depending on
+/// `path`), as event 25 hasn't been added to the server buffer yet.
 async fn should_serve_events_with_query_for_future_event(path: &str) {
     let mut rng = crate::new_rng();
     let mut fixture = TestFixture::new(&mut rng);
@@ -728,6 +751,11 @@ async fn should_serve_signature_events_with_query_for_future_event() {
     should_serve_events_with_query_for_future_event(SIGS_PATH).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_serve_block_summary_events_with_query_for_future_event() {
+    should_serve_events_with_query_for_future_event(BLOCKS_PATH).await;
+}
+
 /// Checks that when a server is shut down (e.g. for a node upgrade), connected clients don't have
 /// an error while handling the HTTP response.
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -884,11 +912,15 @@ async fn should_handle_bad_url_path() {
         format!("http://{}/{}/{}/bad?{}=0", server_address, QUERY_FIELD, ROOT_PATH, DEPLOYS_PATH),
         format!("http://{}/{}/{}/bad", server_address, ROOT_PATH, SIGS_PATH),
         format!("http://{}/{}/{}/bad?{}=0", server_address, QUERY_FIELD, ROOT_PATH, SIGS_PATH),
+        format!("http://{}/{}/{}bad", server_address, ROOT_PATH, BLOCKS_PATH),
+        format!("http://{}/{}/{}bad?{}=0", server_address, QUERY_FIELD, ROOT_PATH, BLOCKS_PATH),
+        format!("http://{}/{}/{}/bad", server_address, ROOT_PATH, BLOCKS_PATH),
+        format!("http://{}/{}/{}/bad?{}=0", server_address, QUERY_FIELD, ROOT_PATH, BLOCKS_PATH),
     ];
 
     let expected_body = format!(
-        "invalid path: expected '/{0}/{1}', '/{0}/{2}' or '/{0}/{3}'",
-        ROOT_PATH, MAIN_PATH, DEPLOYS_PATH, SIGS_PATH
+        "invalid path: expected '/{0}/{1}', '/{0}/{2}', '/{0}/{3}' or '/{0}/{4}'",
+        ROOT_PATH, MAIN_PATH, DEPLOYS_PATH, SIGS_PATH, BLOCKS_PATH
     );
     for url in &urls {
         let response = reqwest::get(url).await.unwrap();
@@ -1021,6 +1053,11 @@ async fn should_persist_signature_event_ids() {
     should_persist_event_ids(SIGS_PATH).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_persist_block_summary_event_ids() {
+    should_persist_event_ids(BLOCKS_PATH).await;
+}
+
 /// Check that a server handles wrapping round past the maximum value for event IDs.
 async fn should_handle_wrapping_past_max_event_id(path: &str) {
     let mut rng = crate::new_rng();
@@ -1079,6 +1116,11 @@ async fn should_handle_wrapping_past_max_event_id_for_signatures() {
     should_handle_wrapping_past_max_event_id(SIGS_PATH).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_handle_wrapping_past_max_event_id_for_block_summaries() {
+    should_handle_wrapping_past_max_event_id(BLOCKS_PATH).await;
+}
+
 /// Checks that a server rejects new clients with an HTTP 503 when it already has the specified
 /// limit of connected clients.
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -3,12 +3,16 @@ mod event;
 mod metrics;
 mod tests;
 
-use std::{collections::HashMap, fmt::Debug, time::Duration};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Debug,
+    time::Duration,
+};
 
 use datasize::DataSize;
 use prometheus::Registry;
 use smallvec::smallvec;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use casper_execution_engine::{
     shared::{newtypes::Blake2bHash, stored_value::StoredValue},
@@ -19,11 +23,14 @@ use casper_types::Key;
 use crate::{
     components::{fetcher::event::FetchResponder, Component},
     effect::{
-        requests::{ContractRuntimeRequest, LinearChainRequest, NetworkRequest, StorageRequest},
-        EffectBuilder, EffectExt, Effects,
+        requests::{
+            ContractRuntimeRequest, LinearChainRequest, NetworkInfoRequest, NetworkRequest,
+            StorageRequest,
+        },
+        EffectBuilder, EffectExt, Effects, TimedOut,
     },
     protocol::Message,
-    types::{Block, BlockByHeight, BlockHash, Deploy, DeployHash, Item, NodeId},
+    types::{Block, BlockByHeight, BlockHash, BlockHeader, Deploy, DeployHash, Item, NodeId},
     utils::Source,
     NodeRng,
 };
@@ -32,10 +39,14 @@ pub use config::Config;
 pub use event::{Event, FetchResult};
 use metrics::FetcherMetrics;
 
+/// The maximum number of peers remembered as having recently failed to deliver an item.
+const MAX_UNRESPONSIVE_PEERS: usize = 1000;
+
 /// A helper trait constraining `Fetcher` compatible reactor events.
 pub trait ReactorEventT<T>:
     From<Event<T>>
     + From<NetworkRequest<NodeId, Message>>
+    + From<NetworkInfoRequest<NodeId>>
     + From<StorageRequest>
     + From<ContractRuntimeRequest>
     // Won't be needed when we implement "get block by height" feature in storage.
@@ -54,6 +65,7 @@ where
     <T as Item>::Id: 'static,
     REv: From<Event<T>>
         + From<NetworkRequest<NodeId, Message>>
+        + From<NetworkInfoRequest<NodeId>>
         + From<StorageRequest>
         + From<ContractRuntimeRequest>
         + From<LinearChainRequest<NodeId>>
@@ -98,6 +110,19 @@ pub trait ItemFetcher<T: Item + 'static> {
         peer: NodeId,
     ) -> Effects<Event<T>>;
 
+    /// Stores an item received from `peer`, returning the effects of doing so.
+    ///
+    /// Returns `None` by default, as most items are validated and stored by a different component
+    /// before they reach the fetcher, like deploys by the deploy acceptor.
+    fn put_to_storage<REv: ReactorEventT<T>>(
+        &mut self,
+        _effect_builder: EffectBuilder<REv>,
+        _item: &T,
+        _peer: NodeId,
+    ) -> Option<Effects<Event<T>>> {
+        None
+    }
+
     /// Handles the `Ok` case for a `Result` of attempting to get the item from the storage
     /// component in order to send it to the requester.
     fn got_from_storage(&mut self, item: T, peer: NodeId) -> Effects<Event<T>> {
@@ -175,8 +200,17 @@ pub struct Fetcher<T>
 where
     T: Item + 'static,
 {
+    /// The name of the fetcher, identifying it in logs and metrics.
+    name: String,
     get_from_peer_timeout: Duration,
     responders: HashMap<T::Id, HashMap<NodeId, Vec<FetchResponder<T>>>>,
+    /// The peers which have already failed to deliver a given item in time.
+    timed_out_peers: HashMap<T::Id, HashSet<NodeId>>,
+    /// The peers still to be asked for a given item, in order, should the current peer fail to
+    /// deliver it.
+    fallback_peers: HashMap<T::Id, VecDeque<NodeId>>,
+    /// The peers which recently failed to deliver an item, least recent failure first.
+    unresponsive_peers: VecDeque<NodeId>,
     #[data_size(skip)]
     metrics: FetcherMetrics,
 }
@@ -188,11 +222,238 @@ impl<T: Item> Fetcher<T> {
         registry: &Registry,
     ) -> Result<Self, prometheus::Error> {
         Ok(Fetcher {
+            name: format!("{}_fetcher", name),
             get_from_peer_timeout: Duration::from_secs(config.get_from_peer_timeout()),
             responders: HashMap::new(),
+            timed_out_peers: HashMap::new(),
+            fallback_peers: HashMap::new(),
+            unresponsive_peers: VecDeque::new(),
             metrics: FetcherMetrics::new(name, registry)?,
         })
     }
+
+    /// Fetches the item, trying each of `peers` in order until one delivers it.
+    ///
+    /// As with `fetch`, the item is retrieved from storage if held locally.  The responder is only
+    /// signalled with `None` once every peer has failed to deliver the item.
+    pub(crate) fn fetch_from_peers<REv: ReactorEventT<T>>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        id: T::Id,
+        peers: Vec<NodeId>,
+        responder: FetchResponder<T>,
+    ) -> Effects<Event<T>>
+    where
+        Self: ItemFetcher<T>,
+    {
+        let mut peers = VecDeque::from(peers);
+        let first_peer = match peers.pop_front() {
+            Some(peer) => peer,
+            None => return responder.respond(None).ignore(),
+        };
+        let fallback_peers = self.fallback_peers.entry(id).or_default();
+        for peer in peers {
+            if peer != first_peer && !fallback_peers.contains(&peer) {
+                fallback_peers.push_back(peer);
+            }
+        }
+        self.fetch(effect_builder, id, first_peer, responder)
+    }
+
+    /// Returns the number of requests waiting for the given item.
+    fn waiting_count(&self, id: &T::Id) -> usize {
+        self.responders.get(id).map_or(0, |all_responders| {
+            all_responders.values().map(Vec::len).sum()
+        })
+    }
+
+    /// Orders the given peers by recent responsiveness: peers which have not recently failed to
+    /// deliver an item come first, followed by those which have, least recent failure first.
+    pub(crate) fn rank_peers(&self, mut peers: Vec<NodeId>) -> Vec<NodeId> {
+        peers.sort_by_key(|peer| {
+            self.unresponsive_peers
+                .iter()
+                .position(|unresponsive_peer| unresponsive_peer == peer)
+        });
+        peers
+    }
+
+    /// Moves `peer` to the back of the rotation after it failed to deliver an item.
+    fn record_failure(&mut self, peer: NodeId) {
+        self.unresponsive_peers
+            .retain(|unresponsive_peer| *unresponsive_peer != peer);
+        self.unresponsive_peers.push_back(peer);
+        if self.unresponsive_peers.len() > MAX_UNRESPONSIVE_PEERS {
+            let _ = self.unresponsive_peers.pop_front();
+        }
+    }
+
+    /// Hands an item received from `peer` to everyone waiting for it.
+    fn got_from_peer(&mut self, item: Box<T>, peer: NodeId) -> Effects<Event<T>>
+    where
+        Self: ItemFetcher<T>,
+    {
+        self.metrics
+            .record_found_on_peer(self.waiting_count(&item.id()));
+        self.record_success(peer);
+        self.signal(item.id(), Some(FetchResult::FromPeer(item, peer)), peer)
+    }
+
+    /// Restores `peer` to the front of the rotation after it delivered an item.
+    fn record_success(&mut self, peer: NodeId) {
+        self.unresponsive_peers
+            .retain(|unresponsive_peer| *unresponsive_peer != peer);
+    }
+
+    /// Returns the next fallback peer for the item which has not yet failed to deliver it.
+    fn next_fallback_peer(&mut self, id: T::Id) -> Option<NodeId> {
+        let fallback_peers = self.fallback_peers.get_mut(&id)?;
+        let timed_out_peers = self.timed_out_peers.get(&id);
+        let next_peer = loop {
+            match fallback_peers.pop_front() {
+                Some(peer) if timed_out_peers.map_or(false, |peers| peers.contains(&peer)) => {
+                    continue
+                }
+                maybe_peer => break maybe_peer,
+            }
+        };
+        if fallback_peers.is_empty() {
+            let _ = self.fallback_peers.remove(&id);
+        }
+        next_peer
+    }
+
+    /// Handles `peer` not having the item.
+    ///
+    /// If the item is being fetched from several peers, it is requested from the next one,
+    /// otherwise everyone waiting on `peer` is signalled with `None`.
+    fn absent_remotely<REv: ReactorEventT<T>>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        id: T::Id,
+        peer: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        Self: ItemFetcher<T>,
+    {
+        let still_waiting = self
+            .responders
+            .get(&id)
+            .map_or(false, |all_responders| all_responders.contains_key(&peer));
+        if !still_waiting {
+            return Effects::new();
+        }
+
+        self.record_failure(peer);
+        match self.next_fallback_peer(id) {
+            Some(next_peer) => self.redirect_to_peer(effect_builder, id, peer, next_peer),
+            None => self.signal(id, None, peer),
+        }
+    }
+
+    /// Handles `peer` not delivering the item in time.
+    ///
+    /// If anyone is still waiting for the item, the currently connected peers are retrieved so the
+    /// request can be retried with a peer which has not yet been asked.
+    fn timed_out<REv: ReactorEventT<T>>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        id: T::Id,
+        peer: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        Self: ItemFetcher<T>,
+    {
+        let still_waiting = self
+            .responders
+            .get(&id)
+            .map_or(false, |all_responders| all_responders.contains_key(&peer));
+        if !still_waiting {
+            return Effects::new();
+        }
+
+        self.record_failure(peer);
+        self.timed_out_peers.entry(id).or_default().insert(peer);
+        if let Some(next_peer) = self.next_fallback_peer(id) {
+            return self.redirect_to_peer(effect_builder, id, peer, next_peer);
+        }
+        effect_builder
+            .timeout(self.get_from_peer_timeout, effect_builder.network_peers())
+            .event(move |result| Event::GotPeers {
+                id,
+                timed_out_peer: peer,
+                peers: result.map(|peers| peers.into_iter().map(|(peer, _)| peer).collect()),
+            })
+    }
+
+    /// Moves everyone waiting on `timed_out_peer` over to a peer which hasn't been asked for the
+    /// item yet, or signals `None` to them if there is no such peer.
+    fn retry_with_another_peer<REv: ReactorEventT<T>>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        id: T::Id,
+        timed_out_peer: NodeId,
+        peers: Result<Vec<NodeId>, TimedOut>,
+    ) -> Effects<Event<T>>
+    where
+        Self: ItemFetcher<T>,
+    {
+        let maybe_next_peer = match peers {
+            Ok(peers) => {
+                let timed_out_peers = self.timed_out_peers.entry(id).or_default();
+                let untried_peers = peers
+                    .into_iter()
+                    .filter(|peer| !timed_out_peers.contains(peer))
+                    .collect();
+                self.rank_peers(untried_peers).into_iter().next()
+            }
+            Err(timed_out) => {
+                warn!(%id, %timed_out, "failed to get peers for retrying fetch");
+                None
+            }
+        };
+
+        let next_peer = match maybe_next_peer {
+            Some(next_peer) => next_peer,
+            None => {
+                info!(%id, "no more peers to fetch from");
+                return self.signal(id, None, timed_out_peer);
+            }
+        };
+
+        self.redirect_to_peer(effect_builder, id, timed_out_peer, next_peer)
+    }
+
+    /// Moves everyone waiting on `failed_peer` over to `next_peer`, and requests the item from it.
+    fn redirect_to_peer<REv: ReactorEventT<T>>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        id: T::Id,
+        failed_peer: NodeId,
+        next_peer: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        Self: ItemFetcher<T>,
+    {
+        let responders = match self
+            .responders
+            .get_mut(&id)
+            .and_then(|all_responders| all_responders.remove(&failed_peer))
+        {
+            Some(responders) => responders,
+            // The item arrived in the meantime.
+            None => return Effects::new(),
+        };
+
+        debug!(%id, %failed_peer, %next_peer, "retrying fetch from another peer");
+        self.responders
+            .entry(id)
+            .or_default()
+            .entry(next_peer)
+            .or_default()
+            .extend(responders);
+        self.failed_to_get_from_storage(effect_builder, id, next_peer)
+    }
 }
 
 impl ItemFetcher<Deploy> for Fetcher<Deploy> {
@@ -277,6 +538,61 @@ impl ItemFetcher<BlockByHeight> for Fetcher<BlockByHeight> {
     }
 }
 
+impl ItemFetcher<BlockHeader> for Fetcher<BlockHeader> {
+    fn responders(
+        &mut self,
+    ) -> &mut HashMap<BlockHash, HashMap<NodeId, Vec<FetchResponder<BlockHeader>>>> {
+        &mut self.responders
+    }
+
+    fn peer_timeout(&self) -> Duration {
+        self.get_from_peer_timeout
+    }
+
+    /// Gets a `BlockHeader` from the storage component, regardless of whether the block's body is
+    /// stored.
+    fn get_from_storage<REv: ReactorEventT<BlockHeader>>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        id: BlockHash,
+        peer: NodeId,
+    ) -> Effects<Event<BlockHeader>> {
+        effect_builder
+            .get_block_header_from_storage(id)
+            .event(move |result| Event::GetFromStorageResult {
+                id,
+                peer,
+                maybe_item: Box::new(result),
+            })
+    }
+
+    /// Stores a `BlockHeader` received from a peer only if it has been requested.
+    ///
+    /// The ID of a header is its hash, so a header matching a requested ID is the requested one.
+    /// Any other header is dropped.
+    fn put_to_storage<REv: ReactorEventT<BlockHeader>>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item: &BlockHeader,
+        peer: NodeId,
+    ) -> Option<Effects<Event<BlockHeader>>> {
+        let block_hash = item.id();
+        if !self.responders.contains_key(&block_hash) {
+            debug!(%block_hash, %peer, "dropping block header which was not requested");
+            return Some(Effects::new());
+        }
+        let block_header = Box::new(item.clone());
+        Some(
+            effect_builder
+                .put_block_header_to_storage(block_header.clone())
+                .event(move |_| Event::StoredRemotely {
+                    item: block_header,
+                    peer,
+                }),
+        )
+    }
+}
+
 type GlobalStorageTrie = Trie<Key, StoredValue>;
 
 impl ItemFetcher<GlobalStorageTrie> for Fetcher<GlobalStorageTrie> {
@@ -315,6 +631,10 @@ where
     type Event = Event<T>;
     type ConstructionError = prometheus::Error;
 
+    fn name(&self) -> &str {
+        &self.name
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -322,46 +642,74 @@ where
         event: Self::Event,
     ) -> Effects<Self::Event> {
         debug!(?event, "handling event");
-        match event {
+        let effects = match event {
             Event::Fetch {
                 id,
                 peer,
                 responder,
-            } => self.fetch(effect_builder, id, peer, responder),
+            } => {
+                self.metrics.attempts_total.inc();
+                self.fetch(effect_builder, id, peer, responder)
+            }
+            Event::FetchFromPeers {
+                id,
+                peers,
+                responder,
+            } => {
+                self.metrics.attempts_total.inc();
+                self.fetch_from_peers(effect_builder, id, peers, responder)
+            }
             Event::GetFromStorageResult {
                 id,
                 peer,
                 maybe_item,
             } => match *maybe_item {
                 Some(item) => {
-                    self.metrics.found_in_storage.inc();
+                    self.metrics
+                        .record_found_in_storage(self.waiting_count(&id));
                     self.got_from_storage(item, peer)
                 }
                 None => self.failed_to_get_from_storage(effect_builder, id, peer),
             },
             Event::GotRemotely { item, source } => {
                 match source {
-                    Source::Peer(peer) => {
-                        self.metrics.found_on_peer.inc();
-                        self.signal(item.id(), Some(FetchResult::FromPeer(item, peer)), peer)
-                    }
+                    Source::Peer(peer) => match self.put_to_storage(effect_builder, &item, peer) {
+                        Some(effects) => effects,
+                        None => self.got_from_peer(item, peer),
+                    },
                     Source::Client | Source::Ourself => {
                         // TODO - we could possibly also handle this case
                         Effects::new()
                     }
                 }
             }
+            Event::StoredRemotely { item, peer } => self.got_from_peer(item, peer),
             // We do nothing in the case of having an incoming deploy rejected.
             Event::RejectedRemotely { .. } => Effects::new(),
             Event::AbsentRemotely { id, peer } => {
                 info!(%id, %peer, "element absent on the remote node");
-                self.signal(id, None, peer)
+                self.absent_remotely(effect_builder, id, peer)
             }
             Event::TimeoutPeer { id, peer } => {
                 info!(%id, %peer, "request timed out");
-                self.metrics.timeouts.inc();
-                self.signal(id, None, peer)
+                self.metrics.record_timeout();
+                self.timed_out(effect_builder, id, peer)
             }
-        }
+            Event::GotPeers {
+                id,
+                timed_out_peer,
+                peers,
+            } => self.retry_with_another_peer(effect_builder, id, timed_out_peer, peers),
+        };
+
+        // Forget the peers which timed out, and those still to be tried, once nobody is waiting
+        // for the item anymore.
+        let responders = &self.responders;
+        self.timed_out_peers
+            .retain(|id, _| responders.contains_key(id));
+        self.fallback_peers
+            .retain(|id, _| responders.contains_key(id));
+
+        effects
     }
 }
@@ -2,23 +2,62 @@ use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
 const DEFAULT_GET_FROM_PEER_TIMEOUT_SECS: u64 = 3;
+/// Default rate limit in qps for the trie requests served to each peer.
+const DEFAULT_PEER_TRIE_REQUEST_QPS_LIMIT: u64 = 500;
+/// Default number of trie requests served to each peer in a single burst.
+const DEFAULT_PEER_TRIE_REQUEST_BURST_LIMIT: u64 = 1000;
 
 /// Configuration options for fetching.
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
 pub struct Config {
     get_from_peer_timeout: u64,
+    /// Max rate limit in qps for the trie requests served to each peer.  A value of 0 disables the
+    /// limit.
+    #[serde(default = "default_peer_trie_request_qps_limit")]
+    peer_trie_request_qps_limit: u64,
+    /// Max number of trie requests served to each peer in a single burst.
+    #[serde(default = "default_peer_trie_request_burst_limit")]
+    peer_trie_request_burst_limit: u64,
 }
 
 impl Config {
     pub(crate) fn get_from_peer_timeout(&self) -> u64 {
         self.get_from_peer_timeout
     }
+
+    pub(crate) fn peer_trie_request_qps_limit(&self) -> u64 {
+        self.peer_trie_request_qps_limit
+    }
+
+    pub(crate) fn peer_trie_request_burst_limit(&self) -> u64 {
+        self.peer_trie_request_burst_limit
+    }
+
+    /// Creates a default config with the given limits on the trie requests served to each peer.
+    #[cfg(test)]
+    pub(crate) fn new_with_trie_request_limits(qps_limit: u64, burst_limit: u64) -> Self {
+        Config {
+            peer_trie_request_qps_limit: qps_limit,
+            peer_trie_request_burst_limit: burst_limit,
+            ..Default::default()
+        }
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             get_from_peer_timeout: DEFAULT_GET_FROM_PEER_TIMEOUT_SECS,
+            peer_trie_request_qps_limit: DEFAULT_PEER_TRIE_REQUEST_QPS_LIMIT,
+            peer_trie_request_burst_limit: DEFAULT_PEER_TRIE_REQUEST_BURST_LIMIT,
         }
     }
 }
+
+fn default_peer_trie_request_qps_limit() -> u64 {
+    DEFAULT_PEER_TRIE_REQUEST_QPS_LIMIT
+}
+
+fn default_peer_trie_request_burst_limit() -> u64 {
+    DEFAULT_PEER_TRIE_REQUEST_BURST_LIMIT
+}
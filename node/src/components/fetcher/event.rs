@@ -5,7 +5,9 @@ use serde::Serialize;
 
 use super::Item;
 use crate::{
-    effect::{announcements::DeployAcceptorAnnouncement, requests::FetcherRequest, Responder},
+    effect::{
+        announcements::DeployAcceptorAnnouncement, requests::FetcherRequest, Responder, TimedOut,
+    },
     types::{Deploy, NodeId},
     utils::Source,
 };
@@ -27,6 +29,12 @@ pub enum Event<T: Item> {
         peer: NodeId,
         responder: FetchResponder<T>,
     },
+    /// The initiating event to fetch an item by its id, trying each of the given peers in turn.
+    FetchFromPeers {
+        id: T::Id,
+        peers: Vec<NodeId>,
+        responder: FetchResponder<T>,
+    },
     /// The result of the `Fetcher` getting a item from the storage component.  If the
     /// result is `None`, the item should be requested from the peer.
     GetFromStorageResult {
@@ -39,6 +47,8 @@ pub enum Event<T: Item> {
         item: Box<T>,
         source: Source<NodeId>,
     },
+    /// An item received from a peer has been stored by the fetcher itself.
+    StoredRemotely { item: Box<T>, peer: NodeId },
     /// A different component rejected an item.
     // TODO: If having this event is not desirable, the `DeployAcceptorAnnouncement` needs to be
     //       split in two instead.
@@ -50,6 +60,13 @@ pub enum Event<T: Item> {
     AbsentRemotely { id: T::Id, peer: NodeId },
     /// The timeout has elapsed and we should clean up state.
     TimeoutPeer { id: T::Id, peer: NodeId },
+    /// The currently connected peers, retrieved after `timed_out_peer` failed to deliver the item
+    /// in time.  If the peers could not be retrieved in time either, `peers` is `Err`.
+    GotPeers {
+        id: T::Id,
+        timed_out_peer: NodeId,
+        peers: Result<Vec<NodeId>, TimedOut>,
+    },
 }
 
 impl<T: Item> From<FetcherRequest<NodeId, T>> for Event<T> {
@@ -64,6 +81,15 @@ impl<T: Item> From<FetcherRequest<NodeId, T>> for Event<T> {
                 peer,
                 responder,
             },
+            FetcherRequest::FetchFromPeers {
+                id,
+                peers,
+                responder,
+            } => Event::FetchFromPeers {
+                id,
+                peers,
+                responder,
+            },
         }
     }
 }
@@ -93,6 +119,12 @@ impl<T: Item> Display for Event<T> {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Event::Fetch { id, .. } => write!(formatter, "request to fetch item at hash {}", id),
+            Event::FetchFromPeers { id, peers, .. } => write!(
+                formatter,
+                "request to fetch item at hash {} from {} peers",
+                id,
+                peers.len()
+            ),
             Event::GetFromStorageResult { id, maybe_item, .. } => {
                 if maybe_item.is_some() {
                     write!(formatter, "got {} from storage", id)
@@ -103,6 +135,9 @@ impl<T: Item> Display for Event<T> {
             Event::GotRemotely { item, source } => {
                 write!(formatter, "got {} from {}", item.id(), source)
             }
+            Event::StoredRemotely { item, peer } => {
+                write!(formatter, "stored {} received from {}", item.id(), peer)
+            }
             Event::RejectedRemotely { item, source } => write!(
                 formatter,
                 "other component rejected {} from {}",
@@ -117,6 +152,24 @@ impl<T: Item> Display for Event<T> {
             Event::AbsentRemotely { id, peer } => {
                 write!(formatter, "Item {} was not available on {}", id, peer)
             }
+            Event::GotPeers {
+                id,
+                timed_out_peer,
+                peers,
+            } => match peers {
+                Ok(peers) => write!(
+                    formatter,
+                    "got {} peers to retry {} after {} timed out",
+                    peers.len(),
+                    id,
+                    timed_out_peer
+                ),
+                Err(timed_out) => write!(
+                    formatter,
+                    "failed to get peers to retry {} after {} timed out: {}",
+                    id, timed_out_peer, timed_out
+                ),
+            },
         }
     }
 }
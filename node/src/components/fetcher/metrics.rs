@@ -1,15 +1,37 @@
-use prometheus::{IntCounter, Registry};
+use prometheus::{IntCounter, Opts, Registry};
 
 use crate::unregister_metric;
 
+/// Creates a counter shared by all fetchers, distinguished by an `item_type` label of the given
+/// fetcher name.
+fn new_item_type_counter(
+    name: &str,
+    help: &str,
+    item_type: &str,
+) -> Result<IntCounter, prometheus::Error> {
+    IntCounter::with_opts(Opts::new(name, help).const_label("item_type", item_type))
+}
+
 #[derive(Debug)]
 pub(super) struct FetcherMetrics {
     /// Number of fetch requests that found an item in the storage.
-    pub(super) found_in_storage: IntCounter,
+    ///
+    /// Deprecated in favor of `successes_total`.
+    found_in_storage: IntCounter,
     /// Number of fetch requests that fetched an item from peer.
-    pub(super) found_on_peer: IntCounter,
+    ///
+    /// Deprecated in favor of `successes_total`.
+    found_on_peer: IntCounter,
     /// Number of fetch requests that timed out.
-    pub(super) timeouts: IntCounter,
+    ///
+    /// Deprecated in favor of `timeouts_total`.
+    timeouts: IntCounter,
+    /// Number of requests to fetch an item, labelled by item type.
+    pub(super) attempts_total: IntCounter,
+    /// Number of requests to fetch an item which returned the item, labelled by item type.
+    pub(super) successes_total: IntCounter,
+    /// Number of requests to a peer for an item which timed out, labelled by item type.
+    pub(super) timeouts_total: IntCounter,
     /// Reference to the registry for unregistering.
     registry: Registry,
 }
@@ -31,17 +53,56 @@ impl FetcherMetrics {
             format!("{}_timeouts", name),
             format!("number of {} fetch requests that timed out", name),
         )?;
+        let attempts_total = new_item_type_counter(
+            "fetcher_attempts_total",
+            "number of requests to fetch an item",
+            name,
+        )?;
+        let successes_total = new_item_type_counter(
+            "fetcher_successes_total",
+            "number of requests to fetch an item which returned the item",
+            name,
+        )?;
+        let timeouts_total = new_item_type_counter(
+            "fetcher_timeouts_total",
+            "number of requests to a peer for an item which timed out",
+            name,
+        )?;
         registry.register(Box::new(found_in_storage.clone()))?;
         registry.register(Box::new(found_on_peer.clone()))?;
         registry.register(Box::new(timeouts.clone()))?;
+        registry.register(Box::new(attempts_total.clone()))?;
+        registry.register(Box::new(successes_total.clone()))?;
+        registry.register(Box::new(timeouts_total.clone()))?;
 
         Ok(FetcherMetrics {
             found_in_storage,
             found_on_peer,
             timeouts,
+            attempts_total,
+            successes_total,
+            timeouts_total,
             registry: registry.clone(),
         })
     }
+
+    /// Records an item found in storage, returned to `successes` requests waiting for it.
+    pub(super) fn record_found_in_storage(&self, successes: usize) {
+        self.found_in_storage.inc();
+        self.successes_total.inc_by(successes as u64);
+    }
+
+    /// Records an item received from a peer, returned to `successes` requests waiting for it.
+    pub(super) fn record_found_on_peer(&self, successes: usize) {
+        self.found_on_peer.inc();
+        self.successes_total.inc_by(successes as u64);
+    }
+
+    /// Records a request to a peer which timed out.
+    pub(super) fn record_timeout(&self) {
+        self.timeouts.inc();
+        self.timeouts_total.inc();
+    }
 }
 
 impl Drop for FetcherMetrics {
@@ -49,5 +110,8 @@ impl Drop for FetcherMetrics {
         unregister_metric!(self.registry, self.found_in_storage);
         unregister_metric!(self.registry, self.found_on_peer);
         unregister_metric!(self.registry, self.timeouts);
+        unregister_metric!(self.registry, self.attempts_total);
+        unregister_metric!(self.registry, self.successes_total);
+        unregister_metric!(self.registry, self.timeouts_total);
     }
 }
@@ -16,15 +16,16 @@ use crate::{
     components::{deploy_acceptor, in_memory_network::NetworkController, storage},
     effect::{
         announcements::{DeployAcceptorAnnouncement, NetworkAnnouncement},
+        requests::BlockProposerRequest,
         Responder,
     },
     protocol::Message,
-    reactor::{Reactor as ReactorTrait, Runner},
+    reactor::{QueueKind, Reactor as ReactorTrait, Runner},
     testing::{
         network::{Network, NetworkedReactor},
         ConditionCheckReactor, TestRng,
     },
-    types::{Deploy, DeployHash, NodeId},
+    types::{Block, BlockHeader, Deploy, DeployHash, NodeId, Tag},
     utils::{WithDir, RESOURCES_PATH},
 };
 
@@ -77,23 +78,28 @@ reactor!(Reactor {
             chainspec_loader.hard_reset_to_start_of_era(),
             ProtocolVersion::from_parts(1, 0, 0),
             false,
+            registry,
         );
         deploy_acceptor = infallible DeployAcceptor(cfg.deploy_acceptor_config, &*chainspec_loader.chainspec());
         deploy_fetcher = Fetcher::<Deploy>("deploy", cfg.fetcher_config, registry);
+        block_header_fetcher = Fetcher::<BlockHeader>("block_header_by_hash", cfg.fetcher_config, registry);
     }
 
     events: {
         network = Event<Message>;
         deploy_fetcher = Event<Deploy>;
+        block_header_fetcher = Event<BlockHeader>;
     }
 
     requests: {
         // This test contains no linear chain requests, so we panic if we receive any.
         LinearChainRequest<NodeId> -> !;
         NetworkRequest<NodeId, Message> -> network;
+        NetworkInfoRequest<NodeId> -> fn handle_network_info_request;
         StorageRequest -> storage;
         StateStoreRequest -> storage;
         FetcherRequest<NodeId, Deploy> -> deploy_fetcher;
+        BlockProposerRequest -> fn handle_block_proposer_request;
 
         // The only contract runtime request will be the commit of genesis, which we discard.
         ContractRuntimeRequest -> #;
@@ -107,6 +113,10 @@ reactor!(Reactor {
         // from a client.
         RpcServerAnnouncement -> [deploy_acceptor];
         ChainspecLoaderAnnouncement -> [!];
+        // Only valid deploys are sent between peers, so no protocol violations are announced.
+        BlocklistAnnouncement<NodeId> -> [!];
+        // Nothing corrupts the storage of the test nodes, so no corrupt entries are announced.
+        StorageAnnouncement -> [!];
     }
 });
 
@@ -121,6 +131,36 @@ impl Reactor {
         // announcements.
         match network_announcement {
             NetworkAnnouncement::MessageReceived { sender, payload } => match payload {
+                Message::GetRequest {
+                    tag: Tag::BlockHeaderByHash,
+                    serialized_id,
+                } => {
+                    let block_hash = match bincode::deserialize(&serialized_id) {
+                        Ok(hash) => hash,
+                        Err(error) => {
+                            error!(
+                                "failed to decode {:?} from {}: {}",
+                                serialized_id, sender, error
+                            );
+                            return Effects::new();
+                        }
+                    };
+
+                    match self.storage.read_block_header_by_hash(&block_hash) {
+                        Ok(Some(block_header)) => match Message::new_get_response(&block_header) {
+                            Ok(message) => effect_builder.send_message(sender, message).ignore(),
+                            Err(error) => {
+                                error!("failed to create get-response: {}", error);
+                                Effects::new()
+                            }
+                        },
+                        _ => {
+                            debug!(%sender, %block_hash, "failed to get block header");
+                            Effects::new()
+                        }
+                    }
+                }
+
                 Message::GetRequest { serialized_id, .. } => {
                     let deploy_hash = match bincode::deserialize(&serialized_id) {
                         Ok(hash) => hash,
@@ -150,6 +190,29 @@ impl Reactor {
                     }
                 }
 
+                Message::GetResponse {
+                    tag: Tag::BlockHeaderByHash,
+                    serialized_item,
+                } => {
+                    let block_header: Box<BlockHeader> =
+                        match bincode::deserialize(&serialized_item) {
+                            Ok(block_header) => Box::new(block_header),
+                            Err(error) => {
+                                error!("failed to decode block header from {}: {}", sender, error);
+                                return Effects::new();
+                            }
+                        };
+
+                    self.dispatch_event(
+                        effect_builder,
+                        rng,
+                        ReactorEvent::BlockHeaderFetcher(Event::GotRemotely {
+                            item: block_header,
+                            source: Source::Peer(sender),
+                        }),
+                    )
+                }
+
                 Message::GetResponse {
                     serialized_item, ..
                 } => {
@@ -176,6 +239,40 @@ impl Reactor {
             ann => panic!("should not received any network announcements: {:?}", ann),
         }
     }
+
+    fn handle_network_info_request(
+        &mut self,
+        _effect_builder: EffectBuilder<ReactorEvent>,
+        _rng: &mut NodeRng,
+        request: NetworkInfoRequest<NodeId>,
+    ) -> Effects<ReactorEvent> {
+        match request {
+            NetworkInfoRequest::GetPeers { responder } => {
+                responder.respond(self.network.peers()).ignore()
+            }
+            NetworkInfoRequest::GetPeerConnections { responder } => {
+                responder.respond(Vec::new()).ignore()
+            }
+            NetworkInfoRequest::GetPeerProtocolVersion { responder, .. } => {
+                responder.respond(None).ignore()
+            }
+        }
+    }
+
+    fn handle_block_proposer_request(
+        &mut self,
+        _effect_builder: EffectBuilder<ReactorEvent>,
+        _rng: &mut NodeRng,
+        request: BlockProposerRequest,
+    ) -> Effects<ReactorEvent> {
+        match request {
+            // There is no block proposer, so the deploy buffer is never full.
+            BlockProposerRequest::IsBufferFull { responder } => responder.respond(false).ignore(),
+            BlockProposerRequest::RequestBlockPayload(_) => {
+                panic!("unexpected block payload request")
+            }
+        }
+    }
 }
 
 impl NetworkedReactor for Reactor {
@@ -216,6 +313,36 @@ fn fetch_deploy(
     }
 }
 
+fn fetch_deploy_from_peers(
+    deploy_hash: DeployHash,
+    peers: Vec<NodeId>,
+    fetched: FetchedDeployResult,
+) -> impl FnOnce(EffectBuilder<ReactorEvent>) -> Effects<ReactorEvent> {
+    move |effect_builder: EffectBuilder<ReactorEvent>| {
+        effect_builder
+            .fetch_deploy_from_peers(deploy_hash, peers)
+            .then(move |maybe_deploy| async move {
+                let mut result = fetched.lock().unwrap();
+                result.0 = true;
+                result.1 = maybe_deploy;
+            })
+            .ignore()
+    }
+}
+
+/// Returns a condition which is met once a `GetRequest` is sent to `peer`.
+fn sent_get_request_to(peer: NodeId) -> impl Fn(&ReactorEvent) -> bool {
+    move |event: &ReactorEvent| {
+        if let ReactorEvent::NetworkRequest(NetworkRequest::SendMessage { dest, payload, .. }) =
+            event
+        {
+            **dest == peer && matches!(**payload, Message::GetRequest { .. })
+        } else {
+            false
+        }
+    }
+}
+
 /// Store a deploy on a target node.
 async fn store_deploy(
     deploy: &Deploy,
@@ -455,3 +582,720 @@ async fn should_timeout_fetch_from_peer() {
 
     NetworkController::<Message>::remove_active();
 }
+
+#[tokio::test]
+async fn should_refetch_from_another_peer_on_timeout() {
+    const NETWORK_SIZE: usize = 2;
+
+    NetworkController::<Message>::create_active();
+    let (mut network, mut rng, node_ids) = {
+        let mut network = Network::<Reactor>::new();
+        let mut rng = TestRng::new();
+        let node_ids = network.add_nodes(&mut rng, NETWORK_SIZE).await;
+        (network, rng, node_ids)
+    };
+
+    // Create a random deploy.
+    let deploy = Deploy::random(&mut rng);
+    let deploy_hash = *deploy.id();
+
+    let holding_node = node_ids[0];
+    let requesting_node = node_ids[1];
+    // A peer which is not part of the network, so will never respond.
+    let unresponsive_node = NodeId::random(&mut rng);
+
+    // Store deploy on holding node.
+    store_deploy(&deploy, &holding_node, &mut network, None, &mut rng).await;
+
+    // Initiate requesting node asking for deploy from the unresponsive node.
+    let fetched = Arc::new(Mutex::new((false, None)));
+    network
+        .process_injected_effect_on(
+            &requesting_node,
+            fetch_deploy(deploy_hash, unresponsive_node, Arc::clone(&fetched)),
+        )
+        .await;
+
+    // Crank until message sent from the requester.
+    network
+        .crank_until(
+            &requesting_node,
+            &mut rng,
+            move |event: &ReactorEvent| {
+                if let ReactorEvent::NetworkRequest(NetworkRequest::SendMessage {
+                    dest,
+                    payload,
+                    ..
+                }) = event
+                {
+                    **dest == unresponsive_node && matches!(**payload, Message::GetRequest { .. })
+                } else {
+                    false
+                }
+            },
+            TIMEOUT,
+        )
+        .await;
+
+    // Advance time past the peer timeout.
+    let secs_to_advance = Config::default().get_from_peer_timeout();
+    time::pause();
+    time::advance(Duration::from_secs(secs_to_advance + 1)).await;
+    time::resume();
+
+    // The requester should retry with the holding node and get the deploy from there.
+    let expected_result = Some(FetchResult::FromPeer(Box::new(deploy), holding_node));
+    assert_settled(
+        &requesting_node,
+        deploy_hash,
+        expected_result,
+        fetched,
+        &mut network,
+        &mut rng,
+        TIMEOUT,
+    )
+    .await;
+
+    NetworkController::<Message>::remove_active();
+}
+
+#[tokio::test]
+async fn should_fetch_from_peers_in_order() {
+    const NETWORK_SIZE: usize = 4;
+
+    NetworkController::<Message>::create_active();
+    let (mut network, mut rng, node_ids) = {
+        let mut network = Network::<Reactor>::new();
+        let mut rng = TestRng::new();
+        let node_ids = network.add_nodes(&mut rng, NETWORK_SIZE).await;
+        (network, rng, node_ids)
+    };
+
+    // Create a random deploy.
+    let deploy = Deploy::random(&mut rng);
+    let deploy_hash = *deploy.id();
+
+    let requesting_node = node_ids[0];
+    let peers = node_ids[1..].to_vec();
+    let holding_node = peers[2];
+
+    // Store deploy on the last of the peers only.
+    store_deploy(&deploy, &holding_node, &mut network, None, &mut rng).await;
+
+    // Initiate requesting node asking for deploy from each of the peers in turn.
+    let fetched = Arc::new(Mutex::new((false, None)));
+    network
+        .process_injected_effect_on(
+            &requesting_node,
+            fetch_deploy_from_peers(deploy_hash, peers.clone(), Arc::clone(&fetched)),
+        )
+        .await;
+
+    // The first two peers don't hold the deploy, so the request to each of them times out.
+    let secs_to_advance = Config::default().get_from_peer_timeout();
+    for peer in &peers[..2] {
+        network
+            .crank_until(
+                &requesting_node,
+                &mut rng,
+                sent_get_request_to(*peer),
+                TIMEOUT,
+            )
+            .await;
+        time::pause();
+        time::advance(Duration::from_secs(secs_to_advance + 1)).await;
+        time::resume();
+    }
+
+    // The requester should then get the deploy from the third peer.
+    let expected_result = Some(FetchResult::FromPeer(Box::new(deploy), holding_node));
+    assert_settled(
+        &requesting_node,
+        deploy_hash,
+        expected_result,
+        fetched,
+        &mut network,
+        &mut rng,
+        TIMEOUT,
+    )
+    .await;
+
+    // The peers which failed should have been moved to the back of the rotation.
+    let ranked_peers = network
+        .nodes()
+        .get(&requesting_node)
+        .unwrap()
+        .reactor()
+        .inner()
+        .deploy_fetcher
+        .rank_peers(peers.clone());
+    assert_eq!(ranked_peers, vec![peers[2], peers[0], peers[1]]);
+
+    NetworkController::<Message>::remove_active();
+}
+
+#[tokio::test]
+async fn should_fetch_block_header_without_body() {
+    const NETWORK_SIZE: usize = 2;
+
+    NetworkController::<Message>::create_active();
+    let (mut network, mut rng, node_ids) = {
+        let mut network = Network::<Reactor>::new();
+        let mut rng = TestRng::new();
+        let node_ids = network.add_nodes(&mut rng, NETWORK_SIZE).await;
+        (network, rng, node_ids)
+    };
+
+    // Store only the header of a random block on the holding node.
+    let block = Block::random(&mut rng);
+    let block_header = block.header().clone();
+    let block_hash = *block.hash();
+    let holding_node = node_ids[0];
+    let requesting_node = node_ids[1];
+    let stored = Arc::new(Mutex::new(false));
+    let stored_clone = Arc::clone(&stored);
+    let header_to_store = Box::new(block_header.clone());
+    network
+        .process_injected_effect_on(&holding_node, move |effect_builder| {
+            effect_builder
+                .put_block_header_to_storage(header_to_store)
+                .then(move |was_stored| async move {
+                    *stored_clone.lock().unwrap() = was_stored;
+                })
+                .ignore()
+        })
+        .await;
+    network
+        .settle_on(&mut rng, |_| *stored.lock().unwrap(), TIMEOUT)
+        .await;
+
+    // Fetch the header from the holding node.
+    let fetched = Arc::new(Mutex::new((false, None)));
+    let fetched_clone = Arc::clone(&fetched);
+    network
+        .process_injected_effect_on(&requesting_node, move |effect_builder| {
+            // `FetcherRequest`s are routed to the deploy fetcher, so the block header fetcher is
+            // sent its event directly.
+            effect_builder
+                .make_request(
+                    |responder| Event::<BlockHeader>::Fetch {
+                        id: block_hash,
+                        peer: holding_node,
+                        responder,
+                    },
+                    QueueKind::Regular,
+                )
+                .then(move |maybe_block_header| async move {
+                    *fetched_clone.lock().unwrap() = (true, maybe_block_header);
+                })
+                .ignore()
+        })
+        .await;
+    network
+        .settle_on(&mut rng, |_| fetched.lock().unwrap().0, TIMEOUT)
+        .await;
+    assert_eq!(
+        fetched.lock().unwrap().1,
+        Some(FetchResult::FromPeer(
+            Box::new(block_header.clone()),
+            holding_node
+        ))
+    );
+
+    // The header is stored and queryable on its own, while the block is still incomplete.
+    let storage = &network
+        .nodes()
+        .get(&requesting_node)
+        .unwrap()
+        .reactor()
+        .inner()
+        .storage;
+    assert_eq!(
+        storage.read_block_header_by_hash(&block_hash).unwrap(),
+        Some(block_header)
+    );
+    assert_eq!(storage.get_blocks_batch(&[block_hash]).unwrap(), vec![None]);
+
+    NetworkController::<Message>::remove_active();
+}
+
+#[tokio::test]
+async fn should_not_store_block_header_which_was_not_requested() {
+    const NETWORK_SIZE: usize = 2;
+
+    NetworkController::<Message>::create_active();
+    let (mut network, mut rng, node_ids) = {
+        let mut network = Network::<Reactor>::new();
+        let mut rng = TestRng::new();
+        let node_ids = network.add_nodes(&mut rng, NETWORK_SIZE).await;
+        (network, rng, node_ids)
+    };
+
+    // Hand the block header fetcher a header from a peer, without it having been requested.
+    let block_header = Block::random(&mut rng).header().clone();
+    let block_hash = block_header.hash();
+    let sender = node_ids[0];
+    let receiving_node = node_ids[1];
+    let item = Box::new(block_header);
+    network
+        .process_injected_effect_on(&receiving_node, move |effect_builder| {
+            effect_builder
+                .into_inner()
+                .schedule(
+                    Event::<BlockHeader>::GotRemotely {
+                        item,
+                        source: Source::Peer(sender),
+                    },
+                    QueueKind::Regular,
+                )
+                .ignore()
+        })
+        .await;
+    network
+        .settle(&mut rng, Duration::from_millis(100), TIMEOUT)
+        .await;
+
+    let storage = &network
+        .nodes()
+        .get(&receiving_node)
+        .unwrap()
+        .reactor()
+        .inner()
+        .storage;
+    assert_eq!(
+        storage.read_block_header_by_hash(&block_hash).unwrap(),
+        None
+    );
+
+    NetworkController::<Message>::remove_active();
+}
+
+#[tokio::test]
+async fn should_count_fetch_outcomes_by_item_type() {
+    const NETWORK_SIZE: usize = 2;
+
+    NetworkController::<Message>::create_active();
+    let (mut network, mut rng, node_ids) = {
+        let mut network = Network::<Reactor>::new();
+        let mut rng = TestRng::new();
+        let node_ids = network.add_nodes(&mut rng, NETWORK_SIZE).await;
+        (network, rng, node_ids)
+    };
+
+    // Create a random deploy.
+    let deploy = Deploy::random(&mut rng);
+    let deploy_hash = *deploy.id();
+
+    let holding_node = node_ids[0];
+    let requesting_node = node_ids[1];
+    // A peer which is not part of the network, so will never respond.
+    let unresponsive_node = NodeId::random(&mut rng);
+
+    // Store deploy on holding node.
+    store_deploy(&deploy, &holding_node, &mut network, None, &mut rng).await;
+
+    // Fetch the deploy via the unresponsive node, which times out before the holding node is
+    // asked.
+    let fetched = Arc::new(Mutex::new((false, None)));
+    network
+        .process_injected_effect_on(
+            &requesting_node,
+            fetch_deploy(deploy_hash, unresponsive_node, Arc::clone(&fetched)),
+        )
+        .await;
+    network
+        .crank_until(
+            &requesting_node,
+            &mut rng,
+            sent_get_request_to(unresponsive_node),
+            TIMEOUT,
+        )
+        .await;
+    let secs_to_advance = Config::default().get_from_peer_timeout();
+    time::pause();
+    time::advance(Duration::from_secs(secs_to_advance + 1)).await;
+    time::resume();
+    let expected_result = Some(FetchResult::FromPeer(
+        Box::new(deploy.clone()),
+        holding_node,
+    ));
+    assert_settled(
+        &requesting_node,
+        deploy_hash,
+        expected_result,
+        fetched,
+        &mut network,
+        &mut rng,
+        TIMEOUT,
+    )
+    .await;
+
+    // Fetch the deploy again, which should now be found in local storage.
+    let fetched = Arc::new(Mutex::new((false, None)));
+    network
+        .process_injected_effect_on(
+            &requesting_node,
+            fetch_deploy(deploy_hash, holding_node, Arc::clone(&fetched)),
+        )
+        .await;
+    let expected_result = Some(FetchResult::FromStorage(Box::new(deploy)));
+    assert_settled(
+        &requesting_node,
+        deploy_hash,
+        expected_result,
+        fetched,
+        &mut network,
+        &mut rng,
+        TIMEOUT,
+    )
+    .await;
+
+    let metrics = &network
+        .nodes()
+        .get(&requesting_node)
+        .unwrap()
+        .reactor()
+        .inner()
+        .deploy_fetcher
+        .metrics;
+    assert_eq!(metrics.attempts_total.get(), 2);
+    assert_eq!(metrics.successes_total.get(), 2);
+    assert_eq!(metrics.timeouts_total.get(), 1);
+
+    NetworkController::<Message>::remove_active();
+}
+
+#[test]
+fn should_label_fetch_counters_by_item_type() {
+    let registry = Registry::new();
+    let deploy_fetcher = Fetcher::<Deploy>::new("deploy", Config::default(), &registry).unwrap();
+    let _block_fetcher = Fetcher::<Block>::new("block", Config::default(), &registry).unwrap();
+    deploy_fetcher.metrics.attempts_total.inc();
+
+    let attempts = registry
+        .gather()
+        .into_iter()
+        .find(|family| family.get_name() == "fetcher_attempts_total")
+        .expect("should have attempts counter");
+    let mut counts: Vec<_> = attempts
+        .get_metric()
+        .iter()
+        .map(|metric| {
+            let label = &metric.get_label()[0];
+            assert_eq!(label.get_name(), "item_type");
+            (
+                label.get_value().to_string(),
+                metric.get_counter().get_value(),
+            )
+        })
+        .collect();
+    counts.sort_by(|left, right| left.0.cmp(&right.0));
+    assert_eq!(
+        counts,
+        vec![("block".to_string(), 0.0), ("deploy".to_string(), 1.0)]
+    );
+}
+
+/// A reactor syncing global state tries, kept separate as requests are routed by type, so there can
+/// be only one `FetcherRequest` route per reactor.
+mod trie_sync {
+    use rand::Rng;
+
+    use casper_execution_engine::{
+        core::engine_state::WASMLESS_TRANSFER_FIXED_GAS_PRICE, storage::trie::Pointer,
+    };
+    use casper_types::{account::AccountHash, CLValue};
+
+    use super::*;
+    use crate::{
+        components::contract_runtime,
+        crypto::hash::Digest,
+        effect::{announcements::ContractRuntimeAnnouncement, requests::ConsensusRequest},
+    };
+
+    impl Drop for Reactor {
+        fn drop(&mut self) {
+            NetworkController::<Message>::remove_node(&self.network.node_id())
+        }
+    }
+
+    reactor!(Reactor {
+        type Config = FetcherTestConfig;
+
+        components: {
+            network = infallible InMemoryNetwork::<Message>(event_queue, rng);
+            contract_runtime = ContractRuntime(
+                Digest::random(rng),
+                None,
+                ProtocolVersion::from_parts(1, 0, 0),
+                WASMLESS_TRANSFER_FIXED_GAS_PRICE,
+                WithDir::new(cfg.temp_dir.path(), cfg.storage_config.clone()),
+                &Default::default(),
+                registry,
+            );
+            storage = Storage(
+                &WithDir::new(cfg.temp_dir.path(), cfg.storage_config),
+                None,
+                ProtocolVersion::from_parts(1, 0, 0),
+                false,
+                registry,
+            );
+            trie_fetcher = Fetcher::<Trie<Key, StoredValue>>("trie", cfg.fetcher_config, registry);
+        }
+
+        events: {
+            network = Event<Message>;
+            trie_fetcher = Event<Trie<Key, StoredValue>>;
+        }
+
+        requests: {
+            // This test contains no linear chain or consensus requests, so we panic if we receive
+            // any.
+            LinearChainRequest<NodeId> -> !;
+            ConsensusRequest -> !;
+            NetworkRequest<NodeId, Message> -> network;
+            NetworkInfoRequest<NodeId> -> fn handle_network_info_request;
+            StorageRequest -> storage;
+            FetcherRequest<NodeId, Trie<Key, StoredValue>> -> trie_fetcher;
+            ContractRuntimeRequest -> fn handle_contract_runtime_request;
+        }
+
+        announcements: {
+            NetworkAnnouncement<NodeId, Message> -> [fn handle_message];
+            ContractRuntimeAnnouncement -> [!];
+            StorageAnnouncement -> [!];
+        }
+    });
+
+    impl Reactor {
+        fn handle_message(
+            &mut self,
+            effect_builder: EffectBuilder<ReactorEvent>,
+            rng: &mut NodeRng,
+            network_announcement: NetworkAnnouncement<NodeId, Message>,
+        ) -> Effects<ReactorEvent> {
+            match network_announcement {
+                NetworkAnnouncement::MessageReceived { sender, payload } => match payload {
+                    Message::GetRequest {
+                        tag: Tag::Trie,
+                        serialized_id,
+                    } => {
+                        let trie_key: Blake2bHash = match bincode::deserialize(&serialized_id) {
+                            Ok(trie_key) => trie_key,
+                            Err(error) => {
+                                error!(
+                                    "failed to decode {:?} from {}: {}",
+                                    serialized_id, sender, error
+                                );
+                                return Effects::new();
+                            }
+                        };
+
+                        async move {
+                            match effect_builder.read_trie(trie_key).await {
+                                Some(trie) => match Message::new_get_response(&trie) {
+                                    Ok(message) => {
+                                        effect_builder.send_message(sender, message).await
+                                    }
+                                    Err(error) => {
+                                        error!("failed to create get-response: {}", error)
+                                    }
+                                },
+                                None => {
+                                    debug!(%sender, %trie_key, "failed to get trie (not found)")
+                                }
+                            }
+                        }
+                        .ignore()
+                    }
+
+                    Message::GetResponse {
+                        tag: Tag::Trie,
+                        serialized_item,
+                    } => {
+                        let trie = match bincode::deserialize(&serialized_item) {
+                            Ok(trie) => Box::new(trie),
+                            Err(error) => {
+                                error!("failed to decode trie from {}: {}", sender, error);
+                                return Effects::new();
+                            }
+                        };
+
+                        self.dispatch_event(
+                            effect_builder,
+                            rng,
+                            ReactorEvent::TrieFetcher(Event::GotRemotely {
+                                item: trie,
+                                source: Source::Peer(sender),
+                            }),
+                        )
+                    }
+                    msg => panic!("should not get {}", msg),
+                },
+                ann => panic!("should not received any network announcements: {:?}", ann),
+            }
+        }
+
+        fn handle_network_info_request(
+            &mut self,
+            _effect_builder: EffectBuilder<ReactorEvent>,
+            _rng: &mut NodeRng,
+            request: NetworkInfoRequest<NodeId>,
+        ) -> Effects<ReactorEvent> {
+            match request {
+                NetworkInfoRequest::GetPeers { responder } => {
+                    responder.respond(self.network.peers()).ignore()
+                }
+                NetworkInfoRequest::GetPeerConnections { responder } => {
+                    responder.respond(Vec::new()).ignore()
+                }
+                NetworkInfoRequest::GetPeerProtocolVersion { responder, .. } => {
+                    responder.respond(None).ignore()
+                }
+            }
+        }
+
+        fn handle_contract_runtime_request(
+            &mut self,
+            effect_builder: EffectBuilder<ReactorEvent>,
+            rng: &mut NodeRng,
+            request: ContractRuntimeRequest,
+        ) -> Effects<ReactorEvent> {
+            self.dispatch_event(
+                effect_builder,
+                rng,
+                ReactorEvent::ContractRuntime(contract_runtime::Event::Request(Box::new(request))),
+            )
+        }
+    }
+
+    impl NetworkedReactor for Reactor {
+        type NodeId = NodeId;
+
+        fn node_id(&self) -> NodeId {
+            self.network.node_id()
+        }
+    }
+
+    /// Reads the trie stored under `trie_key` on `node_id`.
+    async fn read_trie(
+        trie_key: Blake2bHash,
+        node_id: &NodeId,
+        network: &mut Network<Reactor>,
+        rng: &mut TestRng,
+    ) -> Option<Trie<Key, StoredValue>> {
+        let read = Arc::new(Mutex::new((false, None)));
+        let read_clone = Arc::clone(&read);
+        network
+            .process_injected_effect_on(node_id, move |effect_builder| {
+                effect_builder
+                    .read_trie(trie_key)
+                    .then(move |maybe_trie| async move {
+                        *read_clone.lock().unwrap() = (true, maybe_trie);
+                    })
+                    .ignore()
+            })
+            .await;
+        network
+            .settle_on(rng, |_| read.lock().unwrap().0, TIMEOUT)
+            .await;
+        let mut read = read.lock().unwrap();
+        read.1.take()
+    }
+
+    #[tokio::test]
+    async fn should_sync_trie_from_peer() {
+        const NETWORK_SIZE: usize = 2;
+
+        NetworkController::<Message>::create_active();
+        let (mut network, mut rng, node_ids) = {
+            let mut network = Network::<Reactor>::new();
+            let mut rng = TestRng::new();
+            let node_ids = network.add_nodes(&mut rng, NETWORK_SIZE).await;
+            (network, rng, node_ids)
+        };
+
+        // A small trie of two leaves below a root node, branching on the first byte of the
+        // serialized keys, i.e. their tags.
+        let leaves = vec![
+            Trie::leaf(
+                Key::Account(AccountHash::new(rng.gen())),
+                StoredValue::CLValue(CLValue::from_t(1_u64).unwrap()),
+            ),
+            Trie::leaf(
+                Key::Hash(rng.gen()),
+                StoredValue::CLValue(CLValue::from_t(2_u64).unwrap()),
+            ),
+        ];
+        let pointers: Vec<_> = leaves
+            .iter()
+            .enumerate()
+            .map(|(index, leaf)| (index as u8, Pointer::LeafPointer(leaf.id())))
+            .collect();
+        let root = Trie::node(&pointers);
+        let root_hash = root.id();
+
+        // Store the trie on the holding node, leaves first so none is reported as missing.
+        let holding_node = node_ids[0];
+        let requesting_node = node_ids[1];
+        let stored = Arc::new(Mutex::new(false));
+        let stored_clone = Arc::clone(&stored);
+        let tries: Vec<_> = leaves.iter().chain(Some(&root)).cloned().collect();
+        network
+            .process_injected_effect_on(&holding_node, move |effect_builder| {
+                async move {
+                    for trie in tries {
+                        let missing = effect_builder
+                            .put_trie_and_find_missing_descendant_trie_keys(Box::new(trie))
+                            .await
+                            .expect("should put trie");
+                        assert!(missing.is_empty());
+                    }
+                    *stored_clone.lock().unwrap() = true;
+                }
+                .ignore()
+            })
+            .await;
+        network
+            .settle_on(&mut rng, |_| *stored.lock().unwrap(), TIMEOUT)
+            .await;
+        assert!(
+            read_trie(root_hash, &requesting_node, &mut network, &mut rng)
+                .await
+                .is_none()
+        );
+
+        // Sync the trie to the requesting node.
+        let synced = Arc::new(Mutex::new(None));
+        let synced_clone = Arc::clone(&synced);
+        network
+            .process_injected_effect_on(&requesting_node, move |effect_builder| {
+                effect_builder
+                    .sync_trie_store(root_hash, holding_node)
+                    .then(move |success| async move {
+                        *synced_clone.lock().unwrap() = Some(success);
+                    })
+                    .ignore()
+            })
+            .await;
+        network
+            .settle_on(&mut rng, |_| synced.lock().unwrap().is_some(), TIMEOUT)
+            .await;
+        assert_eq!(*synced.lock().unwrap(), Some(true));
+
+        // The reconstructed trie hashes to the same root, and all its leaves are present.
+        let synced_root = read_trie(root_hash, &requesting_node, &mut network, &mut rng)
+            .await
+            .expect("should have synced root");
+        assert_eq!(synced_root.id(), root_hash);
+        assert_eq!(synced_root, root);
+        for leaf in leaves {
+            assert_eq!(
+                read_trie(leaf.id(), &requesting_node, &mut network, &mut rng).await,
+                Some(leaf)
+            );
+        }
+
+        NetworkController::<Message>::remove_active();
+    }
+}
@@ -4,16 +4,18 @@ mod event;
 mod gossip_table;
 mod message;
 mod metrics;
+mod reassembly;
 mod tests;
 
 use datasize::DataSize;
 use prometheus::Registry;
+use rand::Rng;
 use smallvec::smallvec;
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     convert::Infallible,
     fmt::{self, Debug, Formatter},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tracing::{debug, error, warn};
 
@@ -21,11 +23,11 @@ use crate::{
     components::Component,
     effect::{
         announcements::GossiperAnnouncement,
-        requests::{NetworkRequest, StorageRequest},
+        requests::{NetworkInfoRequest, NetworkRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects,
     },
     protocol::Message as NodeMessage,
-    types::{Deploy, DeployHash, Item, NodeId},
+    types::{Deploy, DeployHash, Item, NodeId, SharedObject},
     utils::Source,
     NodeRng,
 };
@@ -34,7 +36,12 @@ pub use error::Error;
 pub use event::Event;
 use gossip_table::{GossipAction, GossipTable};
 pub use message::Message;
+use message::ITEM_CHUNK_PROTOCOL_VERSION;
 use metrics::GossiperMetrics;
+use reassembly::ReassemblyBuffer;
+
+/// The divisor applied to a retry backoff to give the maximum random jitter subtracted from it.
+const RETRY_JITTER_DIVISOR: u64 = 4;
 
 /// A helper trait whose bounds represent the requirements for a reactor event that `Gossiper` can
 /// work with.
@@ -42,6 +49,7 @@ pub trait ReactorEventT<T>:
     From<Event<T>>
     + From<NetworkRequest<NodeId, Message<T>>>
     + From<NetworkRequest<NodeId, NodeMessage>>
+    + From<NetworkInfoRequest<NodeId>>
     + From<StorageRequest>
     + From<GossiperAnnouncement<T>>
     + Send
@@ -59,6 +67,7 @@ where
     REv: From<Event<T>>
         + From<NetworkRequest<NodeId, Message<T>>>
         + From<NetworkRequest<NodeId, NodeMessage>>
+        + From<NetworkInfoRequest<NodeId>>
         + From<StorageRequest>
         + From<GossiperAnnouncement<T>>
         + Send
@@ -100,9 +109,18 @@ where
     T: Item + 'static,
     REv: ReactorEventT<T>,
 {
+    /// The name of the gossiper, identifying it in logs and metrics.
+    name: String,
     table: GossipTable<T::Id>,
     gossip_timeout: Duration,
     get_from_peer_timeout: Duration,
+    retry_backoff_base: Duration,
+    max_retry_backoff: Duration,
+    /// The number of peers to gossip to for each item awaiting a scheduled retry.
+    pending_retries: HashMap<T::Id, usize>,
+    chunk_size: usize,
+    /// Chunks of items sent to us by peers which are still awaiting their remaining chunks.
+    reassembly_buffer: ReassemblyBuffer<T::Id>,
     #[data_size(skip)] // Not well supported by datasize.
     get_from_holder:
         Box<dyn Fn(EffectBuilder<REv>, T::Id, NodeId) -> Effects<Event<T>> + Send + 'static>,
@@ -136,9 +154,15 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
             "this should only be called for types where T::ID_IS_COMPLETE_ITEM is false"
         );
         Ok(Gossiper {
+            name: name.to_string(),
             table: GossipTable::new(config),
             gossip_timeout: Duration::from_secs(config.gossip_request_timeout_secs()),
             get_from_peer_timeout: Duration::from_secs(config.get_remainder_timeout_secs()),
+            retry_backoff_base: Duration::from_millis(config.retry_backoff_base_millis()),
+            max_retry_backoff: Duration::from_secs(config.max_retry_backoff_secs()),
+            pending_retries: HashMap::new(),
+            chunk_size: config.chunk_size_bytes() as usize,
+            reassembly_buffer: ReassemblyBuffer::new(),
             get_from_holder: Box::new(get_from_holder),
             metrics: GossiperMetrics::new(name, registry)?,
         })
@@ -159,9 +183,15 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
             "this should only be called for types where T::ID_IS_COMPLETE_ITEM is true"
         );
         Ok(Gossiper {
+            name: name.to_string(),
             table: GossipTable::new(config),
             gossip_timeout: Duration::from_secs(config.gossip_request_timeout_secs()),
             get_from_peer_timeout: Duration::from_secs(config.get_remainder_timeout_secs()),
+            retry_backoff_base: Duration::from_millis(config.retry_backoff_base_millis()),
+            max_retry_backoff: Duration::from_secs(config.max_retry_backoff_secs()),
+            pending_retries: HashMap::new(),
+            chunk_size: config.chunk_size_bytes() as usize,
+            reassembly_buffer: ReassemblyBuffer::new(),
             get_from_holder: Box::new(|_, item, _| {
                 panic!("gossiper should never try to get {}", item)
             }),
@@ -179,6 +209,10 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
         source: Source<NodeId>,
     ) -> Effects<Event<T>> {
         debug!(item=%item_id, %source, "received new gossip item");
+        if self.table.already_seen(&item_id) {
+            debug!(item=%item_id, %source, "not re-gossiping item already seen");
+            return Effects::new();
+        }
         match self.table.new_complete_data(&item_id, source.node_id()) {
             GossipAction::ShouldGossip(should_gossip) => {
                 self.metrics.items_received.inc();
@@ -260,16 +294,14 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
     fn check_gossip_timeout(
         &mut self,
         effect_builder: EffectBuilder<REv>,
+        rng: &mut NodeRng,
         item_id: T::Id,
         peer: NodeId,
     ) -> Effects<Event<T>> {
         match self.table.check_timeout(&item_id, peer) {
-            GossipAction::ShouldGossip(should_gossip) => self.gossip(
-                effect_builder,
-                item_id,
-                should_gossip.count,
-                should_gossip.exclude_peers,
-            ),
+            GossipAction::ShouldGossip(should_gossip) => {
+                self.schedule_retry(effect_builder, rng, item_id, should_gossip.count)
+            }
             GossipAction::Noop => Effects::new(),
             GossipAction::AnnounceFinished => {
                 effect_builder.announce_finished_gossiping(item_id).ignore()
@@ -284,6 +316,59 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
         }
     }
 
+    /// Schedules re-gossiping the given item to `count` peers after a backoff delay.
+    ///
+    /// If a retry is already pending for this item, `count` is added to it rather than a further
+    /// retry being scheduled, so that several gossip requests timing out together only count as a
+    /// single retry.
+    fn schedule_retry(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        rng: &mut NodeRng,
+        item_id: T::Id,
+        count: usize,
+    ) -> Effects<Event<T>> {
+        if let Some(pending_count) = self.pending_retries.get_mut(&item_id) {
+            *pending_count += count;
+            return Effects::new();
+        }
+        let previous_retries = match self.table.record_retry(&item_id) {
+            Some(previous_retries) => previous_retries,
+            None => return Effects::new(),
+        };
+        let _ = self.pending_retries.insert(item_id, count);
+        let backoff = retry_backoff(
+            self.retry_backoff_base,
+            self.max_retry_backoff,
+            previous_retries,
+            rng,
+        );
+        debug!(item=%item_id, ?backoff, "scheduling gossip retry");
+        effect_builder
+            .set_timeout(backoff)
+            .event(move |_| Event::RetryGossip { item_id })
+    }
+
+    /// Re-gossips the given item once its retry backoff has elapsed, unless it has since finished
+    /// being gossiped.
+    fn retry_gossip(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+    ) -> Effects<Event<T>> {
+        let count = match self.pending_retries.remove(&item_id) {
+            Some(count) => count,
+            None => return Effects::new(),
+        };
+        match self.table.holders(&item_id) {
+            Some(exclude_peers) => self.gossip(effect_builder, item_id, count, exclude_peers),
+            None => {
+                debug!(item=%item_id, "not retrying gossip for item no longer being gossiped");
+                Effects::new()
+            }
+        }
+    }
+
     /// Checks that the given peer has responded to a previous gossip response or `GetRequest` we
     /// sent it indicating we wanted to get the full item from it.
     fn check_get_from_peer_timeout(
@@ -468,13 +553,129 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
         item: T,
         requester: NodeId,
     ) -> Effects<Event<T>> {
-        match NodeMessage::new_get_response(&item) {
-            Ok(message) => effect_builder.send_message(requester, message).ignore(),
+        let serialized_item = match bincode::serialize(&item) {
+            Ok(serialized_item) => serialized_item,
             Err(error) => {
                 error!("failed to create get-response: {}", error);
-                Effects::new()
+                return Effects::new();
+            }
+        };
+
+        if serialized_item.len() <= self.chunk_size {
+            let message = NodeMessage::new_get_response_raw_unchecked::<T>(SharedObject::owned(
+                serialized_item,
+            ));
+            return effect_builder.send_message(requester, message).ignore();
+        }
+
+        // The item is too large to send in a single message: send it in chunks instead, unless the
+        // requester runs a protocol version which doesn't understand them.
+        let item_id = item.id();
+        let chunk_size = self.chunk_size;
+        async move {
+            let supports_chunks = effect_builder
+                .get_peer_protocol_version(requester)
+                .await
+                .map_or(false, |version| version >= ITEM_CHUNK_PROTOCOL_VERSION);
+            if !supports_chunks {
+                let message = NodeMessage::new_get_response_raw_unchecked::<T>(
+                    SharedObject::owned(serialized_item),
+                );
+                effect_builder.send_message(requester, message).await;
+                return;
+            }
+
+            let chunks = reassembly::split_into_chunks(&serialized_item, chunk_size);
+            let total = chunks.len() as u32;
+            debug!(item=%item_id, %requester, %total, "sending item in chunks");
+            for (data, index) in chunks.into_iter().zip(0..) {
+                let message = Message::ItemChunk {
+                    item_id,
+                    index,
+                    total,
+                    data,
+                };
+                effect_builder.send_message(requester, message).await;
             }
         }
+        .ignore()
+    }
+
+    /// Handles a chunk of an item sent by a peer, announcing the item once all of its chunks have
+    /// been received.
+    ///
+    /// Chunks are only accepted from peers we are awaiting the item from.
+    fn handle_item_chunk(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        index: u32,
+        total: u32,
+        data: Vec<u8>,
+        sender: NodeId,
+    ) -> Effects<Event<T>> {
+        if !self.table.is_awaiting_from(&item_id, &sender) {
+            debug!(item=%item_id, %sender, "received unrequested item chunk");
+            return Effects::new();
+        }
+
+        let is_first_chunk = !self.reassembly_buffer.contains(sender, item_id);
+        let serialized_item = match self.reassembly_buffer.add_chunk(
+            sender,
+            item_id,
+            index,
+            total,
+            data,
+            Instant::now(),
+        ) {
+            Ok(Some(serialized_item)) => serialized_item,
+            Ok(None) if is_first_chunk => {
+                // Set a timeout to drop the chunks if the remainder don't arrive.
+                return effect_builder
+                    .set_timeout(self.get_from_peer_timeout)
+                    .event(move |_| Event::CheckChunksTimeout { item_id, sender });
+            }
+            Ok(None) => return Effects::new(),
+            Err(error) => {
+                warn!(item=%item_id, %sender, %error, "received invalid item chunk");
+                return Effects::new();
+            }
+        };
+
+        let item: T = match bincode::deserialize(&serialized_item) {
+            Ok(item) => item,
+            Err(error) => {
+                warn!(item=%item_id, %sender, %error, "failed to decode reassembled item");
+                return Effects::new();
+            }
+        };
+        if item.id() != item_id {
+            warn!(
+                item=%item_id,
+                %sender,
+                reassembled_item=%item.id(),
+                "reassembled item has unexpected ID"
+            );
+            return Effects::new();
+        }
+
+        debug!(item=%item_id, %sender, "reassembled item from chunks");
+        effect_builder
+            .announce_item_reassembled(Box::new(item), sender)
+            .ignore()
+    }
+
+    /// Drops the chunks of the given item sent by `sender` if not all have been received in time.
+    fn check_chunks_timeout(&mut self, item_id: T::Id, sender: NodeId) -> Effects<Event<T>> {
+        if self.reassembly_buffer.remove_if_expired(
+            sender,
+            item_id,
+            Instant::now(),
+            self.get_from_peer_timeout,
+        ) {
+            debug!(item=%item_id, %sender, "dropped chunks of item not received in time");
+        }
+        Effects::new()
     }
 
     /// Handles the `Err` case for a `Result` of attempting to get the item from the component
@@ -521,10 +722,14 @@ where
     type Event = Event<T>;
     type ConstructionError = Infallible;
 
+    fn name(&self) -> &str {
+        &self.name
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
-        _rng: &mut NodeRng,
+        rng: &mut NodeRng,
         event: Self::Event,
     ) -> Effects<Self::Event> {
         let effects = match event {
@@ -537,8 +742,9 @@ where
                 peers,
             } => self.gossiped_to(effect_builder, item_id, requested_count, peers),
             Event::CheckGossipTimeout { item_id, peer } => {
-                self.check_gossip_timeout(effect_builder, item_id, peer)
+                self.check_gossip_timeout(effect_builder, rng, item_id, peer)
             }
+            Event::RetryGossip { item_id } => self.retry_gossip(effect_builder, item_id),
             Event::CheckGetFromPeerTimeout { item_id, peer } => {
                 self.check_get_from_peer_timeout(effect_builder, item_id, peer)
             }
@@ -548,7 +754,16 @@ where
                     item_id,
                     is_already_held,
                 } => self.handle_gossip_response(effect_builder, item_id, is_already_held, sender),
+                Message::ItemChunk {
+                    item_id,
+                    index,
+                    total,
+                    data,
+                } => self.handle_item_chunk(effect_builder, item_id, index, total, data, sender),
             },
+            Event::CheckChunksTimeout { item_id, sender } => {
+                self.check_chunks_timeout(item_id, sender)
+            }
             Event::GetFromHolderResult {
                 item_id,
                 requester,
@@ -563,6 +778,24 @@ where
     }
 }
 
+/// Returns the delay before retrying gossiping, given the number of retries previously made.
+///
+/// The delay starts at `base` and doubles with each retry up to `max`, less a random jitter of up
+/// to a quarter of the delay so that retries across nodes don't synchronize.
+fn retry_backoff(
+    base: Duration,
+    max: Duration,
+    previous_retries: u32,
+    rng: &mut NodeRng,
+) -> Duration {
+    let multiplier = 1_u64.checked_shl(previous_retries).unwrap_or(u64::MAX);
+    let backoff_millis = (base.as_millis() as u64)
+        .saturating_mul(multiplier)
+        .min(max.as_millis() as u64);
+    let jitter_millis = rng.gen_range(0..=backoff_millis / RETRY_JITTER_DIVISOR);
+    Duration::from_millis(backoff_millis - jitter_millis)
+}
+
 impl<T: Item + 'static, REv: ReactorEventT<T>> Debug for Gossiper<T, REv> {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         formatter
@@ -570,6 +803,8 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Debug for Gossiper<T, REv> {
             .field("table", &self.table)
             .field("gossip_timeout", &self.gossip_timeout)
             .field("get_from_peer_timeout", &self.get_from_peer_timeout)
+            .field("retry_backoff_base", &self.retry_backoff_base)
+            .field("max_retry_backoff", &self.max_retry_backoff)
             .finish()
     }
 }
@@ -14,12 +14,19 @@ pub(super) const MAX_SATURATION_LIMIT_PERCENT: u8 = 99;
 pub(super) const DEFAULT_FINISHED_ENTRY_DURATION_SECS: u64 = 60;
 const DEFAULT_GOSSIP_REQUEST_TIMEOUT_SECS: u64 = 10;
 const DEFAULT_GET_REMAINDER_TIMEOUT_SECS: u64 = 60;
+const DEFAULT_RETRY_BACKOFF_BASE_MILLIS: u64 = 1_000;
+const DEFAULT_MAX_RETRY_BACKOFF_SECS: u64 = 60;
+const DEFAULT_CHUNK_SIZE_BYTES: u32 = 8 * 1024 * 1024;
 #[cfg(test)]
 const SMALL_TIMEOUTS_FINISHED_ENTRY_DURATION_SECS: u64 = 2;
 #[cfg(test)]
 const SMALL_TIMEOUTS_GOSSIP_REQUEST_TIMEOUT_SECS: u64 = 1;
 #[cfg(test)]
 const SMALL_TIMEOUTS_GET_REMAINDER_TIMEOUT_SECS: u64 = 1;
+#[cfg(test)]
+const SMALL_TIMEOUTS_RETRY_BACKOFF_BASE_MILLIS: u64 = 100;
+#[cfg(test)]
+const SMALL_TIMEOUTS_MAX_RETRY_BACKOFF_SECS: u64 = 1;
 
 /// Configuration options for gossiping.
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
@@ -38,6 +45,8 @@ pub struct Config {
     ///
     /// The longer they are retained, the lower the likelihood of re-gossiping a piece of data.
     /// However, the longer they are retained, the larger the list of finished entries can grow.
+    ///
+    /// Data received again within this duration is not re-gossiped, regardless of its source.
     finished_entry_duration_secs: u64,
     /// The timeout duration in seconds for a single gossip request, i.e. for a single gossip
     /// message sent from this node, it will be considered timed out if the expected response from
@@ -46,6 +55,18 @@ pub struct Config {
     /// The timeout duration in seconds for retrieving the remaining part(s) of newly-discovered
     /// data from a peer which gossiped information about that data to this node.
     get_remainder_timeout_secs: u64,
+    /// The delay in milliseconds before first re-gossiping data after a gossip request timed out.
+    ///
+    /// Each subsequent retry for the same data doubles the delay, up to `max_retry_backoff_secs`.
+    #[serde(default = "default_retry_backoff_base_millis")]
+    retry_backoff_base_millis: u64,
+    /// The maximum delay in seconds before re-gossiping data after a gossip request timed out.
+    #[serde(default = "default_max_retry_backoff_secs")]
+    max_retry_backoff_secs: u64,
+    /// The maximum size in bytes of a single item sent to a peer.  Larger items are split into
+    /// chunks of this size, which the peer reassembles.
+    #[serde(default = "default_chunk_size_bytes")]
+    chunk_size_bytes: u32,
 }
 
 impl Config {
@@ -66,6 +87,9 @@ impl Config {
             finished_entry_duration_secs,
             gossip_request_timeout_secs,
             get_remainder_timeout_secs,
+            retry_backoff_base_millis: DEFAULT_RETRY_BACKOFF_BASE_MILLIS,
+            max_retry_backoff_secs: DEFAULT_MAX_RETRY_BACKOFF_SECS,
+            chunk_size_bytes: DEFAULT_CHUNK_SIZE_BYTES,
         })
     }
 
@@ -75,6 +99,8 @@ impl Config {
             finished_entry_duration_secs: SMALL_TIMEOUTS_FINISHED_ENTRY_DURATION_SECS,
             gossip_request_timeout_secs: SMALL_TIMEOUTS_GOSSIP_REQUEST_TIMEOUT_SECS,
             get_remainder_timeout_secs: SMALL_TIMEOUTS_GET_REMAINDER_TIMEOUT_SECS,
+            retry_backoff_base_millis: SMALL_TIMEOUTS_RETRY_BACKOFF_BASE_MILLIS,
+            max_retry_backoff_secs: SMALL_TIMEOUTS_MAX_RETRY_BACKOFF_SECS,
             ..Default::default()
         }
     }
@@ -98,6 +124,24 @@ impl Config {
     pub(crate) fn get_remainder_timeout_secs(&self) -> u64 {
         self.get_remainder_timeout_secs
     }
+
+    pub(crate) fn retry_backoff_base_millis(&self) -> u64 {
+        self.retry_backoff_base_millis
+    }
+
+    pub(crate) fn max_retry_backoff_secs(&self) -> u64 {
+        self.max_retry_backoff_secs
+    }
+
+    pub(crate) fn chunk_size_bytes(&self) -> u32 {
+        self.chunk_size_bytes
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_chunk_size_bytes(mut self, chunk_size_bytes: u32) -> Self {
+        self.chunk_size_bytes = chunk_size_bytes;
+        self
+    }
 }
 
 impl Default for Config {
@@ -108,10 +152,25 @@ impl Default for Config {
             finished_entry_duration_secs: DEFAULT_FINISHED_ENTRY_DURATION_SECS,
             gossip_request_timeout_secs: DEFAULT_GOSSIP_REQUEST_TIMEOUT_SECS,
             get_remainder_timeout_secs: DEFAULT_GET_REMAINDER_TIMEOUT_SECS,
+            retry_backoff_base_millis: DEFAULT_RETRY_BACKOFF_BASE_MILLIS,
+            max_retry_backoff_secs: DEFAULT_MAX_RETRY_BACKOFF_SECS,
+            chunk_size_bytes: DEFAULT_CHUNK_SIZE_BYTES,
         }
     }
 }
 
+fn default_retry_backoff_base_millis() -> u64 {
+    DEFAULT_RETRY_BACKOFF_BASE_MILLIS
+}
+
+fn default_max_retry_backoff_secs() -> u64 {
+    DEFAULT_MAX_RETRY_BACKOFF_SECS
+}
+
+fn default_chunk_size_bytes() -> u32 {
+    DEFAULT_CHUNK_SIZE_BYTES
+}
+
 /// Deserializes a `usize` but fails if it's not in the range 0..100.
 fn deserialize_saturation_limit_percent<'de, D>(deserializer: D) -> Result<u8, D::Error>
 where
@@ -145,6 +204,9 @@ mod tests {
             finished_entry_duration_secs: DEFAULT_FINISHED_ENTRY_DURATION_SECS,
             gossip_request_timeout_secs: DEFAULT_GOSSIP_REQUEST_TIMEOUT_SECS,
             get_remainder_timeout_secs: DEFAULT_GET_REMAINDER_TIMEOUT_SECS,
+            retry_backoff_base_millis: DEFAULT_RETRY_BACKOFF_BASE_MILLIS,
+            max_retry_backoff_secs: DEFAULT_MAX_RETRY_BACKOFF_SECS,
+            chunk_size_bytes: DEFAULT_CHUNK_SIZE_BYTES,
         };
 
         // Parsing should fail.
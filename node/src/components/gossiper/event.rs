@@ -28,9 +28,14 @@ pub enum Event<T: Item> {
     /// The timeout for waiting for a gossip response has elapsed and we should check the response
     /// arrived.
     CheckGossipTimeout { item_id: T::Id, peer: NodeId },
+    /// The backoff delay before retrying gossiping after gossip requests timed out has elapsed.
+    RetryGossip { item_id: T::Id },
     /// The timeout for waiting for the full item has elapsed and we should check the response
     /// arrived.
     CheckGetFromPeerTimeout { item_id: T::Id, peer: NodeId },
+    /// The timeout for receiving every chunk of an item has elapsed and any chunks still buffered
+    /// should be dropped.
+    CheckChunksTimeout { item_id: T::Id, sender: NodeId },
     /// An incoming gossip network message.
     MessageReceived { sender: NodeId, message: Message<T> },
     /// The result of the gossiper getting an item from the component responsible for holding it.
@@ -59,11 +64,17 @@ impl<T: Item> Display for Event<T> {
                 "check gossip timeout for {} with {}",
                 item_id, peer
             ),
+            Event::RetryGossip { item_id } => write!(formatter, "retry gossiping {}", item_id),
             Event::CheckGetFromPeerTimeout { item_id, peer } => write!(
                 formatter,
                 "check get from peer timeout for {} with {}",
                 item_id, peer
             ),
+            Event::CheckChunksTimeout { item_id, sender } => write!(
+                formatter,
+                "check chunks timeout for {} from {}",
+                item_id, sender
+            ),
             Event::MessageReceived { sender, message } => {
                 write!(formatter, "{} received from {}", message, sender)
             }
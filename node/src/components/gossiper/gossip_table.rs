@@ -93,6 +93,8 @@ pub(crate) struct State {
     infected_by_us: HashSet<NodeId>,
     /// The count of in-flight gossip messages sent by us for this data.
     in_flight_count: usize,
+    /// The number of times we have retried gossiping this data after gossip requests timed out.
+    retry_count: u32,
 }
 
 impl State {
@@ -175,8 +177,8 @@ impl<T> Timeouts<T> {
 pub(crate) struct GossipTable<T> {
     /// Data IDs for which gossiping is still ongoing.
     current: HashMap<T, State>,
-    /// Data IDs for which gossiping is complete.
-    finished: HashSet<T>,
+    /// Data IDs for which gossiping is complete, along with the instant at which each expires.
+    finished: HashMap<T, Instant>,
     /// Timeouts for removal of items from the `finished` cache.
     timeouts: Timeouts<T>,
     /// See `Config::infection_target`.
@@ -207,7 +209,7 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
             / (100 - usize::from(config.saturation_limit_percent()));
         GossipTable {
             current: HashMap::new(),
-            finished: HashSet::new(),
+            finished: HashMap::new(),
             timeouts: Timeouts::new(),
             infection_target: usize::from(config.infection_target()),
             holders_limit,
@@ -215,6 +217,20 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
         }
     }
 
+    /// Returns whether we have already received the complete data with the given ID within the
+    /// last `finished_entry_duration`, i.e. whether we are gossiping it or have recently finished
+    /// doing so.
+    ///
+    /// Data which has been seen should not be gossiped again, regardless of its source.
+    pub(crate) fn already_seen(&self, data_id: &T) -> bool {
+        if let Some(state) = self.current.get(data_id) {
+            return state.held_by_us;
+        }
+        self.finished
+            .get(data_id)
+            .map_or(false, |expiry| *expiry > Instant::now())
+    }
+
     /// We received knowledge about potentially new data with given ID from the given peer.  This
     /// should only be called where we don't already hold everything locally we need to be able to
     /// gossip it onwards.  If we are able to gossip the data already, call `new_data` instead.
@@ -226,7 +242,7 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
     pub(crate) fn new_partial_data(&mut self, data_id: &T, holder: NodeId) -> GossipAction {
         self.purge_finished();
 
-        if self.finished.contains(data_id) {
+        if self.finished.contains_key(data_id) {
             debug!(item=%data_id, "no further action: item already finished");
             return GossipAction::Noop;
         }
@@ -265,7 +281,7 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
     ) -> GossipAction {
         self.purge_finished();
 
-        if self.finished.contains(data_id) {
+        if self.finished.contains_key(data_id) {
             debug!(item=%data_id, "no further action: item already finished");
             return GossipAction::Noop;
         }
@@ -420,6 +436,32 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
         GossipAction::Noop
     }
 
+    /// Records a retry of gossiping the given data after gossip requests timed out.
+    ///
+    /// Returns the number of retries previously made, or `None` if the data is not currently
+    /// being gossiped.  Since the count is held in the current entry, it is reset once the data
+    /// is finished being gossiped.
+    pub(crate) fn record_retry(&mut self, data_id: &T) -> Option<u32> {
+        let state = self.current.get_mut(data_id)?;
+        let previous_retries = state.retry_count;
+        state.retry_count = state.retry_count.saturating_add(1);
+        Some(previous_retries)
+    }
+
+    /// Returns the peers known to hold the given data, or `None` if the data is not currently
+    /// being gossiped.
+    pub(crate) fn holders(&self, data_id: &T) -> Option<HashSet<NodeId>> {
+        self.current.get(data_id).map(|state| state.holders.clone())
+    }
+
+    /// Returns whether we are awaiting the full data from `peer`, i.e. we don't hold it yet and
+    /// `peer` is known to hold it.
+    pub(crate) fn is_awaiting_from(&self, data_id: &T, peer: &NodeId) -> bool {
+        self.current.get(data_id).map_or(false, |state| {
+            !state.held_by_us && state.holders.contains(peer)
+        })
+    }
+
     /// We have deemed the data not suitable for gossiping further.  The entry will be marked as
     /// `finished` and eventually be purged.
     ///
@@ -453,9 +495,11 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
         Some(action)
     }
 
+    /// Moves the given entry to `self.finished`, evicting any entries which have expired.
     fn insert_to_finished(&mut self, data_id: &T) {
+        self.purge_finished();
         let timeout = Instant::now() + self.finished_entry_duration;
-        let _ = self.finished.insert(*data_id);
+        let _ = self.finished.insert(*data_id, timeout);
         let _ = self.timeouts.push(timeout, *data_id);
     }
 
@@ -464,7 +508,15 @@ impl<T: Copy + Eq + Hash + Display> GossipTable<T> {
         let now = Instant::now();
 
         for expired_finished in self.timeouts.purge(&now) {
-            let _ = self.finished.remove(&expired_finished);
+            // The entry may have been re-inserted since this timeout was set, in which case it
+            // has a later expiry and should be retained.
+            if self
+                .finished
+                .get(&expired_finished)
+                .map_or(false, |expiry| *expiry <= now)
+            {
+                let _ = self.finished.remove(&expired_finished);
+            }
         }
     }
 }
@@ -577,6 +629,25 @@ mod tests {
         assert_eq!(GossipAction::AwaitingRemainder, action);
     }
 
+    #[test]
+    fn should_only_await_partial_data_from_holders() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let node_ids = random_node_ids(&mut rng);
+        let data_id: u64 = rng.gen();
+
+        let mut gossip_table = GossipTable::new(Config::default());
+        assert!(!gossip_table.is_awaiting_from(&data_id, &node_ids[0]));
+
+        let _ = gossip_table.new_partial_data(&data_id, node_ids[0]);
+        assert!(gossip_table.is_awaiting_from(&data_id, &node_ids[0]));
+        assert!(!gossip_table.is_awaiting_from(&data_id, &node_ids[1]));
+
+        // Once we hold the complete data, we no longer await it from anyone.
+        let _ = gossip_table.new_complete_data(&data_id, Some(node_ids[0]));
+        assert!(!gossip_table.is_awaiting_from(&data_id, &node_ids[0]));
+    }
+
     #[test]
     fn new_complete_data() {
         let _ = logging::init();
@@ -656,7 +727,7 @@ mod tests {
         for node_id in node_ids.iter().take(limit) {
             let action = gossip_table.we_infected(&data_id, *node_id);
             assert_eq!(GossipAction::Noop, action);
-            assert!(!gossip_table.finished.contains(&data_id));
+            assert!(!gossip_table.finished.contains_key(&data_id));
         }
 
         // Check recording an infection from an already-recorded infectee doesn't cause us to stop
@@ -668,12 +739,12 @@ mod tests {
             is_already_held: true,
         });
         assert_eq!(expected, action);
-        assert!(!gossip_table.finished.contains(&data_id));
+        assert!(!gossip_table.finished.contains_key(&data_id));
 
         // Check third new infection does cause us to stop gossiping.
         let action = gossip_table.we_infected(&data_id, node_ids[limit]);
         assert_eq!(GossipAction::AnnounceFinished, action);
-        assert!(gossip_table.finished.contains(&data_id));
+        assert!(gossip_table.finished.contains_key(&data_id));
     }
 
     #[test]
@@ -691,22 +762,22 @@ mod tests {
         let limit = EXPECTED_DEFAULT_HOLDERS_LIMIT - 1;
         for node_id in node_ids.iter().take(limit) {
             let _ = gossip_table.new_partial_data(&data_id1, *node_id);
-            assert!(!gossip_table.finished.contains(&data_id1));
+            assert!(!gossip_table.finished.contains_key(&data_id1));
 
             let _ = gossip_table.new_complete_data(&data_id2, Some(*node_id));
-            assert!(!gossip_table.finished.contains(&data_id2));
+            assert!(!gossip_table.finished.contains_key(&data_id2));
         }
 
         // Simulate receiving a final gossip request for each, which should cause them both to be
         // moved to the `finished` collection.
         let action =
             gossip_table.new_partial_data(&data_id1, node_ids[EXPECTED_DEFAULT_HOLDERS_LIMIT]);
-        assert!(gossip_table.finished.contains(&data_id1));
+        assert!(gossip_table.finished.contains_key(&data_id1));
         assert_eq!(GossipAction::AnnounceFinished, action);
 
         let action = gossip_table
             .new_complete_data(&data_id2, Some(node_ids[EXPECTED_DEFAULT_HOLDERS_LIMIT]));
-        assert!(gossip_table.finished.contains(&data_id2));
+        assert!(gossip_table.finished.contains_key(&data_id2));
         assert_eq!(GossipAction::AnnounceFinished, action);
     }
 
@@ -724,13 +795,13 @@ mod tests {
         let limit = EXPECTED_DEFAULT_HOLDERS_LIMIT - 1;
         for node_id in node_ids.iter().take(limit) {
             let _ = gossip_table.new_complete_data(&data_id, Some(*node_id));
-            assert!(!gossip_table.finished.contains(&data_id));
+            assert!(!gossip_table.finished.contains_key(&data_id));
         }
 
         // Simulate a gossip response timing out, which should cause the item to be moved to the
         // `finished` collection.
         let action = gossip_table.check_timeout(&data_id, node_ids[EXPECTED_DEFAULT_HOLDERS_LIMIT]);
-        assert!(gossip_table.finished.contains(&data_id));
+        assert!(gossip_table.finished.contains_key(&data_id));
         assert_eq!(GossipAction::AnnounceFinished, action);
     }
 
@@ -747,16 +818,16 @@ mod tests {
         let _ = gossip_table.new_complete_data(&data_id, None);
         let limit = EXPECTED_DEFAULT_INFECTION_TARGET - 1;
         assert!(!gossip_table.reduce_in_flight_count(&data_id, limit));
-        assert!(!gossip_table.finished.contains(&data_id));
+        assert!(!gossip_table.finished.contains_key(&data_id));
 
         // Reduce the in-flight count to 0, which should cause the item to be moved to the
         // `finished` collection.
         assert!(gossip_table.reduce_in_flight_count(&data_id, 1));
-        assert!(gossip_table.finished.contains(&data_id));
+        assert!(gossip_table.finished.contains_key(&data_id));
 
         // Check that calling this again has no effect and continues to return `false`.
         assert!(!gossip_table.reduce_in_flight_count(&data_id, 1));
-        assert!(gossip_table.finished.contains(&data_id));
+        assert!(gossip_table.finished.contains_key(&data_id));
     }
 
     #[test]
@@ -898,7 +969,7 @@ mod tests {
         let action = gossip_table.remove_holder_if_unresponsive(&data_id, node_ids[1]);
         assert_eq!(GossipAction::Noop, action);
         assert!(!gossip_table.current.contains_key(&data_id));
-        assert!(!gossip_table.finished.contains(&data_id));
+        assert!(!gossip_table.finished.contains_key(&data_id));
     }
 
     #[test]
@@ -933,7 +1004,7 @@ mod tests {
         // Add new partial data from node 0, then forcibly finish gossiping.
         let _ = gossip_table.new_partial_data(&data_id, node_ids[0]);
         assert!(gossip_table.force_finish(&data_id));
-        assert!(gossip_table.finished.contains(&data_id));
+        assert!(gossip_table.finished.contains_key(&data_id));
 
         // Ensure forcibly finishing the same data returns `false`.
         assert!(!gossip_table.force_finish(&data_id));
@@ -953,22 +1024,56 @@ mod tests {
         for node_id in &node_ids[0..EXPECTED_DEFAULT_INFECTION_TARGET] {
             let _ = gossip_table.we_infected(&data_id, *node_id);
         }
-        assert!(gossip_table.finished.contains(&data_id));
+        assert!(gossip_table.finished.contains_key(&data_id));
 
         // Time the finished data out and check it has been purged.
         Instant::advance_time(DEFAULT_FINISHED_ENTRY_DURATION_SECS * 1_000 + 1);
         gossip_table.purge_finished();
-        assert!(!gossip_table.finished.contains(&data_id));
+        assert!(!gossip_table.finished.contains_key(&data_id));
 
         // Add new complete data and forcibly finish.
         let _ = gossip_table.new_complete_data(&data_id, None);
         assert!(gossip_table.force_finish(&data_id));
-        assert!(gossip_table.finished.contains(&data_id));
+        assert!(gossip_table.finished.contains_key(&data_id));
 
         // Time the finished data out and check it has been purged.
         Instant::advance_time(DEFAULT_FINISHED_ENTRY_DURATION_SECS * 1_000 + 1);
         gossip_table.purge_finished();
-        assert!(!gossip_table.finished.contains(&data_id));
+        assert!(!gossip_table.finished.contains_key(&data_id));
+    }
+
+    #[test]
+    fn should_report_already_seen_until_expiry() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let node_ids = random_node_ids(&mut rng);
+        let data_id: u64 = rng.gen();
+        let other_data_id: u64 = rng.gen();
+
+        let mut gossip_table = GossipTable::new(Config::default());
+        assert!(!gossip_table.already_seen(&data_id));
+
+        // Partial data isn't considered seen until we hold it in full.
+        let _ = gossip_table.new_partial_data(&data_id, node_ids[0]);
+        assert!(!gossip_table.already_seen(&data_id));
+        let _ = gossip_table.new_complete_data(&data_id, Some(node_ids[0]));
+        assert!(gossip_table.already_seen(&data_id));
+
+        // It remains seen once finished.
+        for node_id in &node_ids[1..=EXPECTED_DEFAULT_INFECTION_TARGET] {
+            let _ = gossip_table.we_infected(&data_id, *node_id);
+        }
+        assert!(gossip_table.finished.contains_key(&data_id));
+        assert!(gossip_table.already_seen(&data_id));
+
+        // Once expired, it is no longer seen, and is evicted when another entry is finished.
+        Instant::advance_time(DEFAULT_FINISHED_ENTRY_DURATION_SECS * 1_000 + 1);
+        assert!(!gossip_table.already_seen(&data_id));
+        assert!(gossip_table.finished.contains_key(&data_id));
+        let _ = gossip_table.new_complete_data(&other_data_id, None);
+        assert!(gossip_table.force_finish(&other_data_id));
+        assert!(!gossip_table.finished.contains_key(&data_id));
+        assert!(gossip_table.already_seen(&other_data_id));
     }
 
     #[test]
@@ -1073,7 +1178,7 @@ mod tests {
             for node_id in &node_ids[0..EXPECTED_DEFAULT_INFECTION_TARGET] {
                 let _ = gossip_table.we_infected(deploy_id, *node_id);
             }
-            assert!(gossip_table.finished.contains(deploy_id));
+            assert!(gossip_table.finished.contains_key(deploy_id));
         }
 
         bencher.iter(|| gossip_table.purge_finished());
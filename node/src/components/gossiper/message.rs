@@ -1,9 +1,14 @@
 use std::fmt::{self, Display, Formatter};
 
+use casper_types::ProtocolVersion;
 use serde::{Deserialize, Serialize};
 
 use super::Item;
 
+/// The first protocol version whose nodes understand `Message::ItemChunk`.
+pub(crate) const ITEM_CHUNK_PROTOCOL_VERSION: ProtocolVersion =
+    ProtocolVersion::from_parts(1, 4, 0);
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(bound = "for<'a> T: Deserialize<'a>")]
 pub enum Message<T: Item> {
@@ -15,6 +20,25 @@ pub enum Message<T: Item> {
         item_id: T::Id,
         is_already_held: bool,
     },
+    /// A chunk of an item too large to be sent in a single message, sent in place of a
+    /// `GetResponse`.  The recipient reassembles the item once it holds all `total` chunks.
+    ItemChunk {
+        item_id: T::Id,
+        index: u32,
+        total: u32,
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+    },
+}
+
+impl<T: Item> Message<T> {
+    /// Returns the lowest protocol version a peer must be speaking to understand this message.
+    pub(crate) fn min_protocol_version(&self) -> ProtocolVersion {
+        match self {
+            Message::Gossip(_) | Message::GossipResponse { .. } => ProtocolVersion::V1_0_0,
+            Message::ItemChunk { .. } => ITEM_CHUNK_PROTOCOL_VERSION,
+        }
+    }
 }
 
 impl<T: Item> Display for Message<T> {
@@ -29,6 +53,12 @@ impl<T: Item> Display for Message<T> {
                 "gossip-response({}, {})",
                 item_id, is_already_held
             ),
+            Message::ItemChunk {
+                item_id,
+                index,
+                total,
+                ..
+            } => write!(formatter, "item-chunk({}, {}/{})", item_id, index, total),
         }
     }
 }
@@ -0,0 +1,311 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+use datasize::DataSize;
+use thiserror::Error;
+
+use crate::types::NodeId;
+
+/// The maximum number of chunks into which a single item may be split.
+pub(super) const MAX_CHUNKS_PER_ITEM: u32 = 1024;
+
+/// The maximum number of items for which chunks from a single peer are buffered at any one time.
+pub(super) const MAX_PARTIAL_ITEMS_PER_PEER: usize = 8;
+
+/// Error returned when a received chunk cannot be added to a `ReassemblyBuffer`.
+#[derive(Debug, Error, PartialEq)]
+pub(super) enum ChunkError {
+    /// The chunk's index is not less than the total number of chunks.
+    #[error("chunk index {index} out of range for {total} chunks")]
+    IndexOutOfRange { index: u32, total: u32 },
+
+    /// The total number of chunks exceeds `MAX_CHUNKS_PER_ITEM`.
+    #[error("chunk total {0} exceeds maximum of {}", MAX_CHUNKS_PER_ITEM)]
+    TooManyChunks(u32),
+
+    /// The chunk's total number of chunks differs from that of previously-received chunks.
+    #[error("chunk total {got} differs from previously received total {expected}")]
+    TotalMismatch { expected: u32, got: u32 },
+
+    /// The sender already has `MAX_PARTIAL_ITEMS_PER_PEER` incomplete items buffered.
+    #[error(
+        "already buffering chunks of {} items from the sender",
+        MAX_PARTIAL_ITEMS_PER_PEER
+    )]
+    TooManyPartialItems,
+}
+
+/// Splits the given serialized item into chunks of at most `chunk_size` bytes.
+pub(super) fn split_into_chunks(serialized_item: &[u8], chunk_size: usize) -> Vec<Vec<u8>> {
+    serialized_item
+        .chunks(chunk_size.max(1))
+        .map(<[u8]>::to_vec)
+        .collect()
+}
+
+/// The chunks received so far of a single item.
+#[derive(DataSize, Debug)]
+struct PartialItem {
+    /// The chunks, indexed by their position in the serialized item.
+    chunks: Vec<Option<Vec<u8>>>,
+    /// The number of entries in `chunks` which have been received.
+    received_count: usize,
+    /// The time the first chunk was received.
+    #[data_size(skip)]
+    started: Instant,
+}
+
+/// Buffers chunks of items too large to be sent in a single message until every chunk of a given
+/// item has been received.
+///
+/// Chunks are buffered separately per sending peer, so one peer cannot interfere with the
+/// reassembly of an item sent by another.
+#[derive(DataSize, Debug)]
+pub(super) struct ReassemblyBuffer<T> {
+    partial_items: HashMap<(NodeId, T), PartialItem>,
+}
+
+impl<T: Copy + Eq + Hash> ReassemblyBuffer<T> {
+    /// Returns a new, empty `ReassemblyBuffer`.
+    pub(super) fn new() -> Self {
+        ReassemblyBuffer {
+            partial_items: HashMap::new(),
+        }
+    }
+
+    /// Adds a chunk of the given item sent by `sender`.
+    ///
+    /// Returns the complete serialized item if this was its final outstanding chunk, in which case
+    /// the item is removed from the buffer.
+    pub(super) fn add_chunk(
+        &mut self,
+        sender: NodeId,
+        item_id: T,
+        index: u32,
+        total: u32,
+        data: Vec<u8>,
+        now: Instant,
+    ) -> Result<Option<Vec<u8>>, ChunkError> {
+        if index >= total {
+            return Err(ChunkError::IndexOutOfRange { index, total });
+        }
+        if total > MAX_CHUNKS_PER_ITEM {
+            return Err(ChunkError::TooManyChunks(total));
+        }
+
+        let key = (sender, item_id);
+        if !self.partial_items.contains_key(&key)
+            && self.partial_items_from(&sender) >= MAX_PARTIAL_ITEMS_PER_PEER
+        {
+            return Err(ChunkError::TooManyPartialItems);
+        }
+
+        let partial_item = self
+            .partial_items
+            .entry(key)
+            .or_insert_with(|| PartialItem {
+                chunks: vec![None; total as usize],
+                received_count: 0,
+                started: now,
+            });
+        if partial_item.chunks.len() != total as usize {
+            return Err(ChunkError::TotalMismatch {
+                expected: partial_item.chunks.len() as u32,
+                got: total,
+            });
+        }
+
+        let slot = &mut partial_item.chunks[index as usize];
+        if slot.is_none() {
+            partial_item.received_count += 1;
+        }
+        *slot = Some(data);
+
+        if partial_item.received_count < partial_item.chunks.len() {
+            return Ok(None);
+        }
+
+        let chunks = match self.partial_items.remove(&key) {
+            Some(partial_item) => partial_item.chunks,
+            None => return Ok(None),
+        };
+        Ok(Some(chunks.into_iter().flatten().flatten().collect()))
+    }
+
+    /// Returns whether chunks of the given item sent by `sender` are being buffered.
+    pub(super) fn contains(&self, sender: NodeId, item_id: T) -> bool {
+        self.partial_items.contains_key(&(sender, item_id))
+    }
+
+    /// Drops the chunks of the given item sent by `sender` if its first chunk was received at least
+    /// `max_age` before `now`.
+    ///
+    /// Returns `true` if the item was incomplete and has now been dropped.
+    pub(super) fn remove_if_expired(
+        &mut self,
+        sender: NodeId,
+        item_id: T,
+        now: Instant,
+        max_age: Duration,
+    ) -> bool {
+        let key = (sender, item_id);
+        match self.partial_items.get(&key) {
+            Some(partial_item)
+                if now.saturating_duration_since(partial_item.started) >= max_age =>
+            {
+                self.partial_items.remove(&key);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the number of items for which chunks sent by `sender` are being buffered.
+    fn partial_items_from(&self, sender: &NodeId) -> usize {
+        self.partial_items
+            .keys()
+            .filter(|(peer, _)| peer == sender)
+            .count()
+    }
+
+    /// Number of items for which chunks are buffered.
+    #[cfg(test)]
+    pub(super) fn len(&self) -> usize {
+        self.partial_items.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::seq::SliceRandom;
+
+    use super::*;
+    use crate::{testing::TestRng, types::Deploy};
+
+    #[test]
+    fn should_reassemble_multi_chunk_item() {
+        const CHUNK_SIZE: usize = 100;
+
+        let mut rng = crate::new_rng();
+        let deploy = Deploy::random(&mut rng);
+        let serialized_deploy = bincode::serialize(&deploy).unwrap();
+
+        let chunks = split_into_chunks(&serialized_deploy, CHUNK_SIZE);
+        assert!(chunks.len() > 2);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= CHUNK_SIZE));
+        let total = chunks.len() as u32;
+
+        // Add the chunks in random order, with a duplicate of the first one.
+        let mut indexed_chunks: Vec<_> = (0..total).zip(chunks).collect();
+        indexed_chunks.shuffle(&mut rng);
+        let duplicate = indexed_chunks[0].clone();
+        indexed_chunks.insert(1, duplicate);
+
+        let mut buffer = ReassemblyBuffer::new();
+        let sender = NodeId::random(&mut rng);
+        let item_id = *deploy.id();
+        let now = Instant::now();
+        let (last_index, last_chunk) = indexed_chunks.pop().unwrap();
+        for (index, chunk) in indexed_chunks {
+            assert_eq!(
+                buffer.add_chunk(sender, item_id, index, total, chunk, now),
+                Ok(None)
+            );
+            assert_eq!(buffer.len(), 1);
+        }
+
+        // A chunk of the same item from a different peer is buffered separately.
+        let other_sender = NodeId::random(&mut rng);
+        assert_eq!(
+            buffer.add_chunk(other_sender, item_id, last_index, total, vec![0], now),
+            Ok(None)
+        );
+        assert_eq!(buffer.len(), 2);
+
+        // The final chunk should complete the item and remove it from the buffer.
+        let reassembled = buffer
+            .add_chunk(sender, item_id, last_index, total, last_chunk, now)
+            .unwrap()
+            .expect("should be complete");
+        assert_eq!(reassembled, serialized_deploy);
+        assert_eq!(
+            bincode::deserialize::<Deploy>(&reassembled).unwrap(),
+            deploy
+        );
+        assert_eq!(buffer.len(), 1);
+        assert!(!buffer.contains(sender, item_id));
+        assert!(buffer.contains(other_sender, item_id));
+    }
+
+    #[test]
+    fn should_reject_invalid_chunks_and_drop_expired_items() {
+        let mut rng = TestRng::new();
+        let mut buffer = ReassemblyBuffer::new();
+        let sender = NodeId::random(&mut rng);
+        let item_id = 1_u64;
+        let now = Instant::now();
+        let max_age = Duration::from_secs(10);
+
+        assert_eq!(
+            buffer.add_chunk(sender, item_id, 3, 3, vec![0], now),
+            Err(ChunkError::IndexOutOfRange { index: 3, total: 3 })
+        );
+        assert_eq!(
+            buffer.add_chunk(sender, item_id, 0, MAX_CHUNKS_PER_ITEM + 1, vec![0], now),
+            Err(ChunkError::TooManyChunks(MAX_CHUNKS_PER_ITEM + 1))
+        );
+        assert_eq!(
+            buffer.add_chunk(sender, item_id, 0, 3, vec![0], now),
+            Ok(None)
+        );
+        assert_eq!(
+            buffer.add_chunk(sender, item_id, 1, 4, vec![1], now),
+            Err(ChunkError::TotalMismatch {
+                expected: 3,
+                got: 4
+            })
+        );
+
+        // The item is only dropped once it has expired.
+        assert!(!buffer.remove_if_expired(sender, item_id, now + max_age / 2, max_age));
+        assert_eq!(buffer.len(), 1);
+        assert!(buffer.remove_if_expired(sender, item_id, now + max_age, max_age));
+        assert_eq!(buffer.len(), 0);
+        assert!(!buffer.remove_if_expired(sender, item_id, now + max_age, max_age));
+    }
+
+    #[test]
+    fn should_limit_partial_items_per_peer() {
+        let mut rng = TestRng::new();
+        let mut buffer = ReassemblyBuffer::new();
+        let sender = NodeId::random(&mut rng);
+        let now = Instant::now();
+
+        for item_id in 0..MAX_PARTIAL_ITEMS_PER_PEER as u64 {
+            assert_eq!(
+                buffer.add_chunk(sender, item_id, 0, 2, vec![0], now),
+                Ok(None)
+            );
+        }
+        let item_id = MAX_PARTIAL_ITEMS_PER_PEER as u64;
+        assert_eq!(
+            buffer.add_chunk(sender, item_id, 0, 2, vec![0], now),
+            Err(ChunkError::TooManyPartialItems)
+        );
+
+        // Further chunks of items already being buffered are still accepted, as are chunks from
+        // other peers.
+        assert_eq!(
+            buffer.add_chunk(sender, 0, 1, 2, vec![1], now),
+            Ok(Some(vec![0, 1]))
+        );
+        let other_sender = NodeId::random(&mut rng);
+        assert_eq!(
+            buffer.add_chunk(other_sender, item_id, 0, 2, vec![0], now),
+            Ok(None)
+        );
+    }
+}
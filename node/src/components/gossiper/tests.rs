@@ -3,6 +3,7 @@ use std::{
     collections::{BTreeSet, HashMap},
     fmt::{self, Debug, Display, Formatter},
     iter,
+    sync::Arc,
 };
 
 use derive_more::From;
@@ -13,8 +14,9 @@ use serde::Serialize;
 use tempfile::TempDir;
 use thiserror::Error;
 use tokio::time;
-use tracing::debug;
+use tracing::{debug, Level};
 
+use casper_execution_engine::core::engine_state::WASMLESS_TRANSFER_FIXED_GAS_PRICE;
 use casper_types::ProtocolVersion;
 
 use super::*;
@@ -23,22 +25,26 @@ use crate::{
         contract_runtime::{self, ContractRuntime},
         deploy_acceptor::{self, DeployAcceptor},
         in_memory_network::{self, InMemoryNetwork, NetworkController},
+        small_network::PeerSelection,
         storage::{self, Storage},
     },
     crypto::hash::Digest,
     effect::{
         announcements::{
-            ContractRuntimeAnnouncement, ControlAnnouncement, DeployAcceptorAnnouncement,
-            GossiperAnnouncement, NetworkAnnouncement, RpcServerAnnouncement,
+            BlocklistAnnouncement, ContractRuntimeAnnouncement, ControlAnnouncement,
+            DeployAcceptorAnnouncement, GossiperAnnouncement, NetworkAnnouncement,
+            RpcServerAnnouncement, StorageAnnouncement,
+        },
+        requests::{
+            BlockProposerRequest, ConsensusRequest, ContractRuntimeRequest, LinearChainRequest,
         },
-        requests::{ConsensusRequest, ContractRuntimeRequest, LinearChainRequest},
         Responder,
     },
     protocol::Message as NodeMessage,
-    reactor::{self, EventQueueHandle, Runner},
+    reactor::{self, CorrelationId, EventQueueHandle, Runner},
     testing::{
         network::{Network, NetworkedReactor},
-        ConditionCheckReactor, TestRng,
+        ConditionCheckReactor, LogBuffer, TestRng,
     },
     types::{Chainspec, Deploy, NodeId, Tag},
     utils::{Loadable, WithDir},
@@ -60,6 +66,8 @@ enum Event {
     #[from]
     NetworkRequest(NetworkRequest<NodeId, NodeMessage>),
     #[from]
+    NetworkInfoRequest(NetworkInfoRequest<NodeId>),
+    #[from]
     ControlAnnouncement(ControlAnnouncement),
     #[from]
     NetworkAnnouncement(#[serde(skip_serializing)] NetworkAnnouncement<NodeId, NodeMessage>),
@@ -71,6 +79,8 @@ enum Event {
     DeployGossiperAnnouncement(#[serde(skip_serializing)] GossiperAnnouncement<Deploy>),
     #[from]
     ContractRuntime(#[serde(skip_serializing)] contract_runtime::Event),
+    #[from]
+    BlockProposerRequest(#[serde(skip_serializing)] BlockProposerRequest),
 }
 
 impl ReactorEvent for Event {
@@ -119,6 +129,18 @@ impl From<ContractRuntimeAnnouncement> for Event {
     }
 }
 
+impl From<BlocklistAnnouncement<NodeId>> for Event {
+    fn from(_announcement: BlocklistAnnouncement<NodeId>) -> Self {
+        unimplemented!("not implemented for gossiper tests")
+    }
+}
+
+impl From<StorageAnnouncement> for Event {
+    fn from(_announcement: StorageAnnouncement) -> Self {
+        unimplemented!("not implemented for gossiper tests")
+    }
+}
+
 impl Display for Event {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -127,6 +149,7 @@ impl Display for Event {
             Event::DeployAcceptor(event) => write!(formatter, "deploy acceptor: {}", event),
             Event::DeployGossiper(event) => write!(formatter, "deploy gossiper: {}", event),
             Event::NetworkRequest(req) => write!(formatter, "network request: {}", req),
+            Event::NetworkInfoRequest(req) => write!(formatter, "network info request: {}", req),
             Event::ControlAnnouncement(ctrl_ann) => write!(formatter, "control: {}", ctrl_ann),
             Event::NetworkAnnouncement(ann) => write!(formatter, "network announcement: {}", ann),
             Event::RpcServerAnnouncement(ann) => {
@@ -141,6 +164,9 @@ impl Display for Event {
             Event::ContractRuntime(event) => {
                 write!(formatter, "contract-runtime event: {:?}", event)
             }
+            Event::BlockProposerRequest(req) => {
+                write!(formatter, "block proposer request: {}", req)
+            }
         }
     }
 }
@@ -178,7 +204,9 @@ impl reactor::Reactor for Reactor {
         event_queue: EventQueueHandle<Self::Event>,
         rng: &mut NodeRng,
     ) -> Result<(Self, Effects<Self::Event>), Self::Error> {
-        let network = NetworkController::create_node(event_queue, rng);
+        // Gossip to peers in a fixed order so that test runs are reproducible.
+        let mut network = NetworkController::create_node(event_queue, rng);
+        network.set_peer_selection(PeerSelection::RoundRobin);
 
         let (storage_config, storage_tempdir) = storage::Config::default_for_tests();
         let storage_withdir = WithDir::new(storage_tempdir.path(), storage_config);
@@ -187,6 +215,7 @@ impl reactor::Reactor for Reactor {
             None,
             ProtocolVersion::from_parts(1, 0, 0),
             false,
+            registry,
         )
         .unwrap();
 
@@ -195,6 +224,7 @@ impl reactor::Reactor for Reactor {
             Digest::random(rng),
             None,
             ProtocolVersion::from_parts(1, 0, 0),
+            WASMLESS_TRANSFER_FIXED_GAS_PRICE,
             storage_withdir,
             &contract_runtime_config,
             registry,
@@ -203,7 +233,8 @@ impl reactor::Reactor for Reactor {
 
         let deploy_acceptor = DeployAcceptor::new(
             deploy_acceptor::Config::new(false),
-            &Chainspec::from_resources("local"),
+            Arc::new(Chainspec::from_resources("local")),
+            0,
         );
         let deploy_gossiper = Gossiper::new_for_partial_items(
             "deploy_gossiper",
@@ -252,6 +283,18 @@ impl reactor::Reactor for Reactor {
                 self.network
                     .handle_event(effect_builder, rng, request.into()),
             ),
+            Event::NetworkInfoRequest(NetworkInfoRequest::GetPeerProtocolVersion {
+                responder,
+                ..
+            }) => {
+                // All nodes on the in-memory network run a protocol version supporting chunks.
+                responder
+                    .respond(Some(ITEM_CHUNK_PROTOCOL_VERSION))
+                    .ignore()
+            }
+            Event::NetworkInfoRequest(request) => {
+                unimplemented!("not implemented for gossiper tests: {}", request)
+            }
             Event::ControlAnnouncement(ctrl_ann) => {
                 unreachable!("unhandled control announcement: {}", ctrl_ann)
             }
@@ -351,6 +394,17 @@ impl reactor::Reactor for Reactor {
                 deploy: _,
                 source: _,
             }) => Effects::new(),
+            Event::DeployGossiperAnnouncement(GossiperAnnouncement::ItemReassembled {
+                item,
+                sender,
+            }) => {
+                let event = deploy_acceptor::Event::Accept {
+                    deploy: item,
+                    source: Source::Peer(sender),
+                    responder: None,
+                };
+                self.dispatch_event(effect_builder, rng, Event::DeployAcceptor(event))
+            }
             Event::DeployGossiperAnnouncement(_ann) => {
                 // We do not care about deploy gossiper announcements in the gossiper test.
                 Effects::new()
@@ -364,6 +418,13 @@ impl reactor::Reactor for Reactor {
                 self.contract_runtime
                     .handle_event(effect_builder, rng, event),
             ),
+            // There is no block proposer, so the deploy buffer is never full.
+            Event::BlockProposerRequest(BlockProposerRequest::IsBufferFull { responder }) => {
+                responder.respond(false).ignore()
+            }
+            Event::BlockProposerRequest(request) => {
+                panic!("unexpected block proposer request {}", request)
+            }
         }
     }
 
@@ -596,3 +657,203 @@ async fn should_timeout_gossip_response() {
 
     NetworkController::<NodeMessage>::remove_active();
 }
+
+#[tokio::test]
+async fn should_gossip_duplicate_item_once() {
+    const NETWORK_SIZE: usize = 2;
+    const QUIET_FOR: Duration = Duration::from_millis(50);
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    NetworkController::<NodeMessage>::create_active();
+    let mut network = Network::<Reactor>::new();
+    let mut rng = crate::new_rng();
+
+    // Add `NETWORK_SIZE` nodes.
+    let node_ids = network.add_nodes(&mut rng, NETWORK_SIZE).await;
+
+    // Create random deploy.
+    let deploy = Box::new(Deploy::random(&mut rng));
+
+    // Give the deploy to node 0 and run it until it has gossiped the deploy.
+    network
+        .process_injected_effect_on(&node_ids[0], announce_deploy_received(deploy.clone(), None))
+        .await;
+    let gossiped = |event: &Event| -> bool {
+        matches!(
+            event,
+            Event::DeployGossiper(super::Event::GossipedTo { .. })
+        )
+    };
+    network
+        .crank_until(&node_ids[0], &mut rng, gossiped, TIMEOUT)
+        .await;
+
+    // Have node 0 accept the same deploy again, this time as though received from node 1.
+    let source = Source::Peer(node_ids[1]);
+    network
+        .process_injected_effect_on(&node_ids[0], move |effect_builder: EffectBuilder<Event>| {
+            effect_builder
+                .announce_new_deploy_accepted(deploy, source)
+                .ignore()
+        })
+        .await;
+    network.settle(&mut rng, QUIET_FOR, TIMEOUT).await;
+
+    // Node 0 should only have started gossiping the deploy once.
+    let metrics = &network
+        .nodes()
+        .get(&node_ids[0])
+        .unwrap()
+        .reactor()
+        .inner()
+        .deploy_gossiper
+        .metrics;
+    assert_eq!(metrics.items_received.get(), 1);
+    assert_eq!(metrics.times_gossiped.get(), 1);
+
+    NetworkController::<NodeMessage>::remove_active();
+}
+
+#[test]
+fn retry_backoff_should_grow_geometrically() {
+    const RETRIES: u32 = 8;
+
+    let mut rng = crate::new_rng();
+    let config = Config::default();
+    let base = Duration::from_millis(config.retry_backoff_base_millis());
+    let max = Duration::from_secs(config.max_retry_backoff_secs());
+    let mut table = GossipTable::new(config);
+    let deploy_id = *Deploy::random(&mut rng).id();
+    let _ = table.new_complete_data(&deploy_id, None);
+
+    // Record the timestamp of each retry, relative to the first gossip.
+    let mut now = Duration::default();
+    let mut retry_timestamps = vec![now];
+    for _ in 0..RETRIES {
+        let previous_retries = table.record_retry(&deploy_id).unwrap();
+        now += retry_backoff(base, max, previous_retries, &mut rng);
+        retry_timestamps.push(now);
+    }
+
+    // Each interval should be double the previous one (less jitter) until capped at `max`.
+    let intervals: Vec<_> = retry_timestamps
+        .windows(2)
+        .map(|timestamps| timestamps[1] - timestamps[0])
+        .collect();
+    for (retry, interval) in intervals.iter().enumerate() {
+        let expected = (base * 2_u32.pow(retry as u32)).min(max);
+        assert!(*interval <= expected, "{:?} > {:?}", interval, expected);
+        assert!(
+            *interval >= expected * 3 / 4,
+            "{:?} < {:?}",
+            interval,
+            expected
+        );
+    }
+    for pair in intervals.windows(2) {
+        if pair[0] * 2 <= max {
+            assert!(pair[1] >= pair[0] * 3 / 2, "{:?} didn't grow", pair);
+        }
+    }
+    assert!(*intervals.last().unwrap() >= max * 3 / 4);
+
+    // Once the deploy reaches saturation, the backoff state is reset.
+    let infection_target = usize::from(config.infection_target());
+    for _ in 0..infection_target {
+        let _ = table.we_infected(&deploy_id, NodeId::random(&mut rng));
+    }
+    assert!(table.record_retry(&deploy_id).is_none());
+}
+
+#[tokio::test]
+async fn should_gossip_large_item_in_chunks() {
+    const NETWORK_SIZE: usize = 2;
+    const CHUNK_SIZE_BYTES: u32 = 100;
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    NetworkController::<NodeMessage>::create_active();
+    let mut network = Network::<Reactor>::new();
+    let mut rng = crate::new_rng();
+
+    // Add `NETWORK_SIZE` nodes which send items larger than `CHUNK_SIZE_BYTES` in chunks.
+    let config = Config::default().with_chunk_size_bytes(CHUNK_SIZE_BYTES);
+    let mut node_ids = vec![];
+    for _ in 0..NETWORK_SIZE {
+        let (node_id, _runner) = network
+            .add_node_with_config(config, &mut rng)
+            .await
+            .unwrap();
+        node_ids.push(node_id);
+    }
+
+    // Create random deploy, large enough to be split into several chunks.
+    let deploy = Box::new(Deploy::random(&mut rng));
+    let deploy_id = *deploy.id();
+    assert!(bincode::serialized_size(&*deploy).unwrap() > 2 * u64::from(CHUNK_SIZE_BYTES));
+
+    // Give the deploy to node 0 to be gossiped.
+    network
+        .process_injected_effect_on(&node_ids[0], announce_deploy_received(deploy.clone(), None))
+        .await;
+
+    // Check node 1 has reassembled the deploy and stored it locally.
+    let deploy_held = |nodes: &HashMap<NodeId, Runner<ConditionCheckReactor<Reactor>>>| {
+        let runner = nodes.get(&node_ids[1]).unwrap();
+        runner
+            .reactor()
+            .inner()
+            .storage
+            .get_deploy_by_hash(deploy_id)
+            .map(|retrieved_deploy| retrieved_deploy == *deploy)
+            .unwrap_or_default()
+    };
+    network.settle_on(&mut rng, deploy_held, TIMEOUT).await;
+
+    NetworkController::<NodeMessage>::remove_active();
+}
+
+#[tokio::test]
+async fn should_log_correlation_id_of_received_deploy() {
+    const QUIET_FOR: Duration = Duration::from_millis(50);
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    // Capture the logs of this test, which runs all nodes on the current thread.
+    let log_buffer = LogBuffer::default();
+    let writer = log_buffer.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(Level::DEBUG)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    NetworkController::<NodeMessage>::create_active();
+    let mut network = Network::<Reactor>::new();
+    let mut rng = crate::new_rng();
+    let node_ids = network.add_nodes(&mut rng, 1).await;
+
+    // Give the deploy to the node as if it had been submitted by a client.
+    let correlation_id = CorrelationId::random(&mut rng);
+    let deploy = Box::new(Deploy::random(&mut rng));
+    network
+        .process_injected_effect_on(&node_ids[0], |effect_builder| {
+            correlation_id.correlate(announce_deploy_received(deploy, None)(effect_builder))
+        })
+        .await;
+    network.settle(&mut rng, QUIET_FOR, TIMEOUT).await;
+
+    NetworkController::<NodeMessage>::remove_active();
+
+    // Both the deploy acceptor and the gossiper handled the deploy within its correlation ID.
+    let logs = log_buffer.contents();
+    let correlated = format!("correlation_id={}", correlation_id);
+    for message in &["handling event", "received new gossip item"] {
+        assert!(
+            logs.lines()
+                .any(|line| line.contains(message) && line.contains(&correlated)),
+            "no '{}' log line with {}",
+            message,
+            correlated
+        );
+    }
+}
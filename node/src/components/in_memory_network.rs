@@ -104,6 +104,10 @@
 //! {
 //!     type Event = ShouterEvent<NodeId, Message>;
 //!
+//!     fn name(&self) -> &str {
+//!         "shouter"
+//!     }
+//!
 //!     fn handle_event(&mut self,
 //!         effect_builder: EffectBuilder<REv>,
 //!         _rng: &mut NodeRng,
@@ -282,19 +286,21 @@
 use std::{
     any::Any,
     cell::RefCell,
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     convert::Infallible,
     fmt::{self, Display, Formatter},
     sync::{Arc, RwLock},
 };
 
-use rand::seq::IteratorRandom;
 use serde::Serialize;
 use tokio::sync::mpsc::{self, error::SendError};
 use tracing::{debug, error, info, warn};
 
 use crate::{
-    components::Component,
+    components::{
+        small_network::{PeerSelection, PeerSelectionStrategy},
+        Component,
+    },
     effect::{
         announcements::NetworkAnnouncement, requests::NetworkRequest, EffectBuilder, EffectExt,
         Effects,
@@ -450,6 +456,9 @@ pub struct InMemoryNetwork<P> {
 
     /// The nodes map, contains the incoming channel for each virtual node.
     nodes: Network<P>,
+
+    /// The strategy used to select the nodes a message is gossiped to.
+    peer_selection: Box<dyn PeerSelectionStrategy>,
 }
 
 impl<P> InMemoryNetwork<P>
@@ -486,7 +495,18 @@ where
 
         tokio::spawn(receiver_task(event_queue, receiver));
 
-        InMemoryNetwork { node_id, nodes }
+        InMemoryNetwork {
+            node_id,
+            nodes,
+            peer_selection: PeerSelection::default().strategy(),
+        }
+    }
+
+    /// Sets the strategy used to select the nodes a message is gossiped to.
+    ///
+    /// Tests which need to be reproducible should use `PeerSelection::RoundRobin`.
+    pub(crate) fn set_peer_selection(&mut self, peer_selection: PeerSelection) {
+        self.peer_selection = peer_selection.strategy();
     }
 
     /// Returns this node's ID.
@@ -494,6 +514,21 @@ where
     pub fn node_id(&self) -> NodeId {
         self.node_id
     }
+
+    /// Returns the IDs of all other nodes on the network, mapped to their display form.
+    pub fn peers(&self) -> BTreeMap<NodeId, String> {
+        match self.nodes.read() {
+            Ok(guard) => guard
+                .keys()
+                .filter(|&node_id| node_id != &self.node_id)
+                .map(|node_id| (*node_id, node_id.to_string()))
+                .collect(),
+            Err(_) => {
+                error!("network lock has been poisoned");
+                BTreeMap::new()
+            }
+        }
+    }
 }
 
 impl<P> InMemoryNetwork<P>
@@ -531,6 +566,10 @@ where
     type Event = Event<P>;
     type ConstructionError = Infallible;
 
+    fn name(&self) -> &str {
+        "in_memory_network"
+    }
+
     fn handle_event(
         &mut self,
         _effect_builder: EffectBuilder<REv>,
@@ -573,11 +612,14 @@ where
                 responder,
             } => {
                 if let Ok(guard) = self.nodes.read() {
-                    let chosen: HashSet<_> = guard
+                    let candidates = guard
                         .keys()
                         .filter(|&node_id| !exclude.contains(node_id) && node_id != &self.node_id)
                         .cloned()
-                        .choose_multiple(rng, count)
+                        .collect();
+                    let chosen: HashSet<_> = self
+                        .peer_selection
+                        .select(rng, candidates, count)
                         .into_iter()
                         .collect();
                     // Not terribly efficient, but will always get us the maximum amount of nodes.
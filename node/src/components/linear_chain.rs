@@ -134,6 +134,10 @@ where
     type Event = Event<I>;
     type ConstructionError = Infallible;
 
+    fn name(&self) -> &str {
+        "linear_chain"
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -319,6 +319,10 @@ where
     type Event = Event<I>;
     type ConstructionError = Infallible;
 
+    fn name(&self) -> &str {
+        "linear_chain_fast_sync"
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
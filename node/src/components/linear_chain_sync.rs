@@ -4,7 +4,8 @@
 //!
 //! Steps are:
 //! 1. Fetch blocks up to initial, trusted hash (blocks are downloaded starting from trusted hash up
-//! until Genesis).
+//! until Genesis, or until a block already held in storage, in which case any part of its global
+//! state missing locally is downloaded from peers).
 //! 2. Fetch deploys of the lowest height block.
 //! 3. Execute that block.
 //! 4. Repeat steps 2-3 until trusted hash is reached.
@@ -36,7 +37,7 @@ use datasize::DataSize;
 use prometheus::Registry;
 use tracing::{error, info, trace, warn};
 
-use self::event::{BlockByHashResult, DeploysResult};
+use self::event::{BlockByHashResult, DeploysResult, TrieStoreResult};
 use casper_types::{EraId, ProtocolVersion};
 
 use super::{
@@ -551,6 +552,10 @@ where
     type Event = Event<I>;
     type ConstructionError = Infallible;
 
+    fn name(&self) -> &str {
+        "linear_chain_sync"
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -714,10 +719,11 @@ where
                         // We hit a block that we already had in the storage - which should mean
                         // that we also have all of its ancestors, so we switch to traversing the
                         // chain forwards and downloading the deploys.
-                        // We don't want to download and execute a block we already have, so
-                        // instead of calling self.block_downloaded(), we take a shortcut:
-                        self.set_last_block_if_syncing_trusted_hash(&block);
-                        self.block_handled(rng, effect_builder, *block)
+                        // We don't want to download and execute a block we already have, but its
+                        // descendants can only be executed on top of its global state, so we first
+                        // download whatever part of that is missing locally.
+                        let peer = self.peers.random_unsafe();
+                        sync_trie_store(effect_builder, peer, block)
                     }
                     BlockByHashResult::FromPeer(block, peer) => {
                         self.metrics.observe_get_block_by_hash();
@@ -784,6 +790,35 @@ where
                     }
                 }
             }
+            Event::SyncTrieStoreResult(sync_result) => match sync_result {
+                TrieStoreResult::Synced(block) => {
+                    trace!(
+                        block_hash = %block.hash(),
+                        "global state of linear chain block synchronized"
+                    );
+                    // Instead of calling self.block_downloaded(), we take a shortcut:
+                    self.set_last_block_if_syncing_trusted_hash(&block);
+                    self.block_handled(rng, effect_builder, *block)
+                }
+                TrieStoreResult::Failed(block, peer) => {
+                    let block_hash = block.hash();
+                    trace!(
+                        %block_hash, %peer,
+                        "failed to synchronize global state of linear chain block. Trying next peer"
+                    );
+                    self.peers.failure(&peer);
+                    match self.peers.random() {
+                        None => {
+                            error!(
+                                %block_hash,
+                                "could not synchronize global state from any of the peers."
+                            );
+                            fatal!(effect_builder, "failed to synchronize global state").ignore()
+                        }
+                        Some(peer) => sync_trie_store(effect_builder, peer, block),
+                    }
+                }
+            },
             Event::StartDownloadingDeploys => {
                 // Start downloading deploys from the first block of the linear chain.
                 self.peers.reset(rng);
@@ -858,6 +893,28 @@ where
         })
 }
 
+/// Downloads the parts of the global state under `block`'s state root hash which are missing from
+/// the local trie store.
+fn sync_trie_store<I: Clone + Send + 'static, REv>(
+    effect_builder: EffectBuilder<REv>,
+    peer: I,
+    block: Box<Block>,
+) -> Effects<Event<I>>
+where
+    REv: ReactorEventT<I>,
+{
+    let state_root_hash = (*block.state_root_hash()).into();
+    effect_builder
+        .sync_trie_store(state_root_hash, peer.clone())
+        .event(move |synced| {
+            if synced {
+                Event::SyncTrieStoreResult(TrieStoreResult::Synced(block))
+            } else {
+                Event::SyncTrieStoreResult(TrieStoreResult::Failed(block, peer))
+            }
+        })
+}
+
 fn fetch_block_by_hash<I: Clone + Send + 'static, REv>(
     effect_builder: EffectBuilder<REv>,
     peer: I,
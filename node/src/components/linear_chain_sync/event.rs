@@ -8,6 +8,7 @@ pub enum Event<I> {
     GetBlockHashResult(BlockHash, BlockByHashResult<I>),
     GetBlockHeightResult(u64, BlockByHeightResult<I>),
     GetDeploysResult(DeploysResult<I>),
+    SyncTrieStoreResult(TrieStoreResult<I>),
     StartDownloadingDeploys,
     NewPeerConnected(I),
     BlockHandled(Box<Block>),
@@ -24,6 +25,14 @@ pub enum DeploysResult<I> {
     NotFound(Box<Block>, I),
 }
 
+#[derive(Debug)]
+pub enum TrieStoreResult<I> {
+    /// The global state under the block's state root hash is complete.
+    Synced(Box<Block>),
+    /// The global state under the block's state root hash could not be downloaded from the peer.
+    Failed(Box<Block>, I),
+}
+
 #[derive(Debug)]
 pub enum BlockByHashResult<I> {
     Absent(I),
@@ -51,6 +60,9 @@ where
             Event::GetDeploysResult(result) => {
                 write!(f, "Get deploys for block result {:?}", result)
             }
+            Event::SyncTrieStoreResult(result) => {
+                write!(f, "Sync global state of block result {:?}", result)
+            }
             Event::StartDownloadingDeploys => write!(f, "Start downloading deploys event."),
             Event::NewPeerConnected(peer_id) => write!(f, "A new peer connected: {}", peer_id),
             Event::BlockHandled(block) => {
@@ -1,3 +1,6 @@
+use casper_execution_engine::{shared::stored_value::StoredValue, storage::trie::Trie};
+use casper_types::Key;
+
 use crate::{
     effect::{
         announcements::ControlAnnouncement,
@@ -12,6 +15,7 @@ pub trait ReactorEventT<I>:
     From<StorageRequest>
     + From<FetcherRequest<I, Block>>
     + From<FetcherRequest<I, BlockByHeight>>
+    + From<FetcherRequest<I, Trie<Key, StoredValue>>>
     + From<BlockValidationRequest<I>>
     + From<ContractRuntimeRequest>
     + From<StateStoreRequest>
@@ -24,6 +28,7 @@ impl<I, REv> ReactorEventT<I> for REv where
     REv: From<StorageRequest>
         + From<FetcherRequest<I, Block>>
         + From<FetcherRequest<I, BlockByHeight>>
+        + From<FetcherRequest<I, Trie<Key, StoredValue>>>
         + From<BlockValidationRequest<I>>
         + From<ContractRuntimeRequest>
         + From<StateStoreRequest>
@@ -1,6 +1,8 @@
 //! Metrics component.
 //!
-//! The metrics component renders metrics upon request.
+//! The metrics component renders metrics upon request. It also periodically updates the metrics of
+//! the resources used by the node process, i.e. its resident memory, open file descriptors and
+//! threads.
 //!
 //! # Adding metrics to a component
 //!
@@ -23,9 +25,16 @@
 //!    prevent any actual logic depending on them. If a counter is being increment as a metric and
 //!    also required for business logic, a second counter should be kept in the component's state.
 
-use std::convert::Infallible;
+mod process;
+
+use std::{
+    convert::Infallible,
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
 
 use datasize::DataSize;
+use derive_more::From;
 use prometheus::{Encoder, Registry, TextEncoder};
 use tracing::error;
 
@@ -34,6 +43,29 @@ use crate::{
     effect::{requests::MetricsRequest, EffectBuilder, EffectExt, Effects},
     NodeRng,
 };
+use process::ProcessMetrics;
+
+/// Interval at which the metrics of the node process are updated.
+const PROCESS_METRICS_UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Metrics component event.
+#[derive(Debug, From)]
+pub(crate) enum Event {
+    /// A request to render the metrics.
+    #[from]
+    Request(MetricsRequest),
+    /// The metrics of the node process are due to be updated.
+    UpdateProcessMetrics,
+}
+
+impl Display for Event {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Request(request) => write!(formatter, "{}", request),
+            Event::UpdateProcessMetrics => write!(formatter, "update process metrics"),
+        }
+    }
+}
 
 /// The metrics component.
 #[derive(DataSize, Debug)]
@@ -41,20 +73,30 @@ pub(crate) struct Metrics {
     /// Metrics registry used to answer metrics queries.
     #[data_size(skip)] // Actual implementation is just a wrapper around an `Arc`.
     registry: Registry,
+    /// Metrics of the resources used by the node process.
+    #[data_size(skip)]
+    process_metrics: ProcessMetrics,
 }
 
-impl<REv> Component<REv> for Metrics {
-    type Event = MetricsRequest;
+impl<REv> Component<REv> for Metrics
+where
+    REv: Send + 'static,
+{
+    type Event = Event;
     type ConstructionError = Infallible;
 
+    fn name(&self) -> &str {
+        "metrics"
+    }
+
     fn handle_event(
         &mut self,
-        _effect_builder: EffectBuilder<REv>,
+        effect_builder: EffectBuilder<REv>,
         _rng: &mut NodeRng,
-        req: Self::Event,
+        event: Self::Event,
     ) -> Effects<Self::Event> {
-        match req {
-            MetricsRequest::RenderNodeMetricsText { responder } => {
+        match event {
+            Event::Request(MetricsRequest::RenderNodeMetricsText { responder }) => {
                 let mut buf: Vec<u8> = Vec::<u8>::new();
 
                 if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buf) {
@@ -70,13 +112,35 @@ impl<REv> Component<REv> for Metrics {
                     }
                 }
             }
+            Event::UpdateProcessMetrics => {
+                self.process_metrics.update();
+                schedule_process_metrics_update(effect_builder)
+            }
         }
     }
+
+    fn init(&mut self, effect_builder: EffectBuilder<REv>) -> Effects<Self::Event> {
+        self.process_metrics.update();
+        schedule_process_metrics_update(effect_builder)
+    }
 }
 
 impl Metrics {
     /// Create and initialize a new metrics component.
-    pub(crate) fn new(registry: Registry) -> Self {
-        Metrics { registry }
+    pub(crate) fn new(registry: Registry) -> Result<Self, prometheus::Error> {
+        let process_metrics = ProcessMetrics::new(&registry)?;
+        Ok(Metrics {
+            registry,
+            process_metrics,
+        })
     }
 }
+
+/// Schedules the next update of the metrics of the node process.
+fn schedule_process_metrics_update<REv: Send + 'static>(
+    effect_builder: EffectBuilder<REv>,
+) -> Effects<Event> {
+    effect_builder
+        .set_timeout(PROCESS_METRICS_UPDATE_INTERVAL)
+        .event(|_| Event::UpdateProcessMetrics)
+}
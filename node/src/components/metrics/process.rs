@@ -0,0 +1,140 @@
+//! Metrics of the resources used by the node process.
+//!
+//! The metrics are read from the operating system on a timer rather than whenever the metrics are
+//! scraped, bounding the cost of collecting them. Metrics the platform does not provide are left
+//! at zero.
+
+use prometheus::{IntGauge, Registry};
+
+use crate::unregister_metric;
+
+/// Metrics of the resources used by the node process.
+#[derive(Debug)]
+pub(super) struct ProcessMetrics {
+    /// Resident memory of the process in bytes.
+    resident_memory_bytes: IntGauge,
+    /// Number of open file descriptors of the process.
+    open_fds: IntGauge,
+    /// Number of threads of the process.
+    threads: IntGauge,
+    /// Registry the metrics are registered with.
+    registry: Registry,
+}
+
+impl ProcessMetrics {
+    /// Creates and registers the process metrics.
+    pub(super) fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let resident_memory_bytes = IntGauge::new(
+            "process_resident_memory_bytes",
+            "resident memory of the node process in bytes",
+        )?;
+        let open_fds = IntGauge::new(
+            "process_open_fds",
+            "number of open file descriptors of the node process",
+        )?;
+        let threads = IntGauge::new("process_threads", "number of threads of the node process")?;
+        registry.register(Box::new(resident_memory_bytes.clone()))?;
+        registry.register(Box::new(open_fds.clone()))?;
+        registry.register(Box::new(threads.clone()))?;
+        Ok(ProcessMetrics {
+            resident_memory_bytes,
+            open_fds,
+            threads,
+            registry: registry.clone(),
+        })
+    }
+
+    /// Reads the current resource usage of the process and updates the metrics accordingly.
+    pub(super) fn update(&self) {
+        let stats = ProcessStats::read();
+        if let Some(resident_memory_bytes) = stats.resident_memory_bytes {
+            self.resident_memory_bytes.set(resident_memory_bytes as i64);
+        }
+        if let Some(open_fds) = stats.open_fds {
+            self.open_fds.set(open_fds as i64);
+        }
+        if let Some(threads) = stats.threads {
+            self.threads.set(threads as i64);
+        }
+    }
+}
+
+impl Drop for ProcessMetrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.resident_memory_bytes);
+        unregister_metric!(self.registry, self.open_fds);
+        unregister_metric!(self.registry, self.threads);
+    }
+}
+
+/// Resource usage of the process, `None` where unavailable on the current platform.
+#[derive(Debug, Default)]
+struct ProcessStats {
+    resident_memory_bytes: Option<u64>,
+    open_fds: Option<u64>,
+    threads: Option<u64>,
+}
+
+impl ProcessStats {
+    /// Reads the resource usage of the process from `procfs`.
+    #[cfg(target_os = "linux")]
+    fn read() -> Self {
+        use std::fs;
+
+        let status = fs::read_to_string("/proc/self/status").ok();
+        // Fields of the status file are formatted as e.g. `VmRSS:     1234 kB`.
+        let status_field = |name: &str| -> Option<u64> {
+            status.as_ref()?.lines().find_map(|line| {
+                line.strip_prefix(name)?
+                    .strip_prefix(':')?
+                    .split_whitespace()
+                    .next()?
+                    .parse()
+                    .ok()
+            })
+        };
+
+        ProcessStats {
+            resident_memory_bytes: status_field("VmRSS").map(|kib| kib.saturating_mul(1024)),
+            open_fds: fs::read_dir("/proc/self/fd")
+                .ok()
+                .map(|entries| entries.count() as u64),
+            threads: status_field("Threads"),
+        }
+    }
+
+    /// Resource usage is not available on this platform.
+    #[cfg(not(target_os = "linux"))]
+    fn read() -> Self {
+        ProcessStats::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_report_process_metrics() {
+        let registry = Registry::new();
+        let metrics = ProcessMetrics::new(&registry).unwrap();
+        metrics.update();
+
+        let families = registry.gather();
+        for name in &[
+            "process_resident_memory_bytes",
+            "process_open_fds",
+            "process_threads",
+        ] {
+            let family = families
+                .iter()
+                .find(|family| family.get_name() == *name)
+                .unwrap_or_else(|| panic!("should have {} gauge", name));
+            let value = family.get_metric()[0].get_gauge().get_value();
+            assert!(value >= 0.0, "{} should not be negative", name);
+            if cfg!(target_os = "linux") {
+                assert!(value > 0.0, "{} should be reported", name);
+            }
+        }
+    }
+}
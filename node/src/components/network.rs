@@ -59,7 +59,7 @@ use crate::{
     },
     fatal,
     reactor::{EventQueueHandle, Finalize, QueueKind, ReactorEvent},
-    types::{Chainspec, NodeId},
+    types::{Chainspec, ConnectionDirection, NodeId, PeerConnection, PeerConnectionState},
     utils::{self, ds, CountingReceiver, CountingSender, DisplayIter},
     NodeRng,
 };
@@ -874,6 +874,10 @@ impl<REv: ReactorEventT<P>, P: PayloadT> Component<REv> for Network<REv, P> {
     type Event = Event<P>;
     type ConstructionError = Error;
 
+    fn name(&self) -> &str {
+        "network"
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -1018,6 +1022,31 @@ impl<REv: ReactorEventT<P>, P: PayloadT> Component<REv> for Network<REv, P> {
                         .collect();
                     responder.respond(peers).ignore()
                 }
+                NetworkInfoRequest::GetPeerConnections { responder } => {
+                    // libp2p only notifies us of established connections, and we do not track
+                    // their age.
+                    let connections = self
+                        .peers
+                        .iter()
+                        .map(|(node_id, endpoint)| PeerConnection {
+                            node_id: Some(*node_id),
+                            address: endpoint.get_remote_address().to_string(),
+                            direction: if endpoint.is_dialer() {
+                                ConnectionDirection::Outgoing
+                            } else {
+                                ConnectionDirection::Incoming
+                            },
+                            state: PeerConnectionState::Connected,
+                            age: None,
+                            round_trip_time: None,
+                        })
+                        .collect();
+                    responder.respond(connections).ignore()
+                }
+                NetworkInfoRequest::GetPeerProtocolVersion { responder, .. } => {
+                    // Peers do not exchange protocol versions via libp2p.
+                    responder.respond(None).ignore()
+                }
             },
         }
     }
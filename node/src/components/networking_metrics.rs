@@ -1,6 +1,8 @@
 use std::sync::Weak;
 
-use prometheus::{IntCounter, IntGauge, Registry};
+use prometheus::{
+    self, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+};
 use tracing::debug;
 
 use super::small_network::MessageKind;
@@ -19,6 +21,14 @@ pub(super) struct NetworkingMetrics {
     pub(super) queued_messages: IntGauge,
     /// Number of connected peers.
     pub(super) peers: IntGauge,
+    /// Number of outgoing messages dropped because the send queue of a peer was full, per peer.
+    pub(super) out_dropped_messages: IntCounterVec,
+    /// Number of currently banned peers.
+    pub(super) banned_peers: IntGauge,
+    /// Number of connections closed because the peer did not acknowledge a keepalive in time.
+    pub(super) keepalive_disconnects: IntCounter,
+    /// Round-trip times of acknowledged keepalives.
+    pub(super) keepalive_rtt: Histogram,
 
     /// Count of outgoing messages that are protocol overhead.
     pub(super) out_count_protocol: IntCounter,
@@ -80,6 +90,25 @@ impl NetworkingMetrics {
             "number of messages waiting to be sent out",
         )?;
         let peers = IntGauge::new("peers", "number of connected peers")?;
+        let out_dropped_messages = IntCounterVec::new(
+            Opts::new(
+                "net_out_dropped_messages",
+                "number of outgoing messages dropped because the send queue of a peer was full",
+            ),
+            &["peer"],
+        )?;
+        let banned_peers = IntGauge::new("net_banned_peers", "number of currently banned peers")?;
+        let keepalive_disconnects = IntCounter::new(
+            "net_keepalive_disconnects",
+            "number of connections closed because the peer did not acknowledge a keepalive in time",
+        )?;
+        let keepalive_rtt = Histogram::with_opts(
+            HistogramOpts::new(
+                "net_keepalive_rtt_seconds",
+                "round-trip time of acknowledged keepalives, in seconds",
+            )
+            .buckets(prometheus::exponential_buckets(0.000_1, 2.0, 18)?),
+        )?;
 
         let out_count_protocol = IntCounter::new(
             "net_out_count_protocol",
@@ -161,6 +190,10 @@ impl NetworkingMetrics {
         registry.register(Box::new(open_connections.clone()))?;
         registry.register(Box::new(queued_messages.clone()))?;
         registry.register(Box::new(peers.clone()))?;
+        registry.register(Box::new(out_dropped_messages.clone()))?;
+        registry.register(Box::new(banned_peers.clone()))?;
+        registry.register(Box::new(keepalive_disconnects.clone()))?;
+        registry.register(Box::new(keepalive_rtt.clone()))?;
 
         registry.register(Box::new(out_count_protocol.clone()))?;
         registry.register(Box::new(out_count_consensus.clone()))?;
@@ -189,6 +222,10 @@ impl NetworkingMetrics {
             open_connections,
             queued_messages,
             peers,
+            out_dropped_messages,
+            banned_peers,
+            keepalive_disconnects,
+            keepalive_rtt,
             out_count_protocol,
             out_count_consensus,
             out_count_deploy_gossip,
@@ -257,6 +294,10 @@ impl Drop for NetworkingMetrics {
         unregister_metric!(self.registry, self.open_connections);
         unregister_metric!(self.registry, self.queued_messages);
         unregister_metric!(self.registry, self.peers);
+        unregister_metric!(self.registry, self.out_dropped_messages);
+        unregister_metric!(self.registry, self.banned_peers);
+        unregister_metric!(self.registry, self.keepalive_disconnects);
+        unregister_metric!(self.registry, self.keepalive_rtt);
 
         unregister_metric!(self.registry, self.out_count_protocol);
         unregister_metric!(self.registry, self.out_count_consensus);
@@ -17,6 +17,10 @@
 //!     example: curl -X GET 'http://<ip>:8888/status'
 //! /metrics : time series data collected from the internals of the node being queried.
 //!     example: curl -X GET 'http://<ip>:8888/metrics'
+//!
+//! The metrics can be served on a separate address, e.g. on a private interface, and require a
+//! bearer token, in which case requests not carrying it in their `Authorization` header are
+//! rejected with `401 Unauthorized`.
 
 mod config;
 mod event;
@@ -85,6 +89,9 @@ pub(crate) struct RestServer {
     /// The task handle which will only join once the server loop has exited.
     #[data_size(skip)]
     server_join_handle: Option<JoinHandle<()>>,
+    /// Whether the node is still syncing the linear chain, i.e. whether this server belongs to the
+    /// joiner reactor.
+    is_syncing: bool,
 }
 
 impl RestServer {
@@ -92,6 +99,7 @@ impl RestServer {
         config: Config,
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        is_syncing: bool,
     ) -> Result<Self, ListeningError>
     where
         REv: ReactorEventT,
@@ -99,17 +107,25 @@ impl RestServer {
         let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
 
         let builder = utils::start_listening(&config.address)?;
+        let metrics_builder = config
+            .metrics_address
+            .as_deref()
+            .map(utils::start_listening)
+            .transpose()?;
         let server_join_handle = tokio::spawn(http_server::run(
             builder,
+            metrics_builder,
             effect_builder,
             api_version,
             shutdown_receiver,
             config.qps_limit,
+            config.metrics_token,
         ));
 
         Ok(RestServer {
             shutdown_sender,
             server_join_handle: Some(server_join_handle),
+            is_syncing,
         })
     }
 }
@@ -121,6 +137,10 @@ where
     type Event = Event;
     type ConstructionError = Infallible;
 
+    fn name(&self) -> &str {
+        "rest_server"
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -128,18 +148,26 @@ where
         event: Self::Event,
     ) -> Effects<Self::Event> {
         match event {
-            Event::RestRequest(RestRequest::GetStatus { responder }) => async move {
-                let (last_added_block, peers, chainspec_info, consensus_status) = join!(
-                    effect_builder.get_highest_block_from_storage(),
-                    effect_builder.network_peers(),
-                    effect_builder.get_chainspec_info(),
-                    effect_builder.consensus_status()
-                );
-                let status_feed =
-                    StatusFeed::new(last_added_block, peers, chainspec_info, consensus_status);
-                responder.respond(status_feed).await;
+            Event::RestRequest(RestRequest::GetStatus { responder }) => {
+                let is_syncing = self.is_syncing;
+                async move {
+                    let (last_added_block, peers, chainspec_info, consensus_status) = join!(
+                        effect_builder.get_highest_block_from_storage(),
+                        effect_builder.network_peers(),
+                        effect_builder.get_chainspec_info(),
+                        effect_builder.consensus_status()
+                    );
+                    let status_feed = StatusFeed::new(
+                        last_added_block,
+                        peers,
+                        chainspec_info,
+                        consensus_status,
+                        is_syncing,
+                    );
+                    responder.respond(status_feed).await;
+                }
+                .ignore()
             }
-            .ignore(),
             Event::RestRequest(RestRequest::GetMetrics { responder }) => effect_builder
                 .get_metrics()
                 .event(move |text| Event::GetMetricsResult {
@@ -18,6 +18,18 @@ pub struct Config {
 
     /// Max rate limit in qps.
     pub qps_limit: u64,
+
+    /// Address to bind the metrics endpoint to, instead of `address`.
+    ///
+    /// If unset, the metrics are served along with all other endpoints.
+    #[serde(default)]
+    pub metrics_address: Option<String>,
+
+    /// Bearer token required to access the metrics endpoint.
+    ///
+    /// If unset, the metrics are accessible without authentication.
+    #[serde(default)]
+    pub metrics_token: Option<String>,
 }
 
 impl Config {
@@ -26,6 +38,8 @@ impl Config {
         Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
+            metrics_address: None,
+            metrics_token: None,
         }
     }
 }
@@ -1,7 +1,7 @@
-use futures::FutureExt;
+use futures::{future, FutureExt};
 use http::Response;
 use hyper::Body;
-use tracing::warn;
+use tracing::{debug, warn};
 use warp::{
     filters::BoxedFilter,
     http::StatusCode,
@@ -14,6 +14,7 @@ use casper_types::ProtocolVersion;
 
 use super::ReactorEventT;
 use crate::{
+    crypto::hash::{self, Digest},
     effect::{requests::RestRequest, EffectBuilder},
     reactor::QueueKind,
     types::GetStatusResult,
@@ -28,6 +29,9 @@ pub const METRICS_API_PATH: &str = "metrics";
 /// The OpenRPC scehma URL path.
 pub const JSON_RPC_SCHEMA_API_PATH: &str = "rpc-schema";
 
+/// The header carrying the bearer token required to access the metrics.
+const AUTHORIZATION_HEADER: &str = "authorization";
+
 pub(super) fn create_status_filter<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
@@ -50,10 +54,22 @@ pub(super) fn create_status_filter<REv: ReactorEventT>(
 
 pub(super) fn create_metrics_filter<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
+    metrics_token: Option<String>,
 ) -> BoxedFilter<(Response<Body>,)> {
     warp::get()
         .and(warp::path(METRICS_API_PATH))
-        .and_then(move || {
+        .and(metrics_authorization(metrics_token))
+        .and_then(move |authorized| {
+            if !authorized {
+                debug!("rejecting metrics request without valid token");
+                let response = reply::with_header(
+                    reply::with_status("unauthorized", StatusCode::UNAUTHORIZED),
+                    "www-authenticate",
+                    "Bearer",
+                )
+                .into_response();
+                return future::ok::<_, Rejection>(response).left_future();
+            }
             effect_builder
                 .make_request(
                     |responder| RestRequest::GetMetrics { responder },
@@ -72,10 +88,40 @@ pub(super) fn create_metrics_filter<REv: ReactorEventT>(
                         .into_response())
                     }
                 })
+                .right_future()
+        })
+        .boxed()
+}
+
+/// Returns a filter extracting whether a request carries the bearer token required to access the
+/// metrics. If no token is configured, every request is authorized.
+fn metrics_authorization(metrics_token: Option<String>) -> BoxedFilter<(bool,)> {
+    let expected_header_hash = metrics_token.map(|token| hash::hash(format!("Bearer {}", token)));
+    warp::header::optional::<String>(AUTHORIZATION_HEADER)
+        .map(move |header: Option<String>| match &expected_header_hash {
+            Some(expected_header_hash) => header.map_or(false, |header| {
+                constant_time_eq(&hash::hash(header), expected_header_hash)
+            }),
+            None => true,
         })
         .boxed()
 }
 
+/// Compares two digests in constant time, so that the time taken does not reveal how long a
+/// matching prefix they share.
+///
+/// Comparing digests of the header and token rather than the values themselves also avoids leaking
+/// the token's length.
+fn constant_time_eq(lhs: &Digest, rhs: &Digest) -> bool {
+    lhs.as_ref()
+        .iter()
+        .zip(rhs.as_ref())
+        .fold(0u8, |difference, (lhs_byte, rhs_byte)| {
+            difference | (lhs_byte ^ rhs_byte)
+        })
+        == 0
+}
+
 pub(super) fn create_rpc_schema_filter<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
 ) -> BoxedFilter<(Response<Body>,)> {
@@ -93,3 +139,34 @@ pub(super) fn create_rpc_schema_filter<REv: ReactorEventT>(
         })
         .boxed()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn is_authorized(metrics_token: Option<&str>, header: Option<&str>) -> bool {
+        let mut request = warp::test::request().path("/metrics");
+        if let Some(header) = header {
+            request = request.header(AUTHORIZATION_HEADER, header);
+        }
+        request
+            .filter(&metrics_authorization(metrics_token.map(str::to_string)))
+            .await
+            .expect("should extract authorization")
+    }
+
+    #[tokio::test]
+    async fn should_require_configured_metrics_token() {
+        let token = Some("secret");
+        assert!(!is_authorized(token, None).await);
+        assert!(!is_authorized(token, Some("Bearer wrong")).await);
+        assert!(!is_authorized(token, Some("secret")).await);
+        assert!(is_authorized(token, Some("Bearer secret")).await);
+    }
+
+    #[tokio::test]
+    async fn should_not_require_token_if_none_configured() {
+        assert!(is_authorized(None, None).await);
+        assert!(is_authorized(None, Some("Bearer anything")).await);
+    }
+}
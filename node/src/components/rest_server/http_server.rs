@@ -1,11 +1,15 @@
-use std::{convert::Infallible, time::Duration};
+use std::{convert::Infallible, future::Future, time::Duration};
 
-use futures::{future, TryFutureExt};
-use hyper::server::{conn::AddrIncoming, Builder};
+use futures::{future, join, FutureExt, TryFutureExt};
+use http::Response;
+use hyper::{
+    server::{conn::AddrIncoming, Builder},
+    Body,
+};
 use tokio::sync::oneshot;
 use tower::builder::ServiceBuilder;
 use tracing::{info, warn};
-use warp::Filter;
+use warp::{filters::BoxedFilter, Filter};
 
 use casper_types::ProtocolVersion;
 
@@ -14,22 +18,66 @@ use crate::effect::EffectBuilder;
 
 /// Run the REST HTTP server.
 ///
+/// If `metrics_builder` is given, the metrics endpoint is served by a separate server listening on
+/// it rather than along with the other endpoints.
+///
 /// A message received on `shutdown_receiver` will cause the server to exit cleanly.
 pub(super) async fn run<REv: ReactorEventT>(
     builder: Builder<AddrIncoming>,
+    metrics_builder: Option<Builder<AddrIncoming>>,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
     shutdown_receiver: oneshot::Receiver<()>,
     qps_limit: u64,
+    metrics_token: Option<String>,
 ) {
     // REST filters.
     let rest_status = filters::create_status_filter(effect_builder, api_version);
-    let rest_metrics = filters::create_metrics_filter(effect_builder);
+    let rest_metrics = filters::create_metrics_filter(effect_builder, metrics_token);
     let rest_open_rpc = filters::create_rpc_schema_filter(effect_builder);
 
-    let service = warp::service(rest_status.or(rest_metrics).or(rest_open_rpc));
+    let shutdown = async {
+        shutdown_receiver.await.ok();
+    }
+    .shared();
+
+    match metrics_builder {
+        None => {
+            let filter = rest_status
+                .or(rest_metrics)
+                .unify()
+                .or(rest_open_rpc)
+                .unify()
+                .boxed();
+            serve("REST", builder, filter, qps_limit, shutdown).await;
+        }
+        Some(metrics_builder) => {
+            let filter = rest_status.or(rest_open_rpc).unify().boxed();
+            join!(
+                serve("REST", builder, filter, qps_limit, shutdown.clone()),
+                serve(
+                    "metrics",
+                    metrics_builder,
+                    rest_metrics,
+                    qps_limit,
+                    shutdown
+                )
+            );
+        }
+    }
+}
+
+/// Serves the given filter until `shutdown` completes.
+async fn serve(
+    name: &str,
+    builder: Builder<AddrIncoming>,
+    filter: BoxedFilter<(Response<Body>,)>,
+    qps_limit: u64,
+    shutdown: impl Future<Output = ()>,
+) {
+    let service = warp::service(filter);
 
-    // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
+    // Start the server, passing a shutdown future to allow the server to be shut down gracefully.
     let make_svc =
         hyper::service::make_service_fn(move |_| future::ok::<_, Infallible>(service.clone()));
 
@@ -38,15 +86,13 @@ pub(super) async fn run<REv: ReactorEventT>(
         .service(make_svc);
 
     let server = builder.serve(rate_limited_service);
-    info!(address = %server.local_addr(), "started REST server");
+    info!(address = %server.local_addr(), "started {} server", name);
 
     // Shutdown the server gracefully.
     let _ = server
-        .with_graceful_shutdown(async {
-            shutdown_receiver.await.ok();
-        })
+        .with_graceful_shutdown(shutdown)
         .map_err(|error| {
-            warn!(%error, "error running REST server");
+            warn!(%error, "error running {} server", name);
         })
         .await;
 }
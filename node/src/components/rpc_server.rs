@@ -19,19 +19,21 @@ mod event;
 mod http_server;
 pub mod rpcs;
 
-use std::{convert::Infallible, fmt::Debug};
+use std::{convert::Infallible, fmt::Debug, sync::Arc};
 
 use datasize::DataSize;
 use futures::join;
+use thiserror::Error;
+use tracing::debug;
 
 use casper_execution_engine::{
     core::engine_state::{
-        self, BalanceRequest, BalanceResult, GetBidsRequest, GetEraValidatorsError, QueryRequest,
-        QueryResult,
+        self, BalanceRequest, BalanceResult, DeployItem, ExecuteRequest, GetBidsRequest,
+        GetEraValidatorsError, QueryRequest, QueryResult,
     },
     storage::protocol_data::ProtocolData,
 };
-use casper_types::{system::auction::EraValidators, Key, ProtocolVersion, URef};
+use casper_types::{system::auction::EraValidators, ExecutionResult, Key, ProtocolVersion, URef};
 
 use self::rpcs::chain::BlockIdentifier;
 
@@ -47,8 +49,9 @@ use crate::{
         },
         EffectBuilder, EffectExt, Effects, Responder,
     },
-    types::{NodeId, StatusFeed},
-    utils::{self, ListeningError},
+    reactor::CorrelationId,
+    types::{Chainspec, Deploy, DeployValidationFailure, NodeId, StatusFeed, Timestamp},
+    utils::{self, ClientRateLimiter, ListeningError},
     NodeRng,
 };
 
@@ -87,27 +90,51 @@ impl<REv> ReactorEventT for REv where
 {
 }
 
+/// Error returned when estimating the gas of a deploy.
+#[derive(Debug, Error)]
+pub enum EstimateGasError {
+    /// No block has been added yet, so there is no global state to execute against.
+    #[error("no block has been added yet")]
+    NoBlock,
+    /// The deploy would not be accepted by this node.
+    #[error("invalid deploy: {0}")]
+    InvalidDeploy(DeployValidationFailure),
+    /// Executing the deploy failed.
+    #[error(transparent)]
+    Execution(#[from] engine_state::Error),
+}
+
 #[derive(DataSize, Debug)]
-pub(crate) struct RpcServer {}
+pub(crate) struct RpcServer {
+    /// The chainspec, against whose deploy parameters deploys are checked before estimating gas.
+    chainspec: Arc<Chainspec>,
+}
 
 impl RpcServer {
     pub(crate) fn new<REv>(
         config: Config,
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
+        chainspec: Arc<Chainspec>,
     ) -> Result<Self, ListeningError>
     where
         REv: ReactorEventT,
     {
         let builder = utils::start_listening(&config.address)?;
+        let client_rate_limiter = ClientRateLimiter::new(
+            config.client_qps_limit,
+            config.client_burst_limit,
+            &config.rate_limit_whitelist,
+        );
         tokio::spawn(http_server::run(
             builder,
             effect_builder,
             api_version,
             config.qps_limit,
+            client_rate_limiter,
         ));
 
-        Ok(RpcServer {})
+        Ok(RpcServer { chainspec })
     }
 }
 
@@ -174,6 +201,46 @@ impl RpcServer {
                 main_responder: responder,
             })
     }
+
+    fn handle_estimate_gas<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        mut deploy: Box<Deploy>,
+        responder: Responder<Result<ExecutionResult, EstimateGasError>>,
+    ) -> Effects<Event> {
+        let chainspec = Arc::clone(&self.chainspec);
+        async move {
+            let block = match effect_builder.get_highest_block_from_storage().await {
+                Some(block) => block,
+                None => return responder.respond(Err(EstimateGasError::NoBlock)).await,
+            };
+
+            // Only estimate the gas of deploys which would be accepted for the next block.
+            let deploy_config = chainspec.deploy_config_at(block.height() + 1);
+            if let Err(error) = deploy.is_acceptable(&chainspec.network_config.name, deploy_config)
+            {
+                return responder
+                    .respond(Err(EstimateGasError::InvalidDeploy(error)))
+                    .await;
+            }
+
+            // Execute as though the deploy were included in a block proposed now by the proposer
+            // of the latest block.
+            let execute_request = ExecuteRequest::new(
+                (*block.state_root_hash()).into(),
+                Timestamp::now().millis(),
+                vec![DeployItem::from(*deploy)],
+                block.protocol_version(),
+                block.body().proposer().clone(),
+            );
+            let result = effect_builder
+                .execute_dry_run(execute_request)
+                .await
+                .map_err(EstimateGasError::from);
+            responder.respond(result).await
+        }
+        .ignore()
+    }
 }
 
 impl<REv> Component<REv> for RpcServer
@@ -183,16 +250,27 @@ where
     type Event = Event;
     type ConstructionError = Infallible;
 
+    fn name(&self) -> &str {
+        "rpc_server"
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
-        _rng: &mut NodeRng,
+        rng: &mut NodeRng,
         event: Self::Event,
     ) -> Effects<Self::Event> {
         match event {
-            Event::RpcRequest(RpcRequest::SubmitDeploy { deploy, responder }) => effect_builder
-                .announce_deploy_received(deploy, Some(responder))
-                .ignore(),
+            Event::RpcRequest(RpcRequest::SubmitDeploy { deploy, responder }) => {
+                // Start a new correlation ID, so the deploy can be followed through the node.
+                let correlation_id = CorrelationId::random(rng);
+                debug!(deploy_hash = %deploy.id(), %correlation_id, "received deploy");
+                correlation_id.correlate(
+                    effect_builder
+                        .announce_deploy_received(deploy, Some(responder))
+                        .ignore(),
+                )
+            }
             Event::RpcRequest(RpcRequest::GetBlock {
                 maybe_id: Some(BlockIdentifier::Hash(hash)),
                 responder,
@@ -277,12 +355,17 @@ where
                     result: Box::new(result),
                     main_responder: responder,
                 }),
-            Event::RpcRequest(RpcRequest::GetPeers { responder }) => effect_builder
-                .network_peers()
-                .event(move |peers| Event::GetPeersResult {
-                    peers,
-                    main_responder: responder,
-                }),
+            Event::RpcRequest(RpcRequest::EstimateGas { deploy, responder }) => {
+                self.handle_estimate_gas(effect_builder, deploy, responder)
+            }
+            Event::RpcRequest(RpcRequest::GetPeers { responder }) => async move {
+                let peers_and_connections = join!(
+                    effect_builder.network_peers(),
+                    effect_builder.network_peer_connections()
+                );
+                responder.respond(peers_and_connections).await;
+            }
+            .ignore(),
             Event::RpcRequest(RpcRequest::GetStatus { responder }) => async move {
                 let (last_added_block, peers, chainspec_info, consensus_status) = join!(
                     effect_builder.get_highest_block_from_storage(),
@@ -290,8 +373,14 @@ where
                     effect_builder.get_chainspec_info(),
                     effect_builder.consensus_status()
                 );
-                let status_feed =
-                    StatusFeed::new(last_added_block, peers, chainspec_info, consensus_status);
+                // The RPC server only runs once the node has finished syncing.
+                let status_feed = StatusFeed::new(
+                    last_added_block,
+                    peers,
+                    chainspec_info,
+                    consensus_status,
+                    false,
+                );
                 responder.respond(status_feed).await;
             }
             .ignore(),
@@ -336,10 +425,6 @@ where
                 result,
                 main_responder,
             } => main_responder.respond(*result).ignore(),
-            Event::GetPeersResult {
-                peers,
-                main_responder,
-            } => main_responder.respond(peers).ignore(),
             Event::GetMetricsResult {
                 text,
                 main_responder,
@@ -1,3 +1,5 @@
+use std::net::IpAddr;
+
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
@@ -7,6 +9,10 @@ use serde::{Deserialize, Serialize};
 const DEFAULT_ADDRESS: &str = "0.0.0.0:0";
 /// Default rate limit in qps.
 const DEFAULT_QPS_LIMIT: u64 = 100;
+/// Default rate limit in qps for each client IP address.
+const DEFAULT_CLIENT_QPS_LIMIT: u64 = 20;
+/// Default number of requests each client IP address may make in a single burst.
+const DEFAULT_CLIENT_BURST_LIMIT: u64 = 40;
 
 /// JSON-RPC HTTP server configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
@@ -18,6 +24,20 @@ pub struct Config {
 
     /// Max rate limit in qps.
     pub qps_limit: u64,
+
+    /// Max rate limit in qps for each client IP address.  A value of 0 disables the per-client
+    /// limit.
+    #[serde(default = "default_client_qps_limit")]
+    pub client_qps_limit: u64,
+
+    /// Max number of requests each client IP address may make in a single burst.
+    #[serde(default = "default_client_burst_limit")]
+    pub client_burst_limit: u64,
+
+    /// Client IP addresses which are exempt from the per-client rate limit.
+    #[serde(default)]
+    #[data_size(skip)]
+    pub rate_limit_whitelist: Vec<IpAddr>,
 }
 
 impl Config {
@@ -26,6 +46,9 @@ impl Config {
         Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
+            client_qps_limit: DEFAULT_CLIENT_QPS_LIMIT,
+            client_burst_limit: DEFAULT_CLIENT_BURST_LIMIT,
+            rate_limit_whitelist: Vec::new(),
         }
     }
 }
@@ -35,3 +58,11 @@ impl Default for Config {
         Config::new()
     }
 }
+
+fn default_client_qps_limit() -> u64 {
+    DEFAULT_CLIENT_QPS_LIMIT
+}
+
+fn default_client_burst_limit() -> u64 {
+    DEFAULT_CLIENT_BURST_LIMIT
+}
@@ -1,7 +1,4 @@
-use std::{
-    collections::BTreeMap,
-    fmt::{self, Display, Formatter},
-};
+use std::fmt::{self, Display, Formatter};
 
 use derive_more::From;
 
@@ -52,10 +49,6 @@ pub enum Event {
         result: Box<Option<(Deploy, DeployMetadata)>>,
         main_responder: Responder<Option<(Deploy, DeployMetadata)>>,
     },
-    GetPeersResult {
-        peers: BTreeMap<NodeId, String>,
-        main_responder: Responder<BTreeMap<NodeId, String>>,
-    },
     GetMetricsResult {
         text: Option<String>,
         main_responder: Responder<Option<String>>,
@@ -110,7 +103,6 @@ impl Display for Event {
             Event::GetDeployResult { hash, result, .. } => {
                 write!(formatter, "get deploy result for {}: {:?}", hash, result)
             }
-            Event::GetPeersResult { peers, .. } => write!(formatter, "get peers: {}", peers.len()),
             Event::GetMetricsResult { text, .. } => match text {
                 Some(txt) => write!(formatter, "get metrics ({} bytes)", txt.len()),
                 None => write!(formatter, "get metrics (failed)"),
@@ -1,15 +1,23 @@
-use std::{convert::Infallible, time::Duration};
+use std::{
+    convert::Infallible,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
-use futures::future;
-use http::{Response, StatusCode};
+use futures::future::{self, Either};
+use http::{header, Response, StatusCode};
 use hyper::{
-    server::{conn::AddrIncoming, Builder},
+    server::{
+        conn::{AddrIncoming, AddrStream},
+        Builder,
+    },
+    service::{self, Service},
     Body,
 };
 use serde::Serialize;
 use tokio::sync::oneshot;
 use tower::builder::ServiceBuilder;
-use tracing::{info, trace};
+use tracing::{debug, info, trace};
 use warp::{Filter, Rejection};
 
 use casper_types::ProtocolVersion;
@@ -18,7 +26,7 @@ use super::{
     rpcs::{self, RpcWithOptionalParamsExt, RpcWithParamsExt, RpcWithoutParamsExt, RPC_API_PATH},
     ReactorEventT,
 };
-use crate::effect::EffectBuilder;
+use crate::{effect::EffectBuilder, utils::ClientRateLimiter};
 
 // This is a workaround for not being able to create a `warp_json_rpc::Response` without a
 // `warp_json_rpc::Builder`.
@@ -44,12 +52,24 @@ fn new_error_response(error: warp_json_rpc::Error) -> Response<Body> {
         .unwrap()
 }
 
+// Creates an HTTP 429 response asking the client to retry after the given duration, rounded up to
+// the nearest second.
+fn new_too_many_requests_response(retry_after: Duration) -> Response<Body> {
+    let retry_after_secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    Response::builder()
+        .status(StatusCode::TOO_MANY_REQUESTS)
+        .header(header::RETRY_AFTER, retry_after_secs.max(1))
+        .body(Body::from("client has exceeded its request rate limit"))
+        .unwrap()
+}
+
 /// Run the JSON-RPC server.
 pub(super) async fn run<REv: ReactorEventT>(
     builder: Builder<AddrIncoming>,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
     qps_limit: u64,
+    client_rate_limiter: ClientRateLimiter,
 ) {
     // RPC filters.
     let rpc_put_deploy = rpcs::account::PutDeploy::create_filter(effect_builder, api_version);
@@ -63,6 +83,9 @@ pub(super) async fn run<REv: ReactorEventT>(
     let rpc_get_account_info =
         rpcs::state::GetAccountInfo::create_filter(effect_builder, api_version);
     let rpc_get_deploy = rpcs::info::GetDeploy::create_filter(effect_builder, api_version);
+    let rpc_get_deploy_execution =
+        rpcs::info::GetDeployExecution::create_filter(effect_builder, api_version);
+    let rpc_estimate_gas = rpcs::info::EstimateGas::create_filter(effect_builder, api_version);
     let rpc_get_peers = rpcs::info::GetPeers::create_filter(effect_builder, api_version);
     let rpc_get_status = rpcs::info::GetStatus::create_filter(effect_builder, api_version);
     let rpc_get_era_info =
@@ -99,6 +122,8 @@ pub(super) async fn run<REv: ReactorEventT>(
             .or(rpc_get_item)
             .or(rpc_get_balance)
             .or(rpc_get_deploy)
+            .or(rpc_get_deploy_execution)
+            .or(rpc_estimate_gas)
             .or(rpc_get_peers)
             .or(rpc_get_status)
             .or(rpc_get_era_info)
@@ -111,8 +136,27 @@ pub(super) async fn run<REv: ReactorEventT>(
     );
 
     // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
-    let make_svc =
-        hyper::service::make_service_fn(move |_| future::ok::<_, Infallible>(service.clone()));
+    // Requests from clients which have exceeded their own rate limit are rejected without being
+    // processed.
+    let client_rate_limiter = Arc::new(Mutex::new(client_rate_limiter));
+    let make_svc = service::make_service_fn(move |connection: &AddrStream| {
+        let client_ip = connection.remote_addr().ip();
+        let client_rate_limiter = Arc::clone(&client_rate_limiter);
+        let mut service = service.clone();
+        future::ok::<_, Infallible>(service::service_fn(move |request| {
+            let limit_check = client_rate_limiter
+                .lock()
+                .expect("lock poisoned")
+                .check(client_ip, Instant::now());
+            match limit_check {
+                Ok(()) => Either::Left(service.call(request)),
+                Err(retry_after) => {
+                    debug!(%client_ip, "rate limiting JSON-RPC client");
+                    Either::Right(future::ok(new_too_many_requests_response(retry_after)))
+                }
+            }
+        }))
+    });
 
     let make_svc = ServiceBuilder::new()
         .rate_limit(qps_limit, Duration::from_secs(1))
@@ -48,6 +48,11 @@ enum ErrorCode {
     InvalidDeploy = -32008,
     NoSuchAccount = -32009,
     FailedToGetDictionaryURef = -32010,
+    DeployNotExecuted = -32011,
+    NoSuchStateRoot = -32012,
+    EstimateGasFailed = -32013,
+    DeployBufferFull = -32014,
+    NoSuchPurse = -32015,
 }
 
 #[derive(Debug)]
@@ -298,6 +303,10 @@ mod common {
     ) -> Result<(StoredValue, Vec<u8>), (ErrorCode, String)> {
         let (value, proof) = match query_result {
             Ok(QueryResult::Success { value, proofs }) => (value, proofs),
+            Ok(QueryResult::RootNotFound) => {
+                let error_msg = "state query failed: state root hash not found".to_string();
+                return Err((ErrorCode::NoSuchStateRoot, error_msg));
+            }
             Ok(query_result) => {
                 let error_msg = format!("state query failed: {:?}", query_result);
                 return Err((ErrorCode::QueryFailed, error_msg));
@@ -21,7 +21,7 @@ use super::{
     Error, ReactorEventT, RpcRequest, RpcWithParams, RpcWithParamsExt,
 };
 use crate::{
-    components::rpc_server::rpcs::ErrorCode,
+    components::{deploy_acceptor, rpc_server::rpcs::ErrorCode},
     effect::EffectBuilder,
     reactor::QueueKind,
     types::{Deploy, DeployHash},
@@ -67,6 +67,11 @@ impl DocExample for PutDeployResult {
 }
 
 /// "account_put_deploy" RPC
+///
+/// The deploy is only acknowledged once the node has accepted it.  If it was rejected, the error
+/// returned has the `InvalidDeploy` code, and its data holds the structured reason.  If it was
+/// rejected because the node's deploy buffer is full, the error has the `DeployBufferFull` code
+/// instead, in which case the same deploy can be submitted again later.
 pub struct PutDeploy {}
 
 impl RpcWithParams for PutDeploy {
@@ -96,30 +101,419 @@ impl RpcWithParamsExt for PutDeploy {
                 )
                 .await;
 
-            match put_deploy_result {
-                Ok(_) => {
-                    info!(%deploy_hash,
-                    "deploy was stored"
-                    );
-                    let result = Self::ResponseResult {
-                        api_version,
-                        deploy_hash,
+            match &put_deploy_result {
+                Ok(()) => info!(%deploy_hash, "deploy was stored"),
+                Err(deploy_acceptor::Error::DeployBufferFull) => info!(
+                    %deploy_hash,
+                    "the deploy buffer is full, the client can retry submitting the deploy later",
+                ),
+                Err(error) => info!(
+                    %deploy_hash,
+                    ?error,
+                    "the deploy submitted by the client was invalid",
+                ),
+            }
+
+            match put_deploy_result_of(deploy_hash, put_deploy_result, api_version) {
+                Ok(result) => Ok(response_builder.success(result)?),
+                Err(error) => Ok(response_builder.error(error)?),
+            }
+        }
+        .boxed()
+    }
+}
+
+/// Converts the deploy acceptor's verdict on a deploy into the RPC result, or the error to be
+/// returned to the client, carrying the reason for the rejection as its data.
+fn put_deploy_result_of(
+    deploy_hash: DeployHash,
+    acceptance: Result<(), deploy_acceptor::Error>,
+    api_version: ProtocolVersion,
+) -> Result<PutDeployResult, warp_json_rpc::Error> {
+    match acceptance {
+        Ok(()) => Ok(PutDeployResult {
+            api_version,
+            deploy_hash,
+        }),
+        Err(error) => {
+            let code = match error {
+                deploy_acceptor::Error::DeployBufferFull => ErrorCode::DeployBufferFull,
+                _ => ErrorCode::InvalidDeploy,
+            };
+            Err(warp_json_rpc::Error::custom(code as i64, error.to_string()).with_data(&error))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fmt::{self, Display, Formatter},
+        sync::Arc,
+        time::Duration,
+    };
+
+    use derive_more::From;
+    use futures::channel::oneshot;
+    use prometheus::Registry;
+    use serde_json::json;
+    use tokio::time;
+
+    use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+    use casper_types::{bytesrepr::Bytes, runtime_args, RuntimeArgs, SecretKey, U512};
+
+    use super::*;
+    use crate::{
+        components::{
+            deploy_acceptor::DeployAcceptor,
+            rpc_server::{self, RpcServer},
+            Component,
+        },
+        effect::{
+            announcements::{
+                BlocklistAnnouncement, ControlAnnouncement, DeployAcceptorAnnouncement,
+                RpcServerAnnouncement,
+            },
+            requests::{
+                BlockProposerRequest, ChainspecLoaderRequest, ConsensusRequest,
+                ContractRuntimeRequest, LinearChainRequest, MetricsRequest, NetworkInfoRequest,
+                StorageRequest,
+            },
+            EffectExt, Effects,
+        },
+        reactor::{self, EventQueueHandle, ReactorEvent, Runner},
+        testing::TestRng,
+        types::{Chainspec, DeployValidationFailure, NodeId, TimeDiff, Timestamp},
+        utils::Source,
+        NodeRng,
+    };
+
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Top-level event for the reactor.
+    #[derive(Debug, From, Serialize)]
+    #[must_use]
+    enum Event {
+        #[from]
+        RpcServer(#[serde(skip_serializing)] rpc_server::Event),
+        #[from]
+        DeployAcceptor(#[serde(skip_serializing)] deploy_acceptor::Event),
+        #[from]
+        RpcServerAnnouncement(#[serde(skip_serializing)] RpcServerAnnouncement),
+        #[from]
+        DeployAcceptorAnnouncement(#[serde(skip_serializing)] DeployAcceptorAnnouncement<NodeId>),
+        #[from]
+        BlockProposerRequest(#[serde(skip_serializing)] BlockProposerRequest),
+        #[from]
+        ControlAnnouncement(ControlAnnouncement),
+    }
+
+    impl ReactorEvent for Event {
+        fn as_control(&self) -> Option<&ControlAnnouncement> {
+            if let Self::ControlAnnouncement(ref ctrl_ann) = self {
+                Some(ctrl_ann)
+            } else {
+                None
+            }
+        }
+    }
+
+    impl From<RpcRequest<NodeId>> for Event {
+        fn from(request: RpcRequest<NodeId>) -> Self {
+            Event::RpcServer(rpc_server::Event::RpcRequest(request))
+        }
+    }
+
+    impl From<BlocklistAnnouncement<NodeId>> for Event {
+        fn from(_announcement: BlocklistAnnouncement<NodeId>) -> Self {
+            unimplemented!("not implemented for account RPC tests")
+        }
+    }
+
+    impl From<StorageRequest> for Event {
+        fn from(_request: StorageRequest) -> Self {
+            unimplemented!("not implemented for account RPC tests")
+        }
+    }
+
+    impl From<ChainspecLoaderRequest> for Event {
+        fn from(_request: ChainspecLoaderRequest) -> Self {
+            unimplemented!("not implemented for account RPC tests")
+        }
+    }
+
+    impl From<ContractRuntimeRequest> for Event {
+        fn from(_request: ContractRuntimeRequest) -> Self {
+            unimplemented!("not implemented for account RPC tests")
+        }
+    }
+
+    impl From<ConsensusRequest> for Event {
+        fn from(_request: ConsensusRequest) -> Self {
+            unimplemented!("not implemented for account RPC tests")
+        }
+    }
+
+    impl From<LinearChainRequest<NodeId>> for Event {
+        fn from(_request: LinearChainRequest<NodeId>) -> Self {
+            unimplemented!("not implemented for account RPC tests")
+        }
+    }
+
+    impl From<MetricsRequest> for Event {
+        fn from(_request: MetricsRequest) -> Self {
+            unimplemented!("not implemented for account RPC tests")
+        }
+    }
+
+    impl From<NetworkInfoRequest<NodeId>> for Event {
+        fn from(_request: NetworkInfoRequest<NodeId>) -> Self {
+            unimplemented!("not implemented for account RPC tests")
+        }
+    }
+
+    impl Display for Event {
+        fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+            match self {
+                Event::RpcServer(event) => write!(formatter, "rpc server: {}", event),
+                Event::DeployAcceptor(event) => write!(formatter, "deploy acceptor: {}", event),
+                Event::RpcServerAnnouncement(ann) => {
+                    write!(formatter, "rpc server announcement: {}", ann)
+                }
+                Event::DeployAcceptorAnnouncement(ann) => {
+                    write!(formatter, "deploy acceptor announcement: {}", ann)
+                }
+                Event::BlockProposerRequest(req) => {
+                    write!(formatter, "block proposer request: {}", req)
+                }
+                Event::ControlAnnouncement(ctrl_ann) => write!(formatter, "control: {}", ctrl_ann),
+            }
+        }
+    }
+
+    /// A reactor passing the deploys submitted to the RPC server to a deploy acceptor, standing in
+    /// for the block proposer by reporting whether its buffer is full.
+    struct Reactor {
+        rpc_server: RpcServer,
+        deploy_acceptor: DeployAcceptor,
+        deploy_buffer_full: bool,
+    }
+
+    impl reactor::Reactor for Reactor {
+        type Event = Event;
+        /// Whether the deploy buffer is full.
+        type Config = bool;
+        type Error = prometheus::Error;
+
+        fn new(
+            deploy_buffer_full: Self::Config,
+            _registry: &Registry,
+            _event_queue: EventQueueHandle<Self::Event>,
+            _rng: &mut NodeRng,
+        ) -> Result<(Self, Effects<Self::Event>), Self::Error> {
+            let chainspec = Arc::new(Chainspec::from_resources("local"));
+            let rpc_server = RpcServer {
+                chainspec: Arc::clone(&chainspec),
+            };
+            let deploy_acceptor =
+                DeployAcceptor::new(deploy_acceptor::Config::new(false), chainspec, 0);
+
+            let reactor = Reactor {
+                rpc_server,
+                deploy_acceptor,
+                deploy_buffer_full,
+            };
+            Ok((reactor, Effects::new()))
+        }
+
+        fn dispatch_event(
+            &mut self,
+            effect_builder: EffectBuilder<Self::Event>,
+            rng: &mut NodeRng,
+            event: Event,
+        ) -> Effects<Self::Event> {
+            match event {
+                Event::RpcServer(event) => reactor::wrap_effects(
+                    Event::RpcServer,
+                    self.rpc_server.handle_event(effect_builder, rng, event),
+                ),
+                Event::DeployAcceptor(event) => reactor::wrap_effects(
+                    Event::DeployAcceptor,
+                    self.deploy_acceptor
+                        .handle_event(effect_builder, rng, event),
+                ),
+                Event::RpcServerAnnouncement(RpcServerAnnouncement::DeployReceived {
+                    deploy,
+                    responder,
+                }) => {
+                    let event = deploy_acceptor::Event::Accept {
+                        deploy,
+                        source: Source::<NodeId>::Client,
+                        responder,
                     };
-                    Ok(response_builder.success(result)?)
+                    self.dispatch_event(effect_builder, rng, Event::DeployAcceptor(event))
                 }
-                Err(error) => {
-                    info!(
-                        %deploy_hash,
-                        %error,
-                        "the deploy submitted by the client was invalid",
-                    );
-                    Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::InvalidDeploy as i64,
-                        error.to_string(),
-                    ))?)
+                Event::DeployAcceptorAnnouncement(_) => Effects::new(),
+                Event::BlockProposerRequest(BlockProposerRequest::IsBufferFull { responder }) => {
+                    responder.respond(self.deploy_buffer_full).ignore()
+                }
+                Event::BlockProposerRequest(request) => {
+                    unimplemented!("not implemented for account RPC tests: {}", request)
+                }
+                Event::ControlAnnouncement(ctrl_ann) => {
+                    panic!("unhandled control announcement: {}", ctrl_ann)
                 }
             }
         }
-        .boxed()
+
+        fn maybe_exit(&self) -> Option<crate::reactor::ReactorExit> {
+            None
+        }
+    }
+
+    /// Submits the deploy to the reactor as the `account_put_deploy` RPC does, processing events
+    /// until the deploy acceptor's verdict reaches the responder.
+    async fn submit_deploy(
+        runner: &mut Runner<Reactor>,
+        rng: &mut TestRng,
+        deploy: Deploy,
+    ) -> Result<(), deploy_acceptor::Error> {
+        let (sender, mut receiver) = oneshot::channel();
+        runner
+            .process_injected_effects(|effect_builder| {
+                effect_builder
+                    .make_request(
+                        |responder| RpcRequest::SubmitDeploy {
+                            deploy: Box::new(deploy),
+                            responder,
+                        },
+                        QueueKind::Api,
+                    )
+                    .map(move |result| {
+                        let _ = sender.send(result);
+                    })
+                    .ignore()
+            })
+            .await;
+
+        let processing = async {
+            loop {
+                if let Some(result) = receiver.try_recv().expect("should not drop sender") {
+                    return result;
+                }
+                if runner.try_crank(rng).await.is_none() {
+                    time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        };
+        time::timeout(TIMEOUT, processing)
+            .await
+            .expect("should complete in time")
+    }
+
+    /// Creates a deploy acceptable under the local chainspec, using standard payment.
+    fn new_deploy(rng: &mut TestRng) -> Deploy {
+        let chainspec = Chainspec::from_resources("local");
+        Deploy::new(
+            Timestamp::now(),
+            TimeDiff::from(60_000),
+            1,
+            vec![],
+            chainspec.network_config.name,
+            ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: runtime_args! { "amount" => U512::from(2_500_000_000u64) },
+            },
+            ExecutableDeployItem::ModuleBytes {
+                module_bytes: Bytes::new(),
+                args: RuntimeArgs::new(),
+            },
+            &SecretKey::random(rng),
+        )
+    }
+
+    #[tokio::test]
+    async fn should_pass_rejection_of_submitted_deploy_to_responder() {
+        let mut rng = crate::new_rng();
+
+        let mut runner: Runner<Reactor> = Runner::new(false, &mut rng).await.unwrap();
+        let mut json_deploy = serde_json::to_value(&new_deploy(&mut rng)).unwrap();
+        json_deploy["hash"] = json!(DeployHash::random(&mut rng));
+        let tampered_deploy: Deploy = serde_json::from_value(json_deploy).unwrap();
+        let result = submit_deploy(&mut runner, &mut rng, tampered_deploy).await;
+        assert!(matches!(
+            result,
+            Err(deploy_acceptor::Error::InvalidDeploy(
+                DeployValidationFailure::InvalidDeployHash
+            ))
+        ));
+
+        let mut runner: Runner<Reactor> = Runner::new(true, &mut rng).await.unwrap();
+        let deploy = new_deploy(&mut rng);
+        let result = submit_deploy(&mut runner, &mut rng, deploy).await;
+        assert!(matches!(
+            result,
+            Err(deploy_acceptor::Error::DeployBufferFull)
+        ));
+    }
+
+    #[test]
+    fn should_return_rejection_reason_of_malformed_deploy() {
+        let mut rng = crate::new_rng();
+        let deploy = Deploy::random(&mut rng);
+        let mut json_deploy = serde_json::to_value(&deploy).unwrap();
+        json_deploy["hash"] = json!(DeployHash::random(&mut rng));
+        let malformed_deploy: Deploy = serde_json::from_value(json_deploy).unwrap();
+
+        let acceptance = malformed_deploy
+            .verify_hash()
+            .map_err(deploy_acceptor::Error::InvalidDeploy);
+        let error =
+            put_deploy_result_of(*malformed_deploy.id(), acceptance, ProtocolVersion::V1_0_0)
+                .unwrap_err();
+
+        let json_error = serde_json::to_value(&error).unwrap();
+        assert_eq!(json_error["code"], json!(ErrorCode::InvalidDeploy as i64));
+        assert_eq!(
+            json_error["data"],
+            serde_json::to_value(deploy_acceptor::Error::InvalidDeploy(
+                DeployValidationFailure::InvalidDeployHash
+            ))
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn should_return_hash_of_accepted_deploy() {
+        let mut rng = crate::new_rng();
+        let deploy = Deploy::random(&mut rng);
+        assert!(deploy.verify_hash().is_ok());
+
+        let result = put_deploy_result_of(*deploy.id(), Ok(()), ProtocolVersion::V1_0_0).unwrap();
+        assert_eq!(result.deploy_hash, *deploy.id());
+    }
+
+    #[test]
+    fn should_return_retryable_error_if_deploy_buffer_full() {
+        let mut rng = crate::new_rng();
+        let deploy = Deploy::random(&mut rng);
+
+        let error = put_deploy_result_of(
+            *deploy.id(),
+            Err(deploy_acceptor::Error::DeployBufferFull),
+            ProtocolVersion::V1_0_0,
+        )
+        .unwrap_err();
+
+        let json_error = serde_json::to_value(&error).unwrap();
+        assert_eq!(
+            json_error["code"],
+            json!(ErrorCode::DeployBufferFull as i64)
+        );
+        assert_eq!(
+            json_error["data"],
+            serde_json::to_value(deploy_acceptor::Error::DeployBufferFull).unwrap()
+        );
     }
 }
@@ -77,10 +77,12 @@ pub enum BlockIdentifier {
 }
 
 /// Params for "chain_get_block" RPC request.
+///
+/// If the params are omitted, the latest block is returned.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct GetBlockParams {
-    /// The block hash.
+    /// The block hash or height.
     pub block_identifier: BlockIdentifier,
 }
 
@@ -473,3 +475,329 @@ async fn get_block_with_metadata<REv: ReactorEventT>(
 
     Ok(maybe_result)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fmt::{self, Display, Formatter},
+        future::Future,
+        sync::Arc,
+        time::Duration,
+    };
+
+    use derive_more::From;
+    use futures::channel::oneshot;
+    use prometheus::Registry;
+    use serde_json::json;
+    use tempfile::TempDir;
+    use thiserror::Error;
+    use tokio::time;
+
+    use casper_types::EraId;
+
+    use super::*;
+    use crate::{
+        components::{
+            rpc_server::{self, RpcServer},
+            storage::{self, Storage},
+            Component,
+        },
+        effect::{
+            announcements::{ControlAnnouncement, RpcServerAnnouncement, StorageAnnouncement},
+            requests::{
+                ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest,
+                LinearChainRequest, MetricsRequest, NetworkInfoRequest, StorageRequest,
+            },
+            EffectExt, Effects,
+        },
+        reactor::{self, EventQueueHandle, ReactorEvent, Runner},
+        testing::TestRng,
+        types::{Chainspec, NodeId},
+        utils::{Loadable, WithDir},
+        NodeRng,
+    };
+
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Top-level event for the reactor.
+    #[derive(Debug, From, Serialize)]
+    #[must_use]
+    enum Event {
+        #[from]
+        RpcServer(#[serde(skip_serializing)] rpc_server::Event),
+        #[from]
+        Storage(#[serde(skip_serializing)] storage::Event),
+        #[from]
+        ControlAnnouncement(ControlAnnouncement),
+    }
+
+    impl ReactorEvent for Event {
+        fn as_control(&self) -> Option<&ControlAnnouncement> {
+            if let Self::ControlAnnouncement(ref ctrl_ann) = self {
+                Some(ctrl_ann)
+            } else {
+                None
+            }
+        }
+    }
+
+    impl From<RpcRequest<NodeId>> for Event {
+        fn from(request: RpcRequest<NodeId>) -> Self {
+            Event::RpcServer(rpc_server::Event::RpcRequest(request))
+        }
+    }
+
+    impl From<StorageRequest> for Event {
+        fn from(request: StorageRequest) -> Self {
+            Event::Storage(storage::Event::from(request))
+        }
+    }
+
+    impl From<RpcServerAnnouncement> for Event {
+        fn from(_announcement: RpcServerAnnouncement) -> Self {
+            unimplemented!("not implemented for chain RPC tests")
+        }
+    }
+
+    impl From<StorageAnnouncement> for Event {
+        fn from(_announcement: StorageAnnouncement) -> Self {
+            unimplemented!("not implemented for chain RPC tests")
+        }
+    }
+
+    impl From<ChainspecLoaderRequest> for Event {
+        fn from(_request: ChainspecLoaderRequest) -> Self {
+            unimplemented!("not implemented for chain RPC tests")
+        }
+    }
+
+    impl From<ContractRuntimeRequest> for Event {
+        fn from(_request: ContractRuntimeRequest) -> Self {
+            unimplemented!("not implemented for chain RPC tests")
+        }
+    }
+
+    impl From<ConsensusRequest> for Event {
+        fn from(_request: ConsensusRequest) -> Self {
+            unimplemented!("not implemented for chain RPC tests")
+        }
+    }
+
+    impl From<LinearChainRequest<NodeId>> for Event {
+        fn from(_request: LinearChainRequest<NodeId>) -> Self {
+            unimplemented!("not implemented for chain RPC tests")
+        }
+    }
+
+    impl From<MetricsRequest> for Event {
+        fn from(_request: MetricsRequest) -> Self {
+            unimplemented!("not implemented for chain RPC tests")
+        }
+    }
+
+    impl From<NetworkInfoRequest<NodeId>> for Event {
+        fn from(_request: NetworkInfoRequest<NodeId>) -> Self {
+            unimplemented!("not implemented for chain RPC tests")
+        }
+    }
+
+    impl Display for Event {
+        fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+            match self {
+                Event::RpcServer(event) => write!(formatter, "rpc server: {}", event),
+                Event::Storage(event) => write!(formatter, "storage: {}", event),
+                Event::ControlAnnouncement(ctrl_ann) => write!(formatter, "control: {}", ctrl_ann),
+            }
+        }
+    }
+
+    /// Error type returned by the test reactor.
+    #[derive(Debug, Error)]
+    enum Error {
+        #[error("prometheus (metrics) error: {0}")]
+        Metrics(#[from] prometheus::Error),
+        #[error("storage error: {0}")]
+        Storage(#[from] storage::Error),
+    }
+
+    /// A reactor passing the requests of the RPC server to storage, without listening for clients.
+    struct Reactor {
+        rpc_server: RpcServer,
+        storage: Storage,
+        _storage_tempdir: TempDir,
+    }
+
+    impl reactor::Reactor for Reactor {
+        type Event = Event;
+        type Config = ();
+        type Error = Error;
+
+        fn new(
+            _config: Self::Config,
+            registry: &Registry,
+            _event_queue: EventQueueHandle<Self::Event>,
+            _rng: &mut NodeRng,
+        ) -> Result<(Self, Effects<Self::Event>), Self::Error> {
+            let (storage_config, storage_tempdir) = storage::Config::default_for_tests();
+            let storage = Storage::new(
+                &WithDir::new(storage_tempdir.path(), storage_config),
+                None,
+                ProtocolVersion::from_parts(1, 0, 0),
+                false,
+                registry,
+            )?;
+            let rpc_server = RpcServer {
+                chainspec: Arc::new(Chainspec::from_resources("local")),
+            };
+
+            let reactor = Reactor {
+                rpc_server,
+                storage,
+                _storage_tempdir: storage_tempdir,
+            };
+            Ok((reactor, Effects::new()))
+        }
+
+        fn dispatch_event(
+            &mut self,
+            effect_builder: EffectBuilder<Self::Event>,
+            rng: &mut NodeRng,
+            event: Event,
+        ) -> Effects<Self::Event> {
+            match event {
+                Event::RpcServer(event) => reactor::wrap_effects(
+                    Event::RpcServer,
+                    self.rpc_server.handle_event(effect_builder, rng, event),
+                ),
+                Event::Storage(event) => reactor::wrap_effects(
+                    Event::Storage,
+                    self.storage.handle_event(effect_builder, rng, event),
+                ),
+                Event::ControlAnnouncement(ctrl_ann) => {
+                    panic!("unhandled control announcement: {}", ctrl_ann)
+                }
+            }
+        }
+
+        fn maybe_exit(&self) -> Option<crate::reactor::ReactorExit> {
+            None
+        }
+    }
+
+    /// Runs the future created by `f` on the reactor, processing events until it completes.
+    async fn run<T, F, Fut>(runner: &mut Runner<Reactor>, rng: &mut TestRng, f: F) -> T
+    where
+        F: FnOnce(EffectBuilder<Event>) -> Fut,
+        Fut: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (sender, mut receiver) = oneshot::channel();
+        runner
+            .process_injected_effects(|effect_builder| {
+                f(effect_builder)
+                    .map(move |output| {
+                        let _ = sender.send(output);
+                    })
+                    .ignore()
+            })
+            .await;
+
+        let processing = async {
+            loop {
+                if let Some(output) = receiver.try_recv().expect("should not drop sender") {
+                    return output;
+                }
+                if runner.try_crank(rng).await.is_none() {
+                    time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        };
+        time::timeout(TIMEOUT, processing)
+            .await
+            .expect("should complete in time")
+    }
+
+    #[tokio::test]
+    async fn should_get_same_block_by_hash_height_and_as_latest() {
+        let mut rng = crate::new_rng();
+        let mut runner: Runner<Reactor> = Runner::new((), &mut rng).await.unwrap();
+
+        let blocks: Vec<Block> = (0..3)
+            .map(|height| {
+                Block::random_with_specifics(
+                    &mut rng,
+                    EraId::from(0),
+                    height,
+                    ProtocolVersion::from_parts(1, 0, 0),
+                    false,
+                )
+            })
+            .collect();
+        for block in &blocks {
+            let block = Box::new(block.clone());
+            let stored = run(&mut runner, &mut rng, |effect_builder| {
+                effect_builder.put_block_to_storage(block)
+            })
+            .await;
+            assert!(stored);
+        }
+
+        let block = blocks[1].clone();
+        let block_hash = *block.hash();
+        let by_hash = run(&mut runner, &mut rng, move |effect_builder| {
+            get_block(Some(BlockIdentifier::Hash(block_hash)), effect_builder)
+        })
+        .await
+        .unwrap();
+        let block_height = block.height();
+        let by_height = run(&mut runner, &mut rng, move |effect_builder| {
+            get_block(Some(BlockIdentifier::Height(block_height)), effect_builder)
+        })
+        .await
+        .unwrap();
+        assert_eq!(by_hash.as_ref(), Some(&block));
+        assert_eq!(by_height.as_ref(), Some(&block));
+
+        // Without an identifier, the latest block is returned.
+        let latest = run(&mut runner, &mut rng, |effect_builder| {
+            get_block(None, effect_builder)
+        })
+        .await
+        .unwrap();
+        assert_eq!(latest.as_ref(), Some(&blocks[2]));
+
+        // Unknown blocks are not found.
+        let result = run(&mut runner, &mut rng, |effect_builder| {
+            get_block(Some(BlockIdentifier::Height(3)), effect_builder)
+        })
+        .await;
+        let json_error = serde_json::to_value(&result.unwrap_err()).unwrap();
+        assert_eq!(json_error["code"], json!(ErrorCode::NoSuchBlock as i64));
+    }
+
+    #[test]
+    fn should_require_exactly_one_block_identifier() {
+        let mut rng = crate::new_rng();
+        let block_hash = BlockHash::random(&mut rng);
+
+        let params: GetBlockParams =
+            serde_json::from_value(json!({ "block_identifier": { "Height": 1 } })).unwrap();
+        assert!(matches!(
+            params.block_identifier,
+            BlockIdentifier::Height(1)
+        ));
+        let params: GetBlockParams =
+            serde_json::from_value(json!({ "block_identifier": { "Hash": block_hash } })).unwrap();
+        assert!(
+            matches!(params.block_identifier, BlockIdentifier::Hash(hash) if hash == block_hash)
+        );
+
+        for block_identifier in &[json!({}), json!({ "Hash": block_hash, "Height": 1 })] {
+            let result = serde_json::from_value::<GetBlockParams>(json!({
+                "block_identifier": block_identifier
+            }));
+            assert!(result.is_err(), "{} should be rejected", block_identifier);
+        }
+    }
+}
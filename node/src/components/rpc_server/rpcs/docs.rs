@@ -21,7 +21,7 @@ use casper_types::ProtocolVersion;
 use super::{
     account::PutDeploy,
     chain::{GetBlock, GetBlockTransfers, GetStateRootHash},
-    info::{GetDeploy, GetPeers, GetStatus},
+    info::{EstimateGas, GetDeploy, GetDeployExecution, GetPeers, GetStatus},
     state::{GetAuctionInfo, GetBalance, GetItem},
     Error, ReactorEventT, RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
     RpcWithoutParamsExt,
@@ -76,9 +76,17 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
 
     schema.push_with_params::<PutDeploy>("receives a Deploy to be executed by the network");
     schema.push_with_params::<GetDeploy>("returns a Deploy from the network");
+    schema.push_with_params::<GetDeployExecution>(
+        "returns the result of executing a Deploy, along with the hash of the Block it was executed in",
+    );
+    schema.push_with_params::<EstimateGas>(
+        "returns the gas a Deploy would consume if executed against the latest global state, without committing its effects",
+    );
     schema.push_with_params::<GetAccountInfo>("returns an Account from the network");
     schema.push_with_params::<GetDictionaryItem>("returns an item from a Dictionary");
-    schema.push_without_params::<GetPeers>("returns a list of peers connected to the node");
+    schema.push_without_params::<GetPeers>(
+        "returns a list of peers connected to the node, along with the state of each connection",
+    );
     schema.push_without_params::<GetStatus>("returns the current status of the node");
     schema.push_with_optional_params::<GetBlock>("returns a Block from the network");
     schema.push_with_optional_params::<GetBlockTransfers>(
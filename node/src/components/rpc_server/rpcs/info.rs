@@ -3,7 +3,7 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::str;
+use std::{str, time::Duration};
 
 use futures::{future::BoxFuture, FutureExt};
 use http::Response;
@@ -14,7 +14,8 @@ use serde::{Deserialize, Serialize};
 use tracing::info;
 use warp_json_rpc::Builder;
 
-use casper_types::{ExecutionResult, ProtocolVersion};
+use casper_execution_engine::shared::{gas::Gas, motes::Motes};
+use casper_types::{ExecutionResult, ProtocolVersion, U512};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
@@ -22,9 +23,13 @@ use super::{
     RpcWithoutParamsExt,
 };
 use crate::{
+    components::rpc_server::EstimateGasError,
     effect::EffectBuilder,
     reactor::QueueKind,
-    types::{Block, BlockHash, Deploy, DeployHash, GetStatusResult, Item, PeersMap},
+    types::{
+        Block, BlockHash, ConnectionDirection, Deploy, DeployHash, DeployMetadata, GetStatusResult,
+        Item, NodeId, PeerConnection, PeerConnectionState, PeerConnections, PeersMap,
+    },
 };
 
 static GET_DEPLOY_PARAMS: Lazy<GetDeployParams> = Lazy::new(|| GetDeployParams {
@@ -38,9 +43,43 @@ static GET_DEPLOY_RESULT: Lazy<GetDeployResult> = Lazy::new(|| GetDeployResult {
         result: ExecutionResult::example().clone(),
     }],
 });
-static GET_PEERS_RESULT: Lazy<GetPeersResult> = Lazy::new(|| GetPeersResult {
+static GET_DEPLOY_EXECUTION_PARAMS: Lazy<GetDeployExecutionParams> =
+    Lazy::new(|| GetDeployExecutionParams {
+        deploy_hash: *Deploy::doc_example().id(),
+    });
+static GET_DEPLOY_EXECUTION_RESULT: Lazy<GetDeployExecutionResult> =
+    Lazy::new(|| GetDeployExecutionResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        deploy_hash: *Deploy::doc_example().id(),
+        execution_result: JsonExecutionResult {
+            block_hash: Block::doc_example().id(),
+            result: ExecutionResult::example().clone(),
+        },
+    });
+static ESTIMATE_GAS_PARAMS: Lazy<EstimateGasParams> = Lazy::new(|| EstimateGasParams {
+    deploy: Deploy::doc_example().clone(),
+});
+static ESTIMATE_GAS_RESULT: Lazy<EstimateGasResult> = Lazy::new(|| EstimateGasResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
-    peers: GetStatusResult::doc_example().peers.clone(),
+    deploy_hash: *Deploy::doc_example().id(),
+    gas: U512::from(123_456),
+    cost: U512::from(123_456),
+    execution_result: ExecutionResult::example().clone(),
+});
+static GET_PEERS_RESULT: Lazy<GetPeersResult> = Lazy::new(|| {
+    let connection = PeerConnection {
+        node_id: Some(*NodeId::doc_example()),
+        address: "127.0.0.1:54321".to_string(),
+        direction: ConnectionDirection::Outgoing,
+        state: PeerConnectionState::Connected,
+        age: Some(Duration::from_secs(5 * 60)),
+        round_trip_time: Some(Duration::from_millis(12)),
+    };
+    GetPeersResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        peers: GetStatusResult::doc_example().peers.clone(),
+        connections: PeerConnections::from(vec![connection]),
+    }
 });
 
 /// Params for "info_get_deploy" RPC request.
@@ -146,6 +185,233 @@ impl RpcWithParamsExt for GetDeploy {
     }
 }
 
+/// Params for "info_get_deploy_result" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDeployExecutionParams {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+}
+
+impl DocExample for GetDeployExecutionParams {
+    fn doc_example() -> &'static Self {
+        &*GET_DEPLOY_EXECUTION_PARAMS
+    }
+}
+
+/// Result for "info_get_deploy_result" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDeployExecutionResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// The execution result, along with the hash of the block in which the deploy was executed.
+    pub execution_result: JsonExecutionResult,
+}
+
+impl DocExample for GetDeployExecutionResult {
+    fn doc_example() -> &'static Self {
+        &*GET_DEPLOY_EXECUTION_RESULT
+    }
+}
+
+/// "info_get_deploy_result" RPC.
+pub struct GetDeployExecution {}
+
+impl RpcWithParams for GetDeployExecution {
+    const METHOD: &'static str = "info_get_deploy_result";
+    type RequestParams = GetDeployExecutionParams;
+    type ResponseResult = GetDeployExecutionResult;
+}
+
+impl RpcWithParamsExt for GetDeployExecution {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            // Try to get the deploy's metadata from storage.
+            let maybe_metadata = effect_builder
+                .make_request(
+                    |responder| RpcRequest::GetDeploy {
+                        hash: params.deploy_hash,
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await
+                .map(|(_deploy, metadata)| metadata);
+
+            let execution_result = match execution_result_of(maybe_metadata) {
+                Ok(execution_result) => execution_result,
+                Err(error_code) => {
+                    info!(
+                        "failed to get execution result of {} from storage",
+                        params.deploy_hash
+                    );
+                    let message = match error_code {
+                        ErrorCode::DeployNotExecuted => "deploy not yet executed",
+                        _ => "deploy not known",
+                    };
+                    return Ok(response_builder
+                        .error(warp_json_rpc::Error::custom(error_code as i64, message))?);
+                }
+            };
+
+            let result = Self::ResponseResult {
+                api_version,
+                deploy_hash: params.deploy_hash,
+                execution_result,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Returns the execution result of a deploy, given its metadata if the deploy is known.
+///
+/// If the deploy has been executed in more than one block, the result from any one of them is
+/// returned.
+fn execution_result_of(
+    maybe_metadata: Option<DeployMetadata>,
+) -> Result<JsonExecutionResult, ErrorCode> {
+    let metadata = maybe_metadata.ok_or(ErrorCode::NoSuchDeploy)?;
+    metadata
+        .execution_results
+        .into_iter()
+        .next()
+        .map(|(block_hash, result)| JsonExecutionResult { block_hash, result })
+        .ok_or(ErrorCode::DeployNotExecuted)
+}
+
+/// Params for "info_estimate_gas" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EstimateGasParams {
+    /// The deploy to be executed.
+    pub deploy: Deploy,
+}
+
+impl DocExample for EstimateGasParams {
+    fn doc_example() -> &'static Self {
+        &*ESTIMATE_GAS_PARAMS
+    }
+}
+
+/// Result for "info_estimate_gas" RPC response.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EstimateGasResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+    /// The gas consumed by executing the deploy.
+    #[schemars(with = "String")]
+    pub gas: U512,
+    /// The cost in motes of the consumed gas at the deploy's gas price.
+    #[schemars(with = "String")]
+    pub cost: U512,
+    /// The result of executing the deploy against the global state of the latest block.  Its
+    /// effects were not committed.
+    pub execution_result: ExecutionResult,
+}
+
+impl DocExample for EstimateGasResult {
+    fn doc_example() -> &'static Self {
+        &*ESTIMATE_GAS_RESULT
+    }
+}
+
+/// "info_estimate_gas" RPC.
+pub struct EstimateGas {}
+
+impl RpcWithParams for EstimateGas {
+    const METHOD: &'static str = "info_estimate_gas";
+    type RequestParams = EstimateGasParams;
+    type ResponseResult = EstimateGasResult;
+}
+
+impl RpcWithParamsExt for EstimateGas {
+    fn handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        response_builder: Builder,
+        params: Self::RequestParams,
+        api_version: ProtocolVersion,
+    ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
+        async move {
+            let deploy = params.deploy;
+            let deploy_hash = *deploy.id();
+            let gas_price = deploy.header().gas_price();
+
+            // Check the deploy is acceptable, then execute it without committing its effects.
+            let result = effect_builder
+                .make_request(
+                    |responder| RpcRequest::EstimateGas {
+                        deploy: Box::new(deploy),
+                        responder,
+                    },
+                    QueueKind::Api,
+                )
+                .await;
+
+            let execution_result = match result {
+                Ok(execution_result) => execution_result,
+                Err(error) => {
+                    info!(%deploy_hash, %error, "failed to estimate gas");
+                    let error_code = match error {
+                        EstimateGasError::NoBlock => ErrorCode::NoSuchBlock,
+                        EstimateGasError::InvalidDeploy(_) => ErrorCode::InvalidDeploy,
+                        EstimateGasError::Execution(_) => ErrorCode::EstimateGasFailed,
+                    };
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        error_code as i64,
+                        error.to_string(),
+                    ))?);
+                }
+            };
+
+            let (gas, cost) = match gas_and_cost_of(&execution_result, gas_price) {
+                Some(gas_and_cost) => gas_and_cost,
+                None => {
+                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                        ErrorCode::EstimateGasFailed as i64,
+                        "cost in motes overflowed",
+                    ))?);
+                }
+            };
+
+            let result = Self::ResponseResult {
+                api_version,
+                deploy_hash,
+                gas,
+                cost,
+                execution_result,
+            };
+            Ok(response_builder.success(result)?)
+        }
+        .boxed()
+    }
+}
+
+/// Returns the gas consumed by an execution, along with its cost in motes at the given gas price.
+///
+/// Returns `None` if the cost in motes overflows.
+fn gas_and_cost_of(execution_result: &ExecutionResult, gas_price: u64) -> Option<(U512, U512)> {
+    let gas = match execution_result {
+        ExecutionResult::Success { cost, .. } | ExecutionResult::Failure { cost, .. } => *cost,
+    };
+    let motes = Motes::from_gas(Gas::new(gas), gas_price)?;
+    Some((gas, motes.value()))
+}
+
 /// Result for "info_get_peers" RPC response.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -155,6 +421,9 @@ pub struct GetPeersResult {
     pub api_version: ProtocolVersion,
     /// The node ID and network address of each connected peer.
     pub peers: PeersMap,
+    /// The direction, state and age of each connection to a peer, including the ones still being
+    /// established.
+    pub connections: PeerConnections,
 }
 
 impl DocExample for GetPeersResult {
@@ -178,7 +447,7 @@ impl RpcWithoutParamsExt for GetPeers {
         api_version: ProtocolVersion,
     ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
         async move {
-            let peers = effect_builder
+            let (peers, connections) = effect_builder
                 .make_request(
                     |responder| RpcRequest::GetPeers { responder },
                     QueueKind::Api,
@@ -188,6 +457,7 @@ impl RpcWithoutParamsExt for GetPeers {
             let result = Self::ResponseResult {
                 api_version,
                 peers: PeersMap::from(peers),
+                connections: PeerConnections::from(connections),
             };
             Ok(response_builder.success(result)?)
         }
@@ -225,3 +495,90 @@ impl RpcWithoutParamsExt for GetStatus {
         .boxed()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use prometheus::Registry;
+    use rand::Rng;
+
+    use super::*;
+    use crate::{
+        components::storage::{self, Storage},
+        effect::requests::StorageRequest,
+        testing::{ComponentHarness, UnitTestEvent},
+        utils::WithDir,
+    };
+
+    /// Retrieves the execution result of a deploy via a storage component.
+    fn get_execution_result(
+        harness: &mut ComponentHarness<UnitTestEvent>,
+        storage: &mut Storage,
+        deploy_hash: DeployHash,
+    ) -> Result<JsonExecutionResult, ErrorCode> {
+        let maybe_deploy_and_metadata = harness.send_request(storage, |responder| {
+            StorageRequest::GetDeployAndMetadata {
+                deploy_hash,
+                responder,
+            }
+            .into()
+        });
+        execution_result_of(maybe_deploy_and_metadata.map(|(_deploy, metadata)| metadata))
+    }
+
+    #[test]
+    fn should_get_execution_result_once_deploy_executed() {
+        let mut harness = ComponentHarness::default();
+        let (storage_config, _temp_dir) = storage::Config::default_for_tests();
+        let mut storage = Storage::new(
+            &WithDir::new(harness.tmp.path(), storage_config),
+            None,
+            ProtocolVersion::from_parts(1, 0, 0),
+            false,
+            &Registry::new(),
+        )
+        .unwrap();
+
+        let deploy = Deploy::random(&mut harness.rng);
+        let deploy_hash = *deploy.id();
+        assert!(matches!(
+            get_execution_result(&mut harness, &mut storage, deploy_hash),
+            Err(ErrorCode::NoSuchDeploy)
+        ));
+
+        // Submit the deploy.
+        let stored: bool = harness.send_request(&mut storage, |responder| {
+            StorageRequest::PutDeploy {
+                deploy: Box::new(deploy),
+                responder,
+            }
+            .into()
+        });
+        assert!(stored);
+        assert!(matches!(
+            get_execution_result(&mut harness, &mut storage, deploy_hash),
+            Err(ErrorCode::DeployNotExecuted)
+        ));
+
+        // Execute a block containing the deploy.
+        let block = Block::random(&mut harness.rng);
+        let block_hash = *block.hash();
+        let result: ExecutionResult = harness.rng.gen();
+        let mut execution_results = HashMap::new();
+        execution_results.insert(deploy_hash, result.clone());
+        harness.send_request(&mut storage, |responder| {
+            StorageRequest::PutExecutionResults {
+                block_hash: Box::new(block_hash),
+                execution_results,
+                responder,
+            }
+            .into()
+        });
+
+        let execution_result = get_execution_result(&mut harness, &mut storage, deploy_hash)
+            .unwrap_or_else(|_| panic!("should have execution result"));
+        assert_eq!(execution_result.block_hash, block_hash);
+        assert_eq!(execution_result.result, result);
+    }
+}
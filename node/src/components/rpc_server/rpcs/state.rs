@@ -15,11 +15,16 @@ use tracing::{error, info};
 use warp_json_rpc::Builder;
 
 use casper_execution_engine::{
-    core::engine_state::{BalanceResult, GetBidsResult, QueryResult},
+    core::{
+        engine_state::{self, BalanceResult, GetBidsResult, QueryResult},
+        execution,
+    },
     shared::stored_value::StoredValue as DomainStoredValue,
+    storage::trie::merkle_proof::TrieMerkleProof,
 };
 use casper_types::{
-    bytesrepr::ToBytes, CLValue, Key, ProtocolVersion, PublicKey, SecretKey, URef, U512,
+    account::AccountHash, bytesrepr::ToBytes, CLValue, Key, ProtocolVersion, PublicKey, SecretKey,
+    URef, U512,
 };
 
 use super::{
@@ -210,7 +215,8 @@ impl RpcWithParamsExt for GetItem {
 pub struct GetBalanceParams {
     /// The hash of state root.
     pub state_root_hash: Digest,
-    /// Formatted URef.
+    /// Formatted URef of the purse, or formatted account hash of the account whose main purse is
+    /// to be queried.
     pub purse_uref: String,
 }
 
@@ -227,7 +233,7 @@ pub struct GetBalanceResult {
     /// The RPC API version.
     #[schemars(with = "String")]
     pub api_version: ProtocolVersion,
-    /// The balance value.
+    /// The balance value, in motes.
     pub balance_value: U512,
     /// The merkle proof.
     pub merkle_proof: String,
@@ -256,18 +262,33 @@ impl RpcWithParamsExt for GetBalance {
         api_version: ProtocolVersion,
     ) -> BoxFuture<'static, Result<Response<Body>, Error>> {
         async move {
-            // Try to parse the purse's URef from the params.
-            let purse_uref = match URef::from_formatted_str(&params.purse_uref)
-                .map_err(|error| format!("failed to parse purse_uref: {:?}", error))
-            {
+            // Try to parse the purse's URef from the params, or else the hash of the account whose
+            // main purse is to be queried.
+            let purse_uref = match URef::from_formatted_str(&params.purse_uref) {
                 Ok(uref) => uref,
-                Err(error_msg) => {
-                    info!("{}", error_msg);
-                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::ParseGetBalanceURef as i64,
-                        error_msg,
-                    ))?);
-                }
+                Err(error) => match AccountHash::from_formatted_str(&params.purse_uref) {
+                    Ok(account_hash) => {
+                        match get_main_purse(effect_builder, params.state_root_hash, account_hash)
+                            .await
+                        {
+                            Ok(main_purse) => main_purse,
+                            Err((error_code, error_msg)) => {
+                                info!("{}", error_msg);
+                                return Ok(response_builder.error(
+                                    warp_json_rpc::Error::custom(error_code as i64, error_msg),
+                                )?);
+                            }
+                        }
+                    }
+                    Err(_) => {
+                        let error_msg = format!("failed to parse purse_uref: {:?}", error);
+                        info!("{}", error_msg);
+                        return Ok(response_builder.error(warp_json_rpc::Error::custom(
+                            ErrorCode::ParseGetBalanceURef as i64,
+                            error_msg,
+                        ))?);
+                    }
+                },
             };
 
             // Get the balance.
@@ -282,23 +303,12 @@ impl RpcWithParamsExt for GetBalance {
                 )
                 .await;
 
-            let (balance_value, balance_proof) = match balance_result {
-                Ok(BalanceResult::Success { motes, proof }) => (motes, proof),
-                Ok(balance_result) => {
-                    let error_msg = format!("get-balance failed: {:?}", balance_result);
-                    info!("{}", error_msg);
-                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::GetBalanceFailed as i64,
-                        error_msg,
-                    ))?);
-                }
-                Err(error) => {
-                    let error_msg = format!("get-balance failed to execute: {}", error);
+            let (balance_value, balance_proof) = match extract_balance_result(balance_result) {
+                Ok(tuple) => tuple,
+                Err((error_code, error_msg)) => {
                     info!("{}", error_msg);
-                    return Ok(response_builder.error(warp_json_rpc::Error::custom(
-                        ErrorCode::GetBalanceFailedToExecute as i64,
-                        error_msg,
-                    ))?);
+                    return Ok(response_builder
+                        .error(warp_json_rpc::Error::custom(error_code as i64, error_msg))?);
                 }
             };
 
@@ -324,6 +334,55 @@ impl RpcWithParamsExt for GetBalance {
     }
 }
 
+/// Gets the main purse of the given account.
+async fn get_main_purse<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+    account_hash: AccountHash,
+) -> Result<URef, (ErrorCode, String)> {
+    let query_result = effect_builder
+        .make_request(
+            |responder| RpcRequest::QueryGlobalState {
+                state_root_hash,
+                base_key: Key::Account(account_hash),
+                path: vec![],
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await;
+
+    match common::extract_query_result(query_result) {
+        Ok((StoredValue::Account(account), _proof_bytes)) => Ok(account.main_purse()),
+        Ok(_) | Err((ErrorCode::QueryFailed, _)) => {
+            let error_msg = format!("get-balance failed to get account {}", account_hash);
+            Err((ErrorCode::NoSuchAccount, error_msg))
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Extracts the balance and its proof from the result of a balance query.
+fn extract_balance_result(
+    balance_result: Result<BalanceResult, engine_state::Error>,
+) -> Result<(U512, Box<TrieMerkleProof<Key, DomainStoredValue>>), (ErrorCode, String)> {
+    match balance_result {
+        Ok(BalanceResult::Success { motes, proof }) => Ok((motes, proof)),
+        Ok(BalanceResult::RootNotFound) => {
+            let error_msg = "get-balance failed: state root hash not found".to_string();
+            Err((ErrorCode::GetBalanceFailed, error_msg))
+        }
+        Err(engine_state::Error::Exec(execution::Error::KeyNotFound(key))) => {
+            let error_msg = format!("get-balance failed: no purse found at {}", key);
+            Err((ErrorCode::NoSuchPurse, error_msg))
+        }
+        Err(error) => {
+            let error_msg = format!("get-balance failed to execute: {}", error);
+            Err((ErrorCode::GetBalanceFailedToExecute, error_msg))
+        }
+    }
+}
+
 /// Params for "state_get_auction_info" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -830,3 +889,130 @@ impl RpcWithParamsExt for GetDictionaryItem {
         .boxed()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use casper_execution_engine::core::engine_state::{
+        genesis::GenesisResult, BalanceRequest, QueryRequest,
+    };
+    use prometheus::Registry;
+    use rand::Rng;
+    use tempfile::TempDir;
+
+    use casper_types::AccessRights;
+
+    use super::*;
+    use crate::{
+        components::contract_runtime::{self, ContractRuntime},
+        testing::TestRng,
+        types::Chainspec,
+        utils::{Loadable, WithDir},
+        StorageConfig,
+    };
+
+    /// Creates a contract runtime and commits genesis using the "local" chainspec, returning the
+    /// genesis state root hash.
+    fn commit_genesis() -> (ContractRuntime, Arc<Chainspec>, Digest, TempDir) {
+        let (storage_config, temp_dir) = StorageConfig::default_for_tests();
+        let chainspec = Arc::new(Chainspec::from_resources("local"));
+        let contract_runtime = ContractRuntime::new(
+            Digest::default(),
+            None,
+            chainspec.protocol_config.version,
+            chainspec.deploy_config.native_transfer_gas_price,
+            WithDir::new(temp_dir.path(), storage_config),
+            &contract_runtime::Config::default(),
+            &Registry::new(),
+        )
+        .unwrap();
+
+        let genesis_state_root_hash = match contract_runtime.commit_genesis(Arc::clone(&chainspec))
+        {
+            Ok(GenesisResult::Success {
+                post_state_hash, ..
+            }) => Digest::from(post_state_hash),
+            result => panic!("failed to commit genesis: {:?}", result),
+        };
+        (
+            contract_runtime,
+            chainspec,
+            genesis_state_root_hash,
+            temp_dir,
+        )
+    }
+
+    #[test]
+    fn should_query_account_at_genesis_state_root() {
+        let mut rng = TestRng::new();
+        let (contract_runtime, chainspec, genesis_state_root_hash, _temp_dir) = commit_genesis();
+        let public_key = chainspec.network_config.accounts_config.accounts()[0].public_key();
+        let account_key = Key::Account(public_key.to_account_hash());
+
+        // Querying a genesis account at the genesis state root should succeed.
+        let query_result = contract_runtime.run_query(QueryRequest::new(
+            genesis_state_root_hash.into(),
+            account_key,
+            vec![],
+        ));
+        match common::extract_query_result(query_result) {
+            Ok((StoredValue::Account(_), _proof_bytes)) => (),
+            Ok((stored_value, _proof_bytes)) => panic!("expected account, got {:?}", stored_value),
+            Err((_error_code, error_msg)) => panic!("{}", error_msg),
+        }
+
+        // Querying at an unknown state root should fail with a distinct error.
+        let query_result = contract_runtime.run_query(QueryRequest::new(
+            Digest::random(&mut rng).into(),
+            account_key,
+            vec![],
+        ));
+        assert!(matches!(
+            common::extract_query_result(query_result),
+            Err((ErrorCode::NoSuchStateRoot, _))
+        ));
+    }
+
+    #[test]
+    fn should_get_balance_of_genesis_account() {
+        let mut rng = TestRng::new();
+        let (contract_runtime, chainspec, genesis_state_root_hash, _temp_dir) = commit_genesis();
+        let account_config = &chainspec.network_config.accounts_config.accounts()[0];
+        let account_key = Key::Account(account_config.public_key().to_account_hash());
+
+        let query_result = contract_runtime.run_query(QueryRequest::new(
+            genesis_state_root_hash.into(),
+            account_key,
+            vec![],
+        ));
+        let main_purse = match common::extract_query_result(query_result) {
+            Ok((StoredValue::Account(account), _proof_bytes)) => account.main_purse(),
+            Ok((stored_value, _proof_bytes)) => panic!("expected account, got {:?}", stored_value),
+            Err((_error_code, error_msg)) => panic!("{}", error_msg),
+        };
+
+        // The balance of the account's main purse should be its initial funding.
+        let balance_result = contract_runtime.get_balance(BalanceRequest::new(
+            genesis_state_root_hash.into(),
+            main_purse,
+        ));
+        match extract_balance_result(balance_result) {
+            Ok((balance_value, _proof)) => {
+                assert_eq!(balance_value, account_config.balance().value())
+            }
+            Err((_error_code, error_msg)) => panic!("{}", error_msg),
+        }
+
+        // Querying an unknown purse should fail with a distinct error.
+        let unknown_purse = URef::new(rng.gen(), AccessRights::READ_ADD_WRITE);
+        let balance_result = contract_runtime.get_balance(BalanceRequest::new(
+            genesis_state_root_hash.into(),
+            unknown_purse,
+        ));
+        assert!(matches!(
+            extract_balance_result(balance_result),
+            Err((ErrorCode::NoSuchPurse, _))
+        ));
+    }
+}
@@ -29,10 +29,14 @@ mod counting_format;
 mod error;
 mod event;
 mod gossiped_address;
+mod keepalive;
 mod limiter;
 mod message;
 mod message_pack_format;
 mod outgoing;
+mod outgoing_queue;
+mod peer_selection;
+mod reputation;
 mod symmetry;
 pub(crate) mod tasks;
 #[cfg(test)]
@@ -49,23 +53,18 @@ use std::{
     time::{Duration, Instant},
 };
 
-use casper_types::{EraId, PublicKey};
+use casper_types::{EraId, ProtocolVersion, PublicKey};
 use datasize::DataSize;
-use futures::{future::BoxFuture, FutureExt};
-use openssl::{error::ErrorStack as OpenSslErrorStack, pkey};
+use futures::{
+    future::{self, BoxFuture},
+    FutureExt,
+};
+use openssl::{error::ErrorStack as OpenSslErrorStack, pkey, x509::X509};
 use pkey::{PKey, Private};
 use prometheus::Registry;
-use rand::seq::IteratorRandom;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use tokio::{
-    net::TcpStream,
-    sync::{
-        mpsc::{self, UnboundedSender},
-        watch,
-    },
-    task::JoinHandle,
-};
+use tokio::{net::TcpStream, sync::watch, task::JoinHandle, time};
 use tokio_openssl::SslStream;
 use tokio_util::codec::LengthDelimitedCodec;
 use tracing::{debug, error, info, trace, warn, Instrument, Span};
@@ -74,17 +73,21 @@ use self::{
     counting_format::{ConnectionId, CountingFormat, Role},
     error::{ConnectionError, Result},
     event::{IncomingConnection, OutgoingConnection},
+    keepalive::{KeepaliveAction, Keepalives},
     limiter::Limiter,
     message::ConsensusKeyPair,
     message_pack_format::MessagePackFormat,
     outgoing::{DialOutcome, DialRequest, OutgoingConfig, OutgoingManager},
+    outgoing_queue::{Enqueued, QueueSender, SendError},
+    reputation::Reputations,
     symmetry::ConnectionSymmetry,
     tasks::NetworkContext,
 };
 pub(crate) use self::{
     event::Event,
     gossiped_address::GossipedAddress,
-    message::{Message, MessageKind, Payload},
+    message::{Message, MessageKind, Payload, KEEPALIVE_PROTOCOL_VERSION},
+    peer_selection::{PeerSelection, PeerSelectionStrategy},
 };
 use super::consensus;
 use crate::{
@@ -98,9 +101,9 @@ use crate::{
         EffectBuilder, EffectExt, Effects,
     },
     reactor::{EventQueueHandle, Finalize, ReactorEvent},
-    tls::{self, TlsCert, ValidationError},
-    types::NodeId,
-    utils::{self, display_error, WithDir},
+    tls::{self, CertFingerprint, TlsCert, ValidationError},
+    types::{ConnectionDirection, NodeId, PeerConnection, PeerConnectionState, TimeDiff},
+    utils::{self, display_error, External, LoadError, WithDir},
     NodeRng,
 };
 use chain_info::ChainInfo;
@@ -112,6 +115,9 @@ const MAX_ASYMMETRIC_TIME: Duration = Duration::from_secs(60);
 const MAX_METRICS_DROP_ATTEMPTS: usize = 25;
 const DROP_RETRY_DELAY: Duration = Duration::from_millis(100);
 
+/// How long to wait for the disconnect messages to be sent to all peers when shutting down.
+const DISCONNECT_FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Duration peers are kept on the block list, before being redeemed.
 const BLOCKLIST_RETAIN_DURATION: Duration = Duration::from_secs(60 * 10);
 
@@ -132,9 +138,14 @@ const SYMMETRY_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
 
 #[derive(Clone, DataSize, Debug)]
 pub struct OutgoingHandle<P> {
-    #[data_size(skip)] // Unfortunately, there is no way to inspect an `UnboundedSender`.
-    sender: UnboundedSender<Arc<Message<P>>>,
+    #[data_size(skip)] // The queue is shared with the sending task and bounded in size.
+    sender: QueueSender<P>,
     peer_addr: SocketAddr,
+    /// Protocol version the peer announced in its handshake.
+    peer_protocol_version: ProtocolVersion,
+    /// The time the connection was established.
+    #[data_size(skip)]
+    connected_since: Instant,
 }
 
 impl<P> Display for OutgoingHandle<P> {
@@ -158,6 +169,10 @@ where
     outgoing_manager: OutgoingManager<OutgoingHandle<P>, ConnectionError>,
     /// Tracks whether a connection is symmetric or not.
     connection_symmetries: HashMap<NodeId, ConnectionSymmetry>,
+    /// Established incoming connections, along with the peer's ID and the time they were
+    /// established.
+    #[data_size(skip)]
+    incoming_connections: HashMap<SocketAddr, (NodeId, Instant)>,
 
     /// Channel signaling a shutdown of the small network.
     // Note: This channel is closed when `SmallNetwork` is dropped, signalling the receivers that
@@ -171,6 +186,16 @@ where
     /// Join handle for the server thread.
     #[data_size(skip)]
     server_join_handle: Option<JoinHandle<()>>,
+    /// Listener bound on construction, served by the server thread once the component is
+    /// initialized.
+    #[data_size(skip)]
+    listener: Option<tokio::net::TcpListener>,
+
+    /// Known addresses, learned once the component is initialized.
+    #[data_size(skip)]
+    known_addresses: HashSet<SocketAddr>,
+    /// The era active at startup, if given.
+    initial_era: Option<EraId>,
 
     /// Networking metrics.
     #[data_size(skip)]
@@ -191,6 +216,16 @@ where
     /// This is not incoming bandwidth but an independent resource estimate.
     #[data_size(skip)]
     incoming_limiter: Box<dyn Limiter>,
+
+    /// The strategy used to select the peers a message is gossiped to.
+    #[data_size(skip)]
+    peer_selection: Box<dyn PeerSelectionStrategy>,
+
+    /// Reputations of peers, used to ban misbehaving peers.
+    reputations: Reputations,
+
+    /// Keepalives sent to peers, used to detect unresponsive peers.
+    keepalives: Keepalives,
 }
 
 impl<REv, P> SmallNetwork<REv, P>
@@ -204,7 +239,9 @@ where
         + From<ChainspecLoaderRequest>,
 {
     /// Creates a new small network component instance.
-    #[allow(clippy::type_complexity)]
+    ///
+    /// The listener is bound right away, but connections are only accepted and made once the
+    /// component is initialized.
     pub(crate) fn new<C: Into<ChainInfo>>(
         event_queue: EventQueueHandle<REv>,
         cfg: Config,
@@ -213,7 +250,7 @@ where
         small_network_identity: SmallNetworkIdentity,
         chain_info_source: C,
         initial_era: Option<EraId>,
-    ) -> Result<(SmallNetwork<REv, P>, Effects<Event<P>>)> {
+    ) -> Result<SmallNetwork<REv, P>> {
         let mut known_addresses = HashSet::new();
         for address in &cfg.known_addresses {
             match utils::resolve_address(address) {
@@ -234,6 +271,46 @@ where
             return Err(Error::EmptyKnownHosts);
         }
 
+        let peer_selection = cfg.peer_selection.strategy();
+        let reputations = Reputations::new(
+            cfg.reputation_ban_threshold,
+            cfg.ban_duration.into(),
+            cfg.reputation_decay_interval.into(),
+        );
+        if cfg.keepalive_interval != TimeDiff::from(0)
+            && cfg.keepalive_jitter >= cfg.keepalive_interval
+        {
+            return Err(Error::InvalidKeepaliveJitter {
+                interval: cfg.keepalive_interval,
+                jitter: cfg.keepalive_jitter,
+            });
+        }
+        let keepalives = Keepalives::new(
+            cfg.keepalive_interval.into(),
+            cfg.keepalive_jitter.into(),
+            cfg.keepalive_timeout.into(),
+        );
+
+        let pinned_certificates = if cfg.pinned_certificates.is_empty() {
+            None
+        } else {
+            let mut fingerprints = cfg
+                .pinned_certificates
+                .iter()
+                .map(|hex_encoded| {
+                    CertFingerprint::from_hex(hex_encoded)
+                        .ok_or_else(|| Error::InvalidPinnedCertificate(hex_encoded.clone()))
+                })
+                .collect::<Result<HashSet<_>>>()?;
+            // Our own certificate is always accepted, so that loopback connections are detected.
+            fingerprints.insert(small_network_identity.tls_certificate.fingerprint());
+            info!(
+                count = fingerprints.len(),
+                "peer certificate pinning enabled"
+            );
+            Some(fingerprints)
+        };
+
         let outgoing_limiter: Box<dyn Limiter> = if cfg.max_outgoing_byte_rate_non_validators == 0 {
             Box::new(limiter::Unlimited)
         } else {
@@ -298,66 +375,35 @@ where
             chain_info: chain_info_source.into(),
             public_addr,
             consensus_keys,
+            pinned_certificates,
         });
 
-        // Run the server task.
-        // We spawn it ourselves instead of through an effect to get a hold of the join handle,
-        // which we need to shutdown cleanly later on.
-        info!(%local_addr, %public_addr, "starting server background task");
+        info!(%local_addr, %public_addr, cert_fingerprint=%context.our_cert.fingerprint(), "bound network listener");
+        let listener =
+            tokio::net::TcpListener::from_std(listener).map_err(Error::ListenerConversion)?;
 
-        let (server_shutdown_sender, server_shutdown_receiver) = watch::channel(());
-        let shutdown_receiver = server_shutdown_receiver.clone();
-        let server_join_handle = tokio::spawn(tasks::server(
-            context.clone(),
-            tokio::net::TcpListener::from_std(listener).map_err(Error::ListenerConversion)?,
-            server_shutdown_receiver,
-        ));
+        let (server_shutdown_sender, shutdown_receiver) = watch::channel(());
 
-        let mut component = SmallNetwork {
+        Ok(SmallNetwork {
             cfg,
             context,
             outgoing_manager,
             connection_symmetries: HashMap::new(),
+            incoming_connections: HashMap::new(),
             shutdown_sender: Some(server_shutdown_sender),
             shutdown_receiver,
-            server_join_handle: Some(server_join_handle),
+            server_join_handle: None,
+            listener: Some(listener),
+            known_addresses,
+            initial_era,
             net_metrics,
             highest_era_seen: EraId::new(0),
             outgoing_limiter,
             incoming_limiter,
-        };
-
-        let effect_builder = EffectBuilder::new(event_queue);
-
-        // Learn all known addresses and mark them as unforgettable.
-        let now = Instant::now();
-        let dial_requests: Vec<_> = known_addresses
-            .into_iter()
-            .filter_map(|addr| component.outgoing_manager.learn_addr(addr, true, now))
-            .collect();
-
-        // Initialize the known validator set with the active era, if given.
-        let mut effects = initial_era
-            .map(|era_id| component.handle_active_era_change(effect_builder, era_id))
-            .unwrap_or_default();
-
-        effects.extend(component.process_dial_requests(dial_requests));
-
-        // Start broadcasting our public listening address.
-        effects.extend(
-            effect_builder
-                .set_timeout(component.cfg.initial_gossip_delay.into())
-                .event(|_| Event::GossipOurAddress),
-        );
-
-        // Start regular housekeeping of the outgoing connections.
-        effects.extend(
-            effect_builder
-                .set_timeout(OUTGOING_MANAGER_SWEEP_INTERVAL)
-                .event(|_| Event::SweepOutgoing),
-        );
-
-        Ok((component, effects))
+            peer_selection,
+            reputations,
+            keepalives,
+        })
     }
 
     /// Queues a message to be sent to all nodes.
@@ -367,19 +413,21 @@ where
         }
     }
 
-    /// Queues a message to `count` random nodes on the network.
+    /// Queues a message to `count` nodes on the network, selected by the configured peer selection
+    /// strategy.
     fn gossip_message(
-        &self,
+        &mut self,
         rng: &mut NodeRng,
         msg: Arc<Message<P>>,
         count: usize,
         exclude: HashSet<NodeId>,
     ) -> HashSet<NodeId> {
-        let peer_ids = self
+        let candidates = self
             .outgoing_manager
             .connected_peers()
             .filter(|peer_id| !exclude.contains(peer_id))
-            .choose_multiple(rng, count);
+            .collect();
+        let peer_ids = self.peer_selection.select(rng, candidates, count);
 
         if peer_ids.len() != count {
             // TODO - set this to `warn!` once we are normally testing with networks large enough to
@@ -404,11 +452,27 @@ where
     fn send_message(&self, dest: NodeId, msg: Arc<Message<P>>) {
         // Try to send the message.
         if let Some(connection) = self.outgoing_manager.get_route(dest) {
-            if let Err(msg) = connection.sender.send(msg) {
-                // We lost the connection, but that fact has not reached us yet.
-                warn!(our_id=%self.context.our_id, %dest, ?msg, "dropped outgoing message, lost connection");
-            } else {
-                self.net_metrics.queued_messages.inc();
+            if connection.peer_protocol_version < msg.min_protocol_version() {
+                // The peer would not be able to deserialize the message and drop the connection.
+                debug!(our_id=%self.context.our_id, %dest, peer_protocol_version=%connection.peer_protocol_version, ?msg, "not sending message unsupported by peer");
+                return;
+            }
+            match connection.sender.send(msg) {
+                Ok(Enqueued::Queued) => (),
+                Ok(Enqueued::ReplacedOldest) => {
+                    debug!(our_id=%self.context.our_id, %dest, "outgoing queue full, dropped oldest gossip message");
+                }
+                Ok(Enqueued::Shed) => {
+                    debug!(our_id=%self.context.our_id, %dest, "outgoing queue full, dropped gossip message");
+                }
+                Err(SendError::Disconnected(msg)) => {
+                    // We lost the connection, but that fact has not reached us yet.
+                    warn!(our_id=%self.context.our_id, %dest, ?msg, "dropped outgoing message, lost connection");
+                }
+                Err(SendError::Overloaded(msg)) => {
+                    // The sending task will notice the closed queue and drop the connection.
+                    warn!(our_id=%self.context.our_id, %dest, ?msg, "dropped outgoing message, queue overloaded, disconnecting");
+                }
             }
         } else {
             // We are not connected, so the reconnection is likely already in progress.
@@ -459,7 +523,15 @@ where
                 peer_consensus_public_key,
                 stream,
             } => {
+                if self.reputations.is_banned(&peer_id, Instant::now()) {
+                    // Dropping the stream closes the connection.
+                    info!("refusing incoming connection from banned peer");
+                    return Effects::new();
+                }
+
                 info!("new incoming connection established");
+                self.incoming_connections
+                    .insert(peer_addr, (peer_id, Instant::now()));
 
                 // Learn the address the peer gave us.
                 let dial_requests =
@@ -521,6 +593,8 @@ where
                 }
             }
 
+            self.incoming_connections.remove(&peer_addr);
+
             // Update the connection symmetries.
             self.connection_symmetries
                 .entry(*peer_id)
@@ -548,7 +622,9 @@ where
             | ConnectionError::InvalidConsensusCertificate(_) => false,
 
             // Definitely something we want to avoid.
-            ConnectionError::WrongNetwork(_) => true,
+            ConnectionError::WrongNetwork(_)
+            | ConnectionError::PeerCertificateNotPinned(_)
+            | ConnectionError::PeerBanned => true,
         }
     }
 
@@ -603,12 +679,41 @@ where
                 peer_addr,
                 peer_id,
                 peer_consensus_public_key,
+                peer_protocol_version,
                 sink,
             } => {
+                if self.reputations.is_banned(&peer_id, now) {
+                    // Dropping the sink closes the connection.
+                    info!("refusing outgoing connection to banned peer");
+                    let mut requests = Vec::new();
+                    requests.extend(self.outgoing_manager.block_addr(peer_addr, now).into_iter());
+                    requests.extend(
+                        self.outgoing_manager
+                            .handle_dial_outcome(DialOutcome::Failed {
+                                addr: peer_addr,
+                                error: ConnectionError::PeerBanned,
+                                when: now,
+                            })
+                            .into_iter(),
+                    );
+                    return self.process_dial_requests(requests);
+                }
+
                 info!("new outgoing connection established");
 
-                let (sender, receiver) = mpsc::unbounded_channel();
-                let handle = OutgoingHandle { peer_addr, sender };
+                let (sender, receiver) = outgoing_queue::outgoing_queue(
+                    self.cfg.max_outgoing_queue_size,
+                    self.net_metrics
+                        .out_dropped_messages
+                        .with_label_values(&[&peer_id.to_string()]),
+                    self.net_metrics.queued_messages.clone(),
+                );
+                let handle = OutgoingHandle {
+                    sender,
+                    peer_addr,
+                    peer_protocol_version,
+                    connected_since: Instant::now(),
+                };
 
                 let request = self
                     .outgoing_manager
@@ -636,7 +741,6 @@ where
                         sink,
                         self.outgoing_limiter
                             .create_handle(peer_id, peer_consensus_public_key),
-                        self.net_metrics.queued_messages.clone(),
                     )
                     .instrument(span)
                     .event(move |_| Event::OutgoingDropped {
@@ -705,6 +809,24 @@ where
         self.process_dial_requests(dial_requests)
     }
 
+    /// Disconnects from a peer which was just banned.
+    ///
+    /// The outgoing connection is closed, while messages received on any incoming connection are
+    /// discarded for the duration of the ban.
+    fn ban_peer(&mut self, peer_id: NodeId, now: Instant) -> Effects<Event<P>> {
+        self.net_metrics
+            .banned_peers
+            .set(self.reputations.ban_count() as i64);
+
+        if let Some(addr) = self.outgoing_manager.get_addr(peer_id) {
+            let requests = self.outgoing_manager.block_addr(addr, now);
+            self.process_dial_requests(requests)
+        } else {
+            // No outgoing connection to close, reconnection attempts will be refused.
+            Effects::new()
+        }
+    }
+
     /// Processes a set of `DialRequest`s, updating the component and emitting needed effects.
     fn process_dial_requests<T>(&mut self, requests: T) -> Effects<Event<P>>
     where
@@ -746,6 +868,11 @@ where
     where
         REv: From<NetworkAnnouncement<NodeId, P>>,
     {
+        if self.reputations.is_banned(&peer_id, Instant::now()) {
+            span.in_scope(|| debug!("dropping message from banned peer"));
+            return Effects::new();
+        }
+
         span.in_scope(|| match msg {
             Message::Handshake { .. } => {
                 // We should never receive a handshake message on an established connection. Simply
@@ -757,9 +884,66 @@ where
             Message::Payload(payload) => effect_builder
                 .announce_message_received(peer_id, payload)
                 .ignore(),
+            Message::Disconnect => {
+                // The message reader closes the connection on receipt of a disconnect message, so
+                // it should never be passed on to the component.
+                warn!("received unexpected disconnect");
+                Effects::new()
+            }
+            Message::Keepalive { nonce } => {
+                // Connections are unidirectional, so the acknowledgement is sent on our outgoing
+                // connection to the peer.
+                self.send_message(peer_id, Arc::new(Message::KeepaliveAck { nonce }));
+                Effects::new()
+            }
+            Message::KeepaliveAck { nonce } => {
+                if let Some(rtt) = self.keepalives.record_ack(&peer_id, nonce, Instant::now()) {
+                    self.net_metrics.keepalive_rtt.observe(rtt.as_secs_f64());
+                }
+                Effects::new()
+            }
         })
     }
 
+    /// Sends keepalives to peers connected in both directions, and closes the outgoing connection
+    /// to every peer that did not acknowledge a keepalive in time.
+    ///
+    /// Peers speaking a protocol version without keepalives are never sent any, and thus never
+    /// disconnected for not acknowledging them.
+    fn check_keepalives(&mut self, rng: &mut NodeRng, now: Instant) {
+        let outgoing_manager = &self.outgoing_manager;
+        let keepalive_peers: Vec<_> = self
+            .connection_symmetries
+            .iter()
+            .filter(|(_, sym)| sym.is_symmetric())
+            .map(|(peer_id, _)| *peer_id)
+            .filter(|peer_id| {
+                outgoing_manager
+                    .get_route(*peer_id)
+                    .map_or(false, |handle| {
+                        handle.peer_protocol_version >= KEEPALIVE_PROTOCOL_VERSION
+                    })
+            })
+            .collect();
+
+        for action in self.keepalives.sweep(rng, keepalive_peers, now) {
+            match action {
+                KeepaliveAction::Send { peer_id, nonce } => {
+                    self.send_message(peer_id, Arc::new(Message::Keepalive { nonce }))
+                }
+                KeepaliveAction::Disconnect(peer_id) => {
+                    warn!(%peer_id, "keepalive not acknowledged in time, closing connection");
+                    self.net_metrics.keepalive_disconnects.inc();
+                    // The sending task stops once the queue is closed, after which the connection
+                    // is re-established as for any other dropped connection.
+                    if let Some(handle) = self.outgoing_manager.get_route(peer_id) {
+                        handle.sender.close();
+                    }
+                }
+            }
+        }
+    }
+
     /// Handle the change of the active era.
     fn handle_active_era_change(
         &mut self,
@@ -849,6 +1033,51 @@ where
         ret
     }
 
+    /// Returns all connections to peers, including outgoing connections still being established.
+    ///
+    /// Incoming connections are only known once their handshake has completed.
+    pub(crate) fn peer_connections(&self) -> Vec<PeerConnection<NodeId>> {
+        let now = Instant::now();
+        let mut ret = Vec::new();
+
+        for node_id in self.outgoing_manager.connected_peers() {
+            if let Some(handle) = self.outgoing_manager.get_route(node_id) {
+                ret.push(PeerConnection {
+                    node_id: Some(node_id),
+                    address: handle.peer_addr.to_string(),
+                    direction: ConnectionDirection::Outgoing,
+                    state: PeerConnectionState::Connected,
+                    age: Some(now.saturating_duration_since(handle.connected_since)),
+                    round_trip_time: self.keepalives.rtt(&node_id),
+                });
+            }
+        }
+
+        for (addr, since) in self.outgoing_manager.connecting_addrs() {
+            ret.push(PeerConnection {
+                node_id: None,
+                address: addr.to_string(),
+                direction: ConnectionDirection::Outgoing,
+                state: PeerConnectionState::Connecting,
+                age: Some(now.saturating_duration_since(since)),
+                round_trip_time: None,
+            });
+        }
+
+        for (addr, (node_id, since)) in &self.incoming_connections {
+            ret.push(PeerConnection {
+                node_id: Some(*node_id),
+                address: addr.to_string(),
+                direction: ConnectionDirection::Incoming,
+                state: PeerConnectionState::Connected,
+                age: Some(now.saturating_duration_since(*since)),
+                round_trip_time: self.keepalives.rtt(node_id),
+            });
+        }
+
+        ret
+    }
+
     /// Returns the node id of this network node.
     #[cfg(test)]
     pub(crate) fn node_id(&self) -> NodeId {
@@ -896,6 +1125,10 @@ where
     type Event = Event<P>;
     type ConstructionError = Infallible;
 
+    fn name(&self) -> &str {
+        "small_network"
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -963,6 +1196,16 @@ where
                 NetworkInfoRequest::GetPeers { responder } => {
                     responder.respond(self.peers()).ignore()
                 }
+                NetworkInfoRequest::GetPeerConnections { responder } => {
+                    responder.respond(self.peer_connections()).ignore()
+                }
+                NetworkInfoRequest::GetPeerProtocolVersion { peer, responder } => {
+                    let protocol_version = self
+                        .outgoing_manager
+                        .get_route(peer)
+                        .map(|handle| handle.peer_protocol_version);
+                    responder.respond(protocol_version).ignore()
+                }
             },
             Event::PeerAddressReceived(gossiped_address) => {
                 let requests = self.outgoing_manager.learn_addr(
@@ -973,15 +1216,16 @@ where
                 self.process_dial_requests(requests)
             }
             Event::BlocklistAnnouncement(BlocklistAnnouncement::OffenseCommitted(peer_id)) => {
-                // TODO: We do not have a proper by-node-ID blocklist, but rather only block the
-                // current outgoing address of a peer.
                 warn!(%peer_id, "adding peer to blocklist after transgression");
-
-                if let Some(addr) = self.outgoing_manager.get_addr(*peer_id) {
-                    let requests = self.outgoing_manager.block_addr(addr, Instant::now());
-                    self.process_dial_requests(requests)
+                let now = Instant::now();
+                self.reputations.ban(*peer_id, now);
+                self.ban_peer(*peer_id, now)
+            }
+            Event::BlocklistAnnouncement(BlocklistAnnouncement::ProtocolViolation(peer_id)) => {
+                let now = Instant::now();
+                if self.reputations.record_violation(*peer_id, now) {
+                    self.ban_peer(*peer_id, now)
                 } else {
-                    // Peer got away with it, no longer an outgoing connection.
                     Effects::new()
                 }
             }
@@ -1011,8 +1255,14 @@ where
             }
             Event::SweepOutgoing => {
                 let now = Instant::now();
+                self.reputations.expire_bans(now);
+                self.net_metrics
+                    .banned_peers
+                    .set(self.reputations.ban_count() as i64);
+
                 let requests = self.outgoing_manager.perform_housekeeping(now);
                 let mut effects = self.process_dial_requests(requests);
+                self.check_keepalives(rng, now);
 
                 effects.extend(
                     effect_builder
@@ -1057,17 +1307,97 @@ where
             }
         }
     }
+
+    fn init(&mut self, effect_builder: EffectBuilder<REv>) -> Effects<Self::Event> {
+        // Run the server task.
+        // We spawn it ourselves instead of through an effect to get a hold of the join handle,
+        // which we need to shutdown cleanly later on.
+        if let Some(listener) = self.listener.take() {
+            info!(public_addr=%self.context.public_addr, "starting server background task");
+            self.server_join_handle = Some(tokio::spawn(tasks::server(
+                self.context.clone(),
+                listener,
+                self.shutdown_receiver.clone(),
+            )));
+        }
+
+        // Learn all known addresses and mark them as unforgettable.
+        let now = Instant::now();
+        let known_addresses = mem::take(&mut self.known_addresses);
+        let dial_requests: Vec<_> = known_addresses
+            .into_iter()
+            .filter_map(|addr| self.outgoing_manager.learn_addr(addr, true, now))
+            .collect();
+
+        // Initialize the known validator set with the active era, if given.
+        let mut effects = self
+            .initial_era
+            .take()
+            .map(|era_id| self.handle_active_era_change(effect_builder, era_id))
+            .unwrap_or_default();
+
+        effects.extend(self.process_dial_requests(dial_requests));
+
+        // Start broadcasting our public listening address.
+        effects.extend(
+            effect_builder
+                .set_timeout(self.cfg.initial_gossip_delay.into())
+                .event(|_| Event::GossipOurAddress),
+        );
+
+        // Start regular housekeeping of the outgoing connections.
+        effects.extend(
+            effect_builder
+                .set_timeout(OUTGOING_MANAGER_SWEEP_INTERVAL)
+                .event(|_| Event::SweepOutgoing),
+        );
+
+        effects
+    }
+
+    fn on_shutdown(&mut self, _effect_builder: EffectBuilder<REv>) -> Effects<Self::Event> {
+        // Let all connected peers know we are going away, so they can close their connections
+        // immediately rather than waiting for them to time out.
+        debug!(our_id=%self.context.our_id, "sending disconnect to all peers");
+        self.broadcast_message(Arc::new(Message::Disconnect));
+
+        // The messages are sent by the tasks of the outgoing connections, so wait for them to go
+        // out before the node exits.
+        let queues: Vec<QueueSender<P>> = self
+            .outgoing_manager
+            .connected_peers()
+            .filter_map(|peer_id| self.outgoing_manager.get_route(peer_id))
+            .map(|handle| handle.sender.clone())
+            .collect();
+        let our_id = self.context.our_id;
+        async move {
+            let all_drained = future::join_all(queues.iter().map(QueueSender::drained));
+            if time::timeout(DISCONNECT_FLUSH_TIMEOUT, all_drained)
+                .await
+                .is_err()
+            {
+                warn!(%our_id, "timed out sending disconnect to all peers");
+            }
+        }
+        .ignore()
+    }
 }
 
 #[derive(Debug, Error)]
 pub enum SmallNetworkIdentityError {
     #[error("could not generate TLS certificate: {0}")]
     CouldNotGenerateTlsCertificate(OpenSslErrorStack),
+    #[error("could not load TLS certificate: {0}")]
+    CouldNotLoadTlsCertificate(LoadError<anyhow::Error>),
+    #[error("could not load TLS secret key: {0}")]
+    CouldNotLoadTlsSecretKey(LoadError<anyhow::Error>),
+    #[error("TLS secret key does not match the TLS certificate")]
+    MismatchedTlsSecretKey,
     #[error(transparent)]
     ValidationError(#[from] ValidationError),
 }
 
-/// An ephemeral [PKey<Private>] and [TlsCert] that identifies this node
+/// A [PKey<Private>] and [TlsCert] that identifies this node
 #[derive(DataSize, Debug, Clone)]
 pub struct SmallNetworkIdentity {
     secret_key: Arc<PKey<Private>>,
@@ -1075,9 +1405,50 @@ pub struct SmallNetworkIdentity {
 }
 
 impl SmallNetworkIdentity {
+    /// Generates a new, ephemeral identity.
     pub fn new() -> result::Result<Self, SmallNetworkIdentityError> {
         let (not_yet_validated_x509_cert, secret_key) = tls::generate_node_cert()
             .map_err(SmallNetworkIdentityError::CouldNotGenerateTlsCertificate)?;
+        Self::from_parts(not_yet_validated_x509_cert, secret_key)
+    }
+
+    /// Loads the identity from the TLS certificate and secret key given in the config, so that it
+    /// persists across restarts.
+    ///
+    /// If neither is given, generates an ephemeral identity instead.
+    pub(crate) fn from_config(
+        config: WithDir<Config>,
+    ) -> result::Result<Self, SmallNetworkIdentityError> {
+        let (root, config) = config.into_parts();
+        if matches!(
+            (&config.tls_certificate_path, &config.tls_secret_key_path),
+            (External::Missing, External::Missing)
+        ) {
+            info!("no TLS certificate configured, generating an ephemeral one");
+            return Self::new();
+        }
+
+        let not_yet_validated_x509_cert = config
+            .tls_certificate_path
+            .load(&root)
+            .map_err(SmallNetworkIdentityError::CouldNotLoadTlsCertificate)?;
+        let secret_key = config
+            .tls_secret_key_path
+            .load(&root)
+            .map_err(SmallNetworkIdentityError::CouldNotLoadTlsSecretKey)?;
+        Self::from_parts(not_yet_validated_x509_cert, secret_key)
+    }
+
+    fn from_parts(
+        not_yet_validated_x509_cert: X509,
+        secret_key: PKey<Private>,
+    ) -> result::Result<Self, SmallNetworkIdentityError> {
+        let key_matches = not_yet_validated_x509_cert
+            .public_key()
+            .map_or(false, |public_key| public_key.public_eq(&secret_key));
+        if !key_matches {
+            return Err(SmallNetworkIdentityError::MismatchedTlsSecretKey);
+        }
         let tls_certificate = tls::validate_cert(not_yet_validated_x509_cert)?;
         Ok(SmallNetworkIdentity {
             secret_key: Arc::new(secret_key),
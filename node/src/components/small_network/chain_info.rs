@@ -31,12 +31,15 @@ pub(crate) struct ChainInfo {
 
 impl ChainInfo {
     /// Create an instance of `ChainInfo` for testing.
+    ///
+    /// Test nodes speak the latest protocol version, so that they exchange all kinds of messages,
+    /// including keepalives.
     #[cfg(test)]
     pub fn create_for_testing() -> Self {
         ChainInfo {
             network_name: "rust-tests-network".to_string(),
             maximum_net_message_size: 22 * 1024 * 1024, // Hardcoded at 22M.
-            protocol_version: ProtocolVersion::V1_0_0,
+            protocol_version: ProtocolVersion::from_parts(1, 4, 0),
         }
     }
 
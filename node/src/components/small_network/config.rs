@@ -3,9 +3,14 @@ use std::net::{Ipv4Addr, SocketAddr};
 use std::time::Duration;
 
 use datasize::DataSize;
+use openssl::{
+    pkey::{PKey, Private},
+    x509::X509,
+};
 use serde::{Deserialize, Serialize};
 
-use crate::types::TimeDiff;
+use super::PeerSelection;
+use crate::{types::TimeDiff, utils::External};
 
 /// Default binding address.
 ///
@@ -20,6 +25,27 @@ const DEFAULT_PUBLIC_ADDRESS: &str = "127.0.0.1:0";
 /// Default interval for gossiping network addresses.
 const DEFAULT_GOSSIP_INTERVAL: Duration = Duration::from_secs(30);
 
+/// Default maximum number of messages queued for sending to a single peer.
+const DEFAULT_MAX_OUTGOING_QUEUE_SIZE: usize = 10_000;
+
+/// Default interval at which keepalives are sent to peers.
+const DEFAULT_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Default maximum deviation from the keepalive interval of the time between two keepalives.
+const DEFAULT_KEEPALIVE_JITTER: Duration = Duration::from_secs(5);
+
+/// Default time after which a peer not acknowledging a keepalive is disconnected.
+const DEFAULT_KEEPALIVE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Default reputation score below which a peer is banned.
+const DEFAULT_REPUTATION_BAN_THRESHOLD: i32 = -5;
+
+/// Default duration of a ban.
+const DEFAULT_BAN_DURATION: Duration = Duration::from_secs(10 * 60);
+
+/// Default time after which a reputation score recovers by one point.
+const DEFAULT_REPUTATION_DECAY_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
 // Default values for networking configuration:
 impl Default for Config {
     fn default() -> Self {
@@ -33,6 +59,17 @@ impl Default for Config {
             max_addr_pending_time: TimeDiff::from_seconds(60),
             max_outgoing_byte_rate_non_validators: 0,
             max_incoming_message_rate_non_validators: 0,
+            peer_selection: PeerSelection::default(),
+            max_outgoing_queue_size: DEFAULT_MAX_OUTGOING_QUEUE_SIZE,
+            pinned_certificates: Vec::new(),
+            tls_certificate_path: External::Missing,
+            tls_secret_key_path: External::Missing,
+            reputation_ban_threshold: DEFAULT_REPUTATION_BAN_THRESHOLD,
+            reputation_decay_interval: DEFAULT_REPUTATION_DECAY_INTERVAL.into(),
+            ban_duration: DEFAULT_BAN_DURATION.into(),
+            keepalive_interval: DEFAULT_KEEPALIVE_INTERVAL.into(),
+            keepalive_jitter: DEFAULT_KEEPALIVE_JITTER.into(),
+            keepalive_timeout: DEFAULT_KEEPALIVE_TIMEOUT.into(),
         }
     }
 }
@@ -63,6 +100,83 @@ pub struct Config {
     pub max_outgoing_byte_rate_non_validators: u32,
     /// Maximum of requests answered from non-validating peers. Unlimited if 0.
     pub max_incoming_message_rate_non_validators: u32,
+    /// Strategy used to select the peers a message is gossiped to.
+    #[serde(default)]
+    pub peer_selection: PeerSelection,
+    /// Maximum number of messages queued for sending to a single peer. Unlimited if 0.
+    ///
+    /// Once the queue is full, the oldest queued gossip message is dropped to make room. If there
+    /// is no gossip message to drop, the connection to the peer is closed.
+    #[serde(default = "default_max_outgoing_queue_size")]
+    pub max_outgoing_queue_size: usize,
+    /// Hex encoded SHA-512 fingerprints of the TLS certificates peers are allowed to present.
+    ///
+    /// If empty, certificate pinning is disabled and any valid certificate is accepted.
+    #[serde(default)]
+    pub pinned_certificates: Vec<String>,
+    /// Path to the PEM encoded TLS certificate identifying this node to its peers.
+    ///
+    /// Must be given together with `tls_secret_key_path`.  If neither is given, a new certificate
+    /// is generated on every start, so the node's certificate cannot be pinned by its peers.
+    #[serde(default)]
+    #[data_size(skip)]
+    pub tls_certificate_path: External<X509>,
+    /// Path to the PEM encoded secret key of the certificate at `tls_certificate_path`.
+    #[serde(default)]
+    #[data_size(skip)]
+    pub tls_secret_key_path: External<PKey<Private>>,
+    /// Reputation score below which a peer is banned.
+    ///
+    /// Every peer starts with a score of 0, which is decremented for each protocol violation it
+    /// commits.
+    #[serde(default = "default_reputation_ban_threshold")]
+    pub reputation_ban_threshold: i32,
+    /// Time after which a negative reputation score recovers by one point. Scores never recover
+    /// if 0.
+    #[serde(default = "default_reputation_decay_interval")]
+    pub reputation_decay_interval: TimeDiff,
+    /// How long a banned peer is disconnected and refused reconnection.
+    #[serde(default = "default_ban_duration")]
+    pub ban_duration: TimeDiff,
+    /// Interval at which keepalives are sent to peers connected in both directions. Keepalives
+    /// are disabled if 0.
+    #[serde(default = "default_keepalive_interval")]
+    pub keepalive_interval: TimeDiff,
+    /// Maximum deviation from `keepalive_interval` of the time between two keepalives to a peer,
+    /// sampled anew for every keepalive. Must be smaller than `keepalive_interval`.
+    #[serde(default = "default_keepalive_jitter")]
+    pub keepalive_jitter: TimeDiff,
+    /// Time after which the connection to a peer not acknowledging a keepalive is closed.
+    #[serde(default = "default_keepalive_timeout")]
+    pub keepalive_timeout: TimeDiff,
+}
+
+fn default_max_outgoing_queue_size() -> usize {
+    DEFAULT_MAX_OUTGOING_QUEUE_SIZE
+}
+
+fn default_reputation_ban_threshold() -> i32 {
+    DEFAULT_REPUTATION_BAN_THRESHOLD
+}
+
+fn default_ban_duration() -> TimeDiff {
+    DEFAULT_BAN_DURATION.into()
+}
+
+fn default_reputation_decay_interval() -> TimeDiff {
+    DEFAULT_REPUTATION_DECAY_INTERVAL.into()
+}
+
+fn default_keepalive_interval() -> TimeDiff {
+    DEFAULT_KEEPALIVE_INTERVAL.into()
+}
+
+fn default_keepalive_jitter() -> TimeDiff {
+    DEFAULT_KEEPALIVE_JITTER.into()
+}
+
+fn default_keepalive_timeout() -> TimeDiff {
+    DEFAULT_KEEPALIVE_TIMEOUT.into()
 }
 
 #[cfg(test)]
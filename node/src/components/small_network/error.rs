@@ -8,7 +8,8 @@ use thiserror::Error;
 
 use crate::{
     crypto,
-    tls::ValidationError,
+    tls::{CertFingerprint, ValidationError},
+    types::TimeDiff,
     utils::{LoadError, Loadable, ResolveAddressError},
 };
 
@@ -59,6 +60,19 @@ pub enum Error {
         #[source]
         io::Error,
     ),
+    /// A pinned certificate fingerprint is not a hex encoded SHA-512 digest.
+    #[error("invalid pinned certificate fingerprint: {0}")]
+    InvalidPinnedCertificate(String),
+    /// The keepalive jitter is not smaller than the keepalive interval.
+    #[error(
+        "keepalive jitter of {jitter} must be smaller than the keepalive interval of {interval}"
+    )]
+    InvalidKeepaliveJitter {
+        /// The configured keepalive interval.
+        interval: TimeDiff,
+        /// The configured keepalive jitter.
+        jitter: TimeDiff,
+    },
     /// Could not resolve root node address.
     #[error("failed to resolve network address")]
     ResolveAddr(
@@ -128,6 +142,12 @@ pub enum ConnectionError {
     /// TLS validation error.
     #[error("TLS validation error of peer certificate")]
     PeerCertificateInvalid(#[source] ValidationError),
+    /// Certificate pinning is enabled and the peer certificate is not pinned.
+    #[error("peer certificate {0} is not pinned")]
+    PeerCertificateNotPinned(CertFingerprint),
+    /// The peer is currently banned.
+    #[error("peer is banned")]
+    PeerBanned,
     /// Failed to send handshake.
     #[error("handshake send failed")]
     HandshakeSend(
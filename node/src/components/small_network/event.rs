@@ -6,7 +6,7 @@ use std::{
     sync::Arc,
 };
 
-use casper_types::PublicKey;
+use casper_types::{ProtocolVersion, PublicKey};
 use derive_more::From;
 use futures::stream::{SplitSink, SplitStream};
 use serde::Serialize;
@@ -271,6 +271,8 @@ pub enum OutgoingConnection<P> {
         peer_id: NodeId,
         /// The public key the peer is validating with, if any.
         peer_consensus_public_key: Option<PublicKey>,
+        /// Protocol version the peer announced in its handshake.
+        peer_protocol_version: ProtocolVersion,
         /// Sink for outgoing messages.
         #[serde(skip_serializing)]
         sink: SplitSink<FramedTransport<P>, Arc<Message<P>>>,
@@ -293,6 +295,7 @@ impl<P> Display for OutgoingConnection<P> {
                 peer_addr,
                 peer_id,
                 peer_consensus_public_key,
+                peer_protocol_version: _,
                 sink: _,
             } => {
                 write!(f, "connection established to {}/{}", peer_addr, peer_id)?;
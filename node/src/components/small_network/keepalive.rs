@@ -0,0 +1,282 @@
+//! Connection keepalives
+//!
+//! Peers connected to us in both directions are periodically sent a keepalive on our outgoing
+//! connection, which they acknowledge on theirs. If a peer does not acknowledge a keepalive before
+//! the configured timeout, it is considered unresponsive and our outgoing connection to it is
+//! closed, which results in a reconnection attempt.
+//!
+//! The time between keepalives is randomized within the configured jitter, sampled anew for every
+//! keepalive, so that the keepalives of many nodes do not end up being sent in synchronized bursts.
+//!
+//! Keepalives are part of the networking protocol and never passed on to other components, so they
+//! are independent of any ping-like payload messages exchanged by those. They are only sent to
+//! peers speaking a protocol version which supports them.
+//!
+//! The round-trip time of every acknowledged keepalive is measured. Keepalives that are never
+//! acknowledged do not yield a measurement, they result in a disconnect instead.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use datasize::DataSize;
+use rand::Rng;
+
+use crate::{types::NodeId, NodeRng};
+
+/// Action to be taken by the networking component after checking the keepalives.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum KeepaliveAction {
+    /// A keepalive with the given nonce should be sent to the peer.
+    Send { peer_id: NodeId, nonce: u64 },
+    /// The peer did not acknowledge a keepalive in time, the connection to it should be closed.
+    Disconnect(NodeId),
+}
+
+/// Keepalive state of a single peer.
+#[derive(DataSize, Debug)]
+struct PeerKeepalive {
+    /// Time the peer last acknowledged a keepalive, or the time we started tracking it.
+    #[data_size(skip)]
+    last_ack: Instant,
+    /// Nonce of the keepalive awaiting acknowledgement, along with the time it was sent.
+    #[data_size(skip)]
+    pending: Option<(u64, Instant)>,
+    /// Round-trip time of the most recently acknowledged keepalive.
+    #[data_size(skip)]
+    last_rtt: Option<Duration>,
+    /// Time the next keepalive is due, sampled once no keepalive is pending anymore.
+    #[data_size(skip)]
+    next_due: Option<Instant>,
+}
+
+/// Tracks the keepalives sent to peers.
+#[derive(DataSize, Debug)]
+pub(super) struct Keepalives {
+    /// Keepalive state of all tracked peers.
+    peers: HashMap<NodeId, PeerKeepalive>,
+    /// Interval at which keepalives are sent, disabled if zero.
+    interval: Duration,
+    /// Maximum deviation from `interval` of the time between two keepalives.
+    jitter: Duration,
+    /// Time after which an unacknowledged keepalive causes a disconnect.
+    timeout: Duration,
+}
+
+impl Keepalives {
+    /// Creates a new keepalive tracker.
+    pub(super) fn new(interval: Duration, jitter: Duration, timeout: Duration) -> Self {
+        Keepalives {
+            peers: HashMap::new(),
+            interval,
+            jitter,
+            timeout,
+        }
+    }
+
+    /// Samples the time to wait before the next keepalive, `interval` ± `jitter`.
+    fn sample_interval(&self, rng: &mut NodeRng) -> Duration {
+        let jitter_nanos = self.jitter.as_nanos() as u64;
+        let offset = Duration::from_nanos(rng.gen_range(0..=2 * jitter_nanos));
+        (self.interval + offset).saturating_sub(self.jitter)
+    }
+
+    /// Checks the keepalive state of the given peers, returning the actions to be taken.
+    ///
+    /// `peers` should contain every peer able to acknowledge a keepalive, i.e. connected to us in
+    /// both directions. Peers not included are no longer tracked, and neither are peers to be
+    /// disconnected.
+    pub(super) fn sweep<I>(
+        &mut self,
+        rng: &mut NodeRng,
+        peers: I,
+        now: Instant,
+    ) -> Vec<KeepaliveAction>
+    where
+        I: IntoIterator<Item = NodeId>,
+    {
+        let mut actions = Vec::new();
+        if self.interval == Duration::from_secs(0) {
+            return actions;
+        }
+
+        let mut tracked = HashMap::new();
+        for peer_id in peers {
+            let mut state = self.peers.remove(&peer_id).unwrap_or(PeerKeepalive {
+                last_ack: now,
+                pending: None,
+                last_rtt: None,
+                next_due: None,
+            });
+
+            match state.pending {
+                Some((_, sent)) if now >= sent + self.timeout => {
+                    actions.push(KeepaliveAction::Disconnect(peer_id));
+                    continue;
+                }
+                Some(_) => (),
+                None => {
+                    let next_due = match state.next_due {
+                        Some(next_due) => next_due,
+                        None => state.last_ack + self.sample_interval(rng),
+                    };
+                    if now >= next_due {
+                        let nonce = rng.gen();
+                        state.pending = Some((nonce, now));
+                        state.next_due = None;
+                        actions.push(KeepaliveAction::Send { peer_id, nonce });
+                    } else {
+                        state.next_due = Some(next_due);
+                    }
+                }
+            }
+            tracked.insert(peer_id, state);
+        }
+        self.peers = tracked;
+
+        actions
+    }
+
+    /// Records the acknowledgement of a keepalive by `peer_id`, returning its round-trip time.
+    ///
+    /// Acknowledgements not matching the nonce of the pending keepalive are ignored.
+    pub(super) fn record_ack(
+        &mut self,
+        peer_id: &NodeId,
+        nonce: u64,
+        now: Instant,
+    ) -> Option<Duration> {
+        let state = self.peers.get_mut(peer_id)?;
+        match state.pending {
+            Some((pending_nonce, sent)) if pending_nonce == nonce => {
+                let rtt = now.saturating_duration_since(sent);
+                state.pending = None;
+                state.last_ack = now;
+                state.last_rtt = Some(rtt);
+                Some(rtt)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the round-trip time of the most recently acknowledged keepalive sent to `peer_id`.
+    pub(super) fn rtt(&self, peer_id: &NodeId) -> Option<Duration> {
+        self.peers.get(peer_id)?.last_rtt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unresponsive_peer_should_be_disconnected_after_timeout() {
+        let mut rng = crate::new_rng();
+        let responsive = NodeId::random(&mut rng);
+        let unresponsive = NodeId::random(&mut rng);
+        let peers = [responsive, unresponsive];
+        let mut keepalives = Keepalives::new(
+            Duration::from_secs(30),
+            Duration::from_secs(0),
+            Duration::from_secs(60),
+        );
+        let start = Instant::now();
+
+        // Newly tracked peers are not sent a keepalive before the interval has passed.
+        assert!(keepalives
+            .sweep(&mut rng, peers.iter().copied(), start)
+            .is_empty());
+
+        let first_keepalive = start + Duration::from_secs(30);
+        let mut actions = keepalives.sweep(&mut rng, peers.iter().copied(), first_keepalive);
+        assert_eq!(actions.len(), 2);
+        for action in actions.drain(..) {
+            match action {
+                KeepaliveAction::Send { peer_id, nonce } if peer_id == responsive => {
+                    // A wrong nonce does not count as an acknowledgement.
+                    let ack_time = first_keepalive + Duration::from_millis(20);
+                    assert!(keepalives
+                        .record_ack(&peer_id, nonce.wrapping_add(1), ack_time)
+                        .is_none());
+                    assert_eq!(
+                        keepalives.record_ack(&peer_id, nonce, ack_time),
+                        Some(Duration::from_millis(20))
+                    );
+                }
+                KeepaliveAction::Send { peer_id, .. } => assert_eq!(peer_id, unresponsive),
+                KeepaliveAction::Disconnect(peer_id) => panic!("unexpected disconnect {}", peer_id),
+            }
+        }
+
+        // No further keepalive is sent to the unresponsive peer while one is pending.
+        let before_timeout = start + Duration::from_secs(89);
+        let actions = keepalives.sweep(&mut rng, peers.iter().copied(), before_timeout);
+        assert_eq!(actions.len(), 1);
+        assert!(matches!(
+            actions[0],
+            KeepaliveAction::Send { peer_id, .. } if peer_id == responsive
+        ));
+
+        let timeout = start + Duration::from_secs(90);
+        let actions = keepalives.sweep(&mut rng, peers.iter().copied(), timeout);
+        assert!(actions.contains(&KeepaliveAction::Disconnect(unresponsive)));
+        assert!(!actions.contains(&KeepaliveAction::Disconnect(responsive)));
+
+        // Only acknowledged keepalives yield a round-trip time.
+        assert_eq!(keepalives.rtt(&responsive), Some(Duration::from_millis(20)));
+        assert!(keepalives.rtt(&unresponsive).is_none());
+    }
+
+    #[test]
+    fn should_not_send_keepalives_if_disabled() {
+        let mut rng = crate::new_rng();
+        let peer_id = NodeId::random(&mut rng);
+        let mut keepalives = Keepalives::new(
+            Duration::from_secs(0),
+            Duration::from_secs(0),
+            Duration::from_secs(60),
+        );
+        let later = Instant::now() + Duration::from_secs(3600);
+
+        assert!(keepalives.sweep(&mut rng, vec![peer_id], later).is_empty());
+    }
+
+    #[test]
+    fn keepalive_times_should_be_jittered() {
+        let mut rng = crate::new_rng();
+        let peer_id = NodeId::random(&mut rng);
+        let interval = Duration::from_secs(30);
+        let jitter = Duration::from_secs(5);
+        let step = Duration::from_millis(100);
+        let mut keepalives = Keepalives::new(interval, jitter, Duration::from_secs(60));
+
+        // Sweep in small steps, acknowledging every keepalive right away.
+        let mut now = Instant::now();
+        let mut sent_times = vec![now];
+        while sent_times.len() < 20 {
+            for action in keepalives.sweep(&mut rng, vec![peer_id], now) {
+                match action {
+                    KeepaliveAction::Send { nonce, .. } => {
+                        assert!(keepalives.record_ack(&peer_id, nonce, now).is_some());
+                        sent_times.push(now);
+                    }
+                    KeepaliveAction::Disconnect(_) => panic!("unexpected disconnect"),
+                }
+            }
+            now += step;
+        }
+
+        let gaps: Vec<_> = sent_times
+            .windows(2)
+            .map(|times| times[1] - times[0])
+            .collect();
+        for gap in &gaps {
+            assert!(*gap >= interval - jitter, "gap {:?} too short", gap);
+            assert!(*gap <= interval + jitter + step, "gap {:?} too long", gap);
+        }
+
+        // The jitter is sampled anew for every keepalive.
+        assert!(gaps.iter().any(|gap| *gap != gaps[0]));
+    }
+}
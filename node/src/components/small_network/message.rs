@@ -17,6 +17,14 @@ fn default_protocol_version() -> ProtocolVersion {
     ProtocolVersion::V1_0_0
 }
 
+/// The first protocol version whose nodes understand `Message::Disconnect`.
+pub(super) const DISCONNECT_PROTOCOL_VERSION: ProtocolVersion =
+    ProtocolVersion::from_parts(1, 4, 0);
+
+/// The first protocol version whose nodes understand `Message::Keepalive` and
+/// `Message::KeepaliveAck`.
+pub(super) const KEEPALIVE_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::from_parts(1, 4, 0);
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum Message<P> {
     Handshake {
@@ -32,6 +40,16 @@ pub enum Message<P> {
         consensus_certificate: Option<ConsensusCertificate>,
     },
     Payload(P),
+    /// Notifies the peer that we are shutting down and closing the connection.
+    Disconnect,
+    /// Checks that the peer is still responsive, expecting a `KeepaliveAck` with the same nonce.
+    Keepalive {
+        nonce: u64,
+    },
+    /// Acknowledges a `Keepalive`.
+    KeepaliveAck {
+        nonce: u64,
+    },
 }
 
 impl<P: Payload> Message<P> {
@@ -41,6 +59,9 @@ impl<P: Payload> Message<P> {
         match self {
             Message::Handshake { .. } => MessageKind::Protocol,
             Message::Payload(payload) => payload.classify(),
+            Message::Disconnect | Message::Keepalive { .. } | Message::KeepaliveAck { .. } => {
+                MessageKind::Protocol
+            }
         }
     }
 
@@ -50,6 +71,21 @@ impl<P: Payload> Message<P> {
         match self {
             Message::Handshake { .. } => 0,
             Message::Payload(payload) => payload.incoming_resource_estimate(),
+            Message::Disconnect | Message::Keepalive { .. } | Message::KeepaliveAck { .. } => 0,
+        }
+    }
+
+    /// Returns the lowest protocol version a peer must be speaking to understand this message.
+    ///
+    /// Peers running an older protocol version would fail to deserialize the message and drop the
+    /// connection, so it must not be sent to them.
+    #[inline]
+    pub(super) fn min_protocol_version(&self) -> ProtocolVersion {
+        match self {
+            Message::Handshake { .. } => ProtocolVersion::V1_0_0,
+            Message::Payload(payload) => payload.min_protocol_version(),
+            Message::Disconnect => DISCONNECT_PROTOCOL_VERSION,
+            Message::Keepalive { .. } | Message::KeepaliveAck { .. } => KEEPALIVE_PROTOCOL_VERSION,
         }
     }
 }
@@ -127,6 +163,9 @@ impl<P: Display> Display for Message<P> {
                 }
             }
             Message::Payload(payload) => write!(f, "payload: {}", payload),
+            Message::Disconnect => f.write_str("disconnect"),
+            Message::Keepalive { nonce } => write!(f, "keepalive: {}", nonce),
+            Message::KeepaliveAck { nonce } => write!(f, "keepalive ack: {}", nonce),
         }
     }
 }
@@ -178,6 +217,11 @@ pub trait Payload:
     fn incoming_resource_estimate(&self) -> u32 {
         0
     }
+
+    /// The lowest protocol version a peer must be speaking to be able to deserialize the payload.
+    fn min_protocol_version(&self) -> ProtocolVersion {
+        ProtocolVersion::V1_0_0
+    }
 }
 
 #[cfg(test)]
@@ -189,9 +233,9 @@ mod tests {
     use casper_types::ProtocolVersion;
     use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-    use crate::protocol;
+    use crate::{protocol, types::Tag};
 
-    use super::Message;
+    use super::{Message, DISCONNECT_PROTOCOL_VERSION, KEEPALIVE_PROTOCOL_VERSION};
 
     /// Version 1.0.0 network level message.
     ///
@@ -336,8 +380,11 @@ mod tests {
                 assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
                 assert!(consensus_certificate.is_none());
             }
-            Message::Payload(_) => {
-                panic!("did not expect modern handshake to deserialize to payload")
+            other => {
+                panic!(
+                    "did not expect modern handshake to deserialize to {:?}",
+                    other
+                )
             }
         }
     }
@@ -358,9 +405,64 @@ mod tests {
                 assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
                 assert!(consensus_certificate.is_none());
             }
-            Message::Payload(_) => {
-                panic!("did not expect modern handshake to deserialize to payload")
+            other => {
+                panic!(
+                    "did not expect modern handshake to deserialize to {:?}",
+                    other
+                )
             }
         }
     }
+
+    #[test]
+    fn disconnect_is_not_sent_to_v1_0_0_peers() {
+        let disconnect = Message::<protocol::Message>::Disconnect;
+        assert!(disconnect.min_protocol_version() > ProtocolVersion::V1_0_0);
+        assert_eq!(
+            disconnect.min_protocol_version(),
+            DISCONNECT_PROTOCOL_VERSION
+        );
+
+        let handshake = Message::<protocol::Message>::Handshake {
+            network_name: "serialization-test".to_owned(),
+            public_addr: ([12, 34, 56, 78], 12346).into(),
+            protocol_version: ProtocolVersion::V1_0_0,
+            consensus_certificate: None,
+        };
+        assert_eq!(handshake.min_protocol_version(), ProtocolVersion::V1_0_0);
+    }
+
+    #[test]
+    fn keepalives_are_not_sent_to_v1_0_0_peers() {
+        let keepalive = Message::<protocol::Message>::Keepalive { nonce: 1 };
+        let keepalive_ack = Message::<protocol::Message>::KeepaliveAck { nonce: 1 };
+        assert!(KEEPALIVE_PROTOCOL_VERSION > ProtocolVersion::V1_0_0);
+        assert_eq!(keepalive.min_protocol_version(), KEEPALIVE_PROTOCOL_VERSION);
+        assert_eq!(
+            keepalive_ack.min_protocol_version(),
+            KEEPALIVE_PROTOCOL_VERSION
+        );
+    }
+
+    #[test]
+    fn trie_requests_are_not_sent_to_v1_0_0_peers() {
+        let trie_request = Message::Payload(protocol::Message::GetRequest {
+            tag: Tag::Trie,
+            serialized_id: vec![],
+        });
+        assert!(protocol::TRIE_PROTOCOL_VERSION > ProtocolVersion::V1_0_0);
+        assert_eq!(
+            trie_request.min_protocol_version(),
+            protocol::TRIE_PROTOCOL_VERSION
+        );
+
+        let deploy_request = Message::Payload(protocol::Message::GetRequest {
+            tag: Tag::Deploy,
+            serialized_id: vec![],
+        });
+        assert_eq!(
+            deploy_request.min_protocol_version(),
+            ProtocolVersion::V1_0_0
+        );
+    }
 }
@@ -424,6 +424,20 @@ where
         self.routes.keys().cloned()
     }
 
+    /// Iterates over all addresses currently being connected to, along with the time the current
+    /// connection attempt started.
+    #[allow(clippy::needless_lifetimes)]
+    pub(crate) fn connecting_addrs<'a>(
+        &'a self,
+    ) -> impl Iterator<Item = (SocketAddr, Instant)> + 'a {
+        self.outgoing
+            .iter()
+            .filter_map(|(addr, outgoing)| match outgoing.state {
+                OutgoingState::Connecting { since, .. } => Some((*addr, since)),
+                _ => None,
+            })
+    }
+
     /// Notify about a potentially new address that has been discovered.
     ///
     /// Immediately triggers the connection process to said address if it was not known before.
@@ -1247,6 +1261,42 @@ mod tests {
         assert_eq!(peer_ids, expected);
     }
 
+    #[test]
+    fn connecting_addrs_works() {
+        init_logging();
+
+        let mut rng = crate::new_rng();
+        let mut clock = TestClock::new();
+
+        let addr_a: SocketAddr = "1.2.3.4:1234".parse().unwrap();
+        let addr_b: SocketAddr = "5.6.7.8:5678".parse().unwrap();
+
+        let id_a = NodeId::random_tls(&mut rng);
+
+        let mut manager = OutgoingManager::<u32, TestDialerError>::new(test_config());
+
+        let started_a = clock.now();
+        manager.learn_addr(addr_a, false, started_a);
+        clock.advance_time(1_000);
+        let started_b = clock.now();
+        manager.learn_addr(addr_b, false, started_b);
+
+        let mut connecting: Vec<_> = manager.connecting_addrs().collect();
+        connecting.sort();
+        assert_eq!(connecting, vec![(addr_a, started_a), (addr_b, started_b)]);
+
+        // Established connections are no longer reported as connecting.
+        manager.handle_dial_outcome(DialOutcome::Successful {
+            addr: addr_a,
+            handle: 22,
+            node_id: id_a,
+        });
+        assert_eq!(
+            manager.connecting_addrs().collect::<Vec<_>>(),
+            vec![(addr_b, started_b)]
+        );
+    }
+
     #[test]
     fn sweeping_works() {
         init_logging();
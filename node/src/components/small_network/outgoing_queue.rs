@@ -0,0 +1,604 @@
+//! Bounded outgoing message queues
+//!
+//! Every outgoing connection has a queue of messages waiting to be sent to the peer. To prevent a
+//! slow peer from making the queue grow without bounds, the queue has a maximum capacity. Once it
+//! is reached, the oldest queued gossip message is shed to make room for a new message, as gossip
+//! messages will be resent anyway. If there is no gossip message that can be shed, the queue is
+//! closed, which causes the connection to the peer to be dropped.
+//!
+//! A queue can also be closed explicitly, e.g. when the peer stopped responding.
+//!
+//! The sending half can wait for the queue to be drained, i.e. for all queued messages to have
+//! been sent, e.g. to deliver a last message before shutting down.
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug, Formatter},
+    sync::{Arc, Mutex},
+};
+
+use prometheus::{IntCounter, IntGauge};
+use tokio::sync::Notify;
+
+use super::{Message, MessageKind, Payload};
+
+/// Creates a new outgoing queue holding at most `capacity` messages, unlimited if 0.
+///
+/// Every message dropped because the queue is full is counted in `dropped_messages`, while
+/// `queued_messages` always includes the number of messages currently held by the queue.
+pub(super) fn outgoing_queue<P>(
+    capacity: usize,
+    dropped_messages: IntCounter,
+    queued_messages: IntGauge,
+) -> (QueueSender<P>, QueueReceiver<P>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            messages: VecDeque::new(),
+            queued_messages,
+            reported_len: 0,
+            senders: 1,
+            receiver_alive: true,
+            sending: false,
+            closed: false,
+        }),
+        notify: Notify::new(),
+        drained: Notify::new(),
+    });
+
+    let sender = QueueSender {
+        shared: shared.clone(),
+        capacity,
+        dropped_messages,
+    };
+    (sender, QueueReceiver { shared })
+}
+
+/// Returns whether messages of the given kind may be shed when the queue is full.
+fn is_sheddable(kind: MessageKind) -> bool {
+    matches!(kind, MessageKind::DeployGossip | MessageKind::AddressGossip)
+}
+
+/// State shared between the sending and the receiving half of a queue.
+struct Shared<P> {
+    state: Mutex<State<P>>,
+    /// Notifies the receiver of new messages or of the queue being closed.
+    notify: Notify,
+    /// Notifies senders waiting for the queue to be drained.
+    drained: Notify,
+}
+
+struct State<P> {
+    /// Messages waiting to be sent, oldest first.
+    messages: VecDeque<Arc<Message<P>>>,
+    /// Gauge of messages queued for all peers, to which the length of `messages` contributes.
+    queued_messages: IntGauge,
+    /// The length of `messages` last added to `queued_messages`.
+    reported_len: usize,
+    /// Number of live `QueueSender`s.
+    senders: usize,
+    /// Whether the `QueueReceiver` has not been dropped yet.
+    receiver_alive: bool,
+    /// Whether the receiver is still sending the last message it received, i.e. has not asked for
+    /// the next one yet.
+    sending: bool,
+    /// Whether the queue was closed, either explicitly or because it was full and no message could
+    /// be shed.
+    closed: bool,
+}
+
+impl<P> State<P> {
+    /// Returns whether the receiver will not return any more messages.
+    fn is_closed(&self) -> bool {
+        self.closed || (self.senders == 0 && self.messages.is_empty())
+    }
+
+    /// Returns whether all queued messages have been sent, or never will be.
+    fn is_drained(&self) -> bool {
+        self.closed || !self.receiver_alive || (self.messages.is_empty() && !self.sending)
+    }
+
+    /// Updates the gauge of queued messages after the length of `messages` changed.
+    fn update_queued_messages(&mut self) {
+        let len = self.messages.len();
+        self.queued_messages
+            .add(len as i64 - self.reported_len as i64);
+        self.reported_len = len;
+    }
+}
+
+/// Outcome of successfully passing a message to `QueueSender::send`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) enum Enqueued {
+    /// The message was added to the queue.
+    Queued,
+    /// The message was added to the queue, replacing an older gossip message that was shed.
+    ReplacedOldest,
+    /// The queue was full, so the message was shed instead of being queued.
+    Shed,
+}
+
+/// Error returned by `QueueSender::send`.
+pub(super) enum SendError<P> {
+    /// The receiving half of the queue was dropped, i.e. the connection was lost.
+    Disconnected(Arc<Message<P>>),
+    /// The queue is full without any message that could be shed, so the queue was closed.
+    Overloaded(Arc<Message<P>>),
+}
+
+impl<P: Payload> Debug for SendError<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SendError::Disconnected(msg) => f.debug_tuple("Disconnected").field(msg).finish(),
+            SendError::Overloaded(msg) => f.debug_tuple("Overloaded").field(msg).finish(),
+        }
+    }
+}
+
+/// The sending half of an outgoing queue.
+pub(super) struct QueueSender<P> {
+    shared: Arc<Shared<P>>,
+    /// Maximum number of queued messages, unlimited if 0.
+    capacity: usize,
+    /// Counter of messages dropped because the queue was full.
+    dropped_messages: IntCounter,
+}
+
+impl<P: Payload> QueueSender<P> {
+    /// Adds a message to the queue.
+    ///
+    /// If the queue is full, the oldest queued gossip message is shed, or the new message itself
+    /// if it is a gossip message. If neither is possible, the queue is closed.
+    pub(super) fn send(&self, msg: Arc<Message<P>>) -> Result<Enqueued, SendError<P>> {
+        let mut state = self
+            .shared
+            .state
+            .lock()
+            .expect("outgoing queue lock poisoned");
+        if !state.receiver_alive || state.closed {
+            return Err(SendError::Disconnected(msg));
+        }
+
+        let mut outcome = Enqueued::Queued;
+        if self.capacity != 0 && state.messages.len() >= self.capacity {
+            let oldest_sheddable = state
+                .messages
+                .iter()
+                .position(|queued| is_sheddable(queued.classify()));
+            if let Some(index) = oldest_sheddable {
+                state.messages.remove(index);
+                outcome = Enqueued::ReplacedOldest;
+            } else if is_sheddable(msg.classify()) {
+                self.dropped_messages.inc();
+                return Ok(Enqueued::Shed);
+            } else {
+                state.closed = true;
+                self.dropped_messages
+                    .inc_by(state.messages.len() as u64 + 1);
+                state.messages.clear();
+                state.update_queued_messages();
+                drop(state);
+                self.shared.notify.notify_one();
+                self.shared.drained.notify_waiters();
+                return Err(SendError::Overloaded(msg));
+            }
+            self.dropped_messages.inc();
+        }
+
+        state.messages.push_back(msg);
+        state.update_queued_messages();
+        drop(state);
+        self.shared.notify.notify_one();
+        Ok(outcome)
+    }
+
+    /// Closes the queue, discarding all queued messages.
+    ///
+    /// The receiver stops immediately, even if it is in the middle of sending a message, causing
+    /// the connection to the peer to be dropped.
+    pub(super) fn close(&self) {
+        let mut state = self
+            .shared
+            .state
+            .lock()
+            .expect("outgoing queue lock poisoned");
+        state.closed = true;
+        state.messages.clear();
+        state.update_queued_messages();
+        drop(state);
+        self.shared.notify.notify_one();
+        self.shared.drained.notify_waiters();
+    }
+
+    /// Waits until the queue is drained, i.e. all queued messages have been sent, or the queue has
+    /// been closed or its receiver dropped.
+    pub(super) async fn drained(&self) {
+        loop {
+            // Waiters are registered on creation, so a notification sent after checking the state
+            // cannot be missed.
+            let notified = self.shared.drained.notified();
+            if self
+                .shared
+                .state
+                .lock()
+                .expect("outgoing queue lock poisoned")
+                .is_drained()
+            {
+                return;
+            }
+
+            notified.await;
+        }
+    }
+}
+
+impl<P> Clone for QueueSender<P> {
+    fn clone(&self) -> Self {
+        self.shared
+            .state
+            .lock()
+            .expect("outgoing queue lock poisoned")
+            .senders += 1;
+        QueueSender {
+            shared: self.shared.clone(),
+            capacity: self.capacity,
+            dropped_messages: self.dropped_messages.clone(),
+        }
+    }
+}
+
+impl<P> Drop for QueueSender<P> {
+    fn drop(&mut self) {
+        let mut state = self
+            .shared
+            .state
+            .lock()
+            .expect("outgoing queue lock poisoned");
+        state.senders -= 1;
+        let closed = state.senders == 0;
+        drop(state);
+
+        if closed {
+            self.shared.notify.notify_one();
+        }
+    }
+}
+
+/// The receiving half of an outgoing queue.
+pub(super) struct QueueReceiver<P> {
+    shared: Arc<Shared<P>>,
+}
+
+impl<P> QueueReceiver<P> {
+    /// Receives the next message from the queue.
+    ///
+    /// Calling this again signals that the previously received message has been sent.
+    ///
+    /// Returns `None` once all senders have been dropped and the queue is empty, or immediately
+    /// after the queue has been closed.
+    pub(super) async fn recv(&mut self) -> Option<Arc<Message<P>>> {
+        loop {
+            {
+                let mut state = self
+                    .shared
+                    .state
+                    .lock()
+                    .expect("outgoing queue lock poisoned");
+                if state.sending {
+                    state.sending = false;
+                    if state.is_drained() {
+                        self.shared.drained.notify_waiters();
+                    }
+                }
+                if state.is_closed() {
+                    return None;
+                }
+                if let Some(msg) = state.messages.pop_front() {
+                    state.sending = true;
+                    state.update_queued_messages();
+                    return Some(msg);
+                }
+            }
+
+            // A notification sent between releasing the lock and this point is stored as a
+            // permit, so it cannot be missed.
+            self.shared.notify.notified().await;
+        }
+    }
+
+    /// Waits until the queue has been closed, either explicitly or due to being overloaded.
+    ///
+    /// Unlike `recv`, this does not complete once all senders have been dropped, as the messages
+    /// still queued should be sent in that case.
+    pub(super) async fn closed(&self) {
+        loop {
+            if self
+                .shared
+                .state
+                .lock()
+                .expect("outgoing queue lock poisoned")
+                .closed
+            {
+                return;
+            }
+
+            self.shared.notify.notified().await;
+        }
+    }
+}
+
+impl<P> Drop for QueueReceiver<P> {
+    fn drop(&mut self) {
+        let mut state = self
+            .shared
+            .state
+            .lock()
+            .expect("outgoing queue lock poisoned");
+        state.receiver_alive = false;
+        state.messages.clear();
+        state.update_queued_messages();
+        drop(state);
+        self.shared.drained.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fmt::{self, Display, Formatter},
+        time::Duration,
+    };
+
+    use futures::FutureExt;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+    enum TestPayload {
+        Gossip(u32),
+        Consensus(u32),
+    }
+
+    impl Display for TestPayload {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            Debug::fmt(self, f)
+        }
+    }
+
+    impl Payload for TestPayload {
+        fn classify(&self) -> MessageKind {
+            match self {
+                TestPayload::Gossip(_) => MessageKind::DeployGossip,
+                TestPayload::Consensus(_) => MessageKind::Consensus,
+            }
+        }
+    }
+
+    fn gossip(id: u32) -> Arc<Message<TestPayload>> {
+        Arc::new(Message::Payload(TestPayload::Gossip(id)))
+    }
+
+    fn consensus(id: u32) -> Arc<Message<TestPayload>> {
+        Arc::new(Message::Payload(TestPayload::Consensus(id)))
+    }
+
+    fn payload(msg: Option<Arc<Message<TestPayload>>>) -> TestPayload {
+        match msg.as_deref() {
+            Some(Message::Payload(payload)) => payload.clone(),
+            other => panic!("expected payload message, got {:?}", other),
+        }
+    }
+
+    fn dropped_counter() -> IntCounter {
+        IntCounter::new("test_dropped", "test counter").unwrap()
+    }
+
+    fn queued_gauge() -> IntGauge {
+        IntGauge::new("test_queued", "test gauge").unwrap()
+    }
+
+    #[tokio::test]
+    async fn slow_consumer_should_shed_oldest_gossip_first() {
+        let dropped = dropped_counter();
+        let (sender, mut receiver) = outgoing_queue(3, dropped.clone(), queued_gauge());
+
+        // A consumer which is slower than the producer: only one message is received for every
+        // two sent.
+        let mut received = Vec::new();
+        for id in 0..3 {
+            assert_eq!(sender.send(gossip(2 * id)).unwrap(), Enqueued::Queued);
+            let _ = sender.send(gossip(2 * id + 1)).unwrap();
+            received.push(payload(receiver.recv().await));
+        }
+        assert_eq!(
+            received,
+            vec![
+                TestPayload::Gossip(0),
+                TestPayload::Gossip(1),
+                TestPayload::Gossip(3),
+            ]
+        );
+        assert_eq!(dropped.get(), 1);
+
+        // Once the queue is full, the oldest queued gossip message is shed for every new one,
+        // while high-priority messages are kept.
+        assert_eq!(sender.send(gossip(6)).unwrap(), Enqueued::Queued);
+        assert_eq!(sender.send(consensus(0)).unwrap(), Enqueued::ReplacedOldest);
+        assert_eq!(sender.send(gossip(7)).unwrap(), Enqueued::ReplacedOldest);
+        assert_eq!(sender.send(consensus(1)).unwrap(), Enqueued::ReplacedOldest);
+        assert_eq!(dropped.get(), 4);
+
+        drop(sender);
+        let mut remaining = Vec::new();
+        while let Some(msg) = receiver.recv().await {
+            remaining.push(payload(Some(msg)));
+        }
+        assert_eq!(
+            remaining,
+            vec![
+                TestPayload::Consensus(0),
+                TestPayload::Gossip(7),
+                TestPayload::Consensus(1),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_shed_new_gossip_when_only_high_priority_queued() {
+        let dropped = dropped_counter();
+        let (sender, mut receiver) = outgoing_queue(2, dropped.clone(), queued_gauge());
+
+        sender.send(consensus(0)).unwrap();
+        sender.send(consensus(1)).unwrap();
+        assert_eq!(sender.send(gossip(0)).unwrap(), Enqueued::Shed);
+        assert_eq!(dropped.get(), 1);
+
+        assert_eq!(payload(receiver.recv().await), TestPayload::Consensus(0));
+        assert_eq!(payload(receiver.recv().await), TestPayload::Consensus(1));
+    }
+
+    #[tokio::test]
+    async fn should_close_when_overloaded_with_high_priority_messages() {
+        let dropped = dropped_counter();
+        let (sender, mut receiver) = outgoing_queue(2, dropped.clone(), queued_gauge());
+
+        sender.send(consensus(0)).unwrap();
+        sender.send(consensus(1)).unwrap();
+        assert!(matches!(
+            sender.send(consensus(2)),
+            Err(SendError::Overloaded(_))
+        ));
+        assert_eq!(dropped.get(), 3);
+
+        // The receiver stops immediately, causing the connection to be closed.
+        assert!(receiver.recv().await.is_none());
+        assert!(matches!(
+            sender.send(consensus(3)),
+            Err(SendError::Disconnected(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_stop_receiver_when_closed() {
+        let (sender, mut receiver) = outgoing_queue(0, dropped_counter(), queued_gauge());
+
+        sender.send(consensus(0)).unwrap();
+        sender.close();
+
+        // Queued messages are discarded, and the receiver notices the closing while waiting.
+        receiver.closed().await;
+        assert!(receiver.recv().await.is_none());
+        assert!(matches!(
+            sender.send(consensus(1)),
+            Err(SendError::Disconnected(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_not_limit_queue_with_zero_capacity() {
+        let dropped = dropped_counter();
+        let (sender, mut receiver) = outgoing_queue(0, dropped.clone(), queued_gauge());
+
+        for id in 0..100 {
+            assert_eq!(sender.send(consensus(id)).unwrap(), Enqueued::Queued);
+        }
+        assert_eq!(dropped.get(), 0);
+        assert_eq!(payload(receiver.recv().await), TestPayload::Consensus(0));
+    }
+
+    #[tokio::test]
+    async fn receiver_should_wait_for_messages() {
+        let (sender, mut receiver) = outgoing_queue(1, dropped_counter(), queued_gauge());
+
+        let consumer = tokio::spawn(async move {
+            let mut received = Vec::new();
+            while let Some(msg) = receiver.recv().await {
+                received.push(payload(Some(msg)));
+            }
+            received
+        });
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender.send(consensus(0)).unwrap();
+        drop(sender);
+
+        assert_eq!(consumer.await.unwrap(), vec![TestPayload::Consensus(0)]);
+    }
+
+    #[tokio::test]
+    async fn should_be_drained_once_last_message_sent() {
+        let (sender, mut receiver) = outgoing_queue(0, dropped_counter(), queued_gauge());
+        let drained = |sender: &QueueSender<TestPayload>| sender.drained().now_or_never().is_some();
+        assert!(drained(&sender));
+
+        sender.send(consensus(0)).unwrap();
+        sender.send(consensus(1)).unwrap();
+        assert!(!drained(&sender));
+
+        // The queue is empty, but the last message received has not been sent yet.
+        receiver.recv().await.unwrap();
+        receiver.recv().await.unwrap();
+        assert!(!drained(&sender));
+
+        // Asking for the next message signals that the last one has been sent, waking up waiters.
+        let waiter = {
+            let sender = sender.clone();
+            tokio::spawn(async move { sender.drained().await })
+        };
+        tokio::task::yield_now().await;
+        assert!(receiver.recv().now_or_never().is_none());
+        assert!(drained(&sender));
+        waiter.await.unwrap();
+
+        // A dropped receiver will never send the queued messages.
+        sender.send(consensus(2)).unwrap();
+        assert!(!drained(&sender));
+        drop(receiver);
+        assert!(drained(&sender));
+    }
+
+    #[test]
+    fn send_should_fail_once_receiver_dropped() {
+        let (sender, receiver) = outgoing_queue(1, dropped_counter(), queued_gauge());
+        drop(receiver);
+        assert!(matches!(
+            sender.send(consensus(0)),
+            Err(SendError::Disconnected(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_track_number_of_queued_messages() {
+        let queued = queued_gauge();
+        let (sender_1, mut receiver_1) = outgoing_queue(2, dropped_counter(), queued.clone());
+        let (sender_2, receiver_2) = outgoing_queue(2, dropped_counter(), queued.clone());
+
+        // Shed and replaced messages do not change the length of a full queue.
+        sender_1.send(gossip(0)).unwrap();
+        sender_1.send(gossip(1)).unwrap();
+        sender_1.send(gossip(2)).unwrap();
+        sender_2.send(consensus(0)).unwrap();
+        sender_2.send(consensus(1)).unwrap();
+        assert_eq!(sender_2.send(gossip(3)).unwrap(), Enqueued::Shed);
+        assert_eq!(queued.get(), 4);
+
+        receiver_1.recv().await.unwrap();
+        assert_eq!(queued.get(), 3);
+
+        // Messages discarded when the queue is closed or overloaded, or when the receiver is
+        // dropped, are no longer counted.
+        sender_1.close();
+        assert_eq!(queued.get(), 2);
+        drop(receiver_2);
+        assert_eq!(queued.get(), 0);
+
+        let (sender_3, _receiver_3) = outgoing_queue(1, dropped_counter(), queued.clone());
+        sender_3.send(consensus(0)).unwrap();
+        assert_eq!(queued.get(), 1);
+        assert!(matches!(
+            sender_3.send(consensus(1)),
+            Err(SendError::Overloaded(_))
+        ));
+        assert_eq!(queued.get(), 0);
+    }
+}
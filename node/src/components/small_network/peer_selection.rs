@@ -0,0 +1,160 @@
+//! Peer selection strategies
+//!
+//! A peer selection strategy decides which of the currently connected peers a gossiped message is
+//! sent to.
+
+use std::fmt::Debug;
+
+use datasize::DataSize;
+use rand::seq::IteratorRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::{types::NodeId, NodeRng};
+
+/// The kind of strategy used to select peers for gossiping.
+#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerSelection {
+    /// Peers are selected at random.
+    Random,
+    /// Peers are selected in turn, in the order of their node IDs.
+    ///
+    /// Intended for tests which need to be reproducible.
+    RoundRobin,
+}
+
+impl Default for PeerSelection {
+    fn default() -> Self {
+        PeerSelection::Random
+    }
+}
+
+impl PeerSelection {
+    /// Creates a new strategy of this kind.
+    pub(crate) fn strategy(self) -> Box<dyn PeerSelectionStrategy> {
+        match self {
+            PeerSelection::Random => Box::new(RandomSelection),
+            PeerSelection::RoundRobin => Box::new(RoundRobinSelection::default()),
+        }
+    }
+}
+
+/// A peer selection strategy.
+pub(crate) trait PeerSelectionStrategy: Send + Debug {
+    /// Selects up to `count` distinct peers out of `candidates`.
+    ///
+    /// Fewer than `count` peers are returned only if there are not enough candidates.
+    fn select(&mut self, rng: &mut NodeRng, candidates: Vec<NodeId>, count: usize) -> Vec<NodeId>;
+}
+
+/// Selects peers at random.
+///
+/// Candidates are sorted before selecting, so the choice depends only on the given random number
+/// generator and not on the order in which the candidates were collected.
+#[derive(Debug)]
+pub(crate) struct RandomSelection;
+
+impl PeerSelectionStrategy for RandomSelection {
+    fn select(
+        &mut self,
+        rng: &mut NodeRng,
+        mut candidates: Vec<NodeId>,
+        count: usize,
+    ) -> Vec<NodeId> {
+        candidates.sort();
+        candidates.dedup();
+        candidates.into_iter().choose_multiple(rng, count)
+    }
+}
+
+/// Selects peers in turn, cycling through the candidates in the order of their node IDs.
+///
+/// Each selection continues where the previous one stopped.
+#[derive(Debug, Default)]
+pub(crate) struct RoundRobinSelection {
+    /// Position of the next candidate to select.
+    next: usize,
+}
+
+impl PeerSelectionStrategy for RoundRobinSelection {
+    fn select(
+        &mut self,
+        _rng: &mut NodeRng,
+        mut candidates: Vec<NodeId>,
+        count: usize,
+    ) -> Vec<NodeId> {
+        candidates.sort();
+        candidates.dedup();
+        if candidates.is_empty() {
+            return Vec::new();
+        }
+
+        let count = count.min(candidates.len());
+        let start = self.next % candidates.len();
+        self.next = start + count;
+        candidates
+            .into_iter()
+            .cycle()
+            .skip(start)
+            .take(count)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::testing::TestRng;
+
+    fn sorted_node_ids(rng: &mut TestRng, count: usize) -> Vec<NodeId> {
+        let mut node_ids: Vec<_> = (0..count).map(|_| NodeId::random(rng)).collect();
+        node_ids.sort();
+        node_ids
+    }
+
+    #[test]
+    fn round_robin_should_select_peers_in_predictable_order() {
+        let mut rng = crate::new_rng();
+        let node_ids = sorted_node_ids(&mut rng, 4);
+        let mut strategy = PeerSelection::RoundRobin.strategy();
+
+        // The order of the candidates must not affect the selection.
+        let mut candidates = node_ids.clone();
+        candidates.reverse();
+
+        let expected = vec![
+            vec![node_ids[0], node_ids[1], node_ids[2]],
+            vec![node_ids[3], node_ids[0], node_ids[1]],
+            vec![node_ids[2], node_ids[3], node_ids[0]],
+        ];
+        for expected_selection in expected {
+            assert_eq!(
+                strategy.select(&mut rng, candidates.clone(), 3),
+                expected_selection
+            );
+        }
+
+        // Asking for more peers than available selects each candidate once.
+        assert_eq!(
+            strategy.select(&mut rng, candidates, 10),
+            vec![node_ids[1], node_ids[2], node_ids[3], node_ids[0]]
+        );
+        assert!(strategy.select(&mut rng, Vec::new(), 3).is_empty());
+    }
+
+    #[test]
+    fn random_should_select_distinct_candidates() {
+        let mut rng = crate::new_rng();
+        let node_ids = sorted_node_ids(&mut rng, 5);
+        let mut strategy = PeerSelection::Random.strategy();
+
+        let selected = strategy.select(&mut rng, node_ids.clone(), 3);
+        assert_eq!(selected.len(), 3);
+        assert_eq!(selected.iter().collect::<HashSet<_>>().len(), 3);
+        assert!(selected.iter().all(|node_id| node_ids.contains(node_id)));
+
+        assert_eq!(strategy.select(&mut rng, node_ids, 10).len(), 5);
+    }
+}
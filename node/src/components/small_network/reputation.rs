@@ -0,0 +1,216 @@
+//! Peer reputation
+//!
+//! Every peer starts out with a reputation score of zero, which is decremented each time another
+//! component reports a protocol violation by the peer, such as sending a deploy with an invalid
+//! approval. Once the score drops below the configured threshold, the peer is banned: it is
+//! disconnected and any connection to or from it is refused until the ban expires.
+//!
+//! Scores recover over time, by one point per configured decay interval, so that only peers
+//! committing violations repeatedly within a short time get banned.
+
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    time::{Duration, Instant},
+};
+
+use datasize::DataSize;
+use tracing::{info, warn};
+
+use crate::types::NodeId;
+
+/// The reputation score of a peer which committed violations.
+#[derive(Copy, Clone, DataSize, Debug)]
+struct Score {
+    /// The score as of `updated`.
+    value: i32,
+    /// The time of the peer's latest violation.
+    #[data_size(skip)]
+    updated: Instant,
+}
+
+/// Tracks the reputation of peers and the peers currently banned.
+#[derive(DataSize, Debug)]
+pub(super) struct Reputations {
+    /// Reputation scores of peers that committed violations, peers not present have a score of 0.
+    scores: HashMap<NodeId, Score>,
+    /// Banned peers, along with the time their ban expires.
+    #[data_size(skip)]
+    bans: HashMap<NodeId, Instant>,
+    /// Score below which a peer gets banned.
+    ban_threshold: i32,
+    /// How long a ban lasts.
+    ban_duration: Duration,
+    /// Time after which a score recovers by one point. Scores do not recover if zero.
+    decay_interval: Duration,
+}
+
+impl Reputations {
+    /// Creates a new set of reputations.
+    pub(super) fn new(
+        ban_threshold: i32,
+        ban_duration: Duration,
+        decay_interval: Duration,
+    ) -> Self {
+        Reputations {
+            scores: HashMap::new(),
+            bans: HashMap::new(),
+            ban_threshold,
+            ban_duration,
+            decay_interval,
+        }
+    }
+
+    /// Returns the current score of `peer_id`, taking into account its recovery since its latest
+    /// violation.
+    pub(super) fn score(&self, peer_id: &NodeId, now: Instant) -> i32 {
+        self.scores
+            .get(peer_id)
+            .map_or(0, |score| self.decayed(score, now))
+    }
+
+    /// Returns the value of `score` at `now`, after recovering towards zero.
+    fn decayed(&self, score: &Score, now: Instant) -> i32 {
+        if self.decay_interval == Duration::from_secs(0) {
+            return score.value;
+        }
+        let recovered = now.saturating_duration_since(score.updated).as_nanos()
+            / self.decay_interval.as_nanos();
+        let recovered = i32::try_from(recovered).unwrap_or(i32::MAX);
+        score.value.saturating_add(recovered).min(0)
+    }
+
+    /// Records a protocol violation committed by `peer_id`.
+    ///
+    /// Returns `true` if the peer got banned as a result.
+    pub(super) fn record_violation(&mut self, peer_id: NodeId, now: Instant) -> bool {
+        if self.is_banned(&peer_id, now) {
+            return false;
+        }
+
+        let score = self.score(&peer_id, now) - 1;
+        if score < self.ban_threshold {
+            self.ban(peer_id, now);
+            true
+        } else {
+            info!(%peer_id, score, "peer reputation lowered after protocol violation");
+            self.scores.insert(
+                peer_id,
+                Score {
+                    value: score,
+                    updated: now,
+                },
+            );
+            false
+        }
+    }
+
+    /// Bans `peer_id`, regardless of its current score.
+    pub(super) fn ban(&mut self, peer_id: NodeId, now: Instant) {
+        warn!(%peer_id, ban_duration = ?self.ban_duration, "banning peer");
+        // A peer is given a fresh start once its ban expired.
+        self.scores.remove(&peer_id);
+        self.bans.insert(peer_id, now + self.ban_duration);
+    }
+
+    /// Returns whether `peer_id` is currently banned.
+    pub(super) fn is_banned(&self, peer_id: &NodeId, now: Instant) -> bool {
+        self.bans
+            .get(peer_id)
+            .map_or(false, |&expires| now < expires)
+    }
+
+    /// Lifts all bans that have expired, and forgets the scores of peers which fully recovered.
+    pub(super) fn expire_bans(&mut self, now: Instant) {
+        self.bans.retain(|peer_id, &mut expires| {
+            let retain = now < expires;
+            if !retain {
+                info!(%peer_id, "ban expired");
+            }
+            retain
+        });
+
+        let recovered: Vec<_> = self
+            .scores
+            .iter()
+            .filter(|(_, score)| self.decayed(score, now) == 0)
+            .map(|(peer_id, _)| *peer_id)
+            .collect();
+        for peer_id in recovered {
+            self.scores.remove(&peer_id);
+        }
+    }
+
+    /// Returns the number of peers currently banned, including those whose ban has expired but was
+    /// not lifted yet.
+    pub(super) fn ban_count(&self) -> usize {
+        self.bans.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_ban_after_repeated_violations() {
+        let mut rng = crate::new_rng();
+        let peer_id = NodeId::random(&mut rng);
+        let other_peer_id = NodeId::random(&mut rng);
+        let mut reputations = Reputations::new(-3, Duration::from_secs(60), Duration::from_secs(0));
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(!reputations.record_violation(peer_id, now));
+        }
+        assert!(!reputations.is_banned(&peer_id, now));
+        assert!(reputations.record_violation(peer_id, now));
+        assert!(reputations.is_banned(&peer_id, now));
+        assert!(!reputations.is_banned(&other_peer_id, now));
+        assert_eq!(reputations.ban_count(), 1);
+
+        // Violations by a banned peer do not extend its ban.
+        assert!(!reputations.record_violation(peer_id, now));
+
+        let later = now + Duration::from_secs(60);
+        assert!(!reputations.is_banned(&peer_id, later));
+        reputations.expire_bans(later);
+        assert_eq!(reputations.ban_count(), 0);
+
+        // The peer starts with a clean slate after its ban.
+        assert!(!reputations.record_violation(peer_id, later));
+    }
+
+    #[test]
+    fn scores_should_recover_over_time() {
+        let mut rng = crate::new_rng();
+        let peer_id = NodeId::random(&mut rng);
+        let decay_interval = Duration::from_secs(10);
+        let mut reputations = Reputations::new(-3, Duration::from_secs(60), decay_interval);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(!reputations.record_violation(peer_id, now));
+        }
+        assert_eq!(reputations.score(&peer_id, now), -3);
+
+        // Violations spread out over time do not lead to a ban.
+        let later = now + decay_interval * 2;
+        assert_eq!(reputations.score(&peer_id, later), -1);
+        assert!(!reputations.record_violation(peer_id, later));
+        assert_eq!(reputations.score(&peer_id, later), -2);
+
+        // Scores never recover above zero, and are forgotten once they are back at zero.
+        let much_later = later + decay_interval * 10;
+        assert_eq!(reputations.score(&peer_id, much_later), 0);
+        reputations.expire_bans(much_later);
+        assert!(reputations.scores.is_empty());
+
+        // Repeated violations within a short time still lead to a ban.
+        for _ in 0..3 {
+            assert!(!reputations.record_violation(peer_id, much_later));
+        }
+        assert!(reputations.record_violation(peer_id, much_later));
+        assert!(reputations.is_banned(&peer_id, much_later));
+    }
+}
@@ -1,6 +1,7 @@
 //! Tasks run by the component.
 
 use std::{
+    collections::HashSet,
     error::Error as StdError,
     fmt::Display,
     io,
@@ -10,7 +11,7 @@ use std::{
     time::Duration,
 };
 
-use casper_types::PublicKey;
+use casper_types::{ProtocolVersion, PublicKey};
 use futures::{
     future::{self, Either},
     stream::{SplitSink, SplitStream},
@@ -19,14 +20,12 @@ use futures::{
 use openssl::{
     pkey::{PKey, Private},
     ssl::Ssl,
+    x509::X509,
 };
-use prometheus::IntGauge;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use tokio::{
-    net::TcpStream,
-    sync::{mpsc::UnboundedReceiver, watch},
-};
+use tokio::{net::TcpStream, sync::watch};
 use tokio_openssl::SslStream;
+use tokio_util::codec::LengthDelimitedCodecError;
 use tracing::{
     debug, error_span,
     field::{self, Empty},
@@ -41,12 +40,13 @@ use super::{
     framed,
     limiter::LimiterHandle,
     message::ConsensusKeyPair,
+    outgoing_queue::QueueReceiver,
     Event, FramedTransport, Message, Payload, Transport,
 };
 use crate::{
     components::networking_metrics::NetworkingMetrics,
     reactor::{EventQueueHandle, QueueKind},
-    tls::{self, TlsCert},
+    tls::{self, CertFingerprint, TlsCert},
     types::NodeId,
     utils::display_error,
 };
@@ -88,11 +88,7 @@ where
         .peer_certificate()
         .ok_or(ConnectionError::NoPeerCertificate)?;
 
-    let peer_id = NodeId::from(
-        tls::validate_cert(peer_cert)
-            .map_err(ConnectionError::PeerCertificateInvalid)?
-            .public_key_fingerprint(),
-    );
+    let peer_id = validate_peer_cert(peer_cert, context.pinned_certificates.as_ref())?;
 
     Ok((peer_id, transport))
 }
@@ -133,7 +129,7 @@ where
 
     // Negotiate the handshake, concluding the incoming connection process.
     match negotiate_handshake(&context, &mut transport, connection_id).await {
-        Ok((public_addr, peer_consensus_public_key)) => {
+        Ok((public_addr, peer_consensus_public_key, peer_protocol_version)) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("validator_id", &field::display(public_key));
             }
@@ -150,6 +146,7 @@ where
                 peer_addr,
                 peer_id,
                 peer_consensus_public_key,
+                peer_protocol_version,
                 sink,
             }
         }
@@ -182,6 +179,8 @@ where
     pub(super) public_addr: SocketAddr,
     /// Optional set of consensus keys, to identify as a validator during handshake.
     pub(super) consensus_keys: Option<ConsensusKeyPair>,
+    /// Fingerprints of the certificates peers may present, if certificate pinning is enabled.
+    pub(super) pinned_certificates: Option<HashSet<CertFingerprint>>,
 }
 
 /// Handles an incoming connection.
@@ -198,13 +197,19 @@ where
     for<'de> P: Serialize + Deserialize<'de>,
     for<'de> Message<P>: Serialize + Deserialize<'de>,
 {
-    let (peer_id, transport) =
-        match server_setup_tls(stream, &context.our_cert, &context.secret_key).await {
-            Ok(value) => value,
-            Err(error) => {
-                return IncomingConnection::FailedEarly { peer_addr, error };
-            }
-        };
+    let (peer_id, transport) = match server_setup_tls(
+        stream,
+        &context.our_cert,
+        &context.secret_key,
+        context.pinned_certificates.as_ref(),
+    )
+    .await
+    {
+        Ok(value) => value,
+        Err(error) => {
+            return IncomingConnection::FailedEarly { peer_addr, error };
+        }
+    };
 
     // Register the `peer_id` on the [`Span`] for logging the ID from here on out.
     Span::current().record("peer_id", &field::display(peer_id));
@@ -228,7 +233,7 @@ where
 
     // Negotiate the handshake, concluding the incoming connection process.
     match negotiate_handshake(&context, &mut transport, connection_id).await {
-        Ok((public_addr, peer_consensus_public_key)) => {
+        Ok((public_addr, peer_consensus_public_key, _peer_protocol_version)) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("validator_id", &field::display(public_key));
             }
@@ -259,6 +264,7 @@ pub(super) async fn server_setup_tls(
     stream: TcpStream,
     cert: &TlsCert,
     secret_key: &PKey<Private>,
+    pinned_certificates: Option<&HashSet<CertFingerprint>>,
 ) -> Result<(NodeId, Transport), ConnectionError> {
     let mut tls_stream = tls::create_tls_acceptor(cert.as_x509().as_ref(), secret_key.as_ref())
         .and_then(|ssl_acceptor| Ssl::new(ssl_acceptor.context()))
@@ -275,14 +281,30 @@ pub(super) async fn server_setup_tls(
         .peer_certificate()
         .ok_or(ConnectionError::NoPeerCertificate)?;
 
-    Ok((
-        NodeId::from(
-            tls::validate_cert(peer_cert)
-                .map_err(ConnectionError::PeerCertificateInvalid)?
-                .public_key_fingerprint(),
-        ),
-        tls_stream,
-    ))
+    let peer_id = validate_peer_cert(peer_cert, pinned_certificates)?;
+
+    Ok((peer_id, tls_stream))
+}
+
+/// Validates a certificate presented by a peer and returns the peer's node ID.
+///
+/// If `pinned_certificates` is given, the certificate must also be one of them.
+fn validate_peer_cert(
+    peer_cert: X509,
+    pinned_certificates: Option<&HashSet<CertFingerprint>>,
+) -> Result<NodeId, ConnectionError> {
+    let peer_cert =
+        tls::validate_cert(peer_cert).map_err(ConnectionError::PeerCertificateInvalid)?;
+
+    if let Some(pinned_certificates) = pinned_certificates {
+        let fingerprint = peer_cert.fingerprint();
+        if !pinned_certificates.contains(&fingerprint) {
+            warn!(%fingerprint, "rejecting connection, peer certificate is not pinned");
+            return Err(ConnectionError::PeerCertificateNotPinned(fingerprint));
+        }
+    }
+
+    Ok(NodeId::from(peer_cert.public_key_fingerprint()))
 }
 
 /// Performs an IO-operation that can time out.
@@ -318,7 +340,7 @@ async fn negotiate_handshake<P, REv>(
     context: &NetworkContext<REv>,
     transport: &mut FramedTransport<P>,
     connection_id: ConnectionId,
-) -> Result<(SocketAddr, Option<PublicKey>), ConnectionError>
+) -> Result<(SocketAddr, Option<PublicKey>, ProtocolVersion), ConnectionError>
 where
     P: Payload,
 {
@@ -358,7 +380,7 @@ where
             })
             .transpose()?;
 
-        Ok((public_addr, peer_consensus_public_key))
+        Ok((public_addr, peer_consensus_public_key, protocol_version))
     } else {
         // Received a non-handshake, this is an error.
         Err(ConnectionError::DidNotSendHandshake)
@@ -456,6 +478,10 @@ where
     let read_messages = async move {
         while let Some(msg_result) = stream.next().await {
             match msg_result {
+                Ok(Message::Disconnect) => {
+                    info!("peer is shutting down, closing connection");
+                    return Ok(());
+                }
                 Ok(msg) => {
                     trace!(%msg, "message received");
                     // We've received a message. Ensure we have the proper amount of resources,
@@ -476,6 +502,13 @@ where
                         )
                         .await;
                 }
+                Err(err) if is_oversized_frame(&err) => {
+                    warn!(
+                        maximum_net_message_size = context.chain_info.maximum_net_message_size,
+                        "peer announced a frame exceeding the maximum message size, closing connection"
+                    );
+                    return Err(err);
+                }
                 Err(err) => {
                     warn!(
                         err = display_error(&err),
@@ -500,20 +533,25 @@ where
     Ok(())
 }
 
+/// Returns whether `err` was caused by a peer announcing a frame above the maximum message size.
+///
+/// The frame length is checked as soon as it is read, before any buffer is allocated for the frame.
+pub(super) fn is_oversized_frame(err: &io::Error) -> bool {
+    err.get_ref()
+        .map_or(false, |inner| inner.is::<LengthDelimitedCodecError>())
+}
+
 /// Network message sender.
 ///
 /// Reads from a channel and sends all messages, until the stream is closed or an error occurs.
 pub(super) async fn message_sender<P>(
-    mut queue: UnboundedReceiver<Arc<Message<P>>>,
+    mut queue: QueueReceiver<P>,
     mut sink: SplitSink<FramedTransport<P>, Arc<Message<P>>>,
     limiter: Box<dyn LimiterHandle>,
-    counter: IntGauge,
 ) where
     P: Payload,
 {
     while let Some(message) = queue.recv().await {
-        counter.dec();
-
         // TODO: Refactor message sending to not use `tokio_serde` anymore to avoid duplicate
         //       serialization.
         let estimated_wire_size = rmp_serde::to_vec(&message)
@@ -522,13 +560,22 @@ pub(super) async fn message_sender<P>(
             .unwrap_or(0) as u32;
         limiter.request_allowance(estimated_wire_size).await;
 
-        // We simply error-out if the sink fails, it means that our connection broke.
-        if let Err(ref err) = sink.send(message).await {
-            info!(
-                err = display_error(err),
-                "message send failed, closing outgoing connection"
-            );
-            break;
-        };
+        // Sending may block for a long time if the peer stopped reading, so we also stop if the
+        // queue is closed in the meantime.
+        match future::select(sink.send(message), Box::pin(queue.closed())).await {
+            // We simply error-out if the sink fails, it means that our connection broke.
+            Either::Left((Err(ref err), _)) => {
+                info!(
+                    err = display_error(err),
+                    "message send failed, closing outgoing connection"
+                );
+                break;
+            }
+            Either::Left((Ok(()), _)) => (),
+            Either::Right(_) => {
+                info!("outgoing queue closed while sending, closing outgoing connection");
+                break;
+            }
+        }
     }
 }
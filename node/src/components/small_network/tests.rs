@@ -6,32 +6,47 @@
 use std::{
     collections::{HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
+    net::SocketAddr,
+    pin::Pin,
+    sync::Weak,
     time::{Duration, Instant},
 };
 
 use derive_more::From;
+use futures::StreamExt;
 use pnet::datalink;
 use prometheus::Registry;
 use reactor::ReactorEvent;
 use serde::{Deserialize, Serialize};
+use tokio::{
+    io::AsyncWriteExt,
+    net::{TcpListener, TcpStream},
+};
+use tokio_openssl::SslStream;
 use tracing::{debug, info};
 
 use super::{
-    chain_info::ChainInfo, Config, Event as SmallNetworkEvent, GossipedAddress, MessageKind,
-    Payload, SmallNetwork,
+    chain_info::ChainInfo,
+    counting_format::{ConnectionId, Role},
+    error::ConnectionError,
+    framed, tasks, Config, Event as SmallNetworkEvent, GossipedAddress, MessageKind, Payload,
+    SmallNetwork,
 };
 use crate::{
     components::{
         gossiper::{self, Gossiper},
-        small_network::SmallNetworkIdentity,
+        small_network::{SmallNetworkIdentity, SmallNetworkIdentityError},
         Component,
     },
     effect::{
-        announcements::{ControlAnnouncement, GossiperAnnouncement, NetworkAnnouncement},
+        announcements::{
+            BlocklistAnnouncement, ControlAnnouncement, GossiperAnnouncement, NetworkAnnouncement,
+        },
         requests::{
-            ChainspecLoaderRequest, ContractRuntimeRequest, NetworkRequest, StorageRequest,
+            ChainspecLoaderRequest, ContractRuntimeRequest, NetworkInfoRequest, NetworkRequest,
+            StorageRequest,
         },
-        EffectBuilder, Effects,
+        EffectBuilder, EffectExt, Effects,
     },
     protocol,
     reactor::{self, EventQueueHandle, Finalize, Reactor, Runner},
@@ -40,8 +55,9 @@ use crate::{
         network::{Network, NetworkedReactor, Nodes},
         ConditionCheckReactor,
     },
-    types::NodeId,
-    utils::Source,
+    tls::{self, CertFingerprint},
+    types::{ConnectionDirection, NodeId, PeerConnectionState, TimeDiff},
+    utils::{External, Source, WithDir},
     NodeRng,
 };
 
@@ -86,6 +102,12 @@ impl From<NetworkRequest<NodeId, Message>> for SmallNetworkEvent<Message> {
     }
 }
 
+impl From<BlocklistAnnouncement<NodeId>> for Event {
+    fn from(announcement: BlocklistAnnouncement<NodeId>) -> Self {
+        Event::SmallNet(SmallNetworkEvent::from(announcement))
+    }
+}
+
 impl From<NetworkRequest<NodeId, protocol::Message>> for Event {
     fn from(_request: NetworkRequest<NodeId, protocol::Message>) -> Self {
         unreachable!()
@@ -98,6 +120,14 @@ impl From<StorageRequest> for Event {
     }
 }
 
+impl From<NetworkInfoRequest<NodeId>> for Event {
+    fn from(request: NetworkInfoRequest<NodeId>) -> Self {
+        Event::SmallNet(SmallNetworkEvent::NetworkInfoRequest {
+            req: Box::new(request),
+        })
+    }
+}
+
 impl From<ChainspecLoaderRequest> for Event {
     fn from(_request: ChainspecLoaderRequest) -> Self {
         unreachable!()
@@ -158,7 +188,7 @@ impl Reactor for TestReactor {
         _rng: &mut NodeRng,
     ) -> anyhow::Result<(Self, Effects<Self::Event>)> {
         let small_network_identity = SmallNetworkIdentity::new()?;
-        let (net, effects) = SmallNetwork::new(
+        let mut net = SmallNetwork::new(
             event_queue,
             cfg,
             None,
@@ -167,6 +197,8 @@ impl Reactor for TestReactor {
             ChainInfo::create_for_testing(),
             None,
         )?;
+        let effects =
+            reactor::init_component(Event::SmallNet, &mut net, EffectBuilder::new(event_queue));
         let gossiper_config = gossiper::Config::new_with_small_timeouts();
         let address_gossiper =
             Gossiper::new_for_complete_items("address_gossiper", gossiper_config, registry)?;
@@ -176,7 +208,7 @@ impl Reactor for TestReactor {
                 net,
                 address_gossiper,
             },
-            reactor::wrap_effects(Event::SmallNet, effects),
+            effects,
         ))
     }
 
@@ -237,9 +269,18 @@ impl Reactor for TestReactor {
                 // We do not care about the announcement of gossiping finished in this test.
                 Effects::new()
             }
+            Event::AddressGossiperAnnouncement(GossiperAnnouncement::ItemReassembled {
+                ..
+            }) => {
+                unreachable!("gossiped addresses should never be sent in chunks")
+            }
         }
     }
 
+    fn shutdown(&mut self, effect_builder: EffectBuilder<Self::Event>) -> Effects<Self::Event> {
+        reactor::shutdown_component(Event::SmallNet, &mut self.net, effect_builder)
+    }
+
     fn maybe_exit(&self) -> Option<crate::reactor::ReactorExit> {
         unimplemented!()
     }
@@ -502,3 +543,450 @@ async fn ensure_peers_metric_is_correct() {
         net.finalize().await;
     }
 }
+
+/// Connects to `server_addr` and performs the client side of the TLS handshake.
+async fn client_setup_tls(
+    client: &SmallNetworkIdentity,
+    server_addr: SocketAddr,
+) -> SslStream<TcpStream> {
+    let stream = TcpStream::connect(server_addr).await.unwrap();
+    let mut transport =
+        tls::create_tls_connector(client.tls_certificate.as_x509(), &client.secret_key)
+            .and_then(|connector| connector.configure())
+            .and_then(|mut config| {
+                config.set_verify_hostname(false);
+                config.into_ssl("this-will-not-be-checked.example.com")
+            })
+            .and_then(|ssl| SslStream::new(ssl, stream))
+            .unwrap();
+    SslStream::connect(Pin::new(&mut transport)).await.unwrap();
+    transport
+}
+
+/// Performs a TLS handshake between a client and a server with the given identities.
+///
+/// Returns the outcome of the server side setup, which enforces `pinned_certificates`.
+async fn server_tls_setup_result(
+    server: &SmallNetworkIdentity,
+    client: &SmallNetworkIdentity,
+    pinned_certificates: Option<&HashSet<CertFingerprint>>,
+) -> Result<NodeId, ConnectionError> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let server_addr = listener.local_addr().unwrap();
+
+    let server_side = async {
+        let (stream, _peer_addr) = listener.accept().await.unwrap();
+        tasks::server_setup_tls(
+            stream,
+            &server.tls_certificate,
+            &server.secret_key,
+            pinned_certificates,
+        )
+        .await
+        .map(|(peer_id, _transport)| peer_id)
+    };
+
+    // The server only checks the pinned certificates after the handshake has completed. The
+    // client keeps the connection open until the server is done.
+    let client_side = client_setup_tls(client, server_addr);
+
+    let (result, _transport) = tokio::join!(server_side, client_side);
+    result
+}
+
+/// Check that a node's identity is loaded from the configured certificate and secret key, so that
+/// it can be pinned by peers across restarts.
+#[test]
+fn should_load_identity_from_config() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let (cert, secret_key) = tls::generate_node_cert().unwrap();
+    tls::save_cert(&cert, tempdir.path().join("node.crt")).unwrap();
+    tls::save_private_key(&secret_key, tempdir.path().join("node.key")).unwrap();
+    let (_, other_secret_key) = tls::generate_node_cert().unwrap();
+    tls::save_private_key(&other_secret_key, tempdir.path().join("other.key")).unwrap();
+
+    let config = Config {
+        tls_certificate_path: External::from_path("node.crt"),
+        tls_secret_key_path: External::from_path("node.key"),
+        ..Default::default()
+    };
+    let first = SmallNetworkIdentity::from_config(WithDir::new(tempdir.path(), config.clone()))
+        .expect("should load identity");
+    let second = SmallNetworkIdentity::from_config(WithDir::new(tempdir.path(), config))
+        .expect("should load identity");
+    assert_eq!(
+        first.tls_certificate.fingerprint(),
+        second.tls_certificate.fingerprint()
+    );
+    assert_eq!(NodeId::from(&first), NodeId::from(&second));
+
+    // A secret key not matching the certificate is rejected.
+    let config = Config {
+        tls_certificate_path: External::from_path("node.crt"),
+        tls_secret_key_path: External::from_path("other.key"),
+        ..Default::default()
+    };
+    assert!(matches!(
+        SmallNetworkIdentity::from_config(WithDir::new(tempdir.path(), config)),
+        Err(SmallNetworkIdentityError::MismatchedTlsSecretKey)
+    ));
+
+    // A certificate without a secret key is rejected.
+    let config = Config {
+        tls_certificate_path: External::from_path("node.crt"),
+        ..Default::default()
+    };
+    assert!(matches!(
+        SmallNetworkIdentity::from_config(WithDir::new(tempdir.path(), config)),
+        Err(SmallNetworkIdentityError::CouldNotLoadTlsSecretKey(_))
+    ));
+
+    // Without either, a new identity is generated every time.
+    let first =
+        SmallNetworkIdentity::from_config(WithDir::new(tempdir.path(), Config::default())).unwrap();
+    let second =
+        SmallNetworkIdentity::from_config(WithDir::new(tempdir.path(), Config::default())).unwrap();
+    assert_ne!(NodeId::from(&first), NodeId::from(&second));
+}
+
+/// Check that only peers presenting a pinned certificate are accepted when pinning is enabled.
+#[tokio::test]
+async fn should_only_accept_pinned_certificates() {
+    init_logging();
+
+    let server = SmallNetworkIdentity::new().unwrap();
+    let pinned_client = SmallNetworkIdentity::new().unwrap();
+    let unpinned_client = SmallNetworkIdentity::new().unwrap();
+
+    let pinned_certificates: HashSet<_> = vec![pinned_client.tls_certificate.fingerprint()]
+        .into_iter()
+        .collect();
+
+    let peer_id = server_tls_setup_result(&server, &pinned_client, Some(&pinned_certificates))
+        .await
+        .expect("pinned peer should be accepted");
+    assert_eq!(peer_id, NodeId::from(&pinned_client));
+
+    let error = server_tls_setup_result(&server, &unpinned_client, Some(&pinned_certificates))
+        .await
+        .expect_err("unpinned peer should be refused");
+    assert!(matches!(
+        error,
+        ConnectionError::PeerCertificateNotPinned(fingerprint)
+            if fingerprint == unpinned_client.tls_certificate.fingerprint()
+    ));
+
+    // Without pinning, any peer with a valid certificate is accepted.
+    let peer_id = server_tls_setup_result(&server, &unpinned_client, None)
+        .await
+        .expect("peer should be accepted without pinning");
+    assert_eq!(peer_id, NodeId::from(&unpinned_client));
+}
+
+/// Check that a frame announcing a length above the maximum message size is rejected without
+/// allocating a buffer for it.
+#[tokio::test]
+async fn should_reject_oversized_frame() {
+    init_logging();
+
+    let server = SmallNetworkIdentity::new().unwrap();
+    let client = SmallNetworkIdentity::new().unwrap();
+    let maximum_net_message_size = 1024;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let server_addr = listener.local_addr().unwrap();
+
+    let server_side = async {
+        let (stream, _peer_addr) = listener.accept().await.unwrap();
+        let (peer_id, transport) =
+            tasks::server_setup_tls(stream, &server.tls_certificate, &server.secret_key, None)
+                .await
+                .unwrap();
+        let connection_id =
+            ConnectionId::from_connection(transport.ssl(), NodeId::from(&server), peer_id);
+        let mut transport = framed::<Message>(
+            Weak::new(),
+            connection_id,
+            transport,
+            Role::Listener,
+            maximum_net_message_size,
+        );
+        transport.next().await
+    };
+
+    let client_side = async {
+        let mut transport = client_setup_tls(&client, server_addr).await;
+        // Announce a 4 GiB frame, which would exhaust the memory if its buffer was allocated up
+        // front.
+        transport.write_all(&u32::MAX.to_be_bytes()).await.unwrap();
+        transport.flush().await.unwrap();
+        transport
+    };
+
+    let (result, _transport) = tokio::join!(server_side, client_side);
+    let error = result
+        .expect("stream should not end before the frame is read")
+        .expect_err("oversized frame should be rejected");
+    assert!(tasks::is_oversized_frame(&error));
+}
+
+/// Check that a peer repeatedly reported for protocol violations gets banned and disconnected.
+#[tokio::test]
+async fn peer_is_banned_after_repeated_protocol_violations() {
+    init_logging();
+
+    let mut rng = crate::new_rng();
+    let mut net = Network::new();
+    let first_node_port = testing::unused_port_on_localhost();
+
+    let (node_a, _) = net
+        .add_node_with_config(
+            Config::default_local_net_first_node(first_node_port),
+            &mut rng,
+        )
+        .await
+        .unwrap();
+    let (node_b, _) = net
+        .add_node_with_config(Config::default_local_net(first_node_port), &mut rng)
+        .await
+        .unwrap();
+
+    let blocklist = HashSet::new();
+    net.settle_on(
+        &mut rng,
+        |nodes| network_is_complete(&blocklist, nodes),
+        Duration::from_secs(20),
+    )
+    .await;
+
+    let is_banned = |nodes: &Nodes<TestReactor>| {
+        nodes[&node_a]
+            .reactor()
+            .inner()
+            .net
+            .reputations
+            .is_banned(&node_b, Instant::now())
+    };
+
+    // Violations up to the threshold only lower the peer's reputation.
+    let threshold = Config::default().reputation_ban_threshold;
+    for _ in 0..-threshold {
+        net.process_injected_effect_on(&node_a, |effect_builder| {
+            effect_builder.announce_protocol_violation(node_b).ignore()
+        })
+        .await;
+    }
+    net.settle(&mut rng, Duration::from_millis(25), Duration::from_secs(2))
+        .await;
+    assert!(!is_banned(net.nodes()));
+
+    // The next one gets the peer banned.
+    net.process_injected_effect_on(&node_a, |effect_builder| {
+        effect_builder.announce_protocol_violation(node_b).ignore()
+    })
+    .await;
+    net.settle_on(&mut rng, is_banned, Duration::from_secs(2))
+        .await;
+
+    let net_a = &net.nodes()[&node_a].reactor().inner().net;
+    assert_eq!(net_a.net_metrics.banned_peers.get(), 1);
+    assert!(net_a.outgoing_manager.get_route(node_b).is_none());
+
+    net.finalize().await;
+}
+
+/// Check that the connections between two nodes are reported with the correct direction.
+#[tokio::test]
+async fn peer_connections_are_reported_with_direction() {
+    init_logging();
+
+    let mut rng = crate::new_rng();
+    let mut net = Network::new();
+    let first_node_port = testing::unused_port_on_localhost();
+
+    let (node_a, _) = net
+        .add_node_with_config(
+            Config::default_local_net_first_node(first_node_port),
+            &mut rng,
+        )
+        .await
+        .unwrap();
+    let (node_b, _) = net
+        .add_node_with_config(Config::default_local_net(first_node_port), &mut rng)
+        .await
+        .unwrap();
+
+    // Node B knows the address of node A and connects to it, node A learns the address of node B
+    // from the handshake and connects back.
+    let is_connected = |nodes: &Nodes<TestReactor>,
+                        node_id: NodeId,
+                        peer_id: NodeId,
+                        direction: ConnectionDirection| {
+        nodes[&node_id]
+            .reactor()
+            .inner()
+            .net
+            .peer_connections()
+            .iter()
+            .any(|connection| {
+                connection.node_id == Some(peer_id)
+                    && connection.direction == direction
+                    && connection.state == PeerConnectionState::Connected
+            })
+    };
+    net.settle_on(
+        &mut rng,
+        |nodes| {
+            is_connected(nodes, node_a, node_b, ConnectionDirection::Incoming)
+                && is_connected(nodes, node_a, node_b, ConnectionDirection::Outgoing)
+                && is_connected(nodes, node_b, node_a, ConnectionDirection::Incoming)
+                && is_connected(nodes, node_b, node_a, ConnectionDirection::Outgoing)
+        },
+        Duration::from_secs(20),
+    )
+    .await;
+
+    for (node_id, peer_id) in &[(node_a, node_b), (node_b, node_a)] {
+        let connections = net.nodes()[node_id]
+            .reactor()
+            .inner()
+            .net
+            .peer_connections();
+        // Only the other node should be connected, and the age of every connection is known.
+        assert!(connections
+            .iter()
+            .filter(|connection| connection.state == PeerConnectionState::Connected)
+            .all(|connection| connection.node_id == Some(*peer_id)));
+        assert!(connections
+            .iter()
+            .all(|connection| connection.age.is_some()));
+    }
+
+    net.finalize().await;
+}
+
+/// Check that a peer closes its connection when the node on the other end shuts down.
+#[tokio::test]
+async fn peer_is_disconnected_on_shutdown() {
+    init_logging();
+
+    let mut rng = crate::new_rng();
+    let mut net = Network::new();
+    let first_node_port = testing::unused_port_on_localhost();
+
+    let (node_a, _) = net
+        .add_node_with_config(
+            Config::default_local_net_first_node(first_node_port),
+            &mut rng,
+        )
+        .await
+        .unwrap();
+    let (node_b, _) = net
+        .add_node_with_config(Config::default_local_net(first_node_port), &mut rng)
+        .await
+        .unwrap();
+
+    let is_connected = |nodes: &Nodes<TestReactor>,
+                        node_id: NodeId,
+                        peer_id: NodeId,
+                        direction: ConnectionDirection| {
+        nodes[&node_id]
+            .reactor()
+            .inner()
+            .net
+            .peer_connections()
+            .iter()
+            .any(|connection| {
+                connection.node_id == Some(peer_id)
+                    && connection.direction == direction
+                    && connection.state == PeerConnectionState::Connected
+            })
+    };
+    net.settle_on(
+        &mut rng,
+        |nodes| {
+            is_connected(nodes, node_a, node_b, ConnectionDirection::Outgoing)
+                && is_connected(nodes, node_b, node_a, ConnectionDirection::Incoming)
+        },
+        Duration::from_secs(20),
+    )
+    .await;
+
+    // Node A is still running, so node B only closes the connection if it receives `Disconnect`.
+    net.nodes_mut().get_mut(&node_a).unwrap().shutdown().await;
+    net.settle_on(
+        &mut rng,
+        |nodes| !is_connected(nodes, node_b, node_a, ConnectionDirection::Incoming),
+        Duration::from_secs(5),
+    )
+    .await;
+
+    net.finalize().await;
+}
+
+/// Check that the round-trip time of acknowledged keepalives is recorded.
+#[tokio::test]
+async fn keepalive_round_trip_time_is_recorded() {
+    init_logging();
+
+    let mut rng = crate::new_rng();
+    let mut net = Network::new();
+    let first_node_port = testing::unused_port_on_localhost();
+
+    let mut first_node_config = Config::default_local_net_first_node(first_node_port);
+    first_node_config.keepalive_interval = TimeDiff::from(100);
+    first_node_config.keepalive_jitter = TimeDiff::from(10);
+    let (node_a, _) = net
+        .add_node_with_config(first_node_config, &mut rng)
+        .await
+        .unwrap();
+    let (node_b, _) = net
+        .add_node_with_config(Config::default_local_net(first_node_port), &mut rng)
+        .await
+        .unwrap();
+
+    let rtt_recorded = |nodes: &Nodes<TestReactor>| {
+        nodes[&node_a]
+            .reactor()
+            .inner()
+            .net
+            .net_metrics
+            .keepalive_rtt
+            .get_sample_count()
+            > 0
+    };
+    net.settle_on(&mut rng, rtt_recorded, Duration::from_secs(20))
+        .await;
+
+    let net_a = &net.nodes()[&node_a].reactor().inner().net;
+    assert!(net_a.keepalives.rtt(&node_b).is_some());
+    assert!(net_a
+        .peer_connections()
+        .iter()
+        .filter(|connection| connection.node_id == Some(node_b))
+        .all(|connection| connection.round_trip_time.is_some()));
+
+    net.finalize().await;
+}
+
+/// Check that a keepalive jitter not smaller than the keepalive interval is rejected.
+#[tokio::test]
+async fn keepalive_jitter_must_be_smaller_than_interval() {
+    init_logging();
+
+    let mut rng = crate::new_rng();
+    let mut net = Network::<TestReactor>::new();
+    let first_node_port = testing::unused_port_on_localhost();
+
+    let mut config = Config::default_local_net_first_node(first_node_port);
+    config.keepalive_interval = TimeDiff::from(100);
+    config.keepalive_jitter = TimeDiff::from(100);
+    assert!(net.add_node_with_config(config, &mut rng).await.is_err());
+
+    // Keepalives are disabled with an interval of 0, so the jitter is not checked.
+    let mut config = Config::default_local_net_first_node(first_node_port);
+    config.keepalive_interval = TimeDiff::from(0);
+    assert!(net.add_node_with_config(config, &mut rng).await.is_ok());
+
+    net.finalize().await;
+}
@@ -7,8 +7,9 @@
 //! * storing and loading deploys,
 //! * [temporary until refactored] holding `DeployMetadata` for each deploy,
 //! * holding a read-only copy of the chainspec,
-//! * keeping an index of blocks by height and
-//! * [unimplemented] managing disk usage by pruning blocks and deploys from storage.
+//! * keeping an index of blocks by height,
+//! * keeping an index of deploys by sending account and
+//! * managing disk usage by pruning old block bodies from storage and optionally compressing them.
 //!
 //! Any I/O performed by the component is done on the event handling thread, this is on purpose as
 //! the assumption is that caching by LMDB will offset any gains from offloading it onto a separate
@@ -26,28 +27,49 @@
 //! * Storing a deploy or block that already exists (same hash) is fine and will silently be
 //!   accepted.
 //!
+//! ## Backends
+//!
+//! Blocks and deploys are stored through a [`StorageBackend`], which by default persists them via
+//! LMDB.  Setting `blocks_and_deploys_in_memory` in the config holds them in memory instead, for
+//! use in tests and ephemeral nodes.  All other data, such as block signatures, execution results
+//! and transfers, is always persisted via LMDB under the configured path.
+//!
 //! ## Indices
 //!
-//! The current implementation keeps only in-memory indices, which are not persisted, based upon the
-//! estimate that they are reasonably quick to rebuild on start-up and do not take up much memory.
+//! Most indices are kept in memory only and are not persisted, based upon the estimate that they
+//! are reasonably quick to rebuild on start-up and do not take up much memory.
+//!
+//! The index of deploys by sending account is the exception: Rebuilding it requires reading every
+//! stored deploy, so it is persisted in its own database instead. Deploys stored by a previous
+//! version of the node are added to it by a schema migration.
 //!
 //! ## Errors
 //!
 //! The storage component itself is panic free and in general reports three classes of errors:
 //! Corruption, temporary resource exhaustion and potential bugs.
+//!
+//! A single block or deploy failing to deserialize is not considered fatal though: The corrupt
+//! entry is logged and reported as missing to the requesting component, which can then retrieve it
+//! from a peer again.
 
+mod backend;
 mod blob_cache;
+mod compression;
 mod lmdb_ext;
+mod metrics;
+mod migration;
 
 #[cfg(test)]
 mod tests;
 
 #[cfg(test)]
-use std::{collections::BTreeSet, convert::TryFrom};
+use std::convert::TryFrom;
 use std::{
-    collections::{btree_map::Entry, BTreeMap, HashSet},
+    cell::RefCell,
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::{self, Display, Formatter},
     fs, io, mem,
+    ops::Bound,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -55,8 +77,10 @@ use std::{
 use datasize::DataSize;
 use derive_more::From;
 use lmdb::{
-    Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, Transaction, WriteFlags,
+    Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, RwTransaction, Transaction,
+    WriteFlags,
 };
+use prometheus::Registry;
 use serde::{Deserialize, Serialize};
 use static_assertions::const_assert;
 #[cfg(test)]
@@ -65,13 +89,16 @@ use thiserror::Error;
 use tracing::{debug, error, info};
 
 use casper_execution_engine::shared::newtypes::Blake2bHash;
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, Transfer, Transform};
+use casper_types::{
+    account::AccountHash, EraId, ExecutionResult, ProtocolVersion, Transfer, Transform,
+};
 
 use super::Component;
 #[cfg(test)]
 use crate::crypto::hash::Digest;
 use crate::{
     effect::{
+        announcements::StorageAnnouncement,
         requests::{StateStoreRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects,
     },
@@ -84,11 +111,20 @@ use crate::{
     utils::{display_error, WithDir},
     NodeRng,
 };
+use backend::{InMemoryStorage, LmdbStorage, StorageBackend};
 use blob_cache::BlobCache;
 use lmdb_ext::{LmdbExtError, TransactionExt, WriteTransactionExt};
+use metrics::StorageMetrics;
+pub(crate) use migration::SCHEMA_VERSION;
 
 /// Filename for the LMDB database created by the Storage component.
 const STORAGE_DB_FILENAME: &str = "storage.lmdb";
+/// Name of the LMDB database holding deploys.
+const DEPLOY_DB_NAME: &str = "deploys";
+/// Name of the LMDB database holding the storage schema version.
+const SCHEMA_DB_NAME: &str = "schema";
+/// Name of the LMDB database holding the index of deploys by sending account.
+const DEPLOY_ACCOUNT_INDEX_DB_NAME: &str = "deploy_account_index";
 
 /// We can set this very low, as there is only a single reader/writer accessing the component at any
 /// one time.
@@ -106,7 +142,7 @@ const DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE: usize = 300 * GIB;
 /// Default max state store size.
 const DEFAULT_MAX_STATE_STORE_SIZE: usize = 10 * GIB;
 /// Maximum number of allowed dbs.
-const MAX_DB_COUNT: u32 = 7;
+const MAX_DB_COUNT: u32 = 10;
 
 /// OS-specific lmdb flags.
 #[cfg(not(target_os = "macos"))]
@@ -170,6 +206,81 @@ pub enum Error {
     /// LMDB error while operating.
     #[error("internal database error: {0}")]
     InternalStorage(#[from] LmdbExtError),
+    /// Failure to register metrics.
+    #[error("failed to register metrics: {0}")]
+    Metrics(#[from] prometheus::Error),
+    /// The databases were written by a newer version of the node.
+    #[error("unsupported storage schema version {found}, latest supported is {supported}")]
+    UnsupportedSchemaVersion {
+        /// Schema version of the databases.
+        found: u32,
+        /// Latest schema version supported by this version of the node.
+        supported: u32,
+    },
+    /// A migration step failed and was rolled back.
+    #[error("failed to migrate storage to schema version {version}: {error}")]
+    Migration {
+        /// Schema version the failed step would have reached.
+        version: u32,
+        /// The underlying error.
+        error: LmdbExtError,
+    },
+}
+
+/// Error retrieving a single block or deploy.
+#[derive(Debug, Error)]
+enum GetItemError {
+    /// No item is stored under the given key.
+    #[error("not found")]
+    NotFound,
+    /// The stored item is corrupt, e.g. due to disk corruption or an incompatible format.
+    #[error("corrupt entry: {0}")]
+    Corrupt(String),
+    /// Any other storage error.
+    #[error(transparent)]
+    Storage(LmdbExtError),
+}
+
+impl GetItemError {
+    /// Classifies the result of retrieving the item stored under `key` from the backend, logging
+    /// any corruption.
+    fn classify<T, K: Display>(
+        result: Result<Option<T>, LmdbExtError>,
+        key: &K,
+    ) -> Result<T, GetItemError> {
+        match result {
+            Ok(Some(item)) => Ok(item),
+            Ok(None) => Err(GetItemError::NotFound),
+            Err(error) if error.is_corrupt_entry() => {
+                error!(%key, %error, "found corrupt entry in storage");
+                Err(GetItemError::Corrupt(error.to_string()))
+            }
+            Err(error) => Err(GetItemError::Storage(error)),
+        }
+    }
+}
+
+/// Converts the result of retrieving an item into an `Option`, treating a corrupt entry like a
+/// missing one, so that it is fetched from a peer again instead of the node crashing.
+fn missing_if_corrupt<T>(result: Result<T, GetItemError>) -> Result<Option<T>, LmdbExtError> {
+    match result {
+        Ok(item) => Ok(Some(item)),
+        Err(GetItemError::NotFound) | Err(GetItemError::Corrupt(_)) => Ok(None),
+        Err(GetItemError::Storage(error)) => Err(error),
+    }
+}
+
+/// Converts the result of retrieving an item into an `Option`, treating a corrupt entry as an
+/// error.
+///
+/// Used where skipping a corrupt item would leave storage's own view of the chain incomplete.
+fn fail_if_corrupt<T>(result: Result<T, GetItemError>) -> Result<Option<T>, LmdbExtError> {
+    match result {
+        Ok(item) => Ok(Some(item)),
+        Err(GetItemError::NotFound) => Ok(None),
+        Err(GetItemError::Corrupt(reason)) => Err(LmdbExtError::DataCorrupted(reason.into())),
+        Err(GetItemError::Storage(error)) => Err(error),
+    }
 }
 
 // We wholesale wrap lmdb errors and treat them as internal errors here.
@@ -185,7 +296,10 @@ pub struct Storage {
     root: PathBuf,
     /// Environment holding LMDB databases.
     #[data_size(skip)]
-    env: Environment,
+    env: Arc<Environment>,
+    /// The backend holding blocks and deploys.
+    #[data_size(skip)]
+    backend: Box<dyn StorageBackend>,
     /// The block header database.
     #[data_size(skip)]
     block_header_db: Database,
@@ -195,9 +309,6 @@ pub struct Storage {
     /// The block metadata db.
     #[data_size(skip)]
     block_metadata_db: Database,
-    /// The deploy database.
-    #[data_size(skip)]
-    deploy_db: Database,
     /// The deploy metadata database.
     #[data_size(skip)]
     deploy_metadata_db: Database,
@@ -213,19 +324,41 @@ pub struct Storage {
     switch_block_era_id_index: BTreeMap<EraId, BlockHash>,
     /// A map of deploy hashes to hashes of blocks containing them.
     deploy_hash_index: BTreeMap<DeployHash, BlockHash>,
+    /// The index of deploys by sending account, mapping account hashes to the set of hashes of the
+    /// deploys sent by them.
+    #[data_size(skip)]
+    deploy_account_index_db: Database,
     /// Whether or not memory deduplication is enabled.
     enable_mem_deduplication: bool,
+    /// Whether or not newly stored block bodies are compressed.
+    compress_block_bodies: bool,
+    /// The number of most recent block bodies to retain, if older ones are pruned automatically.
+    retained_block_bodies: Option<u64>,
+    /// The height below which all block bodies have been pruned since startup.
+    ///
+    /// Blocks stored below this height afterwards are only pruned automatically after a restart.
+    pruned_below: u64,
     /// Pool of loaded items.
     deploy_cache: BlobCache<<Deploy as Item>::Id>,
+    /// Metrics for the storage component.
+    #[data_size(skip)]
+    metrics: StorageMetrics,
+    /// Corrupt entries found while handling the current event, yet to be announced.
+    #[data_size(skip)]
+    corrupt_entries: RefCell<Vec<StorageAnnouncement>>,
 }
 
 impl<REv> Component<REv> for Storage
 where
-    REv: ReactorEvent,
+    REv: ReactorEvent + From<StorageAnnouncement>,
 {
     type Event = Event;
     type ConstructionError = Error;
 
+    fn name(&self) -> &str {
+        "storage"
+    }
+
     fn handle_event(
         &mut self,
         effect_builder: EffectBuilder<REv>,
@@ -242,10 +375,31 @@ where
         // Any error is turned into a fatal effect, the component itself does not panic. Note that
         // we are dropping a lot of responders this way, but since we are crashing with fatal
         // anyway, it should not matter.
-        match result {
+        let mut effects = match result {
             Ok(effects) => effects,
-            Err(err) => fatal!(effect_builder, "storage error: {}", err).ignore(),
+            Err(err) => return fatal!(effect_builder, "storage error: {}", err).ignore(),
+        };
+
+        // Corrupt entries are reported as missing to the requester, and announced so that the
+        // reactor can decide how to recover them.
+        for announcement in mem::take(self.corrupt_entries.get_mut()) {
+            effects.extend(
+                effect_builder
+                    .announce_storage_corruption(announcement)
+                    .ignore(),
+            );
+        }
+        effects
+    }
+
+    fn on_shutdown(&mut self, _effect_builder: EffectBuilder<REv>) -> Effects<Self::Event> {
+        // Flush all committed transactions to disk, so that no partially-written state is left
+        // behind if the process is killed after shutting down.
+        match self.env.sync(true) {
+            Ok(()) => debug!("synced storage to disk"),
+            Err(error) => error!(%error, "failed to sync storage to disk"),
         }
+        Effects::new()
     }
 }
 
@@ -260,6 +414,7 @@ impl Storage {
         hard_reset_to_start_of_era: Option<EraId>,
         protocol_version: ProtocolVersion,
         should_check_integrity: bool,
+        registry: &Registry,
     ) -> Result<Self, Error> {
         let config = cfg.value();
 
@@ -277,6 +432,8 @@ impl Storage {
             .saturating_add(config.max_deploy_metadata_store_size);
 
         // Creates the environment and databases.
+        let db_path = root.join(STORAGE_DB_FILENAME);
+        let is_new = !db_path.exists();
         let env = Environment::new()
             .set_flags(
                 OS_FLAGS |
@@ -288,72 +445,108 @@ impl Storage {
             .set_max_readers(MAX_TRANSACTIONS)
             .set_max_dbs(MAX_DB_COUNT)
             .set_map_size(total_size)
-            .open(&root.join(STORAGE_DB_FILENAME))?;
+            .open(&db_path)?;
 
         let block_header_db = env.create_db(Some("block_header"), DatabaseFlags::empty())?;
         let block_metadata_db = env.create_db(Some("block_metadata"), DatabaseFlags::empty())?;
-        let deploy_db = env.create_db(Some("deploys"), DatabaseFlags::empty())?;
+        let deploy_db = env.create_db(Some(DEPLOY_DB_NAME), DatabaseFlags::empty())?;
         let deploy_metadata_db = env.create_db(Some("deploy_metadata"), DatabaseFlags::empty())?;
         let transfer_db = env.create_db(Some("transfer"), DatabaseFlags::empty())?;
         let state_store_db = env.create_db(Some("state_store"), DatabaseFlags::empty())?;
         let block_body_db = env.create_db(Some("block_body"), DatabaseFlags::empty())?;
+        let standalone_block_header_db =
+            env.create_db(Some("standalone_block_header"), DatabaseFlags::empty())?;
+        let schema_db = env.create_db(Some(SCHEMA_DB_NAME), DatabaseFlags::empty())?;
+        let deploy_account_index_db =
+            env.create_db(Some(DEPLOY_ACCOUNT_INDEX_DB_NAME), DatabaseFlags::empty())?;
+
+        // Bring the databases up to date before reading anything from them.
+        let schema_version = migration::migrate(&env, schema_db, is_new, migration::MIGRATIONS)?;
+        debug!(schema_version, "storage schema is up to date");
 
-        // We now need to restore the block-height index. Log messages allow timing here.
-        info!("reindexing block store");
         let mut block_height_index = BTreeMap::new();
         let mut switch_block_era_id_index = BTreeMap::new();
         let mut deploy_hash_index = BTreeMap::new();
-        let mut block_txn = env.begin_rw_txn()?;
-        let mut cursor = block_txn.open_rw_cursor(block_header_db)?;
 
         let mut deleted_block_hashes = HashSet::new();
-        // Note: `iter_start` has an undocumented panic if called on an empty database. We rely on
-        //       the iterator being at the start when created.
-        for (raw_key, raw_val) in cursor.iter() {
-            let block: BlockHeader = lmdb_ext::deserialize(raw_val)?;
-            if let Some(invalid_era) = hard_reset_to_start_of_era {
-                // Remove blocks that are in to-be-upgraded eras, but have obsolete protocol
-                // versions - they were most likely created before the upgrade and should be
-                // reverted.
-                if block.era_id() >= invalid_era && block.protocol_version() < protocol_version {
-                    let _ = deleted_block_hashes.insert(block.hash());
-                    cursor.del(WriteFlags::empty())?;
-                    continue;
+        // Blocks held in memory start out empty, so there is nothing to reindex.
+        if !config.blocks_and_deploys_in_memory {
+            // We now need to restore the block-height index. Log messages allow timing here.
+            info!("reindexing block store");
+            let mut block_txn = env.begin_rw_txn()?;
+            let mut cursor = block_txn.open_rw_cursor(block_header_db)?;
+
+            // Note: `iter_start` has an undocumented panic if called on an empty database. We rely
+            //       on the iterator being at the start when created.
+            for (raw_key, raw_val) in cursor.iter() {
+                let block: BlockHeader = lmdb_ext::deserialize(raw_val)?;
+                if let Some(invalid_era) = hard_reset_to_start_of_era {
+                    // Remove blocks that are in to-be-upgraded eras, but have obsolete protocol
+                    // versions - they were most likely created before the upgrade and should be
+                    // reverted.
+                    if block.era_id() >= invalid_era && block.protocol_version() < protocol_version
+                    {
+                        let _ = deleted_block_hashes.insert(block.hash());
+                        cursor.del(WriteFlags::empty())?;
+                        continue;
+                    }
                 }
-            }
 
-            if should_check_integrity {
-                assert_eq!(
-                    raw_key,
-                    block.hash().as_ref(),
-                    "found corrupt block in database"
-                );
-            }
+                if should_check_integrity {
+                    assert_eq!(
+                        raw_key,
+                        block.hash().as_ref(),
+                        "found corrupt block in database"
+                    );
+                }
 
-            insert_to_block_header_indices(
-                &mut block_height_index,
-                &mut switch_block_era_id_index,
-                &block,
-            )?;
-
-            let mut body_txn = env.begin_ro_txn()?;
-            let block_body: BlockBody = body_txn
-                .get_value(block_body_db, block.body_hash())?
-                .expect("non-existent block body referred to by header");
-
-            if should_check_integrity {
-                assert_eq!(
-                    *block.body_hash(),
-                    block_body.hash(),
-                    "found corrupt block body in database"
-                );
-            }
+                insert_to_block_header_indices(
+                    &mut block_height_index,
+                    &mut switch_block_era_id_index,
+                    &block,
+                )?;
 
-            insert_to_deploy_index(&mut deploy_hash_index, block.hash(), &block_body)?;
+                // The body is absent if it has been pruned, in which case the block's deploys are
+                // no longer indexed.
+                let mut body_txn = env.begin_ro_txn()?;
+                let maybe_block_body =
+                    compression::get_block_body(&mut body_txn, block_body_db, block.body_hash())?;
+
+                if let Some(block_body) = maybe_block_body {
+                    if should_check_integrity {
+                        assert_eq!(
+                            *block.body_hash(),
+                            block_body.hash(),
+                            "found corrupt block body in database"
+                        );
+                    }
+
+                    insert_to_deploy_index(&mut deploy_hash_index, block.hash(), &block_body)?;
+                }
+            }
+            info!("block store reindexing complete");
+            drop(cursor);
+            block_txn.commit()?;
+        } else {
+            // Deploys held in memory did not survive the restart, so neither may their index.
+            let mut txn = env.begin_rw_txn()?;
+            txn.clear_db(deploy_account_index_db)?;
+            txn.commit()?;
         }
-        info!("block store reindexing complete");
-        drop(cursor);
-        block_txn.commit()?;
+
+        let env = Arc::new(env);
+        let backend: Box<dyn StorageBackend> = if config.blocks_and_deploys_in_memory {
+            Box::new(InMemoryStorage::default())
+        } else {
+            Box::new(LmdbStorage::new(
+                Arc::clone(&env),
+                block_header_db,
+                standalone_block_header_db,
+                block_body_db,
+                deploy_db,
+                config.compress_block_bodies,
+            ))
+        };
 
         let deleted_block_hashes_raw = deleted_block_hashes.iter().map(BlockHash::as_ref).collect();
 
@@ -374,18 +567,24 @@ impl Storage {
         Ok(Storage {
             root,
             env,
+            backend,
             block_header_db,
             block_body_db,
             block_metadata_db,
-            deploy_db,
             deploy_metadata_db,
             transfer_db,
             state_store_db,
             block_height_index,
             switch_block_era_id_index,
             deploy_hash_index,
+            deploy_account_index_db,
             enable_mem_deduplication: config.enable_mem_deduplication,
+            compress_block_bodies: config.compress_block_bodies,
+            retained_block_bodies: config.retained_block_bodies,
+            pruned_below: 0,
             deploy_cache: BlobCache::new(config.mem_pool_prune_interval),
+            metrics: StorageMetrics::new(registry)?,
+            corrupt_entries: RefCell::new(Vec::new()),
         })
     }
 
@@ -454,6 +653,120 @@ impl Storage {
         Ok(result)
     }
 
+    /// Deletes the bodies of all blocks below `height`, along with their transfers and the index
+    /// entries associating their deploys with them.  The block headers are retained.
+    ///
+    /// All deletions happen in a single write transaction.  Returns the number of blocks pruned.
+    /// Only blocks persisted via LMDB are pruned.
+    pub(crate) fn prune_blocks_below(&mut self, height: u64) -> Result<usize, Error> {
+        self.prune_blocks_between(0, height)
+    }
+
+    /// Deletes the bodies of all blocks from `from_height` up to but excluding `height`, as
+    /// `prune_blocks_below` does.
+    ///
+    /// Used when pruning automatically after storing a block, where all blocks below the height
+    /// pruned previously need not be visited again.
+    fn prune_blocks_between(&mut self, from_height: u64, height: u64) -> Result<usize, Error> {
+        let mut txn = self.env.begin_rw_txn()?;
+
+        // Bodies are stored under their hash, so identical bodies are shared between blocks.  Those
+        // still used by a retained block must be kept.
+        let mut retained_body_hashes = HashSet::new();
+        for block_hash in self
+            .block_height_index
+            .range(height..)
+            .map(|(_, hash)| hash)
+        {
+            if let Some(block_header) =
+                backend::get_single_block_header(&mut txn, self.block_header_db, block_hash)?
+            {
+                retained_body_hashes.insert(*block_header.body_hash());
+            }
+        }
+
+        // The deploy hashes of each pruned body, needed to remove them from the deploy index.
+        let mut pruned_bodies: HashMap<Digest, Vec<DeployHash>> = HashMap::new();
+        let mut pruned_block_count = 0;
+        for block_hash in self
+            .block_height_index
+            .range(from_height.min(height)..height)
+            .map(|(_, hash)| hash)
+        {
+            let body_hash =
+                match backend::get_single_block_header(&mut txn, self.block_header_db, block_hash)?
+                {
+                    Some(block_header) => *block_header.body_hash(),
+                    None => continue,
+                };
+            if retained_body_hashes.contains(&body_hash) {
+                continue;
+            }
+            if !pruned_bodies.contains_key(&body_hash) {
+                let block_body = match txn.get(self.block_body_db, &body_hash) {
+                    Ok(raw) => compression::decode_block_body(raw)?,
+                    // Pruned by a previous call already.
+                    Err(lmdb::Error::NotFound) => continue,
+                    Err(err) => return Err(err.into()),
+                };
+                txn.del_value(self.block_body_db, &body_hash)?;
+                let deploy_hashes = block_body
+                    .deploy_hashes()
+                    .iter()
+                    .chain(block_body.transfer_hashes())
+                    .copied()
+                    .collect();
+                pruned_bodies.insert(body_hash, deploy_hashes);
+            }
+            txn.del_value(self.transfer_db, block_hash)?;
+            for deploy_hash in &pruned_bodies[&body_hash] {
+                if self.deploy_hash_index.get(deploy_hash) == Some(block_hash) {
+                    self.deploy_hash_index.remove(deploy_hash);
+                }
+            }
+            pruned_block_count += 1;
+        }
+
+        txn.commit()?;
+        self.pruned_below = self.pruned_below.max(height);
+
+        info!(
+            count = pruned_block_count,
+            height, "pruned block bodies below height"
+        );
+        Ok(pruned_block_count)
+    }
+
+    /// Rewrites every stored block body whose on-disk format does not match the configured
+    /// `compress_block_bodies` setting, compressing or decompressing it as required.
+    ///
+    /// All rewrites happen in a single write transaction.  Returns the number of bodies rewritten.
+    pub(crate) fn recompress_all(&mut self) -> Result<usize, Error> {
+        let mut txn = self.env.begin_rw_txn()?;
+        let mut cursor = txn.open_rw_cursor(self.block_body_db)?;
+
+        let mut count = 0;
+        for (raw_key, raw_val) in cursor.iter() {
+            if compression::is_compressed(raw_val) == self.compress_block_bodies {
+                continue;
+            }
+            let block_body = compression::decode_block_body(raw_val)?;
+            let buffer = compression::encode_block_body(&block_body, self.compress_block_bodies)?;
+            cursor.put(&raw_key, &buffer, WriteFlags::empty())?;
+            count += 1;
+        }
+
+        drop(cursor);
+        txn.commit()?;
+
+        info!(
+            count,
+            compressed = self.compress_block_bodies,
+            "rewrote block bodies"
+        );
+        Ok(count)
+    }
+
     /// Returns the path to the storage folder.
     pub(crate) fn root_path(&self) -> &Path {
         &self.root
@@ -469,23 +782,10 @@ impl Storage {
         // average the actual execution time will be very low.
         Ok(match req {
             StorageRequest::PutBlock { block, responder } => {
-                let mut txn = self.env.begin_rw_txn()?;
-                if !txn.put_value(
-                    self.block_body_db,
-                    block.header().body_hash(),
-                    block.body(),
-                    true,
-                )? {
-                    error!("Could not insert block body for block: {}", block);
-                    txn.abort();
-                    return Ok(responder.respond(false).ignore());
-                }
-                if !txn.put_value(self.block_header_db, block.hash(), block.header(), true)? {
-                    error!("Could not insert block header for block: {}", block);
-                    txn.abort();
+                if !self.put_block(&block)? {
+                    error!("Could not insert block: {}", block);
                     return Ok(responder.respond(false).ignore());
                 }
-                txn.commit()?;
                 insert_to_block_header_indices(
                     &mut self.block_height_index,
                     &mut self.switch_block_era_id_index,
@@ -496,70 +796,74 @@ impl Storage {
                     block.header().hash(),
                     block.body(),
                 )?;
+                if let Some(retained_block_bodies) = self.retained_block_bodies {
+                    let prune_height = (block.height() + 1).saturating_sub(retained_block_bodies);
+                    if prune_height > self.pruned_below {
+                        self.prune_blocks_between(self.pruned_below, prune_height)?;
+                    }
+                }
+                responder.respond(true).ignore()
+            }
+            StorageRequest::PutBlockHeader {
+                block_header,
+                responder,
+            } => {
+                if !self.put_block_header(&block_header)? {
+                    error!("Could not insert block header: {}", block_header);
+                    return Ok(responder.respond(false).ignore());
+                }
+                // The header is not indexed by height or era before its block is stored in full,
+                // as it has not been verified to be part of the chain.
                 responder.respond(true).ignore()
             }
             StorageRequest::GetBlock {
                 block_hash,
                 responder,
             } => responder
-                .respond(self.get_single_block(&mut self.env.begin_ro_txn()?, &block_hash)?)
+                .respond(missing_if_corrupt(self.get_block(&block_hash))?)
+                .ignore(),
+            StorageRequest::GetBlocks {
+                block_hashes,
+                responder,
+            } => responder
+                .respond(self.get_blocks_batch(block_hashes.as_slice())?)
                 .ignore(),
             StorageRequest::GetBlockHeaderAtHeight { height, responder } => responder
-                .respond(self.get_block_header_by_height(&mut self.env.begin_ro_txn()?, height)?)
+                .respond(self.get_block_header_by_height(height)?)
                 .ignore(),
             StorageRequest::GetBlockAtHeight { height, responder } => responder
-                .respond(self.get_block_by_height(&mut self.env.begin_ro_txn()?, height)?)
+                .respond(self.get_block_by_height(height)?)
                 .ignore(),
             StorageRequest::GetHighestBlock { responder } => {
-                let mut txn = self.env.begin_ro_txn()?;
-                responder
-                    .respond(self.get_highest_block(&mut txn)?)
-                    .ignore()
+                responder.respond(self.get_highest_block()?).ignore()
             }
             StorageRequest::GetSwitchBlockHeaderAtEraId { era_id, responder } => responder
-                .respond(
-                    self.get_switch_block_header_by_era_id(&mut self.env.begin_ro_txn()?, era_id)?,
-                )
+                .respond(self.get_switch_block_header_by_era_id(era_id)?)
                 .ignore(),
             StorageRequest::GetSwitchBlockAtEraId { era_id, responder } => responder
-                .respond(self.get_switch_block_by_era_id(&mut self.env.begin_ro_txn()?, era_id)?)
+                .respond(self.get_switch_block_by_era_id(era_id)?)
                 .ignore(),
             StorageRequest::GetBlockHeaderForDeploy {
                 deploy_hash,
                 responder,
-            } => {
-                responder
-                    .respond(self.get_block_header_by_deploy_hash(
-                        &mut self.env.begin_ro_txn()?,
-                        deploy_hash,
-                    )?)
-                    .ignore()
-            }
-            StorageRequest::GetHighestSwitchBlock { responder } => {
-                let mut txn = self.env.begin_ro_txn()?;
-                responder
-                    .respond(
-                        self.switch_block_era_id_index
-                            .keys()
-                            .last()
-                            .and_then(|&era_id| {
-                                self.get_switch_block_by_era_id(&mut txn, era_id)
-                                    .transpose()
-                            })
-                            .transpose()?,
-                    )
-                    .ignore()
-            }
+            } => responder
+                .respond(self.get_block_header_by_deploy_hash(deploy_hash)?)
+                .ignore(),
+            StorageRequest::GetHighestSwitchBlock { responder } => responder
+                .respond(
+                    self.switch_block_era_id_index
+                        .keys()
+                        .last()
+                        .and_then(|&era_id| self.get_switch_block_by_era_id(era_id).transpose())
+                        .transpose()?,
+                )
+                .ignore(),
             StorageRequest::GetBlockHeader {
                 block_hash,
                 responder,
             } => responder
-                // TODO: Find a solution for efficiently retrieving the blocker header without the
-                // block. Deserialization that allows trailing bytes could be a possible solution.
-                .respond(
-                    self.get_single_block(&mut self.env.begin_ro_txn()?, &block_hash)?
-                        .map(|block| block.header().clone()),
-                )
+                // The header is read on its own, as the block's body may have been pruned.
+                .respond(self.get_block_header(&block_hash)?)
                 .ignore(),
             StorageRequest::GetBlockTransfers {
                 block_hash,
@@ -568,8 +872,9 @@ impl Storage {
                 .respond(self.get_transfers(&mut self.env.begin_ro_txn()?, &block_hash)?)
                 .ignore(),
             StorageRequest::PutDeploy { deploy, responder } => {
+                let outcome = self.put_deploy(&deploy)?;
                 let mut txn = self.env.begin_rw_txn()?;
-                let outcome = txn.put_value(self.deploy_db, deploy.id(), &deploy, false)?;
+                insert_to_deploy_account_index(&mut txn, self.deploy_account_index_db, &deploy)?;
                 txn.commit()?;
                 responder.respond(outcome).ignore()
             }
@@ -577,7 +882,7 @@ impl Storage {
                 deploy_hashes,
                 responder,
             } => responder
-                .respond(self.get_deploys(&mut self.env.begin_ro_txn()?, deploy_hashes.as_slice())?)
+                .respond(self.get_deploys_batch(deploy_hashes.as_slice())?)
                 .ignore(),
             StorageRequest::GetDeployHeaders {
                 deploy_hashes,
@@ -585,12 +890,20 @@ impl Storage {
             } => responder
                 .respond(
                     // TODO: Similarly to getting block headers, requires optimized function.
-                    self.get_deploys(&mut self.env.begin_ro_txn()?, deploy_hashes.as_slice())?
+                    self.get_deploys_batch(deploy_hashes.as_slice())?
                         .into_iter()
                         .map(|opt| opt.map(|deploy| deploy.header().clone()))
                         .collect(),
                 )
                 .ignore(),
+            StorageRequest::GetDeploysByAccount {
+                account_hash,
+                limit,
+                cursor,
+                responder,
+            } => responder
+                .respond(self.get_deploys_by_account(&account_hash, limit, cursor)?)
+                .ignore(),
             StorageRequest::PutExecutionResults {
                 block_hash,
                 execution_results,
@@ -656,11 +969,9 @@ impl Storage {
                 deploy_hash,
                 responder,
             } => {
-                let mut txn = self.env.begin_ro_txn()?;
-
                 // A missing deploy causes an early `None` return.
                 let deploy: Deploy =
-                    if let Some(deploy) = txn.get_value(self.deploy_db, &deploy_hash)? {
+                    if let Some(deploy) = missing_if_corrupt(self.get_deploy(&deploy_hash))? {
                         deploy
                     } else {
                         return Ok(responder.respond(None).ignore());
@@ -668,7 +979,7 @@ impl Storage {
 
                 // Missing metadata is filled using a default.
                 let metadata = self
-                    .get_deploy_metadata(&mut txn, &deploy_hash)?
+                    .get_deploy_metadata(&mut self.env.begin_ro_txn()?, &deploy_hash)?
                     .unwrap_or_default();
                 responder.respond(Some((deploy, metadata))).ignore()
             }
@@ -676,17 +987,17 @@ impl Storage {
                 block_hash,
                 responder,
             } => {
-                let mut txn = self.env.begin_ro_txn()?;
-
                 let block: Block =
-                    if let Some(block) = self.get_single_block(&mut txn, &block_hash)? {
+                    if let Some(block) = missing_if_corrupt(self.get_block(&block_hash))? {
                         block
                     } else {
                         return Ok(responder.respond(None).ignore());
                     };
                 // Check that the hash of the block retrieved is correct.
                 assert_eq!(&block_hash, block.hash());
-                let signatures = match self.get_finality_signatures(&mut txn, &block_hash)? {
+                let signatures = match self
+                    .get_finality_signatures(&mut self.env.begin_ro_txn()?, &block_hash)?
+                {
                     Some(signatures) => signatures,
                     None => BlockSignatures::new(block_hash, block.header().era_id()),
                 };
@@ -698,40 +1009,32 @@ impl Storage {
                 block_height,
                 responder,
             } => {
-                let mut txn = self.env.begin_ro_txn()?;
-
-                let block: Block =
-                    if let Some(block) = self.get_block_by_height(&mut txn, block_height)? {
-                        block
-                    } else {
-                        return Ok(responder.respond(None).ignore());
-                    };
+                let block: Block = if let Some(block) = self.get_block_by_height(block_height)? {
+                    block
+                } else {
+                    return Ok(responder.respond(None).ignore());
+                };
 
                 let hash = block.hash();
-                let signatures = match self.get_finality_signatures(&mut txn, hash)? {
-                    Some(signatures) => signatures,
-                    None => BlockSignatures::new(*hash, block.header().era_id()),
-                };
+                let signatures =
+                    match self.get_finality_signatures(&mut self.env.begin_ro_txn()?, hash)? {
+                        Some(signatures) => signatures,
+                        None => BlockSignatures::new(*hash, block.header().era_id()),
+                    };
                 responder.respond(Some((block, signatures))).ignore()
             }
             StorageRequest::GetHighestBlockWithMetadata { responder } => {
-                let mut txn = self.env.begin_ro_txn()?;
-                let highest_block: Block = if let Some(block) = self
-                    .block_height_index
-                    .keys()
-                    .last()
-                    .and_then(|&height| self.get_block_by_height(&mut txn, height).transpose())
-                    .transpose()?
-                {
+                let highest_block: Block = if let Some(block) = self.get_highest_block()? {
                     block
                 } else {
                     return Ok(responder.respond(None).ignore());
                 };
                 let hash = highest_block.hash();
-                let signatures = match self.get_finality_signatures(&mut txn, hash)? {
-                    Some(signatures) => signatures,
-                    None => BlockSignatures::new(*hash, highest_block.header().era_id()),
-                };
+                let signatures =
+                    match self.get_finality_signatures(&mut self.env.begin_ro_txn()?, hash)? {
+                        Some(signatures) => signatures,
+                        None => BlockSignatures::new(*hash, highest_block.header().era_id()),
+                    };
                 responder
                     .respond(Some((highest_block, signatures)))
                     .ignore()
@@ -769,6 +1072,9 @@ impl Storage {
                     self.get_finality_signatures(&mut self.env.begin_ro_txn()?, &block_hash)?;
                 responder.respond(result).ignore()
             }
+            StorageRequest::PruneBlocksBelow { height, responder } => {
+                responder.respond(self.prune_blocks_below(height)?).ignore()
+            }
             StorageRequest::GetFinalizedDeploys { ttl, responder } => {
                 responder.respond(self.get_finalized_deploys(ttl)?).ignore()
             }
@@ -776,23 +1082,23 @@ impl Storage {
     }
 
     /// Retrieves single block header by height by looking it up in the index and returning it.
-    fn get_block_header_and_metadata_by_height<Tx: Transaction>(
+    fn get_block_header_and_metadata_by_height(
         &self,
-        tx: &mut Tx,
         height: u64,
     ) -> Result<Option<BlockHeaderWithMetadata>, Error> {
         let block_hash = match self.block_height_index.get(&height) {
             None => return Ok(None),
             Some(block_hash) => block_hash,
         };
-        let block_header = match self.get_single_block_header(tx, block_hash)? {
+        let block_header = match self.get_block_header(block_hash)? {
             None => return Ok(None),
             Some(block_header) => block_header,
         };
-        let block_signatures = match self.get_finality_signatures(tx, block_hash)? {
-            None => BlockSignatures::new(*block_hash, block_header.era_id()),
-            Some(signatures) => signatures,
-        };
+        let block_signatures =
+            match self.get_finality_signatures(&mut self.env.begin_ro_txn()?, block_hash)? {
+                None => BlockSignatures::new(*block_hash, block_header.era_id()),
+                Some(signatures) => signatures,
+            };
         Ok(Some(BlockHeaderWithMetadata {
             block_header,
             block_signatures,
@@ -804,87 +1110,66 @@ impl Storage {
         &self,
         height: u64,
     ) -> Result<Option<BlockHeaderWithMetadata>, Error> {
-        let mut txn = self.env.begin_ro_txn()?;
-        let maybe_block_header_and_finality_signatures =
-            self.get_block_header_and_metadata_by_height(&mut txn, height)?;
-        drop(txn);
-        Ok(maybe_block_header_and_finality_signatures)
+        self.get_block_header_and_metadata_by_height(height)
     }
 
     /// Retrieves single block header by height by looking it up in the index and returning it.
-    fn get_block_header_by_height<Tx: Transaction>(
-        &self,
-        tx: &mut Tx,
-        height: u64,
-    ) -> Result<Option<BlockHeader>, LmdbExtError> {
+    fn get_block_header_by_height(&self, height: u64) -> Result<Option<BlockHeader>, LmdbExtError> {
         self.block_height_index
             .get(&height)
-            .and_then(|block_hash| self.get_single_block_header(tx, block_hash).transpose())
+            .and_then(|block_hash| self.get_block_header(block_hash).transpose())
             .transpose()
     }
 
     /// Retrieves single block by height by looking it up in the index and returning it.
-    fn get_block_by_height<Tx: Transaction>(
-        &self,
-        tx: &mut Tx,
-        height: u64,
-    ) -> Result<Option<Block>, LmdbExtError> {
+    fn get_block_by_height(&self, height: u64) -> Result<Option<Block>, LmdbExtError> {
         self.block_height_index
             .get(&height)
-            .and_then(|block_hash| self.get_single_block(tx, block_hash).transpose())
+            .and_then(|block_hash| missing_if_corrupt(self.get_block(block_hash)).transpose())
             .transpose()
     }
 
     /// Retrieves single switch block header by era ID by looking it up in the index and returning
     /// it.
-    fn get_switch_block_header_by_era_id<Tx: Transaction>(
+    fn get_switch_block_header_by_era_id(
         &self,
-        tx: &mut Tx,
         era_id: EraId,
     ) -> Result<Option<BlockHeader>, LmdbExtError> {
         self.switch_block_era_id_index
             .get(&era_id)
-            .and_then(|block_hash| self.get_single_block_header(tx, block_hash).transpose())
+            .and_then(|block_hash| self.get_block_header(block_hash).transpose())
             .transpose()
     }
 
     /// Retrieves a single block header by deploy hash by looking it up in the index and returning
     /// it.
-    fn get_block_header_by_deploy_hash<Tx: Transaction>(
+    fn get_block_header_by_deploy_hash(
         &self,
-        tx: &mut Tx,
         deploy_hash: DeployHash,
     ) -> Result<Option<BlockHeader>, LmdbExtError> {
         self.deploy_hash_index
             .get(&deploy_hash)
-            .and_then(|block_hash| self.get_single_block_header(tx, block_hash).transpose())
+            .and_then(|block_hash| self.get_block_header(block_hash).transpose())
             .transpose()
     }
 
     /// Retrieves the highest block from the storage, if one exists.
     /// May return an LMDB error.
-    fn get_highest_block<Tx: Transaction>(
-        &self,
-        txn: &mut Tx,
-    ) -> Result<Option<Block>, LmdbExtError> {
+    fn get_highest_block(&self) -> Result<Option<Block>, LmdbExtError> {
         self.block_height_index
             .keys()
             .last()
-            .and_then(|&height| self.get_block_by_height(txn, height).transpose())
+            .and_then(|&height| self.get_block_by_height(height).transpose())
             .transpose()
     }
 
     /// Returns vector blocks that satisfy the predicate, starting from the latest one and following
     /// the ancestry chain.
-    fn get_blocks_while<F, Tx: Transaction>(
-        &self,
-        txn: &mut Tx,
-        predicate: F,
-    ) -> Result<Vec<Block>, LmdbExtError>
+    fn get_blocks_while<F>(&self, predicate: F) -> Result<Vec<Block>, LmdbExtError>
     where
         F: Fn(&Block) -> bool,
     {
-        let mut next_block = self.get_highest_block(txn)?;
+        let mut next_block = self.get_highest_block()?;
         let mut blocks = Vec::new();
         loop {
             match next_block {
@@ -893,7 +1178,7 @@ impl Storage {
                 Some(block) => {
                     next_block = match block.parent() {
                         None => None,
-                        Some(parent_hash) => self.get_single_block(txn, parent_hash)?,
+                        Some(parent_hash) => fail_if_corrupt(self.get_block(parent_hash))?,
                     };
                     blocks.push(block);
                 }
@@ -907,20 +1192,19 @@ impl Storage {
         &self,
         ttl: TimeDiff,
     ) -> Result<Vec<(DeployHash, DeployHeader)>, LmdbExtError> {
-        let mut txn = self.env.begin_ro_txn()?;
         // We're interested in deploys whose TTL hasn't expired yet.
         let ttl_expired = |block: &Block| block.timestamp().elapsed() < ttl;
         let mut deploys = Vec::new();
-        for block in self.get_blocks_while(&mut txn, ttl_expired)? {
+        for block in self.get_blocks_while(ttl_expired)? {
             for deploy_hash in block
                 .body()
                 .deploy_hashes()
                 .iter()
                 .chain(block.body().transfer_hashes())
             {
-                let deploy_header = self
-                    .get_deploy_header(&mut txn, deploy_hash)?
-                    .expect("deploy to exist in storage");
+                let deploy_header = fail_if_corrupt(self.get_deploy(deploy_hash))?
+                    .expect("deploy to exist in storage")
+                    .take_header();
                 // If block's deploy has already expired, ignore it.
                 // It may happen that deploy was not expired at the time of proposing a block but it
                 // is now.
@@ -934,14 +1218,10 @@ impl Storage {
     }
 
     /// Retrieves single switch block by era ID by looking it up in the index and returning it.
-    fn get_switch_block_by_era_id<Tx: Transaction>(
-        &self,
-        tx: &mut Tx,
-        era_id: EraId,
-    ) -> Result<Option<Block>, LmdbExtError> {
+    fn get_switch_block_by_era_id(&self, era_id: EraId) -> Result<Option<Block>, LmdbExtError> {
         self.switch_block_era_id_index
             .get(&era_id)
-            .and_then(|block_hash| self.get_single_block(tx, block_hash).transpose())
+            .and_then(|block_hash| missing_if_corrupt(self.get_block(block_hash)).transpose())
             .transpose()
     }
 
@@ -968,83 +1248,147 @@ impl Storage {
         Some(blake_hashes)
     }
 
-    /// Retrieves a single block header in a separate transaction from storage.
-    fn get_single_block_header<Tx: Transaction>(
+    // Retrieves a block header to handle a network request.
+    pub fn read_block_header_by_hash(
         &self,
-        tx: &mut Tx,
         block_hash: &BlockHash,
     ) -> Result<Option<BlockHeader>, LmdbExtError> {
-        let block_header: BlockHeader = match tx.get_value(self.block_header_db, &block_hash)? {
-            Some(block_header) => block_header,
-            None => return Ok(None),
-        };
-        let found_block_header_hash = block_header.hash();
-        if found_block_header_hash != *block_hash {
-            return Err(LmdbExtError::BlockHeaderNotStoredUnderItsHash {
-                queried_block_hash: *block_hash,
-                found_block_header_hash,
-            });
-        };
-        Ok(Some(block_header))
+        self.get_block_header(block_hash)
     }
 
-    // Retrieves a block header to handle a network request.
-    pub fn read_block_header_by_hash(
+    /// Stores a block via the backend.
+    fn put_block(&mut self, block: &Block) -> Result<bool, LmdbExtError> {
+        let timer = self.metrics.block_put_seconds.start_timer();
+        let outcome = self.backend.put_block(block)?;
+        timer.observe_duration();
+        Ok(outcome)
+    }
+
+    /// Writes a block header on its own via the backend.
+    fn put_block_header(&mut self, block_header: &BlockHeader) -> Result<bool, LmdbExtError> {
+        let timer = self.metrics.block_put_seconds.start_timer();
+        let outcome = self.backend.put_block_header(block_header)?;
+        timer.observe_duration();
+        Ok(outcome)
+    }
+
+    /// Retrieves a single block via the backend.
+    fn get_block(&self, block_hash: &BlockHash) -> Result<Block, GetItemError> {
+        let timer = self.metrics.block_get_seconds.start_timer();
+        let result = GetItemError::classify(self.backend.get_block(block_hash), block_hash);
+        timer.observe_duration();
+        if let Err(GetItemError::Corrupt(_)) = result {
+            self.corrupt_entries
+                .borrow_mut()
+                .push(StorageAnnouncement::CorruptBlock(*block_hash));
+        }
+        self.metrics.record_block_read(result.is_ok());
+        result
+    }
+
+    /// Retrieves a single block header via the backend.
+    fn get_block_header(
         &self,
         block_hash: &BlockHash,
     ) -> Result<Option<BlockHeader>, LmdbExtError> {
-        let mut txn = self.env.begin_ro_txn()?;
-        let maybe_block_header = self.get_single_block_header(&mut txn, block_hash)?;
-        drop(txn);
+        let timer = self.metrics.block_get_seconds.start_timer();
+        let maybe_block_header = self.backend.get_block_header(block_hash)?;
+        timer.observe_duration();
+        self.metrics.record_block_read(maybe_block_header.is_some());
         Ok(maybe_block_header)
     }
 
-    /// Retrieves a single block in a separate transaction from storage.
-    fn get_single_block<Tx: Transaction>(
+    /// Stores a deploy via the backend.
+    fn put_deploy(&mut self, deploy: &Deploy) -> Result<bool, LmdbExtError> {
+        let timer = self.metrics.deploy_put_seconds.start_timer();
+        let outcome = self.backend.put_deploy(deploy)?;
+        timer.observe_duration();
+        Ok(outcome)
+    }
+
+    /// Returns a page of at most `limit` hashes of deploys sent by the given account, in ascending
+    /// order, starting after `cursor` if given.
+    fn get_deploys_by_account(
         &self,
-        tx: &mut Tx,
-        block_hash: &BlockHash,
-    ) -> Result<Option<Block>, LmdbExtError> {
-        let block_header: BlockHeader = match self.get_single_block_header(tx, block_hash)? {
-            Some(block_header) => block_header,
-            None => return Ok(None),
+        account_hash: &AccountHash,
+        limit: usize,
+        cursor: Option<DeployHash>,
+    ) -> Result<Vec<DeployHash>, LmdbExtError> {
+        let deploy_hashes: BTreeSet<DeployHash> = match self
+            .env
+            .begin_ro_txn()?
+            .get_value(self.deploy_account_index_db, account_hash)?
+        {
+            Some(deploy_hashes) => deploy_hashes,
+            None => return Ok(Vec::new()),
         };
-        let block_body: BlockBody =
-            match tx.get_value(self.block_body_db, block_header.body_hash())? {
-                Some(block_header) => block_header,
-                None => return Ok(None),
-            };
-        let found_block_body_hash = block_body.hash();
-        if found_block_body_hash != *block_header.body_hash() {
-            return Err(LmdbExtError::BlockBodyNotStoredUnderItsHash {
-                queried_block_body_hash: *block_header.body_hash(),
-                found_block_body_hash,
-            });
+        let lower_bound = match cursor {
+            Some(cursor) => Bound::Excluded(cursor),
+            None => Bound::Unbounded,
+        };
+        Ok(deploy_hashes
+            .range((lower_bound, Bound::Unbounded))
+            .take(limit)
+            .copied()
+            .collect())
+    }
+
+    /// Retrieves a single deploy via the backend.
+    fn get_deploy(&self, deploy_hash: &DeployHash) -> Result<Deploy, GetItemError> {
+        let timer = self.metrics.deploy_get_seconds.start_timer();
+        let result = GetItemError::classify(self.backend.get_deploy(deploy_hash), deploy_hash);
+        timer.observe_duration();
+        if let Err(GetItemError::Corrupt(_)) = result {
+            self.corrupt_entries
+                .borrow_mut()
+                .push(StorageAnnouncement::CorruptDeploy(*deploy_hash));
         }
-        let block = Block::new_from_header_and_body(block_header, block_body);
-        Ok(Some(block))
+        self.metrics.record_deploy_read(result.is_ok());
+        result
     }
 
-    /// Retrieves a set of deploys from storage.
-    fn get_deploys<Tx: Transaction>(
+    /// Retrieves a set of blocks from storage, using a single read transaction for all of them
+    /// where the backend supports it.
+    ///
+    /// The results are in the same order as `block_hashes`, with `None` for any block not found.
+    pub fn get_blocks_batch(
         &self,
-        tx: &mut Tx,
-        deploy_hashes: &[DeployHash],
-    ) -> Result<Vec<Option<Deploy>>, LmdbExtError> {
-        deploy_hashes
-            .iter()
-            .map(|deploy_hash| tx.get_value(self.deploy_db, deploy_hash))
-            .collect()
+        block_hashes: &[BlockHash],
+    ) -> Result<Vec<Option<Block>>, LmdbExtError> {
+        let timer = self.metrics.block_get_seconds.start_timer();
+        let blocks = self.backend.get_blocks(block_hashes)?;
+        timer.observe_duration();
+        for maybe_block in &blocks {
+            self.metrics.record_block_read(maybe_block.is_some());
+        }
+        Ok(blocks)
     }
 
-    /// Returns the deploy's header.
-    fn get_deploy_header<Tx: Transaction>(
+    /// Retrieves a set of deploys from storage, using a single read transaction for all of them
+    /// where the backend supports it.
+    ///
+    /// The results are in the same order as `deploy_hashes`, with `None` for any deploy not found.
+    pub fn get_deploys_batch(
         &self,
-        txn: &mut Tx,
-        deploy_hash: &DeployHash,
-    ) -> Result<Option<DeployHeader>, LmdbExtError> {
-        let maybe_deploy: Option<Deploy> = txn.get_value(self.deploy_db, deploy_hash)?;
-        Ok(maybe_deploy.map(|deploy| deploy.header().clone()))
+        deploy_hashes: &[DeployHash],
+    ) -> Result<Vec<Option<Deploy>>, LmdbExtError> {
+        let timer = self.metrics.deploy_get_seconds.start_timer();
+        let deploys = match self.backend.get_deploys(deploy_hashes) {
+            Ok(deploys) => deploys,
+            // Retrieve the deploys one by one to single out the corrupt ones.
+            Err(error) if error.is_corrupt_entry() => {
+                return deploy_hashes
+                    .iter()
+                    .map(|deploy_hash| missing_if_corrupt(self.get_deploy(deploy_hash)))
+                    .collect();
+            }
+            Err(error) => return Err(error),
+        };
+        timer.observe_duration();
+        for maybe_deploy in &deploys {
+            self.metrics.record_deploy_read(maybe_deploy.is_some());
+        }
+        Ok(deploys)
     }
 
     /// Retrieves deploy metadata associated with deploy.
@@ -1079,12 +1423,6 @@ impl Storage {
     ) -> Result<Option<BlockSignatures>, Error> {
         Ok(tx.get_value(self.block_metadata_db, block_hash)?)
     }
-
-    /// Get the lmdb environment
-    #[cfg(test)]
-    pub(crate) fn env(&self) -> &Environment {
-        &self.env
-    }
 }
 
 /// Inserts the relevant entries to the two indices.
@@ -1127,6 +1465,22 @@ fn insert_to_block_header_indices(
     Ok(())
 }
 
+/// Inserts the deploy into the persisted index of deploys by sending account.
+fn insert_to_deploy_account_index(
+    txn: &mut RwTransaction,
+    deploy_account_index_db: Database,
+    deploy: &Deploy,
+) -> Result<(), LmdbExtError> {
+    let account_hash = deploy.header().account().to_account_hash();
+    let mut deploy_hashes: BTreeSet<DeployHash> = txn
+        .get_value(deploy_account_index_db, &account_hash)?
+        .unwrap_or_default();
+    if deploy_hashes.insert(*deploy.id()) {
+        let _ = txn.put_value(deploy_account_index_db, &account_hash, &deploy_hashes, true)?;
+    }
+    Ok(())
+}
+
 /// Inserts the relevant entries to the index.
 ///
 /// If a duplicate entry is encountered, index is not updated and an error is returned.
@@ -1191,6 +1545,23 @@ pub struct Config {
     enable_mem_deduplication: bool,
     /// How many loads before memory duplication checks for dead references.
     mem_pool_prune_interval: u16,
+    /// Whether or not block bodies are compressed with zstd before being written to disk.
+    ///
+    /// Bodies already on disk remain readable regardless of this setting.
+    #[serde(default)]
+    compress_block_bodies: bool,
+    /// Whether or not blocks and deploys are held in memory only, rather than persisted to disk.
+    ///
+    /// Intended for tests and ephemeral nodes, as all blocks and deploys are lost on restart.  All
+    /// other data is still persisted to disk under `path`.
+    #[serde(default)]
+    blocks_and_deploys_in_memory: bool,
+    /// The number of most recent block bodies to retain.
+    ///
+    /// If set, the bodies of blocks this many blocks or more below a newly stored block are
+    /// pruned, retaining their headers.  If unset, no block bodies are pruned.
+    #[serde(default)]
+    retained_block_bodies: Option<u64>,
 }
 
 impl Default for Config {
@@ -1204,6 +1575,9 @@ impl Default for Config {
             max_state_store_size: DEFAULT_MAX_STATE_STORE_SIZE,
             enable_mem_deduplication: false,
             mem_pool_prune_interval: 1024,
+            compress_block_bodies: false,
+            blocks_and_deploys_in_memory: false,
+            retained_block_bodies: None,
         }
     }
 }
@@ -1247,10 +1621,7 @@ impl Storage {
         // directly. This caused a dependency of the storage component on networking functionality,
         // which is highly problematic. For this reason, the code to send a reply has been moved to
         // the dispatching code (which should be removed anyway) as to not taint the interface.
-        self.env
-            .begin_ro_txn()
-            .map_err(Into::into)
-            .and_then(|mut tx| tx.get_value(self.deploy_db, &deploy_hash))
+        missing_if_corrupt(self.get_deploy(&deploy_hash))
             .expect("legacy direct deploy request failed")
     }
 
@@ -1303,11 +1674,7 @@ impl Storage {
     ///
     /// Panics if an IO error occurs.
     pub fn get_deploy_by_hash(&self, deploy_hash: DeployHash) -> Option<Deploy> {
-        let mut txn = self
-            .env
-            .begin_ro_txn()
-            .expect("could not create RO transaction");
-        txn.get_value(self.deploy_db, &deploy_hash)
+        missing_if_corrupt(self.get_deploy(&deploy_hash))
             .expect("could not retrieve value from storage")
     }
 
@@ -1317,13 +1684,17 @@ impl Storage {
     ///
     /// Panics on any IO or db corruption error.
     pub fn get_all_deploy_hashes(&self) -> BTreeSet<DeployHash> {
+        let deploy_db = self
+            .env
+            .open_db(Some(DEPLOY_DB_NAME))
+            .expect("could not open deploy database");
         let txn = self
             .env
             .begin_ro_txn()
             .expect("could not create RO transaction");
 
         let mut cursor = txn
-            .open_ro_cursor(self.deploy_db)
+            .open_ro_cursor(deploy_db)
             .expect("could not create cursor");
 
         cursor
@@ -1334,7 +1705,7 @@ impl Storage {
             .collect()
     }
 
-    /// Get the switch block for a specified era number in a read-only LMDB database transaction.
+    /// Get the switch block for a specified era number.
     ///
     /// # Panics
     ///
@@ -1343,20 +1714,8 @@ impl Storage {
         &self,
         switch_block_era_num: u64,
     ) -> Option<Block> {
-        let mut read_only_lmdb_transaction = self
-            .env()
-            .begin_ro_txn()
-            .expect("Could not start read only transaction for lmdb");
-        let switch_block = self
-            .get_switch_block_by_era_id(
-                &mut read_only_lmdb_transaction,
-                EraId::from(switch_block_era_num),
-            )
-            .expect("LMDB panicked trying to get switch block");
-        read_only_lmdb_transaction
-            .commit()
-            .expect("Could not commit transaction");
-        switch_block
+        self.get_switch_block_by_era_id(EraId::from(switch_block_era_num))
+            .expect("LMDB panicked trying to get switch block")
     }
 }
 
@@ -1379,7 +1738,7 @@ fn initialize_block_body_db(
         }
 
         if should_check_integrity {
-            let body: BlockBody = lmdb_ext::deserialize(raw_val)?;
+            let body = compression::decode_block_body(raw_val)?;
             assert_eq!(
                 raw_key,
                 body.hash().as_ref(),
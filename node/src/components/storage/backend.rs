@@ -0,0 +1,322 @@
+//! Backends for the block and deploy stores of the storage component.
+//!
+//! By default blocks and deploys are persisted via LMDB by the [`LmdbStorage`] backend.  The
+//! [`InMemoryStorage`] backend instead holds them in memory, which is useful for tests and
+//! ephemeral nodes, where their contents need not survive a restart.
+
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
+
+use lmdb::{Database, Environment, Transaction};
+
+use super::{
+    compression,
+    lmdb_ext::{LmdbExtError, TransactionExt, WriteTransactionExt},
+};
+use crate::types::{Block, BlockHash, BlockHeader, Deploy, DeployHash, Item};
+
+/// A store for blocks and deploys, indexed by their hashes.
+pub(crate) trait StorageBackend: Debug + Send {
+    /// Stores a block, overwriting any block previously stored under the same hash.
+    ///
+    /// Returns `true` if the block has been written.
+    fn put_block(&mut self, block: &Block) -> Result<bool, LmdbExtError>;
+
+    /// Stores a block header on its own, overwriting any header previously stored under the same
+    /// hash.
+    ///
+    /// The header is kept apart from those of complete blocks until the complete block is stored,
+    /// so it is not indexed when storage is reopened.
+    ///
+    /// Returns `true` if the header has been written.
+    fn put_block_header(&mut self, block_header: &BlockHeader) -> Result<bool, LmdbExtError>;
+
+    /// Retrieves a block.
+    ///
+    /// Returns `None` if either the block's header or body is not stored.
+    fn get_block(&self, block_hash: &BlockHash) -> Result<Option<Block>, LmdbExtError>;
+
+    /// Retrieves a block header.
+    ///
+    /// The header may be present even if the block's body is not.
+    fn get_block_header(&self, block_hash: &BlockHash)
+        -> Result<Option<BlockHeader>, LmdbExtError>;
+
+    /// Stores a deploy.
+    ///
+    /// Returns `true` if the deploy has been written, `false` if it was already stored. A corrupt
+    /// entry stored under the same hash is replaced.
+    fn put_deploy(&mut self, deploy: &Deploy) -> Result<bool, LmdbExtError>;
+
+    /// Retrieves a deploy.
+    fn get_deploy(&self, deploy_hash: &DeployHash) -> Result<Option<Deploy>, LmdbExtError>;
+
+    /// Retrieves a set of blocks, in the same order as `block_hashes`.
+    fn get_blocks(&self, block_hashes: &[BlockHash]) -> Result<Vec<Option<Block>>, LmdbExtError> {
+        block_hashes
+            .iter()
+            .map(|block_hash| self.get_block(block_hash))
+            .collect()
+    }
+
+    /// Retrieves a set of deploys, in the same order as `deploy_hashes`.
+    fn get_deploys(
+        &self,
+        deploy_hashes: &[DeployHash],
+    ) -> Result<Vec<Option<Deploy>>, LmdbExtError> {
+        deploy_hashes
+            .iter()
+            .map(|deploy_hash| self.get_deploy(deploy_hash))
+            .collect()
+    }
+}
+
+/// Backend persisting blocks and deploys via LMDB.
+#[derive(Debug)]
+pub(crate) struct LmdbStorage {
+    /// Environment holding LMDB databases, shared with the storage component.
+    env: Arc<Environment>,
+    /// The block header database.
+    block_header_db: Database,
+    /// The database of block headers stored without their blocks' bodies.
+    standalone_block_header_db: Database,
+    /// The block body database.
+    block_body_db: Database,
+    /// The deploy database.
+    deploy_db: Database,
+    /// Whether or not newly stored block bodies are compressed.
+    compress_block_bodies: bool,
+}
+
+impl LmdbStorage {
+    /// Creates a new LMDB backend using the given databases.
+    pub(crate) fn new(
+        env: Arc<Environment>,
+        block_header_db: Database,
+        standalone_block_header_db: Database,
+        block_body_db: Database,
+        deploy_db: Database,
+        compress_block_bodies: bool,
+    ) -> Self {
+        LmdbStorage {
+            env,
+            block_header_db,
+            standalone_block_header_db,
+            block_body_db,
+            deploy_db,
+            compress_block_bodies,
+        }
+    }
+
+    /// Retrieves a single block header within the given transaction.
+    fn get_single_block_header<Tx: Transaction>(
+        &self,
+        tx: &mut Tx,
+        block_hash: &BlockHash,
+    ) -> Result<Option<BlockHeader>, LmdbExtError> {
+        get_single_block_header(tx, self.block_header_db, block_hash)
+    }
+
+    /// Retrieves a single block within the given transaction.
+    fn get_single_block<Tx: Transaction>(
+        &self,
+        tx: &mut Tx,
+        block_hash: &BlockHash,
+    ) -> Result<Option<Block>, LmdbExtError> {
+        let block_header = match self.get_single_block_header(tx, block_hash)? {
+            Some(block_header) => block_header,
+            None => return Ok(None),
+        };
+        let block_body =
+            match compression::get_block_body(tx, self.block_body_db, block_header.body_hash())? {
+                Some(block_body) => block_body,
+                None => return Ok(None),
+            };
+        let found_block_body_hash = block_body.hash();
+        if found_block_body_hash != *block_header.body_hash() {
+            return Err(LmdbExtError::BlockBodyNotStoredUnderItsHash {
+                queried_block_body_hash: *block_header.body_hash(),
+                found_block_body_hash,
+            });
+        }
+        let block = Block::new_from_header_and_body(block_header, block_body);
+        Ok(Some(block))
+    }
+}
+
+impl StorageBackend for LmdbStorage {
+    fn put_block(&mut self, block: &Block) -> Result<bool, LmdbExtError> {
+        let mut txn = self.env.begin_rw_txn()?;
+        compression::put_block_body(
+            &mut txn,
+            self.block_body_db,
+            block.header().body_hash(),
+            block.body(),
+            self.compress_block_bodies,
+        )?;
+        if !txn.put_value(self.block_header_db, block.hash(), block.header(), true)? {
+            txn.abort();
+            return Ok(false);
+        }
+        let _ = txn.del_value(self.standalone_block_header_db, block.hash())?;
+        txn.commit()?;
+        Ok(true)
+    }
+
+    fn put_block_header(&mut self, block_header: &BlockHeader) -> Result<bool, LmdbExtError> {
+        let mut txn = self.env.begin_rw_txn()?;
+        if !txn.put_value(
+            self.standalone_block_header_db,
+            &block_header.hash(),
+            block_header,
+            true,
+        )? {
+            txn.abort();
+            return Ok(false);
+        }
+        txn.commit()?;
+        Ok(true)
+    }
+
+    fn get_block(&self, block_hash: &BlockHash) -> Result<Option<Block>, LmdbExtError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let maybe_block = self.get_single_block(&mut txn, block_hash)?;
+        drop(txn);
+        Ok(maybe_block)
+    }
+
+    fn get_block_header(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<Option<BlockHeader>, LmdbExtError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let maybe_block_header = match self.get_single_block_header(&mut txn, block_hash)? {
+            Some(block_header) => Some(block_header),
+            None => get_single_block_header(&mut txn, self.standalone_block_header_db, block_hash)?,
+        };
+        drop(txn);
+        Ok(maybe_block_header)
+    }
+
+    fn put_deploy(&mut self, deploy: &Deploy) -> Result<bool, LmdbExtError> {
+        let mut txn = self.env.begin_rw_txn()?;
+        let mut outcome = txn.put_value(self.deploy_db, deploy.id(), deploy, false)?;
+        if !outcome {
+            // Only an intact entry counts as already stored, a corrupt one is overwritten.
+            match txn.get_value::<_, Deploy>(self.deploy_db, deploy.id()) {
+                Ok(_) => (),
+                Err(error) if error.is_corrupt_entry() => {
+                    outcome = txn.put_value(self.deploy_db, deploy.id(), deploy, true)?;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        txn.commit()?;
+        Ok(outcome)
+    }
+
+    fn get_deploy(&self, deploy_hash: &DeployHash) -> Result<Option<Deploy>, LmdbExtError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let maybe_deploy = txn.get_value(self.deploy_db, deploy_hash)?;
+        drop(txn);
+        Ok(maybe_deploy)
+    }
+
+    /// Retrieves a set of blocks, using a single read transaction for all of them.
+    fn get_blocks(&self, block_hashes: &[BlockHash]) -> Result<Vec<Option<Block>>, LmdbExtError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let blocks = block_hashes
+            .iter()
+            .map(|block_hash| self.get_single_block(&mut txn, block_hash))
+            .collect::<Result<_, _>>()?;
+        drop(txn);
+        Ok(blocks)
+    }
+
+    /// Retrieves a set of deploys, using a single read transaction for all of them.
+    fn get_deploys(
+        &self,
+        deploy_hashes: &[DeployHash],
+    ) -> Result<Vec<Option<Deploy>>, LmdbExtError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        let deploys = deploy_hashes
+            .iter()
+            .map(|deploy_hash| txn.get_value(self.deploy_db, deploy_hash))
+            .collect::<Result<_, _>>()?;
+        drop(txn);
+        Ok(deploys)
+    }
+}
+
+/// Backend holding blocks and deploys in memory.
+#[derive(Debug, Default)]
+pub(crate) struct InMemoryStorage {
+    /// The stored blocks.
+    blocks: HashMap<BlockHash, Block>,
+    /// Block headers stored without their bodies.
+    block_headers: HashMap<BlockHash, BlockHeader>,
+    /// The stored deploys.
+    deploys: HashMap<DeployHash, Deploy>,
+}
+
+impl StorageBackend for InMemoryStorage {
+    fn put_block(&mut self, block: &Block) -> Result<bool, LmdbExtError> {
+        self.blocks.insert(*block.hash(), block.clone());
+        self.block_headers.remove(block.hash());
+        Ok(true)
+    }
+
+    fn put_block_header(&mut self, block_header: &BlockHeader) -> Result<bool, LmdbExtError> {
+        self.block_headers
+            .insert(block_header.hash(), block_header.clone());
+        Ok(true)
+    }
+
+    fn get_block(&self, block_hash: &BlockHash) -> Result<Option<Block>, LmdbExtError> {
+        Ok(self.blocks.get(block_hash).cloned())
+    }
+
+    fn get_block_header(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<Option<BlockHeader>, LmdbExtError> {
+        Ok(self
+            .blocks
+            .get(block_hash)
+            .map(|block| block.header())
+            .or_else(|| self.block_headers.get(block_hash))
+            .cloned())
+    }
+
+    fn put_deploy(&mut self, deploy: &Deploy) -> Result<bool, LmdbExtError> {
+        if self.deploys.contains_key(deploy.id()) {
+            return Ok(false);
+        }
+        self.deploys.insert(*deploy.id(), deploy.clone());
+        Ok(true)
+    }
+
+    fn get_deploy(&self, deploy_hash: &DeployHash) -> Result<Option<Deploy>, LmdbExtError> {
+        Ok(self.deploys.get(deploy_hash).cloned())
+    }
+}
+
+/// Retrieves a single block header from the block header database within the given transaction,
+/// verifying it is stored under its own hash.
+pub(super) fn get_single_block_header<Tx: Transaction>(
+    tx: &mut Tx,
+    block_header_db: Database,
+    block_hash: &BlockHash,
+) -> Result<Option<BlockHeader>, LmdbExtError> {
+    let block_header: BlockHeader = match tx.get_value(block_header_db, &block_hash)? {
+        Some(block_header) => block_header,
+        None => return Ok(None),
+    };
+    let found_block_header_hash = block_header.hash();
+    if found_block_header_hash != *block_hash {
+        return Err(LmdbExtError::BlockHeaderNotStoredUnderItsHash {
+            queried_block_hash: *block_hash,
+            found_block_header_hash,
+        });
+    };
+    Ok(Some(block_header))
+}
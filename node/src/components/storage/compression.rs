@@ -0,0 +1,78 @@
+//! Optional compression of block bodies.
+//!
+//! A block body is stored either as plain bincode, as written by earlier versions of the node, or
+//! as a version byte followed by the zstd-compressed bincode. Plain bincode of a `BlockBody` starts
+//! with the little-endian `u32` variant index of the proposer's public key, so its first byte is
+//! always far below `ZSTD_VERSION`, allowing both formats to coexist in the same database.
+
+use lmdb::{Database, RwTransaction, Transaction, WriteFlags};
+
+use super::lmdb_ext::{self, LmdbExtError};
+use crate::{crypto::hash::Digest, types::BlockBody};
+
+/// Version byte prefixed to a zstd-compressed block body.
+const ZSTD_VERSION: u8 = 0xC5;
+
+/// The zstd compression level, where 0 selects zstd's default level.
+const COMPRESSION_LEVEL: i32 = 0;
+
+/// Returns whether the raw block body record is compressed.
+pub(super) fn is_compressed(raw: &[u8]) -> bool {
+    raw.first() == Some(&ZSTD_VERSION)
+}
+
+/// Encodes a block body, compressing it if `compress` is true.
+pub(super) fn encode_block_body(
+    block_body: &BlockBody,
+    compress: bool,
+) -> Result<Vec<u8>, LmdbExtError> {
+    let serialized = lmdb_ext::serialize(block_body)?;
+    if !compress {
+        return Ok(serialized);
+    }
+
+    let mut buffer = vec![ZSTD_VERSION];
+    zstd::stream::copy_encode(serialized.as_slice(), &mut buffer, COMPRESSION_LEVEL)
+        .map_err(|err| LmdbExtError::Other(Box::new(err)))?;
+    Ok(buffer)
+}
+
+/// Decodes a block body record in either format.
+pub(super) fn decode_block_body(raw: &[u8]) -> Result<BlockBody, LmdbExtError> {
+    match raw.split_first() {
+        Some((&ZSTD_VERSION, compressed)) => {
+            let serialized = zstd::stream::decode_all(compressed)
+                .map_err(|err| LmdbExtError::DataCorrupted(Box::new(err)))?;
+            lmdb_ext::deserialize(&serialized)
+        }
+        _ => lmdb_ext::deserialize(raw),
+    }
+}
+
+/// Loads a block body from the database, decompressing it if required.
+pub(super) fn get_block_body<Tx: Transaction>(
+    txn: &mut Tx,
+    block_body_db: Database,
+    body_hash: &Digest,
+) -> Result<Option<BlockBody>, LmdbExtError> {
+    match txn.get(block_body_db, body_hash) {
+        Ok(raw) => decode_block_body(raw).map(Some),
+        Err(lmdb::Error::NotFound) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Writes a block body to the database, compressing it if `compress` is true.
+///
+/// Any existing value stored under `body_hash` is overwritten.
+pub(super) fn put_block_body(
+    txn: &mut RwTransaction,
+    block_body_db: Database,
+    body_hash: &Digest,
+    block_body: &BlockBody,
+    compress: bool,
+) -> Result<(), LmdbExtError> {
+    let buffer = encode_block_body(block_body, compress)?;
+    txn.put(block_body_db, body_hash, &buffer, WriteFlags::empty())?;
+    Ok(())
+}
@@ -58,6 +58,19 @@ pub enum LmdbExtError {
     },
 }
 
+impl LmdbExtError {
+    /// Returns whether the error is caused by a single corrupt entry, rather than the database as a
+    /// whole.
+    pub(super) fn is_corrupt_entry(&self) -> bool {
+        matches!(
+            self,
+            LmdbExtError::DataCorrupted(_)
+                | LmdbExtError::BlockHeaderNotStoredUnderItsHash { .. }
+                | LmdbExtError::BlockBodyNotStoredUnderItsHash { .. }
+        )
+    }
+}
+
 // Classifies an `lmdb::Error` according to our scheme. This one of the rare cases where we accept a
 // blanked `From<>` implementation for error type conversion.
 impl From<lmdb::Error> for LmdbExtError {
@@ -114,6 +127,11 @@ pub(super) trait WriteTransactionExt {
         value: &V,
         overwrite: bool,
     ) -> Result<bool, LmdbExtError>;
+
+    /// Helper function to delete a value from a database.
+    ///
+    /// Returns `true` if the value has been deleted, `false` if the key did not exist.
+    fn del_value<K: AsRef<[u8]>>(&mut self, db: Database, key: &K) -> Result<bool, LmdbExtError>;
 }
 
 impl<T> TransactionExt for T
@@ -158,6 +176,14 @@ impl WriteTransactionExt for RwTransaction<'_> {
             Err(err) => Err(err.into()),
         }
     }
+
+    fn del_value<K: AsRef<[u8]>>(&mut self, db: Database, key: &K) -> Result<bool, LmdbExtError> {
+        match self.del(db, key, None) {
+            Ok(()) => Ok(true),
+            Err(lmdb::Error::NotFound) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
 }
 
 /// Deserializes from a buffer.
@@ -0,0 +1,124 @@
+use prometheus::{self, Histogram, HistogramOpts, IntCounter, Registry};
+
+use crate::unregister_metric;
+
+/// Creates a histogram of transaction durations, with buckets from one microsecond to eight
+/// seconds.
+fn new_duration_histogram(name: &str, help: &str) -> Result<Histogram, prometheus::Error> {
+    Histogram::with_opts(
+        HistogramOpts::new(name, help)
+            .buckets(prometheus::exponential_buckets(0.000_001, 2.0, 24)?),
+    )
+}
+
+/// Metrics for the storage component.
+#[derive(Debug)]
+pub(super) struct StorageMetrics {
+    /// Number of block or block header reads which found the requested item.
+    pub(super) block_hits: IntCounter,
+    /// Number of block or block header reads which did not find the requested item.
+    pub(super) block_misses: IntCounter,
+    /// Number of deploy reads which found the requested deploy.
+    pub(super) deploy_hits: IntCounter,
+    /// Number of deploy reads which did not find the requested deploy.
+    pub(super) deploy_misses: IntCounter,
+    /// Duration of transactions reading blocks or block headers.
+    pub(super) block_get_seconds: Histogram,
+    /// Duration of transactions writing blocks.
+    pub(super) block_put_seconds: Histogram,
+    /// Duration of transactions reading deploys.
+    pub(super) deploy_get_seconds: Histogram,
+    /// Duration of transactions writing deploys.
+    pub(super) deploy_put_seconds: Histogram,
+    /// Reference to the registry for unregistering.
+    registry: Registry,
+}
+
+impl StorageMetrics {
+    pub(super) fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let block_hits = IntCounter::new(
+            "storage_block_hits",
+            "number of block or block header reads which found the requested item",
+        )?;
+        let block_misses = IntCounter::new(
+            "storage_block_misses",
+            "number of block or block header reads which did not find the requested item",
+        )?;
+        let deploy_hits = IntCounter::new(
+            "storage_deploy_hits",
+            "number of deploy reads which found the requested deploy",
+        )?;
+        let deploy_misses = IntCounter::new(
+            "storage_deploy_misses",
+            "number of deploy reads which did not find the requested deploy",
+        )?;
+        let block_get_seconds = new_duration_histogram(
+            "storage_block_get_seconds",
+            "time taken by storage transactions reading blocks or block headers, in seconds",
+        )?;
+        let block_put_seconds = new_duration_histogram(
+            "storage_block_put_seconds",
+            "time taken by storage transactions writing blocks, in seconds",
+        )?;
+        let deploy_get_seconds = new_duration_histogram(
+            "storage_deploy_get_seconds",
+            "time taken by storage transactions reading deploys, in seconds",
+        )?;
+        let deploy_put_seconds = new_duration_histogram(
+            "storage_deploy_put_seconds",
+            "time taken by storage transactions writing deploys, in seconds",
+        )?;
+
+        registry.register(Box::new(block_hits.clone()))?;
+        registry.register(Box::new(block_misses.clone()))?;
+        registry.register(Box::new(deploy_hits.clone()))?;
+        registry.register(Box::new(deploy_misses.clone()))?;
+        registry.register(Box::new(block_get_seconds.clone()))?;
+        registry.register(Box::new(block_put_seconds.clone()))?;
+        registry.register(Box::new(deploy_get_seconds.clone()))?;
+        registry.register(Box::new(deploy_put_seconds.clone()))?;
+
+        Ok(StorageMetrics {
+            block_hits,
+            block_misses,
+            deploy_hits,
+            deploy_misses,
+            block_get_seconds,
+            block_put_seconds,
+            deploy_get_seconds,
+            deploy_put_seconds,
+            registry: registry.clone(),
+        })
+    }
+
+    /// Records the outcome of reading a block.
+    pub(super) fn record_block_read(&self, found: bool) {
+        if found {
+            self.block_hits.inc();
+        } else {
+            self.block_misses.inc();
+        }
+    }
+
+    /// Records the outcome of reading a deploy.
+    pub(super) fn record_deploy_read(&self, found: bool) {
+        if found {
+            self.deploy_hits.inc();
+        } else {
+            self.deploy_misses.inc();
+        }
+    }
+}
+
+impl Drop for StorageMetrics {
+    fn drop(&mut self) {
+        unregister_metric!(self.registry, self.block_hits);
+        unregister_metric!(self.registry, self.block_misses);
+        unregister_metric!(self.registry, self.deploy_hits);
+        unregister_metric!(self.registry, self.deploy_misses);
+        unregister_metric!(self.registry, self.block_get_seconds);
+        unregister_metric!(self.registry, self.block_put_seconds);
+        unregister_metric!(self.registry, self.deploy_get_seconds);
+        unregister_metric!(self.registry, self.deploy_put_seconds);
+    }
+}
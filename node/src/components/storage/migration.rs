@@ -0,0 +1,184 @@
+//! Versioned storage schema and migrations.
+//!
+//! The version of the layout of the storage databases is recorded in the schema database. On
+//! startup, a database written by an earlier version of the node is brought up to date by applying
+//! the migration steps leading from its version to [`SCHEMA_VERSION`] in order.
+//!
+//! Every step is applied within a single transaction, which also records the version reached, so a
+//! failing step is rolled back entirely and leaves the database at the version of the previous
+//! step. Databases written before the schema was versioned have no version record and are treated
+//! as being at version 1.
+
+use std::convert::TryInto;
+
+use lmdb::{Cursor, Database, Environment, RwTransaction, Transaction, WriteFlags};
+use tracing::{error, info};
+
+use super::{
+    insert_to_deploy_account_index, lmdb_ext, lmdb_ext::LmdbExtError, Error,
+    DEPLOY_ACCOUNT_INDEX_DB_NAME, DEPLOY_DB_NAME,
+};
+use crate::types::Deploy;
+
+/// The version of the storage schema written by this version of the node.
+pub(crate) const SCHEMA_VERSION: u32 = 3;
+
+/// The version of a database without a version record, written before the schema was versioned.
+const UNVERSIONED_SCHEMA_VERSION: u32 = 1;
+
+/// Key under which the schema version is stored in the schema database.
+pub(super) const SCHEMA_VERSION_KEY: &[u8] = b"schema_version";
+
+/// A single migration step.
+pub(super) struct Migration {
+    /// The schema version reached by applying this step.
+    pub(super) version: u32,
+    /// Short description of the step, for logging.
+    pub(super) description: &'static str,
+    /// Migrates the databases from the previous version to `version`.
+    pub(super) apply: fn(&Environment, &mut RwTransaction) -> Result<(), LmdbExtError>,
+}
+
+/// All migration steps, ordered by the version they reach.
+pub(super) const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 2,
+        description: "introduce the schema version record",
+        apply: introduce_schema_version,
+    },
+    Migration {
+        version: 3,
+        description: "index deploys by sending account",
+        apply: index_deploys_by_account,
+    },
+];
+
+/// Migrates from version 1 to 2.
+///
+/// The layout of the databases is unchanged, the version record itself is written by `migrate`.
+fn introduce_schema_version(
+    _env: &Environment,
+    _txn: &mut RwTransaction,
+) -> Result<(), LmdbExtError> {
+    Ok(())
+}
+
+/// Migrates from version 2 to 3.
+///
+/// Adds all stored deploys to the index of deploys by sending account. A deploy failing to
+/// deserialize is logged and left out of the index, as it is reported as missing when read anyway.
+fn index_deploys_by_account(
+    env: &Environment,
+    txn: &mut RwTransaction,
+) -> Result<(), LmdbExtError> {
+    let deploy_db = env.open_db(Some(DEPLOY_DB_NAME))?;
+    let deploy_account_index_db = env.open_db(Some(DEPLOY_ACCOUNT_INDEX_DB_NAME))?;
+
+    let mut deploys = Vec::new();
+    {
+        let mut cursor = txn.open_ro_cursor(deploy_db)?;
+        for (raw_key, raw_val) in cursor.iter() {
+            match lmdb_ext::deserialize::<Deploy>(raw_val) {
+                Ok(deploy) => deploys.push(deploy),
+                Err(error) => error!(
+                    key = %base16::encode_lower(raw_key),
+                    %error,
+                    "skipping corrupt deploy while indexing deploys by account"
+                ),
+            }
+        }
+    }
+
+    for deploy in &deploys {
+        insert_to_deploy_account_index(txn, deploy_account_index_db, deploy)?;
+    }
+    Ok(())
+}
+
+/// Reads the schema version of the databases.
+pub(super) fn read_schema_version(env: &Environment, schema_db: Database) -> Result<u32, Error> {
+    let txn = env.begin_ro_txn()?;
+    let version = match txn.get(schema_db, &SCHEMA_VERSION_KEY) {
+        Ok(raw) => {
+            let bytes = raw.try_into().map_err(|_| {
+                LmdbExtError::DataCorrupted(
+                    format!("invalid schema version record {:?}", raw).into(),
+                )
+            })?;
+            u32::from_le_bytes(bytes)
+        }
+        Err(lmdb::Error::NotFound) => UNVERSIONED_SCHEMA_VERSION,
+        Err(error) => return Err(error.into()),
+    };
+    Ok(version)
+}
+
+/// Records the schema version of the databases.
+fn write_schema_version(
+    txn: &mut RwTransaction,
+    schema_db: Database,
+    version: u32,
+) -> Result<(), LmdbExtError> {
+    txn.put(
+        schema_db,
+        &SCHEMA_VERSION_KEY,
+        &version.to_le_bytes(),
+        WriteFlags::empty(),
+    )?;
+    Ok(())
+}
+
+/// Applies the steps of `migrations` the databases have not reached yet, returning the resulting
+/// schema version.
+///
+/// A newly created database has nothing to migrate and is stamped with the latest version right
+/// away.
+pub(super) fn migrate(
+    env: &Environment,
+    schema_db: Database,
+    is_new: bool,
+    migrations: &[Migration],
+) -> Result<u32, Error> {
+    let latest_version = migrations
+        .last()
+        .map_or(UNVERSIONED_SCHEMA_VERSION, |migration| migration.version);
+
+    if is_new {
+        let mut txn = env.begin_rw_txn()?;
+        write_schema_version(&mut txn, schema_db, latest_version)?;
+        txn.commit()?;
+        return Ok(latest_version);
+    }
+
+    let mut version = read_schema_version(env, schema_db)?;
+    if version > latest_version {
+        return Err(Error::UnsupportedSchemaVersion {
+            found: version,
+            supported: latest_version,
+        });
+    }
+
+    for migration in migrations
+        .iter()
+        .filter(|migration| migration.version > version)
+    {
+        info!(
+            from = version,
+            to = migration.version,
+            description = migration.description,
+            "migrating storage schema"
+        );
+        // Dropping the transaction on error aborts it, rolling back the step.
+        let mut txn = env.begin_rw_txn()?;
+        (migration.apply)(env, &mut txn)
+            .and_then(|()| write_schema_version(&mut txn, schema_db, migration.version))
+            .map_err(|error| Error::Migration {
+                version: migration.version,
+                error,
+            })?;
+        txn.commit()?;
+        version = migration.version;
+    }
+
+    Ok(version)
+}
@@ -2,25 +2,37 @@
 
 use std::{borrow::Cow, collections::HashMap};
 
-use lmdb::Transaction;
+use lmdb::{Cursor, Environment, RwTransaction, Transaction, WriteFlags};
+use prometheus::Registry;
 use rand::{prelude::SliceRandom, Rng};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use smallvec::smallvec;
+use test::Bencher;
 
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey, SecretKey};
+use casper_types::{
+    account::AccountHash, EraId, ExecutionResult, ProtocolVersion, PublicKey, SecretKey,
+};
 
-use super::{Config, Storage};
+use super::{
+    backend::{InMemoryStorage, StorageBackend},
+    compression,
+    lmdb_ext::LmdbExtError,
+    migration::{self, Migration},
+    Config, Error, GetItemError, Storage, DEPLOY_ACCOUNT_INDEX_DB_NAME, DEPLOY_DB_NAME,
+    SCHEMA_DB_NAME, SCHEMA_VERSION,
+};
 use crate::{
-    components::storage::lmdb_ext::WriteTransactionExt,
+    components::{storage::lmdb_ext::WriteTransactionExt, Component},
     crypto::AsymmetricKeyExt,
     effect::{
+        announcements::StorageAnnouncement,
         requests::{StateStoreRequest, StorageRequest},
         Multiple,
     },
     testing::{ComponentHarness, TestRng, UnitTestEvent},
     types::{
         Block, BlockHash, BlockHeader, BlockSignatures, Deploy, DeployHash, DeployMetadata,
-        FinalitySignature,
+        FinalitySignature, TimeDiff, Timestamp,
     },
     utils::WithDir,
 };
@@ -37,6 +49,9 @@ fn new_config(harness: &ComponentHarness<UnitTestEvent>) -> Config {
         max_state_store_size: 50 * MIB,
         enable_mem_deduplication: false,
         mem_pool_prune_interval: 1024,
+        compress_block_bodies: false,
+        blocks_and_deploys_in_memory: false,
+        retained_block_bodies: None,
     }
 }
 
@@ -54,10 +69,35 @@ fn storage_fixture(harness: &ComponentHarness<UnitTestEvent>) -> Storage {
         None,
         ProtocolVersion::from_parts(1, 0, 0),
         false,
+        &Registry::new(),
     )
     .expect("could not create storage component fixture")
 }
 
+/// Storage component test fixture.
+///
+/// Creates a storage component holding blocks and deploys in memory, with its remaining data in a
+/// temporary directory distinct from that of `storage_fixture`.
+///
+/// # Panics
+///
+/// Panics if setting up the storage fixture fails.
+fn in_memory_storage_fixture(harness: &ComponentHarness<UnitTestEvent>) -> Storage {
+    let cfg = Config {
+        path: harness.tmp.path().join("in_memory_storage"),
+        blocks_and_deploys_in_memory: true,
+        ..new_config(harness)
+    };
+    Storage::new(
+        &WithDir::new(harness.tmp.path(), cfg),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        false,
+        &Registry::new(),
+    )
+    .expect("could not create in-memory storage component fixture")
+}
+
 /// Storage component test fixture.
 ///
 /// Creates a storage component in a temporary directory, but with a hard reset to a specified era.
@@ -75,6 +115,7 @@ fn storage_fixture_with_hard_reset(
         Some(reset_era_id),
         ProtocolVersion::from_parts(1, 1, 0),
         false,
+        &Registry::new(),
     )
     .expect("could not create storage component fixture")
 }
@@ -271,6 +312,41 @@ fn put_deploy(
     response
 }
 
+/// Creates a random deploy sent by the account of the given secret key.
+fn random_deploy_from(rng: &mut TestRng, secret_key: &SecretKey) -> Deploy {
+    Deploy::new(
+        Timestamp::random(rng),
+        TimeDiff::from(60_000),
+        1,
+        vec![],
+        String::from("casper-example"),
+        rng.gen(),
+        rng.gen(),
+        secret_key,
+    )
+}
+
+/// Requests a page of the hashes of deploys sent by an account from a storage component.
+fn get_deploys_by_account(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    account_hash: AccountHash,
+    limit: usize,
+    cursor: Option<DeployHash>,
+) -> Vec<DeployHash> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetDeploysByAccount {
+            account_hash: Box::new(account_hash),
+            limit,
+            cursor,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 /// Stores execution results in a storage component.
 fn put_execution_results(
     harness: &mut ComponentHarness<UnitTestEvent>,
@@ -574,6 +650,56 @@ fn can_retrieve_block_by_height() {
     );
 }
 
+#[test]
+fn should_index_block_header_stored_on_its_own_only_once_block_is_complete() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = Box::new(Block::random(&mut harness.rng));
+    let block_header = Box::new(block.header().clone());
+    let header_to_store = block_header.clone();
+    let was_stored = harness.send_request(&mut storage, move |responder| {
+        StorageRequest::PutBlockHeader {
+            block_header: header_to_store,
+            responder,
+        }
+        .into()
+    });
+    assert!(was_stored);
+    assert_eq!(
+        storage.read_block_header_by_hash(block.hash()).unwrap(),
+        Some(*block_header.clone())
+    );
+    assert_eq!(
+        get_block_header_at_height(&mut harness, &mut storage, block.height()),
+        None
+    );
+
+    // The header is not indexed after a restart either.
+    drop(storage);
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture(&harness);
+    assert_eq!(
+        storage.read_block_header_by_hash(block.hash()).unwrap(),
+        Some(*block_header.clone())
+    );
+    assert_eq!(
+        get_block_header_at_height(&mut harness, &mut storage, block.height()),
+        None
+    );
+
+    // Storing the complete block indexes it.
+    put_block(&mut harness, &mut storage, block.clone());
+    assert_eq!(
+        get_block_header_at_height(&mut harness, &mut storage, block.height()),
+        Some(*block_header)
+    );
+}
+
 #[test]
 #[should_panic(expected = "duplicate entries")]
 fn different_block_at_height_is_fatal() {
@@ -694,6 +820,378 @@ fn storing_and_loading_a_lot_of_deploys_does_not_exhaust_handles() {
     }
 }
 
+#[test]
+fn should_get_blocks_and_deploys_in_batch_preserving_order() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let blocks: Vec<_> = (0..5)
+        .map(|height| random_block_at_height(&mut harness.rng, height))
+        .collect();
+    for block in &blocks {
+        put_block(&mut harness, &mut storage, block.clone());
+    }
+    let deploys: Vec<_> = (0..5)
+        .map(|_| Box::new(Deploy::random(&mut harness.rng)))
+        .collect();
+    for deploy in &deploys {
+        put_deploy(&mut harness, &mut storage, deploy.clone());
+    }
+
+    // Request in reverse order, with an unknown hash in the middle.
+    let mut block_hashes: Vec<_> = blocks.iter().rev().map(|block| *block.hash()).collect();
+    block_hashes.insert(2, BlockHash::random(&mut harness.rng));
+    let mut expected_blocks: Vec<_> = blocks
+        .iter()
+        .rev()
+        .map(|block| Some(*block.clone()))
+        .collect();
+    expected_blocks.insert(2, None);
+
+    assert_eq!(
+        storage.get_blocks_batch(&block_hashes).unwrap(),
+        expected_blocks
+    );
+    let response = harness.send_request(&mut storage, |responder| {
+        StorageRequest::GetBlocks {
+            block_hashes,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    assert_eq!(response, expected_blocks);
+
+    let mut deploy_hashes: Vec<_> = deploys.iter().rev().map(|deploy| *deploy.id()).collect();
+    deploy_hashes.insert(2, DeployHash::random(&mut harness.rng));
+    let mut expected_deploys: Vec<_> = deploys
+        .iter()
+        .rev()
+        .map(|deploy| Some(*deploy.clone()))
+        .collect();
+    expected_deploys.insert(2, None);
+
+    assert_eq!(
+        storage.get_deploys_batch(&deploy_hashes).unwrap(),
+        expected_deploys
+    );
+}
+
+#[test]
+fn should_get_deploys_by_account_in_pages() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let alice_secret_key = SecretKey::random(&mut harness.rng);
+    let bob_secret_key = SecretKey::random(&mut harness.rng);
+    let alice_account_hash = PublicKey::from(&alice_secret_key).to_account_hash();
+
+    let mut alice_deploy_hashes = Vec::new();
+    for _ in 0..5 {
+        let alice_deploy = random_deploy_from(&mut harness.rng, &alice_secret_key);
+        let bob_deploy = random_deploy_from(&mut harness.rng, &bob_secret_key);
+        alice_deploy_hashes.push(*alice_deploy.id());
+        put_deploy(&mut harness, &mut storage, Box::new(alice_deploy));
+        put_deploy(&mut harness, &mut storage, Box::new(bob_deploy));
+    }
+    alice_deploy_hashes.sort();
+
+    // Page through Alice's deploys, two at a time.
+    let mut retrieved = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page =
+            get_deploys_by_account(&mut harness, &mut storage, alice_account_hash, 2, cursor);
+        assert!(page.len() <= 2);
+        match page.last() {
+            Some(last) => cursor = Some(*last),
+            None => break,
+        }
+        retrieved.extend(page);
+    }
+    assert_eq!(retrieved, alice_deploy_hashes);
+
+    let unknown_account_hash = AccountHash::new(harness.rng.gen());
+    assert!(
+        get_deploys_by_account(&mut harness, &mut storage, unknown_account_hash, 10, None)
+            .is_empty()
+    );
+
+    // The index is persisted across a restart.
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture(&harness);
+    assert_eq!(
+        get_deploys_by_account(&mut harness, &mut storage, alice_account_hash, 10, None),
+        alice_deploy_hashes
+    );
+}
+
+#[test]
+fn should_migrate_unversioned_database() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = Box::new(Block::random(&mut harness.rng));
+    let deploy = Box::new(Deploy::random(&mut harness.rng));
+    put_block(&mut harness, &mut storage, block.clone());
+    put_deploy(&mut harness, &mut storage, deploy.clone());
+
+    let schema_db = storage
+        .env
+        .open_db(Some(SCHEMA_DB_NAME))
+        .expect("could not open schema database");
+    assert_eq!(
+        migration::read_schema_version(&storage.env, schema_db).unwrap(),
+        SCHEMA_VERSION
+    );
+
+    // Turn the database into one written before the schema was versioned.
+    let mut txn = storage
+        .env
+        .begin_rw_txn()
+        .expect("could not start transaction");
+    txn.del(schema_db, &migration::SCHEMA_VERSION_KEY, None)
+        .expect("could not remove schema version");
+    txn.commit().expect("could not commit transaction");
+    assert_eq!(
+        migration::read_schema_version(&storage.env, schema_db).unwrap(),
+        1
+    );
+    drop(storage);
+
+    // The database is migrated on restart, without losing any data.
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture(&harness);
+    let schema_db = storage
+        .env
+        .open_db(Some(SCHEMA_DB_NAME))
+        .expect("could not open schema database");
+    assert_eq!(
+        migration::read_schema_version(&storage.env, schema_db).unwrap(),
+        SCHEMA_VERSION
+    );
+    assert_eq!(
+        get_block(&mut harness, &mut storage, *block.hash()),
+        Some(*block.clone())
+    );
+    assert_eq!(
+        get_block_at_height(&mut harness, &mut storage, block.height()),
+        Some(*block)
+    );
+    assert_eq!(
+        get_deploys(&mut harness, &mut storage, smallvec![*deploy.id()]),
+        vec![Some(*deploy)]
+    );
+}
+
+#[test]
+fn should_index_deploys_by_account_when_migrating() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let secret_key = SecretKey::random(&mut harness.rng);
+    let account_hash = PublicKey::from(&secret_key).to_account_hash();
+    let deploy = Box::new(random_deploy_from(&mut harness.rng, &secret_key));
+    put_deploy(&mut harness, &mut storage, deploy.clone());
+
+    // Turn the database into one written before deploys were indexed by account, holding a corrupt
+    // deploy as well.
+    let schema_db = storage
+        .env
+        .open_db(Some(SCHEMA_DB_NAME))
+        .expect("could not open schema database");
+    let deploy_db = storage
+        .env
+        .open_db(Some(DEPLOY_DB_NAME))
+        .expect("could not open deploy database");
+    let deploy_account_index_db = storage
+        .env
+        .open_db(Some(DEPLOY_ACCOUNT_INDEX_DB_NAME))
+        .expect("could not open deploy account index database");
+    let mut txn = storage
+        .env
+        .begin_rw_txn()
+        .expect("could not start transaction");
+    txn.put(
+        schema_db,
+        &migration::SCHEMA_VERSION_KEY,
+        &2u32.to_le_bytes(),
+        WriteFlags::empty(),
+    )
+    .expect("could not write schema version");
+    txn.clear_db(deploy_account_index_db)
+        .expect("could not clear deploy account index");
+    txn.put(
+        deploy_db,
+        &DeployHash::random(&mut harness.rng),
+        &[0xff; 7],
+        WriteFlags::empty(),
+    )
+    .expect("could not write garbage");
+    txn.commit().expect("could not commit transaction");
+    assert!(get_deploys_by_account(&mut harness, &mut storage, account_hash, 10, None).is_empty());
+    drop(storage);
+
+    // The migration skips the corrupt deploy and indexes the intact one.
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture(&harness);
+    let schema_db = storage
+        .env
+        .open_db(Some(SCHEMA_DB_NAME))
+        .expect("could not open schema database");
+    assert_eq!(
+        migration::read_schema_version(&storage.env, schema_db).unwrap(),
+        SCHEMA_VERSION
+    );
+    assert_eq!(
+        get_deploys_by_account(&mut harness, &mut storage, account_hash, 10, None),
+        vec![*deploy.id()]
+    );
+}
+
+#[test]
+fn should_roll_back_failed_migration() {
+    fn unchanged(_env: &Environment, _txn: &mut RwTransaction) -> Result<(), LmdbExtError> {
+        Ok(())
+    }
+
+    fn write_then_fail(env: &Environment, txn: &mut RwTransaction) -> Result<(), LmdbExtError> {
+        let deploy_db = env.open_db(Some(DEPLOY_DB_NAME))?;
+        txn.put(deploy_db, b"partial", b"migration", WriteFlags::empty())?;
+        Err(LmdbExtError::Other("migration failed".into()))
+    }
+
+    let migrations = [
+        Migration {
+            version: SCHEMA_VERSION,
+            description: "current version",
+            apply: unchanged,
+        },
+        Migration {
+            version: SCHEMA_VERSION + 1,
+            description: "failing step",
+            apply: write_then_fail,
+        },
+    ];
+
+    let harness = ComponentHarness::default();
+    let storage = storage_fixture(&harness);
+    let schema_db = storage
+        .env
+        .open_db(Some(SCHEMA_DB_NAME))
+        .expect("could not open schema database");
+
+    let result = migration::migrate(&storage.env, schema_db, false, &migrations);
+    assert!(matches!(
+        result,
+        Err(Error::Migration { version, .. }) if version == SCHEMA_VERSION + 1
+    ));
+
+    // Neither the changes of the failed step nor its version were committed.
+    assert_eq!(
+        migration::read_schema_version(&storage.env, schema_db).unwrap(),
+        SCHEMA_VERSION
+    );
+    let deploy_db = storage
+        .env
+        .open_db(Some(DEPLOY_DB_NAME))
+        .expect("could not open deploy database");
+    let txn = storage
+        .env
+        .begin_ro_txn()
+        .expect("could not start transaction");
+    assert_eq!(txn.get(deploy_db, b"partial"), Err(lmdb::Error::NotFound));
+}
+
+#[test]
+fn should_report_corrupt_deploy_as_missing() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploy = Box::new(Deploy::random(&mut harness.rng));
+    let corrupt_deploy = Box::new(Deploy::random(&mut harness.rng));
+    put_deploy(&mut harness, &mut storage, deploy.clone());
+
+    // Write garbage bytes under the hash of the second deploy, bypassing the component.
+    let deploy_db = storage
+        .env
+        .open_db(Some(DEPLOY_DB_NAME))
+        .expect("could not open deploy database");
+    let mut txn = storage
+        .env
+        .begin_rw_txn()
+        .expect("could not start transaction");
+    txn.put(
+        deploy_db,
+        corrupt_deploy.id(),
+        &[0xff; 7],
+        WriteFlags::empty(),
+    )
+    .expect("could not write garbage");
+    txn.commit().expect("could not commit transaction");
+
+    assert!(matches!(
+        storage.get_deploy(corrupt_deploy.id()),
+        Err(GetItemError::Corrupt(_))
+    ));
+    assert!(matches!(
+        storage.get_deploy(&DeployHash::random(&mut harness.rng)),
+        Err(GetItemError::NotFound)
+    ));
+
+    // Requests treat the corrupt deploy as missing rather than failing, so it can be fetched again.
+    let deploy_hashes = vec![*deploy.id(), *corrupt_deploy.id()];
+    let response = harness.send_request(&mut storage, |responder| {
+        StorageRequest::GetDeploys {
+            deploy_hashes,
+            responder,
+        }
+        .into()
+    });
+    assert_eq!(response, vec![Some(*deploy.clone()), None]);
+
+    // Both the direct read and the request above announce the corrupt deploy to the reactor.
+    for _ in 0..2 {
+        let ((event, _context), _queue_kind) = harness.runtime.block_on(harness.scheduler.pop());
+        assert!(matches!(
+            event,
+            UnitTestEvent::StorageAnnouncement(StorageAnnouncement::CorruptDeploy(deploy_hash))
+                if deploy_hash == *corrupt_deploy.id()
+        ));
+    }
+    assert!(harness.is_idle());
+
+    // Storing the deploy again replaces the corrupt entry.
+    assert!(put_deploy(
+        &mut harness,
+        &mut storage,
+        corrupt_deploy.clone()
+    ));
+    assert!(!put_deploy(
+        &mut harness,
+        &mut storage,
+        corrupt_deploy.clone()
+    ));
+    let response = get_deploys(
+        &mut harness,
+        &mut storage,
+        smallvec![*deploy.id(), *corrupt_deploy.id()],
+    );
+    assert_eq!(response, vec![Some(*deploy), Some(*corrupt_deploy)]);
+}
+
 #[test]
 fn store_execution_results_for_two_blocks() {
     let mut harness = ComponentHarness::default();
@@ -1037,6 +1535,31 @@ fn persist_blocks_deploys_and_deploy_metadata_across_instantiations() {
     );
 }
 
+#[test]
+fn should_retain_blocks_after_shutdown() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = random_block_at_height(&mut harness.rng, 42);
+    put_block(&mut harness, &mut storage, block.clone());
+
+    let effects =
+        <Storage as Component<UnitTestEvent>>::on_shutdown(&mut storage, harness.effect_builder);
+    assert!(effects.is_empty());
+    drop(storage);
+
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture(&harness);
+
+    let actual_block = get_block(&mut harness, &mut storage, *block.hash())
+        .expect("missing block we stored before shutting down");
+    assert_eq!(actual_block, *block);
+}
+
 #[test]
 fn should_hard_reset() {
     let blocks_count = 8_usize;
@@ -1152,3 +1675,424 @@ fn should_hard_reset() {
     // Test with a hard reset to era 0, deleting all blocks and associated data.
     check(0);
 }
+
+#[test]
+fn should_prune_block_bodies_but_retain_headers() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let blocks: Vec<_> = (0..6)
+        .map(|height| random_block_at_height(&mut harness.rng, height))
+        .collect();
+    for block in &blocks {
+        put_block(&mut harness, &mut storage, block.clone());
+    }
+
+    let pruned = harness.send_request(&mut storage, |responder| {
+        StorageRequest::PruneBlocksBelow {
+            height: 4,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    assert_eq!(pruned, 4);
+
+    for (height, block) in blocks.iter().enumerate() {
+        let height = height as u64;
+        let should_have_body = height >= 4;
+        assert_eq!(
+            get_block(&mut harness, &mut storage, *block.hash()).is_some(),
+            should_have_body
+        );
+        assert_eq!(
+            get_block_at_height(&mut harness, &mut storage, height).is_some(),
+            should_have_body
+        );
+
+        // Headers remain queryable, both by height and by hash.
+        assert_eq!(
+            get_block_header_at_height(&mut harness, &mut storage, height).as_ref(),
+            Some(block.header())
+        );
+        let block_hash = *block.hash();
+        let header = harness.send_request(&mut storage, move |responder| {
+            StorageRequest::GetBlockHeader {
+                block_hash,
+                responder,
+            }
+            .into()
+        });
+        assert_eq!(header.as_ref(), Some(block.header()));
+    }
+
+    // Pruning again is a no-op.
+    assert_eq!(storage.prune_blocks_below(4).unwrap(), 0);
+
+    // The pruned storage can be reopened, and still holds the headers.
+    drop(storage);
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture(&harness);
+    for (height, block) in blocks.iter().enumerate() {
+        let height = height as u64;
+        assert_eq!(
+            get_block_header_at_height(&mut harness, &mut storage, height).as_ref(),
+            Some(block.header())
+        );
+        assert_eq!(
+            get_block_at_height(&mut harness, &mut storage, height).is_some(),
+            height >= 4
+        );
+    }
+}
+
+#[test]
+fn should_prune_block_bodies_automatically_when_configured() {
+    let mut harness = ComponentHarness::default();
+    let cfg = Config {
+        retained_block_bodies: Some(2),
+        ..new_config(&harness)
+    };
+    let mut storage = Storage::new(
+        &WithDir::new(harness.tmp.path(), cfg),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        false,
+        &Registry::new(),
+    )
+    .expect("could not create storage component fixture");
+
+    let blocks: Vec<_> = (0..6)
+        .map(|height| random_block_at_height(&mut harness.rng, height))
+        .collect();
+    for (height, block) in blocks.iter().enumerate() {
+        put_block(&mut harness, &mut storage, block.clone());
+
+        // Only the bodies of the newly stored block and the one before it are retained.
+        for (other_height, other_block) in blocks.iter().enumerate().take(height + 1) {
+            assert_eq!(
+                get_block(&mut harness, &mut storage, *other_block.hash()).is_some(),
+                other_height + 2 > height
+            );
+            assert_eq!(
+                get_block_header_at_height(&mut harness, &mut storage, other_height as u64)
+                    .as_ref(),
+                Some(other_block.header())
+            );
+        }
+    }
+}
+
+/// Returns the number of compressed and uncompressed block body records held by `storage`.
+fn count_block_body_formats(storage: &Storage) -> (usize, usize) {
+    let txn = storage.env.begin_ro_txn().unwrap();
+    let mut cursor = txn.open_ro_cursor(storage.block_body_db).unwrap();
+    let (compressed, uncompressed): (Vec<_>, Vec<_>) = cursor
+        .iter()
+        .map(|(_, raw_val)| compression::is_compressed(raw_val))
+        .partition(|is_compressed| *is_compressed);
+    (compressed.len(), uncompressed.len())
+}
+
+#[test]
+fn should_read_compressed_and_uncompressed_block_bodies() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let uncompressed_blocks: Vec<_> = (0..3)
+        .map(|height| random_block_at_height(&mut harness.rng, height))
+        .collect();
+    for block in &uncompressed_blocks {
+        put_block(&mut harness, &mut storage, block.clone());
+    }
+
+    storage.compress_block_bodies = true;
+    let compressed_blocks: Vec<_> = (3..5)
+        .map(|height| random_block_at_height(&mut harness.rng, height))
+        .collect();
+    for block in &compressed_blocks {
+        put_block(&mut harness, &mut storage, block.clone());
+    }
+    assert_eq!(count_block_body_formats(&storage), (2, 3));
+
+    let all_blocks: Vec<_> = uncompressed_blocks
+        .iter()
+        .chain(compressed_blocks.iter())
+        .collect();
+    for block in &all_blocks {
+        assert_eq!(
+            get_block(&mut harness, &mut storage, *block.hash()).as_ref(),
+            Some(&***block)
+        );
+    }
+
+    // Recompressing only rewrites the uncompressed records.
+    assert_eq!(storage.recompress_all().unwrap(), 3);
+    assert_eq!(count_block_body_formats(&storage), (5, 0));
+    assert_eq!(storage.recompress_all().unwrap(), 0);
+
+    // Switching compression off again decompresses everything.
+    storage.compress_block_bodies = false;
+    assert_eq!(storage.recompress_all().unwrap(), 5);
+    assert_eq!(count_block_body_formats(&storage), (0, 5));
+
+    for block in &all_blocks {
+        assert_eq!(
+            get_block(&mut harness, &mut storage, *block.hash()).as_ref(),
+            Some(&***block)
+        );
+    }
+}
+
+#[test]
+fn should_reopen_storage_with_mixed_block_body_formats() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let blocks: Vec<_> = (0..4)
+        .map(|height| random_block_at_height(&mut harness.rng, height))
+        .collect();
+    for (index, block) in blocks.iter().enumerate() {
+        storage.compress_block_bodies = index % 2 == 0;
+        put_block(&mut harness, &mut storage, block.clone());
+    }
+    assert_eq!(count_block_body_formats(&storage), (2, 2));
+
+    // Reopen with integrity checks enabled, which requires decoding every stored body.
+    drop(storage);
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = Storage::new(
+        &WithDir::new(harness.tmp.path(), new_config(&harness)),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        true,
+        &Registry::new(),
+    )
+    .expect("could not reopen storage");
+
+    for (height, block) in blocks.iter().enumerate() {
+        assert_eq!(
+            get_block_at_height(&mut harness, &mut storage, height as u64).as_ref(),
+            Some(&**block)
+        );
+    }
+}
+
+#[test]
+fn lmdb_and_in_memory_backends_should_have_parity() {
+    let mut harness = ComponentHarness::default();
+    let mut lmdb_storage = storage_fixture(&harness);
+    let mut in_memory_storage = in_memory_storage_fixture(&harness);
+
+    let blocks: Vec<_> = (0..3)
+        .map(|height| random_block_at_height(&mut harness.rng, height))
+        .collect();
+    let deploys: Vec<_> = (0..3)
+        .map(|_| Box::new(Deploy::random(&mut harness.rng)))
+        .collect();
+    let unknown_block_hash = BlockHash::random(&mut harness.rng);
+    let unknown_deploy_hash = DeployHash::random(&mut harness.rng);
+
+    for storage in [&mut lmdb_storage, &mut in_memory_storage].iter_mut() {
+        for block in &blocks {
+            assert!(put_block(&mut harness, storage, block.clone()));
+        }
+        for deploy in &deploys {
+            assert!(put_deploy(&mut harness, storage, deploy.clone()));
+            // Storing a deploy a second time is reported as a no-op.
+            assert!(!put_deploy(&mut harness, storage, deploy.clone()));
+        }
+
+        for (height, block) in blocks.iter().enumerate() {
+            assert_eq!(
+                get_block(&mut harness, storage, *block.hash()).as_ref(),
+                Some(&**block)
+            );
+            assert_eq!(
+                get_block_at_height(&mut harness, storage, height as u64).as_ref(),
+                Some(&**block)
+            );
+            assert_eq!(
+                get_block_header_at_height(&mut harness, storage, height as u64).as_ref(),
+                Some(block.header())
+            );
+        }
+        assert_eq!(
+            get_highest_block(&mut harness, storage).as_ref(),
+            blocks.last().map(|block| &**block)
+        );
+        assert!(get_block(&mut harness, storage, unknown_block_hash).is_none());
+
+        let deploy_hashes = deploys
+            .iter()
+            .map(|deploy| *deploy.id())
+            .chain(std::iter::once(unknown_deploy_hash))
+            .collect();
+        let expected_deploys: Vec<_> = deploys
+            .iter()
+            .map(|deploy| Some((**deploy).clone()))
+            .chain(std::iter::once(None))
+            .collect();
+        assert_eq!(
+            get_deploys(&mut harness, storage, deploy_hashes),
+            expected_deploys
+        );
+        assert_eq!(
+            get_deploy_and_metadata(&mut harness, storage, *deploys[0].id())
+                .map(|(deploy, _metadata)| deploy)
+                .as_ref(),
+            Some(&*deploys[0])
+        );
+    }
+}
+
+#[test]
+fn in_memory_backend_should_round_trip_blocks_and_deploys() {
+    let mut rng = TestRng::new();
+    let mut backend = InMemoryStorage::default();
+
+    let block = Block::random(&mut rng);
+    let deploy = Deploy::random(&mut rng);
+    assert!(backend.get_block(block.hash()).unwrap().is_none());
+    assert!(backend.get_deploy(deploy.id()).unwrap().is_none());
+
+    assert!(backend.put_block(&block).unwrap());
+    assert!(backend.put_deploy(&deploy).unwrap());
+    assert!(!backend.put_deploy(&deploy).unwrap());
+
+    assert_eq!(
+        backend.get_block(block.hash()).unwrap(),
+        Some(block.clone())
+    );
+    assert_eq!(
+        backend.get_block_header(block.hash()).unwrap().as_ref(),
+        Some(block.header())
+    );
+    assert_eq!(backend.get_deploy(deploy.id()).unwrap(), Some(deploy));
+}
+
+#[test]
+fn in_memory_backend_should_not_persist_blocks() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = in_memory_storage_fixture(&harness);
+
+    let block = random_block_at_height(&mut harness.rng, 0);
+    put_block(&mut harness, &mut storage, block.clone());
+    assert!(get_block(&mut harness, &mut storage, *block.hash()).is_some());
+
+    drop(storage);
+    let mut storage = in_memory_storage_fixture(&harness);
+    assert!(get_block(&mut harness, &mut storage, *block.hash()).is_none());
+    assert!(get_highest_block(&mut harness, &mut storage).is_none());
+}
+
+#[test]
+fn should_record_storage_metrics() {
+    let mut harness = ComponentHarness::default();
+    let registry = Registry::new();
+    let mut storage = Storage::new(
+        &WithDir::new(harness.tmp.path(), new_config(&harness)),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        false,
+        &registry,
+    )
+    .expect("could not create storage component fixture");
+
+    let block = random_block_at_height(&mut harness.rng, 0);
+    let deploy = Box::new(Deploy::random(&mut harness.rng));
+    put_block(&mut harness, &mut storage, block.clone());
+    put_deploy(&mut harness, &mut storage, deploy.clone());
+
+    assert!(get_block(&mut harness, &mut storage, *block.hash()).is_some());
+    let unknown_block_hash = BlockHash::random(&mut harness.rng);
+    assert!(get_block(&mut harness, &mut storage, unknown_block_hash).is_none());
+    // Both deploys are read in a single transaction.
+    let unknown_deploy_hash = DeployHash::random(&mut harness.rng);
+    get_deploys(
+        &mut harness,
+        &mut storage,
+        smallvec![*deploy.id(), unknown_deploy_hash],
+    );
+
+    let metric_families: HashMap<_, _> = registry
+        .gather()
+        .into_iter()
+        .map(|family| (family.get_name().to_string(), family))
+        .collect();
+    let counter = |name: &str| {
+        metric_families[name].get_metric()[0]
+            .get_counter()
+            .get_value()
+    };
+    let sample_count = |name: &str| {
+        metric_families[name].get_metric()[0]
+            .get_histogram()
+            .get_sample_count()
+    };
+
+    assert_eq!(counter("storage_block_hits"), 1.0);
+    assert_eq!(counter("storage_block_misses"), 1.0);
+    assert_eq!(counter("storage_deploy_hits"), 1.0);
+    assert_eq!(counter("storage_deploy_misses"), 1.0);
+    assert_eq!(sample_count("storage_block_get_seconds"), 2);
+    assert_eq!(sample_count("storage_block_put_seconds"), 1);
+    assert_eq!(sample_count("storage_deploy_get_seconds"), 1);
+    assert_eq!(sample_count("storage_deploy_put_seconds"), 1);
+
+    // Dropping the component unregisters its metrics.
+    drop(storage);
+    assert!(registry
+        .gather()
+        .iter()
+        .all(|family| !family.get_name().starts_with("storage_")));
+}
+
+/// Number of blocks read per iteration by the block retrieval benchmarks.
+const BENCH_BLOCK_COUNT: u64 = 100;
+
+/// Creates a storage fixture holding `BENCH_BLOCK_COUNT` blocks, returning their hashes.
+fn bench_storage_fixture(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+) -> (Storage, Vec<BlockHash>) {
+    let mut storage = storage_fixture(harness);
+    let block_hashes = (0..BENCH_BLOCK_COUNT)
+        .map(|height| {
+            let block = random_block_at_height(&mut harness.rng, height);
+            let block_hash = *block.hash();
+            put_block(harness, &mut storage, block);
+            block_hash
+        })
+        .collect();
+    (storage, block_hashes)
+}
+
+#[bench]
+fn bench_get_blocks_individually(bencher: &mut Bencher) {
+    let mut harness = ComponentHarness::default();
+    let (storage, block_hashes) = bench_storage_fixture(&mut harness);
+
+    // One read transaction per block, as for a sequence of `GetBlock` requests.
+    bencher.iter(|| {
+        block_hashes
+            .iter()
+            .map(|block_hash| storage.backend.get_block(block_hash).unwrap())
+            .collect::<Vec<_>>()
+    });
+}
+
+#[bench]
+fn bench_get_blocks_batched(bencher: &mut Bencher) {
+    let mut harness = ComponentHarness::default();
+    let (storage, block_hashes) = bench_storage_fixture(&mut harness);
+
+    bencher.iter(|| storage.get_blocks_batch(&block_hashes).unwrap());
+}
@@ -5,6 +5,8 @@ mod asymmetric_key_ext;
 mod error;
 pub mod hash;
 
-pub use asymmetric_key::{generate_ed25519_keypair, sign, verify};
-pub use asymmetric_key_ext::AsymmetricKeyExt;
+pub use asymmetric_key::{
+    generate_ed25519_keypair, sign, sign_recoverable, verify, RecoverableSignature,
+};
+pub use asymmetric_key_ext::{AsymmetricKeyExt, PublicKeyExt, SecretKeyExt};
 pub use error::{Error, Result};
@@ -4,16 +4,23 @@ use std::path::Path;
 
 use derp::{Der, Tag};
 use once_cell::sync::Lazy;
+use openssl::{pkey::PKey, symm::Cipher};
 use pem::Pem;
 #[cfg(test)]
 use rand::{Rng, RngCore};
 use untrusted::Input;
 
-use casper_types::{AsymmetricType, PublicKey, SecretKey, ED25519_TAG, SECP256K1_TAG, SYSTEM_TAG};
+use casper_types::{
+    AsymmetricType, PublicKey, SecretKey, Signature, ED25519_TAG, SECP256K1_TAG, SECP256R1_TAG,
+    SYSTEM_TAG,
+};
 
 #[cfg(test)]
 use crate::testing::TestRng;
-use crate::{crypto::Error, utils};
+use crate::{
+    crypto::{self, Error, RecoverableSignature},
+    utils,
+};
 
 // See https://tools.ietf.org/html/rfc8410#section-10.3
 const ED25519_OBJECT_IDENTIFIER: [u8; 3] = [43, 101, 112];
@@ -25,6 +32,11 @@ const SECP256K1_OBJECT_IDENTIFIER: [u8; 5] = [43, 129, 4, 0, 10];
 const SECP256K1_PEM_SECRET_KEY_TAG: &str = "EC PRIVATE KEY";
 const SECP256K1_PEM_PUBLIC_KEY_TAG: &str = "PUBLIC KEY";
 
+// See https://tools.ietf.org/html/rfc5480#section-2.1.1.1
+const SECP256R1_OBJECT_IDENTIFIER: [u8; 8] = [42, 134, 72, 206, 61, 3, 1, 7];
+const SECP256R1_PEM_SECRET_KEY_TAG: &str = "EC PRIVATE KEY";
+const SECP256R1_PEM_PUBLIC_KEY_TAG: &str = "PUBLIC KEY";
+
 // See https://www.secg.org/sec1-v2.pdf#subsection.C.4
 const EC_PUBLIC_KEY_OBJECT_IDENTIFIER: [u8; 7] = [42, 134, 72, 206, 61, 2, 1];
 
@@ -49,6 +61,10 @@ pub trait AsymmetricKeyExt: Sized {
     /// random number generator.
     fn generate_secp256k1() -> Result<Self, Error>;
 
+    /// Constructs a new secp256r1 (NIST P-256) variant using the operating system's
+    /// cryptographically secure random number generator.
+    fn generate_p256() -> Result<Self, Error>;
+
     /// Attempts to write the key bytes to the configured file path.
     fn to_file<P: AsRef<Path>>(&self, file: P) -> Result<(), Error>;
 
@@ -79,10 +95,91 @@ pub trait AsymmetricKeyExt: Sized {
     #[cfg(test)]
     fn random_secp256k1(rng: &mut TestRng) -> Self;
 
+    /// Generates a random secp256r1 instance using a `TestRng`.
+    #[cfg(test)]
+    fn random_secp256r1(rng: &mut TestRng) -> Self;
+
     /// Returns an example value for documentation purposes.
     fn doc_example() -> &'static Self;
 }
 
+/// Additional operations on a secret key.
+pub trait SecretKeyExt: Sized {
+    /// Signs the given message using this key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is the system secret key, as it cannot be used to create signatures.
+    fn sign<T: AsRef<[u8]>>(&self, message: T) -> Signature;
+
+    /// Signs the given message using this key, producing a signature from which the public key
+    /// can be recovered.
+    ///
+    /// Returns an `Unsupported` error unless this is a secp256k1 key.
+    fn sign_recoverable<T: AsRef<[u8]>>(&self, message: T) -> Result<RecoverableSignature, Error>;
+
+    /// PEM encodes the key as a PKCS#8 container encrypted with AES-256 using the given passphrase.
+    fn to_encrypted_pem(&self, passphrase: &[u8]) -> Result<String, Error>;
+
+    /// Decodes a key from a PEM-encoded, passphrase-protected PKCS#8 container.
+    fn from_encrypted_pem<T: AsRef<[u8]>>(input: T, passphrase: &[u8]) -> Result<Self, Error>;
+
+    /// Attempts to write the key, encrypted using the given passphrase, to the configured file
+    /// path.
+    fn to_encrypted_file<P: AsRef<Path>>(&self, file: P, passphrase: &[u8]) -> Result<(), Error>;
+
+    /// Attempts to read and decrypt the key using the given passphrase from the configured file
+    /// path.
+    fn from_encrypted_file<P: AsRef<Path>>(file: P, passphrase: &[u8]) -> Result<Self, Error>;
+}
+
+impl SecretKeyExt for SecretKey {
+    fn sign<T: AsRef<[u8]>>(&self, message: T) -> Signature {
+        crypto::sign(message, self, &PublicKey::from(self))
+    }
+
+    fn sign_recoverable<T: AsRef<[u8]>>(&self, message: T) -> Result<RecoverableSignature, Error> {
+        crypto::sign_recoverable(message, self)
+    }
+
+    fn to_encrypted_pem(&self, passphrase: &[u8]) -> Result<String, Error> {
+        let private_key = PKey::private_key_from_der(&self.to_der()?)?;
+        let pem =
+            private_key.private_key_to_pem_pkcs8_passphrase(Cipher::aes_256_cbc(), passphrase)?;
+        String::from_utf8(pem).map_err(|error| Error::FromPem(error.to_string()))
+    }
+
+    fn from_encrypted_pem<T: AsRef<[u8]>>(input: T, passphrase: &[u8]) -> Result<Self, Error> {
+        let private_key = PKey::private_key_from_pem_passphrase(input.as_ref(), passphrase)?;
+        Self::from_der(private_key.private_key_to_der()?)
+    }
+
+    fn to_encrypted_file<P: AsRef<Path>>(&self, file: P, passphrase: &[u8]) -> Result<(), Error> {
+        utils::write_private_file(file, self.to_encrypted_pem(passphrase)?)
+            .map_err(Error::SecretKeySave)
+    }
+
+    fn from_encrypted_file<P: AsRef<Path>>(file: P, passphrase: &[u8]) -> Result<Self, Error> {
+        let data = utils::read_file(file).map_err(Error::SecretKeyLoad)?;
+        Self::from_encrypted_pem(data, passphrase)
+    }
+}
+
+/// Additional operations on a public key.
+pub trait PublicKeyExt {
+    /// Verifies the signature of the given message against this key.
+    ///
+    /// Returns an error if the signature is invalid, or if its algorithm doesn't match that of
+    /// this key.
+    fn verify<T: AsRef<[u8]>>(&self, message: T, signature: &Signature) -> Result<(), Error>;
+}
+
+impl PublicKeyExt for PublicKey {
+    fn verify<T: AsRef<[u8]>>(&self, message: T, signature: &Signature) -> Result<(), Error> {
+        crypto::verify(message, signature, self)
+    }
+}
+
 impl AsymmetricKeyExt for SecretKey {
     fn generate_ed25519() -> Result<Self, Error> {
         let mut bytes = [0u8; Self::ED25519_LENGTH];
@@ -96,6 +193,12 @@ impl AsymmetricKeyExt for SecretKey {
         Ok(SecretKey::secp256k1_from_bytes(bytes)?)
     }
 
+    fn generate_p256() -> Result<Self, Error> {
+        let mut bytes = [0u8; Self::SECP256R1_LENGTH];
+        getrandom::getrandom(&mut bytes[..])?;
+        Ok(SecretKey::secp256r1_from_bytes(bytes)?)
+    }
+
     fn to_file<P: AsRef<Path>>(&self, file: P) -> Result<(), Error> {
         utils::write_private_file(file, self.to_pem()?).map_err(Error::SecretKeySave)
     }
@@ -129,6 +232,21 @@ impl AsymmetricKeyExt for SecretKey {
                 let mut der = Der::new(&mut oid_bytes);
                 der.oid(&SECP256K1_OBJECT_IDENTIFIER)?;
 
+                let mut encoded = vec![];
+                der = Der::new(&mut encoded);
+                der.sequence(|der| {
+                    der.integer(&[1])?;
+                    der.octet_string(secret_key.to_bytes().as_slice())?;
+                    der.element(Tag::ContextSpecificConstructed0, &oid_bytes)
+                })?;
+                Ok(encoded)
+            }
+            SecretKey::Secp256r1(secret_key) => {
+                // See https://www.secg.org/sec1-v2.pdf#subsection.C.4
+                let mut oid_bytes = vec![];
+                let mut der = Der::new(&mut oid_bytes);
+                der.oid(&SECP256R1_OBJECT_IDENTIFIER)?;
+
                 let mut encoded = vec![];
                 der = Der::new(&mut encoded);
                 der.sequence(|der| {
@@ -179,7 +297,7 @@ impl AsymmetricKeyExt for SecretKey {
 
                     return Ok((ED25519_TAG, raw_bytes));
                 } else if tag == Tag::OctetString as u8 {
-                    // Expecting a secp256k1 key.
+                    // Expecting a secp256k1 or secp256r1 key.
                     if version != 1 {
                         return Err(derp::Error::WrongValue);
                     }
@@ -193,16 +311,18 @@ impl AsymmetricKeyExt for SecretKey {
                     let object_identifier = parameter0.read_all(derp::Error::Read, |input| {
                         derp::expect_tag_and_get_value(input, Tag::Oid)
                     })?;
-                    if object_identifier.as_slice_less_safe() != SECP256K1_OBJECT_IDENTIFIER {
-                        return Err(derp::Error::WrongValue);
-                    }
+                    let key_type_tag = match object_identifier.as_slice_less_safe() {
+                        oid if oid == SECP256K1_OBJECT_IDENTIFIER => SECP256K1_TAG,
+                        oid if oid == SECP256R1_OBJECT_IDENTIFIER => SECP256R1_TAG,
+                        _ => return Err(derp::Error::WrongValue),
+                    };
 
                     // There might be an optional public key as the final value, but we're not
                     // interested in parsing that.  Read it to ensure `input.read_all` doesn't fail
                     // with unused bytes error.
                     let _ = derp::read_tag_and_get_value(input);
 
-                    return Ok((SECP256K1_TAG, raw_bytes));
+                    return Ok((key_type_tag, raw_bytes));
                 }
 
                 Err(derp::Error::WrongValue)
@@ -213,6 +333,7 @@ impl AsymmetricKeyExt for SecretKey {
             SYSTEM_TAG => Err(Error::AsymmetricKey("cannot construct variant".to_string())),
             ED25519_TAG => SecretKey::ed25519_from_bytes(raw_bytes).map_err(Into::into),
             SECP256K1_TAG => SecretKey::secp256k1_from_bytes(raw_bytes).map_err(Into::into),
+            SECP256R1_TAG => SecretKey::secp256r1_from_bytes(raw_bytes).map_err(Into::into),
             _ => Err(Error::AsymmetricKey("unknown type tag".to_string())),
         }
     }
@@ -222,6 +343,7 @@ impl AsymmetricKeyExt for SecretKey {
             SecretKey::System => return Err(Error::System(String::from("to_pem"))),
             SecretKey::Ed25519(_) => ED25519_PEM_SECRET_KEY_TAG.to_string(),
             SecretKey::Secp256k1(_) => SECP256K1_PEM_SECRET_KEY_TAG.to_string(),
+            SecretKey::Secp256r1(_) => SECP256R1_PEM_SECRET_KEY_TAG.to_string(),
         };
         let contents = self.to_der()?;
         let pem = Pem { tag, contents };
@@ -252,6 +374,11 @@ impl AsymmetricKeyExt for SecretKey {
                     return Err(bad_tag(SECP256K1_PEM_SECRET_KEY_TAG));
                 }
             }
+            SecretKey::Secp256r1(_) => {
+                if pem.tag != SECP256R1_PEM_SECRET_KEY_TAG {
+                    return Err(bad_tag(SECP256R1_PEM_SECRET_KEY_TAG));
+                }
+            }
         }
 
         Ok(secret_key)
@@ -280,6 +407,13 @@ impl AsymmetricKeyExt for SecretKey {
         SecretKey::secp256k1_from_bytes(bytes).unwrap()
     }
 
+    #[cfg(test)]
+    fn random_secp256r1(rng: &mut TestRng) -> Self {
+        let mut bytes = [0u8; Self::SECP256R1_LENGTH];
+        rng.fill_bytes(&mut bytes[..]);
+        SecretKey::secp256r1_from_bytes(bytes).unwrap()
+    }
+
     fn doc_example() -> &'static Self {
         &*ED25519_SECRET_KEY
     }
@@ -298,6 +432,11 @@ impl AsymmetricKeyExt for PublicKey {
         PublicKey::secp256k1_from_bytes(bytes).map_err(Into::into)
     }
 
+    fn generate_p256() -> Result<Self, Error> {
+        let secret_key = SecretKey::generate_p256()?;
+        Ok(PublicKey::from(&secret_key))
+    }
+
     fn to_file<P: AsRef<Path>>(&self, file: P) -> Result<(), Error> {
         utils::write_file(file, self.to_pem()?).map_err(Error::PublicKeySave)
     }
@@ -333,6 +472,19 @@ impl AsymmetricKeyExt for PublicKey {
                 })?;
                 Ok(encoded)
             }
+            PublicKey::Secp256r1(public_key) => {
+                // See https://www.secg.org/sec1-v2.pdf#subsection.C.3
+                let mut encoded = vec![];
+                let mut der = Der::new(&mut encoded);
+                der.sequence(|der| {
+                    der.sequence(|der| {
+                        der.oid(&EC_PUBLIC_KEY_OBJECT_IDENTIFIER)?;
+                        der.oid(&SECP256R1_OBJECT_IDENTIFIER)
+                    })?;
+                    der.bit_string(0, public_key.to_encoded_point(true).as_bytes())
+                })?;
+                Ok(encoded)
+            }
         }
     }
 
@@ -350,14 +502,16 @@ impl AsymmetricKeyExt for PublicKey {
                         key_type_tag = ED25519_TAG;
                         Ok(())
                     } else if object_identifier == EC_PUBLIC_KEY_OBJECT_IDENTIFIER {
-                        // Assert the next object identifier is the secp256k1 ID.
+                        // Assert the next object identifier is the secp256k1 or secp256r1 ID.
                         let next_object_identifier =
                             derp::expect_tag_and_get_value(input, Tag::Oid)?.as_slice_less_safe();
-                        if next_object_identifier != SECP256K1_OBJECT_IDENTIFIER {
+                        if next_object_identifier == SECP256K1_OBJECT_IDENTIFIER {
+                            key_type_tag = SECP256K1_TAG;
+                        } else if next_object_identifier == SECP256R1_OBJECT_IDENTIFIER {
+                            key_type_tag = SECP256R1_TAG;
+                        } else {
                             return Err(derp::Error::WrongValue);
                         }
-
-                        key_type_tag = SECP256K1_TAG;
                         Ok(())
                     } else {
                         Err(derp::Error::WrongValue)
@@ -370,6 +524,7 @@ impl AsymmetricKeyExt for PublicKey {
         match key_type_tag {
             ED25519_TAG => PublicKey::ed25519_from_bytes(raw_bytes).map_err(Into::into),
             SECP256K1_TAG => PublicKey::secp256k1_from_bytes(raw_bytes).map_err(Into::into),
+            SECP256R1_TAG => PublicKey::secp256r1_from_bytes(raw_bytes).map_err(Into::into),
             _ => unreachable!(),
         }
     }
@@ -379,6 +534,7 @@ impl AsymmetricKeyExt for PublicKey {
             PublicKey::System => return Err(Error::System(String::from("to_pem"))),
             PublicKey::Ed25519(_) => ED25519_PEM_PUBLIC_KEY_TAG.to_string(),
             PublicKey::Secp256k1(_) => SECP256K1_PEM_PUBLIC_KEY_TAG.to_string(),
+            PublicKey::Secp256r1(_) => SECP256R1_PEM_PUBLIC_KEY_TAG.to_string(),
         };
         let contents = self.to_der()?;
         let pem = Pem { tag, contents };
@@ -406,6 +562,11 @@ impl AsymmetricKeyExt for PublicKey {
                     return Err(bad_tag(SECP256K1_PEM_PUBLIC_KEY_TAG));
                 }
             }
+            PublicKey::Secp256r1(_) => {
+                if pem.tag != SECP256R1_PEM_PUBLIC_KEY_TAG {
+                    return Err(bad_tag(SECP256R1_PEM_PUBLIC_KEY_TAG));
+                }
+            }
         }
         Ok(public_key)
     }
@@ -428,6 +589,12 @@ impl AsymmetricKeyExt for PublicKey {
         PublicKey::from(&secret_key)
     }
 
+    #[cfg(test)]
+    fn random_secp256r1(rng: &mut TestRng) -> Self {
+        let secret_key = SecretKey::random_secp256r1(rng);
+        PublicKey::from(&secret_key)
+    }
+
     fn doc_example() -> &'static Self {
         &*ED25519_PUBLIC_KEY
     }
@@ -2,6 +2,7 @@ use std::result;
 
 use base64::DecodeError;
 use hex::FromHexError;
+use openssl::error::ErrorStack;
 use pem::PemError;
 use signature::Error as SignatureError;
 use thiserror::Error;
@@ -55,6 +56,10 @@ pub enum Error {
     #[error("invalid operation on system key: {0}")]
     System(String),
 
+    /// Error resulting from an operation which is not supported by the given key type.
+    #[error("unsupported operation: {0}")]
+    Unsupported(String),
+
     /// Error related to the underlying signature crate.
     #[error("error in signature")]
     Signature(SignatureError),
@@ -62,6 +67,10 @@ pub enum Error {
     /// Error in getting random bytes from the system's preferred random number source.
     #[error("failed to get random bytes: {0}")]
     GetRandomBytes(#[from] getrandom::Error),
+
+    /// Error encrypting or decrypting a key, e.g. due to an incorrect passphrase.
+    #[error("openssl error: {0}")]
+    OpenSsl(#[from] ErrorStack),
 }
 
 impl From<PemError> for Error {
@@ -1,5 +1,6 @@
 use std::{env, fs, io, path::PathBuf};
 
+use prometheus::Registry;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use toml::de::Error as TomlDecodeError;
@@ -9,6 +10,7 @@ use casper_execution_engine::shared::newtypes::Blake2bHash;
 use casper_types::{ProtocolVersion, PublicKey, SecretKey, Signature};
 
 use crate::{
+    components::storage::{Error as StorageError, Storage},
     crypto,
     reactor::participating::Config,
     types::{chainspec, Chainspec},
@@ -83,6 +85,10 @@ pub enum Error {
     /// Error loading the chainspec.
     #[error("error loading chainspec: {0}")]
     LoadChainspec(chainspec::Error),
+
+    /// Error opening or rewriting the storage.
+    #[error("error migrating storage: {0}")]
+    Storage(StorageError),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -211,6 +217,18 @@ pub fn migrate_data(
         .load(&new_root)
         .map_err(Error::LoadSecretKey)?;
 
+    // Rewrite the stored block bodies to match the configured compression setting.
+    let storage_config = WithDir::new(&new_root, new_config.storage.clone());
+    let mut storage = Storage::new(
+        &storage_config,
+        None,
+        new_protocol_version,
+        false,
+        &Registry::new(),
+    )
+    .map_err(Error::Storage)?;
+    storage.recompress_all().map_err(Error::Storage)?;
+
     // Get this by actually migrating the global state data.
     let state_hash = Blake2bHash::default();
 
@@ -30,6 +30,13 @@
 //! `Event::ThreeSecondsElapsed`. Note that effects do nothing on their own, they need to be passed
 //! to a [`reactor`](../reactor/index.html) to be executed.
 //!
+//! ## CPU-bound work
+//!
+//! Effects are run on the reactor's async runtime, so they must not block either. Work that takes
+//! a significant amount of CPU time, such as executing deploys or verifying signatures, should be
+//! moved to a blocking thread using `EffectBuilder::run_blocking` and turned into an event once
+//! done.
+//!
 //! ## Arbitrary effects
 //!
 //! While it is technically possible to turn any future into an effect, it is advisable to only use
@@ -67,6 +74,7 @@ use std::{
     collections::{BTreeMap, HashMap, HashSet},
     fmt::{self, Debug, Display, Formatter},
     future::Future,
+    panic,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -76,10 +84,10 @@ use futures::{channel::oneshot, future::BoxFuture, FutureExt};
 use once_cell::sync::Lazy;
 use serde::{de::DeserializeOwned, Serialize};
 use smallvec::{smallvec, SmallVec};
-use tokio::{sync::Semaphore, time};
-use tracing::error;
+use tokio::{sync::Semaphore, task, time};
 #[cfg(not(feature = "fast-sync"))]
 use tracing::warn;
+use tracing::{debug, error};
 
 use casper_execution_engine::{
     core::engine_state::{
@@ -89,8 +97,8 @@ use casper_execution_engine::{
         genesis::GenesisResult,
         step::{StepRequest, StepResult},
         upgrade::{UpgradeConfig, UpgradeResult},
-        BalanceRequest, BalanceResult, GetBidsRequest, GetBidsResult, QueryRequest, QueryResult,
-        MAX_PAYMENT,
+        BalanceRequest, BalanceResult, ExecuteRequest, GetBidsRequest, GetBidsResult, QueryRequest,
+        QueryResult, MAX_PAYMENT,
     },
     shared::{newtypes::Blake2bHash, stored_value::StoredValue},
     storage::{protocol_data::ProtocolData, trie::Trie},
@@ -115,15 +123,15 @@ use crate::{
     reactor::{EventQueueHandle, QueueKind},
     types::{
         Block, BlockByHeight, BlockHash, BlockHeader, BlockPayload, BlockSignatures, Chainspec,
-        ChainspecInfo, Deploy, DeployHash, DeployHeader, DeployMetadata, FinalitySignature,
-        FinalizedBlock, Item, TimeDiff, Timestamp,
+        ChainspecInfo, Deploy, DeployHash, DeployHeader, DeployMetadata, EraReport,
+        FinalitySignature, FinalizedBlock, Item, NodeId, PeerConnection, TimeDiff, Timestamp,
     },
     utils::Source,
 };
 use announcements::{
     ChainspecLoaderAnnouncement, ConsensusAnnouncement, ContractRuntimeAnnouncement,
     ControlAnnouncement, DeployAcceptorAnnouncement, GossiperAnnouncement, LinearChainAnnouncement,
-    NetworkAnnouncement, RpcServerAnnouncement,
+    NetworkAnnouncement, RpcServerAnnouncement, StorageAnnouncement,
 };
 use requests::{
     BlockPayloadRequest, BlockProposerRequest, BlockValidationRequest, ChainspecLoaderRequest,
@@ -220,6 +228,16 @@ impl<T> Serialize for Responder<T> {
     }
 }
 
+/// The error returned by [`EffectBuilder::timeout`] if the wrapped future did not complete in time.
+#[derive(Copy, Clone, DataSize, Debug, Eq, PartialEq, Serialize)]
+pub struct TimedOut(pub Duration);
+
+impl Display for TimedOut {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "timed out after {:?}", self.0)
+    }
+}
+
 /// Effect extension for futures, used to convert futures into actual effects.
 pub trait EffectExt: Future + Send {
     /// Finalizes a future into an effect that returns a single event.
@@ -481,6 +499,48 @@ impl<REv> EffectBuilder<REv> {
         Instant::now() - then
     }
 
+    /// Races `future` against a timer of `duration`.
+    ///
+    /// Returns the output of `future` if it completes first, or `TimedOut` if the timer fires
+    /// before it does, in which case `future` is dropped.
+    pub(crate) async fn timeout<F>(
+        self,
+        duration: Duration,
+        future: F,
+    ) -> Result<F::Output, TimedOut>
+    where
+        F: Future,
+    {
+        time::timeout(duration, future)
+            .await
+            .map_err(|_elapsed| TimedOut(duration))
+    }
+
+    /// Runs the CPU-bound or otherwise blocking closure `f` on a thread pool dedicated to blocking
+    /// work, returning its result.
+    ///
+    /// Use this instead of running such work inline, which would stall the processing of events by
+    /// other components. A panic of `f` is propagated.
+    pub(crate) async fn run_blocking<F, T>(self, f: F) -> T
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        match task::spawn_blocking(f).await {
+            Ok(output) => output,
+            Err(error) => match error.try_into_panic() {
+                Ok(reason) => panic::resume_unwind(reason),
+                Err(error) => {
+                    // The task can only be cancelled if the runtime is shutting down, in which case
+                    // there is no result to return, so we abandon this task.
+                    debug!(%error, "blocking task cancelled");
+                    let _ = UNOBTAINABLE.acquire().await;
+                    panic!("should never obtain unobtainable semaphore");
+                }
+            },
+        }
+    }
+
     /// Retrieve a snapshot of the nodes current metrics formatted as string.
     ///
     /// If an error occurred producing the metrics, `None` is returned.
@@ -585,6 +645,33 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets all connections to network peers, including the ones still being established.
+    pub async fn network_peer_connections<I>(self) -> Vec<PeerConnection<I>>
+    where
+        REv: From<NetworkInfoRequest<I>>,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::GetPeerConnections { responder },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Gets the protocol version a connected peer announced, or `None` if not connected to the peer
+    /// or its protocol version is unknown.
+    pub(crate) async fn get_peer_protocol_version<I>(self, peer: I) -> Option<ProtocolVersion>
+    where
+        REv: From<NetworkInfoRequest<I>>,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::GetPeerProtocolVersion { peer, responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Announces that a network message has been received.
     pub(crate) async fn announce_message_received<I, P>(self, sender: I, payload: P)
     where
@@ -673,6 +760,20 @@ impl<REv> EffectBuilder<REv> {
         )
     }
 
+    /// Announces that an item sent in chunks by the given peer has been reassembled.
+    pub(crate) async fn announce_item_reassembled<T>(self, item: Box<T>, sender: NodeId)
+    where
+        REv: From<GossiperAnnouncement<T>>,
+        T: Item,
+    {
+        self.0
+            .schedule(
+                GossiperAnnouncement::ItemReassembled { item, sender },
+                QueueKind::Regular,
+            )
+            .await;
+    }
+
     /// Announces that we have finished gossiping the indicated item.
     pub(crate) async fn announce_finished_gossiping<T>(self, item_id: T::Id)
     where
@@ -760,6 +861,14 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Announce that storage found a corrupt entry.
+    pub(crate) async fn announce_storage_corruption(self, announcement: StorageAnnouncement)
+    where
+        REv: From<StorageAnnouncement>,
+    {
+        self.0.schedule(announcement, QueueKind::Regular).await
+    }
+
     /// Puts the given block into the linear block store.
     pub(crate) async fn put_block_to_storage(self, block: Box<Block>) -> bool
     where
@@ -772,6 +881,21 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Puts the given block header into the linear block store, without the block's body.
+    pub(crate) async fn put_block_header_to_storage(self, block_header: Box<BlockHeader>) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutBlockHeader {
+                block_header,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Gets the requested block from the linear block store.
     pub(crate) async fn get_block_from_storage(self, block_hash: BlockHash) -> Option<Block>
     where
@@ -973,7 +1097,6 @@ impl<REv> EffectBuilder<REv> {
     }
 
     /// Puts a trie into the trie store and asynchronously returns any missing descendant trie keys.
-    #[allow(unused)]
     pub(crate) async fn put_trie_and_find_missing_descendant_trie_keys(
         self,
         trie: Box<Trie<Key, StoredValue>>,
@@ -1127,6 +1250,28 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the requested deploy using the `DeployFetcher`, asking each of the given peers in turn
+    /// until one provides it.
+    pub(crate) async fn fetch_deploy_from_peers<I>(
+        self,
+        deploy_hash: DeployHash,
+        peers: Vec<I>,
+    ) -> Option<FetchResult<Deploy, I>>
+    where
+        REv: From<FetcherRequest<I, Deploy>>,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| FetcherRequest::FetchFromPeers {
+                id: deploy_hash,
+                peers,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Gets the requested block using the `BlockFetcher`
     pub(crate) async fn fetch_block<I>(
         self,
@@ -1148,6 +1293,61 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the requested global state trie node using the `TrieFetcher`.
+    pub(crate) async fn fetch_trie<I>(
+        self,
+        trie_key: Blake2bHash,
+        peer: I,
+    ) -> Option<FetchResult<Trie<Key, StoredValue>, I>>
+    where
+        REv: From<FetcherRequest<I, Trie<Key, StoredValue>>>,
+        I: Send + 'static,
+    {
+        self.make_request(
+            |responder| FetcherRequest::Fetch {
+                id: trie_key,
+                peer,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Downloads the global state trie under `state_root_hash` from `peer`, storing every trie
+    /// node not yet present in the trie store.
+    ///
+    /// The fetcher only accepts a trie node hashing to the key it was requested by, so a peer
+    /// cannot substitute a different trie. Returns `false` if a trie node could not be fetched or
+    /// stored.
+    pub(crate) async fn sync_trie_store<I>(self, state_root_hash: Blake2bHash, peer: I) -> bool
+    where
+        REv: From<FetcherRequest<I, Trie<Key, StoredValue>>> + From<ContractRuntimeRequest>,
+        I: Clone + Send + 'static,
+    {
+        let mut outstanding_trie_keys = vec![state_root_hash];
+        while let Some(trie_key) = outstanding_trie_keys.pop() {
+            let trie = match self.fetch_trie(trie_key, peer.clone()).await {
+                Some(FetchResult::FromStorage(trie)) | Some(FetchResult::FromPeer(trie, _)) => trie,
+                None => {
+                    warn!(%trie_key, "failed to fetch trie node");
+                    return false;
+                }
+            };
+            match self
+                .put_trie_and_find_missing_descendant_trie_keys(trie)
+                .await
+            {
+                Ok(missing_trie_keys) => outstanding_trie_keys.extend(missing_trie_keys),
+                Err(error) => {
+                    warn!(%trie_key, %error, "failed to store trie node");
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
     /// Requests a linear chain block at `block_height`.
     pub(crate) async fn fetch_block_by_height<I>(
         self,
@@ -1169,6 +1369,18 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Checks whether the block proposer's buffer of pending deploys is full.
+    pub(crate) async fn is_deploy_buffer_full(self) -> bool
+    where
+        REv: From<BlockProposerRequest>,
+    {
+        self.make_request(
+            |responder| BlockProposerRequest::IsBufferFull { responder },
+            QueueKind::Api,
+        )
+        .await
+    }
+
     /// Passes the timestamp of a future block for which deploys are to be proposed.
     pub(crate) async fn request_block_payload(
         self,
@@ -1239,6 +1451,29 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Announces that an era ended with the switch block at the given height.
+    ///
+    /// Must be called after the switch block was announced as finalized.
+    pub(crate) async fn announce_era_ended(
+        self,
+        era_id: EraId,
+        switch_block_height: u64,
+        era_report: EraReport,
+    ) where
+        REv: From<ConsensusAnnouncement>,
+    {
+        self.0
+            .schedule(
+                ConsensusAnnouncement::EraEnded {
+                    era_id,
+                    switch_block_height,
+                    era_report: Box::new(era_report),
+                },
+                QueueKind::Regular,
+            )
+            .await
+    }
+
     /// Announces that a finality signature has been created.
     pub(crate) async fn announce_created_finality_signature(
         self,
@@ -1254,12 +1489,14 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
-    /// An equivocation has been detected.
+    /// An equivocation has been detected, proven by the two conflicting signed messages if the
+    /// validator signed both.
     pub(crate) async fn announce_fault_event(
         self,
         era_id: EraId,
         public_key: PublicKey,
         timestamp: Timestamp,
+        conflicting_messages: Option<(Vec<u8>, Vec<u8>)>,
     ) where
         REv: From<ConsensusAnnouncement>,
     {
@@ -1269,6 +1506,7 @@ impl<REv> EffectBuilder<REv> {
                     era_id,
                     public_key: Box::new(public_key),
                     timestamp,
+                    conflicting_messages: conflicting_messages.map(Box::new),
                 },
                 QueueKind::Regular,
             )
@@ -1288,6 +1526,19 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
+    /// Announce that a specific peer violated the protocol, e.g. by sending an invalid deploy.
+    pub(crate) async fn announce_protocol_violation<I>(self, peer: I)
+    where
+        REv: From<BlocklistAnnouncement<I>>,
+    {
+        self.0
+            .schedule(
+                BlocklistAnnouncement::ProtocolViolation(Box::new(peer)),
+                QueueKind::Regular,
+            )
+            .await
+    }
+
     /// The linear chain has stored a newly-created block.
     pub(crate) async fn announce_block_added(self, block: Box<Block>)
     where
@@ -1567,6 +1818,25 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Executes a request against global state without committing the resulting effects, e.g. to
+    /// estimate the gas cost of a deploy.
+    pub(crate) async fn execute_dry_run(
+        self,
+        execute_request: ExecuteRequest,
+    ) -> Result<ExecutionResult, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::ExecuteDryRun {
+                execute_request: Box::new(execute_request),
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Runs the end of era step using the system smart contract.
     pub(crate) async fn run_step(
         self,
@@ -1759,3 +2029,36 @@ macro_rules! fatal {
         $effect_builder.fatal(file!(), line!(), format_args!($($arg)*).to_string())
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::{reactor::Scheduler, utils};
+
+    #[tokio::test]
+    async fn run_blocking_should_not_stall_event_processing() {
+        let scheduler = utils::leak(Scheduler::<u32>::new(QueueKind::weights()));
+        let effect_builder = EffectBuilder::new(EventQueueHandle::new(scheduler));
+
+        // The closure only returns once the event below has been processed, so running it inline on
+        // the single-threaded test runtime would stall until the timeout.
+        let (started_sender, started_receiver) = oneshot::channel();
+        let (release_sender, release_receiver) = mpsc::channel();
+        let blocking = tokio::spawn(effect_builder.run_blocking(move || {
+            started_sender.send(()).expect("should signal start");
+            release_receiver.recv_timeout(Duration::from_secs(10))
+        }));
+        started_receiver.await.expect("closure should start");
+
+        effect_builder
+            .into_inner()
+            .schedule(42u32, QueueKind::Regular)
+            .await;
+        let ((event, _context), _queue_kind) = scheduler.pop().await;
+        release_sender.send(event).expect("should release closure");
+
+        assert_eq!(blocking.await.expect("should join task"), Ok(42));
+    }
+}
@@ -18,7 +18,8 @@ use crate::{
     },
     effect::Responder,
     types::{
-        Block, Deploy, DeployHash, DeployHeader, FinalitySignature, FinalizedBlock, Item, Timestamp,
+        Block, BlockHash, Deploy, DeployHash, DeployHeader, EraReport, FinalitySignature,
+        FinalizedBlock, Item, NodeId, Timestamp,
     },
     utils::Source,
 };
@@ -160,6 +161,17 @@ impl<I: Display> Display for DeployAcceptorAnnouncement<I> {
 pub enum ConsensusAnnouncement {
     /// A block was finalized.
     Finalized(Box<FinalizedBlock>),
+    /// An era ended, i.e. its switch block was finalized.
+    ///
+    /// This is always announced after the `Finalized` announcement of the era's switch block.
+    EraEnded {
+        /// The ID of the era that ended.
+        era_id: EraId,
+        /// The height of the era's switch block.
+        switch_block_height: u64,
+        /// The rewards and the equivocating and inactive validators of the era.
+        era_report: Box<EraReport>,
+    },
     /// A finality signature was created.
     CreatedFinalitySignature(Box<FinalitySignature>),
     /// An equivocation has been detected.
@@ -170,6 +182,8 @@ pub enum ConsensusAnnouncement {
         public_key: Box<PublicKey>,
         /// The timestamp when the evidence of the equivocation was detected.
         timestamp: Timestamp,
+        /// The two conflicting signed messages, serialized, if the validator signed both.
+        conflicting_messages: Option<Box<(Vec<u8>, Vec<u8>)>>,
     },
 }
 
@@ -179,6 +193,15 @@ impl Display for ConsensusAnnouncement {
             ConsensusAnnouncement::Finalized(block) => {
                 write!(formatter, "finalized block payload {}", block)
             }
+            ConsensusAnnouncement::EraEnded {
+                era_id,
+                switch_block_height,
+                ..
+            } => write!(
+                formatter,
+                "era {} ended with switch block at height {}",
+                era_id, switch_block_height
+            ),
             ConsensusAnnouncement::CreatedFinalitySignature(fs) => {
                 write!(formatter, "signed an executed block: {}", fs)
             }
@@ -186,6 +209,7 @@ impl Display for ConsensusAnnouncement {
                 era_id,
                 public_key,
                 timestamp,
+                ..
             } => write!(
                 formatter,
                 "Validator fault with public key: {} has been identified at time: {} in era: {}",
@@ -200,6 +224,9 @@ impl Display for ConsensusAnnouncement {
 pub enum BlocklistAnnouncement<I> {
     /// A given peer committed a blockable offense.
     OffenseCommitted(Box<I>),
+    /// A given peer violated the protocol, e.g. by sending an invalid deploy or block, lowering
+    /// its reputation.
+    ProtocolViolation(Box<I>),
 }
 
 impl<I> Display for BlocklistAnnouncement<I>
@@ -211,6 +238,9 @@ where
             BlocklistAnnouncement::OffenseCommitted(peer) => {
                 write!(f, "peer {} committed offense", peer)
             }
+            BlocklistAnnouncement::ProtocolViolation(peer) => {
+                write!(f, "peer {} violated protocol", peer)
+            }
         }
     }
 }
@@ -293,6 +323,9 @@ pub enum GossiperAnnouncement<T: Item> {
 
     /// Finished gossiping about the indicated item.
     FinishedGossiping(T::Id),
+
+    /// An item sent in chunks by the given peer has been reassembled.
+    ItemReassembled { item: Box<T>, sender: NodeId },
 }
 
 impl<T: Item> Display for GossiperAnnouncement<T> {
@@ -302,6 +335,9 @@ impl<T: Item> Display for GossiperAnnouncement<T> {
             GossiperAnnouncement::FinishedGossiping(item_id) => {
                 write!(f, "finished gossiping {}", item_id)
             }
+            GossiperAnnouncement::ItemReassembled { item, sender } => {
+                write!(f, "reassembled {} from {}", item.id(), sender)
+            }
         }
     }
 }
@@ -344,3 +380,29 @@ impl Display for ChainspecLoaderAnnouncement {
         }
     }
 }
+
+/// A storage announcement.
+#[derive(Debug, Serialize)]
+pub enum StorageAnnouncement {
+    /// A stored block failed to be read due to corruption.
+    ///
+    /// It is reported as missing until it is stored again.
+    CorruptBlock(BlockHash),
+    /// A stored deploy failed to be read due to corruption.
+    ///
+    /// It is reported as missing until it is stored again.
+    CorruptDeploy(DeployHash),
+}
+
+impl Display for StorageAnnouncement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageAnnouncement::CorruptBlock(block_hash) => {
+                write!(f, "corrupt block {}", block_hash)
+            }
+            StorageAnnouncement::CorruptDeploy(deploy_hash) => {
+                write!(f, "corrupt deploy {}", deploy_hash)
+            }
+        }
+    }
+}
@@ -25,11 +25,13 @@ use casper_execution_engine::{
         query::{GetBidsRequest, GetBidsResult, QueryRequest, QueryResult},
         step::{StepRequest, StepResult},
         upgrade::{UpgradeConfig, UpgradeResult},
+        ExecuteRequest,
     },
     shared::{newtypes::Blake2bHash, stored_value::StoredValue},
     storage::{protocol_data::ProtocolData, trie::Trie},
 };
 use casper_types::{
+    account::AccountHash,
     system::auction::{EraValidators, ValidatorWeights},
     EraId, ExecutionResult, Key, ProtocolVersion, PublicKey, Transfer, URef,
 };
@@ -43,13 +45,14 @@ use crate::{
         contract_runtime::{EraValidatorsRequest, ValidatorWeightsByEraIdRequest},
         deploy_acceptor::Error,
         fetcher::FetchResult,
+        rpc_server::EstimateGasError,
     },
     crypto::hash::Digest,
     rpcs::{chain::BlockIdentifier, docs::OpenRpcSchema},
     types::{
         Block as LinearBlock, Block, BlockHash, BlockHeader, BlockPayload, BlockSignatures,
         Chainspec, ChainspecInfo, Deploy, DeployHash, DeployHeader, DeployMetadata, FinalizedBlock,
-        Item, NodeId, StatusFeed, TimeDiff,
+        Item, NodeId, PeerConnection, StatusFeed, TimeDiff,
     },
     utils::DisplayIter,
 };
@@ -184,6 +187,19 @@ pub enum NetworkInfoRequest<I> {
         // TODO - change the `String` field to a `libp2p::Multiaddr` once small_network is removed.
         responder: Responder<BTreeMap<I, String>>,
     },
+    /// Get all connections to peers, including the ones still being established.
+    GetPeerConnections {
+        /// Responder to be called with the connections.
+        responder: Responder<Vec<PeerConnection<I>>>,
+    },
+    /// Get the protocol version a connected peer announced when the connection was established.
+    GetPeerProtocolVersion {
+        /// The peer whose protocol version is requested.
+        peer: I,
+        /// Responder to be called with the protocol version, or `None` if not connected to the
+        /// peer or its protocol version is unknown.
+        responder: Responder<Option<ProtocolVersion>>,
+    },
 }
 
 impl<I> Display for NetworkInfoRequest<I>
@@ -193,6 +209,12 @@ where
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
             NetworkInfoRequest::GetPeers { responder: _ } => write!(formatter, "get peers"),
+            NetworkInfoRequest::GetPeerConnections { responder: _ } => {
+                write!(formatter, "get peer connections")
+            }
+            NetworkInfoRequest::GetPeerProtocolVersion { peer, responder: _ } => {
+                write!(formatter, "get protocol version of {}", peer)
+            }
         }
     }
 }
@@ -210,6 +232,15 @@ pub enum StorageRequest {
         /// attempt or false if it was previously stored.
         responder: Responder<bool>,
     },
+    /// Store given block header, without the block's body.
+    ///
+    /// The header is only retrievable by its hash until the complete block is stored.
+    PutBlockHeader {
+        /// Block header to be stored.
+        block_header: Box<BlockHeader>,
+        /// Responder to call with the result.  Returns true if the block header was stored.
+        responder: Responder<bool>,
+    },
     /// Retrieve block with given hash.
     GetBlock {
         /// Hash of block to be retrieved.
@@ -218,6 +249,20 @@ pub enum StorageRequest {
         /// storage.
         responder: Responder<Option<Block>>,
     },
+    /// Retrieve blocks with given hashes.
+    GetBlocks {
+        /// Hashes of blocks to be retrieved.
+        block_hashes: Vec<BlockHash>,
+        /// Responder to call with the results, in the same order as `block_hashes`.
+        responder: Responder<Vec<Option<Block>>>,
+    },
+    /// Delete the bodies of all blocks below the given height, retaining their headers.
+    PruneBlocksBelow {
+        /// Blocks below this height are pruned.
+        height: u64,
+        /// Responder to call with the number of blocks pruned.
+        responder: Responder<usize>,
+    },
     /// Retrieve block header with given height.
     GetBlockHeaderAtHeight {
         /// Height of the block.
@@ -301,6 +346,18 @@ pub enum StorageRequest {
         /// Responder to call with the results.
         responder: Responder<Vec<Option<DeployHeader>>>,
     },
+    /// Retrieve a page of the hashes of deploys sent by the given account, in ascending order.
+    GetDeploysByAccount {
+        /// Hash of the account which sent the deploys.
+        account_hash: Box<AccountHash>,
+        /// Maximum number of deploy hashes to be retrieved.
+        limit: usize,
+        /// If given, only deploy hashes greater than this one are retrieved, i.e. the last deploy
+        /// hash of the previous page.
+        cursor: Option<DeployHash>,
+        /// Responder to call with the results.
+        responder: Responder<Vec<DeployHash>>,
+    },
     /// Retrieve deploys that are finalized and whose TTL hasn't expired yet.
     GetFinalizedDeploys {
         /// Maximum TTL of block we're interested in.
@@ -371,7 +428,16 @@ impl Display for StorageRequest {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
             StorageRequest::PutBlock { block, .. } => write!(formatter, "put {}", block),
+            StorageRequest::PutBlockHeader { block_header, .. } => {
+                write!(formatter, "put {}", block_header)
+            }
             StorageRequest::GetBlock { block_hash, .. } => write!(formatter, "get {}", block_hash),
+            StorageRequest::GetBlocks { block_hashes, .. } => {
+                write!(formatter, "get {}", DisplayIter::new(block_hashes.iter()))
+            }
+            StorageRequest::PruneBlocksBelow { height, .. } => {
+                write!(formatter, "prune blocks below height {}", height)
+            }
             StorageRequest::GetBlockHeaderAtHeight { height, .. } => {
                 write!(formatter, "get block header at height {}", height)
             }
@@ -406,6 +472,11 @@ impl Display for StorageRequest {
                 "get headers {}",
                 DisplayIter::new(deploy_hashes.iter())
             ),
+            StorageRequest::GetDeploysByAccount {
+                account_hash,
+                limit,
+                ..
+            } => write!(formatter, "get {} deploys of {}", limit, account_hash),
             StorageRequest::PutExecutionResults { block_hash, .. } => {
                 write!(formatter, "put execution results for {}", block_hash)
             }
@@ -506,6 +577,11 @@ pub struct BlockPayloadRequest {
 pub enum BlockProposerRequest {
     /// Request a list of deploys to propose in a new block.
     RequestBlockPayload(BlockPayloadRequest),
+    /// Check whether the buffer of pending deploys is full.
+    IsBufferFull {
+        /// Responder to call with the result.
+        responder: Responder<bool>,
+    },
 }
 
 impl Display for BlockProposerRequest {
@@ -524,6 +600,7 @@ impl Display for BlockProposerRequest {
                 context.height(),
                 next_finalized
             ),
+            BlockProposerRequest::IsBufferFull { .. } => write!(formatter, "is buffer full"),
         }
     }
 }
@@ -607,10 +684,18 @@ pub enum RpcRequest<I> {
         /// Responder to call with the result.
         responder: Responder<Option<(Deploy, DeployMetadata)>>,
     },
-    /// Return the connected peers.
+    /// Check the deploy is acceptable, then execute it against the global state of the latest
+    /// block without committing the resulting effects.
+    EstimateGas {
+        /// The deploy to be executed.
+        deploy: Box<Deploy>,
+        /// Responder to call with the result.
+        responder: Responder<Result<ExecutionResult, EstimateGasError>>,
+    },
+    /// Return the connected peers, along with all connections to peers.
     GetPeers {
         /// Responder to call with the result.
-        responder: Responder<BTreeMap<I, String>>,
+        responder: Responder<(BTreeMap<I, String>, Vec<PeerConnection<I>>)>,
     },
     /// Return string formatted status or `None` if an error occurred.
     GetStatus {
@@ -671,6 +756,9 @@ impl<I> Display for RpcRequest<I> {
                 state_root_hash, purse_uref
             ),
             RpcRequest::GetDeploy { hash, .. } => write!(formatter, "get {}", hash),
+            RpcRequest::EstimateGas { deploy, .. } => {
+                write!(formatter, "estimate gas of {}", deploy.id())
+            }
             RpcRequest::GetPeers { .. } => write!(formatter, "get peers"),
             RpcRequest::GetStatus { .. } => write!(formatter, "get status"),
             RpcRequest::GetMetrics { .. } => write!(formatter, "get metrics"),
@@ -781,6 +869,14 @@ pub enum ContractRuntimeRequest {
         /// Responder to call with the result.
         responder: Responder<Result<GetBidsResult, engine_state::Error>>,
     },
+    /// Executes a request against global state without committing the resulting effects.
+    ExecuteDryRun {
+        /// The execute request.
+        #[serde(skip_serializing)]
+        execute_request: Box<ExecuteRequest>,
+        /// Responder to call with the execution result.
+        responder: Responder<Result<ExecutionResult, engine_state::Error>>,
+    },
     /// Performs a step consisting of calculating rewards, slashing and running the auction at the
     /// end of an era.
     Step {
@@ -866,6 +962,12 @@ impl Display for ContractRuntimeRequest {
                 write!(formatter, "get bids request: {:?}", get_bids_request)
             }
 
+            ContractRuntimeRequest::ExecuteDryRun {
+                execute_request, ..
+            } => {
+                write!(formatter, "execute dry run: {:?}", execute_request)
+            }
+
             ContractRuntimeRequest::Step { step_request, .. } => {
                 write!(formatter, "step: {:?}", step_request)
             }
@@ -909,12 +1011,28 @@ pub enum FetcherRequest<I, T: Item> {
         /// Responder to call with the result.
         responder: Responder<Option<FetchResult<T, I>>>,
     },
+    /// Return the specified item if it exists, else try each of the given peers in order until
+    /// one provides it.
+    FetchFromPeers {
+        /// The ID of the item to be retrieved.
+        id: T::Id,
+        /// The peers to be asked in turn if the item is not held locally.
+        peers: Vec<I>,
+        /// Responder to call with the result.
+        responder: Responder<Option<FetchResult<T, I>>>,
+    },
 }
 
 impl<I, T: Item> Display for FetcherRequest<I, T> {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         match self {
             FetcherRequest::Fetch { id, .. } => write!(formatter, "request item by id {}", id),
+            FetcherRequest::FetchFromPeers { id, peers, .. } => write!(
+                formatter,
+                "request item by id {} from {} peers",
+                id,
+                peers.len()
+            ),
         }
     }
 }
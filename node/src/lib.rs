@@ -98,6 +98,9 @@ pub static VERSION_STRING_COLOR: Lazy<String> = Lazy::new(|| version_string(true
 /// Version string for the compiled node. Filled in at build time, output allocated at runtime.
 pub static VERSION_STRING: Lazy<String> = Lazy::new(|| version_string(false));
 
+/// The time at which the node started running, from which its uptime is measured.
+pub static NODE_START_TIME: Lazy<types::Timestamp> = Lazy::new(types::Timestamp::now);
+
 /// Global value that indicates the currently running reactor should exit if it is non-zero.
 pub static TERMINATION_REQUESTED: Lazy<Arc<AtomicUsize>> =
     Lazy::new(|| Arc::new(AtomicUsize::new(0)));
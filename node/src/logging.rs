@@ -1,6 +1,6 @@
 //! Logging via the tracing crate.
 
-use std::{env, fmt, io};
+use std::{env, fmt, io, str::FromStr};
 
 use ansi_term::{Color, Style};
 use anyhow::anyhow;
@@ -13,9 +13,9 @@ use tracing::{
 };
 use tracing_subscriber::{
     fmt::{
-        format,
+        format::{self, Format, Json, JsonFields},
         time::{FormatTime, SystemTime},
-        FmtContext, FormatEvent, FormatFields, FormattedFields,
+        FmtContext, FormatEvent, FormatFields, FormattedFields, MakeWriter, SubscriberBuilder,
     },
     registry::LookupSpan,
     EnvFilter,
@@ -58,17 +58,27 @@ impl LoggingConfig {
             abbreviate_modules,
         }
     }
+
+    /// Sets the output format, replacing the configured one.
+    pub fn set_format(&mut self, format: LoggingFormat) {
+        self.format = format;
+    }
 }
 
 /// Logging output format.
 ///
 /// Defaults to "text"".
-#[derive(DataSize, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum LoggingFormat {
     /// Text format.
     Text,
     /// JSON format.
+    ///
+    /// Each log line is a JSON object holding the `timestamp`, `level`, `target` (the module
+    /// emitting it, and thereby the component) and `message`, along with the event's fields.  The
+    /// fields of the enclosing spans are nested in the `span` object for the innermost span, and
+    /// in the `spans` list for all of them.
     Json,
 }
 
@@ -78,6 +88,21 @@ impl Default for LoggingFormat {
     }
 }
 
+impl FromStr for LoggingFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "text" => Ok(LoggingFormat::Text),
+            "json" => Ok(LoggingFormat::Json),
+            _ => Err(anyhow!(
+                "invalid log format '{}', expected 'text' or 'json'",
+                input
+            )),
+        }
+    }
+}
+
 /// This is used to implement tracing's `FormatEvent` so that we can customize the way tracing
 /// events are formatted.
 struct FmtEvent {
@@ -286,11 +311,59 @@ pub fn init_with_config(config: &LoggingConfig) -> anyhow::Result<()> {
             .event_format(FmtEvent::new(config.color, config.abbreviate_modules))
             .try_init(),
         // JSON logging writes to `stdout` as well but uses the JSON format.
-        LoggingFormat::Json => tracing_subscriber::fmt()
-            .with_writer(io::stdout)
-            .with_env_filter(filter)
-            .json()
-            .try_init(),
+        LoggingFormat::Json => json_subscriber(filter, io::stdout).try_init(),
     }
     .map_err(|error| anyhow!(error))
 }
+
+/// Creates a builder for a subscriber writing newline-delimited JSON to `make_writer`.
+fn json_subscriber<W>(
+    filter: EnvFilter,
+    make_writer: W,
+) -> SubscriberBuilder<JsonFields, Format<Json>, EnvFilter, W>
+where
+    W: MakeWriter + 'static,
+{
+    tracing_subscriber::fmt()
+        .with_writer(make_writer)
+        .with_env_filter(filter)
+        .json()
+        .flatten_event(true)
+        .with_current_span(true)
+        .with_span_list(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use tracing::{info, info_span};
+
+    use super::*;
+    use crate::{reactor::CorrelationId, testing::LogBuffer};
+
+    #[test]
+    fn json_format_should_nest_span_fields() {
+        let mut rng = crate::new_rng();
+        let correlation_id = CorrelationId::random(&mut rng);
+
+        let log_buffer = LogBuffer::default();
+        let writer = log_buffer.clone();
+        let subscriber = json_subscriber(EnvFilter::new("info"), move || writer.clone()).finish();
+        tracing::subscriber::with_default(subscriber, || {
+            let span = info_span!("dispatch events", ev = 7, %correlation_id);
+            span.in_scope(|| info!(deploy_hash = "abc", "handling event"));
+        });
+
+        let output = log_buffer.contents();
+        let line: Value = serde_json::from_str(output.lines().next().expect("should log a line"))
+            .expect("should be valid JSON");
+        assert!(line["timestamp"].is_string());
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["target"], module_path!());
+        assert_eq!(line["message"], "handling event");
+        assert_eq!(line["deploy_hash"], "abc");
+        assert_eq!(line["span"]["name"], "dispatch events");
+        assert_eq!(line["span"]["correlation_id"], correlation_id.to_string());
+        assert_eq!(line["spans"][0]["ev"], 7);
+    }
+}
@@ -2,6 +2,7 @@
 
 use std::fmt::{self, Display, Formatter};
 
+use casper_types::ProtocolVersion;
 use derive_more::From;
 use fmt::Debug;
 use hex_fmt::HexFmt;
@@ -15,6 +16,10 @@ use crate::{
     types::{Deploy, FinalitySignature, Item, SharedObject, Tag},
 };
 
+/// The first protocol version whose nodes understand requests for and responses with global state
+/// trie nodes.
+pub(crate) const TRIE_PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::from_parts(1, 4, 0);
+
 /// Reactor message.
 #[derive(Clone, From, Serialize, Deserialize)]
 pub enum Message {
@@ -62,6 +67,7 @@ impl Payload for Message {
                     Tag::BlockByHeight => MessageKind::BlockTransfer,
                     Tag::BlockHeaderByHash => MessageKind::BlockTransfer,
                     Tag::BlockHeaderAndFinalitySignaturesByHeight => MessageKind::BlockTransfer,
+                    Tag::Trie => MessageKind::Other,
                 }
             }
             Message::FinalitySignature(_) => MessageKind::Consensus,
@@ -81,10 +87,25 @@ impl Payload for Message {
                 Tag::BlockByHeight => 0,
                 Tag::BlockHeaderByHash => 0,
                 Tag::BlockHeaderAndFinalitySignaturesByHeight => 0,
+                Tag::Trie => 0,
             },
             Message::FinalitySignature(_) => 0,
         }
     }
+
+    #[inline]
+    fn min_protocol_version(&self) -> ProtocolVersion {
+        match self {
+            Message::DeployGossiper(message) => message.min_protocol_version(),
+            Message::AddressGossiper(message) => message.min_protocol_version(),
+            Message::GetRequest { tag: Tag::Trie, .. }
+            | Message::GetResponse { tag: Tag::Trie, .. } => TRIE_PROTOCOL_VERSION,
+            Message::Consensus(_)
+            | Message::GetRequest { .. }
+            | Message::GetResponse { .. }
+            | Message::FinalitySignature(_) => ProtocolVersion::V1_0_0,
+        }
+    }
 }
 
 impl Message {
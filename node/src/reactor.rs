@@ -24,6 +24,7 @@
 //! in a step-wise manner using [`crank`](struct.Runner.html#method.crank) or indefinitely using
 //! [`run`](struct.Runner.html#method.crank).
 
+mod correlation_id;
 mod event_queue_metrics;
 pub mod initializer;
 pub mod joiner;
@@ -33,33 +34,45 @@ mod queue_kind;
 #[cfg(test)]
 use std::sync::Arc;
 use std::{
-    any,
+    any::{self, Any},
+    cell::Cell,
     collections::HashMap,
     env,
-    fmt::{Debug, Display},
+    fmt::{self, Debug, Display, Formatter},
     fs::File,
     mem,
+    panic::{self, AssertUnwindSafe},
     str::FromStr,
-    sync::atomic::Ordering,
+    sync::{atomic::Ordering, RwLock},
 };
 
 use datasize::DataSize;
-use futures::{future::BoxFuture, FutureExt};
+use futures::{
+    future::{self, BoxFuture},
+    FutureExt,
+};
 use jemalloc_ctl::{epoch as jemalloc_epoch, stats::allocated as jemalloc_allocated};
 use once_cell::sync::Lazy;
-use prometheus::{self, Histogram, HistogramOpts, IntCounter, IntGauge, Registry};
+use prometheus::{
+    self, Histogram, HistogramOpts, IntCounter, IntGauge, IntGaugeVec, Opts, Registry,
+};
 use quanta::{Clock, IntoNanoseconds};
 use serde::Serialize;
 use signal_hook::consts::signal::{SIGINT, SIGQUIT, SIGTERM};
-use tokio::time::{Duration, Instant};
-use tracing::{debug, debug_span, error, info, instrument, trace, warn};
+use tokio::{
+    task::JoinHandle,
+    time::{Duration, Instant},
+};
+use tracing::{debug, debug_span, error, field, info, instrument, trace, trace_span, warn};
 use tracing_futures::Instrument;
 
 #[cfg(target_os = "linux")]
 use utils::rlimit::{Limit, OpenFiles, ResourceLimit};
 
 use crate::{
-    effect::{announcements::ControlAnnouncement, Effect, EffectBuilder, Effects},
+    components::Component,
+    effect::{announcements::ControlAnnouncement, Effect, EffectBuilder, EffectExt, Effects},
+    fatal,
     types::{ExitCode, Timestamp},
     unregister_metric,
     utils::{self, WeightedRoundRobin},
@@ -67,6 +80,8 @@ use crate::{
 };
 #[cfg(test)]
 use crate::{reactor::initializer::Reactor as InitializerReactor, types::Chainspec};
+pub(crate) use correlation_id::with_correlation_id;
+pub use correlation_id::CorrelationId;
 pub use queue_kind::QueueKind;
 
 /// Optional upper threshold for total RAM allocated in mB before dumping queues to disk.
@@ -89,6 +104,9 @@ static MEM_DUMP_THRESHOLD_MB: Lazy<Option<u64>> = Lazy::new(|| {
 const DEFAULT_DISPATCH_EVENT_THRESHOLD: Duration = Duration::from_secs(1);
 const DISPATCH_EVENT_THRESHOLD_ENV_VAR: &str = "CL_EVENT_MAX_MICROSECS";
 
+/// How long to wait for the effects returned when shutting down the reactor to complete.
+const SHUTDOWN_EFFECTS_TIMEOUT: Duration = Duration::from_secs(5);
+
 static DISPATCH_EVENT_THRESHOLD: Lazy<Duration> = Lazy::new(|| {
     env::var(DISPATCH_EVENT_THRESHOLD_ENV_VAR)
         .map(|threshold_str| {
@@ -167,38 +185,145 @@ pub enum ReactorExit {
 /// is the central hook for any part of the program that schedules events directly.
 ///
 /// Components rarely use this, but use a bound `EventQueueHandle` instead.
-pub type Scheduler<Ev> = WeightedRoundRobin<Ev, QueueKind>;
+///
+/// Each event is queued along with the context it was scheduled in.
+pub type Scheduler<Ev> = WeightedRoundRobin<(Ev, EventContext), QueueKind>;
+
+/// Context an event is queued along with.
+#[derive(Debug, Serialize)]
+pub struct EventContext {
+    /// Correlation ID of the event, if it has one.
+    correlation_id: Option<CorrelationId>,
+    /// Queue depth gauge of the component the event is destined for, if known.
+    ///
+    /// The gauge is incremented when the event is queued and decremented when it is popped.
+    #[serde(skip)]
+    queue_depth: Option<IntGauge>,
+}
+
+impl EventContext {
+    /// Records that the event has been popped off the scheduler, returning its correlation ID.
+    pub(crate) fn dequeue(self) -> Option<CorrelationId> {
+        if let Some(queue_depth) = self.queue_depth {
+            queue_depth.dec();
+        }
+        self.correlation_id
+    }
+}
+
+/// Gauges of the number of events queued for each component.
+pub(crate) struct QueueDepth<REv> {
+    /// Gauges labeled by the name of their component.
+    gauges: IntGaugeVec,
+    /// Gauges looked up so far, by the name of their component.
+    cache: RwLock<HashMap<&'static str, IntGauge>>,
+    /// Returns the name of the component an event is destined for.
+    destination: fn(&REv) -> &'static str,
+}
+
+impl<REv> QueueDepth<REv> {
+    /// Creates new queue depth gauges, labeling events by their `ReactorEvent::destination`.
+    fn new(gauges: IntGaugeVec) -> Self
+    where
+        REv: ReactorEvent,
+    {
+        QueueDepth {
+            gauges,
+            cache: RwLock::new(HashMap::new()),
+            destination: REv::destination,
+        }
+    }
+
+    /// Returns the gauge of the component the given event is destined for.
+    fn gauge(&self, event: &REv) -> IntGauge {
+        let destination = (self.destination)(event);
+        if let Some(gauge) = self
+            .cache
+            .read()
+            .expect("queue depth cache lock poisoned")
+            .get(destination)
+        {
+            return gauge.clone();
+        }
+        self.cache
+            .write()
+            .expect("queue depth cache lock poisoned")
+            .entry(destination)
+            .or_insert_with(|| self.gauges.with_label_values(&[destination]))
+            .clone()
+    }
+}
+
+impl<REv> Debug for QueueDepth<REv> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("QueueDepth")
+            .field("gauges", &self.gauges)
+            .field("cache", &self.cache)
+            .finish()
+    }
+}
 
 /// Event queue handle
 ///
 /// The event queue handle is how almost all parts of the application interact with the reactor
 /// outside of the normal event loop. It gives different parts a chance to schedule messages that
 /// stem from things like external IO.
+///
+/// Events scheduled through a handle created by a `Runner` are counted in the queue depth gauge of
+/// the component they are destined for until they are popped off the scheduler.
 #[derive(DataSize, Debug)]
-pub struct EventQueueHandle<REv>(&'static Scheduler<REv>)
+pub struct EventQueueHandle<REv>(
+    &'static Scheduler<REv>,
+    #[data_size(skip)] Option<&'static QueueDepth<REv>>,
+)
 where
     REv: 'static;
 
 // Implement `Clone` and `Copy` manually, as `derive` will make it depend on `R` and `Ev` otherwise.
 impl<REv> Clone for EventQueueHandle<REv> {
     fn clone(&self) -> Self {
-        EventQueueHandle(self.0)
+        EventQueueHandle(self.0, self.1)
     }
 }
 impl<REv> Copy for EventQueueHandle<REv> {}
 
 impl<REv> EventQueueHandle<REv> {
     pub(crate) fn new(scheduler: &'static Scheduler<REv>) -> Self {
-        EventQueueHandle(scheduler)
+        EventQueueHandle(scheduler, None)
+    }
+
+    /// Creates a new event queue handle counting the events it schedules in `queue_depth`.
+    fn with_queue_depth(
+        scheduler: &'static Scheduler<REv>,
+        queue_depth: &'static QueueDepth<REv>,
+    ) -> Self {
+        EventQueueHandle(scheduler, Some(queue_depth))
     }
 
     /// Schedule an event on a specific queue.
+    ///
+    /// The event inherits the correlation ID of the effect scheduling it.
     #[inline]
     pub(crate) async fn schedule<Ev>(self, event: Ev, queue_kind: QueueKind)
     where
         REv: From<Ev>,
     {
-        self.0.push(event.into(), queue_kind).await
+        self.push(event.into(), CorrelationId::current(), queue_kind)
+            .await
+    }
+
+    /// Pushes an event onto the scheduler, counting it in the queue depth of its destination.
+    async fn push(self, event: REv, correlation_id: Option<CorrelationId>, queue_kind: QueueKind) {
+        let queue_depth = self.1.map(|queue_depth| queue_depth.gauge(&event));
+        if let Some(ref gauge) = queue_depth {
+            gauge.inc();
+        }
+        let context = EventContext {
+            correlation_id,
+            queue_depth,
+        };
+        self.0.push((event, context), queue_kind).await
     }
 
     /// Returns number of events in each of the scheduler's queues.
@@ -241,7 +366,8 @@ pub trait Reactor: Sized {
     /// Creates a new instance of the reactor.
     ///
     /// This method creates the full state, which consists of all components, and returns a reactor
-    /// instance along with the effects that the components generated upon instantiation.
+    /// instance along with the effects that the components generated upon instantiation and
+    /// initialization.
     ///
     /// If any instantiation fails, an error is returned.
     fn new(
@@ -257,6 +383,14 @@ pub trait Reactor: Sized {
 
     /// Instructs the reactor to update performance metrics, if any.
     fn update_metrics(&mut self, _event_queue_handle: EventQueueHandle<Self::Event>) {}
+
+    /// Notifies the reactor that it is shutting down due to a fatal error or a termination signal.
+    ///
+    /// Reactors should call `Component::on_shutdown` on each of their components, e.g. via
+    /// `shutdown_component`.
+    fn shutdown(&mut self, _effect_builder: EffectBuilder<Self::Event>) -> Effects<Self::Event> {
+        Effects::new()
+    }
 }
 
 /// A reactor event type.
@@ -267,6 +401,14 @@ pub trait ReactorEvent: Send + Debug + From<ControlAnnouncement> + 'static {
     /// [`ControlAnnouncement`](`crate::effect::announcements::ControlAnnouncement`) if the event
     /// is indeed a control announcement variant.
     fn as_control(&self) -> Option<&ControlAnnouncement>;
+
+    /// Returns the name of the component the event is destined for.
+    ///
+    /// Used to label the event in the `reactor_queue_depth` metrics. Events handled by the reactor
+    /// itself, e.g. announcements passed on to several components, are labeled `"reactor"`.
+    fn destination(&self) -> &'static str {
+        "reactor"
+    }
 }
 
 /// A drop-like trait for `async` compatible drop-and-wait.
@@ -304,6 +446,9 @@ where
     /// The scheduler used for the reactor.
     scheduler: &'static Scheduler<R::Event>,
 
+    /// Handle to the scheduler, counting the events it schedules in the queue depth metrics.
+    event_queue: EventQueueHandle<R::Event>,
+
     /// The reactor instance itself.
     reactor: R,
 
@@ -342,6 +487,8 @@ struct RunnerMetrics {
     consumed_ram_bytes: IntGauge,
     /// Total system RAM in bytes, as reported by sys-info.
     total_ram_bytes: IntGauge,
+    /// Number of events queued for each component, labeled by the component's name.
+    queue_depth: IntGaugeVec,
     /// Handle to the metrics registry, in case we need to unregister.
     registry: Registry,
 }
@@ -385,12 +532,20 @@ impl RunnerMetrics {
         let consumed_ram_bytes =
             IntGauge::new("consumed_ram_bytes", "total consumed ram in bytes")?;
         let total_ram_bytes = IntGauge::new("total_ram_bytes", "total system ram in bytes")?;
+        let queue_depth = IntGaugeVec::new(
+            Opts::new(
+                "reactor_queue_depth",
+                "number of events queued for a component",
+            ),
+            &["component"],
+        )?;
 
         registry.register(Box::new(events.clone()))?;
         registry.register(Box::new(event_dispatch_duration.clone()))?;
         registry.register(Box::new(allocated_ram_bytes.clone()))?;
         registry.register(Box::new(consumed_ram_bytes.clone()))?;
         registry.register(Box::new(total_ram_bytes.clone()))?;
+        registry.register(Box::new(queue_depth.clone()))?;
 
         Ok(RunnerMetrics {
             events,
@@ -399,6 +554,7 @@ impl RunnerMetrics {
             allocated_ram_bytes,
             consumed_ram_bytes,
             total_ram_bytes,
+            queue_depth,
         })
     }
 }
@@ -410,6 +566,7 @@ impl Drop for RunnerMetrics {
         unregister_metric!(self.registry, self.allocated_ram_bytes);
         unregister_metric!(self.registry, self.consumed_ram_bytes);
         unregister_metric!(self.registry, self.total_ram_bytes);
+        unregister_metric!(self.registry, self.queue_depth);
     }
 }
 
@@ -452,12 +609,14 @@ where
         }
 
         let scheduler = utils::leak(Scheduler::new(QueueKind::weights()));
+        let metrics = RunnerMetrics::new(registry)?;
+        let queue_depth = utils::leak(QueueDepth::new(metrics.queue_depth.clone()));
 
-        let event_queue = EventQueueHandle::new(scheduler);
+        let event_queue = EventQueueHandle::with_queue_depth(scheduler, queue_depth);
         let (reactor, initial_effects) = R::new(cfg, registry, event_queue, rng)?;
 
         // Run all effects from component instantiation.
-        process_effects(scheduler, initial_effects)
+        process_effects(event_queue, initial_effects, None)
             .instrument(debug_span!("process initial effects"))
             .await;
 
@@ -465,9 +624,10 @@ where
 
         Ok(Runner {
             scheduler,
+            event_queue,
             reactor,
             event_count: 0,
-            metrics: RunnerMetrics::new(registry)?,
+            metrics,
             last_metrics: Instant::now(),
             event_metrics_min_delay: Duration::from_secs(30),
             event_metrics_threshold: 1000,
@@ -483,12 +643,11 @@ where
     where
         F: FnOnce(EffectBuilder<R::Event>) -> Effects<R::Event>,
     {
-        let event_queue = EventQueueHandle::new(self.scheduler);
-        let effect_builder = EffectBuilder::new(event_queue);
+        let effect_builder = EffectBuilder::new(self.event_queue);
 
         let effects = create_effects(effect_builder);
 
-        process_effects(self.scheduler, effects)
+        process_effects(self.event_queue, effects, None)
             .instrument(debug_span!(
                 "process injected effects",
                 ev = self.event_count
@@ -504,7 +663,7 @@ where
     pub async fn crank(&mut self, rng: &mut NodeRng) -> bool {
         self.metrics.events.inc();
 
-        let event_queue = EventQueueHandle::new(self.scheduler);
+        let event_queue = self.event_queue;
         let effect_builder = EffectBuilder::new(event_queue);
 
         // Update metrics like memory usage and event queue sizes.
@@ -552,10 +711,18 @@ where
             QUEUE_DUMP_REQUESTED.store(false, Ordering::SeqCst);
         }
 
-        let (event, q) = self.scheduler.pop().await;
+        let ((event, context), q) = self.scheduler.pop().await;
+        let correlation_id = context.dequeue();
 
         // Create another span for tracing the processing of one event.
-        let event_span = debug_span!("dispatch events", ev = self.event_count);
+        let event_span = debug_span!(
+            "dispatch events",
+            ev = self.event_count,
+            correlation_id = field::Empty
+        );
+        if let Some(correlation_id) = correlation_id {
+            event_span.record("correlation_id", &field::display(correlation_id));
+        }
         let (effects, keep_going) = event_span.in_scope(|| {
             // We log events twice, once in display and once in debug mode.
             let event_as_string = format!("{}", event);
@@ -570,7 +737,7 @@ where
                 match ctrl_ann {
                     ControlAnnouncement::FatalError { file, line, msg } => {
                         error!(%file, %line, %msg, "fatal error via control announcement");
-                        (Default::default(), false)
+                        (self.reactor.shutdown(effect_builder), false)
                     }
                 }
             } else {
@@ -598,10 +765,15 @@ where
             (effects, keep_going)
         });
 
-        process_effects(self.scheduler, effects)
+        let effect_handles = process_effects(self.event_queue, effects, correlation_id)
             .instrument(debug_span!("process effects", ev = self.event_count))
             .await;
 
+        if !keep_going {
+            // The reactor has been shut down, give its final effects a chance to complete.
+            await_shutdown_effects(effect_handles).await;
+        }
+
         self.event_count += 1;
 
         keep_going
@@ -716,11 +888,14 @@ where
                         // since that workaround of making two attempts with the first wrapped in a
                         // timeout should no longer be required.
 
-                        for event in self.scheduler.drain_queue(QueueKind::Control).await {
+                        for (event, context) in self.scheduler.drain_queue(QueueKind::Control).await
+                        {
+                            context.dequeue();
                             if let Some(ctrl_ann) = event.as_control() {
                                 match ctrl_ann {
                                     ControlAnnouncement::FatalError { file, line, msg } => {
                                         warn!(%file, line=*line, %msg, "exiting due to fatal error scheduled before reactor completion");
+                                        self.shutdown().await;
                                         return ReactorExit::ProcessShouldExit(ExitCode::Abort);
                                     }
                                 }
@@ -735,14 +910,35 @@ where
                         break ReactorExit::ProcessShouldExit(ExitCode::Abort);
                     }
                 }
-                SIGINT => break ReactorExit::ProcessShouldExit(ExitCode::SigInt),
-                SIGQUIT => break ReactorExit::ProcessShouldExit(ExitCode::SigQuit),
-                SIGTERM => break ReactorExit::ProcessShouldExit(ExitCode::SigTerm),
+                SIGINT => {
+                    self.shutdown().await;
+                    break ReactorExit::ProcessShouldExit(ExitCode::SigInt);
+                }
+                SIGQUIT => {
+                    self.shutdown().await;
+                    break ReactorExit::ProcessShouldExit(ExitCode::SigQuit);
+                }
+                SIGTERM => {
+                    self.shutdown().await;
+                    break ReactorExit::ProcessShouldExit(ExitCode::SigTerm);
+                }
                 _ => error!("should be unreachable - bug in signal handler"),
             }
         }
     }
 
+    /// Notifies the reactor that it is shutting down, processes the resulting effects and waits
+    /// for them to complete, up to `SHUTDOWN_EFFECTS_TIMEOUT`.
+    pub(crate) async fn shutdown(&mut self) {
+        info!("shutting down reactor");
+        let effect_builder = EffectBuilder::new(self.event_queue);
+        let effects = self.reactor.shutdown(effect_builder);
+        let effect_handles = process_effects(self.event_queue, effects, None)
+            .instrument(debug_span!("process shutdown effects"))
+            .await;
+        await_shutdown_effects(effect_handles).await;
+    }
+
     /// Returns a reference to the reactor.
     #[inline]
     pub fn reactor(&self) -> &R {
@@ -759,7 +955,8 @@ where
     #[inline]
     pub async fn drain_into_inner(self) -> R {
         self.scheduler.seal();
-        for event in self.scheduler.drain_queues().await {
+        for (event, context) in self.scheduler.drain_queues().await {
+            context.dequeue();
             debug!(event=%event, "drained event");
         }
         self.reactor
@@ -774,14 +971,16 @@ impl Runner<InitializerReactor> {
     ) -> Result<Self, <InitializerReactor as Reactor>::Error> {
         let registry = Registry::new();
         let scheduler = utils::leak(Scheduler::new(QueueKind::weights()));
+        let metrics = RunnerMetrics::new(&registry)?;
+        let queue_depth = utils::leak(QueueDepth::new(metrics.queue_depth.clone()));
 
-        let event_queue = EventQueueHandle::new(scheduler);
+        let event_queue = EventQueueHandle::with_queue_depth(scheduler, queue_depth);
         let (reactor, initial_effects) =
             InitializerReactor::new_with_chainspec(cfg, &registry, event_queue, chainspec)?;
 
         // Run all effects from component instantiation.
         let span = debug_span!("process initial effects");
-        process_effects(scheduler, initial_effects)
+        process_effects(event_queue, initial_effects, None)
             .instrument(span)
             .await;
 
@@ -791,9 +990,10 @@ impl Runner<InitializerReactor> {
         let now = Instant::now();
         Ok(Runner {
             scheduler,
+            event_queue,
             reactor,
             event_count: 0,
-            metrics: RunnerMetrics::new(&registry)?,
+            metrics,
             // Calculate the `last_metrics` timestamp to be exactly one delay in the past. This will
             // cause the runner to collect metrics at the first opportunity.
             last_metrics: now.checked_sub(event_metrics_min_delay).unwrap_or(now),
@@ -806,20 +1006,50 @@ impl Runner<InitializerReactor> {
 }
 
 /// Spawns tasks that will process the given effects.
+///
+/// The effects are run with the given correlation ID, which the resulting events inherit unless an
+/// effect replaced it.
+///
+/// The resulting events are queued through `event_queue`, which counts them in the queue depth
+/// gauge of the component they are destined for.
 #[inline]
-async fn process_effects<Ev>(scheduler: &'static Scheduler<Ev>, effects: Effects<Ev>)
+async fn process_effects<Ev>(
+    event_queue: EventQueueHandle<Ev>,
+    effects: Effects<Ev>,
+    correlation_id: Option<CorrelationId>,
+) -> Vec<JoinHandle<()>>
 where
     Ev: Send + 'static,
 {
     // TODO: Properly carry around priorities.
     let queue_kind = QueueKind::default();
 
-    for effect in effects {
-        tokio::spawn(async move {
-            for event in effect.await {
-                scheduler.push(event, queue_kind).await
-            }
-        });
+    effects
+        .into_iter()
+        .map(|effect| {
+            tokio::spawn(with_correlation_id(correlation_id, async move {
+                let events = effect.await;
+                let correlation_id = CorrelationId::current();
+                for event in events {
+                    event_queue.push(event, correlation_id, queue_kind).await
+                }
+            }))
+        })
+        .collect()
+}
+
+/// Waits for the tasks running the effects returned on shutdown, up to `SHUTDOWN_EFFECTS_TIMEOUT`.
+async fn await_shutdown_effects(effect_handles: Vec<JoinHandle<()>>) {
+    if effect_handles.is_empty() {
+        return;
+    }
+
+    let all_completed = future::join_all(effect_handles);
+    if tokio::time::timeout(SHUTDOWN_EFFECTS_TIMEOUT, all_completed)
+        .await
+        .is_err()
+    {
+        warn!("timed out waiting for shutdown effects to complete");
     }
 }
 
@@ -852,3 +1082,406 @@ where
         .map(move |effect| wrap_effect(wrap.clone(), effect))
         .collect()
 }
+
+thread_local! {
+    /// Whether a component is currently handling an event on this thread, inside the panic
+    /// boundary of `dispatch_to_component`.
+    static IN_PANIC_BOUNDARY: Cell<bool> = Cell::new(false);
+}
+
+/// Returns whether a panic on the current thread is caught by the reactor's panic boundary around
+/// `Component::handle_event` and converted into a fatal error.
+///
+/// Panic hooks aborting the process should not do so if this returns `true`, as the reactor then
+/// shuts down on its own.
+pub fn in_panic_boundary() -> bool {
+    IN_PANIC_BOUNDARY.with(Cell::get)
+}
+
+/// Returns the message of a panic, if its payload is a string.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg
+    } else {
+        "<non-string panic payload>"
+    }
+}
+
+/// Passes an event to a component and converts the resulting effects into reactor effects by
+/// wrapping.
+///
+/// The call to `Component::handle_event` is traced inside a span carrying the component's name.
+/// A panic while handling the event is caught and converted into a fatal error naming the
+/// component, so the reactor shuts down in an orderly fashion instead of unwinding.
+#[inline]
+pub(crate) fn dispatch_to_component<C, REv, F>(
+    wrap: F,
+    component: &mut C,
+    effect_builder: EffectBuilder<REv>,
+    rng: &mut NodeRng,
+    event: C::Event,
+) -> Effects<REv>
+where
+    C: Component<REv>,
+    C::Event: Send + 'static,
+    F: Fn(C::Event) -> REv + Send + 'static + Clone,
+    REv: From<ControlAnnouncement> + Send + 'static,
+{
+    let span = trace_span!("handle_event", component = component.name());
+    let was_in_panic_boundary = IN_PANIC_BOUNDARY.with(|in_boundary| in_boundary.replace(true));
+    let result = span.in_scope(|| {
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            component.handle_event(effect_builder, rng, event)
+        }))
+    });
+    IN_PANIC_BOUNDARY.with(|in_boundary| in_boundary.set(was_in_panic_boundary));
+
+    match result {
+        Ok(effects) => wrap_effects(wrap, effects),
+        Err(payload) => fatal!(
+            effect_builder,
+            "component {} panicked while handling an event: {}",
+            component.name(),
+            panic_message(&*payload)
+        )
+        .ignore(),
+    }
+}
+
+/// Initializes a component and converts the resulting effects into reactor effects by wrapping.
+#[inline]
+pub(crate) fn init_component<C, REv, F>(
+    wrap: F,
+    component: &mut C,
+    effect_builder: EffectBuilder<REv>,
+) -> Effects<REv>
+where
+    C: Component<REv>,
+    C::Event: Send + 'static,
+    F: Fn(C::Event) -> REv + Send + 'static + Clone,
+    REv: Send + 'static,
+{
+    let span = trace_span!("init", component = component.name());
+    let effects = span.in_scope(|| component.init(effect_builder));
+    wrap_effects(wrap, effects)
+}
+
+/// Notifies a component that the reactor is shutting down and converts the resulting effects into
+/// reactor effects by wrapping.
+#[inline]
+pub(crate) fn shutdown_component<C, REv, F>(
+    wrap: F,
+    component: &mut C,
+    effect_builder: EffectBuilder<REv>,
+) -> Effects<REv>
+where
+    C: Component<REv>,
+    C::Event: Send + 'static,
+    F: Fn(C::Event) -> REv + Send + 'static + Clone,
+    REv: Send + 'static,
+{
+    let span = trace_span!("on_shutdown", component = component.name());
+    let effects = span.in_scope(|| component.on_shutdown(effect_builder));
+    wrap_effects(wrap, effects)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::Infallible,
+        fmt::{self, Formatter},
+    };
+
+    use derive_more::From;
+
+    use super::*;
+    use crate::{components::Component, effect::EffectExt};
+
+    /// Event of the test component.
+    #[derive(Debug, PartialEq, Serialize)]
+    enum ComponentEvent {
+        Initialized,
+        External,
+        Panic,
+        /// Asks the component to send itself the given number of `External` events.
+        Flood(usize),
+    }
+
+    /// Component recording the events it handles, panicking on `ComponentEvent::Panic`.
+    struct RecordingComponent {
+        name: &'static str,
+        handled: Vec<ComponentEvent>,
+        shut_down: bool,
+    }
+
+    impl RecordingComponent {
+        fn new(name: &'static str) -> Self {
+            RecordingComponent {
+                name,
+                handled: Vec::new(),
+                shut_down: false,
+            }
+        }
+    }
+
+    impl<REv> Component<REv> for RecordingComponent {
+        type Event = ComponentEvent;
+        type ConstructionError = Infallible;
+
+        fn handle_event(
+            &mut self,
+            _effect_builder: EffectBuilder<REv>,
+            _rng: &mut NodeRng,
+            event: Self::Event,
+        ) -> Effects<Self::Event> {
+            let effects = match event {
+                ComponentEvent::Panic => panic!("asked to panic"),
+                ComponentEvent::Flood(count) => (0..count)
+                    .flat_map(|_| async {}.event(|()| ComponentEvent::External))
+                    .collect(),
+                _ => Effects::new(),
+            };
+            self.handled.push(event);
+            effects
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn init(&mut self, _effect_builder: EffectBuilder<REv>) -> Effects<Self::Event> {
+            async {}.event(|()| ComponentEvent::Initialized)
+        }
+
+        fn on_shutdown(&mut self, _effect_builder: EffectBuilder<REv>) -> Effects<Self::Event> {
+            self.shut_down = true;
+            Effects::new()
+        }
+    }
+
+    #[derive(Debug, From, Serialize)]
+    enum Event {
+        #[from]
+        Component(ComponentEvent),
+        Other(ComponentEvent),
+        #[from]
+        ControlAnnouncement(ControlAnnouncement),
+    }
+
+    impl ReactorEvent for Event {
+        fn as_control(&self) -> Option<&ControlAnnouncement> {
+            if let Self::ControlAnnouncement(ref ctrl_ann) = self {
+                Some(ctrl_ann)
+            } else {
+                None
+            }
+        }
+
+        fn destination(&self) -> &'static str {
+            match self {
+                Event::Component(_) => "recording",
+                Event::Other(_) => "other",
+                Event::ControlAnnouncement(_) => "reactor",
+            }
+        }
+    }
+
+    impl Display for Event {
+        fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+            Debug::fmt(self, formatter)
+        }
+    }
+
+    struct TestReactor {
+        component: RecordingComponent,
+        other: RecordingComponent,
+    }
+
+    impl Reactor for TestReactor {
+        type Event = Event;
+        type Config = ();
+        type Error = prometheus::Error;
+
+        fn dispatch_event(
+            &mut self,
+            effect_builder: EffectBuilder<Event>,
+            rng: &mut NodeRng,
+            event: Event,
+        ) -> Effects<Event> {
+            match event {
+                Event::Component(event) => dispatch_to_component(
+                    Event::Component,
+                    &mut self.component,
+                    effect_builder,
+                    rng,
+                    event,
+                ),
+                Event::Other(event) => {
+                    dispatch_to_component(Event::Other, &mut self.other, effect_builder, rng, event)
+                }
+                Event::ControlAnnouncement(ctrl_ann) => {
+                    unreachable!("unhandled control announcement: {}", ctrl_ann)
+                }
+            }
+        }
+
+        fn new(
+            _cfg: Self::Config,
+            _registry: &Registry,
+            event_queue: EventQueueHandle<Event>,
+            _rng: &mut NodeRng,
+        ) -> Result<(Self, Effects<Event>), Self::Error> {
+            let mut component = RecordingComponent::new("recording");
+            let mut other = RecordingComponent::new("other");
+            let mut effects = init_component(
+                Event::Component,
+                &mut component,
+                EffectBuilder::new(event_queue),
+            );
+            effects.extend(init_component(
+                Event::Other,
+                &mut other,
+                EffectBuilder::new(event_queue),
+            ));
+            Ok((TestReactor { component, other }, effects))
+        }
+
+        fn maybe_exit(&self) -> Option<ReactorExit> {
+            None
+        }
+
+        fn shutdown(&mut self, effect_builder: EffectBuilder<Event>) -> Effects<Event> {
+            let mut effects =
+                shutdown_component(Event::Component, &mut self.component, effect_builder);
+            effects.extend(shutdown_component(
+                Event::Other,
+                &mut self.other,
+                effect_builder,
+            ));
+            effects
+        }
+    }
+
+    /// Returns the value of the `reactor_queue_depth` gauge of the given component.
+    fn queue_depth(registry: &Registry, component: &str) -> i64 {
+        registry
+            .gather()
+            .iter()
+            .filter(|family| family.get_name() == "reactor_queue_depth")
+            .flat_map(|family| family.get_metric())
+            .find(|metric| {
+                metric
+                    .get_label()
+                    .iter()
+                    .any(|label| label.get_name() == "component" && label.get_value() == component)
+            })
+            .map_or(0, |metric| metric.get_gauge().get_value() as i64)
+    }
+
+    #[tokio::test]
+    async fn init_effects_should_be_delivered_before_external_events() {
+        let mut rng = crate::new_rng();
+        let mut runner = Runner::<TestReactor>::new((), &mut rng).await.unwrap();
+
+        runner
+            .process_injected_effects(|_| {
+                async {}.event(|()| Event::Component(ComponentEvent::External))
+            })
+            .await;
+        for _ in 0..3 {
+            assert!(runner.crank(&mut rng).await);
+        }
+
+        assert_eq!(
+            runner.reactor().other.handled,
+            vec![ComponentEvent::Initialized]
+        );
+        assert_eq!(
+            runner.reactor().component.handled,
+            vec![ComponentEvent::Initialized, ComponentEvent::External]
+        );
+    }
+
+    #[tokio::test]
+    async fn component_panic_should_shut_down_reactor() {
+        let mut rng = crate::new_rng();
+        let mut runner = Runner::<TestReactor>::new((), &mut rng).await.unwrap();
+
+        runner
+            .process_injected_effects(|_| {
+                async {}.event(|()| Event::Component(ComponentEvent::Panic))
+            })
+            .await;
+        for _ in 0..3 {
+            assert!(runner.crank(&mut rng).await);
+        }
+
+        // The panic has been converted into a fatal error naming the component.
+        let ((event, context), queue) = runner.scheduler.pop().await;
+        match event {
+            Event::ControlAnnouncement(ControlAnnouncement::FatalError { ref msg, .. }) => {
+                assert!(msg.contains("component recording panicked"), "{}", msg);
+                assert!(msg.contains("asked to panic"), "{}", msg);
+            }
+            ref other => panic!("expected a fatal error, got {:?}", other),
+        }
+        runner.scheduler.push((event, context), queue).await;
+
+        assert_eq!(
+            runner.run(&mut rng).await,
+            ReactorExit::ProcessShouldExit(ExitCode::Abort)
+        );
+        assert!(runner.reactor().component.shut_down);
+        assert_eq!(
+            runner.reactor().component.handled,
+            vec![ComponentEvent::Initialized]
+        );
+    }
+
+    #[tokio::test]
+    async fn queue_depth_should_be_tracked_per_component() {
+        const FLOOD_SIZE: usize = 100;
+
+        let mut rng = crate::new_rng();
+        let registry = Registry::new();
+        let mut runner = Runner::<TestReactor>::with_metrics((), &mut rng, &registry)
+            .await
+            .unwrap();
+
+        // Deliver the `Initialized` events of both components.
+        assert!(runner.crank(&mut rng).await);
+        assert!(runner.crank(&mut rng).await);
+        assert_eq!(queue_depth(&registry, "recording"), 0);
+        assert_eq!(queue_depth(&registry, "other"), 0);
+
+        runner
+            .process_injected_effects(|_| {
+                async {}.event(|()| Event::Component(ComponentEvent::Flood(FLOOD_SIZE)))
+            })
+            .await;
+        assert!(runner.crank(&mut rng).await);
+        while runner.scheduler.item_count() < FLOOD_SIZE {
+            tokio::task::yield_now().await;
+        }
+
+        assert_eq!(queue_depth(&registry, "recording"), FLOOD_SIZE as i64);
+        assert_eq!(queue_depth(&registry, "other"), 0);
+
+        for _ in 0..FLOOD_SIZE {
+            assert!(runner.crank(&mut rng).await);
+        }
+        assert_eq!(queue_depth(&registry, "recording"), 0);
+        assert_eq!(queue_depth(&registry, "other"), 0);
+
+        // Events scheduled directly are counted as well.
+        runner
+            .event_queue
+            .schedule(Event::Other(ComponentEvent::External), QueueKind::Regular)
+            .await;
+        assert_eq!(queue_depth(&registry, "other"), 1);
+        assert!(runner.crank(&mut rng).await);
+        assert_eq!(queue_depth(&registry, "other"), 0);
+    }
+}
@@ -0,0 +1,78 @@
+//! Correlation IDs
+//!
+//! A correlation ID ties together all the events caused by a single external request, such as a
+//! deploy submitted by a client, so its journey through the components can be followed in the
+//! logs.
+//!
+//! Every event is queued along with the correlation ID of the effect that produced it, and the
+//! effects resulting from dispatching an event inherit its correlation ID in turn. Components
+//! therefore propagate correlation IDs without further ado, the only places they need to be dealt
+//! with explicitly are those where a new one is started, using [`CorrelationId::correlate`].
+//!
+//! The correlation ID of an event is recorded in the span the event is dispatched in, so it is
+//! included in every log line emitted by the component handling it.
+
+use std::{cell::Cell, future::Future};
+
+use datasize::DataSize;
+use derive_more::Display;
+use futures::FutureExt;
+use rand::Rng;
+use serde::Serialize;
+
+use crate::{effect::Effects, NodeRng};
+
+tokio::task_local! {
+    /// Correlation ID of the effect currently being run.
+    static CURRENT: Cell<Option<CorrelationId>>;
+}
+
+/// Identifier shared by all events caused by the same external request.
+#[derive(Clone, Copy, DataSize, Debug, Display, Eq, PartialEq, Hash, Serialize)]
+#[display(fmt = "{:016x}", _0)]
+pub struct CorrelationId(u64);
+
+impl CorrelationId {
+    /// Creates a new random correlation ID.
+    pub(crate) fn random(rng: &mut NodeRng) -> Self {
+        CorrelationId(rng.gen())
+    }
+
+    /// Returns the correlation ID of the effect currently being run, if any.
+    pub(crate) fn current() -> Option<Self> {
+        CURRENT.try_with(Cell::get).ok().flatten()
+    }
+
+    /// Tags the given effects with this correlation ID, replacing the one inherited from the event
+    /// that caused them.
+    ///
+    /// The ID is passed on to the events produced by the effects and everything that follows
+    /// from them.
+    pub(crate) fn correlate<Ev>(self, effects: Effects<Ev>) -> Effects<Ev>
+    where
+        Ev: Send + 'static,
+    {
+        effects
+            .into_iter()
+            .map(|effect| {
+                async move {
+                    // Effects are always run by `with_correlation_id`, so the task-local is set.
+                    let _ = CURRENT.try_with(|current| current.set(Some(self)));
+                    effect.await
+                }
+                .boxed()
+            })
+            .collect()
+    }
+}
+
+/// Runs `future` with the given correlation ID being the current one.
+pub(crate) async fn with_correlation_id<F>(
+    correlation_id: Option<CorrelationId>,
+    future: F,
+) -> F::Output
+where
+    F: Future,
+{
+    CURRENT.scope(Cell::new(correlation_id), future).await
+}
@@ -23,6 +23,7 @@ use crate::{
     effect::{
         announcements::{
             ChainspecLoaderAnnouncement, ContractRuntimeAnnouncement, ControlAnnouncement,
+            StorageAnnouncement,
         },
         requests::{
             ConsensusRequest, ContractRuntimeRequest, LinearChainRequest, NetworkRequest,
@@ -61,6 +62,10 @@ pub enum Event {
     /// Control announcement
     #[from]
     ControlAnnouncement(ControlAnnouncement),
+
+    /// Storage announcement.
+    #[from]
+    StorageAnnouncement(#[serde(skip_serializing)] StorageAnnouncement),
 }
 
 impl ReactorEvent for Event {
@@ -71,6 +76,15 @@ impl ReactorEvent for Event {
             None
         }
     }
+
+    fn destination(&self) -> &'static str {
+        match self {
+            Event::Chainspec(_) => "chainspec_loader",
+            Event::Storage(_) | Event::StateStoreRequest(_) => "storage",
+            Event::ContractRuntime(_) => "contract_runtime",
+            Event::ControlAnnouncement(_) | Event::StorageAnnouncement(_) => "reactor",
+        }
+    }
 }
 
 impl From<StorageRequest> for Event {
@@ -137,6 +151,7 @@ impl Display for Event {
                 write!(formatter, "state store request: {}", request)
             }
             Event::ControlAnnouncement(ctrl_ann) => write!(formatter, "control: {}", ctrl_ann),
+            Event::StorageAnnouncement(ann) => write!(formatter, "storage announcement: {}", ann),
         }
     }
 }
@@ -196,12 +211,17 @@ impl Reactor {
             hard_reset_to_start_of_era,
             chainspec_loader.chainspec().protocol_config.version,
             crashed,
+            registry,
         )?;
 
         let contract_runtime = ContractRuntime::new(
             chainspec_loader.initial_state_root_hash(),
             chainspec_loader.initial_block_header(),
             chainspec_loader.chainspec().protocol_config.version,
+            chainspec_loader
+                .chainspec()
+                .deploy_config
+                .native_transfer_gas_price,
             storage_config,
             &config.value().contract_runtime,
             registry,
@@ -230,7 +250,8 @@ impl Reactor {
 
         let effects = reactor::wrap_effects(Event::Chainspec, chainspec_effects);
 
-        let small_network_identity = SmallNetworkIdentity::new()?;
+        let small_network_identity =
+            SmallNetworkIdentity::from_config(config.map_ref(|cfg| cfg.network.clone()))?;
 
         let network_identity = NetworkIdentity::new();
 
@@ -268,8 +289,11 @@ impl reactor::Reactor for Reactor {
         let effect_builder = EffectBuilder::new(event_queue);
 
         // Construct the `ChainspecLoader` first so we fail fast if the chainspec is invalid.
-        let (chainspec_loader, chainspec_effects) =
-            ChainspecLoader::new(config.1.dir(), effect_builder)?;
+        let (chainspec_loader, chainspec_effects) = ChainspecLoader::new(
+            config.1.dir(),
+            config.1.value().node.expected_chainspec_hash,
+            effect_builder,
+        )?;
         Self::new_with_chainspec_loader(config, registry, chainspec_loader, chainspec_effects)
     }
 
@@ -280,30 +304,60 @@ impl reactor::Reactor for Reactor {
         event: Event,
     ) -> Effects<Self::Event> {
         match event {
-            Event::Chainspec(event) => reactor::wrap_effects(
+            Event::Chainspec(event) => reactor::dispatch_to_component(
                 Event::Chainspec,
-                self.chainspec_loader
-                    .handle_event(effect_builder, rng, event),
+                &mut self.chainspec_loader,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::Storage(event) => reactor::wrap_effects(
+            Event::Storage(event) => reactor::dispatch_to_component(
                 Event::Storage,
-                self.storage.handle_event(effect_builder, rng, event),
+                &mut self.storage,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::ContractRuntime(event) => reactor::wrap_effects(
+            Event::ContractRuntime(event) => reactor::dispatch_to_component(
                 Event::ContractRuntime,
-                self.contract_runtime
-                    .handle_event(effect_builder, rng, event),
+                &mut self.contract_runtime,
+                effect_builder,
+                rng,
+                event,
             ),
             Event::StateStoreRequest(request) => {
                 self.dispatch_event(effect_builder, rng, Event::Storage(request.into()))
             }
             Event::ControlAnnouncement(_) => unreachable!("unhandled control announcement"),
+            // Nothing is fetched during initialization, the corrupt entry is reported as missing by
+            // storage until the joiner fetches it again.
+            Event::StorageAnnouncement(_) => Effects::new(),
         }
     }
 
     fn maybe_exit(&self) -> Option<ReactorExit> {
         self.chainspec_loader.reactor_exit()
     }
+
+    fn shutdown(&mut self, effect_builder: EffectBuilder<Self::Event>) -> Effects<Self::Event> {
+        let mut effects = Effects::new();
+        effects.extend(reactor::shutdown_component(
+            Event::Chainspec,
+            &mut self.chainspec_loader,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::ContractRuntime,
+            &mut self.contract_runtime,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::Storage,
+            &mut self.storage,
+            effect_builder,
+        ));
+        effects
+    }
 }
 
 #[cfg(test)]
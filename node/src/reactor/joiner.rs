@@ -17,6 +17,9 @@ use reactor::ReactorEvent;
 use serde::Serialize;
 use tracing::{debug, error, info, warn};
 
+use casper_execution_engine::{shared::stored_value::StoredValue, storage::trie::Trie};
+use casper_types::Key;
+
 #[cfg(test)]
 use crate::testing::network::NetworkedReactor;
 use crate::{
@@ -31,7 +34,7 @@ use crate::{
         gossiper::{self, Gossiper},
         linear_chain,
         linear_chain_sync::{self, LinearChainSync},
-        metrics::Metrics,
+        metrics::{self, Metrics},
         network::{self, Network, NetworkIdentity, ENABLE_LIBP2P_NET_ENV_VAR},
         rest_server::{self, RestServer},
         small_network::{self, GossipedAddress, SmallNetwork, SmallNetworkIdentity},
@@ -40,9 +43,9 @@ use crate::{
     },
     effect::{
         announcements::{
-            ChainspecLoaderAnnouncement, ContractRuntimeAnnouncement, ControlAnnouncement,
-            DeployAcceptorAnnouncement, GossiperAnnouncement, LinearChainAnnouncement,
-            LinearChainBlock, NetworkAnnouncement,
+            BlocklistAnnouncement, ChainspecLoaderAnnouncement, ContractRuntimeAnnouncement,
+            ControlAnnouncement, DeployAcceptorAnnouncement, GossiperAnnouncement,
+            LinearChainAnnouncement, LinearChainBlock, NetworkAnnouncement, StorageAnnouncement,
         },
         requests::{
             BlockProposerRequest, BlockValidationRequest, ChainspecLoaderRequest, ConsensusRequest,
@@ -92,6 +95,10 @@ pub enum Event {
     /// Event stream server event.
     EventStreamServer(#[serde(skip_serializing)] event_stream_server::Event),
 
+    /// Metrics event.
+    #[from]
+    Metrics(#[serde(skip_serializing)] metrics::Event),
+
     /// Metrics request.
     #[from]
     MetricsRequest(#[serde(skip_serializing)] MetricsRequest),
@@ -120,6 +127,14 @@ pub enum Event {
     #[from]
     DeployFetcher(#[serde(skip_serializing)] fetcher::Event<Deploy>),
 
+    /// Block header (by hash) fetcher event.
+    #[from]
+    BlockHeaderFetcher(#[serde(skip_serializing)] fetcher::Event<BlockHeader>),
+
+    /// Global state trie fetcher event.
+    #[from]
+    TrieFetcher(#[serde(skip_serializing)] fetcher::Event<Trie<Key, StoredValue>>),
+
     /// Deploy acceptor event.
     #[from]
     DeployAcceptor(#[serde(skip_serializing)] deploy_acceptor::Event),
@@ -157,6 +172,14 @@ pub enum Event {
     #[from]
     DeployFetcherRequest(#[serde(skip_serializing)] FetcherRequest<NodeId, Deploy>),
 
+    /// Block header (by hash) fetcher request.
+    #[from]
+    BlockHeaderFetcherRequest(#[serde(skip_serializing)] FetcherRequest<NodeId, BlockHeader>),
+
+    /// Global state trie fetcher request.
+    #[from]
+    TrieFetcherRequest(#[serde(skip_serializing)] FetcherRequest<NodeId, Trie<Key, StoredValue>>),
+
     /// Block validation request.
     #[from]
     BlockValidatorRequest(#[serde(skip_serializing)] BlockValidationRequest<NodeId>),
@@ -198,6 +221,10 @@ pub enum Event {
     #[from]
     ChainspecLoaderAnnouncement(#[serde(skip_serializing)] ChainspecLoaderAnnouncement),
 
+    /// Storage announcement.
+    #[from]
+    StorageAnnouncement(#[serde(skip_serializing)] StorageAnnouncement),
+
     /// Consensus request.
     #[from]
     ConsensusRequest(#[serde(skip_serializing)] ConsensusRequest),
@@ -211,6 +238,43 @@ impl ReactorEvent for Event {
             None
         }
     }
+
+    fn destination(&self) -> &'static str {
+        match self {
+            Event::Network(_) => "network",
+            Event::SmallNetwork(_) | Event::NetworkInfoRequest(_) => "small_network",
+            Event::Storage(_) | Event::StateStoreRequest(_) => "storage",
+            Event::RestServer(_) => "rest_server",
+            Event::EventStreamServer(_) => "event_stream_server",
+            Event::Metrics(_) | Event::MetricsRequest(_) => "metrics",
+            Event::ChainspecLoader(_) | Event::ChainspecLoaderRequest(_) => "chainspec_loader",
+            Event::BlockFetcher(_) | Event::BlockFetcherRequest(_) => "linear_chain_fetcher",
+            Event::BlockByHeightFetcher(_) | Event::BlockByHeightFetcherRequest(_) => {
+                "block_by_height_fetcher"
+            }
+            Event::DeployFetcher(_) | Event::DeployFetcherRequest(_) => "deploy_fetcher",
+            Event::BlockHeaderFetcher(_) | Event::BlockHeaderFetcherRequest(_) => {
+                "block_header_by_hash_fetcher"
+            }
+            Event::TrieFetcher(_) | Event::TrieFetcherRequest(_) => "trie_fetcher",
+            Event::DeployAcceptor(_) => "deploy_acceptor",
+            Event::BlockValidator(_) | Event::BlockValidatorRequest(_) => "block_validator",
+            Event::LinearChainSync(_) => "linear_chain_sync",
+            Event::ContractRuntime(_) => "contract_runtime",
+            Event::LinearChain(_) => "linear_chain",
+            Event::AddressGossiper(_) => "address_gossiper",
+            Event::BlockProposerRequest(_)
+            | Event::ConsensusRequest(_)
+            | Event::ControlAnnouncement(_)
+            | Event::NetworkAnnouncement(_)
+            | Event::ContractRuntimeAnnouncement(_)
+            | Event::AddressGossiperAnnouncement(_)
+            | Event::DeployAcceptorAnnouncement(_)
+            | Event::LinearChainAnnouncement(_)
+            | Event::ChainspecLoaderAnnouncement(_)
+            | Event::StorageAnnouncement(_) => "reactor",
+        }
+    }
 }
 
 impl From<LinearChainRequest<NodeId>> for Event {
@@ -225,6 +289,12 @@ impl From<StorageRequest> for Event {
     }
 }
 
+impl From<BlocklistAnnouncement<NodeId>> for Event {
+    fn from(announcement: BlocklistAnnouncement<NodeId>) -> Self {
+        Event::SmallNetwork(announcement.into())
+    }
+}
+
 impl From<NetworkRequest<NodeId, Message>> for Event {
     fn from(request: NetworkRequest<NodeId, Message>) -> Self {
         if env::var(ENABLE_LIBP2P_NET_ENV_VAR).is_ok() {
@@ -264,6 +334,7 @@ impl Display for Event {
             Event::Storage(request) => write!(f, "storage: {}", request),
             Event::RestServer(event) => write!(f, "rest server: {}", event),
             Event::EventStreamServer(event) => write!(f, "event stream server: {}", event),
+            Event::Metrics(event) => write!(f, "metrics event: {}", event),
             Event::MetricsRequest(req) => write!(f, "metrics request: {}", req),
             Event::ChainspecLoader(event) => write!(f, "chainspec loader: {}", event),
             Event::ChainspecLoaderRequest(req) => write!(f, "chainspec loader request: {}", req),
@@ -295,6 +366,12 @@ impl Display for Event {
             Event::BlockByHeightFetcher(event) => {
                 write!(f, "block by height fetcher event: {}", event)
             }
+            Event::BlockHeaderFetcherRequest(request) => {
+                write!(f, "block header fetcher request: {}", request)
+            }
+            Event::BlockHeaderFetcher(event) => write!(f, "block header fetcher event: {}", event),
+            Event::TrieFetcherRequest(request) => write!(f, "trie fetcher request: {}", request),
+            Event::TrieFetcher(event) => write!(f, "trie fetcher event: {}", event),
             Event::DeployAcceptorAnnouncement(ann) => {
                 write!(f, "deploy acceptor announcement: {}", ann)
             }
@@ -304,6 +381,7 @@ impl Display for Event {
             Event::ChainspecLoaderAnnouncement(ann) => {
                 write!(f, "chainspec loader announcement: {}", ann)
             }
+            Event::StorageAnnouncement(ann) => write!(f, "storage announcement: {}", ann),
             Event::StateStoreRequest(req) => write!(f, "state store request: {}", req),
             Event::ConsensusRequest(req) => write!(f, "consensus request: {:?}", req),
         }
@@ -331,6 +409,7 @@ pub struct Reactor {
     block_by_height_fetcher: Fetcher<BlockByHeight>,
     pub(super) block_header_by_hash_fetcher: Fetcher<BlockHeader>,
     pub(super) block_header_with_metadata_fetcher: Fetcher<BlockHeaderWithMetadata>,
+    trie_fetcher: Fetcher<Trie<Key, StoredValue>>,
     #[data_size(skip)]
     deploy_acceptor: DeployAcceptor,
     #[data_size(skip)]
@@ -376,7 +455,7 @@ impl reactor::Reactor for Reactor {
 
         let event_queue_metrics = EventQueueMetrics::new(registry.clone(), event_queue)?;
 
-        let metrics = Metrics::new(registry.clone());
+        let metrics = Metrics::new(registry.clone())?;
 
         let network_config = network::Config::from(&config.network);
         let (network, network_effects) = Network::new(
@@ -386,7 +465,7 @@ impl reactor::Reactor for Reactor {
             network_identity,
             chainspec_loader.chainspec(),
         )?;
-        let (small_network, small_network_effects) = SmallNetwork::new(
+        let small_network = SmallNetwork::new(
             event_queue,
             config.network.clone(),
             Some(WithDir::new(&root, &config.consensus)),
@@ -399,10 +478,6 @@ impl reactor::Reactor for Reactor {
         let linear_chain_fetcher = Fetcher::new("linear_chain", config.fetcher, registry)?;
 
         let mut effects = reactor::wrap_effects(Event::Network, network_effects);
-        effects.extend(reactor::wrap_effects(
-            Event::SmallNetwork,
-            small_network_effects,
-        ));
 
         let address_gossiper =
             Gossiper::new_for_complete_items("address_gossiper", config.gossip, registry)?;
@@ -441,6 +516,7 @@ impl reactor::Reactor for Reactor {
             config.rest_server.clone(),
             effect_builder,
             *protocol_version,
+            true,
         )?;
 
         let event_stream_server = EventStreamServer::new(
@@ -450,12 +526,19 @@ impl reactor::Reactor for Reactor {
             DeployGetter::new(effect_builder),
         )?;
 
-        let block_validator = BlockValidator::new(Arc::clone(chainspec_loader.chainspec()));
+        let next_block_height = chainspec_loader
+            .initial_block_header()
+            .map(|block_header| block_header.height() + 1)
+            .unwrap_or(0);
+        let block_validator =
+            BlockValidator::new(Arc::clone(chainspec_loader.chainspec()), next_block_height);
 
         let deploy_fetcher = Fetcher::new("deploy", config.fetcher, registry)?;
 
         let block_by_height_fetcher = Fetcher::new("block_by_height", config.fetcher, registry)?;
 
+        let trie_fetcher = Fetcher::new("trie", config.fetcher, registry)?;
+
         let block_header_and_finality_signatures_by_height_fetcher: Fetcher<
             BlockHeaderWithMetadata,
         > = Fetcher::new(
@@ -467,8 +550,11 @@ impl reactor::Reactor for Reactor {
         let block_header_by_hash_fetcher: Fetcher<BlockHeader> =
             Fetcher::new("block_header_by_hash", config.fetcher, registry)?;
 
-        let deploy_acceptor =
-            DeployAcceptor::new(config.deploy_acceptor, &*chainspec_loader.chainspec());
+        let deploy_acceptor = DeployAcceptor::new(
+            config.deploy_acceptor,
+            Arc::clone(chainspec_loader.chainspec()),
+            next_block_height,
+        );
 
         contract_runtime.set_initial_state(
             chainspec_loader.initial_state_root_hash(),
@@ -506,34 +592,35 @@ impl reactor::Reactor for Reactor {
             chainspec_loader.start_checking_for_upgrades(effect_builder),
         ));
 
-        Ok((
-            Self {
-                root,
-                metrics,
-                network,
-                small_network,
-                address_gossiper,
-                config,
-                chainspec_loader,
-                storage,
-                contract_runtime,
-                linear_chain_sync,
-                linear_chain_fetcher,
-                block_validator,
-                deploy_fetcher,
-                linear_chain,
-                block_by_height_fetcher,
-                block_header_by_hash_fetcher,
-                block_header_with_metadata_fetcher:
-                    block_header_and_finality_signatures_by_height_fetcher,
-                deploy_acceptor,
-                event_queue_metrics,
-                rest_server,
-                event_stream_server,
-                memory_metrics,
-            },
-            effects,
-        ))
+        let mut reactor = Self {
+            root,
+            metrics,
+            network,
+            small_network,
+            address_gossiper,
+            config,
+            chainspec_loader,
+            storage,
+            contract_runtime,
+            linear_chain_sync,
+            linear_chain_fetcher,
+            block_validator,
+            deploy_fetcher,
+            linear_chain,
+            block_by_height_fetcher,
+            block_header_by_hash_fetcher,
+            block_header_with_metadata_fetcher:
+                block_header_and_finality_signatures_by_height_fetcher,
+            trie_fetcher,
+            deploy_acceptor,
+            event_queue_metrics,
+            rest_server,
+            event_stream_server,
+            memory_metrics,
+        };
+        effects.extend(reactor.init_components(effect_builder));
+
+        Ok((reactor, effects))
     }
 
     fn dispatch_event(
@@ -543,13 +630,19 @@ impl reactor::Reactor for Reactor {
         event: Self::Event,
     ) -> Effects<Self::Event> {
         match event {
-            Event::Network(event) => reactor::wrap_effects(
+            Event::Network(event) => reactor::dispatch_to_component(
                 Event::Network,
-                self.network.handle_event(effect_builder, rng, event),
+                &mut self.network,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::SmallNetwork(event) => reactor::wrap_effects(
+            Event::SmallNetwork(event) => reactor::dispatch_to_component(
                 Event::SmallNetwork,
-                self.small_network.handle_event(effect_builder, rng, event),
+                &mut self.small_network,
+                effect_builder,
+                rng,
+                event,
             ),
             Event::ControlAnnouncement(ctrl_ann) => {
                 unreachable!("unhandled control announcement: {}", ctrl_ann)
@@ -615,6 +708,44 @@ impl reactor::Reactor for Reactor {
                     };
                     self.dispatch_event(effect_builder, rng, Event::BlockByHeightFetcher(event))
                 }
+                Message::GetResponse {
+                    tag: Tag::BlockHeaderByHash,
+                    serialized_item,
+                } => {
+                    let block_header: Box<BlockHeader> =
+                        match bincode::deserialize(&serialized_item) {
+                            Ok(block_header) => Box::new(block_header),
+                            Err(err) => {
+                                error!("failed to decode block header from {}: {}", sender, err);
+                                return Effects::new();
+                            }
+                        };
+                    // The fetcher stores the header once it has verified it was requested.
+                    let event = fetcher::Event::GotRemotely {
+                        item: block_header,
+                        source: Source::Peer(sender),
+                    };
+                    self.dispatch_event(effect_builder, rng, Event::BlockHeaderFetcher(event))
+                }
+                Message::GetResponse {
+                    tag: Tag::Trie,
+                    serialized_item,
+                } => {
+                    let trie = match bincode::deserialize(&serialized_item) {
+                        Ok(trie) => Box::new(trie),
+                        Err(err) => {
+                            error!("failed to decode trie from {}: {}", sender, err);
+                            return Effects::new();
+                        }
+                    };
+                    // The fetcher only hands the trie to whoever requested it under its own hash,
+                    // so a peer sending a different trie does not satisfy the request.
+                    let event = fetcher::Event::GotRemotely {
+                        item: trie,
+                        source: Source::Peer(sender),
+                    };
+                    self.dispatch_event(effect_builder, rng, Event::TrieFetcher(event))
+                }
                 Message::GetResponse {
                     tag: Tag::Deploy,
                     serialized_item,
@@ -678,9 +809,12 @@ impl reactor::Reactor for Reactor {
                 warn!(?deploy_hash, ?peer, "Invalid deploy received from a peer.");
                 Effects::new()
             }
-            Event::Storage(event) => reactor::wrap_effects(
+            Event::Storage(event) => reactor::dispatch_to_component(
                 Event::Storage,
-                self.storage.handle_event(effect_builder, rng, event),
+                &mut self.storage,
+                effect_builder,
+                rng,
+                event,
             ),
             Event::BlockFetcherRequest(request) => {
                 self.dispatch_event(effect_builder, rng, Event::BlockFetcher(request.into()))
@@ -688,34 +822,47 @@ impl reactor::Reactor for Reactor {
             Event::BlockValidatorRequest(request) => {
                 self.dispatch_event(effect_builder, rng, Event::BlockValidator(request.into()))
             }
-            Event::DeployAcceptor(event) => reactor::wrap_effects(
+            Event::DeployAcceptor(event) => reactor::dispatch_to_component(
                 Event::DeployAcceptor,
-                self.deploy_acceptor
-                    .handle_event(effect_builder, rng, event),
+                &mut self.deploy_acceptor,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::LinearChainSync(event) => reactor::wrap_effects(
+            Event::LinearChainSync(event) => reactor::dispatch_to_component(
                 Event::LinearChainSync,
-                self.linear_chain_sync
-                    .handle_event(effect_builder, rng, event),
+                &mut self.linear_chain_sync,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::BlockFetcher(event) => reactor::wrap_effects(
+            Event::BlockFetcher(event) => reactor::dispatch_to_component(
                 Event::BlockFetcher,
-                self.linear_chain_fetcher
-                    .handle_event(effect_builder, rng, event),
+                &mut self.linear_chain_fetcher,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::BlockValidator(event) => reactor::wrap_effects(
+            Event::BlockValidator(event) => reactor::dispatch_to_component(
                 Event::BlockValidator,
-                self.block_validator
-                    .handle_event(effect_builder, rng, event),
+                &mut self.block_validator,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::DeployFetcher(event) => reactor::wrap_effects(
+            Event::DeployFetcher(event) => reactor::dispatch_to_component(
                 Event::DeployFetcher,
-                self.deploy_fetcher.handle_event(effect_builder, rng, event),
+                &mut self.deploy_fetcher,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::BlockByHeightFetcher(event) => reactor::wrap_effects(
+            Event::BlockByHeightFetcher(event) => reactor::dispatch_to_component(
                 Event::BlockByHeightFetcher,
-                self.block_by_height_fetcher
-                    .handle_event(effect_builder, rng, event),
+                &mut self.block_by_height_fetcher,
+                effect_builder,
+                rng,
+                event,
             ),
             Event::DeployFetcherRequest(request) => {
                 self.dispatch_event(effect_builder, rng, Event::DeployFetcher(request.into()))
@@ -725,10 +872,34 @@ impl reactor::Reactor for Reactor {
                 rng,
                 Event::BlockByHeightFetcher(request.into()),
             ),
-            Event::ContractRuntime(event) => reactor::wrap_effects(
+            Event::BlockHeaderFetcher(event) => reactor::dispatch_to_component(
+                Event::BlockHeaderFetcher,
+                &mut self.block_header_by_hash_fetcher,
+                effect_builder,
+                rng,
+                event,
+            ),
+            Event::BlockHeaderFetcherRequest(request) => self.dispatch_event(
+                effect_builder,
+                rng,
+                Event::BlockHeaderFetcher(request.into()),
+            ),
+            Event::TrieFetcher(event) => reactor::dispatch_to_component(
+                Event::TrieFetcher,
+                &mut self.trie_fetcher,
+                effect_builder,
+                rng,
+                event,
+            ),
+            Event::TrieFetcherRequest(request) => {
+                self.dispatch_event(effect_builder, rng, Event::TrieFetcher(request.into()))
+            }
+            Event::ContractRuntime(event) => reactor::dispatch_to_component(
                 Event::ContractRuntime,
-                self.contract_runtime
-                    .handle_event(effect_builder, rng, event),
+                &mut self.contract_runtime,
+                effect_builder,
+                rng,
+                event,
             ),
             Event::ContractRuntimeAnnouncement(ContractRuntimeAnnouncement::LinearChainBlock(
                 linear_chain_block,
@@ -782,9 +953,12 @@ impl reactor::Reactor for Reactor {
                     effect: execution_effect,
                 }),
             ),
-            Event::LinearChain(event) => reactor::wrap_effects(
+            Event::LinearChain(event) => reactor::dispatch_to_component(
                 Event::LinearChain,
-                self.linear_chain.handle_event(effect_builder, rng, event),
+                &mut self.linear_chain,
+                effect_builder,
+                rng,
+                event,
             ),
             Event::BlockProposerRequest(request) => {
                 // Consensus component should not be trying to create new blocks during joining
@@ -792,10 +966,12 @@ impl reactor::Reactor for Reactor {
                 error!("ignoring block proposer request {}", request);
                 Effects::new()
             }
-            Event::AddressGossiper(event) => reactor::wrap_effects(
+            Event::AddressGossiper(event) => reactor::dispatch_to_component(
                 Event::AddressGossiper,
-                self.address_gossiper
-                    .handle_event(effect_builder, rng, event),
+                &mut self.address_gossiper,
+                effect_builder,
+                rng,
+                event,
             ),
             Event::AddressGossiperAnnouncement(GossiperAnnouncement::NewCompleteItem(
                 gossiped_address,
@@ -809,8 +985,16 @@ impl reactor::Reactor for Reactor {
                 // We don't care about completion of gossiping an address.
                 Effects::new()
             }
+            Event::AddressGossiperAnnouncement(GossiperAnnouncement::ItemReassembled {
+                item,
+                sender,
+            }) => {
+                error!(%item, %sender, "gossiped addresses should never be sent in chunks");
+                Effects::new()
+            }
 
             Event::LinearChainAnnouncement(LinearChainAnnouncement::BlockAdded(block)) => {
+                let height = block.height();
                 let mut effects = reactor::wrap_effects(
                     Event::EventStreamServer,
                     self.event_stream_server.handle_event(
@@ -822,6 +1006,16 @@ impl reactor::Reactor for Reactor {
                 let reactor_event =
                     Event::LinearChainSync(linear_chain_sync::Event::BlockHandled(block.clone()));
                 effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
+                effects.extend(self.dispatch_event(
+                    effect_builder,
+                    rng,
+                    Event::BlockValidator(block_validator::Event::BlockAdded { height }),
+                ));
+                effects.extend(self.dispatch_event(
+                    effect_builder,
+                    rng,
+                    Event::DeployAcceptor(deploy_acceptor::Event::BlockAdded { height }),
+                ));
                 effects.extend(self.dispatch_event(
                     effect_builder,
                     rng,
@@ -834,23 +1028,36 @@ impl reactor::Reactor for Reactor {
                     Event::EventStreamServer(event_stream_server::Event::FinalitySignature(fs));
                 self.dispatch_event(effect_builder, rng, reactor_event)
             }
-            Event::RestServer(event) => reactor::wrap_effects(
+            Event::RestServer(event) => reactor::dispatch_to_component(
                 Event::RestServer,
-                self.rest_server.handle_event(effect_builder, rng, event),
+                &mut self.rest_server,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::EventStreamServer(event) => reactor::wrap_effects(
+            Event::EventStreamServer(event) => reactor::dispatch_to_component(
                 Event::EventStreamServer,
-                self.event_stream_server
-                    .handle_event(effect_builder, rng, event),
+                &mut self.event_stream_server,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::MetricsRequest(req) => reactor::wrap_effects(
-                Event::MetricsRequest,
-                self.metrics.handle_event(effect_builder, rng, req),
+            Event::Metrics(event) => reactor::dispatch_to_component(
+                Event::Metrics,
+                &mut self.metrics,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::ChainspecLoader(event) => reactor::wrap_effects(
+            Event::MetricsRequest(req) => {
+                self.dispatch_event(effect_builder, rng, Event::Metrics(req.into()))
+            }
+            Event::ChainspecLoader(event) => reactor::dispatch_to_component(
                 Event::ChainspecLoader,
-                self.chainspec_loader
-                    .handle_event(effect_builder, rng, event),
+                &mut self.chainspec_loader,
+                effect_builder,
+                rng,
+                event,
             ),
             Event::ChainspecLoaderRequest(req) => {
                 self.dispatch_event(effect_builder, rng, Event::ChainspecLoader(req.into()))
@@ -881,6 +1088,9 @@ impl reactor::Reactor for Reactor {
                 effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
                 effects
             }
+            // The corrupt entry is reported as missing by storage, so it is fetched from a peer
+            // again by whichever component needs it next.
+            Event::StorageAnnouncement(_) => Effects::new(),
             // This is done to handle status requests from the RestServer
             Event::ConsensusRequest(ConsensusRequest::Status(responder)) => {
                 // no consensus, respond with None
@@ -904,6 +1114,101 @@ impl reactor::Reactor for Reactor {
         self.event_queue_metrics
             .record_event_queue_counts(&event_queue_handle);
     }
+
+    fn shutdown(&mut self, effect_builder: EffectBuilder<Self::Event>) -> Effects<Self::Event> {
+        let mut effects = Effects::new();
+        effects.extend(reactor::shutdown_component(
+            Event::SmallNetwork,
+            &mut self.small_network,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::Network,
+            &mut self.network,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::AddressGossiper,
+            &mut self.address_gossiper,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::RestServer,
+            &mut self.rest_server,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::EventStreamServer,
+            &mut self.event_stream_server,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::DeployAcceptor,
+            &mut self.deploy_acceptor,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::DeployFetcher,
+            &mut self.deploy_fetcher,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::BlockFetcher,
+            &mut self.linear_chain_fetcher,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::BlockByHeightFetcher,
+            &mut self.block_by_height_fetcher,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::BlockHeaderFetcher,
+            &mut self.block_header_by_hash_fetcher,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::TrieFetcher,
+            &mut self.trie_fetcher,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::LinearChainSync,
+            &mut self.linear_chain_sync,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::BlockValidator,
+            &mut self.block_validator,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::LinearChain,
+            &mut self.linear_chain,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::ChainspecLoader,
+            &mut self.chainspec_loader,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::ContractRuntime,
+            &mut self.contract_runtime,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::Metrics,
+            &mut self.metrics,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::Storage,
+            &mut self.storage,
+            effect_builder,
+        ));
+        effects
+    }
 }
 
 impl Reactor {
@@ -934,6 +1239,102 @@ impl Reactor {
         self.rest_server.finalize().await;
         Ok(config)
     }
+
+    /// Initializes all components, returning the resulting effects.
+    fn init_components(&mut self, effect_builder: EffectBuilder<Event>) -> Effects<Event> {
+        let mut effects = Effects::new();
+        effects.extend(reactor::init_component(
+            Event::SmallNetwork,
+            &mut self.small_network,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::Network,
+            &mut self.network,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::AddressGossiper,
+            &mut self.address_gossiper,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::RestServer,
+            &mut self.rest_server,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::EventStreamServer,
+            &mut self.event_stream_server,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::DeployAcceptor,
+            &mut self.deploy_acceptor,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::DeployFetcher,
+            &mut self.deploy_fetcher,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::BlockFetcher,
+            &mut self.linear_chain_fetcher,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::BlockByHeightFetcher,
+            &mut self.block_by_height_fetcher,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::BlockHeaderFetcher,
+            &mut self.block_header_by_hash_fetcher,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::TrieFetcher,
+            &mut self.trie_fetcher,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::LinearChainSync,
+            &mut self.linear_chain_sync,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::BlockValidator,
+            &mut self.block_validator,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::LinearChain,
+            &mut self.linear_chain,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::ChainspecLoader,
+            &mut self.chainspec_loader,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::ContractRuntime,
+            &mut self.contract_runtime,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::Metrics,
+            &mut self.metrics,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::Storage,
+            &mut self.storage,
+            effect_builder,
+        ));
+        effects
+    }
 }
 
 #[cfg(test)]
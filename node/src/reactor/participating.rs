@@ -13,6 +13,7 @@ use std::{
     fmt::{self, Debug, Display, Formatter},
     path::PathBuf,
     sync::Arc,
+    time::Instant,
 };
 
 use datasize::DataSize;
@@ -22,6 +23,8 @@ use reactor::ReactorEvent;
 use serde::Serialize;
 use tracing::{debug, error, trace, warn};
 
+use casper_execution_engine::shared::newtypes::Blake2bHash;
+
 #[cfg(test)]
 use crate::testing::network::NetworkedReactor;
 
@@ -37,7 +40,7 @@ use crate::{
         fetcher::{self, Fetcher},
         gossiper::{self, Gossiper},
         linear_chain,
-        metrics::Metrics,
+        metrics::{self, Metrics},
         network::{self, Network, NetworkIdentity, ENABLE_LIBP2P_NET_ENV_VAR},
         rest_server::{self, RestServer},
         rpc_server::{self, RpcServer},
@@ -50,7 +53,7 @@ use crate::{
             BlocklistAnnouncement, ChainspecLoaderAnnouncement, ConsensusAnnouncement,
             ContractRuntimeAnnouncement, ControlAnnouncement, DeployAcceptorAnnouncement,
             GossiperAnnouncement, LinearChainAnnouncement, LinearChainBlock, NetworkAnnouncement,
-            RpcServerAnnouncement,
+            RpcServerAnnouncement, StorageAnnouncement,
         },
         requests::{
             BlockProposerRequest, BlockValidationRequest, ChainspecLoaderRequest, ConsensusRequest,
@@ -63,7 +66,7 @@ use crate::{
     protocol::Message,
     reactor::{self, event_queue_metrics::EventQueueMetrics, EventQueueHandle, ReactorExit},
     types::{BlockHash, BlockHeader, Deploy, ExitCode, NodeId, Tag},
-    utils::{Source, WithDir},
+    utils::{ClientRateLimiter, Source, WithDir},
     NodeRng,
 };
 pub use config::Config;
@@ -140,6 +143,9 @@ pub enum Event {
     /// Block validator request.
     #[from]
     BlockValidatorRequest(#[serde(skip_serializing)] BlockValidationRequest<NodeId>),
+    /// Metrics event.
+    #[from]
+    Metrics(#[serde(skip_serializing)] metrics::Event),
     /// Metrics request.
     #[from]
     MetricsRequest(#[serde(skip_serializing)] MetricsRequest),
@@ -187,6 +193,9 @@ pub enum Event {
     /// Blocklist announcement.
     #[from]
     BlocklistAnnouncement(BlocklistAnnouncement<NodeId>),
+    /// Storage announcement.
+    #[from]
+    StorageAnnouncement(#[serde(skip_serializing)] StorageAnnouncement),
 }
 
 impl ReactorEvent for Event {
@@ -197,6 +206,42 @@ impl ReactorEvent for Event {
             None
         }
     }
+
+    fn destination(&self) -> &'static str {
+        match self {
+            Event::Network(_) => "network",
+            Event::SmallNetwork(_) | Event::NetworkRequest(_) | Event::NetworkInfoRequest(_) => {
+                "small_network"
+            }
+            Event::BlockProposer(_) | Event::BlockProposerRequest(_) => "block_proposer",
+            Event::Storage(_) | Event::StorageRequest(_) | Event::StateStoreRequest(_) => "storage",
+            Event::RpcServer(_) => "rpc_server",
+            Event::RestServer(_) => "rest_server",
+            Event::EventStreamServer(_) => "event_stream_server",
+            Event::ChainspecLoader(_) | Event::ChainspecLoaderRequest(_) => "chainspec_loader",
+            Event::Consensus(_) => "consensus",
+            Event::DeployAcceptor(_) => "deploy_acceptor",
+            Event::DeployFetcher(_) | Event::DeployFetcherRequest(_) => "deploy_fetcher",
+            Event::DeployGossiper(_) => "deploy_gossiper",
+            Event::AddressGossiper(_) => "address_gossiper",
+            Event::ContractRuntime(_) => "contract_runtime",
+            Event::BlockValidator(_) | Event::BlockValidatorRequest(_) => "block_validator",
+            Event::LinearChain(_) => "linear_chain",
+            Event::Metrics(_) | Event::MetricsRequest(_) => "metrics",
+            Event::ControlAnnouncement(_)
+            | Event::NetworkAnnouncement(_)
+            | Event::RpcServerAnnouncement(_)
+            | Event::DeployAcceptorAnnouncement(_)
+            | Event::ConsensusAnnouncement(_)
+            | Event::ContractRuntimeAnnouncement(_)
+            | Event::DeployGossiperAnnouncement(_)
+            | Event::AddressGossiperAnnouncement(_)
+            | Event::LinearChainAnnouncement(_)
+            | Event::ChainspecLoaderAnnouncement(_)
+            | Event::BlocklistAnnouncement(_)
+            | Event::StorageAnnouncement(_) => "reactor",
+        }
+    }
 }
 
 impl From<RpcRequest<NodeId>> for Event {
@@ -276,6 +321,7 @@ impl Display for Event {
             Event::BlockValidatorRequest(req) => {
                 write!(f, "block validator request: {}", req)
             }
+            Event::Metrics(event) => write!(f, "metrics event: {}", event),
             Event::MetricsRequest(req) => write!(f, "metrics request: {}", req),
             Event::ControlAnnouncement(ctrl_ann) => write!(f, "control: {}", ctrl_ann),
             Event::NetworkAnnouncement(ann) => write!(f, "network announcement: {}", ann),
@@ -300,6 +346,7 @@ impl Display for Event {
             Event::BlocklistAnnouncement(ann) => {
                 write!(f, "blocklist announcement: {}", ann)
             }
+            Event::StorageAnnouncement(ann) => write!(f, "storage announcement: {}", ann),
         }
     }
 }
@@ -354,6 +401,10 @@ pub struct Reactor {
     linear_chain: LinearChainComponent<NodeId>,
 
     // Non-components.
+    /// Limits the rate at which global state trie nodes are served to each peer.
+    #[data_size(skip)]
+    trie_request_limiter: ClientRateLimiter<NodeId>,
+
     #[data_size(skip)] // Never allocates heap data.
     memory_metrics: MemoryMetrics,
 
@@ -374,6 +425,99 @@ impl Reactor {
     }
 }
 
+impl Reactor {
+    /// Initializes all components, returning the resulting effects.
+    fn init_components(&mut self, effect_builder: EffectBuilder<Event>) -> Effects<Event> {
+        let mut effects = Effects::new();
+        effects.extend(reactor::init_component(
+            Event::SmallNetwork,
+            &mut self.small_network,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::Network,
+            &mut self.network,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::AddressGossiper,
+            &mut self.address_gossiper,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::DeployGossiper,
+            &mut self.deploy_gossiper,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::RpcServer,
+            &mut self.rpc_server,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::RestServer,
+            &mut self.rest_server,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::EventStreamServer,
+            &mut self.event_stream_server,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::DeployAcceptor,
+            &mut self.deploy_acceptor,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::DeployFetcher,
+            &mut self.deploy_fetcher,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::BlockProposer,
+            &mut self.block_proposer,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::Consensus,
+            &mut self.consensus,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::BlockValidator,
+            &mut self.block_validator,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::LinearChain,
+            &mut self.linear_chain,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::ChainspecLoader,
+            &mut self.chainspec_loader,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::ContractRuntime,
+            &mut self.contract_runtime,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::Metrics,
+            &mut self.metrics,
+            effect_builder,
+        ));
+        effects.extend(reactor::init_component(
+            Event::Storage,
+            &mut self.storage,
+            effect_builder,
+        ));
+        effects
+    }
+}
+
 impl reactor::Reactor for Reactor {
     type Event = Event;
 
@@ -404,7 +548,7 @@ impl reactor::Reactor for Reactor {
 
         let event_queue_metrics = EventQueueMetrics::new(registry.clone(), event_queue)?;
 
-        let metrics = Metrics::new(registry.clone());
+        let metrics = Metrics::new(registry.clone())?;
 
         let effect_builder = EffectBuilder::new(event_queue);
         let network_config = network::Config::from(&config.network);
@@ -420,16 +564,33 @@ impl reactor::Reactor for Reactor {
             Gossiper::new_for_complete_items("address_gossiper", config.gossip, registry)?;
 
         let protocol_version = &chainspec_loader.chainspec().protocol_config.version;
-        let rpc_server =
-            RpcServer::new(config.rpc_server.clone(), effect_builder, *protocol_version)?;
+        let rpc_server = RpcServer::new(
+            config.rpc_server.clone(),
+            effect_builder,
+            *protocol_version,
+            Arc::clone(chainspec_loader.chainspec()),
+        )?;
         let rest_server = RestServer::new(
             config.rest_server.clone(),
             effect_builder,
             *protocol_version,
+            false,
         )?;
 
-        let deploy_acceptor =
-            DeployAcceptor::new(config.deploy_acceptor, &*chainspec_loader.chainspec());
+        let next_block_height = maybe_latest_block_header
+            .as_ref()
+            .map(|block_header| block_header.height() + 1)
+            .unwrap_or(0);
+        let deploy_acceptor = DeployAcceptor::new(
+            config.deploy_acceptor,
+            Arc::clone(chainspec_loader.chainspec()),
+            next_block_height,
+        );
+        let trie_request_limiter = ClientRateLimiter::new(
+            config.fetcher.peer_trie_request_qps_limit(),
+            config.fetcher.peer_trie_request_burst_limit(),
+            &[],
+        );
         let deploy_fetcher = Fetcher::new("deploy", config.fetcher, registry)?;
         let deploy_gossiper = Gossiper::new_for_partial_items(
             "deploy_gossiper",
@@ -440,10 +601,7 @@ impl reactor::Reactor for Reactor {
         let (block_proposer, block_proposer_effects) = BlockProposer::new(
             registry.clone(),
             effect_builder,
-            maybe_latest_block_header
-                .as_ref()
-                .map(|block_header| block_header.height() + 1)
-                .unwrap_or(0),
+            next_block_height,
             chainspec_loader.chainspec().as_ref(),
             config.block_proposer,
         )?;
@@ -453,7 +611,7 @@ impl reactor::Reactor for Reactor {
             |block_header| block_header.next_block_era_id(),
         );
 
-        let (small_network, small_network_effects) = SmallNetwork::new(
+        let small_network = SmallNetwork::new(
             event_queue,
             config.network,
             Some(WithDir::new(&root, &config.consensus)),
@@ -488,7 +646,8 @@ impl reactor::Reactor for Reactor {
         );
         contract_runtime.set_parent_map_from_block(maybe_latest_block_header);
 
-        let block_validator = BlockValidator::new(Arc::clone(chainspec_loader.chainspec()));
+        let block_validator =
+            BlockValidator::new(Arc::clone(chainspec_loader.chainspec()), next_block_height);
         let linear_chain = linear_chain::LinearChainComponent::new(
             registry,
             *protocol_version,
@@ -497,10 +656,6 @@ impl reactor::Reactor for Reactor {
         )?;
 
         effects.extend(reactor::wrap_effects(Event::Network, network_effects));
-        effects.extend(reactor::wrap_effects(
-            Event::SmallNetwork,
-            small_network_effects,
-        ));
         effects.extend(reactor::wrap_effects(
             Event::ChainspecLoader,
             chainspec_loader.start_checking_for_upgrades(effect_builder),
@@ -508,30 +663,31 @@ impl reactor::Reactor for Reactor {
 
         event_stream_server.set_participating_effect_builder(effect_builder);
 
-        Ok((
-            Reactor {
-                metrics,
-                network,
-                small_network,
-                address_gossiper,
-                storage,
-                contract_runtime,
-                rpc_server,
-                rest_server,
-                event_stream_server,
-                chainspec_loader,
-                consensus,
-                deploy_acceptor,
-                deploy_fetcher,
-                deploy_gossiper,
-                block_proposer,
-                block_validator,
-                linear_chain,
-                memory_metrics,
-                event_queue_metrics,
-            },
-            effects,
-        ))
+        let mut reactor = Reactor {
+            metrics,
+            network,
+            small_network,
+            address_gossiper,
+            storage,
+            contract_runtime,
+            rpc_server,
+            rest_server,
+            event_stream_server,
+            chainspec_loader,
+            consensus,
+            deploy_acceptor,
+            deploy_fetcher,
+            deploy_gossiper,
+            block_proposer,
+            block_validator,
+            linear_chain,
+            trie_request_limiter,
+            memory_metrics,
+            event_queue_metrics,
+        };
+        effects.extend(reactor.init_components(effect_builder));
+
+        Ok((reactor, effects))
     }
 
     fn dispatch_event(
@@ -541,76 +697,117 @@ impl reactor::Reactor for Reactor {
         event: Event,
     ) -> Effects<Self::Event> {
         match event {
-            Event::Network(event) => reactor::wrap_effects(
+            Event::Network(event) => reactor::dispatch_to_component(
                 Event::Network,
-                self.network.handle_event(effect_builder, rng, event),
+                &mut self.network,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::SmallNetwork(event) => reactor::wrap_effects(
+            Event::SmallNetwork(event) => reactor::dispatch_to_component(
                 Event::SmallNetwork,
-                self.small_network.handle_event(effect_builder, rng, event),
+                &mut self.small_network,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::BlockProposer(event) => reactor::wrap_effects(
+            Event::BlockProposer(event) => reactor::dispatch_to_component(
                 Event::BlockProposer,
-                self.block_proposer.handle_event(effect_builder, rng, event),
+                &mut self.block_proposer,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::Storage(event) => reactor::wrap_effects(
+            Event::Storage(event) => reactor::dispatch_to_component(
                 Event::Storage,
-                self.storage.handle_event(effect_builder, rng, event),
+                &mut self.storage,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::RpcServer(event) => reactor::wrap_effects(
+            Event::RpcServer(event) => reactor::dispatch_to_component(
                 Event::RpcServer,
-                self.rpc_server.handle_event(effect_builder, rng, event),
+                &mut self.rpc_server,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::RestServer(event) => reactor::wrap_effects(
+            Event::RestServer(event) => reactor::dispatch_to_component(
                 Event::RestServer,
-                self.rest_server.handle_event(effect_builder, rng, event),
+                &mut self.rest_server,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::EventStreamServer(event) => reactor::wrap_effects(
+            Event::EventStreamServer(event) => reactor::dispatch_to_component(
                 Event::EventStreamServer,
-                self.event_stream_server
-                    .handle_event(effect_builder, rng, event),
+                &mut self.event_stream_server,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::ChainspecLoader(event) => reactor::wrap_effects(
+            Event::ChainspecLoader(event) => reactor::dispatch_to_component(
                 Event::ChainspecLoader,
-                self.chainspec_loader
-                    .handle_event(effect_builder, rng, event),
+                &mut self.chainspec_loader,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::Consensus(event) => reactor::wrap_effects(
+            Event::Consensus(event) => reactor::dispatch_to_component(
                 Event::Consensus,
-                self.consensus.handle_event(effect_builder, rng, event),
+                &mut self.consensus,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::DeployAcceptor(event) => reactor::wrap_effects(
+            Event::DeployAcceptor(event) => reactor::dispatch_to_component(
                 Event::DeployAcceptor,
-                self.deploy_acceptor
-                    .handle_event(effect_builder, rng, event),
+                &mut self.deploy_acceptor,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::DeployFetcher(event) => reactor::wrap_effects(
+            Event::DeployFetcher(event) => reactor::dispatch_to_component(
                 Event::DeployFetcher,
-                self.deploy_fetcher.handle_event(effect_builder, rng, event),
+                &mut self.deploy_fetcher,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::DeployGossiper(event) => reactor::wrap_effects(
+            Event::DeployGossiper(event) => reactor::dispatch_to_component(
                 Event::DeployGossiper,
-                self.deploy_gossiper
-                    .handle_event(effect_builder, rng, event),
+                &mut self.deploy_gossiper,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::AddressGossiper(event) => reactor::wrap_effects(
+            Event::AddressGossiper(event) => reactor::dispatch_to_component(
                 Event::AddressGossiper,
-                self.address_gossiper
-                    .handle_event(effect_builder, rng, event),
+                &mut self.address_gossiper,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::ContractRuntime(event) => reactor::wrap_effects(
+            Event::ContractRuntime(event) => reactor::dispatch_to_component(
                 Event::ContractRuntime,
-                self.contract_runtime
-                    .handle_event(effect_builder, rng, event),
+                &mut self.contract_runtime,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::BlockValidator(event) => reactor::wrap_effects(
+            Event::BlockValidator(event) => reactor::dispatch_to_component(
                 Event::BlockValidator,
-                self.block_validator
-                    .handle_event(effect_builder, rng, event),
+                &mut self.block_validator,
+                effect_builder,
+                rng,
+                event,
             ),
-            Event::LinearChain(event) => reactor::wrap_effects(
+            Event::LinearChain(event) => reactor::dispatch_to_component(
                 Event::LinearChain,
-                self.linear_chain.handle_event(effect_builder, rng, event),
+                &mut self.linear_chain,
+                effect_builder,
+                rng,
+                event,
             ),
 
             // Requests:
@@ -641,10 +838,16 @@ impl reactor::Reactor for Reactor {
                 rng,
                 Event::BlockValidator(block_validator::Event::from(req)),
             ),
-            Event::MetricsRequest(req) => reactor::wrap_effects(
-                Event::MetricsRequest,
-                self.metrics.handle_event(effect_builder, rng, req),
+            Event::Metrics(event) => reactor::dispatch_to_component(
+                Event::Metrics,
+                &mut self.metrics,
+                effect_builder,
+                rng,
+                event,
             ),
+            Event::MetricsRequest(req) => {
+                self.dispatch_event(effect_builder, rng, Event::Metrics(req.into()))
+            }
             Event::ChainspecLoaderRequest(req) => {
                 self.dispatch_event(effect_builder, rng, Event::ChainspecLoader(req.into()))
             }
@@ -817,6 +1020,43 @@ impl reactor::Reactor for Reactor {
                                 }
                             }
                         }
+                        Tag::Trie => {
+                            if let Err(retry_after) =
+                                self.trie_request_limiter.check(sender, Instant::now())
+                            {
+                                debug!(
+                                    %sender, ?retry_after,
+                                    "ignoring trie request exceeding peer's rate limit"
+                                );
+                                return Effects::new();
+                            }
+                            let trie_key: Blake2bHash = match bincode::deserialize(&serialized_id) {
+                                Ok(trie_key) => trie_key,
+                                Err(error) => {
+                                    error!(
+                                        "failed to decode {:?} from {}: {}",
+                                        serialized_id, sender, error
+                                    );
+                                    return Effects::new();
+                                }
+                            };
+                            return async move {
+                                match effect_builder.read_trie(trie_key).await {
+                                    Some(trie) => match Message::new_get_response(&trie) {
+                                        Ok(message) => {
+                                            effect_builder.send_message(sender, message).await
+                                        }
+                                        Err(error) => {
+                                            error!("failed to create get-response: {}", error)
+                                        }
+                                    },
+                                    None => {
+                                        debug!(%sender, %trie_key, "failed to get trie (not found)")
+                                    }
+                                }
+                            }
+                            .ignore();
+                        }
                     },
                     Message::GetResponse {
                         tag,
@@ -872,6 +1112,10 @@ impl reactor::Reactor for Reactor {
                             );
                             return Effects::new();
                         }
+                        Tag::Trie => {
+                            error!("cannot handle get response for trie from {}", sender);
+                            return Effects::new();
+                        }
                     },
                     Message::FinalitySignature(fs) => {
                         Event::LinearChain(linear_chain::Event::FinalitySignatureReceived(fs, true))
@@ -959,6 +1203,18 @@ impl reactor::Reactor for Reactor {
                         Event::BlockProposer(block_proposer::Event::FinalizedBlock(block));
                     self.dispatch_event(effect_builder, rng, reactor_event)
                 }
+                ConsensusAnnouncement::EraEnded {
+                    era_id,
+                    switch_block_height,
+                    ..
+                } => {
+                    let reactor_event =
+                        Event::EventStreamServer(event_stream_server::Event::EraEnded {
+                            era_id,
+                            switch_block_height,
+                        });
+                    self.dispatch_event(effect_builder, rng, reactor_event)
+                }
                 ConsensusAnnouncement::CreatedFinalitySignature(fs) => self.dispatch_event(
                     effect_builder,
                     rng,
@@ -968,6 +1224,7 @@ impl reactor::Reactor for Reactor {
                     era_id,
                     public_key,
                     timestamp,
+                    ..
                 } => {
                     let reactor_event =
                         Event::EventStreamServer(event_stream_server::Event::Fault {
@@ -1034,6 +1291,17 @@ impl reactor::Reactor for Reactor {
                 error!(%gossiped_deploy_id, "gossiper should not announce new deploy");
                 Effects::new()
             }
+            Event::DeployGossiperAnnouncement(GossiperAnnouncement::ItemReassembled {
+                item,
+                sender,
+            }) => {
+                let event = deploy_acceptor::Event::Accept {
+                    deploy: item,
+                    source: Source::Peer(sender),
+                    responder: None,
+                };
+                self.dispatch_event(effect_builder, rng, Event::DeployAcceptor(event))
+            }
             Event::DeployGossiperAnnouncement(GossiperAnnouncement::FinishedGossiping(
                 _gossiped_deploy_id,
             )) => {
@@ -1055,7 +1323,15 @@ impl reactor::Reactor for Reactor {
                 // We don't care about completion of gossiping an address.
                 Effects::new()
             }
+            Event::AddressGossiperAnnouncement(GossiperAnnouncement::ItemReassembled {
+                item,
+                sender,
+            }) => {
+                error!(%item, %sender, "gossiped addresses should never be sent in chunks");
+                Effects::new()
+            }
             Event::LinearChainAnnouncement(LinearChainAnnouncement::BlockAdded(block)) => {
+                let height = block.height();
                 let reactor_event_consensus = Event::Consensus(consensus::Event::BlockAdded(
                     Box::new(block.header().clone()),
                 ));
@@ -1063,6 +1339,16 @@ impl reactor::Reactor for Reactor {
                     Event::EventStreamServer(event_stream_server::Event::BlockAdded(block.clone()));
                 let mut effects = self.dispatch_event(effect_builder, rng, reactor_event_es);
                 effects.extend(self.dispatch_event(effect_builder, rng, reactor_event_consensus));
+                effects.extend(self.dispatch_event(
+                    effect_builder,
+                    rng,
+                    Event::BlockValidator(block_validator::Event::BlockAdded { height }),
+                ));
+                effects.extend(self.dispatch_event(
+                    effect_builder,
+                    rng,
+                    Event::DeployAcceptor(deploy_acceptor::Event::BlockAdded { height }),
+                ));
                 effects.extend(self.dispatch_event(
                     effect_builder,
                     rng,
@@ -1092,6 +1378,9 @@ impl reactor::Reactor for Reactor {
             Event::BlocklistAnnouncement(ann) => {
                 self.dispatch_event(effect_builder, rng, Event::SmallNetwork(ann.into()))
             }
+            // The corrupt entry is reported as missing by storage, so it is fetched from a peer
+            // again by whichever component needs it next.
+            Event::StorageAnnouncement(_) => Effects::new(),
         }
     }
 
@@ -1106,6 +1395,96 @@ impl reactor::Reactor for Reactor {
             .stop_for_upgrade()
             .then(|| ReactorExit::ProcessShouldExit(ExitCode::Success))
     }
+
+    fn shutdown(&mut self, effect_builder: EffectBuilder<Self::Event>) -> Effects<Self::Event> {
+        let mut effects = Effects::new();
+        effects.extend(reactor::shutdown_component(
+            Event::SmallNetwork,
+            &mut self.small_network,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::Network,
+            &mut self.network,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::AddressGossiper,
+            &mut self.address_gossiper,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::DeployGossiper,
+            &mut self.deploy_gossiper,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::RpcServer,
+            &mut self.rpc_server,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::RestServer,
+            &mut self.rest_server,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::EventStreamServer,
+            &mut self.event_stream_server,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::DeployAcceptor,
+            &mut self.deploy_acceptor,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::DeployFetcher,
+            &mut self.deploy_fetcher,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::BlockProposer,
+            &mut self.block_proposer,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::Consensus,
+            &mut self.consensus,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::BlockValidator,
+            &mut self.block_validator,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::LinearChain,
+            &mut self.linear_chain,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::ChainspecLoader,
+            &mut self.chainspec_loader,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::ContractRuntime,
+            &mut self.contract_runtime,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::Metrics,
+            &mut self.metrics,
+            effect_builder,
+        ));
+        effects.extend(reactor::shutdown_component(
+            Event::Storage,
+            &mut self.storage,
+            effect_builder,
+        ));
+        effects
+    }
 }
 
 #[cfg(test)]
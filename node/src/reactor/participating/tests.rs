@@ -1,4 +1,11 @@
-use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
 use anyhow::bail;
 use log::info;
@@ -7,17 +14,22 @@ use num_rational::Ratio;
 use rand::Rng;
 use tempfile::TempDir;
 
-use casper_execution_engine::shared::motes::Motes;
+use casper_execution_engine::shared::{motes::Motes, newtypes::Blake2bHash};
 use casper_types::{system::auction::DelegationRate, EraId, PublicKey, SecretKey, U512};
 
 use crate::{
-    components::{consensus, gossiper, small_network, storage},
+    components::{consensus, fetcher, gossiper, small_network, storage},
     crypto::AsymmetricKeyExt,
+    effect::{
+        announcements::{ConsensusAnnouncement, NetworkAnnouncement},
+        EffectExt,
+    },
+    protocol::Message,
     reactor::{initializer, joiner, participating, ReactorExit, Runner},
     testing::{self, network::Network, TestRng},
     types::{
         chainspec::{AccountConfig, AccountsConfig, ValidatorConfig},
-        ActivationPoint, Chainspec, Timestamp,
+        ActivationPoint, Chainspec, Tag, Timestamp,
     },
     utils::{External, Loadable, WithDir, RESOURCES_PATH},
     NodeRng,
@@ -28,6 +40,7 @@ struct TestChain {
     keys: Vec<Arc<SecretKey>>,
     storages: Vec<TempDir>,
     chainspec: Arc<Chainspec>,
+    fetcher_config: fetcher::Config,
 }
 
 type Nodes = crate::testing::network::Nodes<participating::Reactor>;
@@ -93,6 +106,7 @@ impl TestChain {
             keys,
             chainspec: Arc::new(chainspec),
             storages: Vec::new(),
+            fetcher_config: Default::default(),
         }
     }
 
@@ -106,6 +120,7 @@ impl TestChain {
                 small_network::Config::default_local_net(first_node_port)
             },
             gossip: gossiper::Config::new_with_small_timeouts(),
+            fetcher: self.fetcher_config,
             ..Default::default()
         };
 
@@ -199,6 +214,218 @@ async fn run_participating_network() {
         .await;
 }
 
+/// A consensus announcement about the chain's progress, as recorded by a test.
+#[derive(Debug, PartialEq)]
+enum ChainProgress {
+    BlockFinalized {
+        era_id: EraId,
+        height: u64,
+        is_switch_block: bool,
+    },
+    EraEnded {
+        era_id: EraId,
+        switch_block_height: u64,
+    },
+}
+
+#[tokio::test]
+async fn era_end_is_announced_after_its_last_block() {
+    testing::init_logging();
+
+    let mut rng = crate::new_rng();
+
+    let mut chain = TestChain::new(&mut rng, 2);
+    let mut net = chain
+        .create_initialized_network(&mut rng)
+        .await
+        .expect("network initialization failed");
+
+    // Record the finalized blocks and era ends announced on one of the nodes.
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let recorded_progress = Arc::clone(&progress);
+    let node_id = *net.nodes().keys().next().expect("should have a node");
+    net.nodes_mut()
+        .get_mut(&node_id)
+        .unwrap()
+        .reactor_mut()
+        .set_condition_checker(Box::new(move |event| {
+            let announced = match event {
+                participating::Event::ConsensusAnnouncement(ConsensusAnnouncement::Finalized(
+                    block,
+                )) => ChainProgress::BlockFinalized {
+                    era_id: block.era_id(),
+                    height: block.height(),
+                    is_switch_block: block.era_report().is_some(),
+                },
+                participating::Event::ConsensusAnnouncement(ConsensusAnnouncement::EraEnded {
+                    era_id,
+                    switch_block_height,
+                    ..
+                }) => ChainProgress::EraEnded {
+                    era_id: *era_id,
+                    switch_block_height: *switch_block_height,
+                },
+                _ => return false,
+            };
+            recorded_progress.lock().unwrap().push(announced);
+            false
+        }));
+
+    net.settle_on(&mut rng, is_in_era(EraId::from(2)), Duration::from_secs(90))
+        .await;
+
+    let progress = progress.lock().unwrap();
+    let era_ends: Vec<_> = progress
+        .iter()
+        .enumerate()
+        .filter(|(_, announced)| matches!(announced, ChainProgress::EraEnded { .. }))
+        .collect();
+    assert!(
+        era_ends.len() >= 2,
+        "expected the ends of eras 0 and 1, got {:?}",
+        progress
+    );
+
+    for (index, era_end) in era_ends {
+        let (era_id, switch_block_height) = match era_end {
+            ChainProgress::EraEnded {
+                era_id,
+                switch_block_height,
+            } => (*era_id, *switch_block_height),
+            ChainProgress::BlockFinalized { .. } => unreachable!(),
+        };
+        // The era's switch block is announced right before the era's end...
+        assert_eq!(
+            index.checked_sub(1).map(|previous| &progress[previous]),
+            Some(&ChainProgress::BlockFinalized {
+                era_id,
+                height: switch_block_height,
+                is_switch_block: true,
+            })
+        );
+        // ...and no other block of the era is finalized afterwards.
+        assert!(progress[index..].iter().all(|announced| !matches!(
+            announced,
+            ChainProgress::BlockFinalized { era_id: block_era_id, .. } if *block_era_id == era_id
+        )));
+    }
+}
+
+#[tokio::test]
+async fn trie_requests_are_served_within_peer_rate_limit() {
+    testing::init_logging();
+
+    const BURST_LIMIT: usize = 3;
+    const REQUEST_COUNT: usize = 10;
+
+    let mut rng = crate::new_rng();
+
+    let mut chain = TestChain::new(&mut rng, 2);
+    chain.fetcher_config = fetcher::Config::new_with_trie_request_limits(1, BURST_LIMIT as u64);
+    let mut net = chain
+        .create_initialized_network(&mut rng)
+        .await
+        .expect("network initialization failed");
+
+    net.settle_on(&mut rng, is_in_era(EraId::from(1)), Duration::from_secs(90))
+        .await;
+
+    let mut node_ids = net.nodes().keys().copied();
+    let server = node_ids.next().expect("should have a node");
+    let requester = node_ids.next().expect("should have two nodes");
+
+    let genesis_state_root_hash = Blake2bHash::from(
+        *net.nodes()[&server]
+            .reactor()
+            .inner()
+            .storage()
+            .read_block_header_and_finality_signatures_by_height(0)
+            .expect("should read genesis block header")
+            .expect("should have genesis block header")
+            .block_header
+            .state_root_hash(),
+    );
+    let trie_request = Message::GetRequest {
+        tag: Tag::Trie,
+        serialized_id: bincode::serialize(&genesis_state_root_hash)
+            .expect("should serialize trie key"),
+    };
+
+    // Count the trie requests handled by the server, and the trie responses it sent back.
+    let requests_handled = Arc::new(AtomicUsize::new(0));
+    let counted_requests = Arc::clone(&requests_handled);
+    net.nodes_mut()
+        .get_mut(&server)
+        .unwrap()
+        .reactor_mut()
+        .set_condition_checker(Box::new(move |event| {
+            if let participating::Event::NetworkAnnouncement(
+                NetworkAnnouncement::MessageReceived {
+                    payload: Message::GetRequest { tag: Tag::Trie, .. },
+                    ..
+                },
+            ) = event
+            {
+                counted_requests.fetch_add(1, Ordering::SeqCst);
+            }
+            false
+        }));
+    let responses_received = Arc::new(AtomicUsize::new(0));
+    let counted_responses = Arc::clone(&responses_received);
+    net.nodes_mut()
+        .get_mut(&requester)
+        .unwrap()
+        .reactor_mut()
+        .set_condition_checker(Box::new(move |event| {
+            if let participating::Event::NetworkAnnouncement(
+                NetworkAnnouncement::MessageReceived {
+                    sender,
+                    payload: Message::GetResponse { tag: Tag::Trie, .. },
+                },
+            ) = event
+            {
+                assert_eq!(*sender, server);
+                counted_responses.fetch_add(1, Ordering::SeqCst);
+            }
+            false
+        }));
+
+    // Have the server receive a burst of requests for the genesis global state root from the
+    // requester.
+    net.process_injected_effect_on(&server, move |effect_builder| {
+        (0..REQUEST_COUNT)
+            .flat_map(|_| {
+                effect_builder
+                    .announce_message_received(requester, trie_request.clone())
+                    .ignore()
+            })
+            .collect()
+    })
+    .await;
+
+    net.settle_on(
+        &mut rng,
+        |_| {
+            requests_handled.load(Ordering::SeqCst) == REQUEST_COUNT
+                && responses_received.load(Ordering::SeqCst) >= BURST_LIMIT
+        },
+        Duration::from_secs(30),
+    )
+    .await;
+
+    // Allow any further, wrongly served responses to arrive.
+    let start = Instant::now();
+    net.settle_on(
+        &mut rng,
+        |_| start.elapsed() > Duration::from_secs(1),
+        Duration::from_secs(30),
+    )
+    .await;
+
+    // Only a single burst of requests should have been served.
+    assert_eq!(responses_received.load(Ordering::SeqCst), BURST_LIMIT);
+}
+
 // TODO: fix this test
 #[tokio::test]
 async fn run_equivocator_network() {
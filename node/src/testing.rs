@@ -12,8 +12,10 @@ mod test_rng;
 use std::{
     any::type_name,
     fmt::Debug,
+    io,
     marker::PhantomData,
     net::{Ipv4Addr, TcpListener},
+    sync::{Arc, Mutex},
     time,
 };
 
@@ -27,7 +29,10 @@ use tracing::{debug, info, warn};
 
 use crate::{
     components::Component,
-    effect::{announcements::ControlAnnouncement, EffectBuilder, Effects, Responder},
+    effect::{
+        announcements::{ControlAnnouncement, StorageAnnouncement},
+        EffectBuilder, Effects, Responder,
+    },
     logging,
     reactor::{EventQueueHandle, QueueKind, ReactorEvent, Scheduler},
 };
@@ -92,6 +97,31 @@ pub(crate) fn init_logging() {
         .ok();
 }
 
+/// In-memory sink for log output, used to check what a test logged.
+///
+/// Clones share the same buffer, so one can be handed to a subscriber, e.g. via
+/// `with_writer(move || log_buffer.clone())`, while another is kept to read the output.
+#[derive(Clone, Debug, Default)]
+pub struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl LogBuffer {
+    /// Returns the output written so far.
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().expect("lock poisoned")).into_owned()
+    }
+}
+
+impl io::Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().expect("lock poisoned").extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 /// Harness to test a single component as isolated as possible.
 ///
 /// Contains enough reactor machinery to drive a single component and a temporary directory.
@@ -256,7 +286,8 @@ impl<REv: 'static> ComponentHarness<REv> {
 
             // Iterate over all events that currently are inside the queue and fish out any fatal.
             for _ in 0..(self.scheduler.item_count()) {
-                let (ev, _queue_kind) = self.runtime.block_on(self.scheduler.pop());
+                let ((ev, context), _queue_kind) = self.runtime.block_on(self.scheduler.pop());
+                context.dequeue();
 
                 if let Some(ctrl_ann) = ev.as_control() {
                     match ctrl_ann {
@@ -300,12 +331,15 @@ impl<REv: 'static> Default for ComponentHarness<REv> {
 /// A special event for unit tests.
 ///
 /// Essentially discards all event (they are not even processed by the unit testing hardness),
-/// except for control announcements, which are preserved.
+/// except for control and storage announcements, which are preserved.
 #[derive(Debug, From)]
 pub enum UnitTestEvent {
     /// A preserved control announcement.
     #[from]
     ControlAnnouncement(ControlAnnouncement),
+    /// A preserved storage announcement.
+    #[from]
+    StorageAnnouncement(StorageAnnouncement),
     /// A different event.
     Other,
 }
@@ -314,7 +348,7 @@ impl ReactorEvent for UnitTestEvent {
     fn as_control(&self) -> Option<&ControlAnnouncement> {
         match self {
             UnitTestEvent::ControlAnnouncement(ctrl_ann) => Some(ctrl_ann),
-            UnitTestEvent::Other => None,
+            UnitTestEvent::StorageAnnouncement(_) | UnitTestEvent::Other => None,
         }
     }
 }
@@ -91,6 +91,10 @@ impl<R: Reactor> Reactor for ConditionCheckReactor<R> {
     fn maybe_exit(&self) -> Option<ReactorExit> {
         self.reactor.maybe_exit()
     }
+
+    fn shutdown(&mut self, effect_builder: EffectBuilder<Self::Event>) -> Effects<Self::Event> {
+        self.reactor.shutdown(effect_builder)
+    }
 }
 
 impl<R: Reactor + Finalize> Finalize for ConditionCheckReactor<R> {
@@ -22,7 +22,8 @@ use crate::{
         initializer::Reactor as InitializerReactor,
         joiner::Reactor as JoinerReactor,
         participating::{ParticipatingInitConfig, Reactor as ParticipatingReactor},
-        wrap_effects, EventQueueHandle, QueueKind, Reactor, ReactorEvent, ReactorExit, Scheduler,
+        with_correlation_id, wrap_effects, EventQueueHandle, QueueKind, Reactor, ReactorEvent,
+        ReactorExit, Scheduler,
     },
     testing::network::NetworkedReactor,
     types::{Chainspec, NodeId},
@@ -164,8 +165,8 @@ where
 {
     // Note: This will keep waiting forever if the sending end disappears, which is fine for tests.
     loop {
-        let (event, queue_kind) = source.pop().await;
-        target_queue.schedule(event, queue_kind).await;
+        let ((event, context), queue_kind) = source.pop().await;
+        with_correlation_id(context.dequeue(), target_queue.schedule(event, queue_kind)).await;
     }
 }
 
@@ -133,12 +133,29 @@ impl Sha512 {
 #[derive(Copy, Clone, DataSize, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub(crate) struct CertFingerprint(Sha512);
 
+impl CertFingerprint {
+    /// Parses a fingerprint from its hex encoding, as produced by its `Display` implementation.
+    ///
+    /// Returns `None` if the string is not a hex encoded SHA-512 digest.
+    pub(crate) fn from_hex(hex_encoded: &str) -> Option<Self> {
+        let bytes = hex::decode(hex_encoded).ok()?;
+        let raw_bytes: [u8; Sha512::SIZE] = bytes.as_slice().try_into().ok()?;
+        Some(CertFingerprint(Sha512(raw_bytes)))
+    }
+}
+
 impl Debug for CertFingerprint {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "CertFingerprint({:10})", HexFmt(self.0.bytes()))
     }
 }
 
+impl Display for CertFingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", HexFmt(self.0.bytes()))
+    }
+}
+
 /// Public key fingerprint.
 #[derive(Copy, Clone, DataSize, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct KeyFingerprint(Sha512);
@@ -817,7 +834,9 @@ impl Hash for Sha512 {
 
 #[cfg(test)]
 mod test {
-    use super::{generate_node_cert, mkname, name_to_string, validate_cert, TlsCert};
+    use super::{
+        generate_node_cert, mkname, name_to_string, validate_cert, CertFingerprint, TlsCert,
+    };
 
     #[test]
     fn simple_name_to_string() {
@@ -843,4 +862,19 @@ mod test {
 
         assert_eq!(serialized, serialized_again);
     }
+
+    #[test]
+    fn cert_fingerprint_hex_roundtrip() {
+        let (cert, _private_key) = generate_node_cert().expect("failed to generate key, cert pair");
+        let fingerprint = validate_cert(cert)
+            .expect("generated cert is not valid")
+            .fingerprint();
+
+        let hex_encoded = fingerprint.to_string();
+        assert_eq!(hex_encoded.len(), 128);
+        assert_eq!(CertFingerprint::from_hex(&hex_encoded), Some(fingerprint));
+
+        assert_eq!(CertFingerprint::from_hex(&hex_encoded[2..]), None);
+        assert_eq!(CertFingerprint::from_hex("not hex"), None);
+    }
 }
@@ -22,7 +22,9 @@ pub use block::{
     json_compatibility::JsonBlock, Block, BlockBody, BlockHash, BlockHeader, BlockSignatures,
     BlockValidationError, FinalitySignature,
 };
-pub(crate) use block::{BlockByHeight, BlockHeaderWithMetadata, BlockPayload, FinalizedBlock};
+pub(crate) use block::{
+    BlockByHeight, BlockHeaderWithMetadata, BlockPayload, EraReport, FinalizedBlock,
+};
 pub(crate) use chainspec::ActivationPoint;
 pub use chainspec::Chainspec;
 pub use datasize::DataSize;
@@ -34,7 +36,9 @@ pub use exit_code::ExitCode;
 pub use item::{Item, Tag};
 pub use node_config::NodeConfig;
 pub(crate) use node_id::NodeId;
-pub use peers_map::PeersMap;
+pub use peers_map::{
+    ConnectionDirection, PeerConnection, PeerConnectionState, PeerConnections, PeersMap,
+};
 pub(crate) use shared_object::SharedObject;
 pub use status_feed::{ChainspecInfo, GetStatusResult, StatusFeed};
 pub use timestamp::{TimeDiff, Timestamp};
@@ -1532,6 +1532,16 @@ pub(crate) mod json_compatibility {
         pub fn transfer_hashes(&self) -> &Vec<DeployHash> {
             &self.body.transfer_hashes
         }
+
+        /// Returns the height of the `Block`.
+        pub fn height(&self) -> u64 {
+            self.header.height
+        }
+
+        /// Returns the timestamp of the `Block`.
+        pub fn timestamp(&self) -> Timestamp {
+            self.header.timestamp
+        }
     }
 
     impl DocExample for JsonBlock {
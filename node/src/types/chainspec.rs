@@ -3,10 +3,12 @@
 
 mod accounts_config;
 mod activation_point;
+mod chainspec_format;
 mod core_config;
 mod deploy_config;
 mod error;
 mod global_state_update;
+mod height_activation_point;
 mod highway_config;
 mod network_config;
 mod parse_toml;
@@ -31,11 +33,18 @@ use casper_types::{
 
 #[cfg(test)]
 pub(crate) use self::accounts_config::{AccountConfig, ValidatorConfig};
-pub use self::error::Error;
+pub use self::error::{Error, ValidationError};
 pub(crate) use self::{
-    accounts_config::AccountsConfig, activation_point::ActivationPoint, core_config::CoreConfig,
-    deploy_config::DeployConfig, global_state_update::GlobalStateUpdate,
-    highway_config::HighwayConfig, network_config::NetworkConfig, protocol_config::ProtocolConfig,
+    accounts_config::AccountsConfig,
+    activation_point::ActivationPoint,
+    chainspec_format::{chainspec_file_path, ChainspecFormat},
+    core_config::CoreConfig,
+    deploy_config::DeployConfig,
+    global_state_update::GlobalStateUpdate,
+    height_activation_point::HeightActivationPoint,
+    highway_config::HighwayConfig,
+    network_config::NetworkConfig,
+    protocol_config::ProtocolConfig,
 };
 #[cfg(test)]
 use crate::testing::TestRng;
@@ -47,6 +56,9 @@ use crate::{
 /// The name of the chainspec file on disk.
 pub const CHAINSPEC_NAME: &str = "chainspec.toml";
 
+/// The name of the chainspec file on disk when encoded as JSON.
+pub const CHAINSPEC_JSON_NAME: &str = "chainspec.json";
+
 /// A collection of configuration settings describing the state of the system at genesis and after
 /// upgrades to basic system functionality occurring after genesis.
 #[derive(DataSize, PartialEq, Eq, Serialize, Debug)]
@@ -65,6 +77,11 @@ pub struct Chainspec {
     pub(crate) wasm_config: WasmConfig,
     #[serde(rename = "system_costs")]
     pub(crate) system_costs_config: SystemConfig,
+    /// Deploy parameters replacing `deploy_config` from given block heights, in increasing order
+    /// of height.
+    ///
+    /// Only serialized if non-empty, so that the hash of a chainspec without any is unchanged.
+    pub(crate) height_activation_points: Vec<HeightActivationPoint>,
 }
 
 impl Chainspec {
@@ -80,11 +97,33 @@ impl Chainspec {
             warn!("era duration is less than minimum era height * round length!");
         }
 
-        self.protocol_config.is_valid() && self.highway_config.is_valid()
+        if let Err(error) = self.validate() {
+            error!(%error, "invalid chainspec");
+            return false;
+        }
+        true
+    }
+
+    /// Checks whether the values set in the config make sense, returning the first problem found.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.protocol_config.validate()?;
+        self.highway_config.validate()?;
+        self.deploy_config.validate()?;
+        // The contract runtime only applies the native transfer gas price of `deploy_config`.
+        for activation_point in &self.height_activation_points {
+            if activation_point.deploys.native_transfer_gas_price
+                != self.deploy_config.native_transfer_gas_price
+            {
+                return Err(ValidationError::NativeTransferGasPriceChangedAtHeight {
+                    height: activation_point.height,
+                });
+            }
+        }
+        Ok(())
     }
 
     /// Serializes `self` and hashes the resulting bytes.
-    pub(crate) fn hash(&self) -> Digest {
+    pub fn hash(&self) -> Digest {
         let serialized_chainspec = self.to_bytes().unwrap_or_else(|error| {
             error!(%error, "failed to serialize chainspec");
             vec![]
@@ -101,6 +140,18 @@ impl Chainspec {
     pub(crate) fn protocol_version(&self) -> ProtocolVersion {
         self.protocol_config.version
     }
+
+    /// Returns the deploy parameters which apply to the block at the given height: those of the
+    /// last height activation point at or below `height`, or else `deploy_config`.
+    pub(crate) fn deploy_config_at(&self, height: u64) -> &DeployConfig {
+        self.height_activation_points
+            .iter()
+            .rev()
+            .find(|activation_point| activation_point.height <= height)
+            .map_or(&self.deploy_config, |activation_point| {
+                &activation_point.deploys
+            })
+    }
 }
 
 #[cfg(test)]
@@ -111,9 +162,19 @@ impl Chainspec {
         let network_config = NetworkConfig::random(rng);
         let core_config = CoreConfig::random(rng);
         let highway_config = HighwayConfig::random(rng);
-        let deploy_config = DeployConfig::random(rng);
+        let mut deploy_config = DeployConfig::random(rng);
+        deploy_config.native_transfer_gas_price = rng.gen_range(1..100);
         let wasm_costs_config = rng.gen();
         let system_costs_config = rng.gen();
+        let mut height_activation_points = (0..rng.gen_range(0..3))
+            .map(|_| HeightActivationPoint::random(rng))
+            .collect::<Vec<_>>();
+        height_activation_points.sort_by_key(|activation_point| activation_point.height);
+        height_activation_points.dedup_by_key(|activation_point| activation_point.height);
+        for activation_point in &mut height_activation_points {
+            activation_point.deploys.native_transfer_gas_price =
+                deploy_config.native_transfer_gas_price;
+        }
 
         Chainspec {
             protocol_config,
@@ -123,6 +184,7 @@ impl Chainspec {
             deploy_config,
             wasm_config: wasm_costs_config,
             system_costs_config,
+            height_activation_points,
         }
     }
 }
@@ -137,6 +199,16 @@ impl ToBytes for Chainspec {
         buffer.extend(self.deploy_config.to_bytes()?);
         buffer.extend(self.wasm_config.to_bytes()?);
         buffer.extend(self.system_costs_config.to_bytes()?);
+        // Optional trailing fields are only serialized if set, so as not to change the hash of
+        // chainspecs which don't use them.
+        let has_custom_native_transfer_gas_price =
+            self.deploy_config.has_custom_native_transfer_gas_price();
+        if !self.height_activation_points.is_empty() || has_custom_native_transfer_gas_price {
+            buffer.extend(self.height_activation_points.to_bytes()?);
+        }
+        if has_custom_native_transfer_gas_price {
+            buffer.extend(self.deploy_config.native_transfer_gas_price.to_bytes()?);
+        }
         Ok(buffer)
     }
 
@@ -148,6 +220,17 @@ impl ToBytes for Chainspec {
             + self.deploy_config.serialized_length()
             + self.wasm_config.serialized_length()
             + self.system_costs_config.serialized_length()
+            + if self.deploy_config.has_custom_native_transfer_gas_price() {
+                self.height_activation_points.serialized_length()
+                    + self
+                        .deploy_config
+                        .native_transfer_gas_price
+                        .serialized_length()
+            } else if self.height_activation_points.is_empty() {
+                0
+            } else {
+                self.height_activation_points.serialized_length()
+            }
     }
 }
 
@@ -157,9 +240,23 @@ impl FromBytes for Chainspec {
         let (network_config, remainder) = NetworkConfig::from_bytes(remainder)?;
         let (core_config, remainder) = CoreConfig::from_bytes(remainder)?;
         let (highway_config, remainder) = HighwayConfig::from_bytes(remainder)?;
-        let (deploy_config, remainder) = DeployConfig::from_bytes(remainder)?;
+        let (mut deploy_config, remainder) = DeployConfig::from_bytes(remainder)?;
         let (wasm_config, remainder) = WasmConfig::from_bytes(remainder)?;
         let (system_costs_config, remainder) = SystemConfig::from_bytes(remainder)?;
+        let (mut height_activation_points, remainder) = if remainder.is_empty() {
+            (vec![], remainder)
+        } else {
+            Vec::<HeightActivationPoint>::from_bytes(remainder)?
+        };
+        let (native_transfer_gas_price, remainder) = if remainder.is_empty() {
+            (deploy_config.native_transfer_gas_price, remainder)
+        } else {
+            u64::from_bytes(remainder)?
+        };
+        deploy_config.native_transfer_gas_price = native_transfer_gas_price;
+        for activation_point in &mut height_activation_points {
+            activation_point.deploys.native_transfer_gas_price = native_transfer_gas_price;
+        }
         let chainspec = Chainspec {
             protocol_config,
             network_config,
@@ -168,6 +265,7 @@ impl FromBytes for Chainspec {
             deploy_config,
             wasm_config,
             system_costs_config,
+            height_activation_points,
         };
         Ok((chainspec, remainder))
     }
@@ -177,7 +275,7 @@ impl Loadable for Chainspec {
     type Error = Error;
 
     fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Self::Error> {
-        parse_toml::parse_toml(path.as_ref().join(CHAINSPEC_NAME))
+        parse_toml::parse_chainspec_file(chainspec_file_path(path.as_ref()))
     }
 }
 
@@ -208,13 +306,16 @@ mod tests {
     use num_rational::Ratio;
     use once_cell::sync::Lazy;
 
-    use casper_execution_engine::shared::{
-        host_function_costs::{HostFunction, HostFunctionCosts},
-        motes::Motes,
-        opcode_costs::OpcodeCosts,
-        storage_costs::StorageCosts,
-        stored_value::StoredValue,
-        wasm_config::WasmConfig,
+    use casper_execution_engine::{
+        core::engine_state::WASMLESS_TRANSFER_FIXED_GAS_PRICE,
+        shared::{
+            host_function_costs::{HostFunction, HostFunctionCosts},
+            motes::Motes,
+            opcode_costs::OpcodeCosts,
+            storage_costs::StorageCosts,
+            stored_value::StoredValue,
+            wasm_config::WasmConfig,
+        },
     };
     use casper_types::{EraId, ProtocolVersion, U512};
 
@@ -373,6 +474,7 @@ mod tests {
         assert_eq!(spec.deploy_config.max_block_size, 12);
         assert_eq!(spec.deploy_config.block_max_deploy_count, 125);
         assert_eq!(spec.deploy_config.block_gas_limit, 13);
+        assert_eq!(spec.deploy_config.native_transfer_gas_price, 3);
 
         assert_eq!(spec.wasm_config, *EXPECTED_GENESIS_WASM_COSTS);
     }
@@ -393,7 +495,144 @@ mod tests {
         bytesrepr::test_serialization_roundtrip(&chainspec);
     }
 
-    #[ignore = "We probably need to reconsider our approach here"]
+    #[test]
+    fn should_not_serialize_empty_height_activation_points() {
+        let mut rng = crate::new_rng();
+        let mut chainspec = Chainspec::random(&mut rng);
+        chainspec.height_activation_points.clear();
+        chainspec.deploy_config.native_transfer_gas_price = WASMLESS_TRANSFER_FIXED_GAS_PRICE;
+
+        let mut expected = vec![];
+        expected.extend(chainspec.protocol_config.to_bytes().unwrap());
+        expected.extend(chainspec.network_config.to_bytes().unwrap());
+        expected.extend(chainspec.core_config.to_bytes().unwrap());
+        expected.extend(chainspec.highway_config.to_bytes().unwrap());
+        expected.extend(chainspec.deploy_config.to_bytes().unwrap());
+        expected.extend(chainspec.wasm_config.to_bytes().unwrap());
+        expected.extend(chainspec.system_costs_config.to_bytes().unwrap());
+        assert_eq!(chainspec.to_bytes().unwrap(), expected);
+        bytesrepr::test_serialization_roundtrip(&chainspec);
+    }
+
+    #[test]
+    fn should_serialize_custom_native_transfer_gas_price() {
+        let mut rng = crate::new_rng();
+        let mut chainspec = Chainspec::random(&mut rng);
+        chainspec.height_activation_points.clear();
+        chainspec.deploy_config.native_transfer_gas_price = WASMLESS_TRANSFER_FIXED_GAS_PRICE;
+        let default_hash = chainspec.hash();
+
+        chainspec.deploy_config.native_transfer_gas_price = WASMLESS_TRANSFER_FIXED_GAS_PRICE + 1;
+        assert_ne!(chainspec.hash(), default_hash);
+        bytesrepr::test_serialization_roundtrip(&chainspec);
+    }
+
+    #[test]
+    fn should_reject_native_transfer_gas_price_changed_at_height() {
+        let mut chainspec = Chainspec::from_resources("local");
+        let mut deploys = chainspec.deploy_config;
+        deploys.native_transfer_gas_price += 1;
+        chainspec.height_activation_points = vec![HeightActivationPoint {
+            height: 10,
+            deploys,
+        }];
+        assert_eq!(
+            chainspec.validate(),
+            Err(ValidationError::NativeTransferGasPriceChangedAtHeight { height: 10 })
+        );
+
+        chainspec.deploy_config.native_transfer_gas_price = 0;
+        assert_eq!(
+            chainspec.validate(),
+            Err(ValidationError::ZeroNativeTransferGasPrice)
+        );
+    }
+
+    #[test]
+    fn should_apply_deploy_config_of_height_activation_points() {
+        let mut rng = crate::new_rng();
+        let mut chainspec = Chainspec::from_resources("local");
+        let mut first_deploys = DeployConfig::random(&mut rng);
+        first_deploys.block_max_deploy_count = chainspec.deploy_config.block_max_deploy_count + 1;
+        let mut second_deploys = DeployConfig::random(&mut rng);
+        second_deploys.block_max_deploy_count = first_deploys.block_max_deploy_count + 1;
+        chainspec.height_activation_points = vec![
+            HeightActivationPoint {
+                height: 10,
+                deploys: first_deploys,
+            },
+            HeightActivationPoint {
+                height: 20,
+                deploys: second_deploys,
+            },
+        ];
+
+        // Write the chainspec out and parse it back, to check the activation points are loaded.
+        let tempdir = tempfile::tempdir().expect("should create temp dir");
+        let toml_chainspec = parse_toml::TomlChainspec::from(&chainspec);
+        fs::write(
+            tempdir.path().join(CHAINSPEC_NAME),
+            toml::to_string_pretty(&toml_chainspec).expect("should encode to toml"),
+        )
+        .expect("should write chainspec");
+        let loaded = Chainspec::from_path(tempdir.path()).expect("should load chainspec");
+        assert_eq!(
+            loaded.height_activation_points,
+            chainspec.height_activation_points
+        );
+
+        for height in [0, 9].iter().copied() {
+            assert_eq!(*loaded.deploy_config_at(height), chainspec.deploy_config);
+        }
+        for height in [10, 19].iter().copied() {
+            assert_eq!(*loaded.deploy_config_at(height), first_deploys);
+        }
+        for height in [20, u64::MAX].iter().copied() {
+            assert_eq!(*loaded.deploy_config_at(height), second_deploys);
+        }
+
+        // Activation points out of order are rejected at load time.
+        chainspec.height_activation_points.swap(0, 1);
+        let toml_chainspec = parse_toml::TomlChainspec::from(&chainspec);
+        fs::write(
+            tempdir.path().join(CHAINSPEC_NAME),
+            toml::to_string_pretty(&toml_chainspec).expect("should encode to toml"),
+        )
+        .expect("should write chainspec");
+        match Chainspec::from_path(tempdir.path()) {
+            Err(Error::HeightActivationPointsNotIncreasing {
+                previous_height: 20,
+                height: 10,
+            }) => (),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn should_load_same_chainspec_from_toml_and_json() {
+        let chainspec = Chainspec::from_resources("local");
+        let toml_chainspec = parse_toml::TomlChainspec::from(&chainspec);
+
+        let toml_dir = tempfile::tempdir().expect("should create temp dir");
+        fs::write(
+            toml_dir.path().join(CHAINSPEC_NAME),
+            toml::to_string_pretty(&toml_chainspec).expect("should encode to toml"),
+        )
+        .expect("should write chainspec");
+        let from_toml = Chainspec::from_path(toml_dir.path()).expect("should load toml chainspec");
+
+        let json_dir = tempfile::tempdir().expect("should create temp dir");
+        fs::write(
+            json_dir.path().join(CHAINSPEC_JSON_NAME),
+            serde_json::to_string_pretty(&toml_chainspec).expect("should encode to json"),
+        )
+        .expect("should write chainspec");
+        let from_json = Chainspec::from_path(json_dir.path()).expect("should load json chainspec");
+
+        assert_eq!(from_toml, from_json);
+        assert_eq!(from_toml.hash(), from_json.hash());
+    }
+
     #[test]
     fn should_have_deterministic_chainspec_hash() {
         const PATH: &str = "test/valid/0_9_0";
@@ -420,5 +659,14 @@ mod tests {
 
         // With equal hashes
         assert_eq!(chainspec.hash(), chainspec_unordered.hash());
+
+        // Also when constructed with the accounts in a different order.
+        let mut reordered = chainspec.clone();
+        let accounts_config = &chainspec.network_config.accounts_config;
+        reordered.network_config.accounts_config = AccountsConfig::new(
+            accounts_config.accounts().iter().rev().cloned().collect(),
+            accounts_config.delegators().iter().rev().cloned().collect(),
+        );
+        assert_eq!(chainspec.hash(), reordered.hash());
     }
 }
@@ -33,6 +33,8 @@ where
     Ok(vec)
 }
 
+/// The genesis accounts and delegators, each kept sorted so that the serialized form, and hence the
+/// chainspec hash, does not depend on the order in which they were specified.
 #[derive(PartialEq, Eq, Serialize, Deserialize, DataSize, Debug, Clone)]
 pub struct AccountsConfig {
     #[serde(deserialize_with = "sorted_vec_deserializer")]
@@ -42,7 +44,9 @@ pub struct AccountsConfig {
 }
 
 impl AccountsConfig {
-    pub fn new(accounts: Vec<AccountConfig>, delegators: Vec<DelegatorConfig>) -> Self {
+    pub fn new(mut accounts: Vec<AccountConfig>, mut delegators: Vec<DelegatorConfig>) -> Self {
+        accounts.sort_unstable();
+        delegators.sort_unstable();
         Self {
             accounts,
             delegators,
@@ -73,10 +77,7 @@ impl AccountsConfig {
 
         let delegators = vec![delegator];
 
-        AccountsConfig {
-            accounts,
-            delegators,
-        }
+        AccountsConfig::new(accounts, delegators)
     }
 }
 
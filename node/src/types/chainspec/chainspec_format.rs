@@ -0,0 +1,94 @@
+//! Detection of the format in which a chainspec file is encoded.
+
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use tracing::warn;
+
+use super::{Error, CHAINSPEC_JSON_NAME, CHAINSPEC_NAME};
+
+/// A description of the supported chainspec formats, for use in error messages.
+pub(super) const SUPPORTED_CHAINSPEC_FORMATS: &str = "TOML ('.toml') or JSON ('.json')";
+
+/// The format in which a chainspec file is encoded.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum ChainspecFormat {
+    Toml,
+    Json,
+}
+
+impl ChainspecFormat {
+    /// Returns the format indicated by the extension of the given file path.
+    pub(crate) fn from_path(path: &Path) -> Result<Self, Error> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Ok(ChainspecFormat::Toml),
+            Some("json") => Ok(ChainspecFormat::Json),
+            _ => Err(Error::UnsupportedFormat {
+                path: path.to_path_buf(),
+            }),
+        }
+    }
+
+    /// Decodes the given bytes in this format.
+    pub(crate) fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, Error> {
+        match self {
+            ChainspecFormat::Toml => Ok(toml::from_slice(bytes)?),
+            ChainspecFormat::Json => Ok(serde_json::from_slice(bytes)?),
+        }
+    }
+}
+
+/// Returns the path of the chainspec file in the given directory.
+///
+/// This is `chainspec.toml` unless only `chainspec.json` exists, in which case it is the latter.
+pub(crate) fn chainspec_file_path(dir: &Path) -> PathBuf {
+    let toml_path = dir.join(CHAINSPEC_NAME);
+    let json_path = dir.join(CHAINSPEC_JSON_NAME);
+    match (toml_path.is_file(), json_path.is_file()) {
+        (false, true) => json_path,
+        (true, true) => {
+            warn!(
+                dir = %dir.display(),
+                "found both TOML and JSON chainspec files, using {}", CHAINSPEC_NAME
+            );
+            toml_path
+        }
+        _ => toml_path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_detect_format_from_extension() {
+        assert_eq!(
+            ChainspecFormat::from_path(Path::new("dir/chainspec.toml")).unwrap(),
+            ChainspecFormat::Toml
+        );
+        assert_eq!(
+            ChainspecFormat::from_path(Path::new("dir/chainspec.json")).unwrap(),
+            ChainspecFormat::Json
+        );
+
+        for path in ["dir/chainspec.yaml", "dir/chainspec"].iter() {
+            let error = ChainspecFormat::from_path(Path::new(path)).unwrap_err();
+            assert!(matches!(error, Error::UnsupportedFormat { .. }));
+            assert!(error.to_string().contains(SUPPORTED_CHAINSPEC_FORMATS));
+        }
+    }
+
+    #[test]
+    fn should_prefer_toml_chainspec_file() {
+        let tempdir = tempfile::tempdir().expect("should create temp dir");
+        let toml_path = tempdir.path().join(CHAINSPEC_NAME);
+        let json_path = tempdir.path().join(CHAINSPEC_JSON_NAME);
+
+        assert_eq!(chainspec_file_path(tempdir.path()), toml_path);
+        std::fs::write(&json_path, "{}").unwrap();
+        assert_eq!(chainspec_file_path(tempdir.path()), json_path);
+        std::fs::write(&toml_path, "").unwrap();
+        assert_eq!(chainspec_file_path(tempdir.path()), toml_path);
+    }
+}
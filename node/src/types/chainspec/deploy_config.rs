@@ -10,12 +10,15 @@ use serde::{Deserialize, Serialize};
 
 #[cfg(test)]
 use casper_execution_engine::core::engine_state::MAX_PAYMENT_AMOUNT;
-use casper_execution_engine::shared::motes::Motes;
+use casper_execution_engine::{
+    core::engine_state::WASMLESS_TRANSFER_FIXED_GAS_PRICE, shared::motes::Motes,
+};
 use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
     U512,
 };
 
+use super::error::ValidationError;
 #[cfg(test)]
 use crate::testing::TestRng;
 use crate::types::TimeDiff;
@@ -35,6 +38,32 @@ pub struct DeployConfig {
     pub(crate) payment_args_max_length: u32,
     pub(crate) session_args_max_length: u32,
     pub(crate) native_transfer_minimum_motes: u64,
+    /// The gas/motes conversion rate applied to the cost of native transfers.
+    ///
+    /// This is not part of the bytesrepr serialization of the deploy config: the chainspec only
+    /// serializes it when it differs from the default, so that setting it is required to change
+    /// the chainspec hash.  It can't be changed by height activation points.
+    #[serde(default = "default_native_transfer_gas_price")]
+    pub(crate) native_transfer_gas_price: u64,
+}
+
+fn default_native_transfer_gas_price() -> u64 {
+    WASMLESS_TRANSFER_FIXED_GAS_PRICE
+}
+
+impl DeployConfig {
+    /// Returns whether the native transfer gas price differs from the default.
+    pub(super) fn has_custom_native_transfer_gas_price(&self) -> bool {
+        self.native_transfer_gas_price != default_native_transfer_gas_price()
+    }
+
+    /// Checks whether the values set in the config make sense and returns an error if they don't.
+    pub(super) fn validate(&self) -> Result<(), ValidationError> {
+        if self.native_transfer_gas_price == 0 {
+            return Err(ValidationError::ZeroNativeTransferGasPrice);
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -53,6 +82,8 @@ impl DeployConfig {
         let session_args_max_length = rng.gen();
         let native_transfer_minimum_motes =
             rng.gen_range(MAX_PAYMENT_AMOUNT..1_000_000_000_000_000);
+        // Not part of the serialized deploy config, see `Chainspec::random` for a random value.
+        let native_transfer_gas_price = default_native_transfer_gas_price();
 
         DeployConfig {
             max_payment_cost,
@@ -66,6 +97,7 @@ impl DeployConfig {
             payment_args_max_length,
             session_args_max_length,
             native_transfer_minimum_motes,
+            native_transfer_gas_price,
         }
     }
 }
@@ -85,6 +117,7 @@ impl Default for DeployConfig {
             payment_args_max_length: 1024,
             session_args_max_length: 1024,
             native_transfer_minimum_motes: MAX_PAYMENT_AMOUNT,
+            native_transfer_gas_price: WASMLESS_TRANSFER_FIXED_GAS_PRICE,
         }
     }
 }
@@ -147,6 +180,7 @@ impl FromBytes for DeployConfig {
             payment_args_max_length,
             session_args_max_length,
             native_transfer_minimum_motes,
+            native_transfer_gas_price: default_native_transfer_gas_price(),
         };
         Ok((config, remainder))
     }
@@ -171,4 +205,34 @@ mod tests {
         let decoded = toml::from_str(&encoded).unwrap();
         assert_eq!(config, decoded);
     }
+
+    #[test]
+    fn should_default_native_transfer_gas_price() {
+        let mut rng = crate::new_rng();
+        let mut config = DeployConfig::random(&mut rng);
+        config.native_transfer_gas_price = 7;
+        let encoded = toml::to_string_pretty(&config)
+            .unwrap()
+            .lines()
+            .filter(|line| !line.starts_with("native_transfer_gas_price"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let decoded: DeployConfig = toml::from_str(&encoded).unwrap();
+        assert_eq!(
+            decoded.native_transfer_gas_price,
+            WASMLESS_TRANSFER_FIXED_GAS_PRICE
+        );
+    }
+
+    #[test]
+    fn should_reject_zero_native_transfer_gas_price() {
+        let mut rng = crate::new_rng();
+        let mut config = DeployConfig::random(&mut rng);
+        assert!(config.validate().is_ok());
+        config.native_transfer_gas_price = 0;
+        assert_eq!(
+            config.validate(),
+            Err(ValidationError::ZeroNativeTransferGasPrice)
+        );
+    }
 }
@@ -1,11 +1,13 @@
 use std::{io, path::PathBuf};
 
+use num::rational::Ratio;
 use thiserror::Error;
 use uint::FromDecStrErr;
 
-use casper_types::account::ACCOUNT_HASH_LENGTH;
+use casper_types::{account::ACCOUNT_HASH_LENGTH, EraId};
 
-use crate::utils::ReadFileError;
+use super::chainspec_format::SUPPORTED_CHAINSPEC_FORMATS;
+use crate::{crypto::hash::Digest, utils::ReadFileError};
 
 /// Error returned by the ChainspecLoader.
 #[derive(Debug, Error)]
@@ -14,10 +16,25 @@ pub enum Error {
     #[error("decoding from TOML error: {0}")]
     DecodingFromToml(#[from] toml::de::Error),
 
+    /// Error while decoding the chainspec from JSON format.
+    #[error("decoding from JSON error: {0}")]
+    DecodingFromJson(#[from] serde_json::Error),
+
     /// Error while decoding Motes from a decimal format.
     #[error("decoding motes from base-10 error: {0}")]
     DecodingMotes(#[from] FromDecStrErr),
 
+    /// The chainspec file's extension does not indicate a supported format.
+    #[error(
+        "unsupported format of chainspec file {}: must be {}",
+        path.display(),
+        SUPPORTED_CHAINSPEC_FORMATS
+    )]
+    UnsupportedFormat {
+        /// The path of the chainspec file.
+        path: PathBuf,
+    },
+
     /// Error loading the chainspec.
     #[error("could not load chainspec: {0}")]
     LoadChainspec(ReadFileError),
@@ -49,6 +66,102 @@ pub enum Error {
         /// The searched directory.
         dir: PathBuf,
     },
+
+    /// The hash of the loaded chainspec differs from the one expected.
+    #[error(
+        "chainspec hash {actual_hash:?} does not match the expected hash {expected_hash:?}: the \
+         chainspec differs from the one the network is expected to run"
+    )]
+    HashMismatch {
+        /// The expected chainspec hash.
+        expected_hash: Digest,
+        /// The hash of the loaded chainspec.
+        actual_hash: Digest,
+    },
+
+    /// The height activation points are not in strictly increasing order of block height.
+    #[error(
+        "height activation point at block height {height} must be above the preceding one at \
+         block height {previous_height}"
+    )]
+    HeightActivationPointsNotIncreasing {
+        /// The height of the preceding activation point, or zero for the first one.
+        previous_height: u64,
+        /// The height of the offending activation point.
+        height: u64,
+    },
+}
+
+/// Error returned when the values set in a loaded chainspec don't make sense.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The last emergency restart is not before the activation point of a regular upgrade.
+    #[error(
+        "[protocol.last_emergency_restart] {last_emergency_restart} must be lower than \
+         [protocol.activation_point] {activation_point}"
+    )]
+    EmergencyRestartNotBeforeActivationPoint {
+        /// The era ID of the activation point.
+        activation_point: EraId,
+        /// The era ID of the last emergency restart.
+        last_emergency_restart: EraId,
+    },
+
+    /// A global state update was provided, but no last emergency restart.
+    #[error(
+        "[protocol.last_emergency_restart] must exist since a global state update was provided, \
+         implying this upgrade is an emergency restart"
+    )]
+    MissingLastEmergencyRestart,
+
+    /// A global state update was provided, but the last emergency restart differs from the
+    /// activation point.
+    #[error(
+        "[protocol.last_emergency_restart] {last_emergency_restart} must equal \
+         [protocol.activation_point] {activation_point} since a global state update was provided, \
+         implying this upgrade is an emergency restart"
+    )]
+    EmergencyRestartNotAtActivationPoint {
+        /// The era ID of the activation point.
+        activation_point: EraId,
+        /// The era ID of the last emergency restart.
+        last_emergency_restart: EraId,
+    },
+
+    /// The minimum round exponent is greater than the maximum one.
+    #[error(
+        "[highway.minimum_round_exponent] {min} is greater than \
+         [highway.maximum_round_exponent] {max}"
+    )]
+    RoundExponentsNotOrdered {
+        /// The minimum round exponent.
+        min: u8,
+        /// The maximum round exponent.
+        max: u8,
+    },
+
+    /// The finality threshold fraction is not in the range (0, 1).
+    #[error("[highway.finality_threshold_fraction] {0} is not in the range (0, 1)")]
+    FinalityThresholdFractionOutOfRange(Ratio<u64>),
+
+    /// The reduced reward multiplier is not in the range [0, 1].
+    #[error("[highway.reduced_reward_multiplier] {0} is not in the range [0, 1]")]
+    ReducedRewardMultiplierOutOfRange(Ratio<u64>),
+
+    /// The native transfer gas price is zero.
+    #[error("[deploys.native_transfer_gas_price] must be greater than zero")]
+    ZeroNativeTransferGasPrice,
+
+    /// A height activation point sets a native transfer gas price different from the one of the
+    /// chainspec.
+    #[error(
+        "native transfer gas price of the height activation point at block height {height} must \
+         equal [deploys.native_transfer_gas_price]"
+    )]
+    NativeTransferGasPriceChangedAtHeight {
+        /// The height of the offending activation point.
+        height: u64,
+    },
 }
 
 /// Error loading chainspec accounts file.
@@ -0,0 +1,127 @@
+use datasize::DataSize;
+#[cfg(test)]
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+
+use super::{DeployConfig, Error};
+#[cfg(test)]
+use crate::testing::TestRng;
+
+/// A set of deploy parameters which replaces the preceding one from a given block height onwards.
+#[derive(Copy, Clone, DataSize, PartialEq, Eq, Serialize, Deserialize, Debug)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct HeightActivationPoint {
+    /// The height of the first block to which `deploys` applies.
+    pub(crate) height: u64,
+    /// The deploy parameters.
+    pub(crate) deploys: DeployConfig,
+}
+
+impl HeightActivationPoint {
+    /// Returns an error unless the heights of the given activation points are strictly increasing,
+    /// starting above the genesis block height of zero.
+    pub(super) fn validate_heights(
+        activation_points: &[HeightActivationPoint],
+    ) -> Result<(), Error> {
+        let mut previous_height = 0;
+        for activation_point in activation_points {
+            if activation_point.height <= previous_height {
+                return Err(Error::HeightActivationPointsNotIncreasing {
+                    previous_height,
+                    height: activation_point.height,
+                });
+            }
+            previous_height = activation_point.height;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl HeightActivationPoint {
+    /// Generates a random instance using a `TestRng`.
+    pub fn random(rng: &mut TestRng) -> Self {
+        HeightActivationPoint {
+            height: rng.gen_range(1..1_000_000),
+            deploys: DeployConfig::random(rng),
+        }
+    }
+}
+
+impl ToBytes for HeightActivationPoint {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.height.to_bytes()?);
+        buffer.extend(self.deploys.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.height.serialized_length() + self.deploys.serialized_length()
+    }
+}
+
+impl FromBytes for HeightActivationPoint {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (height, remainder) = u64::from_bytes(bytes)?;
+        let (deploys, remainder) = DeployConfig::from_bytes(remainder)?;
+        let activation_point = HeightActivationPoint { height, deploys };
+        Ok((activation_point, remainder))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytesrepr_roundtrip() {
+        let mut rng = crate::new_rng();
+        let activation_point = HeightActivationPoint::random(&mut rng);
+        bytesrepr::test_serialization_roundtrip(&activation_point);
+    }
+
+    #[test]
+    fn should_reject_heights_not_strictly_increasing() {
+        let mut rng = crate::new_rng();
+        let mut activation_points = vec![
+            HeightActivationPoint::random(&mut rng),
+            HeightActivationPoint::random(&mut rng),
+        ];
+        activation_points[0].height = 10;
+        activation_points[1].height = 20;
+        assert!(HeightActivationPoint::validate_heights(&activation_points).is_ok());
+
+        // Equal heights would overlap.
+        activation_points[1].height = 10;
+        match HeightActivationPoint::validate_heights(&activation_points) {
+            Err(Error::HeightActivationPointsNotIncreasing {
+                previous_height: 10,
+                height: 10,
+            }) => (),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        activation_points[1].height = 5;
+        match HeightActivationPoint::validate_heights(&activation_points) {
+            Err(Error::HeightActivationPointsNotIncreasing {
+                previous_height: 10,
+                height: 5,
+            }) => (),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        // The chainspec's own deploy parameters apply from genesis.
+        activation_points[0].height = 0;
+        match HeightActivationPoint::validate_heights(&activation_points[..1]) {
+            Err(Error::HeightActivationPointsNotIncreasing {
+                previous_height: 0,
+                height: 0,
+            }) => (),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
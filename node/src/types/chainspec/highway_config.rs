@@ -3,10 +3,11 @@ use num::rational::Ratio;
 #[cfg(test)]
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use tracing::error;
 
 use casper_types::bytesrepr::{self, FromBytes, ToBytes};
 
+use super::error::ValidationError;
+
 #[cfg(test)]
 use crate::testing::TestRng;
 use crate::types::TimeDiff;
@@ -26,36 +27,30 @@ pub(crate) struct HighwayConfig {
 }
 
 impl HighwayConfig {
-    /// Checks whether the values set in the config make sense and returns `false` if they don't.
-    pub(super) fn is_valid(&self) -> bool {
+    /// Checks whether the values set in the config make sense and returns an error if they don't.
+    pub(super) fn validate(&self) -> Result<(), ValidationError> {
         if self.minimum_round_exponent > self.maximum_round_exponent {
-            error!(
-                min = %self.minimum_round_exponent,
-                max = %self.maximum_round_exponent,
-                "minimum round exponent is greater than the maximum round exponent",
-            );
-            return false;
+            return Err(ValidationError::RoundExponentsNotOrdered {
+                min: self.minimum_round_exponent,
+                max: self.maximum_round_exponent,
+            });
         }
 
         if self.finality_threshold_fraction <= Ratio::new(0, 1)
             || self.finality_threshold_fraction >= Ratio::new(1, 1)
         {
-            error!(
-                ftf = %self.finality_threshold_fraction,
-                "finality threshold fraction is not in the range (0, 1)",
-            );
-            return false;
+            return Err(ValidationError::FinalityThresholdFractionOutOfRange(
+                self.finality_threshold_fraction,
+            ));
         }
 
         if self.reduced_reward_multiplier > Ratio::new(1, 1) {
-            error!(
-                rrm = %self.reduced_reward_multiplier,
-                "reduced reward multiplier is not in the range [0, 1]",
-            );
-            return false;
+            return Err(ValidationError::ReducedRewardMultiplierOutOfRange(
+                self.reduced_reward_multiplier,
+            ));
         }
 
-        true
+        Ok(())
     }
 
     /// Returns the length of the shortest allowed round.
@@ -143,13 +138,13 @@ mod tests {
 
         // Should be valid for round exponents where min <= max.
         highway_config.minimum_round_exponent = highway_config.maximum_round_exponent - 1;
-        assert!(highway_config.is_valid());
+        assert!(highway_config.validate().is_ok());
         highway_config.minimum_round_exponent = highway_config.maximum_round_exponent;
-        assert!(highway_config.is_valid());
+        assert!(highway_config.validate().is_ok());
 
         // Should be invalid for round exponents where min > max.
         highway_config.minimum_round_exponent = highway_config.maximum_round_exponent + 1;
-        assert!(!highway_config.is_valid());
+        assert!(highway_config.validate().is_err());
     }
 
     #[test]
@@ -159,19 +154,19 @@ mod tests {
 
         // Should be valid for FTT > 0 and < 1.
         highway_config.finality_threshold_fraction = Ratio::new(1, u64::MAX);
-        assert!(highway_config.is_valid());
+        assert!(highway_config.validate().is_ok());
         highway_config.finality_threshold_fraction = Ratio::new(u64::MAX - 1, u64::MAX);
-        assert!(highway_config.is_valid());
+        assert!(highway_config.validate().is_ok());
 
         // Should be invalid for FTT == 0 or >= 1.
         highway_config.finality_threshold_fraction = Ratio::new(0, 1);
-        assert!(!highway_config.is_valid());
+        assert!(highway_config.validate().is_err());
         highway_config.finality_threshold_fraction = Ratio::new(1, 1);
-        assert!(!highway_config.is_valid());
+        assert!(highway_config.validate().is_err());
         highway_config.finality_threshold_fraction = Ratio::new(u64::MAX, u64::MAX);
-        assert!(!highway_config.is_valid());
+        assert!(highway_config.validate().is_err());
         highway_config.finality_threshold_fraction = Ratio::new(u64::MAX, u64::MAX - 1);
-        assert!(!highway_config.is_valid());
+        assert!(highway_config.validate().is_err());
     }
 
     #[test]
@@ -181,14 +176,14 @@ mod tests {
 
         // Should be valid for 0 <= RRM <= 1.
         highway_config.reduced_reward_multiplier = Ratio::new(0, 1);
-        assert!(highway_config.is_valid());
+        assert!(highway_config.validate().is_ok());
         highway_config.reduced_reward_multiplier = Ratio::new(1, 1);
-        assert!(highway_config.is_valid());
+        assert!(highway_config.validate().is_ok());
         highway_config.reduced_reward_multiplier = Ratio::new(u64::MAX, u64::MAX);
-        assert!(highway_config.is_valid());
+        assert!(highway_config.validate().is_ok());
 
         // Should be invalid for RRM > 1.
         highway_config.reduced_reward_multiplier = Ratio::new(u64::MAX, u64::MAX - 1);
-        assert!(!highway_config.is_valid());
+        assert!(highway_config.validate().is_err());
     }
 }
@@ -1,5 +1,5 @@
-//! Helper struct and function for parsing a chainspec configuration file into its respective domain
-//! object.
+//! Helper struct and function for parsing a chainspec configuration file, encoded as either TOML or
+//! JSON, into its respective domain object.
 //!
 //! This is necessary because the `network_config` field of the `Chainspec` struct contains a `Vec`
 //! of chainspec accounts, but the chainspec toml file contains a path to a further file which
@@ -15,8 +15,8 @@ use casper_types::{EraId, ProtocolVersion};
 
 use super::{
     accounts_config::AccountsConfig, global_state_update::GlobalStateUpdateConfig, ActivationPoint,
-    Chainspec, CoreConfig, DeployConfig, Error, GlobalStateUpdate, HighwayConfig, NetworkConfig,
-    ProtocolConfig,
+    Chainspec, ChainspecFormat, CoreConfig, DeployConfig, Error, GlobalStateUpdate,
+    HeightActivationPoint, HighwayConfig, NetworkConfig, ProtocolConfig,
 };
 use crate::utils::{self, Loadable};
 
@@ -38,7 +38,7 @@ struct TomlProtocol {
     last_emergency_restart: Option<EraId>,
 }
 
-/// A chainspec configuration as laid out in the TOML-encoded configuration file.
+/// A chainspec configuration as laid out in the TOML- or JSON-encoded configuration file.
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
 #[serde(deny_unknown_fields)]
@@ -50,6 +50,8 @@ pub(super) struct TomlChainspec {
     highway: HighwayConfig,
     wasm: WasmConfig,
     system_costs: SystemConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    height_activation_points: Vec<HeightActivationPoint>,
 }
 
 impl From<&Chainspec> for TomlChainspec {
@@ -69,6 +71,7 @@ impl From<&Chainspec> for TomlChainspec {
         let highway = chainspec.highway_config;
         let wasm = chainspec.wasm_config;
         let system_costs = chainspec.system_costs_config;
+        let height_activation_points = chainspec.height_activation_points.clone();
 
         TomlChainspec {
             protocol,
@@ -78,13 +81,17 @@ impl From<&Chainspec> for TomlChainspec {
             highway,
             wasm,
             system_costs,
+            height_activation_points,
         }
     }
 }
 
-pub(super) fn parse_toml<P: AsRef<Path>>(chainspec_path: P) -> Result<Chainspec, Error> {
+/// Parses the chainspec file at the given path, in the format indicated by its extension.
+pub(super) fn parse_chainspec_file<P: AsRef<Path>>(chainspec_path: P) -> Result<Chainspec, Error> {
+    let format = ChainspecFormat::from_path(chainspec_path.as_ref())?;
     let bytes = utils::read_file(chainspec_path.as_ref()).map_err(Error::LoadChainspec)?;
-    let toml_chainspec: TomlChainspec = toml::from_slice(&bytes)?;
+    let toml_chainspec: TomlChainspec = format.decode(&bytes)?;
+    HeightActivationPoint::validate_heights(&toml_chainspec.height_activation_points)?;
 
     let root = chainspec_path
         .as_ref()
@@ -120,5 +127,6 @@ pub(super) fn parse_toml<P: AsRef<Path>>(chainspec_path: P) -> Result<Chainspec,
         highway_config: toml_chainspec.highway,
         wasm_config: toml_chainspec.wasm,
         system_costs_config: toml_chainspec.system_costs,
+        height_activation_points: toml_chainspec.height_activation_points,
     })
 }
@@ -7,14 +7,13 @@ use datasize::DataSize;
 #[cfg(test)]
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use tracing::error;
 
 use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
     EraId, ProtocolVersion,
 };
 
-use super::{ActivationPoint, GlobalStateUpdate};
+use super::{error::ValidationError, ActivationPoint, GlobalStateUpdate};
 #[cfg(test)]
 use crate::testing::TestRng;
 
@@ -35,51 +34,37 @@ pub struct ProtocolConfig {
 }
 
 impl ProtocolConfig {
-    /// Checks whether the values set in the config make sense and returns `false` if they don't.
-    pub(super) fn is_valid(&self) -> bool {
+    /// Checks whether the values set in the config make sense and returns an error if they don't.
+    pub(super) fn validate(&self) -> Result<(), ValidationError> {
         // If this is not an emergency restart config, assert the `last_emergency_restart` is `None`
         // or less than `activation_point`.
         if self.global_state_update.is_none() {
             if let Some(last_emergency_restart) = self.last_emergency_restart {
                 let activation_point = self.activation_point.era_id();
                 if last_emergency_restart >= activation_point {
-                    error!(
-                        %activation_point,
-                        %last_emergency_restart,
-                        "[protocol.last_emergency_restart] must be lower than \
-                        [protocol.activation_point] in the chainspec."
-                    );
-                    return false;
+                    return Err(ValidationError::EmergencyRestartNotBeforeActivationPoint {
+                        activation_point,
+                        last_emergency_restart,
+                    });
                 };
             }
-            return true;
+            return Ok(());
         }
 
         // If this IS an emergency restart config, assert the `last_emergency_restart` is `Some` and
         // equal to `activation_point`.
-        let last_emergency_restart = match self.last_emergency_restart {
-            Some(era_id) => era_id,
-            None => {
-                error!(
-                    "[protocol.last_emergency_restart] must exist in the chainspec since a global \
-                    state update was provided, implying this upgrade is an emergency restart."
-                );
-                return false;
-            }
-        };
+        let last_emergency_restart = self
+            .last_emergency_restart
+            .ok_or(ValidationError::MissingLastEmergencyRestart)?;
         let activation_point = self.activation_point.era_id();
         if activation_point != last_emergency_restart {
-            error!(
-                %activation_point,
-                %last_emergency_restart,
-                "[protocol.last_emergency_restart] must equal [protocol.activation_point] in the \
-                chainspec since a global state update was provided, implying this upgrade is an \
-                emergency restart."
-            );
-            return false;
+            return Err(ValidationError::EmergencyRestartNotAtActivationPoint {
+                activation_point,
+                last_emergency_restart,
+            });
         }
 
-        true
+        Ok(())
     }
 
     /// Generates a random instance using a `TestRng`.
@@ -179,14 +164,14 @@ mod tests {
         // also `None`.
         protocol_config.global_state_update = None;
         protocol_config.last_emergency_restart = None;
-        assert!(protocol_config.is_valid());
+        assert!(protocol_config.validate().is_ok());
 
         // If `global_state_update` is `None` then config is valid if `last_emergency_restart` is
         // less than `activation_point`.
         let activation_point = EraId::new(rng.gen_range(2..u64::MAX));
         protocol_config.activation_point = ActivationPoint::EraId(activation_point);
         protocol_config.last_emergency_restart = Some(activation_point - 1);
-        assert!(protocol_config.is_valid());
+        assert!(protocol_config.validate().is_ok());
     }
 
     #[test]
@@ -198,7 +183,10 @@ mod tests {
         // `last_emergency_restart` should be `Some`.
         protocol_config.global_state_update = Some(GlobalStateUpdate::random(&mut rng));
         protocol_config.last_emergency_restart = None;
-        assert!(!protocol_config.is_valid());
+        assert_eq!(
+            protocol_config.validate(),
+            Err(ValidationError::MissingLastEmergencyRestart)
+        );
     }
 
     #[test]
@@ -214,10 +202,10 @@ mod tests {
         protocol_config.activation_point = ActivationPoint::EraId(activation_point);
 
         protocol_config.last_emergency_restart = Some(activation_point + 1);
-        assert!(!protocol_config.is_valid());
+        assert!(protocol_config.validate().is_err());
 
         protocol_config.last_emergency_restart = Some(activation_point - 1);
-        assert!(!protocol_config.is_valid());
+        assert!(protocol_config.validate().is_err());
     }
 
     #[test]
@@ -232,9 +220,9 @@ mod tests {
         protocol_config.global_state_update = None;
         protocol_config.activation_point = ActivationPoint::EraId(activation_point);
         protocol_config.last_emergency_restart = Some(activation_point);
-        assert!(!protocol_config.is_valid());
+        assert!(protocol_config.validate().is_err());
 
         protocol_config.last_emergency_restart = Some(activation_point + 1);
-        assert!(!protocol_config.is_valid());
+        assert!(protocol_config.validate().is_err());
     }
 }
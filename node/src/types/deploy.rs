@@ -98,7 +98,7 @@ static DEPLOY: Lazy<Deploy> = Lazy::new(|| {
 });
 
 /// A representation of the way in which a deploy failed validation checks.
-#[derive(Clone, DataSize, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Error)]
+#[derive(Clone, DataSize, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Error, Serialize)]
 pub enum DeployValidationFailure {
     /// Invalid chain name.
     #[error("invalid chain name: expected {expected}, got {got}")]
@@ -118,6 +118,13 @@ pub enum DeployValidationFailure {
         got: usize,
     },
 
+    /// Deploy lists itself among its dependencies.
+    ///
+    /// Since the hash of a deploy covers its dependencies, a deploy can only depend on deploys
+    /// created before it, so this is the only way for dependencies to form a cycle.
+    #[error("deploy depends on itself")]
+    CyclicDependency,
+
     /// Deploy is too large.
     #[error("deploy size too large: {0}")]
     ExcessiveSize(#[from] ExcessiveSizeError),
@@ -185,7 +192,7 @@ pub enum DeployValidationFailure {
 }
 
 /// Error returned when a Deploy is too large.
-#[derive(Clone, DataSize, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Error)]
+#[derive(Clone, DataSize, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Error, Serialize)]
 #[error("deploy size of {actual_deploy_size} bytes exceeds limit of {max_deploy_size}")]
 pub struct ExcessiveSizeError {
     /// The maximum permitted serialized deploy size, in bytes.
@@ -603,6 +610,36 @@ impl Deploy {
         &self.hash
     }
 
+    /// Computes the hash of this deploy from its header.
+    ///
+    /// The header covers the body only via its body hash, so use `verify_hash` to check both the
+    /// deploy hash and the body hash against the contents of this deploy.
+    pub fn compute_hash(&self) -> DeployHash {
+        DeployHash::new(hash::hash(&serialize_header(&self.header)))
+    }
+
+    /// Returns `Ok` if and only if:
+    ///   * the body hash is correct (should be the hash of the body), and
+    ///   * the deploy hash is correct (should be the hash of the header)
+    ///
+    /// Unlike `is_valid`, this does not verify the approvals, so it is cheap to call.
+    pub fn verify_hash(&self) -> Result<(), DeployValidationFailure> {
+        let serialized_body = serialize_body(&self.payment, &self.session);
+        let body_hash = hash::hash(&serialized_body);
+        if body_hash != self.header.body_hash {
+            warn!(deploy = ?self, ?body_hash, "invalid deploy body hash");
+            return Err(DeployValidationFailure::InvalidBodyHash);
+        }
+
+        let hash = self.compute_hash();
+        if hash != self.hash {
+            warn!(deploy = ?self, ?hash, "invalid deploy hash");
+            return Err(DeployValidationFailure::InvalidDeployHash);
+        }
+
+        Ok(())
+    }
+
     /// Returns a reference to the `DeployHeader` of this `Deploy`.
     pub fn header(&self) -> &DeployHeader {
         &self.header
@@ -735,6 +772,15 @@ impl Deploy {
             });
         }
 
+        if header.dependencies().contains(self.id()) {
+            info!(
+                deploy_hash = %self.id(),
+                deploy_header = %header,
+                "deploy depends on itself"
+            );
+            return Err(DeployValidationFailure::CyclicDependency);
+        }
+
         if header.ttl() > config.max_ttl {
             info!(
                 deploy_hash = %self.id(),
@@ -854,19 +900,7 @@ fn serialize_body(payment: &ExecutableDeployItem, session: &ExecutableDeployItem
 // Computationally expensive validity check for a given deploy instance, including
 // asymmetric_key signing verification.
 fn validate_deploy(deploy: &Deploy) -> Result<(), DeployValidationFailure> {
-    let serialized_body = serialize_body(&deploy.payment, &deploy.session);
-    let body_hash = hash::hash(&serialized_body);
-    if body_hash != deploy.header.body_hash {
-        warn!(?deploy, ?body_hash, "invalid deploy body hash");
-        return Err(DeployValidationFailure::InvalidBodyHash);
-    }
-
-    let serialized_header = serialize_header(&deploy.header);
-    let hash = DeployHash::new(hash::hash(&serialized_header));
-    if hash != deploy.hash {
-        warn!(?deploy, ?hash, "invalid deploy hash");
-        return Err(DeployValidationFailure::InvalidDeployHash);
-    }
+    deploy.verify_hash()?;
 
     // We don't need to check for an empty set here. EE checks that the correct number and weight of
     // signatures are provided when executing the deploy, so all we need to do here is check that
@@ -1119,6 +1153,28 @@ mod tests {
         check_is_not_valid(deploy, DeployValidationFailure::InvalidDeployHash);
     }
 
+    #[test]
+    fn verify_hash_should_fail_if_body_mutated() {
+        let mut rng = crate::new_rng();
+        let mut deploy = create_deploy(&mut rng, DeployConfig::default().max_ttl, 0, "net-1");
+        assert_eq!(deploy.compute_hash(), *deploy.id());
+        assert_eq!(deploy.verify_hash(), Ok(()));
+
+        deploy.payment = ExecutableDeployItem::ModuleBytes {
+            module_bytes: Bytes::new(),
+            args: runtime_args! {
+                "amount" => 2
+            },
+        };
+        assert_eq!(
+            deploy.verify_hash(),
+            Err(DeployValidationFailure::InvalidBodyHash)
+        );
+
+        // The deploy hash only covers the body via the body hash in the header.
+        assert_eq!(deploy.compute_hash(), *deploy.id());
+    }
+
     #[test]
     fn not_valid_due_to_invalid_approval() {
         let mut rng = crate::new_rng();
@@ -1212,6 +1268,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn not_acceptable_due_to_cyclic_dependency() {
+        let mut rng = crate::new_rng();
+        let chain_name = "net-1";
+        let deploy_config = DeployConfig::default();
+
+        let mut deploy = create_deploy(&mut rng, deploy_config.max_ttl, 1, chain_name);
+        deploy.header.dependencies = vec![deploy.hash];
+
+        assert_eq!(
+            deploy.is_acceptable(chain_name, &deploy_config),
+            Err(DeployValidationFailure::CyclicDependency)
+        );
+        assert!(
+            deploy.is_valid.is_none(),
+            "deploy should not have run expensive `is_valid` call"
+        );
+    }
+
     #[test]
     fn not_acceptable_due_to_excessive_ttl() {
         let mut rng = crate::new_rng();
@@ -62,6 +62,13 @@ pub struct Account {
     action_thresholds: ActionThresholds,
 }
 
+impl Account {
+    /// Returns the main purse of the account.
+    pub fn main_purse(&self) -> URef {
+        self.main_purse
+    }
+}
+
 impl From<&ExecutionEngineAccount> for Account {
     fn from(ee_account: &ExecutionEngineAccount) -> Self {
         Account {
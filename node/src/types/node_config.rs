@@ -1,7 +1,7 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
-use crate::types::BlockHash;
+use crate::{crypto::hash::Digest, types::BlockHash};
 
 /// Node configuration.
 #[derive(Default, DataSize, Debug, Deserialize, Serialize)]
@@ -10,4 +10,8 @@ use crate::types::BlockHash;
 pub struct NodeConfig {
     /// Hash used as a trust anchor when joining, if any.
     pub trusted_hash: Option<BlockHash>,
+
+    /// Hash which the loaded chainspec is required to have, if any.  The node refuses to start if
+    /// the hashes differ.
+    pub expected_chainspec_hash: Option<Digest>,
 }
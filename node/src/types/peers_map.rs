@@ -1,12 +1,12 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, time::Duration};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::types::NodeId;
+use crate::types::{NodeId, TimeDiff};
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -32,3 +32,74 @@ impl From<BTreeMap<NodeId, String>> for PeersMap {
         PeersMap(ret)
     }
 }
+
+/// The direction of a connection to a peer.
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionDirection {
+    /// The peer connected to us.
+    Incoming,
+    /// We connected to the peer.
+    Outgoing,
+}
+
+/// The state of a connection to a peer.
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerConnectionState {
+    /// The connection is being established, which includes the TLS and protocol handshakes.
+    Connecting,
+    /// The connection has been established.
+    Connected,
+}
+
+/// A connection to a peer, as reported by the networking component.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeerConnection<I> {
+    /// The ID of the peer, unknown until the handshake has completed.
+    pub node_id: Option<I>,
+    /// The network address of the peer.
+    pub address: String,
+    /// The direction of the connection.
+    pub direction: ConnectionDirection,
+    /// The state of the connection.
+    pub state: PeerConnectionState,
+    /// The time elapsed since the connection was established, or since the current attempt to
+    /// establish it started.  `None` if not tracked by the networking component.
+    pub age: Option<Duration>,
+    /// The round-trip time of the most recent keepalive acknowledged by the peer, if any.
+    pub round_trip_time: Option<Duration>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct PeerConnectionEntry {
+    node_id: Option<String>,
+    address: String,
+    direction: ConnectionDirection,
+    state: PeerConnectionState,
+    age: Option<TimeDiff>,
+    round_trip_time: Option<TimeDiff>,
+}
+
+/// List of connections to peers, including the ones still being established.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PeerConnections(Vec<PeerConnectionEntry>);
+
+impl From<Vec<PeerConnection<NodeId>>> for PeerConnections {
+    fn from(input: Vec<PeerConnection<NodeId>>) -> Self {
+        let ret = input
+            .into_iter()
+            .map(|connection| PeerConnectionEntry {
+                node_id: connection.node_id.map(|node_id| node_id.to_string()),
+                address: connection.address,
+                direction: connection.direction,
+                state: connection.state,
+                age: connection.age.map(TimeDiff::from),
+                round_trip_time: connection.round_trip_time.map(TimeDiff::from),
+            })
+            .collect();
+        PeerConnections(ret)
+    }
+}
@@ -17,6 +17,7 @@ use crate::{
     components::{
         chainspec_loader::NextUpgrade,
         rpc_server::rpcs::docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
+        storage,
     },
     crypto::{hash::Digest, AsymmetricKeyExt},
     types::{ActivationPoint, Block, BlockHash, NodeId, PeersMap, TimeDiff, Timestamp},
@@ -46,6 +47,8 @@ static GET_STATUS_RESULT: Lazy<GetStatusResult> = Lazy::new(|| {
         our_public_signing_key: Some(PublicKey::doc_example().clone()),
         round_length: Some(TimeDiff::from(1 << 16)),
         version: crate::VERSION_STRING.as_str(),
+        is_syncing: false,
+        uptime: TimeDiff::from(13 * 60 * 60 * 1000),
     };
     GetStatusResult::new(status_feed, DOCS_EXAMPLE_PROTOCOL_VERSION)
 });
@@ -98,6 +101,10 @@ pub struct StatusFeed<I> {
     pub round_length: Option<TimeDiff>,
     /// The compiled node version.
     pub version: &'static str,
+    /// Whether the node is still syncing the linear chain before participating in the network.
+    pub is_syncing: bool,
+    /// The time elapsed since the node started.
+    pub uptime: TimeDiff,
 }
 
 impl<I> StatusFeed<I> {
@@ -106,6 +113,7 @@ impl<I> StatusFeed<I> {
         peers: BTreeMap<I, String>,
         chainspec_info: ChainspecInfo,
         consensus_status: Option<(PublicKey, Option<TimeDiff>)>,
+        is_syncing: bool,
     ) -> Self {
         let (our_public_signing_key, round_length) = match consensus_status {
             Some((public_key, round_length)) => (Some(public_key), round_length),
@@ -118,6 +126,8 @@ impl<I> StatusFeed<I> {
             our_public_signing_key,
             round_length,
             version: crate::VERSION_STRING.as_str(),
+            is_syncing,
+            uptime: crate::NODE_START_TIME.elapsed(),
         }
     }
 }
@@ -170,6 +180,12 @@ pub struct GetStatusResult {
     pub next_upgrade: Option<NextUpgrade>,
     /// The compiled node version.
     pub build_version: String,
+    /// Whether the node is still syncing the linear chain before participating in the network.
+    pub is_syncing: bool,
+    /// The time elapsed since the node started.
+    pub uptime: TimeDiff,
+    /// The version of the storage schema, i.e. the layout of the node's databases.
+    pub storage_schema_version: u32,
 }
 
 impl GetStatusResult {
@@ -184,6 +200,9 @@ impl GetStatusResult {
             round_length: status_feed.round_length,
             next_upgrade: status_feed.chainspec_info.next_upgrade,
             build_version: crate::VERSION_STRING.clone(),
+            is_syncing: status_feed.is_syncing,
+            uptime: status_feed.uptime,
+            storage_schema_version: storage::SCHEMA_VERSION,
         }
     }
 }
@@ -193,3 +212,36 @@ impl DocExample for GetStatusResult {
         &*GET_STATUS_RESULT
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestRng;
+
+    #[test]
+    fn status_should_report_sync_state_and_uptime() {
+        let mut rng = TestRng::new();
+        let block = Block::random(&mut rng);
+        let mut peers = BTreeMap::new();
+        for _ in 0..3 {
+            peers.insert(NodeId::random(&mut rng), "127.0.0.1:34553".to_string());
+        }
+        let chainspec_info = ChainspecInfo::doc_example().clone();
+
+        let status_feed = StatusFeed::new(Some(block.clone()), peers, chainspec_info, None, true);
+        let status = GetStatusResult::new(status_feed, ProtocolVersion::V1_0_0);
+        let json = serde_json::to_value(&status).unwrap();
+
+        let last_added_block_info = &json["last_added_block_info"];
+        assert_eq!(
+            last_added_block_info["era_id"],
+            serde_json::to_value(block.header().era_id()).unwrap()
+        );
+        assert_eq!(last_added_block_info["height"], block.height());
+        assert_eq!(json["peers"].as_array().unwrap().len(), 3);
+        assert_eq!(json["is_syncing"], true);
+        let uptime: TimeDiff = json["uptime"].as_str().unwrap().parse().unwrap();
+        assert!(uptime <= crate::NODE_START_TIME.elapsed());
+        assert_eq!(json["storage_schema_version"], storage::SCHEMA_VERSION);
+    }
+}
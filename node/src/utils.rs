@@ -7,6 +7,7 @@ pub mod ds;
 mod external;
 pub mod milliseconds;
 pub mod pid_file;
+mod rate_limiter;
 #[cfg(target_os = "linux")]
 pub(crate) mod rlimit;
 mod round_robin;
@@ -40,6 +41,7 @@ pub(crate) use display_error::display_error;
 #[cfg(test)]
 pub use external::RESOURCES_PATH;
 pub use external::{External, LoadError, Loadable};
+pub(crate) use rate_limiter::ClientRateLimiter;
 pub(crate) use round_robin::WeightedRoundRobin;
 
 /// Sensible default for many if not all systems.
@@ -0,0 +1,149 @@
+//! Per-client rate limiting of requests, e.g. to the JSON-RPC server or from network peers.
+//!
+//! Each client, identified by e.g. its IP address or node ID, is assigned a token bucket which
+//! holds up to the configured burst size of tokens, and which is refilled at the configured rate
+//! per second.  Every request takes a single token from its client's bucket, and is rejected if the
+//! bucket is empty.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+/// The number of clients tracked before buckets which have refilled completely are purged.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// The tokens available to a single client.
+#[derive(Debug)]
+struct TokenBucket {
+    /// The number of tokens in the bucket as at `last_refill`.
+    tokens: f64,
+    /// The time at which `tokens` was last updated.
+    last_refill: Instant,
+}
+
+/// A token-bucket rate limiter keyed by client, by default by client IP address.
+#[derive(Debug)]
+pub(crate) struct ClientRateLimiter<C = IpAddr> {
+    /// The number of tokens added to each bucket per second.  If zero, no client is limited.
+    refill_rate: f64,
+    /// The maximum number of tokens each bucket can hold.
+    burst_size: f64,
+    /// Clients which are never limited.
+    whitelist: HashSet<C>,
+    /// The bucket of each client which has made a request.
+    buckets: HashMap<C, TokenBucket>,
+}
+
+impl<C: Copy + Eq + Hash> ClientRateLimiter<C> {
+    /// Creates a new rate limiter allowing each client `qps_limit` requests per second, with bursts
+    /// of up to `burst_limit` requests.
+    pub(crate) fn new(qps_limit: u64, burst_limit: u64, whitelist: &[C]) -> Self {
+        ClientRateLimiter {
+            refill_rate: qps_limit as f64,
+            burst_size: burst_limit.max(1) as f64,
+            whitelist: whitelist.iter().copied().collect(),
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Takes a token for a request from the given client made at `now`.
+    ///
+    /// Returns the time the client should wait before retrying if it has exceeded its limit.
+    pub(crate) fn check(&mut self, client: C, now: Instant) -> Result<(), Duration> {
+        if self.refill_rate <= 0.0 || self.whitelist.contains(&client) {
+            return Ok(());
+        }
+
+        if self.buckets.len() >= MAX_TRACKED_CLIENTS && !self.buckets.contains_key(&client) {
+            self.purge_full_buckets(now);
+        }
+
+        let burst_size = self.burst_size;
+        let refill_rate = self.refill_rate;
+        let bucket = self.buckets.entry(client).or_insert(TokenBucket {
+            tokens: burst_size,
+            last_refill: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.last_refill);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * refill_rate).min(burst_size);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / refill_rate))
+        }
+    }
+
+    /// Removes the buckets of clients which would have been refilled completely by `now`.
+    fn purge_full_buckets(&mut self, now: Instant) {
+        let burst_size = self.burst_size;
+        let refill_rate = self.refill_rate;
+        self.buckets.retain(|_, bucket| {
+            let elapsed = now.saturating_duration_since(bucket.last_refill);
+            bucket.tokens + elapsed.as_secs_f64() * refill_rate < burst_size
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    const QPS_LIMIT: u64 = 10;
+    const BURST_LIMIT: u64 = 5;
+
+    #[test]
+    fn should_throttle_bursts_from_single_client() {
+        let client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let other_client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+        let whitelisted_client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 3));
+        let mut rate_limiter =
+            ClientRateLimiter::new(QPS_LIMIT, BURST_LIMIT, &[whitelisted_client]);
+        let start = Instant::now();
+
+        // A full burst should be allowed, after which the client should be throttled.
+        for _ in 0..BURST_LIMIT {
+            assert_eq!(rate_limiter.check(client, start), Ok(()));
+        }
+        let retry_after = rate_limiter
+            .check(client, start)
+            .expect_err("should be throttled");
+        assert!(retry_after > Duration::from_secs(0));
+        assert!(retry_after <= Duration::from_secs(1) / QPS_LIMIT as u32);
+
+        // Other clients should be unaffected, and whitelisted ones never throttled.
+        assert_eq!(rate_limiter.check(other_client, start), Ok(()));
+        for _ in 0..(BURST_LIMIT * 10) {
+            assert_eq!(rate_limiter.check(whitelisted_client, start), Ok(()));
+        }
+
+        // Once the client has waited, a single further request should be allowed.
+        let later = start + retry_after + Duration::from_millis(1);
+        assert_eq!(rate_limiter.check(client, later), Ok(()));
+        assert!(rate_limiter.check(client, later).is_err());
+
+        // After a second, the client's bucket should have refilled to the burst limit only.
+        let much_later = later + Duration::from_secs(1);
+        for _ in 0..BURST_LIMIT {
+            assert_eq!(rate_limiter.check(client, much_later), Ok(()));
+        }
+        assert!(rate_limiter.check(client, much_later).is_err());
+    }
+
+    #[test]
+    fn should_not_throttle_if_disabled() {
+        let client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let mut rate_limiter = ClientRateLimiter::new(0, BURST_LIMIT, &[]);
+        let now = Instant::now();
+        for _ in 0..(BURST_LIMIT * 10) {
+            assert_eq!(rate_limiter.check(client, now), Ok(()));
+        }
+    }
+}
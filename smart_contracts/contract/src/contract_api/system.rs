@@ -207,6 +207,25 @@ pub fn transfer_from_purse_to_purse(
     api_error::result_from(result)
 }
 
+/// Transfers motes from `source` purse to each of the `targets`, given as pairs of target purse
+/// and amount.
+///
+/// Every transfer is subject to the same checks as [`transfer_from_purse_to_purse`].  If any of
+/// them fails, execution is reverted with its error, so none of the transfers take effect.
+///
+/// Note that there is no dedicated host function for batched transfers: this calls
+/// [`transfer_from_purse_to_purse`] once per target, so each transfer is charged for separately,
+/// and atomicity relies on the revert discarding the effects of the whole session.  Callers which
+/// handle the error of a failed transfer themselves, rather than reverting, should call
+/// [`transfer_from_purse_to_purse`] directly.
+pub fn transfer_from_purse_to_purses(source: URef, targets: &[(URef, U512)], id: Option<u64>) {
+    for &(target, amount) in targets {
+        if let Err(error) = transfer_from_purse_to_purse(source, target, amount, id) {
+            runtime::revert(error);
+        }
+    }
+}
+
 /// Records a transfer.  Can only be called from within the mint contract.
 /// Needed to support system contract-based execution.
 #[doc(hidden)]
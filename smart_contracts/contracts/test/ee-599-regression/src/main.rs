@@ -4,7 +4,7 @@
 
 extern crate alloc;
 
-use alloc::{collections::BTreeMap, string::String};
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
 
 use casper_contract::{
     contract_api::{account, runtime, storage, system},
@@ -22,14 +22,18 @@ const DONATION_AMOUNT: u64 = 1;
 // sources wouldn't overlap (if ever that's possible)
 const DONATION_PURSE_COPY: &str = "donation_purse_copy";
 const DONATION_PURSE: &str = "donation_purse";
+const BATCH_PURSE_PREFIX: &str = "batch_purse";
 const MAINTAINER: &str = "maintainer";
+const METHOD_BATCH_TRANSFER: &str = "batch_transfer";
 const METHOD_CALL: &str = "call";
 const METHOD_INSTALL: &str = "install";
 const TRANSFER_FROM_PURSE_TO_ACCOUNT: &str = "transfer_from_purse_to_account_ext";
 const TRANSFER_FROM_PURSE_TO_PURSE: &str = "transfer_from_purse_to_purse_ext";
+const TRANSFER_FROM_PURSE_TO_PURSES: &str = "transfer_from_purse_to_purses_ext";
 const TRANSFER_FUNDS_KEY: &str = "transfer_funds";
 const TRANSFER_TO_ACCOUNT: &str = "transfer_to_account_ext";
 
+const ARG_AMOUNTS: &str = "amounts";
 const ARG_METHOD: &str = "method";
 const ARG_CONTRACTKEY: &str = "contract_key";
 const ARG_SUBCONTRACTMETHODFWD: &str = "sub_contract_method_fwd";
@@ -76,6 +80,22 @@ pub extern "C" fn transfer_from_purse_to_purse_ext() {
     .unwrap_or_revert();
 }
 
+#[no_mangle]
+pub extern "C" fn transfer_from_purse_to_purses_ext() {
+    let donation_purse = get_donation_purse().unwrap_or_revert();
+
+    let main_purse = account::get_main_purse();
+
+    system::transfer_from_purse_to_purses(
+        main_purse,
+        &[
+            (donation_purse, U512::from(DONATION_AMOUNT)),
+            (donation_purse, U512::from(DONATION_AMOUNT)),
+        ],
+        None,
+    );
+}
+
 #[no_mangle]
 pub extern "C" fn get_main_purse_ext() {}
 
@@ -167,6 +187,16 @@ fn delegate() -> Result<(), ApiError> {
 
                 entry_points.add_entry_point(entry_point_4);
 
+                let entry_point_5 = EntryPoint::new(
+                    TRANSFER_FROM_PURSE_TO_PURSES,
+                    Parameters::default(),
+                    CLType::Unit,
+                    EntryPointAccess::Public,
+                    EntryPointType::Contract,
+                );
+
+                entry_points.add_entry_point(entry_point_5);
+
                 entry_points
             };
 
@@ -186,6 +216,19 @@ fn delegate() -> Result<(), ApiError> {
             let subcontract_method: String = runtime::get_named_arg(ARG_SUBCONTRACTMETHODFWD);
             runtime::call_contract::<()>(contract_key, &subcontract_method, RuntimeArgs::default());
         }
+        METHOD_BATCH_TRANSFER => {
+            // Transfers from the caller's main purse into a new purse per amount, in a valid
+            // context as this runs as session code of the caller
+            let amounts: Vec<U512> = runtime::get_named_arg(ARG_AMOUNTS);
+            let targets: Vec<(URef, U512)> = amounts
+                .into_iter()
+                .map(|amount| (system::create_purse(), amount))
+                .collect();
+            system::transfer_from_purse_to_purses(account::get_main_purse(), &targets, None);
+            for (index, (purse, _amount)) in targets.into_iter().enumerate() {
+                runtime::put_key(&format!("{}_{}", BATCH_PURSE_PREFIX, index), purse.into());
+            }
+        }
         _ => return Err(ContractError::InvalidDelegateMethod.into()),
     }
     Ok(())
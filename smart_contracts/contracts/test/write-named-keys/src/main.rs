@@ -0,0 +1,19 @@
+#![no_std]
+#![no_main]
+
+use casper_contract::contract_api::{runtime, storage};
+
+const FIRST_KEY_NAME: &str = "first";
+const FIRST_VALUE: u64 = 1;
+const SECOND_KEY_NAME: &str = "second";
+const SECOND_VALUE: u64 = 2;
+
+#[no_mangle]
+pub extern "C" fn call() {
+    // Write two values to global state, each under a new named key.
+    let first_uref = storage::new_uref(FIRST_VALUE);
+    runtime::put_key(FIRST_KEY_NAME, first_uref.into());
+
+    let second_uref = storage::new_uref(SECOND_VALUE);
+    runtime::put_key(SECOND_KEY_NAME, second_uref.into());
+}
@@ -261,11 +261,13 @@ impl AccountHash {
         const SYSTEM_LOWERCASE: &str = "system";
         const ED25519_LOWERCASE: &str = "ed25519";
         const SECP256K1_LOWERCASE: &str = "secp256k1";
+        const SECP256R1_LOWERCASE: &str = "secp256r1";
 
         let algorithm_name = match public_key {
             PublicKey::System => SYSTEM_LOWERCASE,
             PublicKey::Ed25519(_) => ED25519_LOWERCASE,
             PublicKey::Secp256k1(_) => SECP256K1_LOWERCASE,
+            PublicKey::Secp256r1(_) => SECP256R1_LOWERCASE,
         };
         let public_key_bytes: Vec<u8> = public_key.into();
 
@@ -24,9 +24,14 @@ use k256::ecdsa::{
     Signature as Secp256k1Signature, SigningKey as Secp256k1SecretKey,
     VerifyingKey as Secp256k1PublicKey,
 };
+use p256::ecdsa::{
+    Signature as Secp256r1Signature, SigningKey as Secp256r1SecretKey,
+    VerifyingKey as Secp256r1PublicKey,
+};
 #[cfg(feature = "std")]
 use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use zeroize::Zeroizing;
 
 use crate::{
     account::AccountHash,
@@ -55,10 +60,18 @@ const ED25519: &str = "Ed25519";
 pub const SECP256K1_TAG: u8 = 2;
 const SECP256K1: &str = "Secp256k1";
 
+/// Tag for secp256r1 variant.
+pub const SECP256R1_TAG: u8 = 3;
+const SECP256R1: &str = "Secp256r1";
+
 const SECP256K1_SECRET_KEY_LENGTH: usize = 32;
 const SECP256K1_COMPRESSED_PUBLIC_KEY_LENGTH: usize = 33;
 const SECP256K1_SIGNATURE_LENGTH: usize = 64;
 
+const SECP256R1_SECRET_KEY_LENGTH: usize = 32;
+const SECP256R1_COMPRESSED_PUBLIC_KEY_LENGTH: usize = 33;
+const SECP256R1_SIGNATURE_LENGTH: usize = 64;
+
 /// Public key for system account.
 pub const SYSTEM_ACCOUNT: PublicKey = PublicKey::System;
 
@@ -96,9 +109,13 @@ where
                 let bytes = hex::decode(key_bytes)?;
                 Self::secp256k1_from_bytes(&bytes)
             }
+            SECP256R1_TAG => {
+                let bytes = hex::decode(key_bytes)?;
+                Self::secp256r1_from_bytes(&bytes)
+            }
             _ => Err(Error::AsymmetricKey(format!(
-                "invalid tag.  Expected {} or {}, got {}",
-                ED25519_TAG, SECP256K1_TAG, tag[0]
+                "invalid tag.  Expected {}, {} or {}, got {}",
+                ED25519_TAG, SECP256K1_TAG, SECP256R1_TAG, tag[0]
             ))),
         }
     }
@@ -111,9 +128,15 @@ where
 
     /// Constructs a new secp256k1 variant from a byte slice.
     fn secp256k1_from_bytes<T: AsRef<[u8]>>(bytes: T) -> Result<Self, Error>;
+
+    /// Constructs a new secp256r1 variant from a byte slice.
+    fn secp256r1_from_bytes<T: AsRef<[u8]>>(bytes: T) -> Result<Self, Error>;
 }
 
 /// A secret or private asymmetric key.
+///
+/// The key material is zeroed on drop by the underlying key types, via the `zeroize` features of
+/// `ed25519-dalek`, `k256` and `p256`.
 #[derive(DataSize)]
 pub enum SecretKey {
     /// System secret key.
@@ -124,6 +147,9 @@ pub enum SecretKey {
     /// secp256k1 secret key.
     #[data_size(skip)]
     Secp256k1(Secp256k1SecretKey),
+    /// secp256r1 (NIST P-256) secret key.
+    #[data_size(skip)]
+    Secp256r1(Secp256r1SecretKey),
 }
 
 impl SecretKey {
@@ -136,6 +162,12 @@ impl SecretKey {
     /// The length in bytes of a secp256k1 secret key.
     pub const SECP256K1_LENGTH: usize = SECP256K1_SECRET_KEY_LENGTH;
 
+    /// The length in bytes of a secp256r1 secret key.
+    pub const SECP256R1_LENGTH: usize = SECP256R1_SECRET_KEY_LENGTH;
+
+    /// The length in bytes of a seed used to deterministically derive a secret key.
+    pub const SEED_LENGTH: usize = 32;
+
     /// Constructs a new system variant.
     pub fn system() -> Self {
         SecretKey::System
@@ -155,15 +187,91 @@ impl SecretKey {
         )?))
     }
 
+    /// Constructs a new secp256r1 variant from a byte slice.
+    pub fn secp256r1_from_bytes<T: AsRef<[u8]>>(bytes: T) -> Result<Self, Error> {
+        Ok(SecretKey::Secp256r1(Secp256r1SecretKey::from_bytes(
+            bytes.as_ref(),
+        )?))
+    }
+
+    /// Deterministically derives a new ed25519 variant from the given seed.
+    ///
+    /// The same seed always yields the same secret key.
+    pub fn from_seed_ed25519(seed: &[u8; Self::SEED_LENGTH]) -> Result<Self, Error> {
+        Self::ed25519_from_bytes(seed)
+    }
+
+    /// Deterministically derives a new secp256k1 variant from the given seed.
+    ///
+    /// The same seed always yields the same secret key.  The seed is used as the secret scalar, so
+    /// this fails if it is zero or not less than the order of the curve.
+    pub fn from_seed_secp256k1(seed: &[u8; Self::SEED_LENGTH]) -> Result<Self, Error> {
+        Self::secp256k1_from_bytes(seed)
+    }
+
+    /// Deterministically derives a new secp256r1 variant from the given seed.
+    ///
+    /// The same seed always yields the same secret key.  The seed is used as the secret scalar, so
+    /// this fails if it is zero or not less than the order of the curve.
+    pub fn from_seed_secp256r1(seed: &[u8; Self::SEED_LENGTH]) -> Result<Self, Error> {
+        Self::secp256r1_from_bytes(seed)
+    }
+
+    /// Constructs a new ed25519 variant from its hex-encoded raw bytes.
+    ///
+    /// Unlike `PublicKey::from_hex`, the input must not be prefixed with the algorithm tag.
+    pub fn from_hex_ed25519<T: AsRef<[u8]>>(input: T) -> Result<Self, Error> {
+        let bytes = decode_secret_key_hex(input, ED25519, Self::ED25519_LENGTH)?;
+        Self::ed25519_from_bytes(bytes.as_slice())
+    }
+
+    /// Constructs a new secp256k1 variant from its hex-encoded raw bytes.
+    ///
+    /// Unlike `PublicKey::from_hex`, the input must not be prefixed with the algorithm tag.
+    pub fn from_hex_secp256k1<T: AsRef<[u8]>>(input: T) -> Result<Self, Error> {
+        let bytes = decode_secret_key_hex(input, SECP256K1, Self::SECP256K1_LENGTH)?;
+        Self::secp256k1_from_bytes(bytes.as_slice())
+    }
+
+    /// Constructs a new secp256r1 variant from its hex-encoded raw bytes.
+    ///
+    /// Unlike `PublicKey::from_hex`, the input must not be prefixed with the algorithm tag.
+    pub fn from_hex_secp256r1<T: AsRef<[u8]>>(input: T) -> Result<Self, Error> {
+        let bytes = decode_secret_key_hex(input, SECP256R1, Self::SECP256R1_LENGTH)?;
+        Self::secp256r1_from_bytes(bytes.as_slice())
+    }
+
     fn variant_name(&self) -> &str {
         match self {
             SecretKey::System => SYSTEM,
             SecretKey::Ed25519(_) => ED25519,
             SecretKey::Secp256k1(_) => SECP256K1,
+            SecretKey::Secp256r1(_) => SECP256R1,
         }
     }
 }
 
+/// Decodes the hex-encoded raw bytes of a secret key, checking they have the expected length.
+///
+/// The decoded bytes are zeroed when the returned value is dropped.
+fn decode_secret_key_hex<T: AsRef<[u8]>>(
+    input: T,
+    variant_name: &str,
+    expected_length: usize,
+) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let bytes = Zeroizing::new(hex::decode(input)?);
+    if bytes.len() != expected_length {
+        return Err(Error::AsymmetricKey(format!(
+            "invalid {} secret key length.  Expected {} bytes ({} hex characters), got {} bytes",
+            variant_name,
+            expected_length,
+            expected_length * 2,
+            bytes.len()
+        )));
+    }
+    Ok(bytes)
+}
+
 impl Debug for SecretKey {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         write!(formatter, "SecretKey::{}", self.variant_name())
@@ -182,6 +290,7 @@ impl Tagged<u8> for SecretKey {
             SecretKey::System => SYSTEM_TAG,
             SecretKey::Ed25519(_) => ED25519_TAG,
             SecretKey::Secp256k1(_) => SECP256K1_TAG,
+            SecretKey::Secp256r1(_) => SECP256R1_TAG,
         }
     }
 }
@@ -197,6 +306,9 @@ pub enum PublicKey {
     /// secp256k1 public key.
     #[data_size(skip)]
     Secp256k1(Secp256k1PublicKey),
+    /// secp256r1 (NIST P-256) public key.
+    #[data_size(skip)]
+    Secp256r1(Secp256r1PublicKey),
 }
 
 impl PublicKey {
@@ -209,6 +321,9 @@ impl PublicKey {
     /// The length in bytes of a secp256k1 public key.
     pub const SECP256K1_LENGTH: usize = SECP256K1_COMPRESSED_PUBLIC_KEY_LENGTH;
 
+    /// The length in bytes of a secp256r1 public key.
+    pub const SECP256R1_LENGTH: usize = SECP256R1_COMPRESSED_PUBLIC_KEY_LENGTH;
+
     /// Creates an `AccountHash` from a given `PublicKey` instance.
     pub fn to_account_hash(&self) -> AccountHash {
         AccountHash::from(self)
@@ -219,6 +334,7 @@ impl PublicKey {
             PublicKey::System => SYSTEM,
             PublicKey::Ed25519(_) => ED25519,
             PublicKey::Secp256k1(_) => SECP256K1,
+            PublicKey::Secp256r1(_) => SECP256R1,
         }
     }
 }
@@ -239,6 +355,12 @@ impl AsymmetricType<'_> for PublicKey {
             bytes.as_ref(),
         )?))
     }
+
+    fn secp256r1_from_bytes<T: AsRef<[u8]>>(bytes: T) -> Result<Self, Error> {
+        Ok(PublicKey::Secp256r1(Secp256r1PublicKey::from_sec1_bytes(
+            bytes.as_ref(),
+        )?))
+    }
 }
 
 impl From<&SecretKey> for PublicKey {
@@ -247,6 +369,7 @@ impl From<&SecretKey> for PublicKey {
             SecretKey::System => PublicKey::System,
             SecretKey::Ed25519(secret_key) => PublicKey::Ed25519(secret_key.into()),
             SecretKey::Secp256k1(secret_key) => PublicKey::Secp256k1(secret_key.into()),
+            SecretKey::Secp256r1(secret_key) => PublicKey::Secp256r1(secret_key.verify_key()),
         }
     }
 }
@@ -257,6 +380,7 @@ impl From<&PublicKey> for Vec<u8> {
             PublicKey::System => Vec::new(),
             PublicKey::Ed25519(key) => key.to_bytes().into(),
             PublicKey::Secp256k1(key) => key.to_bytes().into(),
+            PublicKey::Secp256r1(key) => key.to_encoded_point(true).as_bytes().into(),
         }
     }
 }
@@ -323,6 +447,7 @@ impl Tagged<u8> for PublicKey {
             PublicKey::System => SYSTEM_TAG,
             PublicKey::Ed25519(_) => ED25519_TAG,
             PublicKey::Secp256k1(_) => SECP256K1_TAG,
+            PublicKey::Secp256r1(_) => SECP256R1_TAG,
         }
     }
 }
@@ -344,6 +469,11 @@ impl ToBytes for PublicKey {
                 let secp256k1_bytes = public_key.to_bytes();
                 buffer.extend_from_slice(&secp256k1_bytes);
             }
+            PublicKey::Secp256r1(public_key) => {
+                buffer.insert(0, SECP256R1_TAG);
+                let secp256r1_bytes = public_key.to_encoded_point(true);
+                buffer.extend_from_slice(secp256r1_bytes.as_bytes());
+            }
         }
         Ok(buffer)
     }
@@ -354,6 +484,7 @@ impl ToBytes for PublicKey {
                 PublicKey::System => Self::SYSTEM_LENGTH,
                 PublicKey::Ed25519(_) => Self::ED25519_LENGTH,
                 PublicKey::Secp256k1(_) => Self::SECP256K1_LENGTH,
+                PublicKey::Secp256r1(_) => Self::SECP256R1_LENGTH,
             }
     }
 }
@@ -377,6 +508,13 @@ impl FromBytes for PublicKey {
                     .map_err(|_error| bytesrepr::Error::Formatting)?;
                 Ok((public_key, remainder))
             }
+            SECP256R1_TAG => {
+                let (raw_bytes, remainder): ([u8; Self::SECP256R1_LENGTH], _) =
+                    FromBytes::from_bytes(remainder)?;
+                let public_key = Self::secp256r1_from_bytes(raw_bytes)
+                    .map_err(|_error| bytesrepr::Error::Formatting)?;
+                Ok((public_key, remainder))
+            }
             _ => Err(bytesrepr::Error::Formatting),
         }
     }
@@ -427,6 +565,9 @@ pub enum Signature {
     /// Secp256k1 signature.
     #[data_size(skip)]
     Secp256k1(Secp256k1Signature),
+    /// Secp256r1 signature.
+    #[data_size(skip)]
+    Secp256r1(Secp256r1Signature),
 }
 
 impl Signature {
@@ -439,6 +580,9 @@ impl Signature {
     /// The length in bytes of a secp256k1 signature
     pub const SECP256K1_LENGTH: usize = SECP256K1_SIGNATURE_LENGTH;
 
+    /// The length in bytes of a secp256r1 signature
+    pub const SECP256R1_LENGTH: usize = SECP256R1_SIGNATURE_LENGTH;
+
     /// Constructs a new Ed25519 variant from a byte array.
     pub fn ed25519(bytes: [u8; Self::ED25519_LENGTH]) -> Result<Self, Error> {
         let signature = ed25519_dalek::Signature::from_bytes(&bytes).map_err(|_| {
@@ -463,11 +607,24 @@ impl Signature {
         Ok(Signature::Secp256k1(signature))
     }
 
+    /// Constructs a new secp256r1 variant from a byte array.
+    pub fn secp256r1(bytes: [u8; Self::SECP256R1_LENGTH]) -> Result<Self, Error> {
+        let signature = Secp256r1Signature::try_from(&bytes[..]).map_err(|_| {
+            Error::AsymmetricKey(format!(
+                "failed to construct secp256r1 signature from {:?}",
+                &bytes[..]
+            ))
+        })?;
+
+        Ok(Signature::Secp256r1(signature))
+    }
+
     fn variant_name(&self) -> &str {
         match self {
             Signature::System => SYSTEM,
             Signature::Ed25519(_) => ED25519,
             Signature::Secp256k1(_) => SECP256K1,
+            Signature::Secp256r1(_) => SECP256R1,
         }
     }
 }
@@ -496,6 +653,16 @@ impl AsymmetricType<'_> for Signature {
         })?;
         Ok(Signature::Secp256k1(signature))
     }
+
+    fn secp256r1_from_bytes<T: AsRef<[u8]>>(bytes: T) -> Result<Self, Error> {
+        let signature = Secp256r1Signature::try_from(bytes.as_ref()).map_err(|_| {
+            Error::AsymmetricKey(format!(
+                "failed to construct secp256r1 signature from {:?}",
+                bytes.as_ref()
+            ))
+        })?;
+        Ok(Signature::Secp256r1(signature))
+    }
 }
 
 impl Debug for Signature {
@@ -559,6 +726,7 @@ impl Tagged<u8> for Signature {
             Signature::System => SYSTEM_TAG,
             Signature::Ed25519(_) => ED25519_TAG,
             Signature::Secp256k1(_) => SECP256K1_TAG,
+            Signature::Secp256r1(_) => SECP256R1_TAG,
         }
     }
 }
@@ -580,6 +748,11 @@ impl ToBytes for Signature {
                 let secp256k1_bytes = signature.as_ref();
                 buffer.extend_from_slice(secp256k1_bytes);
             }
+            Signature::Secp256r1(signature) => {
+                buffer.insert(0, SECP256R1_TAG);
+                let secp256r1_bytes = signature.as_ref();
+                buffer.extend_from_slice(secp256r1_bytes);
+            }
         }
         Ok(buffer)
     }
@@ -590,6 +763,7 @@ impl ToBytes for Signature {
                 Signature::System => Self::SYSTEM_LENGTH,
                 Signature::Ed25519(_) => Self::ED25519_LENGTH,
                 Signature::Secp256k1(_) => Self::SECP256K1_LENGTH,
+                Signature::Secp256r1(_) => Self::SECP256R1_LENGTH,
             }
     }
 }
@@ -613,6 +787,13 @@ impl FromBytes for Signature {
                     Self::secp256k1(raw_bytes).map_err(|_error| bytesrepr::Error::Formatting)?;
                 Ok((public_key, remainder))
             }
+            SECP256R1_TAG => {
+                let (raw_bytes, remainder): ([u8; Self::SECP256R1_LENGTH], _) =
+                    FromBytes::from_bytes(remainder)?;
+                let public_key =
+                    Self::secp256r1(raw_bytes).map_err(|_error| bytesrepr::Error::Formatting)?;
+                Ok((public_key, remainder))
+            }
             _ => Err(bytesrepr::Error::Formatting),
         }
     }
@@ -636,6 +817,7 @@ impl From<&Signature> for Vec<u8> {
             Signature::System => Vec::new(),
             Signature::Ed25519(signature) => signature.to_bytes().into(),
             Signature::Secp256k1(signature) => signature.as_ref().into(),
+            Signature::Secp256r1(signature) => signature.as_ref().into(),
         }
     }
 }
@@ -679,6 +861,7 @@ mod detail {
         System,
         Ed25519(Vec<u8>),
         Secp256k1(Vec<u8>),
+        Secp256r1(Vec<u8>),
     }
 
     impl From<&PublicKey> for AsymmetricTypeAsBytes {
@@ -687,6 +870,7 @@ mod detail {
                 PublicKey::System => AsymmetricTypeAsBytes::System,
                 key @ PublicKey::Ed25519(_) => AsymmetricTypeAsBytes::Ed25519(key.into()),
                 key @ PublicKey::Secp256k1(_) => AsymmetricTypeAsBytes::Secp256k1(key.into()),
+                key @ PublicKey::Secp256r1(_) => AsymmetricTypeAsBytes::Secp256r1(key.into()),
             }
         }
     }
@@ -697,6 +881,7 @@ mod detail {
                 Signature::System => AsymmetricTypeAsBytes::System,
                 key @ Signature::Ed25519(_) => AsymmetricTypeAsBytes::Ed25519(key.into()),
                 key @ Signature::Secp256k1(_) => AsymmetricTypeAsBytes::Secp256k1(key.into()),
+                key @ Signature::Secp256r1(_) => AsymmetricTypeAsBytes::Secp256r1(key.into()),
             }
         }
     }
@@ -736,6 +921,9 @@ mod detail {
             AsymmetricTypeAsBytes::Secp256k1(raw_bytes) => {
                 T::secp256k1_from_bytes(raw_bytes).map_err(D::Error::custom)
             }
+            AsymmetricTypeAsBytes::Secp256r1(raw_bytes) => {
+                T::secp256r1_from_bytes(raw_bytes).map_err(D::Error::custom)
+            }
         }
     }
 }
@@ -1,4 +1,33 @@
-use crate::{crypto::SecretKey, AsymmetricType, PublicKey};
+use std::cell::Cell;
+
+use zeroize::{Zeroize, Zeroizing};
+
+use super::ED25519;
+use crate::{
+    crypto::{Error, SecretKey},
+    AsymmetricType, PublicKey,
+};
+
+type Ed25519Bytes = [u8; SecretKey::ED25519_LENGTH];
+
+/// A buffer of secret key bytes which copies out the bytes it still holds when it is dropped, so
+/// that they can be inspected without reading memory which is no longer owned.
+struct DropInspector<'a> {
+    bytes: Ed25519Bytes,
+    bytes_on_drop: &'a Cell<Option<Ed25519Bytes>>,
+}
+
+impl Zeroize for DropInspector<'_> {
+    fn zeroize(&mut self) {
+        self.bytes[..].zeroize();
+    }
+}
+
+impl Drop for DropInspector<'_> {
+    fn drop(&mut self) {
+        self.bytes_on_drop.set(Some(self.bytes));
+    }
+}
 
 #[test]
 fn can_construct_ed25519_keypair_from_zeroes() {
@@ -29,6 +58,21 @@ fn can_construct_secp256k1_keypair_from_ones() {
     let _public_key: PublicKey = (&secret_key).into();
 }
 
+#[test]
+#[should_panic]
+fn cannot_construct_secp256r1_keypair_from_zeroes() {
+    let bytes = [0; SecretKey::SECP256R1_LENGTH];
+    let secret_key = SecretKey::secp256r1_from_bytes(bytes).unwrap();
+    let _public_key: PublicKey = (&secret_key).into();
+}
+
+#[test]
+fn can_construct_secp256r1_keypair_from_ones() {
+    let bytes = [1; SecretKey::SECP256R1_LENGTH];
+    let secret_key = SecretKey::secp256r1_from_bytes(bytes).unwrap();
+    let _public_key: PublicKey = (&secret_key).into();
+}
+
 #[test]
 fn can_construct_system_public_key() {
     let public_key_bytes = [0; PublicKey::ED25519_LENGTH];
@@ -39,3 +83,109 @@ fn can_construct_system_public_key() {
 
     assert_ne!(public_key, (&secret_key).into())
 }
+
+#[test]
+fn should_construct_secret_keys_from_hex() {
+    let secret_key_hex = "0101010101010101010101010101010101010101010101010101010101010101";
+
+    let ed25519_secret_key = SecretKey::from_hex_ed25519(secret_key_hex).unwrap();
+    assert_eq!(
+        PublicKey::from(&ed25519_secret_key),
+        PublicKey::from(&SecretKey::ed25519_from_bytes([1; SecretKey::ED25519_LENGTH]).unwrap())
+    );
+
+    let secp256k1_secret_key = SecretKey::from_hex_secp256k1(secret_key_hex).unwrap();
+    assert_eq!(
+        PublicKey::from(&secp256k1_secret_key),
+        PublicKey::from(
+            &SecretKey::secp256k1_from_bytes([1; SecretKey::SECP256K1_LENGTH]).unwrap()
+        )
+    );
+
+    let secp256r1_secret_key = SecretKey::from_hex_secp256r1(secret_key_hex).unwrap();
+    assert_eq!(
+        PublicKey::from(&secp256r1_secret_key),
+        PublicKey::from(
+            &SecretKey::secp256r1_from_bytes([1; SecretKey::SECP256R1_LENGTH]).unwrap()
+        )
+    );
+}
+
+#[test]
+fn should_not_construct_secret_keys_from_invalid_hex() {
+    assert!(matches!(
+        SecretKey::from_hex_ed25519("not hex"),
+        Err(Error::FromHex(_))
+    ));
+    assert!(matches!(
+        SecretKey::from_hex_ed25519("0101"),
+        Err(Error::AsymmetricKey(_))
+    ));
+    assert!(matches!(
+        SecretKey::from_hex_secp256k1("01".repeat(33)),
+        Err(Error::AsymmetricKey(_))
+    ));
+    assert!(matches!(
+        SecretKey::from_hex_secp256r1("01".repeat(31)),
+        Err(Error::AsymmetricKey(_))
+    ));
+}
+
+#[test]
+fn should_zeroize_decoded_secret_key_bytes_on_drop() {
+    let secret_key_bytes = [7; SecretKey::ED25519_LENGTH];
+    let decoded = super::decode_secret_key_hex(
+        hex::encode(secret_key_bytes),
+        ED25519,
+        secret_key_bytes.len(),
+    )
+    .unwrap();
+    assert_eq!(decoded.as_slice(), secret_key_bytes);
+
+    // The decoded bytes are held in a `Zeroizing` wrapper, which zeroes its contents before they
+    // are dropped.
+    let bytes_on_drop = Cell::new(None);
+    let inspector = Zeroizing::new(DropInspector {
+        bytes: secret_key_bytes,
+        bytes_on_drop: &bytes_on_drop,
+    });
+    assert_eq!(inspector.bytes, secret_key_bytes);
+    drop(inspector);
+    assert_eq!(bytes_on_drop.get(), Some([0; SecretKey::ED25519_LENGTH]));
+}
+
+#[test]
+fn should_derive_same_keys_from_same_seed() {
+    let seed = [7; SecretKey::SEED_LENGTH];
+    let other_seed = [8; SecretKey::SEED_LENGTH];
+
+    let ed25519_public_key = PublicKey::from(&SecretKey::from_seed_ed25519(&seed).unwrap());
+    assert_eq!(
+        ed25519_public_key,
+        PublicKey::from(&SecretKey::from_seed_ed25519(&seed).unwrap())
+    );
+    assert_ne!(
+        ed25519_public_key,
+        PublicKey::from(&SecretKey::from_seed_ed25519(&other_seed).unwrap())
+    );
+
+    let secp256k1_public_key = PublicKey::from(&SecretKey::from_seed_secp256k1(&seed).unwrap());
+    assert_eq!(
+        secp256k1_public_key,
+        PublicKey::from(&SecretKey::from_seed_secp256k1(&seed).unwrap())
+    );
+    assert_ne!(
+        secp256k1_public_key,
+        PublicKey::from(&SecretKey::from_seed_secp256k1(&other_seed).unwrap())
+    );
+
+    let secp256r1_public_key = PublicKey::from(&SecretKey::from_seed_secp256r1(&seed).unwrap());
+    assert_eq!(
+        secp256r1_public_key,
+        PublicKey::from(&SecretKey::from_seed_secp256r1(&seed).unwrap())
+    );
+    assert_ne!(
+        secp256r1_public_key,
+        PublicKey::from(&SecretKey::from_seed_secp256r1(&other_seed).unwrap())
+    );
+}